@@ -0,0 +1,181 @@
+//! `lifeos` — drive a Life OS vault from scripts and cron without the GUI.
+//!
+//!     lifeos task add "Call the dentist" --tag health
+//!     lifeos mail sync --limit 50
+//!     lifeos sync
+//!     lifeos note search "quarterly review" --json
+
+use clap::{Parser, Subcommand};
+use life_os_lib::services::{self, audit, mail, notes, sync, tasks};
+use std::path::Path;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "lifeos", version, about = "Headless access to a Life OS vault")]
+struct Cli {
+    /// Vault root; defaults to the vault selected in the app
+    #[arg(long, global = true, env = "LIFEOS_VAULT")]
+    vault: Option<String>,
+
+    /// Print JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Daily tasks
+    #[command(subcommand)]
+    Task(TaskCommand),
+    /// Mail accounts configured in the app
+    #[command(subcommand)]
+    Mail(MailCommand),
+    /// Markdown notes
+    #[command(subcommand)]
+    Note(NoteCommand),
+    /// Two-way sync with the remote configured in connectors.yaml
+    Sync,
+}
+
+#[derive(Subcommand)]
+enum TaskCommand {
+    /// Add an open task to a day file
+    Add {
+        /// Task text
+        text: String,
+        /// Day file to add to (YYYY-MM-DD), today by default
+        #[arg(long)]
+        date: Option<String>,
+        /// Tag to append as #tag (repeatable)
+        #[arg(long = "tag", short)]
+        tags: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum MailCommand {
+    /// Fetch new mail into the vault's Mailbox cache
+    Sync {
+        /// Only this account (id or address)
+        #[arg(long)]
+        account: Option<String>,
+        /// Newest messages to fetch per folder
+        #[arg(long, default_value_t = 20)]
+        limit: u32,
+    },
+}
+
+#[derive(Subcommand)]
+enum NoteCommand {
+    /// Case-insensitive full-text search
+    Search {
+        query: String,
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("lifeos: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<ExitCode, String> {
+    let vault = cli
+        .vault
+        .or_else(services::configured_vault)
+        .ok_or("no vault configured: pass --vault or set LIFEOS_VAULT")?;
+    let _ = services::load_settings(&vault);
+    audit::set_default_actor("cli");
+
+    match cli.command {
+        Command::Task(TaskCommand::Add { text, date, tags }) => {
+            let date = date.unwrap_or_else(|| chrono::Local::now().format("%Y-%m-%d").to_string());
+            let mut line = text;
+            for tag in &tags {
+                line.push_str(&format!(" #{}", tag.trim_start_matches('#')));
+            }
+            let path = tasks::add_task(&vault, &date, &line)?;
+            if cli.json {
+                print_json(&serde_json::json!({ "path": path, "date": date, "text": line }))?;
+            } else {
+                println!("{}", path.display());
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Mail(MailCommand::Sync { account, limit }) => {
+            let accounts: Vec<_> = mail::load_accounts(&vault)?
+                .into_iter()
+                .filter(|a| match &account {
+                    Some(wanted) => &a.id == wanted || &a.email == wanted,
+                    None => a.enabled,
+                })
+                .collect();
+            if accounts.is_empty() {
+                return Err("no matching mail accounts".into());
+            }
+
+            let reports: Vec<_> = accounts.iter().flat_map(|a| mail::sync_account(&vault, a, limit)).collect();
+            if cli.json {
+                print_json(&reports)?;
+            } else {
+                for r in &reports {
+                    match &r.error {
+                        None => println!("{}/{}: {} messages ({} new)", r.account_id, r.folder, r.fetched, r.new),
+                        Some(e) => println!("{}/{}: error: {}", r.account_id, r.folder, e),
+                    }
+                }
+            }
+            // Non-zero when anything failed so cron can alert
+            Ok(if reports.iter().any(|r| r.error.is_some()) { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+        }
+        Command::Sync => {
+            let report = sync::sync(Path::new(&vault))?;
+            if cli.json {
+                print_json(&report)?;
+            } else {
+                println!(
+                    "{} uploaded, {} downloaded, {} deleted, {} conflicts",
+                    report.uploaded.len(),
+                    report.downloaded.len(),
+                    report.deleted_local.len() + report.deleted_remote.len(),
+                    report.conflicts.len()
+                );
+                for e in &report.errors {
+                    println!("error: {e}");
+                }
+            }
+            Ok(if report.errors.is_empty() { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+        }
+        Command::Note(NoteCommand::Search { query, limit }) => {
+            let matches = notes::search_notes(&vault, &query, limit)?;
+            if cli.json {
+                print_json(&matches)?;
+            } else {
+                for m in &matches {
+                    match m.page {
+                        Some(page) => println!("{}#page={}:{}: {}", m.path, page, m.line, m.text),
+                        None => println!("{}:{}: {}", m.path, m.line, m.text),
+                    }
+                }
+            }
+            // grep convention: 1 when nothing matched
+            Ok(if matches.is_empty() { ExitCode::from(1) } else { ExitCode::SUCCESS })
+        }
+    }
+}
+
+fn print_json<T: serde::Serialize + ?Sized>(value: &T) -> Result<(), String> {
+    let out = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    println!("{out}");
+    Ok(())
+}
@@ -0,0 +1,257 @@
+use chrono::{DateTime, Days, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use walkdir::WalkDir;
+
+use crate::services::mail::EmailMessage;
+use crate::services::notes::split_frontmatter;
+use crate::services::tasks;
+
+const DIARY_DIR: &str = "diary";
+const MAILBOX_DIR: &str = "Mailbox";
+/// Written words that count as one unit of activity
+const WORDS_PER_UNIT: usize = 250;
+/// Default range when `from` is omitted: a year back, like a contribution graph
+const DEFAULT_DAYS: u64 = 364;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ActivityDay {
+    /// YYYY-MM-DD
+    pub date: String,
+    /// Checked tasks in that day's daily/tasks file
+    pub tasks: usize,
+    /// Words in diary entries for the day; CJK characters count one each
+    pub words: usize,
+    /// Your commits (by each repo's user.email) in the repos given
+    pub commits: usize,
+    /// Messages received that day and since marked read
+    pub emails: usize,
+    /// tasks + commits + emails + one per 250 words
+    pub total: usize,
+    /// 0–4, the dashboard's heatmap levels: 0, 1–2, 3–4, 5–6, 7+
+    pub level: u8,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// One entry per day from `from` to `to` inclusive (YYYY-MM-DD; default the
+/// year up to today), for a contribution-graph style heatmap. `repos` are
+/// repository paths, e.g. from the Git scanner.
+#[tauri::command]
+pub fn get_activity_heatmap(
+    vault_path: String,
+    from: Option<String>,
+    to: Option<String>,
+    repos: Option<Vec<String>>,
+) -> Result<Vec<ActivityDay>, String> {
+    let vault = Path::new(&vault_path);
+    let to = match to {
+        Some(d) => parse_date(&d)?,
+        None => Local::now().date_naive(),
+    };
+    let from = match from {
+        Some(d) => parse_date(&d)?,
+        None => to - Days::new(DEFAULT_DAYS),
+    };
+    if from > to {
+        return Err(tr!("Range starts after it ends: {} – {}", from, to));
+    }
+
+    let mut days: BTreeMap<NaiveDate, ActivityDay> = BTreeMap::new();
+    let mut date = from;
+    while date <= to {
+        days.insert(date, ActivityDay { date: date.format("%Y-%m-%d").to_string(), ..Default::default() });
+        let Some(next) = date.succ_opt() else { break };
+        date = next;
+    }
+
+    for day in days.values_mut() {
+        let path = vault.join("daily/tasks").join(format!("{}.md", day.date));
+        if let Ok(raw) = fs::read_to_string(path) {
+            day.tasks = tasks::count_tasks(&raw).0;
+        }
+    }
+    for (date, words) in diary_words(vault, from, to) {
+        if let Some(day) = days.get_mut(&date) {
+            day.words += words;
+        }
+    }
+    for date in repos.unwrap_or_default().iter().flat_map(|r| commit_dates(Path::new(r), from, to)) {
+        if let Some(day) = days.get_mut(&date) {
+            day.commits += 1;
+        }
+    }
+    for date in handled_email_dates(vault) {
+        if let Some(day) = days.get_mut(&date) {
+            day.emails += 1;
+        }
+    }
+
+    Ok(days
+        .into_values()
+        .map(|mut day| {
+            day.total = day.tasks + day.commits + day.emails + day.words.div_ceil(WORDS_PER_UNIT);
+            day.level = level(day.total);
+            day
+        })
+        .collect())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn level(total: usize) -> u8 {
+    match total {
+        0 => 0,
+        1..=2 => 1,
+        3..=4 => 2,
+        5..=6 => 3,
+        _ => 4,
+    }
+}
+
+/// diary/{YYYY}/{YYYY-MM-DD}*.md bodies, by the date in the file name
+fn diary_words(vault: &Path, from: NaiveDate, to: NaiveDate) -> Vec<(NaiveDate, usize)> {
+    WalkDir::new(vault.join(DIARY_DIR))
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|x| x == "md"))
+        .filter_map(|e| {
+            let stem = e.path().file_stem()?.to_string_lossy().to_string();
+            let date = NaiveDate::parse_from_str(stem.get(..10)?, "%Y-%m-%d").ok()?;
+            if date < from || date > to {
+                return None;
+            }
+            let raw = fs::read_to_string(e.path()).ok()?;
+            Some((date, count_words(split_frontmatter(&raw).1)))
+        })
+        .collect()
+}
+
+/// Latin-script words by runs of letters and digits, CJK by character
+fn count_words(text: &str) -> usize {
+    let mut count = 0;
+    let mut in_word = false;
+    for c in text.chars() {
+        if is_cjk(c) {
+            count += 1;
+            in_word = false;
+        } else if c.is_alphanumeric() {
+            count += usize::from(!in_word);
+            in_word = true;
+        } else {
+            in_word = false;
+        }
+    }
+    count
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30ff}' | '\u{3400}'..='\u{4dbf}' | '\u{4e00}'..='\u{9fff}' | '\u{ac00}'..='\u{d7af}' | '\u{f900}'..='\u{faff}')
+}
+
+/// Local author dates of the repo user's commits; empty when git fails
+fn commit_dates(repo: &Path, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(args)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+    };
+    let Some(email) = git(&["config", "user.email"]).map(|e| e.trim().to_string()).filter(|e| !e.is_empty()) else {
+        return Vec::new();
+    };
+    let since = format!("--since={from} 00:00:00");
+    let until = format!("--until={to} 23:59:59");
+    let author = format!("--author={email}");
+    git(&["log", "--all", &since, &until, &author, "--date=format-local:%Y-%m-%d", "--format=%ad"])
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|l| NaiveDate::parse_from_str(l.trim(), "%Y-%m-%d").ok())
+        .collect()
+}
+
+/// Local receive dates of messages flagged Seen, across every account's
+/// index.json. The cache keeps no read time, so a message counts on the
+/// day it arrived.
+fn handled_email_dates(vault: &Path) -> Vec<NaiveDate> {
+    let Ok(accounts) = fs::read_dir(vault.join(MAILBOX_DIR)) else { return Vec::new() };
+    accounts
+        .filter_map(|e| e.ok())
+        .filter_map(|e| fs::read_to_string(e.path().join("index.json")).ok())
+        .filter_map(|raw| serde_json::from_str::<Vec<EmailMessage>>(&raw).ok())
+        .flatten()
+        .filter(|m| m.flags.iter().any(|f| f.trim_start_matches('\\') == "Seen"))
+        .filter_map(|m| message_date(&m.date))
+        .collect()
+}
+
+/// RFC 2822 (a trailing "(CST)" comment allowed) or RFC 3339
+fn message_date(raw: &str) -> Option<NaiveDate> {
+    let raw = raw.split(" (").next().unwrap_or_default().trim();
+    DateTime::parse_from_rfc2822(raw)
+        .or_else(|_| DateTime::parse_from_rfc3339(raw))
+        .ok()
+        .map(|d| d.with_timezone(&Local).date_naive())
+}
+
+fn parse_date(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| tr!("Invalid date (expected YYYY-MM-DD): {}", s))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(vault: &Path, rel: &str, text: &str) {
+        let path = vault.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    #[test]
+    fn test_count_words() {
+        assert_eq!(count_words("Hello, world — it's 2025"), 5);
+        assert_eq!(count_words("今天跑了 5km"), 5);
+    }
+
+    #[test]
+    fn test_heatmap() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        write(v, "daily/tasks/2025-03-02.md", "- [x] 买菜\n- [ ] 写周报\n- [x] 跑步\n- [ ]\n");
+        write(v, "diary/2025/2025-03-03.md", "---\ntitle: 周一\n---\n今天很好\n");
+        write(
+            v,
+            "Mailbox/work/index.json",
+            r#"[{"id":"1","uid":1,"uidString":null,"from":"a","to":"b","subject":"s","date":"Mon, 3 Mar 2025 12:00:00 +0000 (UTC)","bodyText":null,"bodyHtml":null,"attachments":[],"flags":["Seen"],"folder":"INBOX"},
+                {"id":"2","uid":2,"uidString":null,"from":"a","to":"b","subject":"s","date":"Mon, 3 Mar 2025 12:00:00 +0000","bodyText":null,"bodyHtml":null,"attachments":[],"flags":[],"folder":"INBOX"}]"#,
+        );
+
+        let days = get_activity_heatmap(v.to_string_lossy().to_string(), Some("2025-03-01".into()), Some("2025-03-03".into()), None).unwrap();
+        assert_eq!(days.len(), 3);
+        assert_eq!((days[0].total, days[0].level), (0, 0));
+        assert_eq!((days[1].tasks, days[1].total, days[1].level), (2, 2, 1));
+        assert_eq!((days[2].words, days[2].emails, days[2].total), (4, 1, 2));
+
+        assert!(get_activity_heatmap(v.to_string_lossy().to_string(), Some("2025-03-04".into()), Some("2025-03-03".into()), None).is_err());
+    }
+}
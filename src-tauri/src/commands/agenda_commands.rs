@@ -0,0 +1,193 @@
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use super::medication_commands::{self, DoseStatus};
+use super::meeting_commands;
+use super::occasion_commands::{self, Occasion};
+use super::subscription_commands::{self, Subscription};
+use super::trip_commands;
+use crate::services::calendar::{self, AgendaEvent};
+use crate::services::habits;
+use crate::services::notes::split_frontmatter;
+use crate::services::tasks::{self, DayTask};
+
+const PROJECTS_DIR: &str = "projects";
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Everything on one day, for the dashboard to render in one call
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DailyAgenda {
+    /// YYYY-MM-DD
+    pub date: String,
+    pub tasks: Vec<DayTask>,
+    /// Timed events first in time order, then all-day ones
+    pub events: Vec<AgendaEvent>,
+    /// Unfinished projects due on the day or earlier
+    pub projects: Vec<DueProject>,
+    /// Habits whose `target_days` include the day, unless paused for it
+    pub habits: Vec<HabitDue>,
+    pub occasions: Vec<Occasion>,
+    /// Enabled subscriptions renewing on the day
+    pub renewals: Vec<Subscription>,
+    pub medications: Vec<DoseStatus>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DueProject {
+    pub path: String,
+    pub title: String,
+    pub status: String,
+    pub priority: String,
+    pub due: String,
+    /// Due before the day
+    pub overdue: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HabitDue {
+    pub id: String,
+    pub name: String,
+    pub icon: String,
+    pub done: bool,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Tasks, events, due projects, habits and reminders (occasions, renewals,
+/// medication doses) for `date` (default today)
+#[tauri::command]
+pub fn get_daily_agenda(vault_path: String, date: Option<String>) -> Result<DailyAgenda, String> {
+    let vault = Path::new(&vault_path);
+    let day = match &date {
+        Some(d) => NaiveDate::parse_from_str(d, "%Y-%m-%d").map_err(|_| tr!("Invalid date (expected YYYY-MM-DD): {}", d))?,
+        None => Local::now().date_naive(),
+    };
+    let date = day.format("%Y-%m-%d").to_string();
+
+    let store = habits::load(vault);
+    let pauses = habits::load_pauses(vault);
+    let habits = store
+        .habits
+        .iter()
+        .filter(|h| h.due_on(day) && !habits::paused_on(&pauses, &h.id, day))
+        .map(|h| HabitDue { id: h.id.clone(), name: h.name.clone(), icon: h.icon.clone(), done: store.done_on(&h.id, day) })
+        .collect();
+
+    let mut events: Vec<AgendaEvent> = calendar_events(vault, day);
+    events.extend(trip_events(&vault_path, &date));
+    // None sorts first, so all-day events go after the timed ones
+    events.sort_by(|a, b| a.time.is_none().cmp(&b.time.is_none()).then(a.time.cmp(&b.time)).then(a.title.cmp(&b.title)));
+
+    Ok(DailyAgenda {
+        tasks: tasks::day_tasks(&vault_path, &date),
+        events,
+        projects: due_projects(vault, day),
+        habits,
+        occasions: occasion_commands::upcoming(vault, day, 0),
+        renewals: subscription_commands::load_subscriptions(vault, day)
+            .into_iter()
+            .filter(|s| s.enabled && s.next_renewal.as_deref() == Some(date.as_str()))
+            .collect(),
+        medications: medication_commands::get_medication_day(vault_path.clone(), Some(date.clone()))?,
+        date,
+    })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn trip_events(vault_path: &str, date: &str) -> Vec<AgendaEvent> {
+    let mut out = Vec::new();
+    for trip in trip_commands::list_trips(vault_path.to_string()).into_iter().filter(|t| t.meta.status != "cancelled") {
+        let itinerary = trip_commands::itinerary(&trip.meta);
+        let Some(day) = itinerary.into_iter().find(|d| d.date == date) else { continue };
+        out.extend(day.events.into_iter().map(|e| AgendaEvent {
+            time: e.time,
+            end: None,
+            title: if trip.meta.title.is_empty() { e.title } else { format!("{} · {}", trip.meta.title, e.title) },
+            location: e.location,
+            source: "trip".to_string(),
+            path: trip.path.clone(),
+            id: None,
+            note: None,
+        }));
+    }
+    out
+}
+
+/// Unfinished projects whose `due` is on or before `day`, soonest first
+fn due_projects(vault: &Path, day: NaiveDate) -> Vec<DueProject> {
+    let mut out: Vec<DueProject> = WalkDir::new(vault.join(PROJECTS_DIR))
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|x| x == "md"))
+        .filter_map(|e| {
+            let raw = fs::read_to_string(e.path()).ok()?;
+            let fm: serde_yaml::Value = serde_yaml::from_str(split_frontmatter(&raw).0?).ok()?;
+            let field = |key: &str| fm.get(key).and_then(|v| v.as_str()).unwrap_or_default().trim().to_string();
+            let due = field("due");
+            let due_day = NaiveDate::parse_from_str(due.get(..10)?, "%Y-%m-%d").ok()?;
+            let status = field("status");
+            if due_day > day || status == "done" {
+                return None;
+            }
+            let title = field("title");
+            Some(DueProject {
+                path: e.path().to_string_lossy().to_string(),
+                title: if title.is_empty() { e.path().file_stem()?.to_string_lossy().to_string() } else { title },
+                status,
+                priority: field("priority"),
+                overdue: due_day < day,
+                due,
+            })
+        })
+        .collect();
+    out.sort_by(|a, b| a.due.cmp(&b.due).then(a.title.cmp(&b.title)));
+    out
+}
+
+/// Calendar events of `day` with the meeting note of each, when there is one
+pub(crate) fn calendar_events(vault: &Path, day: NaiveDate) -> Vec<AgendaEvent> {
+    let notes = meeting_commands::notes_by_event(vault);
+    calendar::events(vault, day).into_iter().map(|e| AgendaEvent { note: e.id.as_ref().and_then(|id| notes.get(id)).cloned(), ..e }).collect()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_agenda() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        let write = |rel: &str, text: &str| {
+            fs::create_dir_all(v.join(rel).parent().unwrap()).unwrap();
+            fs::write(v.join(rel), text).unwrap();
+        };
+        write("daily/tasks/2025-03-03.md", "## 今日任务\n\n- [ ] 周报 ⏰17:00\n");
+        write("daily/habits/habits.yaml", "habits:\n  - id: run\n    name: 跑步\n    target_days: [1]\n  - id: read\n    name: 阅读\n    target_days: [2]\ncheckins:\n  2025-03-03: [run]\n");
+        write("projects/a.md", "---\ntitle: 上线\nstatus: active\ndue: 2025-03-01\n---\n");
+        write("projects/b.md", "---\ntitle: 完成了\nstatus: done\ndue: 2025-03-01\n---\n");
+        write("projects/c.md", "---\ntitle: 以后\nstatus: todo\ndue: \"\"\n---\n");
+
+        let agenda = get_daily_agenda(v.to_string_lossy().to_string(), Some("2025-03-03".into())).unwrap();
+        assert_eq!(agenda.tasks[0].time.as_deref(), Some("17:00"));
+        assert_eq!(agenda.habits, vec![HabitDue { id: "run".into(), name: "跑步".into(), icon: String::new(), done: true }]);
+        assert_eq!(agenda.projects.len(), 1);
+        assert!(agenda.projects[0].overdue);
+        assert!(get_daily_agenda(v.to_string_lossy().to_string(), Some("3/3".into())).is_err());
+    }
+}
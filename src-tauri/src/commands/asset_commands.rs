@@ -0,0 +1,340 @@
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::services::durable;
+use crate::services::notes::vault_rel;
+
+const ASSETS_DIR: &str = "assets/images";
+const INDEX_FILE: &str = ".lifeos/assets-index.json";
+pub(crate) const THUMBS_DIR: &str = ".lifeos/thumbnails";
+/// Longest side of a thumbnail, in pixels
+const THUMB_SIZE: u32 = 256;
+const IMAGE_EXTS: [&str; 6] = ["jpg", "jpeg", "png", "gif", "webp", "heic"];
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Asset {
+    /// Vault-relative, forward slashes
+    pub path: String,
+    pub name: String,
+    pub size: u64,
+    /// Unix seconds
+    pub modified: u64,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// EXIF DateTimeOriginal, `YYYY-MM-DD HH:MM:SS`
+    pub taken: Option<String>,
+    /// Vault-relative thumbnail, None when the format cannot be decoded
+    pub thumbnail: Option<String>,
+    /// Vault-relative notes that embed or link the asset
+    pub references: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AssetFilter {
+    /// Substring of the file name, case-insensitive
+    pub query: Option<String>,
+    /// `YYYY-MM-DD`, compared to the EXIF date (else the file date)
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub extension: Option<String>,
+    /// Only assets with (true) or without (false) references
+    pub referenced: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AssetIndexReport {
+    pub assets: usize,
+    pub thumbnails_created: usize,
+    pub orphaned: usize,
+    pub bytes: u64,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Rescan assets/images: dimensions, EXIF dates, thumbnails (only for new
+/// or changed files) and note references
+#[tauri::command]
+pub async fn rebuild_asset_index(vault_path: String) -> Result<AssetIndexReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let (assets, thumbnails_created) = build_index(Path::new(&vault_path))?;
+        Ok(AssetIndexReport {
+            assets: assets.len(),
+            thumbnails_created,
+            orphaned: assets.iter().filter(|a| a.references.is_empty()).count(),
+            bytes: assets.iter().map(|a| a.size).sum(),
+        })
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Assets from the saved index (built on first use), newest first
+#[tauri::command]
+pub async fn list_assets(vault_path: String, filters: Option<AssetFilter>) -> Result<Vec<Asset>, String> {
+    tokio::task::spawn_blocking(move || {
+        let vault = Path::new(&vault_path);
+        let assets = match read_index(vault) {
+            Some(assets) => assets,
+            None => build_index(vault)?.0,
+        };
+        Ok(filter_assets(assets, &filters.unwrap_or_default()))
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Assets no note refers to. Always rescans first, so a stale index never
+/// marks a file in use as safe to delete.
+#[tauri::command]
+pub async fn find_orphaned_assets(vault_path: String) -> Result<Vec<Asset>, String> {
+    tokio::task::spawn_blocking(move || {
+        let assets = build_index(Path::new(&vault_path))?.0;
+        Ok(filter_assets(assets, &AssetFilter { referenced: Some(false), ..Default::default() }))
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn read_index(vault: &Path) -> Option<Vec<Asset>> {
+    serde_json::from_str(&fs::read_to_string(vault.join(INDEX_FILE)).ok()?).ok()
+}
+
+/// Returns the assets and how many thumbnails were (re)generated
+fn build_index(vault: &Path) -> Result<(Vec<Asset>, usize), String> {
+    let previous: HashMap<String, Asset> =
+        read_index(vault).unwrap_or_default().into_iter().map(|a| (a.path.clone(), a)).collect();
+    let thumbs = vault.join(THUMBS_DIR);
+    fs::create_dir_all(&thumbs).map_err(|e| tr!("Failed to create directory: {}", e))?;
+
+    let mut created = 0;
+    let mut assets = Vec::new();
+    let files = WalkDir::new(vault.join(ASSETS_DIR))
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && is_image(e.path()));
+    for entry in files {
+        let path = entry.path();
+        let rel = vault_rel(vault, path);
+        let Ok(meta) = entry.metadata() else { continue };
+        let modified = meta.modified().ok().and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok()).map_or(0, |d| d.as_secs());
+
+        let mut asset = match previous.get(&rel) {
+            // Unchanged file: keep the EXIF/size work from last time
+            Some(prev) if prev.modified == modified && prev.size == meta.len() => prev.clone(),
+            _ => {
+                let (width, height) = image::image_dimensions(path).map_or((None, None), |(w, h)| (Some(w), Some(h)));
+                Asset {
+                    path: rel.clone(),
+                    name: entry.file_name().to_string_lossy().to_string(),
+                    size: meta.len(),
+                    modified,
+                    width,
+                    height,
+                    taken: exif_date(path),
+                    thumbnail: None,
+                    references: vec![],
+                }
+            }
+        };
+        let thumb = thumbs.join(format!("{}.jpg", thumb_key(&rel)));
+        let fresh = fs::metadata(&thumb)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .is_some_and(|t| t.as_secs() >= modified);
+        asset.thumbnail = if fresh {
+            Some(vault_rel(vault, &thumb))
+        } else {
+            match make_thumbnail(path, &thumb) {
+                Ok(()) => {
+                    created += 1;
+                    Some(vault_rel(vault, &thumb))
+                }
+                Err(e) => {
+                    println!("[WARN] no thumbnail for {rel}: {e}");
+                    None
+                }
+            }
+        };
+        asset.references.clear();
+        assets.push(asset);
+    }
+
+    link_references(vault, &mut assets);
+    assets.sort_by(|a, b| sort_date(b).cmp(&sort_date(a)).then_with(|| a.path.cmp(&b.path)));
+    let json = serde_json::to_string_pretty(&assets).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(vault.join(INDEX_FILE), json).map_err(|e| tr!("write_file failed: {}", e))?;
+    Ok((assets, created))
+}
+
+fn is_image(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| IMAGE_EXTS.contains(&ext.to_string_lossy().to_lowercase().as_str()))
+}
+
+pub(crate) fn thumb_key(rel: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    rel.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+fn make_thumbnail(src: &Path, dest: &Path) -> Result<(), String> {
+    let img = image::open(src).map_err(|e| e.to_string())?;
+    img.thumbnail(THUMB_SIZE, THUMB_SIZE).into_rgb8().save(dest).map_err(|e| e.to_string())
+}
+
+/// DateTimeOriginal (else DateTime) as `YYYY-MM-DD HH:MM:SS`
+fn exif_date(path: &Path) -> Option<String> {
+    let file = fs::File::open(path).ok()?;
+    let exif = exif::Reader::new().read_from_container(&mut std::io::BufReader::new(file)).ok()?;
+    let field = exif
+        .get_field(exif::Tag::DateTimeOriginal, exif::In::PRIMARY)
+        .or_else(|| exif.get_field(exif::Tag::DateTime, exif::In::PRIMARY))?;
+    match &field.value {
+        exif::Value::Ascii(parts) => parts.first().and_then(|raw| normalize_exif_date(&String::from_utf8_lossy(raw))),
+        _ => None,
+    }
+}
+
+/// `2024:05:01 10:00:00` → `2024-05-01 10:00:00`
+fn normalize_exif_date(raw: &str) -> Option<String> {
+    let dt = chrono::NaiveDateTime::parse_from_str(raw.trim(), "%Y:%m:%d %H:%M:%S").ok()?;
+    Some(dt.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+/// Date used for sorting and range filters: EXIF when known, else mtime
+fn sort_date(asset: &Asset) -> String {
+    asset.taken.clone().unwrap_or_else(|| {
+        chrono::DateTime::from_timestamp(asset.modified as i64, 0)
+            .map(|d| d.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default()
+    })
+}
+
+/// Fill `references` from every note's image embeds and links
+fn link_references(vault: &Path, assets: &mut [Asset]) {
+    let mut by_name: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (i, a) in assets.iter().enumerate() {
+        by_name.entry(a.name.to_lowercase()).or_default().push(i);
+    }
+    let notes = WalkDir::new(vault)
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext == "md"));
+    for note in notes {
+        let Ok(raw) = fs::read_to_string(note.path()) else { continue };
+        let note_rel = vault_rel(vault, note.path());
+        for target in link_targets(&raw) {
+            let name = target.rsplit('/').next().unwrap_or(&target).to_lowercase();
+            let Some(candidates) = by_name.get(&name) else { continue };
+            // Same file name in two folders: the link's path has to match too
+            let hits: Vec<usize> = match candidates.as_slice() {
+                [only] => vec![*only],
+                many => many.iter().copied().filter(|&i| assets[i].path.ends_with(target.trim_start_matches("../"))).collect(),
+            };
+            for i in hits {
+                if !assets[i].references.contains(&note_rel) {
+                    assets[i].references.push(note_rel.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Targets of `![alt](path)`, `[text](path)`, `![[name|size]]` and
+/// `src="path"`, with `./`, titles and %20 cleaned up
+fn link_targets(text: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut collect = |raw: &str| {
+        let raw = raw.trim().trim_start_matches('<').trim_end_matches('>');
+        let raw = raw.split(['|', '#', '?']).next().unwrap_or(raw);
+        // Markdown link titles: (path "title")
+        let raw = raw.split(" \"").next().unwrap_or(raw);
+        let cleaned = raw.replace("%20", " ").trim_start_matches("./").trim_start_matches('/').to_string();
+        if !cleaned.is_empty() {
+            out.push(cleaned);
+        }
+    };
+    for (open, close) in [("](", ")"), ("[[", "]]"), ("src=\"", "\"")] {
+        let mut rest = text;
+        while let Some(start) = rest.find(open) {
+            rest = &rest[start + open.len()..];
+            let Some(end) = rest.find(close) else { break };
+            collect(&rest[..end]);
+            rest = &rest[end + close.len()..];
+        }
+    }
+    out
+}
+
+fn filter_assets(assets: Vec<Asset>, f: &AssetFilter) -> Vec<Asset> {
+    let query = f.query.as_deref().map(str::to_lowercase).filter(|q| !q.is_empty());
+    let ext = f.extension.as_deref().map(|e| e.trim_start_matches('.').to_lowercase());
+    assets
+        .into_iter()
+        .filter(|a| query.as_ref().is_none_or(|q| a.name.to_lowercase().contains(q)))
+        .filter(|a| ext.as_ref().is_none_or(|e| a.name.to_lowercase().ends_with(&format!(".{e}"))))
+        .filter(|a| f.referenced.is_none_or(|want| a.references.is_empty() != want))
+        .filter(|a| {
+            let day = sort_date(a);
+            let day = day.get(..10).unwrap_or(&day);
+            f.from.as_deref().is_none_or(|from| day >= from) && f.to.as_deref().is_none_or(|to| day <= to)
+        })
+        .collect()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_targets() {
+        let note = "![a](../assets/images/cat%20one.png \"Cat\")\n![[dog.jpg|300]]\n<img src=\"./assets/images/x.webp\">\n[doc](notes/a.md)";
+        assert_eq!(link_targets(note), vec!["../assets/images/cat one.png", "notes/a.md", "dog.jpg", "assets/images/x.webp"]);
+        assert_eq!(normalize_exif_date("2024:05:01 10:00:00").as_deref(), Some("2024-05-01 10:00:00"));
+    }
+
+    #[test]
+    fn test_index_references_and_orphans() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path();
+        fs::create_dir_all(vault.join("assets/images/trip")).unwrap();
+        fs::create_dir_all(vault.join("diary/2025")).unwrap();
+        let pixel = image::RgbImage::from_pixel(4, 2, image::Rgb([200, 100, 0]));
+        for name in ["assets/images/a.png", "assets/images/trip/a.png", "assets/images/b.png"] {
+            pixel.save(vault.join(name)).unwrap();
+        }
+        fs::write(vault.join("diary/2025/d.md"), "![](../../assets/images/trip/a.png)\n![[b.png]]").unwrap();
+
+        let (assets, created) = build_index(vault).unwrap();
+        assert_eq!((assets.len(), created), (3, 3));
+        let refs = |p: &str| assets.iter().find(|a| a.path == p).unwrap().references.clone();
+        assert_eq!(refs("assets/images/trip/a.png"), vec!["diary/2025/d.md"]);
+        assert!(refs("assets/images/a.png").is_empty());
+        assert_eq!(assets[0].width, Some(4));
+
+        let orphans = filter_assets(assets, &AssetFilter { referenced: Some(false), ..Default::default() });
+        assert_eq!(orphans.iter().map(|a| a.path.as_str()).collect::<Vec<_>>(), vec!["assets/images/a.png"]);
+        // Second run reuses the thumbnails
+        assert_eq!(build_index(vault).unwrap().1, 0);
+    }
+}
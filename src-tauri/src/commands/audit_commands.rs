@@ -0,0 +1,34 @@
+use std::path::Path;
+use tauri::ipc::Invoke;
+use tauri::Runtime;
+
+use crate::services::audit::{self, AuditEntry, AuditQuery};
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Audit log entries matching `filter`, newest first
+#[tauri::command]
+pub fn query_audit_log(vault_path: String, filter: Option<AuditQuery>) -> Vec<AuditEntry> {
+    audit::query(Path::new(&vault_path), &filter.unwrap_or_default())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Invoke hook
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Wrap the command handler so changes made while it runs are logged under
+/// the command's name and its window. Synchronous commands run inside the
+/// handler; async ones continue on the runtime and are logged as plain
+/// writes by "app".
+pub fn audited<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let label = invoke.message.webview_ref().label().to_string();
+        let actor = if label == "main" { "app".to_string() } else { format!("app:{label}") };
+        let command = invoke.message.command().to_string();
+        audit::scope(&actor, &command, || handler(invoke))
+    }
+}
@@ -0,0 +1,340 @@
+use chrono::{DateTime, Local};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use walkdir::WalkDir;
+
+use crate::services::automations::{self, Event, Rule, RuleFile, RunReport, Trigger, Vars};
+use crate::services::durable;
+
+/// Emitted with a `RunReport` after every automation run
+pub const AUTOMATION_RUN_EVENT: &str = "automation-run";
+
+/// Persisted schedule clocks (rule id → last run), so daily rules survive restarts
+const SCHEDULE_STATE: &str = ".lifeos/automations/.schedule.json";
+const TICK: Duration = Duration::from_secs(30);
+/// Window in which repeat events for one path (editor saves, our own writes) are dropped
+const DEBOUNCE: Duration = Duration::from_secs(3);
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// State shared by the file watcher callback and the scheduler thread
+struct Runner {
+    app: AppHandle,
+    vault: PathBuf,
+    rules: Vec<Rule>,
+    last_run: HashMap<String, DateTime<Local>>,
+    recent: HashMap<PathBuf, Instant>,
+    known_emails: HashSet<PathBuf>,
+    done_tasks: HashMap<PathBuf, HashSet<String>>,
+}
+
+struct Engine {
+    _watcher: RecommendedWatcher,
+    stop: Arc<AtomicBool>,
+}
+
+// One vault at a time, like the config watcher
+static ENGINE: Lazy<Mutex<Option<Engine>>> = Lazy::new(|| Mutex::new(None));
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Start running the vault's automation rules: a recursive watcher feeds
+/// file-change / new-email / task-done triggers and a 30s ticker drives
+/// schedules. Calling again replaces the running engine.
+#[tauri::command]
+pub fn start_automations(app: AppHandle, vault_path: String) -> Result<(), String> {
+    stop_automations();
+
+    let vault = PathBuf::from(&vault_path);
+    if !vault.exists() {
+        return Err(tr!("Path does not exist: {}", vault_path));
+    }
+    fs::create_dir_all(vault.join(automations::RULES_DIR)).map_err(|e| tr!("Failed to create directory: {}", e))?;
+
+    let mut runner = Runner {
+        app,
+        rules: automations::load_rules(&vault),
+        last_run: load_schedule_state(&vault),
+        recent: HashMap::new(),
+        known_emails: snapshot_emails(&vault),
+        done_tasks: snapshot_tasks(&vault),
+        vault: vault.clone(),
+    };
+    runner.start_clocks();
+    let runner = Arc::new(Mutex::new(runner));
+
+    let watch_runner = runner.clone();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| match res {
+        Ok(event) => {
+            for path in &event.paths {
+                handle_path(&watch_runner, path);
+            }
+        }
+        Err(e) => println!("[WARN] automation watcher error: {e}"),
+    })
+    .map_err(|e| tr!("Failed to create file watcher: {}", e))?;
+    watcher
+        .watch(&vault, RecursiveMode::Recursive)
+        .map_err(|e| tr!("Failed to watch {}: {}", vault.display(), e))?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let tick_stop = stop.clone();
+    std::thread::spawn(move || {
+        // Sleep in short steps so stop_automations takes effect promptly
+        let mut last_tick = Instant::now();
+        while !tick_stop.load(Ordering::Relaxed) {
+            std::thread::sleep(Duration::from_secs(1));
+            if last_tick.elapsed() < TICK {
+                continue;
+            }
+            last_tick = Instant::now();
+            let due = runner.lock().unwrap().due_schedules(Local::now());
+            for rule in due {
+                dispatch_rule(&runner, &rule, "schedule", Vars::new());
+            }
+        }
+    });
+
+    *ENGINE.lock().unwrap() = Some(Engine { _watcher: watcher, stop });
+    Ok(())
+}
+
+#[tauri::command]
+pub fn stop_automations() {
+    if let Some(engine) = ENGINE.lock().unwrap().take() {
+        engine.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Every rule file, including ones that fail to parse
+#[tauri::command]
+pub fn list_automations(vault_path: String) -> Vec<RuleFile> {
+    automations::load_rule_files(Path::new(&vault_path))
+}
+
+/// Run one rule now regardless of its trigger (enabled or not). `dry_run`
+/// reports what each action would do instead.
+#[tauri::command]
+pub async fn run_automation(vault_path: String, id: String, dry_run: Option<bool>) -> Result<RunReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let vault = PathBuf::from(&vault_path);
+        let rule = automations::load_rules(&vault)
+            .into_iter()
+            .find(|r| r.id == id)
+            .ok_or_else(|| tr!("Automation not found: {}", id))?;
+        let vars = automations::match_event(&vault, &rule.trigger, &Event::Manual).unwrap_or_default();
+        Ok(automations::run_rule(&vault, &rule, "manual", vars, dry_run.unwrap_or(false)))
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+#[tauri::command]
+pub fn get_automation_runs(vault_path: String, limit: Option<usize>) -> Vec<RunReport> {
+    automations::recent_runs(Path::new(&vault_path), limit.unwrap_or(50))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Engine
+// ─────────────────────────────────────────────────────────────────────────────
+
+impl Runner {
+    /// New schedule rules start counting from now instead of firing at once
+    fn start_clocks(&mut self) {
+        let now = Local::now();
+        for rule in &self.rules {
+            if matches!(rule.trigger, Trigger::Schedule { .. }) {
+                self.last_run.entry(rule.id.clone()).or_insert(now);
+            }
+        }
+    }
+
+    fn due_schedules(&mut self, now: DateTime<Local>) -> Vec<Rule> {
+        let due: Vec<Rule> = self
+            .rules
+            .iter()
+            .filter(|r| r.enabled && automations::schedule_due(&r.trigger, self.last_run.get(&r.id).copied(), now))
+            .cloned()
+            .collect();
+        if !due.is_empty() {
+            for rule in &due {
+                self.last_run.insert(rule.id.clone(), now);
+            }
+            save_schedule_state(&self.vault, &self.last_run);
+        }
+        due
+    }
+
+    /// Drop events for paths seen within DEBOUNCE
+    fn debounced(&mut self, path: &Path) -> bool {
+        let now = Instant::now();
+        self.recent.retain(|_, t| now.duration_since(*t) < DEBOUNCE * 10);
+        if self.recent.get(path).is_some_and(|t| now.duration_since(*t) < DEBOUNCE) {
+            return true;
+        }
+        self.recent.insert(path.to_path_buf(), now);
+        false
+    }
+
+    /// Translate a raw path change into engine events
+    fn events_for(&mut self, path: &Path) -> Vec<(Event, &'static str)> {
+        let Ok(rel) = path.strip_prefix(&self.vault) else { return vec![] };
+        let rel = rel.to_path_buf();
+
+        if rel.starts_with(automations::RULES_DIR) {
+            if rel.extension().is_some_and(|ext| ext == "yaml" || ext == "yml") {
+                self.rules = automations::load_rules(&self.vault);
+                self.start_clocks();
+            }
+            return vec![];
+        }
+        if rel.components().any(|c| c.as_os_str().to_string_lossy().starts_with('.')) || !path.is_file() {
+            return vec![];
+        }
+
+        let mut events = Vec::new();
+        if rel.starts_with("Mailbox") && rel.extension().is_some_and(|ext| ext == "eml") {
+            if self.known_emails.insert(path.to_path_buf()) {
+                events.push((email_event(path, &rel), "new-email"));
+            }
+        } else if rel.starts_with("daily/tasks") && rel.extension().is_some_and(|ext| ext == "md") {
+            let now_done = done_tasks_in(path);
+            let before = self.done_tasks.insert(path.to_path_buf(), now_done.clone()).unwrap_or_default();
+            for task in now_done.difference(&before) {
+                events.push((Event::TaskDone { path: path.to_path_buf(), task: task.clone() }, "task-done"));
+            }
+        }
+        if !self.debounced(path) {
+            events.push((Event::FileChanged { path: path.to_path_buf() }, "file-change"));
+        }
+        events
+    }
+}
+
+fn handle_path(runner: &Arc<Mutex<Runner>>, path: &Path) {
+    let matched: Vec<(Rule, Vars, &'static str)> = {
+        let mut r = runner.lock().unwrap();
+        let events = r.events_for(path);
+        let mut matched = Vec::new();
+        for (event, label) in &events {
+            for rule in r.rules.iter().filter(|r| r.enabled) {
+                if let Some(vars) = automations::match_event(&r.vault, &rule.trigger, event) {
+                    matched.push((rule.clone(), vars, *label));
+                }
+            }
+        }
+        matched
+    };
+    // Run outside the lock: webhooks and shortcuts can take a while
+    for (rule, vars, label) in matched {
+        dispatch_rule(runner, &rule, label, vars);
+    }
+}
+
+fn dispatch_rule(runner: &Arc<Mutex<Runner>>, rule: &Rule, label: &str, vars: Vars) {
+    let (vault, app) = {
+        let r = runner.lock().unwrap();
+        (r.vault.clone(), r.app.clone())
+    };
+    let report = automations::run_rule(&vault, rule, label, vars, false);
+    {
+        // Our own writes must not re-trigger file-change rules
+        let mut r = runner.lock().unwrap();
+        let now = Instant::now();
+        for path in &report.touched {
+            r.recent.insert(path.clone(), now);
+        }
+    }
+    if let Err(e) = app.emit(AUTOMATION_RUN_EVENT, &report) {
+        println!("[WARN] failed to emit {AUTOMATION_RUN_EVENT}: {e}");
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn snapshot_emails(vault: &Path) -> HashSet<PathBuf> {
+    WalkDir::new(vault.join("Mailbox"))
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "eml"))
+        .map(|e| e.into_path())
+        .collect()
+}
+
+fn snapshot_tasks(vault: &Path) -> HashMap<PathBuf, HashSet<String>> {
+    WalkDir::new(vault.join("daily/tasks"))
+        .max_depth(1)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "md"))
+        .map(|e| {
+            let done = done_tasks_in(e.path());
+            (e.into_path(), done)
+        })
+        .collect()
+}
+
+fn done_tasks_in(path: &Path) -> HashSet<String> {
+    let raw = fs::read_to_string(path).unwrap_or_default();
+    raw.lines()
+        .filter_map(|l| {
+            let l = l.trim_start();
+            l.strip_prefix("- [x]").or_else(|| l.strip_prefix("- [X]"))
+        })
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+fn email_event(path: &Path, rel: &Path) -> Event {
+    use mail_parser::MessageParser;
+    let raw = fs::read(path).unwrap_or_default();
+    let parsed = MessageParser::default().parse(&raw);
+    let from = parsed
+        .as_ref()
+        .and_then(|m| m.from())
+        .and_then(|a| a.first())
+        .and_then(|a| a.address())
+        .unwrap_or("")
+        .to_string();
+    let subject = parsed.as_ref().and_then(|m| m.subject()).unwrap_or("").to_string();
+    // Mailbox/{account_id}/...
+    let account = rel
+        .components()
+        .nth(1)
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .unwrap_or_default();
+    Event::NewEmail { path: path.to_path_buf(), account, from, subject }
+}
+
+fn load_schedule_state(vault: &Path) -> HashMap<String, DateTime<Local>> {
+    fs::read_to_string(vault.join(SCHEDULE_STATE))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn save_schedule_state(vault: &Path, state: &HashMap<String, DateTime<Local>>) {
+    let path = vault.join(SCHEDULE_STATE);
+    match serde_json::to_string_pretty(state) {
+        Ok(json) => {
+            if let Err(e) = durable::write(&path, json) {
+                println!("[WARN] failed to write {}: {e}", path.display());
+            }
+        }
+        Err(e) => println!("[WARN] failed to serialize schedule state: {e}"),
+    }
+}
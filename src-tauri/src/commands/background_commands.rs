@@ -0,0 +1,244 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, RunEvent};
+#[cfg(desktop)]
+use tauri::{Manager, Window, WindowEvent};
+
+use super::email_commands;
+use crate::services::mail;
+use crate::services::periodic::Periodic;
+#[cfg(not(target_os = "macos"))]
+use crate::services::unsupported;
+#[cfg(desktop)]
+use crate::services;
+
+/// Kept apart from settings.yaml, which the frontend rewrites with only its own keys
+const BACKGROUND_FILE: &str = ".lifeos/background.yaml";
+/// Passed by the login item: start with the window hidden
+#[cfg(desktop)]
+const BACKGROUND_ARG: &str = "--background";
+/// The LaunchAgent is named after the bundle identifier
+#[cfg(target_os = "macos")]
+pub(crate) const LOGIN_AGENT_ID: &str = "app";
+#[cfg(desktop)]
+const MAIN_WINDOW: &str = "main";
+/// Newest messages fetched per folder on each background mail sync
+const MAIL_SYNC_LIMIT: u32 = 20;
+/// How often folders are checked for a due sync
+const MAIL_CHECK: Duration = Duration::from_secs(30);
+
+static AGENT: Periodic = Periodic::new();
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// .lifeos/background.yaml
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BackgroundSettings {
+    /// Closing the window only hides it, so reminders, sync loops and mail
+    /// sync keep running until the app is quit
+    #[serde(default)]
+    pub keep_running: bool,
+    /// Mail sync interval while the app runs, for folders without their own
+    /// (`syncIntervals` / `syncMinutes` in the account file); 0 = off for all
+    #[serde(default = "default_mail_sync_minutes")]
+    pub mail_sync_minutes: u64,
+}
+
+fn default_mail_sync_minutes() -> u64 {
+    15
+}
+
+impl Default for BackgroundSettings {
+    fn default() -> Self {
+        Self { keep_running: false, mail_sync_minutes: default_mail_sync_minutes() }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn get_background_settings(vault_path: String) -> BackgroundSettings {
+    load_settings(Path::new(&vault_path))
+}
+
+#[tauri::command]
+pub fn save_background_settings(vault_path: String, settings: BackgroundSettings) -> Result<(), String> {
+    let path = PathBuf::from(&vault_path).join(BACKGROUND_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let raw = serde_yaml::to_string(&settings).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    services::durable::write(&path, raw).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+/// Sync each folder of every enabled mail account on its own interval
+/// (`mail_sync_minutes` unless the account file sets one); settings and
+/// accounts are re-read each check so edits apply without a restart
+#[tauri::command]
+pub fn start_background_agent(app: AppHandle, vault_path: String) {
+    let mut last_synced: HashMap<(String, String), Instant> = HashMap::new();
+    AGENT.start(MAIL_CHECK, move || {
+        let minutes = load_settings(Path::new(&vault_path)).mail_sync_minutes;
+        if minutes > 0 {
+            sync_due_mail(&app, &vault_path, minutes, &mut last_synced);
+        }
+    });
+}
+
+#[tauri::command]
+pub fn stop_background_agent() {
+    AGENT.stop();
+}
+
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn get_launch_at_login() -> bool {
+    login_agent_path().is_ok_and(|p| p.exists())
+}
+
+/// Install or remove a LaunchAgent that starts the app hidden at login. It
+/// is not loaded now: that would launch a second copy straight away.
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn set_launch_at_login(enabled: bool) -> Result<(), String> {
+    let path = login_agent_path()?;
+    if !enabled {
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| tr!("Failed to delete plist: {}", e))?;
+        }
+        return Ok(());
+    }
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    let plist = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>com.lifeos.{id}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{exe}</string>
+        <string>{arg}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>"#,
+        id = LOGIN_AGENT_ID,
+        exe = exe.to_string_lossy().replace('&', "&amp;").replace('<', "&lt;"),
+        arg = BACKGROUND_ARG,
+    );
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    fs::write(&path, plist).map_err(|e| tr!("Failed to write plist: {}", e))
+}
+
+/// Nothing can be installed here, so it is never on
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub fn get_launch_at_login() -> bool {
+    false
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub fn set_launch_at_login(enabled: bool) -> Result<(), String> {
+    let _ = enabled;
+    Err(unsupported("set_launch_at_login"))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// App events
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Started by the login item: keep the window hidden. The webview still
+/// loads, so the frontend starts its reminders and loops as usual.
+#[cfg(desktop)]
+pub(crate) fn setup(app: &AppHandle) {
+    if std::env::args().any(|a| a == BACKGROUND_ARG) {
+        if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+            let _ = window.hide();
+        }
+    }
+}
+
+/// With `keep_running`, closing the main window hides it instead
+#[cfg(desktop)]
+pub(crate) fn on_window_event(window: &Window, event: &WindowEvent) {
+    let WindowEvent::CloseRequested { api, .. } = event else { return };
+    let keep_running = services::configured_vault().is_some_and(|v| load_settings(Path::new(&v)).keep_running);
+    if window.label() == MAIN_WINDOW && keep_running {
+        api.prevent_close();
+        let _ = window.hide();
+    }
+}
+
+/// Clicking the Dock icon brings a hidden window back
+pub(crate) fn on_run_event(app: &AppHandle, event: RunEvent) {
+    #[cfg(target_os = "macos")]
+    if let RunEvent::Reopen { .. } = event {
+        if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+    #[cfg(not(target_os = "macos"))]
+    let _ = (app, event);
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn load_settings(vault: &Path) -> BackgroundSettings {
+    fs::read_to_string(vault.join(BACKGROUND_FILE))
+        .ok()
+        .and_then(|raw| serde_yaml::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "macos")]
+fn login_agent_path() -> Result<PathBuf, String> {
+    let home = std::env::var("HOME").map_err(|e| e.to_string())?;
+    Ok(PathBuf::from(home).join(format!("Library/LaunchAgents/com.lifeos.{LOGIN_AGENT_ID}.plist")))
+}
+
+/// Sync the folders whose interval has passed since `last_synced` (all of
+/// them on the first call), recording when each ran
+fn sync_due_mail(app: &AppHandle, vault_path: &str, default_minutes: u64, last_synced: &mut HashMap<(String, String), Instant>) {
+    let accounts = match mail::load_accounts(vault_path) {
+        Ok(accounts) => accounts,
+        Err(e) => return println!("[WARN] background mail sync: {e}"),
+    };
+    for account in accounts.iter().filter(|a| a.enabled) {
+        let due: Vec<String> = mail::synced_folders(account)
+            .into_iter()
+            .filter(|folder| {
+                let minutes = account.sync_interval(folder, default_minutes);
+                let last = last_synced.get(&(account.id.clone(), folder.clone()));
+                minutes > 0 && last.is_none_or(|t| t.elapsed() >= Duration::from_secs(minutes * 60))
+            })
+            .collect();
+        if due.is_empty() {
+            continue;
+        }
+        for folder in due {
+            last_synced.insert((account.id.clone(), folder.clone()), Instant::now());
+            let report = mail::sync_folder(vault_path, account, &folder, MAIL_SYNC_LIMIT);
+            if let Some(e) = report.error {
+                println!("[WARN] mail sync {}/{} failed: {e}", report.account_id, report.folder);
+            }
+        }
+        email_commands::warn_failing_account(app, vault_path, &account.id);
+        email_commands::notify_new_mail(app, &account.id);
+    }
+}
@@ -0,0 +1,236 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::services::git::{self, DevStatus};
+use crate::services::notes::{self, split_frontmatter};
+
+const BOARD_FILE: &str = ".lifeos/board.yaml";
+const PROJECTS_DIR: &str = "projects";
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Deserialize, Debug, Default)]
+struct BoardConfig {
+    #[serde(default)]
+    columns: Vec<BoardColumn>,
+    #[serde(default)]
+    rules: Vec<BoardRule>,
+}
+
+#[derive(Deserialize, Debug)]
+struct BoardColumn {
+    id: String,
+    #[serde(default)]
+    name: String,
+    /// Most projects the column may hold at once; unlimited when omitted
+    #[serde(default)]
+    wip_limit: Option<usize>,
+}
+
+/// Applied when a project moves between columns, e.g.
+/// ```yaml
+/// rules:
+///   - to: done
+///     set: { progress: 100 }
+///     stamp: [completed]
+///   - from: done
+///     set: { completed: null }
+/// ```
+#[derive(Deserialize, Debug)]
+struct BoardRule {
+    /// Column entered; any when omitted
+    #[serde(default)]
+    to: Option<String>,
+    /// Column left; any when omitted
+    #[serde(default)]
+    from: Option<String>,
+    /// Frontmatter fields to write; `null` removes the field
+    #[serde(default)]
+    set: BTreeMap<String, Value>,
+    /// Fields set to today's date
+    #[serde(default)]
+    stamp: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct MoveResult {
+    pub path: String,
+    pub from: String,
+    pub to: String,
+    /// Frontmatter written besides `status` (rules and `updated`); `null` = removed
+    pub fields: BTreeMap<String, Value>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Set a project's `status`, refusing when the target column is at its
+/// `wip_limit`, and apply the board's matching rules
+#[tauri::command]
+pub fn move_project(vault_path: String, path: String, to: String) -> Result<MoveResult, String> {
+    let vault = PathBuf::from(&vault_path);
+    let path = PathBuf::from(&path);
+    let content = fs::read_to_string(&path).map_err(|e| tr!("Failed to read: {}", e))?;
+    let from = status_of(&content).unwrap_or_default();
+    let mut result = MoveResult { path: path.to_string_lossy().to_string(), from: from.clone(), to: to.clone(), fields: BTreeMap::new() };
+    if from == to {
+        return Ok(result);
+    }
+
+    let board = load_board(&vault);
+    if let Some(column) = board.columns.iter().find(|c| c.id == to) {
+        if let Some(limit) = column.wip_limit {
+            let count = count_in_column(&vault, &to, &path);
+            if count >= limit {
+                let name = if column.name.is_empty() { &column.id } else { &column.name };
+                return Err(tr!("WIP limit reached for {}: {}/{}", name, count, limit));
+            }
+        }
+    }
+
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    result.fields.insert("updated".to_string(), Value::String(today.clone()));
+    result.fields.extend(rule_fields(&board.rules, &from, &to, &today));
+
+    let mut updated = notes::set_field(&content, "status", Some(&Value::String(to)))?;
+    for (key, value) in &result.fields {
+        updated = notes::set_field(&updated, key, Some(value).filter(|v| !v.is_null()))?;
+    }
+    fs::write(&path, updated).map_err(|e| tr!("write_file failed: {}", e))?;
+    Ok(result)
+}
+
+/// Branch, uncommitted changes, recent commits and TODO comments of the
+/// local repository named by the project's `repo` frontmatter (`~/code/x`,
+/// absolute, or relative to the vault)
+#[tauri::command]
+pub async fn get_project_dev_status(vault_path: String, slug: String) -> Result<DevStatus, String> {
+    tokio::task::spawn_blocking(move || {
+        let vault = PathBuf::from(&vault_path);
+        let repo = project_repo(&vault, &slug)?;
+        git::dev_status(&repo)
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// `repo` of projects/**/<slug>.md, resolved to a path
+fn project_repo(vault: &Path, slug: &str) -> Result<PathBuf, String> {
+    let path = WalkDir::new(vault.join(PROJECTS_DIR))
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .find(|e| e.file_type().is_file() && e.path().extension().is_some_and(|x| x == "md") && e.path().file_stem().is_some_and(|s| s == slug))
+        .ok_or_else(|| tr!("Project not found: {}", slug))?;
+    let raw = fs::read_to_string(path.path()).map_err(|e| tr!("Failed to read: {}", e))?;
+    let doc: Value = split_frontmatter(&raw).0.and_then(|fm| serde_yaml::from_str(fm).ok()).unwrap_or_default();
+    let repo = doc.get("repo").and_then(|v| v.as_str()).map(str::trim).filter(|r| !r.is_empty());
+    let repo = repo.ok_or_else(|| tr!("Project has no repo: {}", slug))?;
+    Ok(match repo.strip_prefix("~/") {
+        Some(rest) => PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(rest),
+        None if Path::new(repo).is_absolute() => PathBuf::from(repo),
+        None => vault.join(repo),
+    })
+}
+
+/// Fields from every rule matching the move, later rules winning
+fn rule_fields(rules: &[BoardRule], from: &str, to: &str, today: &str) -> BTreeMap<String, Value> {
+    let mut fields = BTreeMap::new();
+    let matches = |want: &Option<String>, actual: &str| want.as_deref().is_none_or(|w| w == actual);
+    for rule in rules.iter().filter(|r| matches(&r.to, to) && matches(&r.from, from)) {
+        fields.extend(rule.set.iter().map(|(k, v)| (k.clone(), v.clone())));
+        fields.extend(rule.stamp.iter().map(|k| (k.clone(), Value::String(today.to_string()))));
+    }
+    fields
+}
+
+/// Projects with `status: <column>`, not counting the one being moved
+fn count_in_column(vault: &Path, column: &str, moving: &Path) -> usize {
+    WalkDir::new(vault.join(PROJECTS_DIR))
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|x| x == "md"))
+        .filter(|e| e.path() != moving)
+        .filter_map(|e| fs::read_to_string(e.path()).ok())
+        .filter(|raw| status_of(raw).as_deref() == Some(column))
+        .count()
+}
+
+fn status_of(raw: &str) -> Option<String> {
+    let (fm, _) = split_frontmatter(raw);
+    let doc: Value = serde_yaml::from_str(fm?).ok()?;
+    doc.get("status")?.as_str().map(str::to_string)
+}
+
+fn load_board(vault: &Path) -> BoardConfig {
+    fs::read_to_string(vault.join(BOARD_FILE))
+        .ok()
+        .and_then(|raw| serde_yaml::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOARD: &str = "columns:\n  - id: active\n    name: 进行中\n    wip_limit: 1\n  - id: done\n    name: 已完成\nrules:\n  - to: done\n    set: { progress: 100 }\n    stamp: [completed]\n  - from: done\n    set: { completed: null }\n";
+
+    fn project(vault: &Path, slug: &str, status: &str) -> String {
+        let path = vault.join(PROJECTS_DIR).join(format!("{slug}.md"));
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, format!("---\ntitle: {slug}\nstatus: {status}\nprogress: 40\n---\n\n## 待规划\n")).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_project_repo() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        project(v, "plain", "todo");
+        fs::create_dir_all(v.join("projects/done")).unwrap();
+        fs::write(v.join("projects/done/site.md"), "---\ntitle: site\nrepo: code/site\n---\n").unwrap();
+        assert_eq!(project_repo(v, "site").unwrap(), v.join("code/site"));
+        assert!(project_repo(v, "plain").is_err());
+        assert!(project_repo(v, "missing").is_err());
+    }
+
+    #[test]
+    fn test_wip_limit_and_rules() {
+        let vault = tempfile::tempdir().unwrap();
+        fs::create_dir_all(vault.path().join(".lifeos")).unwrap();
+        fs::write(vault.path().join(BOARD_FILE), BOARD).unwrap();
+        let v = vault.path().to_string_lossy().to_string();
+        let a = project(vault.path(), "a", "todo");
+        let b = project(vault.path(), "b", "todo");
+
+        move_project(v.clone(), a.clone(), "active".into()).unwrap();
+        assert!(move_project(v.clone(), b.clone(), "active".into()).is_err());
+        // Re-dropping into its own column is a no-op, not a WIP violation
+        assert!(move_project(v.clone(), a.clone(), "active".into()).unwrap().fields.is_empty());
+
+        let moved = move_project(v.clone(), a.clone(), "done".into()).unwrap();
+        assert_eq!(moved.from, "active");
+        assert_eq!(moved.fields["progress"], Value::from(100));
+        let raw = fs::read_to_string(&a).unwrap();
+        assert!(raw.contains("status: done") && raw.contains("progress: 100") && raw.contains("completed: "));
+        move_project(v.clone(), b, "active".into()).unwrap();
+
+        move_project(v, a.clone(), "todo".into()).unwrap();
+        assert!(!fs::read_to_string(&a).unwrap().contains("completed:"));
+    }
+}
@@ -0,0 +1,718 @@
+use chrono::{Datelike, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::services::highlights::{self, Clipping};
+use crate::services::notes::slugify;
+use crate::services::{epub, http, pdf, records};
+
+const BOOKS_DIR: &str = "life/books";
+/// Files `register_reading_file` accepts
+const READING_EXTS: [&str; 2] = ["epub", "pdf"];
+/// Body section holding highlights, one `> quote` block each
+const HIGHLIGHTS_HEADING: &str = "## 摘录";
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Typed frontmatter of life/books/<slug>.md; unknown keys are kept in `extra`
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct BookMeta {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub authors: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isbn: Option<String>,
+    /// "want" | "reading" | "finished" | "abandoned"
+    #[serde(default = "default_status")]
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pages: Option<u32>,
+    /// Current page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress: Option<u32>,
+    /// `YYYY-MM-DD`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started: Option<String>,
+    /// `YYYY-MM-DD`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished: Option<String>,
+    /// 1–5
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cover: Option<String>,
+    /// Vault-relative EPUB or PDF being read in the app
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Where the reader left off in `file`, as it reports it (EPUB CFI, PDF page)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<String>,
+    /// 0–100, for files without page numbers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub percent: Option<u8>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_yaml::Value>,
+}
+
+fn default_status() -> String {
+    "want".to_string()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Highlight {
+    pub text: String,
+    #[serde(default)]
+    pub page: Option<u32>,
+    /// Kindle location (`1234-1236`) or Apple Books chapter
+    #[serde(default)]
+    pub location: Option<String>,
+    /// `YYYY-MM-DD` it was highlighted, when the import knows it
+    #[serde(default)]
+    pub date: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Book {
+    /// File stem; empty on create to derive it from the title
+    #[serde(default)]
+    pub slug: String,
+    #[serde(default)]
+    pub path: String,
+    #[serde(flatten)]
+    pub meta: BookMeta,
+    /// Parsed from the 摘录 section of the body
+    #[serde(default)]
+    pub highlights: Vec<Highlight>,
+    /// Markdown body without the highlights section
+    #[serde(default)]
+    pub notes: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ReadingStats {
+    pub year: i32,
+    pub finished: usize,
+    /// Pages of the books finished this year
+    pub pages: u32,
+    /// Books finished per month, January first
+    pub by_month: [usize; 12],
+    pub average_rating: Option<f64>,
+    pub reading: Vec<Book>,
+    pub finished_books: Vec<Book>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct HighlightImportReport {
+    pub imported: usize,
+    /// Already in the book note, or part of a longer highlight that is
+    pub existing: usize,
+    /// Bookmarks, notes and empty entries
+    pub skipped: usize,
+    /// Book notes created for titles not in the vault yet
+    pub created: Vec<String>,
+    /// Existing books that got new highlights
+    pub updated: Vec<String>,
+    /// Planned only; no book note was written
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Books sorted by title, optionally only one status
+#[tauri::command]
+pub fn list_books(vault_path: String, status: Option<String>) -> Vec<Book> {
+    load_books(Path::new(&vault_path))
+        .into_iter()
+        .filter(|b| status.as_ref().is_none_or(|s| &b.meta.status == s))
+        .collect()
+}
+
+#[tauri::command]
+pub fn get_book(vault_path: String, slug: String) -> Result<Book, String> {
+    read_book(&book_path(Path::new(&vault_path), &slug)?).ok_or_else(|| tr!("Book not found: {}", slug))
+}
+
+#[tauri::command]
+pub fn save_book(vault_path: String, book: Book) -> Result<Book, String> {
+    let vault = PathBuf::from(&vault_path);
+    let mut book = book;
+    book.meta.title = book.meta.title.trim().to_string();
+    if book.meta.title.is_empty() {
+        return Err(tr!("Title is required"));
+    }
+    if book.slug.is_empty() {
+        book.slug = records::unique_slug(&vault, BOOKS_DIR, &slugify(&book.meta.title));
+    }
+    let path = book_path(&vault, &book.slug)?;
+    write_book(&path, &book)?;
+    book.path = path.to_string_lossy().to_string();
+    Ok(book)
+}
+
+#[tauri::command]
+pub fn delete_book(vault_path: String, slug: String) -> Result<(), String> {
+    fs::remove_file(book_path(Path::new(&vault_path), &slug)?).map_err(|e| e.to_string())
+}
+
+/// Metadata for an ISBN from Open Library, without saving anything
+#[tauri::command]
+pub async fn lookup_isbn(isbn: String) -> Result<BookMeta, String> {
+    tokio::task::spawn_blocking(move || fetch_open_library(&isbn))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Look the ISBN up and add it to the reading list (status "want"). An ISBN
+/// already in the vault returns the existing book.
+#[tauri::command]
+pub async fn add_book_by_isbn(vault_path: String, isbn: String) -> Result<Book, String> {
+    let wanted = normalize_isbn(&isbn);
+    if let Some(existing) =
+        load_books(Path::new(&vault_path)).into_iter().find(|b| b.meta.isbn.as_deref().map(normalize_isbn) == Some(wanted.clone()))
+    {
+        return Ok(existing);
+    }
+    let meta = lookup_isbn(isbn).await?;
+    save_book(vault_path, Book { slug: String::new(), path: String::new(), meta, highlights: vec![], notes: String::new() })
+}
+
+/// Set the current page. Starting a book marks it "reading"; reaching the
+/// last page marks it "finished" with today's date.
+#[tauri::command]
+pub fn update_book_progress(vault_path: String, slug: String, page: u32) -> Result<Book, String> {
+    let mut book = get_book(vault_path.clone(), slug)?;
+    let done = book.meta.pages.is_some_and(|total| page >= total);
+    advance(&mut book.meta, page > 0, done);
+    book.meta.progress = Some(page);
+    save_book(vault_path, book)
+}
+
+/// Track an EPUB or PDF in the vault as a book: the book note with that
+/// file, else the one with the same title (which gets the file), else a new
+/// "want" note from the file's metadata. PDFs get their page count.
+#[tauri::command]
+pub async fn register_reading_file(vault_path: String, path: String) -> Result<Book, String> {
+    tokio::task::spawn_blocking(move || register(Path::new(&vault_path), &path))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Remember where the reader is in the book's file. `fraction` (0–1) is how
+/// far through; `page` when the file has pages, else it is estimated from
+/// `pages`. Moves the book to "reading", and to "finished" at the end, like
+/// `update_book_progress`.
+#[tauri::command]
+pub fn save_book_position(vault_path: String, slug: String, position: String, fraction: f64, page: Option<u32>) -> Result<Book, String> {
+    let mut book = get_book(vault_path.clone(), slug)?;
+    let fraction = fraction.clamp(0.0, 1.0);
+    let page = page.or_else(|| book.meta.pages.map(|total| (fraction * total as f64).round() as u32));
+    let done = fraction >= 1.0 || page.zip(book.meta.pages).is_some_and(|(page, total)| page >= total);
+    advance(&mut book.meta, fraction > 0.0 || page.is_some_and(|p| p > 0), done);
+    book.meta.position = Some(position).filter(|p| !p.trim().is_empty());
+    book.meta.percent = Some((fraction * 100.0).round() as u8);
+    book.meta.progress = page.or(book.meta.progress);
+    save_book(vault_path, book)
+}
+
+/// `location` is where the reader found it (EPUB CFI, Kindle location, chapter)
+#[tauri::command]
+pub fn add_book_highlight(vault_path: String, slug: String, text: String, page: Option<u32>, location: Option<String>) -> Result<Book, String> {
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err(tr!("Highlight is empty"));
+    }
+    let mut book = get_book(vault_path.clone(), slug)?;
+    let location = location.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+    let date = Some(Local::now().format("%Y-%m-%d").to_string());
+    book.highlights.push(Highlight { text, page, location, date });
+    save_book(vault_path, book)
+}
+
+/// Import Kindle's `My Clippings.txt`, highlights copied from Apple Books or a
+/// CSV of Apple Books annotations into the book notes with the same title
+/// (created as "reading" when missing). Highlights already filed are left
+/// alone, so the same file can be imported again as it grows. `dry_run`
+/// reports which books would be created or updated without writing them.
+#[tauri::command]
+pub async fn import_highlights(vault_path: String, file_path: String, dry_run: Option<bool>) -> Result<HighlightImportReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let raw = fs::read_to_string(&file_path).map_err(|e| tr!("Failed to read: {}", e))?;
+        let name = Path::new(&file_path).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let (clippings, skipped) = highlights::parse(&name, &raw)?;
+        let mut report = file_clippings(Path::new(&vault_path), clippings, dry_run.unwrap_or(false))?;
+        report.skipped += skipped;
+        Ok(report)
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Finished books, pages and ratings for `year` (default this year)
+#[tauri::command]
+pub fn get_reading_stats(vault_path: String, year: Option<i32>) -> ReadingStats {
+    reading_stats(load_books(Path::new(&vault_path)), year.unwrap_or_else(|| Local::now().year()))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn load_books(vault: &Path) -> Vec<Book> {
+    let mut books: Vec<Book> = records::list(vault, BOOKS_DIR).iter().filter_map(|p| read_book(p)).collect();
+    books.sort_by(|a, b| a.meta.title.cmp(&b.meta.title));
+    books
+}
+
+fn register(vault: &Path, path: &str) -> Result<Book, String> {
+    let (rel, full) = reading_file(vault, path)?;
+    let books = load_books(vault);
+    if let Some(book) = books.iter().find(|b| b.meta.file.as_deref() == Some(rel.as_str())) {
+        return Ok(book.clone());
+    }
+    let stem = full.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let mut meta = BookMeta { title: stem, status: default_status(), ..Default::default() };
+    if ext_of(&full) == "epub" {
+        let info = epub::info(&full)?;
+        meta.title = Some(info.title).filter(|t| !t.is_empty()).unwrap_or(meta.title);
+        meta.authors = info.authors;
+        meta.isbn = info.isbn;
+    } else {
+        // Page count is nice to have; an unreadable PDF is still tracked
+        meta.pages = pdf::extract(vault, &full).ok().map(|t| t.pages.len() as u32);
+    }
+    let same_title = books.into_iter().find(|b| b.meta.file.is_none() && title_key(&b.meta.title) == title_key(&meta.title));
+    let mut book = same_title.unwrap_or(Book { slug: String::new(), path: String::new(), meta, highlights: vec![], notes: String::new() });
+    book.meta.file = Some(rel);
+    save_book(vault.to_string_lossy().to_string(), book)
+}
+
+/// Starting a book marks it "reading" (dated the first time); `done` marks
+/// it "finished" with today's date
+fn advance(meta: &mut BookMeta, started: bool, done: bool) {
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    if meta.started.is_none() && started {
+        meta.started = Some(today.clone());
+    }
+    if done {
+        meta.status = "finished".to_string();
+        meta.finished.get_or_insert(today);
+    } else if meta.status == "want" && started {
+        meta.status = "reading".to_string();
+    }
+}
+
+/// (vault-relative, full) path of an EPUB or PDF inside the vault
+fn reading_file(vault: &Path, path: &str) -> Result<(String, PathBuf), String> {
+    let p = Path::new(path);
+    let full = if p.is_absolute() { p.to_path_buf() } else { vault.join(p) };
+    let rel = full
+        .strip_prefix(vault)
+        .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+        .map_err(|_| tr!("Path is outside the vault: {}", path))?;
+    if !full.is_file() || !READING_EXTS.contains(&ext_of(&full).as_str()) {
+        return Err(tr!("Not an EPUB or PDF file: {}", path));
+    }
+    Ok((rel, full))
+}
+
+fn ext_of(path: &Path) -> String {
+    path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default()
+}
+
+fn book_path(vault: &Path, slug: &str) -> Result<PathBuf, String> {
+    records::path(vault, BOOKS_DIR, slug)
+}
+
+fn read_book(path: &Path) -> Option<Book> {
+    let record = records::read::<BookMeta>(path)?;
+    let mut meta = record.meta.unwrap_or_else(|| BookMeta { status: default_status(), ..Default::default() });
+    if meta.title.is_empty() {
+        meta.title = record.slug.clone();
+    }
+    let (notes, highlights) = split_highlights(&record.body);
+    Some(Book { slug: record.slug, path: record.path, meta, highlights, notes })
+}
+
+fn write_book(path: &Path, book: &Book) -> Result<(), String> {
+    let mut body = match book.notes.trim() {
+        "" => format!("# {}\n", book.meta.title),
+        notes => format!("{notes}\n"),
+    };
+    if !book.highlights.is_empty() {
+        body.push_str(&format!("\n{HIGHLIGHTS_HEADING}\n"));
+        for h in &book.highlights {
+            body.push('\n');
+            for line in h.text.lines() {
+                body.push_str(&format!("> {line}\n"));
+            }
+            let meta: Vec<String> =
+                [h.page.map(|p| format!("p.{p}")), h.location.as_ref().map(|l| format!("loc. {l}")), h.date.clone()].into_iter().flatten().collect();
+            if !meta.is_empty() {
+                body.push_str(&format!("> — {}\n", meta.join(" · ")));
+            }
+        }
+    }
+    records::write(path, &book.meta, &body)
+}
+
+/// Split the 摘录 section (up to the next `## ` heading) off the body and
+/// parse its quote blocks
+fn split_highlights(body: &str) -> (String, Vec<Highlight>) {
+    let Some(start) = body.find(HIGHLIGHTS_HEADING) else { return (body.trim().to_string(), vec![]) };
+    let section_start = start + HIGHLIGHTS_HEADING.len();
+    let end = body[section_start..].find("\n## ").map_or(body.len(), |i| section_start + i + 1);
+    let notes = format!("{}{}", &body[..start], &body[end..]).trim().to_string();
+
+    let mut highlights = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut meta = None;
+    let mut flush = |current: &mut Vec<String>, meta: &mut Option<(Option<u32>, Option<String>, Option<String>)>| {
+        if !current.is_empty() {
+            let (page, location, date) = meta.take().unwrap_or_default();
+            highlights.push(Highlight { text: current.join("\n"), page, location, date });
+            current.clear();
+        }
+    };
+    for line in body[section_start..end].lines() {
+        match line.trim().strip_prefix('>') {
+            Some(quote) => {
+                let quote = quote.trim();
+                match parse_highlight_meta(quote) {
+                    Some(m) => meta = Some(m),
+                    None => current.push(quote.to_string()),
+                }
+            }
+            None => flush(&mut current, &mut meta),
+        }
+    }
+    flush(&mut current, &mut meta);
+    (notes, highlights)
+}
+
+/// `— p.12 · loc. 170-172 · 2024-03-15`, any subset in that order. A line
+/// with anything else after the dash is part of the quote (an attribution).
+fn parse_highlight_meta(line: &str) -> Option<(Option<u32>, Option<String>, Option<String>)> {
+    let (mut page, mut location, mut date) = (None, None, None);
+    for part in line.strip_prefix("— ")?.split(" · ").map(str::trim) {
+        if let Some(p) = part.strip_prefix("p.").and_then(|p| p.trim().parse().ok()) {
+            page = Some(p);
+        } else if let Some(l) = part.strip_prefix("loc. ") {
+            location = Some(l.trim().to_string());
+        } else if NaiveDate::parse_from_str(part, "%Y-%m-%d").is_ok() {
+            date = Some(part.to_string());
+        } else {
+            return None;
+        }
+    }
+    Some((page, location, date))
+}
+
+fn file_clippings(vault: &Path, clippings: Vec<Clipping>, dry_run: bool) -> Result<HighlightImportReport, String> {
+    let mut report = HighlightImportReport { dry_run, ..Default::default() };
+    // Grouped by book, in file order
+    let mut by_book: Vec<(String, Vec<Clipping>)> = Vec::new();
+    for clipping in clippings {
+        let key = title_key(&clipping.title);
+        match by_book.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, list)) => list.push(clipping),
+            None => by_book.push((key, vec![clipping])),
+        }
+    }
+    let books = load_books(vault);
+    for (key, clippings) in by_book {
+        let mut book = books.iter().find(|b| title_key(&b.meta.title) == key).cloned().unwrap_or_else(|| Book {
+            slug: String::new(),
+            path: String::new(),
+            meta: BookMeta {
+                title: clippings[0].title.clone(),
+                authors: clippings[0].authors.clone(),
+                status: "reading".to_string(),
+                ..Default::default()
+            },
+            highlights: vec![],
+            notes: String::new(),
+        });
+        let mut added = false;
+        for clipping in clippings {
+            let text = highlights::normalize(&clipping.text);
+            if book.highlights.iter().any(|h| highlights::normalize(&h.text).contains(&text)) {
+                report.existing += 1;
+                continue;
+            }
+            let highlight = Highlight { text: clipping.text, page: clipping.page, location: clipping.location, date: clipping.date };
+            // Extending a Kindle highlight adds a second clipping at the same start
+            let start = |h: &Highlight| h.location.as_deref().and_then(|l| l.split('-').next()).map(str::to_string);
+            match book.highlights.iter().position(|h| start(h).is_some() && start(h) == start(&highlight) && text.contains(&highlights::normalize(&h.text))) {
+                Some(i) => book.highlights[i] = highlight,
+                None => book.highlights.push(highlight),
+            }
+            report.imported += 1;
+            added = true;
+        }
+        if !added {
+            continue;
+        }
+        let is_new = book.slug.is_empty();
+        let slug = if !dry_run {
+            save_book(vault.to_string_lossy().to_string(), book)?.slug
+        } else if is_new {
+            records::unique_slug(vault, BOOKS_DIR, &slugify(&book.meta.title))
+        } else {
+            book.slug
+        };
+        (if is_new { &mut report.created } else { &mut report.updated }).push(slug);
+    }
+    Ok(report)
+}
+
+/// Main title without subtitle or edition, letters and digits only, so
+/// `Atomic Habits: An Easy & Proven Way…` files into `Atomic Habits`
+fn title_key(title: &str) -> String {
+    let main = title.split([':', '：', '(', '（']).next().unwrap_or(title);
+    let key: String = main.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect();
+    if key.is_empty() { title.trim().to_lowercase() } else { key }
+}
+
+/// Digits and a trailing X only, so `978-7-5442-...` matches `9787544...`
+fn normalize_isbn(isbn: &str) -> String {
+    isbn.chars().filter(|c| c.is_ascii_digit() || *c == 'X' || *c == 'x').collect::<String>().to_uppercase()
+}
+
+fn fetch_open_library(isbn: &str) -> Result<BookMeta, String> {
+    let isbn = normalize_isbn(isbn);
+    if !(isbn.len() == 10 || isbn.len() == 13) {
+        return Err(tr!("Invalid ISBN: {}", isbn));
+    }
+    let key = format!("ISBN:{isbn}");
+    let json = http::get_json(&format!("https://openlibrary.org/api/books?bibkeys={key}&format=json&jscmd=data"))?;
+    let data = json.get(&key).ok_or_else(|| tr!("No book found for ISBN {}", isbn))?;
+    Ok(parse_open_library(data, &isbn))
+}
+
+fn parse_open_library(data: &serde_json::Value, isbn: &str) -> BookMeta {
+    let str_at = |v: &serde_json::Value, key: &str| v.get(key).and_then(|s| s.as_str()).map(str::to_string);
+    let names = |key: &str| -> Vec<String> {
+        data.get(key)
+            .and_then(|v| v.as_array())
+            .map(|items| items.iter().filter_map(|i| str_at(i, "name")).collect())
+            .unwrap_or_default()
+    };
+    BookMeta {
+        title: str_at(data, "title").unwrap_or_default(),
+        authors: names("authors"),
+        isbn: Some(isbn.to_string()),
+        status: default_status(),
+        pages: data.get("number_of_pages").and_then(|n| n.as_u64()).map(|n| n as u32),
+        publisher: names("publishers").into_iter().next(),
+        published: str_at(data, "publish_date"),
+        cover: data.get("cover").and_then(|c| str_at(c, "medium").or_else(|| str_at(c, "large"))),
+        ..Default::default()
+    }
+}
+
+fn reading_stats(books: Vec<Book>, year: i32) -> ReadingStats {
+    let mut stats = ReadingStats { year, ..Default::default() };
+    let mut ratings = Vec::new();
+    for book in books {
+        match book.meta.status.as_str() {
+            "reading" => stats.reading.push(book),
+            "finished" => {
+                let Some(date) = book.meta.finished.as_deref().and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+                else {
+                    continue;
+                };
+                if date.year() != year {
+                    continue;
+                }
+                stats.finished += 1;
+                stats.by_month[date.month0() as usize] += 1;
+                stats.pages += book.meta.pages.unwrap_or(0);
+                ratings.extend(book.meta.rating.map(f64::from));
+                stats.finished_books.push(book);
+            }
+            _ => {}
+        }
+    }
+    if !ratings.is_empty() {
+        let avg = ratings.iter().sum::<f64>() / ratings.len() as f64;
+        stats.average_rating = Some((avg * 10.0).round() / 10.0);
+    }
+    stats.finished_books.sort_by(|a, b| a.meta.finished.cmp(&b.meta.finished));
+    stats
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlights_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("book.md");
+        let book = Book {
+            slug: "book".into(),
+            path: String::new(),
+            meta: BookMeta { title: "活着".into(), status: "reading".into(), ..Default::default() },
+            highlights: vec![
+                Highlight { text: "人是为活着本身而活着的".into(), page: Some(12), location: None, date: None },
+                Highlight { text: "line one\nline two".into(), page: None, location: None, date: None },
+                Highlight { text: "— 福贵".into(), page: None, location: Some("113-114".into()), date: Some("2023-11-02".into()) },
+            ],
+            notes: "# 活着\n\n读后感".into(),
+        };
+        write_book(&path, &book).unwrap();
+        let read = read_book(&path).unwrap();
+        assert_eq!(read.highlights, book.highlights);
+        assert_eq!(read.notes, "# 活着\n\n读后感");
+    }
+
+    #[test]
+    fn test_slug_stays_in_books() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().to_string_lossy().to_string();
+        fs::write(dir.path().join("x.md"), "keep").unwrap();
+        assert_eq!(delete_book(vault.clone(), "../../x".into()).unwrap_err(), tr!("Invalid slug: {}", "../../x"));
+        assert!(get_book(vault, "../../x".into()).is_err());
+        assert!(dir.path().join("x.md").exists());
+    }
+
+    #[test]
+    fn test_import_highlights() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        save_book(v.to_string_lossy().to_string(), Book {
+            slug: String::new(),
+            path: String::new(),
+            meta: BookMeta { title: "Atomic Habits".into(), status: "finished".into(), ..Default::default() },
+            highlights: vec![],
+            notes: String::new(),
+        })
+        .unwrap();
+        let clip = |title: &str, text: &str, location: &str| Clipping {
+            title: title.into(),
+            authors: vec!["Author".into()],
+            text: text.into(),
+            location: Some(location.into()),
+            ..Default::default()
+        };
+        let clippings = vec![
+            clip("Atomic Habits: Tiny Changes", "Small habits", "170-171"),
+            clip("活着", "人是为活着本身而活着的", "113-114"),
+            clip("Atomic Habits: Tiny Changes", "Small habits make a big difference", "170-172"),
+        ];
+        let preview = file_clippings(v, clippings.clone(), true).unwrap();
+        assert_eq!((preview.imported, preview.created.clone()), (3, vec!["活着".to_string()]));
+        assert!(get_book(v.to_string_lossy().to_string(), "活着".into()).is_err());
+
+        let report = file_clippings(v, clippings, false).unwrap();
+        assert_eq!((report.imported, report.created.clone(), report.updated.clone()), (3, vec!["活着".to_string()], vec!["atomic-habits".to_string()]));
+        let book = get_book(v.to_string_lossy().to_string(), "atomic-habits".into()).unwrap();
+        assert_eq!(book.highlights.len(), 1);
+        assert_eq!(book.highlights[0].location.as_deref(), Some("170-172"));
+
+        // Importing again, even re-wrapped, adds nothing
+        let report = file_clippings(v, vec![clip("Atomic Habits", "Small  habits make\na big difference", "170-172"), clip("活着", "人是为活着本身而活着的", "113-114")], false).unwrap();
+        assert_eq!((report.imported, report.existing), (0, 2));
+        assert!(report.created.is_empty() && report.updated.is_empty());
+    }
+
+    #[test]
+    fn test_reading_file_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path();
+        let v = vault.to_string_lossy().to_string();
+        fs::create_dir_all(vault.join("assets/books")).unwrap();
+        // Not a readable PDF: tracked without a page count
+        fs::write(vault.join("assets/books/Deep Work.pdf"), b"%PDF-1.4 broken").unwrap();
+        fs::write(vault.join("assets/books/notes.txt"), b"").unwrap();
+        let existing = save_book(v.clone(), Book {
+            slug: String::new(),
+            path: String::new(),
+            meta: BookMeta { title: "Deep Work: Rules for Focused Success".into(), status: "want".into(), pages: Some(300), ..Default::default() },
+            highlights: vec![],
+            notes: String::new(),
+        })
+        .unwrap();
+
+        let book = register(vault, "assets/books/Deep Work.pdf").unwrap();
+        assert_eq!((book.slug.as_str(), book.meta.file.as_deref()), (existing.slug.as_str(), Some("assets/books/Deep Work.pdf")));
+        assert_eq!(register(vault, &vault.join("assets/books/Deep Work.pdf").to_string_lossy()).unwrap().slug, existing.slug);
+        assert!(register(vault, "assets/books/notes.txt").is_err());
+        assert!(register(vault, "assets/books/missing.pdf").is_err());
+
+        let book = save_book_position(v.clone(), existing.slug.clone(), "page=45".into(), 0.15, None).unwrap();
+        assert_eq!((book.meta.status.as_str(), book.meta.progress, book.meta.percent), ("reading", Some(45), Some(15)));
+        assert!(book.meta.started.is_some() && book.meta.finished.is_none());
+        let book = add_book_highlight(v.clone(), existing.slug.clone(), "Clarity about what matters".into(), Some(45), Some("page=45".into())).unwrap();
+        assert_eq!(book.highlights[0].location.as_deref(), Some("page=45"));
+        let book = save_book_position(v.clone(), existing.slug.clone(), "page=300".into(), 1.0, Some(300)).unwrap();
+        assert_eq!((book.meta.status.as_str(), book.meta.position.as_deref()), ("finished", Some("page=300")));
+        assert_eq!(get_reading_stats(v, None).finished, 1);
+    }
+
+    #[test]
+    fn test_parse_open_library() {
+        let data = serde_json::json!({
+            "title": "The Pragmatic Programmer",
+            "authors": [{"name": "David Thomas"}, {"name": "Andrew Hunt"}],
+            "number_of_pages": 352,
+            "publishers": [{"name": "Addison-Wesley"}],
+            "publish_date": "2019",
+            "cover": {"medium": "https://covers.openlibrary.org/b/id/1-M.jpg"}
+        });
+        let meta = parse_open_library(&data, "9780135957059");
+        assert_eq!(meta.authors, vec!["David Thomas", "Andrew Hunt"]);
+        assert_eq!(meta.pages, Some(352));
+        assert_eq!(meta.publisher.as_deref(), Some("Addison-Wesley"));
+        assert_eq!(normalize_isbn("978-0-13-595705-9"), "9780135957059");
+    }
+
+    #[test]
+    fn test_reading_stats() {
+        let book = |status: &str, finished: Option<&str>, pages, rating| Book {
+            slug: String::new(),
+            path: String::new(),
+            meta: BookMeta {
+                status: status.into(),
+                finished: finished.map(str::to_string),
+                pages: Some(pages),
+                rating,
+                ..Default::default()
+            },
+            highlights: vec![],
+            notes: String::new(),
+        };
+        let stats = reading_stats(
+            vec![
+                book("finished", Some("2025-02-03"), 200, Some(4)),
+                book("finished", Some("2025-02-20"), 100, Some(5)),
+                book("finished", Some("2024-12-31"), 300, None),
+                book("reading", None, 50, None),
+            ],
+            2025,
+        );
+        assert_eq!(stats.finished, 2);
+        assert_eq!(stats.pages, 300);
+        assert_eq!(stats.by_month[1], 2);
+        assert_eq!(stats.average_rating, Some(4.5));
+        assert_eq!(stats.reading.len(), 1);
+    }
+}
@@ -0,0 +1,99 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+use super::outbox_commands;
+use crate::services::google::{self, GoogleStatus};
+use crate::services::google_calendar::{self, CalendarSyncReport};
+use crate::services::outbox::{self, Operation};
+use crate::services::periodic::Periodic;
+
+static CALENDAR_LOOP: Periodic = Periodic::new();
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Whether the `google:` client is set and an account is signed in
+#[tauri::command]
+pub fn google_status(vault_path: String) -> GoogleStatus {
+    google::status(Path::new(&vault_path))
+}
+
+/// Open Google's consent page in the browser and wait for it to come back.
+/// `scopes` defaults to the calendar; returns every scope granted.
+#[tauri::command]
+pub async fn google_sign_in(vault_path: String, scopes: Option<Vec<String>>) -> Result<Vec<String>, String> {
+    let scopes = scopes.unwrap_or_else(|| vec![google::CALENDAR_SCOPE.to_string()]);
+    tokio::task::spawn_blocking(move || {
+        let scopes: Vec<&str> = scopes.iter().map(String::as_str).collect();
+        google::sign_in(Path::new(&vault_path), &scopes, &|url| open::that(url).map_err(|e| tr!("Failed to open link: {}", e)))
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+#[tauri::command]
+pub fn google_sign_out(vault_path: String) -> Result<(), String> {
+    google::sign_out(Path::new(&vault_path))
+}
+
+/// Pull Google Calendar changes and push time blocks now; offline, a retry
+/// is queued in the outbox
+#[tauri::command]
+pub async fn sync_google_calendar(app: AppHandle, vault_path: String) -> Result<CalendarSyncReport, String> {
+    tokio::task::spawn_blocking(move || sync_or_queue(&app, &vault_path))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Report of the last finished calendar sync, if any
+#[tauri::command]
+pub fn get_calendar_sync_status(vault_path: String) -> Option<CalendarSyncReport> {
+    google_calendar::last_report(Path::new(&vault_path))
+}
+
+/// Sync every `calendar.interval_minutes` while `calendar.enabled` is on and
+/// an account is signed in; settings are re-read each tick
+#[tauri::command]
+pub fn start_calendar_sync_loop(app: AppHandle, vault_path: String) {
+    let vault = PathBuf::from(&vault_path);
+    let mut last_run: Option<Instant> = None;
+    CALENDAR_LOOP.start(Duration::from_secs(1), move || {
+        let settings = google_calendar::settings(&vault);
+        let every = Duration::from_secs(settings.interval_minutes.max(1) * 60);
+        if settings.enabled && last_run.is_none_or(|t| t.elapsed() >= every) && google::status(&vault).signed_in {
+            last_run = Some(Instant::now());
+            match sync_or_queue(&app, &vault_path) {
+                Ok(report) if !report.errors.is_empty() => println!("[WARN] calendar sync finished with {} errors", report.errors.len()),
+                Ok(_) => {}
+                Err(e) => println!("[WARN] calendar sync failed: {e}"),
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn stop_calendar_sync_loop() {
+    CALENDAR_LOOP.stop();
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Sync, queuing a retry when the network was the reason it failed (wholly,
+/// or for some events) so the outbox pushes the changes once back online
+fn sync_or_queue(app: &AppHandle, vault_path: &str) -> Result<CalendarSyncReport, String> {
+    let result = google_calendar::sync(Path::new(vault_path));
+    let offline = match &result {
+        Ok(report) => report.errors.iter().any(|e| outbox::is_network_error(e)),
+        Err(e) => outbox::is_network_error(e),
+    };
+    if offline {
+        if let Err(e) = outbox_commands::queue(app, vault_path, Operation::CalendarSync) {
+            println!("[WARN] failed to queue calendar sync: {e}");
+        }
+    }
+    result
+}
@@ -0,0 +1,227 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::asset_commands::{thumb_key, THUMBS_DIR};
+use crate::services::canvas::{self, Canvas, Issue};
+use crate::services::durable;
+use crate::services::journal;
+use crate::services::notes::vault_rel;
+
+/// Longest side of a canvas thumbnail, in pixels
+const THUMB_SIZE: u32 = 256;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CanvasSummary {
+    /// Vault-relative
+    pub path: String,
+    pub nodes: usize,
+    pub edges: usize,
+    /// Vault-relative files its nodes embed
+    pub references: Vec<String>,
+    /// Unix seconds
+    pub modified: u64,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Every `.canvas` file in the vault, by path. Ones that fail to parse are
+/// skipped with a warning.
+#[tauri::command]
+pub async fn list_canvases(vault_path: String) -> Result<Vec<CanvasSummary>, String> {
+    tokio::task::spawn_blocking(move || Ok(list(Path::new(&vault_path))))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+#[tauri::command]
+pub fn read_canvas(vault_path: String, path: String) -> Result<Canvas, String> {
+    let (_, full) = locate(Path::new(&vault_path), &path)?;
+    let raw = fs::read_to_string(&full).map_err(|e| tr!("Failed to read: {}", e))?;
+    canvas::parse(&raw)
+}
+
+/// A new, empty canvas; returns its vault-relative path
+#[tauri::command]
+pub fn create_canvas(vault_path: String, path: String) -> Result<String, String> {
+    let (rel, full) = locate(Path::new(&vault_path), &path)?;
+    if full.exists() {
+        return Err(tr!("Path already exists: {}", rel));
+    }
+    write(&full, &Canvas::default())?;
+    Ok(rel)
+}
+
+/// Write the canvas, refusing one other apps could not read (see
+/// `canvas::problems`). Returns the file nodes whose notes don't exist yet,
+/// which are saved as they are.
+#[tauri::command]
+pub fn save_canvas(vault_path: String, path: String, canvas: Canvas) -> Result<Vec<Issue>, String> {
+    let vault = Path::new(&vault_path);
+    let (_, full) = locate(vault, &path)?;
+    let problems = canvas::problems(&canvas);
+    if !problems.is_empty() {
+        let messages: Vec<String> = problems.into_iter().map(|i| i.message).collect();
+        return Err(tr!("Invalid canvas: {}", messages.join("; ")));
+    }
+    write(&full, &canvas)?;
+    Ok(canvas::missing_files(vault, &canvas))
+}
+
+/// Move the canvas to the trash (undoable) and drop its thumbnail
+#[tauri::command]
+pub fn delete_canvas(vault_path: String, path: String) -> Result<(), String> {
+    let vault = Path::new(&vault_path);
+    let (rel, full) = locate(vault, &path)?;
+    journal::delete(vault, &full)?;
+    fs::remove_file(thumbnail_path(vault, &rel)).ok();
+    Ok(())
+}
+
+/// Everything wrong with a saved canvas, missing embedded files included
+#[tauri::command]
+pub fn validate_canvas(vault_path: String, path: String) -> Result<Vec<Issue>, String> {
+    let vault = Path::new(&vault_path);
+    let canvas = read_canvas(vault_path.clone(), path)?;
+    let mut issues = canvas::problems(&canvas);
+    issues.extend(canvas::missing_files(vault, &canvas));
+    Ok(issues)
+}
+
+/// Render a PNG preview into .lifeos/thumbnails; returns its vault-relative path
+#[tauri::command]
+pub async fn generate_canvas_thumbnail(vault_path: String, path: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let vault = Path::new(&vault_path);
+        let (rel, _) = locate(vault, &path)?;
+        let canvas = read_canvas(vault_path.clone(), path)?;
+        let thumb = thumbnail_path(vault, &rel);
+        if let Some(parent) = thumb.parent() {
+            fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+        }
+        canvas::render(&canvas, THUMB_SIZE).save(&thumb).map_err(|e| tr!("write_file failed: {}", e))?;
+        Ok(vault_rel(vault, &thumb))
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn list(vault: &Path) -> Vec<CanvasSummary> {
+    let files = WalkDir::new(vault)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|x| x == canvas::EXTENSION));
+    let mut summaries = Vec::new();
+    for entry in files {
+        let rel = vault_rel(vault, entry.path());
+        let parsed = fs::read_to_string(entry.path()).map_err(|e| e.to_string()).and_then(|raw| canvas::parse(&raw));
+        let canvas = match parsed {
+            Ok(canvas) => canvas,
+            Err(e) => {
+                println!("[WARN] skipping canvas {rel}: {e}");
+                continue;
+            }
+        };
+        let modified = entry
+            .metadata()
+            .ok()
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs());
+        summaries.push(CanvasSummary {
+            path: rel,
+            nodes: canvas.nodes.len(),
+            edges: canvas.edges.len(),
+            references: canvas::references(&canvas),
+            modified,
+        });
+    }
+    summaries
+}
+
+/// (vault-relative, full) path of a `.canvas` file in the vault
+fn locate(vault: &Path, path: &str) -> Result<(String, PathBuf), String> {
+    let full = vault.join(path);
+    let rel = full
+        .strip_prefix(vault)
+        .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+        .map_err(|_| tr!("Path is outside the vault: {}", path))?;
+    if full.extension().is_none_or(|x| x != canvas::EXTENSION) {
+        return Err(tr!("Not a canvas file: {}", path));
+    }
+    Ok((rel, full))
+}
+
+fn write(path: &Path, canvas: &Canvas) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    durable::write(path, canvas::to_json(canvas)?).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+fn thumbnail_path(vault: &Path, rel: &str) -> PathBuf {
+    vault.join(THUMBS_DIR).join(format!("{}.png", thumb_key(rel)))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::canvas::{CanvasEdge, CanvasNode, NodeKind};
+
+    #[test]
+    fn test_canvas_crud() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path();
+        let v = vault.to_string_lossy().to_string();
+
+        assert_eq!(create_canvas(v.clone(), "boards/q3.canvas".into()).unwrap(), "boards/q3.canvas");
+        assert!(create_canvas(v.clone(), "boards/q3.canvas".into()).is_err());
+        assert!(create_canvas(v.clone(), "boards/q3.md".into()).is_err());
+        assert_eq!(read_canvas(v.clone(), "boards/q3.canvas".into()).unwrap(), Canvas::default());
+
+        let node = |id: &str, kind, file: Option<&str>| CanvasNode {
+            id: id.into(),
+            kind,
+            width: 200,
+            height: 100,
+            text: (kind == NodeKind::Text).then(|| "Goals".to_string()),
+            file: file.map(str::to_string),
+            ..Default::default()
+        };
+        let mut board = Canvas {
+            nodes: vec![node("a", NodeKind::Text, None), node("b", NodeKind::File, Some("projects/launch.md"))],
+            edges: vec![CanvasEdge { id: "e".into(), from_node: "a".into(), to_node: "b".into(), ..Default::default() }],
+        };
+        let missing = save_canvas(v.clone(), "boards/q3.canvas".into(), board.clone()).unwrap();
+        assert_eq!(missing.iter().map(|i| i.id.as_str()).collect::<Vec<_>>(), vec!["b"]);
+        assert_eq!(validate_canvas(v.clone(), "boards/q3.canvas".into()).unwrap().len(), 1);
+
+        board.edges[0].to_node = "gone".into();
+        assert!(save_canvas(v.clone(), "boards/q3.canvas".into(), board).is_err());
+
+        let listed = list(vault);
+        assert_eq!((listed.len(), listed[0].nodes, listed[0].edges), (1, 2, 1));
+        assert_eq!(listed[0].references, vec!["projects/launch.md"]);
+
+        delete_canvas(v.clone(), "boards/q3.canvas".into()).unwrap();
+        assert!(!vault.join("boards/q3.canvas").exists());
+        assert_eq!(journal::recent(vault, 1)[0].path, "boards/q3.canvas");
+    }
+}
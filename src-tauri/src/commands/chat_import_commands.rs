@@ -0,0 +1,391 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::services::notes::{slugify, split_frontmatter};
+use crate::services::people::{load_people, Person};
+
+/// One note per conversation and day: connectors/chats/<conversation>/<YYYY-MM-DD>.md
+const CHATS_DIR: &str = "connectors/chats";
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ChatImportReport {
+    pub conversation: String,
+    /// "whatsapp" | "wechat" | "imessage"
+    pub format: String,
+    /// Day notes created or extended
+    pub days: usize,
+    pub imported: usize,
+    /// Messages already in the vault from an earlier import
+    pub existing: usize,
+    /// System lines (joins, encryption notices) and text before the first message
+    pub skipped: usize,
+    /// People the participants were linked to, by slug
+    pub linked: Vec<String>,
+    /// Senders with no matching person note
+    pub unmatched: Vec<String>,
+    /// Counted only; nothing was written
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ChatMessage {
+    pub at: NaiveDateTime,
+    pub sender: String,
+    pub text: String,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Import a WhatsApp "Export chat" .txt or a WeChat text export into dated
+/// notes under connectors/chats/. Senders matching a person (name, slug or
+/// `aliases`) are linked as `[[slug]]`; importing the same file again only
+/// adds messages that aren't there yet. `title` defaults to the file name;
+/// `dry_run` reports the import without writing the notes.
+#[tauri::command]
+pub async fn import_chat(vault_path: String, file_path: String, title: Option<String>, dry_run: Option<bool>) -> Result<ChatImportReport, String> {
+    tokio::task::spawn_blocking(move || import(Path::new(&vault_path), Path::new(&file_path), title, dry_run.unwrap_or(false)))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Import
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn import(vault: &Path, file: &Path, title: Option<String>, dry_run: bool) -> Result<ChatImportReport, String> {
+    let raw = fs::read_to_string(file).map_err(|e| tr!("Failed to read: {}", e))?;
+    let (format, messages, skipped) = parse_chat(&raw).ok_or_else(|| tr!("Unrecognized chat export: {}", file.display()))?;
+
+    let senders: BTreeSet<String> = messages.iter().map(|m| m.sender.clone()).collect();
+    let conversation = title
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .or_else(|| title_from_file(file))
+        .unwrap_or_else(|| senders.iter().cloned().collect::<Vec<_>>().join(", "));
+    let report = file_messages(vault, &conversation, format, messages, dry_run)?;
+    Ok(ChatImportReport { skipped, ..report })
+}
+
+/// Merge `messages` into the conversation's day notes, linking senders that
+/// match a person. Shared by the export importers and the iMessage connector.
+pub(crate) fn file_messages(vault: &Path, conversation: &str, format: &str, messages: Vec<ChatMessage>, dry_run: bool) -> Result<ChatImportReport, String> {
+    let senders: BTreeSet<String> = messages.iter().map(|m| m.sender.clone()).collect();
+    let people = load_people(vault);
+    let links: BTreeMap<String, String> = senders.iter().filter_map(|s| match_person(&people, s).map(|p| (s.clone(), p.slug.clone()))).collect();
+    let mut report = ChatImportReport {
+        format: format.to_string(),
+        linked: links.values().cloned().collect::<BTreeSet<_>>().into_iter().collect(),
+        unmatched: senders.iter().filter(|s| !links.contains_key(*s)).cloned().collect(),
+        conversation: conversation.to_string(),
+        dry_run,
+        ..Default::default()
+    };
+
+    let mut by_day: BTreeMap<NaiveDate, Vec<ChatMessage>> = BTreeMap::new();
+    for message in messages {
+        by_day.entry(message.at.date()).or_default().push(message);
+    }
+    let dir = vault.join(CHATS_DIR).join(slugify(conversation));
+    for (day, messages) in by_day {
+        let path = dir.join(format!("{}.md", day.format("%Y-%m-%d")));
+        let mut all = fs::read_to_string(&path).map(|raw| read_messages(&raw, day)).unwrap_or_default();
+        let mut seen: HashSet<(NaiveDateTime, String, String)> = all.iter().map(|m| (m.at, m.sender.clone(), m.text.clone())).collect();
+        let before = all.len();
+        for message in messages {
+            if seen.insert((message.at, message.sender.clone(), message.text.clone())) {
+                all.push(message);
+            } else {
+                report.existing += 1;
+            }
+        }
+        if all.len() == before {
+            continue;
+        }
+        report.imported += all.len() - before;
+        report.days += 1;
+        if dry_run {
+            continue;
+        }
+        all.sort_by_key(|m| m.at);
+        fs::create_dir_all(&dir).map_err(|e| tr!("Failed to create directory: {}", e))?;
+        fs::write(&path, render_day(conversation, format, day, &all, &links)).map_err(|e| tr!("write_file failed: {}", e))?;
+    }
+    Ok(report)
+}
+
+/// `WhatsApp Chat with Alice.txt` → `Alice`; WhatsApp's zipped `_chat.txt` has no usable name
+fn title_from_file(file: &Path) -> Option<String> {
+    let stem = file.file_stem()?.to_string_lossy().to_string();
+    let name = ["WhatsApp Chat with ", "WhatsApp Chat - "].iter().find_map(|p| stem.strip_prefix(p)).unwrap_or(&stem).trim();
+    (!name.is_empty() && !name.starts_with('_')).then(|| name.to_string())
+}
+
+/// Same name (case-insensitive), slug or one of the person's `aliases`
+fn match_person<'a>(people: &'a [Person], sender: &str) -> Option<&'a Person> {
+    let wanted = sender.trim().to_lowercase();
+    let slug = slugify(sender);
+    people.iter().find(|p| {
+        p.meta.name.to_lowercase() == wanted
+            || p.slug == slug
+            || p.meta
+                .extra
+                .get("aliases")
+                .and_then(|v| v.as_sequence())
+                .is_some_and(|seq| seq.iter().filter_map(|a| a.as_str()).any(|a| a.trim().to_lowercase() == wanted))
+    })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Parsing
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Detect the export format; (format, messages, skipped lines)
+fn parse_chat(raw: &str) -> Option<(&'static str, Vec<ChatMessage>, usize)> {
+    let raw = raw.trim_start_matches('\u{feff}');
+    let whatsapp = parse_whatsapp(raw);
+    if !whatsapp.0.is_empty() {
+        return Some(("whatsapp", whatsapp.0, whatsapp.1));
+    }
+    let wechat = parse_wechat(raw);
+    (!wechat.0.is_empty()).then_some(("wechat", wechat.0, wechat.1))
+}
+
+/// iOS `[15/03/2024, 14:05:22] Alice: text` and Android `15/03/2024, 14:05 - Alice: text`.
+/// Day/month order follows the phone's locale, so it is decided once for the
+/// whole file: a first field over 12 means day-first, a second one month-first.
+fn parse_whatsapp(raw: &str) -> (Vec<ChatMessage>, usize) {
+    struct Raw {
+        date: (u32, u32, i32),
+        time: NaiveTime,
+        sender: Option<String>,
+        text: String,
+    }
+    let mut rows: Vec<Raw> = Vec::new();
+    let mut skipped = 0;
+    for line in raw.lines() {
+        let line = line.trim_start_matches('\u{200e}').trim_end_matches('\r');
+        match whatsapp_header(line) {
+            Some((date, time, rest)) => {
+                let (sender, text) = match rest.split_once(": ") {
+                    Some((sender, text)) => (Some(sender.trim_start_matches('\u{200e}').trim().to_string()), text.to_string()),
+                    None => (None, rest.to_string()),
+                };
+                rows.push(Raw { date, time, sender, text });
+            }
+            None => match rows.last_mut() {
+                Some(last) => {
+                    last.text.push('\n');
+                    last.text.push_str(line);
+                }
+                None if line.trim().is_empty() => {}
+                None => skipped += 1,
+            },
+        }
+    }
+    let day_first = !rows.iter().any(|r| r.date.1 > 12) || rows.iter().any(|r| r.date.0 > 12);
+    let mut messages = Vec::new();
+    for row in rows {
+        let (a, b, year) = row.date;
+        let (day, month) = if day_first { (a, b) } else { (b, a) };
+        let (Some(sender), Some(date)) = (row.sender, NaiveDate::from_ymd_opt(year, month, day)) else {
+            skipped += 1;
+            continue;
+        };
+        messages.push(ChatMessage { at: date.and_time(row.time), sender, text: row.text.trim().to_string() });
+    }
+    (messages, skipped)
+}
+
+fn whatsapp_header(line: &str) -> Option<((u32, u32, i32), NaiveTime, &str)> {
+    let (stamp, rest) = match line.strip_prefix('[') {
+        Some(inner) => {
+            let end = inner.find(']')?;
+            (&inner[..end], inner[end + 1..].trim_start())
+        }
+        None => {
+            let end = line.find(" - ")?;
+            (&line[..end], &line[end + 3..])
+        }
+    };
+    let (date, time) = stamp.split_once(',')?;
+    let mut parts = date.trim().split(['/', '.', '-']).map(|p| p.parse::<u32>().ok());
+    let (a, b, year) = (parts.next()??, parts.next()??, parts.next()??);
+    if parts.next().is_some() {
+        return None;
+    }
+    let year = if year < 100 { 2000 + year as i32 } else { year as i32 };
+    let time = time.trim().replace(['\u{202f}', '\u{a0}'], " ");
+    let time = ["%H:%M:%S", "%H:%M", "%I:%M:%S %p", "%I:%M %p"].iter().find_map(|f| NaiveTime::parse_from_str(&time, f).ok())?;
+    Some(((a, b, year), time, rest))
+}
+
+/// `Alice 2024-03-15 14:05:22` (or the timestamp first) on its own line,
+/// followed by the message lines
+fn parse_wechat(raw: &str) -> (Vec<ChatMessage>, usize) {
+    let mut messages: Vec<ChatMessage> = Vec::new();
+    let mut skipped = 0;
+    for line in raw.lines().map(|l| l.trim_end_matches('\r')) {
+        if let Some((at, sender)) = wechat_header(line) {
+            messages.push(ChatMessage { at, sender, text: String::new() });
+        } else if let Some(last) = messages.last_mut() {
+            last.text.push_str(line);
+            last.text.push('\n');
+        } else if !line.trim().is_empty() {
+            skipped += 1;
+        }
+    }
+    for message in &mut messages {
+        message.text = message.text.trim().to_string();
+    }
+    (messages, skipped)
+}
+
+fn wechat_header(line: &str) -> Option<(NaiveDateTime, String)> {
+    let line = line.trim();
+    for (len, format) in [(19, "%Y-%m-%d %H:%M:%S"), (16, "%Y-%m-%d %H:%M")] {
+        if line.len() <= len {
+            continue;
+        }
+        let split = [(line.len() - len, true), (len, false)];
+        for (at, stamp_last) in split {
+            if !line.is_char_boundary(at) {
+                continue;
+            }
+            let (stamp, sender) = if stamp_last { (&line[at..], &line[..at]) } else { (&line[..at], &line[at..]) };
+            let Ok(stamp) = NaiveDateTime::parse_from_str(stamp, format) else { continue };
+            let sender = sender.trim();
+            if !sender.is_empty() && sender.starts_with(|c: char| !c.is_ascii_digit()) {
+                return Some((stamp, sender.to_string()));
+            }
+        }
+    }
+    None
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Day notes
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn render_day(conversation: &str, format: &str, day: NaiveDate, messages: &[ChatMessage], links: &BTreeMap<String, String>) -> String {
+    let date = day.format("%Y-%m-%d");
+    let senders: BTreeSet<&str> = messages.iter().map(|m| m.sender.as_str()).collect();
+    let mut out = String::from("---\n");
+    out.push_str(&format!("title: {}\n", yaml_str(&format!("{conversation} {date}"))));
+    out.push_str(&format!("date: {date}\nsource: {format}\n"));
+    out.push_str(&format!("conversation: {}\nparticipants:\n", yaml_str(conversation)));
+    for sender in &senders {
+        out.push_str(&format!("- {}\n", yaml_str(sender)));
+    }
+    out.push_str(&format!("---\n\n# {conversation} · {date}\n\n"));
+    let names: Vec<String> = senders.iter().map(|s| links.get(*s).map_or_else(|| s.to_string(), |slug| format!("[[{slug}]]"))).collect();
+    out.push_str(&format!("Participants: {}\n\n", names.join(", ")));
+    for m in messages {
+        let time = if m.at.second() == 0 { m.at.format("%H:%M") } else { m.at.format("%H:%M:%S") };
+        out.push_str(&format!("- {time} **{}**: {}\n", m.sender, m.text.replace('\n', "\n  ")));
+    }
+    out
+}
+
+/// Messages of a day note written by `render_day`, for merging on re-import
+fn read_messages(raw: &str, day: NaiveDate) -> Vec<ChatMessage> {
+    let (_, body) = split_frontmatter(raw);
+    let mut messages: Vec<ChatMessage> = Vec::new();
+    for line in body.lines() {
+        let parsed = line.strip_prefix("- ").and_then(|rest| {
+            let (time, rest) = rest.split_once(" **")?;
+            let (sender, text) = rest.split_once("**: ").or_else(|| rest.strip_suffix("**:").map(|s| (s, "")))?;
+            let time = NaiveTime::parse_from_str(time, "%H:%M:%S").or_else(|_| NaiveTime::parse_from_str(time, "%H:%M")).ok()?;
+            Some(ChatMessage { at: day.and_time(time), sender: sender.to_string(), text: text.to_string() })
+        });
+        match (parsed, line.strip_prefix("  "), messages.last_mut()) {
+            (Some(message), _, _) => messages.push(message),
+            (None, Some(more), Some(last)) => {
+                last.text.push('\n');
+                last.text.push_str(more);
+            }
+            _ => {}
+        }
+    }
+    messages
+}
+
+fn yaml_str(s: &str) -> String {
+    serde_yaml::to_string(s).map(|y| y.trim_end().to_string()).unwrap_or_else(|_| format!("{s:?}"))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn write(dir: &Path, rel: &str, text: &str) -> PathBuf {
+        let path = dir.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, text).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_parse_whatsapp() {
+        let android = "3/15/24, 9:05 PM - Messages and calls are end-to-end encrypted.\n3/15/24, 9:05 PM - Alice: Dinner?\n3/15/24, 9:06 PM - Bob: Sure\nsee you at 8\n3/16/24, 8:01 AM - Alice: <Media omitted>\n";
+        let (format, messages, skipped) = parse_chat(android).unwrap();
+        assert_eq!((format, messages.len(), skipped), ("whatsapp", 3, 1));
+        assert_eq!(messages[1].text, "Sure\nsee you at 8");
+        assert_eq!(messages[1].at.to_string(), "2024-03-15 21:06:00");
+
+        let ios = "[15/03/2024, 14:05:22] Alice: Hallo\n[01/04/2024, 09:00:00] \u{200e}Bob: Moin\n";
+        let (_, messages, _) = parse_chat(ios).unwrap();
+        assert_eq!(messages[1].at.to_string(), "2024-04-01 09:00:00");
+        assert_eq!(messages[1].sender, "Bob");
+    }
+
+    #[test]
+    fn test_parse_wechat() {
+        let raw = "与 张三 的聊天记录\n\n张三 2024-03-15 14:05:22\n晚上吃饭吗？\n\n我 2024-03-15 14:06:01\n好\n第二行\n";
+        let (format, messages, skipped) = parse_chat(raw).unwrap();
+        assert_eq!((format, skipped), ("wechat", 1));
+        assert_eq!(messages.iter().map(|m| m.sender.as_str()).collect::<Vec<_>>(), ["张三", "我"]);
+        assert_eq!(messages[1].text, "好\n第二行");
+        assert!(parse_chat("just some notes\n").is_none());
+    }
+
+    #[test]
+    fn test_import_links_and_dedups() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        write(v, "people/alice-chen.md", "---\nname: Alice Chen\naliases:\n- Alice\n---\n");
+        let export = write(v, "WhatsApp Chat with Alice.txt", "[15/03/2024, 14:05:22] Alice: Lunch?\nat noon\n[15/03/2024, 14:06:00] Me: ok\n");
+
+        let preview = import(v, &export, None, true).unwrap();
+        assert_eq!((preview.imported, preview.days), (2, 1));
+        assert!(!v.join("connectors/chats").exists());
+
+        let report = import(v, &export, None, false).unwrap();
+        assert_eq!((report.conversation.as_str(), report.imported, report.days), ("Alice", 2, 1));
+        assert_eq!((report.linked.clone(), report.unmatched.clone()), (vec!["alice-chen".to_string()], vec!["Me".to_string()]));
+        let note = fs::read_to_string(v.join("connectors/chats/alice/2024-03-15.md")).unwrap();
+        assert!(note.contains("Participants: [[alice-chen]], Me"));
+        assert!(note.contains("- 14:05:22 **Alice**: Lunch?\n  at noon\n- 14:06 **Me**: ok\n"));
+
+        // A later, longer export only adds what's new
+        fs::write(&export, "[15/03/2024, 14:05:22] Alice: Lunch?\nat noon\n[15/03/2024, 14:06:00] Me: ok\n[16/03/2024, 10:00:00] Alice: Thanks!\n").unwrap();
+        let report = import(v, &export, None, false).unwrap();
+        assert_eq!((report.imported, report.existing, report.days), (1, 2, 1));
+        assert_eq!(fs::read_to_string(v.join("connectors/chats/alice/2024-03-15.md")).unwrap(), note);
+        assert!(v.join("connectors/chats/alice/2024-03-16.md").exists());
+    }
+}
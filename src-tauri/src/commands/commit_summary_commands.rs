@@ -0,0 +1,42 @@
+use chrono::{Local, NaiveDate};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::services::git::{self, CommitSummary};
+use crate::services::periodic::Periodic;
+
+static SUMMARY_LOOP: Periodic = Periodic::new();
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Write the commits of `date` (default today) from the repos under
+/// `git.roots` into that day's daily note now
+#[tauri::command]
+pub async fn append_commit_summary(vault_path: String, date: Option<String>) -> Result<CommitSummary, String> {
+    let day = match &date {
+        Some(d) => NaiveDate::parse_from_str(d, "%Y-%m-%d").map_err(|_| tr!("Invalid date (expected YYYY-MM-DD): {}", d))?,
+        None => Local::now().date_naive(),
+    };
+    tokio::task::spawn_blocking(move || git::summarize(Path::new(&vault_path), day))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Summarize each day once `git.at` has passed while `git.commit_summary`
+/// is on; settings are re-read each minute
+#[tauri::command]
+pub fn start_commit_summary_loop(vault_path: String) {
+    let vault = PathBuf::from(vault_path);
+    SUMMARY_LOOP.start(Duration::from_secs(60), move || {
+        if let Err(e) = git::run_due(&vault, Local::now().naive_local()) {
+            println!("[WARN] commit summary failed: {e}");
+        }
+    });
+}
+
+#[tauri::command]
+pub fn stop_commit_summary_loop() {
+    SUMMARY_LOOP.stop();
+}
@@ -0,0 +1,22 @@
+use std::path::Path;
+
+use crate::services::carddav::{self, ContactSyncReport};
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Two-way sync of people notes with the address book under `carddav:` in
+/// connectors.yaml
+#[tauri::command]
+pub async fn sync_contacts(vault_path: String) -> Result<ContactSyncReport, String> {
+    tokio::task::spawn_blocking(move || carddav::sync(Path::new(&vault_path)))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Report of the last finished contact sync, if any
+#[tauri::command]
+pub fn get_contact_sync_status(vault_path: String) -> Option<ContactSyncReport> {
+    carddav::last_report(Path::new(&vault_path))
+}
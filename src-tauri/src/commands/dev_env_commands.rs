@@ -0,0 +1,231 @@
+use chrono::Local;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// `brew outdated` and friends take seconds; the dashboard card is opened
+/// far more often than anything gets installed
+const CACHE_TTL: Duration = Duration::from_secs(30 * 60);
+/// Toolchains reported: label, program, version arguments
+const TOOLS: &[(&str, &str, &[&str])] = &[
+    ("Homebrew", "brew", &["--version"]),
+    ("Node.js", "node", &["--version"]),
+    ("npm", "npm", &["--version"]),
+    ("Python", "python3", &["--version"]),
+    ("Rust", "rustc", &["--version"]),
+    ("Cargo", "cargo", &["--version"]),
+    ("Go", "go", &["version"]),
+    ("Git", "git", &["--version"]),
+    ("Xcode", "xcodebuild", &["-version"]),
+];
+/// Where installers put binaries that a GUI app's PATH usually lacks
+const EXTRA_BIN_DIRS: &[&str] = &["/opt/homebrew/bin", "/usr/local/bin", "~/.cargo/bin", "~/.local/bin"];
+
+static CACHE: Lazy<Mutex<Option<(Instant, DevEnvironment)>>> = Lazy::new(|| Mutex::new(None));
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DevTool {
+    pub name: String,
+    /// None when the tool isn't installed
+    pub version: Option<String>,
+    pub path: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PendingUpdate {
+    /// "brew", "cask", "npm" or "rustup"
+    pub source: String,
+    pub name: String,
+    pub current: String,
+    pub latest: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DevEnvironment {
+    /// e.g. "macos aarch64"
+    pub os: String,
+    pub tools: Vec<DevTool>,
+    pub updates: Vec<PendingUpdate>,
+    /// RFC 3339, when the report was made
+    pub checked_at: String,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Installed toolchain versions and pending Homebrew, global npm and rustup
+/// updates. Cached for half an hour unless `refresh` is set.
+#[tauri::command]
+pub async fn get_dev_environment(refresh: Option<bool>) -> Result<DevEnvironment, String> {
+    if !refresh.unwrap_or(false) {
+        if let Some((at, env)) = CACHE.lock().unwrap().as_ref() {
+            if at.elapsed() < CACHE_TTL {
+                return Ok(env.clone());
+            }
+        }
+    }
+    let env = tokio::task::spawn_blocking(collect).await.map_err(|e| tr!("Task execution failed: {}", e))?;
+    *CACHE.lock().unwrap() = Some((Instant::now(), env.clone()));
+    Ok(env)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn collect() -> DevEnvironment {
+    let tools: Vec<DevTool> = TOOLS
+        .iter()
+        .map(|(name, program, args)| {
+            let path = which(program);
+            let version = path.as_ref().and_then(|p| run(p, args)).and_then(|out| parse_version(&out));
+            DevTool { name: name.to_string(), path: path.map(|p| p.to_string_lossy().to_string()), version }
+        })
+        .collect();
+
+    let mut updates = Vec::new();
+    if let Some(brew) = which("brew") {
+        // Without this `brew outdated` may first spend a minute updating itself
+        let out = Command::new(&brew).args(["outdated", "--json=v2"]).env("HOMEBREW_NO_AUTO_UPDATE", "1").output();
+        if let Ok(out) = out {
+            updates.extend(parse_brew_outdated(&String::from_utf8_lossy(&out.stdout)));
+        }
+    }
+    if let Some(npm) = which("npm") {
+        // Exits 1 when something is outdated, so the status says nothing
+        if let Ok(out) = Command::new(&npm).args(["outdated", "--global", "--json"]).output() {
+            updates.extend(parse_npm_outdated(&String::from_utf8_lossy(&out.stdout)));
+        }
+    }
+    if let Some(out) = which("rustup").and_then(|rustup| run(&rustup, &["check"])) {
+        updates.extend(parse_rustup_check(&out));
+    }
+
+    DevEnvironment {
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        tools,
+        updates,
+        checked_at: Local::now().to_rfc3339(),
+    }
+}
+
+/// `program` on PATH or in the usual install folders
+fn which(program: &str) -> Option<PathBuf> {
+    let home = std::env::var("HOME").unwrap_or_default();
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    std::env::split_paths(&path)
+        .chain(EXTRA_BIN_DIRS.iter().map(|d| PathBuf::from(d.replacen('~', &home, 1))))
+        .map(|dir| dir.join(program))
+        .find(|p| p.is_file())
+}
+
+/// stdout and stderr of a successful run (some tools print versions to stderr)
+fn run(program: &Path, args: &[&str]) -> Option<String> {
+    let out = Command::new(program).args(args).output().ok().filter(|o| o.status.success())?;
+    Some(format!("{}{}", String::from_utf8_lossy(&out.stdout), String::from_utf8_lossy(&out.stderr)))
+}
+
+/// First dotted version number in a `--version` banner: "v20.11.0",
+/// "Python 3.12.2", "go version go1.22.1 darwin/arm64", "Xcode 15.4"
+fn parse_version(out: &str) -> Option<String> {
+    out.split_whitespace()
+        .map(|token| token.trim_start_matches("go").trim_start_matches('v').trim_end_matches(|c: char| !c.is_ascii_alphanumeric()))
+        .find(|token| token.starts_with(|c: char| c.is_ascii_digit()) && token.contains('.'))
+        .map(str::to_string)
+}
+
+/// `brew outdated --json=v2`: formulae and casks
+fn parse_brew_outdated(json: &str) -> Vec<PendingUpdate> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(json) else { return Vec::new() };
+    [("formulae", "brew"), ("casks", "cask")]
+        .iter()
+        .flat_map(|(key, source)| {
+            value.get(key).and_then(|v| v.as_array()).into_iter().flatten().filter_map(move |item| {
+                let installed = item.get("installed_versions").and_then(|v| v.as_array()).and_then(|v| v.last()).and_then(|v| v.as_str());
+                Some(PendingUpdate {
+                    source: source.to_string(),
+                    name: item.get("name")?.as_str()?.to_string(),
+                    current: installed.unwrap_or_default().to_string(),
+                    latest: item.get("current_version")?.as_str()?.to_string(),
+                })
+            })
+        })
+        .collect()
+}
+
+/// `npm outdated --global --json`: `{ "pkg": { "current", "latest", … } }`
+fn parse_npm_outdated(json: &str) -> Vec<PendingUpdate> {
+    let Ok(serde_json::Value::Object(packages)) = serde_json::from_str(json) else { return Vec::new() };
+    let mut updates: Vec<PendingUpdate> = packages
+        .iter()
+        .filter_map(|(name, info)| {
+            let field = |key: &str| info.get(key).and_then(|v| v.as_str()).map(str::to_string);
+            let (current, latest) = (field("current").unwrap_or_default(), field("latest")?);
+            (current != latest).then(|| PendingUpdate { source: "npm".into(), name: name.clone(), current, latest })
+        })
+        .collect();
+    updates.sort_by(|a, b| a.name.cmp(&b.name));
+    updates
+}
+
+/// `rustup check` lines such as
+/// `stable-aarch64-apple-darwin - Update available : 1.78.0 (9b00956e5 2024-04-29) -> 1.79.0 (129f3b996 2024-06-10)`
+fn parse_rustup_check(out: &str) -> Vec<PendingUpdate> {
+    out.lines()
+        .filter_map(|line| {
+            let (name, rest) = line.split_once(" - Update available : ")?;
+            let (current, latest) = rest.split_once(" -> ")?;
+            let version = |s: &str| s.split_whitespace().next().unwrap_or_default().to_string();
+            Some(PendingUpdate { source: "rustup".into(), name: name.trim().to_string(), current: version(current), latest: version(latest) })
+        })
+        .collect()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("v20.11.0\n").as_deref(), Some("20.11.0"));
+        assert_eq!(parse_version("Python 3.12.2").as_deref(), Some("3.12.2"));
+        assert_eq!(parse_version("rustc 1.79.0 (129f3b996 2024-06-10)").as_deref(), Some("1.79.0"));
+        assert_eq!(parse_version("go version go1.22.1 darwin/arm64").as_deref(), Some("1.22.1"));
+        assert_eq!(parse_version("Xcode 15.4\nBuild version 15F31d").as_deref(), Some("15.4"));
+        assert_eq!(parse_version("Homebrew 4.3.5\n").as_deref(), Some("4.3.5"));
+        assert_eq!(parse_version("no version here"), None);
+    }
+
+    #[test]
+    fn test_parse_outdated() {
+        let brew = r#"{"formulae":[{"name":"node","installed_versions":["21.7.1","22.2.0"],"current_version":"22.3.0","pinned":false}],
+            "casks":[{"name":"iterm2","installed_versions":["3.5.0"],"current_version":"3.5.2"}]}"#;
+        let updates = parse_brew_outdated(brew);
+        assert_eq!(updates.len(), 2);
+        assert_eq!((updates[0].source.as_str(), updates[0].current.as_str(), updates[0].latest.as_str()), ("brew", "22.2.0", "22.3.0"));
+        assert_eq!(updates[1].source, "cask");
+        assert!(parse_brew_outdated("Error: not json").is_empty());
+
+        let npm = r#"{"typescript":{"current":"5.3.3","wanted":"5.4.5","latest":"5.4.5"},"pnpm":{"current":"9.1.0","latest":"9.1.0"}}"#;
+        let updates = parse_npm_outdated(npm);
+        assert_eq!(updates.len(), 1);
+        assert_eq!((updates[0].name.as_str(), updates[0].latest.as_str()), ("typescript", "5.4.5"));
+
+        let rustup = "stable-aarch64-apple-darwin - Update available : 1.78.0 (9b00956e5 2024-04-29) -> 1.79.0 (129f3b996 2024-06-10)\nrustup - Up to date : 1.27.1\n";
+        let updates = parse_rustup_check(rustup);
+        assert_eq!(updates.len(), 1);
+        assert_eq!((updates[0].current.as_str(), updates[0].latest.as_str()), ("1.78.0", "1.79.0"));
+    }
+}
@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::email_commands::import_eml;
+use crate::services::calendar::CALENDAR_DIR;
+use crate::services::notes::vault_rel;
+
+const IMAGES_DIR: &str = "assets/images";
+const DOCUMENTS_DIR: &str = "assets/documents";
+/// Dropped notes land with the quick captures, so they show up in triage
+const NOTES_DIR: &str = "inbox";
+/// Mailbox account dropped .eml files are filed under
+const IMPORT_ACCOUNT: &str = "imported";
+const IMAGE_EXTS: [&str; 6] = ["jpg", "jpeg", "png", "gif", "webp", "heic"];
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DropKind {
+    Image,
+    Pdf,
+    Email,
+    Calendar,
+    Note,
+    Unsupported,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DroppedFile {
+    /// Path as dropped
+    pub source: String,
+    pub kind: DropKind,
+    /// Vault-relative files written; empty when the file failed
+    pub created: Vec<String>,
+    /// Email subject, or how many events a calendar holds
+    pub title: Option<String>,
+    pub error: Option<String>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Import files dropped onto the window, each by type: images and PDFs into
+/// assets, .eml into the Mailbox, .ics into the calendar folder and .md into
+/// the inbox. One failure doesn't stop the rest; it is reported on its file.
+#[tauri::command]
+pub async fn ingest_dropped_files(vault_path: String, paths: Vec<String>) -> Result<Vec<DroppedFile>, String> {
+    tokio::task::spawn_blocking(move || ingest(Path::new(&vault_path), &paths))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn ingest(vault: &Path, paths: &[String]) -> Vec<DroppedFile> {
+    paths
+        .iter()
+        .map(|source| {
+            let path = Path::new(source);
+            let kind = classify(path);
+            let mut dropped = DroppedFile { source: source.clone(), kind, created: Vec::new(), title: None, error: None };
+            match import(vault, path, kind) {
+                Ok((created, title)) => {
+                    dropped.created = created;
+                    dropped.title = title;
+                }
+                Err(e) => dropped.error = Some(e),
+            }
+            dropped
+        })
+        .collect()
+}
+
+fn classify(path: &Path) -> DropKind {
+    if !path.is_file() {
+        return DropKind::Unsupported;
+    }
+    let ext = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    match ext.as_str() {
+        e if IMAGE_EXTS.contains(&e) => DropKind::Image,
+        "pdf" => DropKind::Pdf,
+        "eml" => DropKind::Email,
+        "ics" => DropKind::Calendar,
+        "md" | "markdown" => DropKind::Note,
+        _ => DropKind::Unsupported,
+    }
+}
+
+/// Returns (vault-relative files created, title)
+fn import(vault: &Path, path: &Path, kind: DropKind) -> Result<(Vec<String>, Option<String>), String> {
+    match kind {
+        DropKind::Image => Ok((vec![copy_into(vault, path, IMAGES_DIR)?], None)),
+        DropKind::Pdf => Ok((vec![copy_into(vault, path, DOCUMENTS_DIR)?], None)),
+        DropKind::Note => Ok((vec![copy_into(vault, path, NOTES_DIR)?], None)),
+        DropKind::Calendar => {
+            let raw = fs::read_to_string(path).map_err(|e| tr!("Failed to read: {}", e))?;
+            if !raw.contains("BEGIN:VCALENDAR") {
+                return Err(tr!("Not a calendar file: {}", path.display()));
+            }
+            let events = raw.lines().filter(|l| l.trim_end() == "BEGIN:VEVENT").count();
+            Ok((vec![copy_into(vault, path, CALENDAR_DIR)?], Some(tr!("{} events", events))))
+        }
+        DropKind::Email => {
+            let (email, _) = import_eml(&vault.to_string_lossy(), IMPORT_ACCOUNT, "INBOX", path)?;
+            let rel = format!("Mailbox/{}/{}.eml", IMPORT_ACCOUNT, email.id.replace(['/', '\\'], "_"));
+            Ok((vec![rel], Some(email.subject)))
+        }
+        DropKind::Unsupported => Err(tr!("Unsupported file type: {}", path.display())),
+    }
+}
+
+/// Copy into `dir`, numbering the name (`photo-2.png`) rather than overwrite
+fn copy_into(vault: &Path, src: &Path, dir: &str) -> Result<String, String> {
+    let dest_dir = vault.join(dir);
+    fs::create_dir_all(&dest_dir).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    let dest = unique_path(&dest_dir, src);
+    fs::copy(src, &dest).map_err(|e| tr!("Failed to copy: {}", e))?;
+    Ok(vault_rel(vault, &dest))
+}
+
+fn unique_path(dir: &Path, src: &Path) -> PathBuf {
+    let stem = src.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let ext = src.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    let mut dest = dir.join(format!("{stem}{ext}"));
+    let mut n = 2;
+    while dest.exists() {
+        dest = dir.join(format!("{stem}-{n}{ext}"));
+        n += 1;
+    }
+    dest
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, rel: &str, text: &str) -> String {
+        let path = dir.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, text).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_ingest_by_type() {
+        let vault = tempfile::tempdir().unwrap();
+        let downloads = tempfile::tempdir().unwrap();
+        let (v, d) = (vault.path(), downloads.path());
+        write(v, "assets/images/photo.png", "old");
+        let paths = vec![
+            write(d, "photo.png", "png"),
+            write(d, "合同.pdf", "pdf"),
+            write(d, "会议.ics", "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nSUMMARY:周会\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n"),
+            write(d, "fake.ics", "hello"),
+            write(d, "想法.md", "# 想法\n"),
+            write(d, "hi.eml", "From: Jane <jane@x.com>\r\nTo: me@x.com\r\nSubject: Hi\r\nMessage-ID: <abc@x.com>\r\nDate: Mon, 3 Mar 2025 12:00:00 +0000\r\n\r\nHello\r\n"),
+            write(d, "notes.txt", "txt"),
+        ];
+
+        let results = ingest(v, &paths);
+        let created: Vec<Vec<&str>> = results.iter().map(|r| r.created.iter().map(String::as_str).collect()).collect();
+        assert_eq!(
+            created,
+            vec![
+                vec!["assets/images/photo-2.png"],
+                vec!["assets/documents/合同.pdf"],
+                vec!["connectors/calendar/会议.ics"],
+                vec![],
+                vec!["inbox/想法.md"],
+                vec!["Mailbox/imported/abc@x.com.eml"],
+                vec![],
+            ]
+        );
+        assert_eq!(results[2].title, Some(tr!("{} events", 1)));
+        assert!(results[3].error.is_some() && results[6].error.is_some());
+        assert_eq!((results[5].kind, results[5].title.as_deref()), (DropKind::Email, Some("Hi")));
+        assert_eq!(fs::read_to_string(v.join("assets/images/photo.png")).unwrap(), "old");
+
+        // The same message dropped twice stays one index entry
+        ingest(v, &paths[5..6]);
+        let index = fs::read_to_string(v.join("Mailbox/imported/index.json")).unwrap();
+        assert_eq!(serde_json::from_str::<Vec<serde_json::Value>>(&index).unwrap().len(), 1);
+    }
+}
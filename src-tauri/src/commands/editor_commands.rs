@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::services::automations::vault_file;
+use crate::services::durable;
+use crate::services::notes::vault_rel;
+#[cfg(mobile)]
+use crate::services::unsupported;
+
+/// Kept apart from settings.yaml, which the frontend rewrites with only its own keys
+const EDITORS_FILE: &str = ".lifeos/editors.yaml";
+/// Used when neither the call, the extension map nor `default` names one
+const FALLBACK_EDITOR: &str = "system";
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// .lifeos/editors.yaml
+/// ```yaml
+/// default: vscode
+/// extensions:
+///   md: obsidian
+///   txt: typora
+/// editors:
+///   sublime:
+///     command: subl
+///     args: ["{path}:{line}"]
+/// ```
+/// Built in: vscode, cursor, zed, sublime, obsidian, typora, system.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct EditorSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    /// Extension without the dot → editor name
+    #[serde(default)]
+    pub extensions: BTreeMap<String, String>,
+    /// Custom editors; override built-ins of the same name
+    #[serde(default)]
+    pub editors: BTreeMap<String, CustomEditor>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CustomEditor {
+    pub command: String,
+    /// `{path}` and `{line}` are filled in; just the path when omitted
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// What opening a file comes down to
+#[derive(Debug, Clone, PartialEq)]
+enum Launch {
+    Program { program: String, args: Vec<String> },
+    /// Handed to the OS (a URL or a file for its default app)
+    Open(String),
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn get_editor_settings(vault_path: String) -> Result<EditorSettings, String> {
+    Ok(load_settings(Path::new(&vault_path)))
+}
+
+#[tauri::command]
+pub fn save_editor_settings(vault_path: String, settings: EditorSettings) -> Result<(), String> {
+    let path = PathBuf::from(&vault_path).join(EDITORS_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let raw = serde_yaml::to_string(&settings).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(&path, raw).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+/// Open `path` (absolute or vault-relative) in `editor`, else the one mapped
+/// to its extension, else the default, at `line` (1-based) where the editor
+/// supports it. Returns the editor used.
+#[cfg(desktop)]
+#[tauri::command]
+pub fn open_in_editor(vault_path: String, path: String, line: Option<u32>, editor: Option<String>) -> Result<String, String> {
+    let vault = PathBuf::from(&vault_path);
+    let file = if Path::new(&path).is_absolute() && Path::new(&path).starts_with(&vault) {
+        PathBuf::from(&path)
+    } else {
+        vault_file(&vault, &path)?
+    };
+    if !file.exists() {
+        return Err(tr!("File not found: {}", file.display()));
+    }
+    let settings = load_settings(&vault);
+    let name = choose_editor(&settings, &file, editor.as_deref());
+    match launch_for(&settings, &name, &vault, &file, line)? {
+        Launch::Program { program, args } => {
+            std::process::Command::new(&program).args(&args).spawn().map_err(|e| tr!("Failed to open in {}: {}", name, e))?;
+        }
+        Launch::Open(target) => open::that(&target).map_err(|e| tr!("Failed to open in {}: {}", name, e))?,
+    }
+    Ok(name)
+}
+
+#[cfg(mobile)]
+#[tauri::command]
+pub fn open_in_editor(vault_path: String, path: String, line: Option<u32>, editor: Option<String>) -> Result<String, String> {
+    let _ = (vault_path, path, line, editor);
+    Err(unsupported("open_in_editor"))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn choose_editor(settings: &EditorSettings, file: &Path, asked: Option<&str>) -> String {
+    let ext = file.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    asked
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .map(str::to_string)
+        .or_else(|| settings.extensions.get(&ext).cloned())
+        .or_else(|| settings.default.clone())
+        .unwrap_or_else(|| FALLBACK_EDITOR.to_string())
+}
+
+fn launch_for(settings: &EditorSettings, name: &str, vault: &Path, file: &Path, line: Option<u32>) -> Result<Launch, String> {
+    let path = file.to_string_lossy().to_string();
+    let line = line.unwrap_or(1).max(1);
+    if let Some(custom) = settings.editors.get(name) {
+        let args = if custom.args.is_empty() {
+            vec![path]
+        } else {
+            custom.args.iter().map(|a| a.replace("{path}", &path).replace("{line}", &line.to_string())).collect()
+        };
+        return Ok(Launch::Program { program: custom.command.clone(), args });
+    }
+    let goto = format!("{path}:{line}");
+    Ok(match name.to_lowercase().as_str() {
+        "vscode" | "code" => Launch::Program { program: "code".into(), args: vec!["--goto".into(), goto] },
+        "cursor" => Launch::Program { program: "cursor".into(), args: vec!["--goto".into(), goto] },
+        "zed" => Launch::Program { program: "zed".into(), args: vec![goto] },
+        "sublime" | "subl" => Launch::Program { program: "subl".into(), args: vec![goto] },
+        // Obsidian opens by vault name + note path; there is no line parameter
+        "obsidian" => {
+            let vault_name = vault.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            let rel = vault_rel(vault, file);
+            Launch::Open(format!("obsidian://open?vault={}&file={}", uri_encode(&vault_name), uri_encode(&rel)))
+        }
+        "typora" if cfg!(target_os = "macos") => Launch::Program { program: "open".into(), args: vec!["-a".into(), "Typora".into(), path] },
+        "typora" => Launch::Program { program: "typora".into(), args: vec![path] },
+        "system" => Launch::Open(path),
+        _ => return Err(tr!("Unknown editor: {}", name)),
+    })
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters
+fn uri_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+fn load_settings(vault: &Path) -> EditorSettings {
+    fs::read_to_string(vault.join(EDITORS_FILE))
+        .ok()
+        .and_then(|raw| serde_yaml::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editor_choice_and_launch() {
+        let settings: EditorSettings = serde_yaml::from_str(
+            "default: vscode\nextensions:\n  md: obsidian\neditors:\n  nvim:\n    command: kitty\n    args: [nvim, \"+{line}\", \"{path}\"]\n",
+        )
+        .unwrap();
+        let vault = Path::new("/v/My Vault");
+        let note = vault.join("daily/tasks/今天.md");
+        let json = vault.join("data.json");
+
+        assert_eq!(choose_editor(&settings, &note, None), "obsidian");
+        assert_eq!(choose_editor(&settings, &json, None), "vscode");
+        assert_eq!(choose_editor(&settings, &note, Some("nvim")), "nvim");
+        assert_eq!(choose_editor(&EditorSettings::default(), &json, None), "system");
+
+        assert_eq!(
+            launch_for(&settings, "vscode", vault, &json, Some(12)).unwrap(),
+            Launch::Program { program: "code".into(), args: vec!["--goto".into(), "/v/My Vault/data.json:12".into()] }
+        );
+        assert_eq!(
+            launch_for(&settings, "obsidian", vault, &note, Some(3)).unwrap(),
+            Launch::Open("obsidian://open?vault=My%20Vault&file=daily%2Ftasks%2F%E4%BB%8A%E5%A4%A9.md".into())
+        );
+        assert_eq!(
+            launch_for(&settings, "nvim", vault, &json, None).unwrap(),
+            Launch::Program { program: "kitty".into(), args: vec!["nvim".into(), "+1".into(), "/v/My Vault/data.json".into()] }
+        );
+        assert!(launch_for(&settings, "notepad++", vault, &json, None).is_err());
+    }
+}
@@ -1,429 +1,78 @@
+use imap::extensions::idle::{SetReadTimeout, WaitOutcome};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use native_tls::TlsConnector;
 use std::fs;
-use std::io::{Cursor, Read, Write};
+use std::io::{Read, Write};
 use std::net::TcpStream;
-use std::path::PathBuf;
-
-/// A stream wrapper that replays a prefix buffer before delegating to the inner stream.
-/// Used to replay the IMAP greeting after manually sending the ID command.
-#[derive(Debug)]
-struct PrefixStream<T> {
-    inner: T,
-    prefix: Cursor<Vec<u8>>,
-    prefix_done: bool,
-}
-
-impl<T> PrefixStream<T> {
-    fn new(inner: T, prefix: Vec<u8>) -> Self {
-        PrefixStream {
-            inner,
-            prefix: Cursor::new(prefix),
-            prefix_done: false,
-        }
-    }
-}
-
-impl<T: Read> Read for PrefixStream<T> {
-    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        if !self.prefix_done {
-            let n = self.prefix.read(buf)?;
-            if n == 0 {
-                self.prefix_done = true;
-                self.inner.read(buf)
-            } else {
-                Ok(n)
-            }
-        } else {
-            self.inner.read(buf)
-        }
-    }
-}
-
-impl<T: Write> Write for PrefixStream<T> {
-    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        self.inner.write(buf)
-    }
-    fn flush(&mut self) -> std::io::Result<()> {
-        self.inner.flush()
-    }
-}
-
-/// Sync state for a single folder, persisted between sessions
-#[derive(Debug, Serialize, Deserialize, Default, Clone)]
-pub struct FolderSyncState {
-    #[serde(rename = "uidValidity")]
-    pub uid_validity: u32,
-    #[serde(rename = "lastUid")]
-    pub last_uid: u32,
-    #[serde(rename = "lastSync")]
-    pub last_sync: String,
-}
-
-type SyncStateMap = std::collections::HashMap<String, FolderSyncState>;
-
-fn load_sync_state(vault_path: &str, account_dir: &str) -> SyncStateMap {
-    let path = PathBuf::from(vault_path)
-        .join("Mailbox")
-        .join(account_dir)
-        .join("sync_state.json");
-    if let Ok(content) = fs::read_to_string(&path) {
-        serde_json::from_str(&content).unwrap_or_default()
-    } else {
-        SyncStateMap::new()
-    }
-}
-
-fn save_sync_state(vault_path: &str, account_dir: &str, state: &SyncStateMap) -> Result<(), String> {
-    let dir = PathBuf::from(vault_path).join("Mailbox").join(account_dir);
-    fs::create_dir_all(&dir).map_err(|e| format!("创建目录失败: {}", e))?;
-    let path = dir.join("sync_state.json");
-    let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
-    fs::write(&path, json).map_err(|e| format!("写入 sync_state 失败: {}", e))
-}
-
-/// Read a single CRLF-terminated line from a stream (byte-by-byte for safety)
-fn read_imap_line(stream: &mut impl Read) -> Result<Vec<u8>, String> {
-    let mut line = Vec::with_capacity(256);
-    let mut buf = [0u8; 1];
-    loop {
-        stream.read_exact(&mut buf).map_err(|e| format!("读取 IMAP 响应失败: {}", e))?;
-        line.push(buf[0]);
-        if line.len() >= 2 && line[line.len() - 2] == b'\r' && line[line.len() - 1] == b'\n' {
-            break;
-        }
-        if line.len() > 8192 {
-            break; // Safety limit
-        }
-    }
-    Ok(line)
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct EmailMessage {
-    #[serde(rename = "id")]
-    pub id: String,
-    #[serde(rename = "uid")]
-    pub uid: u32,
-    #[serde(rename = "uidString")]
-    pub uid_string: Option<String>, // POP3 UIDL unique identifier
-    #[serde(rename = "from")]
-    pub from: String,
-    #[serde(rename = "to")]
-    pub to: String,
-    #[serde(rename = "subject")]
-    pub subject: String,
-    #[serde(rename = "date")]
-    pub date: String,
-    #[serde(rename = "bodyText")]
-    pub body_text: Option<String>,
-    #[serde(rename = "bodyHtml")]
-    pub body_html: Option<String>,
-    #[serde(rename = "attachments")]
-    pub attachments: Vec<String>,
-    #[serde(rename = "flags")]
-    pub flags: Vec<String>,
-    #[serde(rename = "folder")]
-    pub folder: String,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct ImapAccount {
-    pub email: String,
-    pub password: String,
-    pub imap_host: String,
-    pub imap_port: u16,
-    pub protocol: Option<String>, // "imap" or "pop3"
-    pub account_id: Option<String>, // 用于区分不同账户的标识
-}
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, State};
+use tokio::time::MissedTickBehavior;
+use tauri_plugin_notification::NotificationExt;
+
+use super::focus_commands::notifications_muted;
+use super::followup_commands::{self, FollowUp};
+use super::outbox_commands;
+use crate::services::{automations, durable, gmail, google};
+use crate::services::mail::{
+    self, account_dir, imap_starttls_client, imap_tls_client, imap_tls_connector, load_existing_emails, parse_pop3_email_with_parser,
+    save_index_json, sync_mailbox, EmailIdentity, EmailMessage, ImapAccount, MailAccount, SendEmailRequest,
+};
+use crate::services::mail_archive::{self, ArchiveImportReport, ArchiveMailbox};
+use crate::services::mail_health::{self, AccountHealth};
+use crate::services::mail_html;
+use crate::services::outbox::{self, Operation, QueuedOperation, RemoteMessage};
+use crate::services::transfer;
+
+/// Where `archive_email` files messages, on the server and in the cache
+const ARCHIVE_FOLDER: &str = "Archive";
+/// Emitted with the account's `AccountHealth` once it starts failing
+pub const MAIL_ACCOUNT_WARNING_EVENT: &str = "mail-account-warning";
+/// Emitted with a `NewMail` when an IDLE session sees its folder change
+pub const NEW_MAIL_EVENT: &str = "new-mail";
+/// Folder the IDLE sessions watch
+const IDLE_FOLDER: &str = "INBOX";
+/// IDLE is re-issued this often, which is also how long stopping can take
+const IDLE_WAIT_SECS: u64 = 60;
+/// Pause before reconnecting a dropped IDLE session
+const IDLE_RETRY_SECS: u64 = 60;
+/// Newest messages fetched when an IDLE session reports a change
+const IDLE_FETCH_LIMIT: u32 = 20;
+/// Emitted with a `MailSynced` after every `start_auto_sync` round
+pub const MAIL_SYNCED_EVENT: &str = "mail-synced";
+/// Emitted with the `ArrivedMail` a sync found past its folders' `last_uid`
+pub const MAIL_ARRIVED_EVENT: &str = "mail-arrived";
+/// Senders listed in the notification for several new messages
+const ARRIVED_LISTED: usize = 3;
+/// Newest messages fetched per folder on each auto sync round
+const AUTO_SYNC_LIMIT: u32 = 20;
+
+static IDLE_STOP: Lazy<Mutex<Option<Arc<AtomicBool>>>> = Lazy::new(|| Mutex::new(None));
 
 /// Connect to IMAP or POP3 server and sync emails (with TLS support)
 #[tauri::command]
 pub async fn imap_sync(
+    app: AppHandle,
     account: ImapAccount,
     vault_path: String,
     folder: String,
     max_emails: u32,
     skip: Option<u32>,
 ) -> Result<Vec<EmailMessage>, String> {
-    let host = account.imap_host.clone();
-    let port = account.imap_port;
-    let email = account.email.clone();
-    let password = account.password.clone();
-    let protocol = account.protocol.clone().unwrap_or_else(|| "imap".to_string());
-    let account_id = account.account_id.clone();
     let skip = skip.unwrap_or(0);
-
-    println!("[DEBUG] imap_sync received - email: {}, account_id: {:?}, skip: {}", email, account_id, skip);
-
-    let vault_path_clone = vault_path.clone();
-    let folder_clone = folder.clone();
-
-    tokio::task::spawn_blocking(move || {
-        let account_dir = account_id
-            .unwrap_or_else(|| {
-                println!("[DEBUG] account_id is None, using email as fallback: {}", email.replace("@", "_at_"));
-                email.replace("@", "_at_")
-            });
-
-        let use_tls = port == 993 || port == 995;
-
-        if protocol == "pop3" {
-            if use_tls {
-                pop3_sync_tls(&host, port, &email, &password, &vault_path_clone, &account_dir, max_emails, skip)
-            } else {
-                pop3_sync_plain(&host, port, &email, &password, &vault_path_clone, &account_dir, max_emails, skip)
-            }
-        } else {
-            imap_sync_with_crate(&host, port, &email, &password, &vault_path_clone, &account_dir, &folder_clone, max_emails, skip, use_tls)
-        }
+    let result = tokio::task::spawn_blocking(move || {
+        let result = sync_mailbox(&account, &vault_path, &folder, max_emails, skip);
+        warn_failing_account(&app, &vault_path, &account_dir(&account));
+        notify_new_mail(&app, &account_dir(&account));
+        result
     })
     .await
-    .map_err(|e| format!("任务执行失败: {}", e))?
-}
-
-// ── IMAP via `imap` crate + `mail-parser` ────────────────────────────────────
-
-fn imap_sync_with_crate(
-    host: &str,
-    port: u16,
-    email: &str,
-    password: &str,
-    vault_path: &str,
-    account_dir: &str,
-    folder: &str,
-    max_emails: u32,
-    skip: u32,
-    use_tls: bool,
-) -> Result<Vec<EmailMessage>, String> {
-    let tls = native_tls::TlsConnector::builder()
-        .danger_accept_invalid_certs(true)
-        .build()
-        .map_err(|e| format!("TLS 创建失败: {}", e))?;
-
-    if use_tls {
-        // Connect manually to send IMAP ID command before login.
-        // Required by NetEase (163/126/yeah.net) to avoid "Unsafe Login" error.
-        let tcp = TcpStream::connect((host, port))
-            .map_err(|e| format!("连接失败: {}", e))?;
-        tcp.set_read_timeout(Some(std::time::Duration::from_secs(30))).ok();
-
-        let mut tls_stream = tls.connect(host, tcp)
-            .map_err(|e| format!("TLS 握手失败: {}", e))?;
-
-        // Read server greeting
-        let greeting = read_imap_line(&mut tls_stream)?;
-        println!("[DEBUG] IMAP greeting: {}", String::from_utf8_lossy(&greeting).trim());
-
-        // Send IMAP ID command (RFC 2971) — needed by 163/126/yeah.net
-        tls_stream.write_all(
-            b"A000 ID (\"name\" \"LifeOS\" \"version\" \"1.0.0\" \"vendor\" \"LifeOS\")\r\n"
-        ).map_err(|e| format!("发送 ID 命令失败: {}", e))?;
-        tls_stream.flush().map_err(|e| format!("flush 失败: {}", e))?;
-
-        // Read ID response until tagged response
-        loop {
-            let line = read_imap_line(&mut tls_stream)?;
-            let line_str = String::from_utf8_lossy(&line);
-            println!("[DEBUG] ID response: {}", line_str.trim());
-            if line_str.starts_with("A000 ") {
-                break;
-            }
-        }
-
-        // Wrap stream: replay greeting so imap::Client::new() sees it
-        let prefix_stream = PrefixStream::new(tls_stream, greeting);
-        let client = imap::Client::new(prefix_stream);
-
-        let mut session = client
-            .login(email, password)
-            .map_err(|e| format!("登录失败: {}", e.0))?;
-
-        let result = imap_fetch_emails(&mut session, folder, max_emails, skip, vault_path, account_dir);
-        session.logout().ok();
-        result
-    } else {
-        // Non-TLS: use STARTTLS via imap crate (ID command not injected here)
-        let stream = TcpStream::connect((host, port))
-            .map_err(|e| format!("连接失败: {}", e))?;
-        let client = imap::Client::new(stream)
-            .secure(host, &tls)
-            .map_err(|e| format!("STARTTLS 失败: {}", e))?;
-
-        let mut session = client
-            .login(email, password)
-            .map_err(|e| format!("登录失败: {}", e.0))?;
-
-        let result = imap_fetch_emails(&mut session, folder, max_emails, skip, vault_path, account_dir);
-        session.logout().ok();
-        result
-    }
-}
-
-/// Fetch a page of emails from IMAP by sequence-number range.
-/// skip=0 → latest max_emails; skip=20 → the 20 emails before those; etc.
-fn imap_fetch_emails<T: Read + Write>(
-    session: &mut imap::Session<T>,
-    folder: &str,
-    max_emails: u32,
-    skip: u32,
-    vault_path: &str,
-    account_dir: &str,
-) -> Result<Vec<EmailMessage>, String> {
-    let mailbox = session
-        .select(folder)
-        .map_err(|e| format!("选择文件夹失败: {}", e))?;
-
-    let total = mailbox.exists as u32;
-
-    if total == 0 || skip >= total {
-        return Ok(Vec::new());
-    }
-
-    // Sequence numbers count from 1 (oldest) to total (newest).
-    // fetch_end is the newest message in this page.
-    let fetch_end = total - skip;
-    let fetch_start = fetch_end.saturating_sub(max_emails.saturating_sub(1)).max(1);
-    let range = format!("{}:{}", fetch_start, fetch_end);
-
-    println!("[SYNC] folder={} total={} skip={} range={}", folder, total, skip, range);
-
-    let emails_dir = PathBuf::from(vault_path).join("Mailbox").join(account_dir);
-    fs::create_dir_all(&emails_dir).map_err(|e| format!("创建目录失败: {}", e))?;
-
-    let messages = session
-        .fetch(&range, "(UID FLAGS RFC822)")
-        .map_err(|e| format!("拉取邮件失败: {}", e))?;
-
-    let mut emails = parse_imap_messages(&messages, folder, &emails_dir)?;
-    emails.reverse(); // newest first within this page
-
-    Ok(emails)
-}
-
-/// Returns current UTC time as RFC3339 string (without chrono dependency)
-fn chrono_now() -> String {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    let secs = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0);
-    // Simple ISO 8601 UTC timestamp
-    let s = secs;
-    let sec = s % 60;
-    let min = (s / 60) % 60;
-    let hour = (s / 3600) % 24;
-    let days = s / 86400;
-    // Approximate date (good enough for sync metadata logging)
-    let year = 1970 + days / 365;
-    let day_of_year = days % 365;
-    let month = day_of_year / 30 + 1;
-    let day = day_of_year % 30 + 1;
-    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, hour, min, sec)
-}
-
-/// Parse a collection of IMAP fetch responses into EmailMessage structs,
-/// saving each RFC822 body as a .eml file.
-fn parse_imap_messages(
-    messages: &imap::types::ZeroCopy<Vec<imap::types::Fetch>>,
-    folder: &str,
-    emails_dir: &PathBuf,
-) -> Result<Vec<EmailMessage>, String> {
-    let mut emails = Vec::new();
-
-    for msg in messages.iter() {
-        let uid = msg.uid.unwrap_or(0);
-        let email_id = format!("{}_{}", folder, uid);
-
-        // Save raw RFC822 as .eml file
-        if let Some(raw) = msg.body() {
-            let eml_path = emails_dir.join(format!("{}.eml", email_id));
-            fs::write(&eml_path, raw).map_err(|e| format!("保存 EML 文件失败: {}", e))?;
-        }
-
-        // Parse flags
-        let flags: Vec<String> = msg
-            .flags()
-            .iter()
-            .map(|f| format!("{:?}", f))
-            .collect();
-
-        // Parse the full email from RFC822 body using mail-parser
-        let (subject, from, to, date, body_text, body_html) = match msg.body() {
-            Some(raw) => {
-                println!("[DEBUG] RFC822 body for uid {}: {} bytes", uid, raw.len());
-                use mail_parser::MessageParser;
-                let parser = MessageParser::default();
-                if let Some(parsed) = parser.parse(raw) {
-                    let subject = parsed.subject().unwrap_or("").to_string();
-                    let from = parsed.from().and_then(|a| a.first())
-                        .map(|a| {
-                            if let Some(name) = a.name() {
-                                if let Some(addr) = a.address() {
-                                    format!("{} <{}>", name, addr)
-                                } else { name.to_string() }
-                            } else {
-                                a.address().unwrap_or("").to_string()
-                            }
-                        }).unwrap_or_default();
-                    let to = parsed.to().and_then(|a| a.first())
-                        .map(|a| a.address().unwrap_or("").to_string())
-                        .unwrap_or_default();
-                    let date = parsed.date()
-                        .map(|d| d.to_rfc3339())
-                        .unwrap_or_default();
-                    let body_text = parsed.body_text(0).map(|t| t.to_string());
-                    let body_html = parsed.body_html(0).map(|h| h.to_string());
-                    (subject, from, to, date, body_text, body_html)
-                } else {
-                    println!("[DEBUG] mail-parser failed to parse uid {}", uid);
-                    (String::new(), String::new(), String::new(), String::new(), None, None)
-                }
-            }
-            None => {
-                println!("[DEBUG] msg.body() returned None for uid {}", uid);
-                (String::new(), String::new(), String::new(), String::new(), None, None)
-            }
-        };
-
-        emails.push(EmailMessage {
-            id: email_id,
-            uid,
-            uid_string: Some(uid.to_string()),
-            from,
-            to,
-            subject,
-            date,
-            body_text,
-            body_html,
-            attachments: vec![],
-            flags,
-            folder: folder.to_string(),
-        });
-    }
-
-    Ok(emails)
-}
-
-/// Save metadata-only index.json (strips body content)
-fn save_index_json(emails_dir: &PathBuf, emails: &[EmailMessage]) -> Result<(), String> {
-    let index_entries: Vec<EmailMessage> = emails.iter().map(|e| EmailMessage {
-        id: e.id.clone(),
-        uid: e.uid,
-        uid_string: e.uid_string.clone(),
-        from: e.from.clone(),
-        to: e.to.clone(),
-        subject: e.subject.clone(),
-        date: e.date.clone(),
-        body_text: None,
-        body_html: None,
-        attachments: e.attachments.clone(),
-        flags: e.flags.clone(),
-        folder: e.folder.clone(),
-    }).collect();
-    let index_path = emails_dir.join("index.json");
-    let index_json = serde_json::to_string_pretty(&index_entries).map_err(|e| e.to_string())?;
-    fs::write(&index_path, index_json).map_err(|e| format!("写入索引文件失败: {}", e))
+    .map_err(|e| tr!("Task execution failed: {}", e))?;
+    let emails = result?;
+    Ok(emails.into_iter().map(|email| clean_html(email, false, false)).collect())
 }
 
 /// Parse email body using mail-parser to extract text and HTML parts
@@ -516,418 +165,41 @@ fn decode_quoted_printable_header(input: &str) -> Option<Vec<u8>> {
     Some(result)
 }
 
-// ── POP3 support ─────────────────────────────────────────────────────────────────
-
-/// Index entry for email metadata (stored in index.json)
-#[derive(Debug, Serialize, Deserialize)]
-pub struct EmailIndexEntry {
-    pub uid: u32,
-    pub message_id: Option<String>,
-    pub subject: String,
-    pub from: String,
-    pub date: String,
-    pub file: String,  // EML filename
-    pub flags: Vec<String>,
+/// Store a .eml file under Mailbox/<account_id>/ the way a POP3 sync does:
+/// the raw message as `<Message-ID>.eml` plus a metadata entry in index.json.
+/// A message already in the index is returned as is, with `false`.
+pub(crate) fn import_eml(vault_path: &str, account_id: &str, folder: &str, eml: &std::path::Path) -> Result<(EmailMessage, bool), String> {
+    let raw = fs::read(eml).map_err(|e| tr!("Failed to read email: {}", e))?;
+    store_eml(vault_path, account_id, folder, &raw)
 }
 
-fn pop3_sync_tls(
-    host: &str,
-    port: u16,
-    email: &str,
-    password: &str,
-    vault_path: &str,
-    account_dir: &str,
-    max_emails: u32,
-    skip: u32,
-) -> Result<Vec<EmailMessage>, String> {
-    use native_tls::TlsStream;
-
-    let connector = TlsConnector::builder()
-        .danger_accept_invalid_certs(true)
-        .build()
-        .map_err(|e| format!("TLS 创建失败: {}", e))?;
-
-    let addr = format!("{}:{}", host, port);
-    let tcp_stream = TcpStream::connect(&addr).map_err(|e| format!("连接失败: {}", e))?;
-    tcp_stream.set_read_timeout(Some(std::time::Duration::from_secs(30))).ok();
-
-    let tls_stream = connector.connect(host, tcp_stream)
-        .map_err(|e| format!("TLS 握手失败: {}", e))?;
-
-    let mut stream: TlsStream<TcpStream> = tls_stream;
-
-    read_response(&mut stream)?;
-
-    // Login
-    let user_cmd = format!("USER {}\r\n", email);
-    stream.write_all(user_cmd.as_bytes()).map_err(|e| format!("发送失败: {}", e))?;
-    let user_resp = read_response(&mut stream)?;
-    if !user_resp.contains("+OK") {
-        return Err(format!("USER 命令失败: {}", user_resp));
-    }
-
-    let pass_cmd = format!("PASS {}\r\n", password);
-    stream.write_all(pass_cmd.as_bytes()).map_err(|e| format!("发送失败: {}", e))?;
-    let pass_resp = read_response(&mut stream)?;
-    if !pass_resp.contains("+OK") {
-        return Err(format!("登录失败: {}", pass_resp));
-    }
-
-    // Get UIDL list (all messages)
-    stream.write_all(b"UIDL\r\n").map_err(|e| format!("发送失败: {}", e))?;
-    let uidl_resp = read_response(&mut stream)?;
-    let mut server_uids = parse_uidl_response(&uidl_resp);
-
-    // Sort newest first (by seq number descending)
-    server_uids.sort_by(|a, b| b.0.cmp(&a.0));
-
-    // Apply skip + limit to get the current page
-    let page: Vec<(u32, String)> = server_uids
-        .into_iter()
-        .skip(skip as usize)
-        .take(max_emails as usize)
-        .collect();
-
-    println!("[SYNC] POP3 TLS: skip={} max={} page_count={}", skip, max_emails, page.len());
-
-    if page.is_empty() {
-        stream.write_all(b"QUIT\r\n").ok();
-        return Ok(Vec::new());
-    }
-
-    let emails_dir = PathBuf::from(vault_path).join("Mailbox").join(account_dir);
-    fs::create_dir_all(&emails_dir).map_err(|e| format!("创建目录失败: {}", e))?;
-
-    let mut emails = Vec::new();
-
-    for (seq, uid_string) in page {
-        let retr_cmd = format!("RETR {}\r\n", seq);
-        stream.write_all(retr_cmd.as_bytes()).map_err(|e| format!("发送失败: {}", e))?;
-
-        let mut response = Vec::new();
-        let mut buf = [0u8; 8192];
-        loop {
-            match stream.read(&mut buf) {
-                Ok(0) => break,
-                Ok(n) => {
-                    response.extend_from_slice(&buf[..n]);
-                    let resp_str = String::from_utf8_lossy(&response);
-                    if resp_str.contains("\r\n.\r\n") || resp_str.contains("\n.\n") {
-                        break;
-                    }
-                }
-                Err(_) => break,
-            }
-        }
-
-        // Strip POP3 +OK header line
-        let resp_str = String::from_utf8_lossy(&response);
-        let raw_email: &[u8] = if let Some(idx) = resp_str.find("\r\n") {
-            &response[idx + 2..]
-        } else {
-            &response[..]
-        };
-
-        let (email_msg, message_id) = parse_pop3_email_with_parser(raw_email, account_dir, seq, Some(uid_string.clone()));
+/// `import_eml` for a message already in memory
+fn store_eml(vault_path: &str, account_id: &str, folder: &str, raw: &[u8]) -> Result<(EmailMessage, bool), String> {
+    let emails_dir = PathBuf::from(vault_path).join("Mailbox").join(account_id);
+    fs::create_dir_all(&emails_dir).map_err(|e| tr!("Failed to create directory: {}", e))?;
 
-        let eml_filename = message_id.clone().unwrap_or_else(|| seq.to_string());
-        let safe_filename = eml_filename.chars().filter(|c| c.is_alphanumeric() || *c == '@' || *c == '.' || *c == '-' || *c == '_').take(100).collect::<String>();
-        let eml_path = emails_dir.join(format!("{}.eml", safe_filename));
-        fs::write(&eml_path, raw_email).map_err(|e| format!("保存 EML 文件失败: {}", e))?;
-
-        emails.push(email_msg);
+    let mut emails = load_existing_emails(vault_path, account_id)?;
+    let (email, new) = file_eml(&emails, folder, raw);
+    if !new {
+        return Ok((email, false));
     }
 
-    stream.write_all(b"QUIT\r\n").ok();
-
-    Ok(emails)
+    fs::write(mail::eml_path(&emails_dir, &email.id), raw).map_err(|e| tr!("Failed to save EML file: {}", e))?;
+    emails.push(email.clone());
+    save_index_json(&emails_dir, &emails)?;
+    Ok((email, true))
 }
 
-fn pop3_sync_plain(
-    host: &str,
-    port: u16,
-    email: &str,
-    password: &str,
-    vault_path: &str,
-    account_dir: &str,
-    max_emails: u32,
-    skip: u32,
-) -> Result<Vec<EmailMessage>, String> {
-    let addr = format!("{}:{}", host, port);
-    let mut stream = TcpStream::connect(&addr).map_err(|e| format!("连接失败: {}", e))?;
-    stream.set_read_timeout(Some(std::time::Duration::from_secs(30))).ok();
-
-    let mut buf = [0u8; 4096];
-    stream.read(&mut buf).map_err(|e| format!("读取失败: {}", e))?;
-
-    // Login
-    let user_cmd = format!("USER {}\r\n", email);
-    stream.write_all(user_cmd.as_bytes()).map_err(|e| format!("发送失败: {}", e))?;
-    let n = stream.read(&mut buf).map_err(|e| format!("读取失败: {}", e))?;
-    if !String::from_utf8_lossy(&buf[..n]).contains("+OK") {
-        return Err(format!("USER 命令失败"));
-    }
-
-    let pass_cmd = format!("PASS {}\r\n", password);
-    stream.write_all(pass_cmd.as_bytes()).map_err(|e| format!("发送失败: {}", e))?;
-    let n = stream.read(&mut buf).map_err(|e| format!("读取失败: {}", e))?;
-    if !String::from_utf8_lossy(&buf[..n]).contains("+OK") {
-        return Err(format!("登录失败"));
+/// `raw` as it would be filed among `emails`, and whether it is new; an
+/// already cached copy is returned as it is
+fn file_eml(emails: &[EmailMessage], folder: &str, raw: &[u8]) -> (EmailMessage, bool) {
+    let seq = emails.iter().map(|e| e.uid).max().unwrap_or(0) + 1;
+    let (mut email, message_id) = parse_pop3_email_with_parser(raw, folder, seq, None);
+    email.uid_string = message_id;
+    match emails.iter().find(|e| e.id == email.id) {
+        Some(existing) => (existing.clone(), false),
+        None => (email, true),
     }
-
-    // Get UIDL list
-    stream.write_all(b"UIDL\r\n").map_err(|e| format!("发送失败: {}", e))?;
-    let n = stream.read(&mut buf).map_err(|e| format!("读取失败: {}", e))?;
-    let uidl_resp = String::from_utf8_lossy(&buf[..n]).to_string();
-    let mut server_uids = parse_uidl_response(&uidl_resp);
-
-    // Sort newest first
-    server_uids.sort_by(|a, b| b.0.cmp(&a.0));
-
-    // Apply skip + limit
-    let page: Vec<(u32, String)> = server_uids
-        .into_iter()
-        .skip(skip as usize)
-        .take(max_emails as usize)
-        .collect();
-
-    println!("[SYNC] POP3 plain: skip={} max={} page_count={}", skip, max_emails, page.len());
-
-    if page.is_empty() {
-        stream.write_all(b"QUIT\r\n").ok();
-        return Ok(Vec::new());
-    }
-
-    let emails_dir = PathBuf::from(vault_path).join("Mailbox").join(account_dir);
-    fs::create_dir_all(&emails_dir).map_err(|e| format!("创建目录失败: {}", e))?;
-
-    let mut emails = Vec::new();
-
-    for (seq, uid_string) in page {
-        let retr_cmd = format!("RETR {}\r\n", seq);
-        stream.write_all(retr_cmd.as_bytes()).map_err(|e| format!("发送失败: {}", e))?;
-
-        let mut response = Vec::new();
-        loop {
-            match stream.read(&mut buf) {
-                Ok(0) => break,
-                Ok(n) => {
-                    response.extend_from_slice(&buf[..n]);
-                    let resp_str = String::from_utf8_lossy(&response);
-                    if resp_str.contains("\r\n.\r\n") || resp_str.contains("\n.\n") {
-                        break;
-                    }
-                }
-                Err(_) => break,
-            }
-        }
-
-        let resp_str = String::from_utf8_lossy(&response);
-        let raw_email: &[u8] = if let Some(idx) = resp_str.find("\r\n") {
-            &response[idx + 2..]
-        } else {
-            &response[..]
-        };
-
-        let (email_msg, message_id) = parse_pop3_email_with_parser(raw_email, account_dir, seq, Some(uid_string.clone()));
-
-        let eml_filename = message_id.clone().unwrap_or_else(|| seq.to_string());
-        let safe_filename = eml_filename.chars().filter(|c| c.is_alphanumeric() || *c == '@' || *c == '.' || *c == '-' || *c == '_').take(100).collect::<String>();
-        let eml_path = emails_dir.join(format!("{}.eml", safe_filename));
-        fs::write(&eml_path, raw_email).map_err(|e| format!("保存 EML 文件失败: {}", e))?;
-
-        emails.push(email_msg);
-    }
-
-    stream.write_all(b"QUIT\r\n").ok();
-
-    Ok(emails)
-}
-
-/// Parse a POP3 email using mail-parser for proper MIME handling
-/// Returns (EmailMessage, Option<Message-ID>)
-fn parse_pop3_email_with_parser(raw: &[u8], folder: &str, seq: u32, uid_string: Option<String>) -> (EmailMessage, Option<String>) {
-    use mail_parser::MessageParser;
-
-    let parser = MessageParser::default();
-    if let Some(message) = parser.parse(raw) {
-        let subject = message.subject().unwrap_or("").to_string();
-        let from = message.from().and_then(|a| a.first())
-            .map(|a| {
-                if let Some(name) = a.name() {
-                    if let Some(addr) = a.address() {
-                        format!("{} <{}>", name, addr)
-                    } else {
-                        name.to_string()
-                    }
-                } else {
-                    a.address().unwrap_or("").to_string()
-                }
-            })
-            .unwrap_or_default();
-        let to = message.to().and_then(|a| a.first())
-            .map(|a| a.address().unwrap_or("").to_string())
-            .unwrap_or_default();
-        let date = message.date()
-            .map(|d| d.to_rfc3339())
-            .unwrap_or_default();
-        let body_text = message.body_text(0).map(|t| t.to_string());
-        let body_html = message.body_html(0).map(|h| h.to_string());
-
-        // Extract Message-ID for unique filename
-        let message_id = message.message_id().map(|id| {
-            let id_str = id.to_string();
-            // Sanitize: remove < > brackets and invalid chars
-            id_str.trim_matches(|c| c == '<' || c == '>')
-                .chars()
-                .filter(|c| c.is_alphanumeric() || *c == '@' || *c == '.' || *c == '-' || *c == '_')
-                .take(100)
-                .collect()
-        });
-
-        let email_msg = EmailMessage {
-            id: message_id.clone().unwrap_or_else(|| format!("{}_{}", folder, seq)),
-            uid: seq,
-            uid_string,
-            from,
-            to,
-            subject,
-            date,
-            body_text,
-            body_html,
-            attachments: vec![],
-            flags: vec![],
-            folder: folder.to_string(),
-        };
-
-        (email_msg, message_id)
-    } else {
-        // Fallback to basic header parsing
-        let text = String::from_utf8_lossy(raw);
-        parse_pop3_email_basic_raw(&text, folder, seq, None)
-    }
-}
-
-fn parse_pop3_email_basic(response: &str, folder: &str, seq: u32) -> EmailMessage {
-    let (msg, _) = parse_pop3_email_basic_raw(response, folder, seq, None);
-    msg
-}
-
-fn parse_pop3_email_basic_raw(response: &str, folder: &str, seq: u32, uid_string: Option<String>) -> (EmailMessage, Option<String>) {
-    let mut from = String::new();
-    let mut to = String::new();
-    let mut subject = String::new();
-    let mut date = String::new();
-    let mut message_id: Option<String> = None;
-
-    for line in response.lines() {
-        let lower = line.to_lowercase();
-        if lower.starts_with("from:") {
-            from = line[5..].trim().to_string();
-        } else if lower.starts_with("to:") {
-            to = line[3..].trim().to_string();
-        } else if lower.starts_with("subject:") {
-            subject = line[8..].trim().to_string();
-        } else if lower.starts_with("date:") {
-            date = line[5..].trim().to_string();
-        } else if lower.starts_with("message-id:") {
-            let id = line[11..].trim().to_string();
-            message_id = Some(id.trim_matches(|c| c == '<' || c == '>').to_string());
-        }
-    }
-
-    let msg_id = message_id.clone().unwrap_or_else(|| format!("{}_{}", folder, seq));
-    let email_msg = EmailMessage {
-        id: msg_id,
-        uid: seq,
-        uid_string,
-        from,
-        to,
-        subject,
-        date,
-        body_text: None,
-        body_html: None,
-        attachments: vec![],
-        flags: vec![],
-        folder: folder.to_string(),
-    };
-
-    (email_msg, message_id)
-}
-
-// ── Helper functions for EML file storage ─────────────────────────────────────
-
-/// Load existing email UIDs from local index (using string UID from UIDL)
-fn load_local_uids(vault_path: &str, folder: &str) -> std::collections::HashSet<String> {
-    let index_path = PathBuf::from(vault_path)
-        .join("Mailbox")
-        .join(folder)
-        .join("index.json");
-
-    if !index_path.exists() {
-        return std::collections::HashSet::new();
-    }
-
-    if let Ok(content) = fs::read_to_string(&index_path) {
-        if let Ok(emails) = serde_json::from_str::<Vec<EmailMessage>>(&content) {
-            // Use uid_string if available, otherwise fall back to uid
-            return emails.iter()
-                .filter_map(|e| e.uid_string.clone().or_else(|| Some(e.uid.to_string())))
-                .collect();
-        }
-    }
-
-    std::collections::HashSet::new()
-}
-
-/// Parse UIDL response from POP3 server
-/// Returns vector of (message_number, unique_id) tuples
-fn parse_uidl_response(response: &str) -> Vec<(u32, String)> {
-    let mut result = Vec::new();
-
-    for line in response.lines() {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with("+OK") {
-            continue;
-        }
-        if line == "." {
-            break;
-        }
-
-        // Format: "1 unique_id_12345"
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 2 {
-            if let Ok(seq) = parts[0].parse::<u32>() {
-                let uid = parts[1].to_string();
-                result.push((seq, uid));
-            }
-        }
-    }
-
-    result
-}
-
-/// Load existing emails from local storage
-fn load_existing_emails(vault_path: &str, folder: &str) -> Result<Vec<EmailMessage>, String> {
-    let index_path = PathBuf::from(vault_path)
-        .join("Mailbox")
-        .join(folder)
-        .join("index.json");
-
-    if !index_path.exists() {
-        return Ok(Vec::new());
-    }
-
-    let content = fs::read_to_string(&index_path).map_err(|e| format!("读取失败: {}", e))?;
-    let emails: Vec<EmailMessage> = serde_json::from_str(&content).map_err(|e| format!("解析失败: {}", e))?;
-
-    Ok(emails)
-}
-
-fn read_response<T: Read>(stream: &mut T) -> Result<String, String> {
-    let mut buf = [0u8; 4096];
-    let n = stream.read(&mut buf).map_err(|e| format!("读取失败: {}", e))?;
-    Ok(String::from_utf8_lossy(&buf[..n]).to_string())
 }
 
 /// Get emails from local cache with optional pagination
@@ -942,8 +214,8 @@ pub fn get_cached_emails(vault_path: String, account_id: String, offset: Option<
         return Ok(Vec::new());
     }
 
-    let content = fs::read_to_string(&index_path).map_err(|e| format!("读取失败: {}", e))?;
-    let all_emails: Vec<EmailMessage> = serde_json::from_str(&content).map_err(|e| format!("解析失败: {}", e))?;
+    let content = fs::read_to_string(&index_path).map_err(|e| tr!("Failed to read: {}", e))?;
+    let all_emails: Vec<EmailMessage> = serde_json::from_str(&content).map_err(|e| tr!("Failed to parse: {}", e))?;
 
     let offset = offset.unwrap_or(0);
     let emails = if let Some(limit) = limit {
@@ -955,20 +227,65 @@ pub fn get_cached_emails(vault_path: String, account_id: String, offset: Option<
     Ok(emails)
 }
 
-/// Get full email content from .eml file
+/// Get full email content from .eml file, its HTML sanitized and remote content
+/// blocked. With `unwrap_links`, wrapped tracking links go straight to their destination.
+#[tauri::command]
+pub fn get_email_content(vault_path: String, account_id: String, email_id: String, unwrap_links: Option<bool>) -> Result<EmailMessage, String> {
+    read_email_content(vault_path, account_id, email_id).map(|email| clean_html(email, false, unwrap_links.unwrap_or(false)))
+}
+
+/// `get_email_content` with remote images and styles left in, for a reader
+/// who chose to load them
+#[tauri::command]
+pub fn load_remote_content(vault_path: String, account_id: String, email_id: String, unwrap_links: Option<bool>) -> Result<EmailMessage, String> {
+    read_email_content(vault_path, account_id, email_id).map(|email| clean_html(email, true, unwrap_links.unwrap_or(false)))
+}
+
+/// Save attachment `index` (as listed in `EmailMessage::attachments`) of a
+/// cached message to `dest`, or into `dest` under its own name when that is
+/// a folder. Returns the file written.
 #[tauri::command]
-pub fn get_email_content(vault_path: String, account_id: String, email_id: String) -> Result<EmailMessage, String> {
-    let safe_id = email_id.replace('/', "_").replace('\\', "_");
+pub fn download_attachment(vault_path: String, account_id: String, email_id: String, index: usize, dest: String) -> Result<String, String> {
+    let raw = fs::read(mailbox_file(&vault_path, &account_id, &email_id)).map_err(|_| tr!("Email file not found: {}", email_id))?;
+    let (attachment, bytes) = mail::attachment(&raw, index).ok_or_else(|| tr!("No attachment {} in this email", index))?;
+
+    let mut path = PathBuf::from(&dest);
+    if path.is_dir() {
+        // The name comes from the sender: keep only its last component
+        let name = Path::new(&attachment.name)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| format!("attachment-{}", index + 1));
+        path = path.join(name);
+        if path.exists() {
+            path = transfer::free_name(&path);
+        }
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    fs::write(&path, bytes).map_err(|e| tr!("write_file failed: {}", e))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Note trackers in the raw HTML, then sanitize it
+fn clean_html(mut email: EmailMessage, allow_remote: bool, unwrap_links: bool) -> EmailMessage {
+    if let Some(html) = email.body_html.take() {
+        email.trackers = mail_html::find_trackers(&html);
+        let clean = mail_html::sanitize(&html, allow_remote, unwrap_links);
+        email.body_html = Some(clean.html);
+        email.remote_blocked = clean.remote_blocked;
+    }
+    email
+}
 
+pub(crate) fn read_email_content(vault_path: String, account_id: String, email_id: String) -> Result<EmailMessage, String> {
     // Try .eml file first (standard format)
-    let eml_path = PathBuf::from(&vault_path)
-        .join("Mailbox")
-        .join(&account_id)
-        .join(format!("{}.eml", safe_id));
+    let eml_path = mailbox_file(&vault_path, &account_id, &email_id);
 
     if eml_path.exists() {
         // Read and parse .eml file
-        let raw_bytes = fs::read(&eml_path).map_err(|e| format!("读取邮件失败: {}", e))?;
+        let raw_bytes = fs::read(&eml_path).map_err(|e| tr!("Failed to read email: {}", e))?;
         use mail_parser::MessageParser;
         let parser = MessageParser::default();
 
@@ -1008,99 +325,628 @@ pub fn get_email_content(vault_path: String, account_id: String, email_id: Strin
                 date,
                 body_text,
                 body_html,
-                attachments: vec![],
+                attachments: mail::attachments(&parsed),
                 flags: vec![],
                 folder: account_id,
+                remote_blocked: 0,
+                trackers: vec![],
+                list_id: mail::list_id(&raw_bytes),
+                in_reply_to: mail::in_reply_to(&raw_bytes),
+                replies: vec![],
             });
         }
     }
 
-    // Fallback: try JSON file (legacy format)
-    let json_path = PathBuf::from(&vault_path)
-        .join("Mailbox")
-        .join(&account_id)
-        .join(format!("{}.json", safe_id));
+    // Fallback: try JSON file (legacy format)
+    let json_path = PathBuf::from(&vault_path)
+        .join("Mailbox")
+        .join(&account_id)
+        .join(format!("{}.json", email_id.replace(['/', '\\'], "_")));
+
+    if json_path.exists() {
+        let content = fs::read_to_string(&json_path).map_err(|e| tr!("Failed to read email: {}", e))?;
+        let email: EmailMessage = serde_json::from_str(&content).map_err(|e| tr!("Failed to parse email: {}", e))?;
+        return Ok(email);
+    }
+
+    Err(tr!("Email file not found: {}", email_id))
+}
+
+/// List available email folders
+#[tauri::command]
+pub fn list_email_folders(vault_path: String) -> Result<Vec<String>, String> {
+    let emails_dir = PathBuf::from(&vault_path).join("Mailbox");
+
+    if !emails_dir.exists() {
+        return Ok(vec![
+            "INBOX".to_string(),
+            "Sent".to_string(),
+            "Drafts".to_string(),
+            "Trash".to_string(),
+            "Archive".to_string(),
+        ]);
+    }
+
+    let mut folders = Vec::new();
+    if let Ok(entries) = fs::read_dir(&emails_dir) {
+        for entry in entries.flatten() {
+            if entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
+                folders.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+    }
+
+    if folders.is_empty() {
+        folders = vec![
+            "INBOX".to_string(),
+            "Sent".to_string(),
+            "Drafts".to_string(),
+            "Trash".to_string(),
+            "Archive".to_string(),
+        ];
+    }
+
+    Ok(folders)
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EmlImportReport {
+    pub imported: Vec<EmailMessage>,
+    /// Already cached under the same Message-ID
+    pub duplicates: usize,
+    /// (path, error)
+    pub failed: Vec<(String, String)>,
+    /// Counted only; nothing was written
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Add .eml files exported from other clients to Mailbox/<account_id>/,
+/// filed under `folder` (default INBOX), so they list and search like synced
+/// mail. `dry_run` reports what would be added without writing it.
+#[tauri::command]
+pub async fn import_eml_files(
+    vault_path: String,
+    paths: Vec<String>,
+    account_id: String,
+    folder: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<EmlImportReport, String> {
+    let folder = folder.filter(|f| !f.trim().is_empty()).unwrap_or_else(|| "INBOX".to_string());
+    let account_id = account_id.replace(['/', '\\'], "_");
+    let dry_run = dry_run.unwrap_or(false);
+    tokio::task::spawn_blocking(move || import_eml_paths(&vault_path, &account_id, &folder, paths, dry_run))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))
+}
+
+fn import_eml_paths(vault_path: &str, account_id: &str, folder: &str, paths: Vec<String>, dry_run: bool) -> EmlImportReport {
+    let mut report = EmlImportReport { dry_run, ..Default::default() };
+    // A dry run files into this copy of the index, so repeats in `paths` count as duplicates
+    let mut preview = if dry_run { load_existing_emails(vault_path, account_id).unwrap_or_default() } else { Vec::new() };
+    for path in paths {
+        let imported = if dry_run {
+            fs::read(&path).map_err(|e| tr!("Failed to read email: {}", e)).map(|raw| file_eml(&preview, folder, &raw))
+        } else {
+            import_eml(vault_path, account_id, folder, std::path::Path::new(&path))
+        };
+        match imported {
+            Ok((email, true)) => {
+                if dry_run {
+                    preview.push(email.clone());
+                }
+                report.imported.push(email)
+            }
+            Ok((_, false)) => report.duplicates += 1,
+            Err(e) => report.failed.push((path, e)),
+        }
+    }
+    report
+}
+
+/// Mailboxes in an Apple Mail folder (`~/Library/Mail/V10` or part of it)
+/// or an Outlook .olm export, to pick from before importing
+#[tauri::command]
+pub async fn scan_mail_archive(path: String) -> Result<Vec<ArchiveMailbox>, String> {
+    tokio::task::spawn_blocking(move || mail_archive::mailboxes(std::path::Path::new(&path)))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Copy the chosen mailboxes (all when none are given) of an Apple Mail
+/// folder or .olm export into Mailbox/<account_id>/; see `mail_archive`.
+/// `dry_run` only counts what would be copied.
+#[tauri::command]
+pub async fn import_mail_archive(
+    vault_path: String,
+    path: String,
+    account_id: String,
+    mailboxes: Option<Vec<String>>,
+    dry_run: Option<bool>,
+) -> Result<ArchiveImportReport, String> {
+    let account_id = account_id.replace(['/', '\\'], "_");
+    let dry_run = dry_run.unwrap_or(false);
+    tokio::task::spawn_blocking(move || {
+        mail_archive::import(&vault_path, &account_id, std::path::Path::new(&path), &mailboxes.unwrap_or_default(), dry_run)
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+// ── Folder selection ───────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteFolder {
+    pub name: String,
+    /// In the server's LSUB list
+    pub subscribed: bool,
+    /// False for `\Noselect` containers that hold only subfolders
+    pub selectable: bool,
+    /// Among the account's `folders`, i.e. pulled by sync
+    pub synced: bool,
+}
+
+/// The account's folders on the server (IMAP LIST, with LSUB for which are
+/// subscribed; labels for Gmail API accounts), marked with whether sync
+/// currently pulls them. POP3 has only INBOX.
+#[tauri::command]
+pub async fn discover_email_folders(vault_path: String, account_id: String) -> Result<Vec<RemoteFolder>, String> {
+    tokio::task::spawn_blocking(move || remote_folders(&vault_path, &find_account(&vault_path, &account_id)?))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Sync every selectable folder on the server, whatever the account's
+/// `folders` say: LIST, then the newest `limit` (default 20) of each into
+/// its own cache, Mailbox/<account>/<folder>. Returns one report per folder
+/// with that directory; a folder that fails does not stop the rest.
+#[tauri::command]
+pub async fn imap_sync_all(app: AppHandle, vault_path: String, account_id: String, limit: Option<u32>) -> Result<Vec<mail::SyncReport>, String> {
+    tokio::task::spawn_blocking(move || {
+        let account = find_account(&vault_path, &account_id)?;
+        let reports = remote_folders(&vault_path, &account)?
+            .into_iter()
+            .filter(|f| f.selectable)
+            .map(|f| mail::sync_folder_into(&vault_path, &account, &f.name, &mail::folder_dir(&account.id, &f.name), limit.unwrap_or(20)))
+            .collect();
+        warn_failing_account(&app, &vault_path, &account.id);
+        notify_new_mail(&app, &account.id);
+        Ok(reports)
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+fn find_account(vault_path: &str, account_id: &str) -> Result<MailAccount, String> {
+    mail::load_accounts(vault_path)?
+        .into_iter()
+        .find(|a| a.id == account_id)
+        .ok_or_else(|| tr!("Account not found: {}", account_id))
+}
+
+/// The account's folders on the server, marked with whether sync pulls them
+fn remote_folders(vault_path: &str, account: &MailAccount) -> Result<Vec<RemoteFolder>, String> {
+    let imap = &account.imap;
+    let mut folders = if imap.protocol.as_deref() == Some("pop3") {
+        vec![RemoteFolder { name: "INBOX".into(), subscribed: true, selectable: true, synced: false }]
+    } else if imap.protocol.as_deref() == Some("gmail") {
+        gmail::folders(vault_path)?
+            .into_iter()
+            .map(|name| RemoteFolder { name, subscribed: true, selectable: true, synced: false })
+            .collect()
+    } else {
+        let use_tls = imap.imap_port == 993 || imap.imap_port == 995;
+        imap_list_folders_with_crate(&imap.imap_host, imap.imap_port, &imap.email, &imap.password, use_tls)?
+    };
+    let synced = mail::synced_folders(account);
+    for folder in &mut folders {
+        folder.synced = synced.contains(&folder.name);
+    }
+    Ok(folders)
+}
+
+/// Choose which folders sync pulls for the account, so large archives can stay on the server
+#[tauri::command]
+pub fn set_synced_folders(vault_path: String, account_id: String, folders: Vec<String>) -> Result<(), String> {
+    mail::set_account_folders(&vault_path, &account_id, &folders)
+}
+
+/// Sign in to Google for Gmail and add the account to the Mail view (once
+/// per address); returns the account id
+#[tauri::command]
+pub async fn connect_gmail(vault_path: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let vault = Path::new(&vault_path);
+        google::sign_in(vault, &[gmail::SCOPE], &|url| open::that(url).map_err(|e| tr!("Failed to open link: {}", e)))?;
+        let address = gmail::profile_email(&vault_path)?;
+        mail::add_gmail_account(&vault_path, &address)
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Sync every enabled account's chosen folders, newest `limit` (default 20) each
+#[tauri::command]
+pub async fn sync_all_accounts(app: AppHandle, vault_path: String, limit: Option<u32>) -> Result<Vec<mail::SyncReport>, String> {
+    tokio::task::spawn_blocking(move || {
+        let accounts = mail::load_accounts(&vault_path)?;
+        let mut reports = Vec::new();
+        for account in accounts.iter().filter(|a| a.enabled) {
+            reports.extend(mail::sync_account(&vault_path, account, limit.unwrap_or(20)));
+            warn_failing_account(&app, &vault_path, &account.id);
+            notify_new_mail(&app, &account.id);
+        }
+        Ok(reports)
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Sync health of every configured account: recent attempts, latency and
+/// whether it is failing, worst first
+#[tauri::command]
+pub fn get_account_health(vault_path: String) -> Result<Vec<AccountHealth>, String> {
+    let mut health: Vec<AccountHealth> = mail::load_accounts(&vault_path)?.iter().map(|a| mail_health::health(&vault_path, &a.id)).collect();
+    health.sort_by_key(|h| std::cmp::Reverse(h.consecutive_failures));
+    Ok(health)
+}
+
+/// Tell the window and the system once an account starts failing, so a
+/// broken login doesn't go unnoticed while mail silently stops arriving
+pub(crate) fn warn_failing_account(app: &AppHandle, vault_path: &str, account_id: &str) {
+    let Some(health) = mail_health::take_warning(vault_path, account_id) else { return };
+    if let Err(e) = app.emit(MAIL_ACCOUNT_WARNING_EVENT, &health) {
+        println!("[WARN] failed to emit {MAIL_ACCOUNT_WARNING_EVENT}: {e}");
+    }
+    let name = mail::load_accounts(vault_path)
+        .ok()
+        .and_then(|accounts| accounts.into_iter().find(|a| a.id == account_id))
+        .map_or_else(|| account_id.to_string(), |a| a.email);
+    let body = if health.auth_error {
+        tr!("The server rejected the login; check the password or app password")
+    } else {
+        health.last_error.clone().unwrap_or_default()
+    };
+    if let Err(e) = app.notification().builder().title(tr!("Mail sync failing: {}", name)).body(body).show() {
+        println!("[WARN] failed to show mail warning: {e}");
+    }
+}
+
+/// Announce what the account's last syncs found past `last_uid`: emit
+/// `MAIL_ARRIVED_EVENT` for the window and show one system notification with
+/// the sender and subject (or a count), unless a focus session mutes them
+pub(crate) fn notify_new_mail(app: &AppHandle, account_id: &str) {
+    let Some(arrived) = mail::take_arrived(account_id) else { return };
+    if let Err(e) = app.emit(MAIL_ARRIVED_EVENT, &arrived) {
+        println!("[WARN] failed to emit {MAIL_ARRIVED_EVENT}: {e}");
+    }
+    if arrived.is_empty() || notifications_muted() {
+        return;
+    }
+    let (title, body) = match arrived.as_slice() {
+        [one] => (sender_name(&one.from), one.subject.clone()),
+        many => {
+            let lines: Vec<String> = many.iter().take(ARRIVED_LISTED).map(|m| format!("{}: {}", sender_name(&m.from), m.subject)).collect();
+            (tr!("{} new messages", many.len()), lines.join("\n"))
+        }
+    };
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        println!("[WARN] failed to show new mail notification: {e}");
+    }
+}
+
+/// `"Name" <a@b>` → `Name`, else the address
+fn sender_name(from: &str) -> String {
+    match from.split_once('<') {
+        Some((name, _)) if !name.trim().trim_matches('"').is_empty() => name.trim().trim_matches('"').to_string(),
+        _ => from.trim().trim_start_matches('<').trim_end_matches('>').to_string(),
+    }
+}
+
+/// Messages cached more than once under the same Message-ID, across all
+/// accounts: the same newsletter arriving at several addresses
+#[tauri::command]
+pub async fn find_duplicate_emails(vault_path: String) -> Result<Vec<mail::DuplicateGroup>, String> {
+    tokio::task::spawn_blocking(move || mail::find_duplicates(&vault_path))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))
+}
+
+/// Every account's cached `folder` (INBOX by default), newest first, at most
+/// `limit` (default 100). `merge_duplicates` shows a message that reached
+/// several accounts once, listing the others in `alsoIn`.
+#[tauri::command]
+pub async fn get_unified_inbox(vault_path: String, folder: Option<String>, limit: Option<usize>, merge_duplicates: Option<bool>) -> Result<Vec<mail::UnifiedEmail>, String> {
+    tokio::task::spawn_blocking(move || {
+        let folder = folder.unwrap_or_else(|| "INBOX".to_string());
+        let mut rows = mail::unified_inbox(&vault_path, &folder, merge_duplicates.unwrap_or(true));
+        rows.truncate(limit.unwrap_or(100));
+        rows
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))
+}
+
+fn imap_list_folders_with_crate(host: &str, port: u16, email: &str, password: &str, use_tls: bool) -> Result<Vec<RemoteFolder>, String> {
+    let tls = imap_tls_connector()?;
+
+    if use_tls {
+        let mut session = imap_tls_client(host, port, &tls)?
+            .login(email, password)
+            .map_err(|e| tr!("Login failed: {}", e.0))?;
+        let result = imap_list_folders(&mut session);
+        session.logout().ok();
+        result
+    } else {
+        let mut session = imap_starttls_client(host, port, &tls)?
+            .login(email, password)
+            .map_err(|e| tr!("Login failed: {}", e.0))?;
+        let result = imap_list_folders(&mut session);
+        session.logout().ok();
+        result
+    }
+}
+
+/// INBOX first, then by name
+pub(crate) fn imap_list_folders<T: Read + Write>(session: &mut imap::Session<T>) -> Result<Vec<RemoteFolder>, String> {
+    let names = session
+        .list(None, Some("*"))
+        .map_err(|e| tr!("Failed to list folders: {}", e))?;
+    // Some servers don't implement LSUB; treat that as nothing subscribed
+    let subscribed: std::collections::HashSet<String> = session
+        .lsub(None, Some("*"))
+        .map(|names| names.iter().map(|n| n.name().to_string()).collect())
+        .unwrap_or_default();
+
+    let mut folders: Vec<RemoteFolder> = names
+        .iter()
+        .map(|n| RemoteFolder {
+            name: n.name().to_string(),
+            subscribed: subscribed.contains(n.name()),
+            selectable: !n.attributes().iter().any(|a| matches!(a, imap::types::NameAttribute::NoSelect)),
+            synced: false,
+        })
+        .collect();
+    folders.sort_by_key(|f| (!f.name.eq_ignore_ascii_case("INBOX"), f.name.clone()));
+    Ok(folders)
+}
+
+// ── IMAP IDLE ──────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewMail {
+    pub account_id: String,
+    pub folder: String,
+    /// Newest messages after the change, as `imap_sync` returns them
+    pub emails: Vec<EmailMessage>,
+}
+
+/// Keep an IDLE session open on the INBOX of every enabled IMAP account and
+/// sync it as soon as the server reports a change, emitting `NEW_MAIL_EVENT`.
+/// POP3 and Gmail API accounts are left to the background sync. Returns the
+/// accounts being watched.
+#[tauri::command]
+pub fn start_mail_idle(app: AppHandle, vault_path: String) -> Result<Vec<String>, String> {
+    stop_mail_idle();
+    let stop = Arc::new(AtomicBool::new(false));
+    *IDLE_STOP.lock().unwrap() = Some(stop.clone());
+
+    let accounts: Vec<MailAccount> = mail::load_accounts(&vault_path)?
+        .into_iter()
+        .filter(|a| a.enabled && !a.imap.password.is_empty() && a.imap.protocol.as_deref().is_none_or(|p| p == "imap"))
+        .collect();
+    for account in &accounts {
+        let (app, vault_path, account, stop) = (app.clone(), vault_path.clone(), account.clone(), stop.clone());
+        std::thread::spawn(move || idle_loop(&app, &vault_path, &account, &stop));
+    }
+    Ok(accounts.into_iter().map(|a| a.id).collect())
+}
+
+#[tauri::command]
+pub fn stop_mail_idle() {
+    if let Some(stop) = IDLE_STOP.lock().unwrap().take() {
+        stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Reconnect whenever the session drops, until stopped or the server turns
+/// out not to support IDLE
+fn idle_loop(app: &AppHandle, vault_path: &str, account: &MailAccount, stop: &AtomicBool) {
+    while !stop.load(Ordering::Relaxed) {
+        match idle_session(app, vault_path, account, stop) {
+            Ok(true) => {}
+            Ok(false) => return println!("[mail] {} does not support IDLE; leaving it to background sync", account.id),
+            Err(e) => {
+                println!("[WARN] IMAP IDLE for {} failed: {e}", account.id);
+                for _ in 0..IDLE_RETRY_SECS {
+                    if stop.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    std::thread::sleep(Duration::from_secs(1));
+                }
+            }
+        }
+    }
+}
+
+/// One connection's worth of IDLE; false when the server has no IDLE
+fn idle_session(app: &AppHandle, vault_path: &str, account: &MailAccount, stop: &AtomicBool) -> Result<bool, String> {
+    let imap = &account.imap;
+    let tls = imap_tls_connector()?;
+    if imap.imap_port == 993 {
+        let mut session = imap_tls_client(&imap.imap_host, imap.imap_port, &tls)?
+            .login(&imap.email, &imap.password)
+            .map_err(|e| tr!("Login failed: {}", e.0))?;
+        let result = idle_wait(&mut session, app, vault_path, account, stop);
+        session.logout().ok();
+        result
+    } else {
+        let mut session = imap_starttls_client(&imap.imap_host, imap.imap_port, &tls)?
+            .login(&imap.email, &imap.password)
+            .map_err(|e| tr!("Login failed: {}", e.0))?;
+        let result = idle_wait(&mut session, app, vault_path, account, stop);
+        session.logout().ok();
+        result
+    }
+}
 
-    if json_path.exists() {
-        let content = fs::read_to_string(&json_path).map_err(|e| format!("读取邮件失败: {}", e))?;
-        let email: EmailMessage = serde_json::from_str(&content).map_err(|e| format!("解析邮件失败: {}", e))?;
-        return Ok(email);
+/// IDLE on the folder, syncing it on every change, until stopped
+fn idle_wait<T: Read + Write + SetReadTimeout>(
+    session: &mut imap::Session<T>,
+    app: &AppHandle,
+    vault_path: &str,
+    account: &MailAccount,
+    stop: &AtomicBool,
+) -> Result<bool, String> {
+    let capabilities = session.capabilities().map_err(|e| tr!("IMAP IDLE failed: {}", e))?;
+    if !capabilities.has_str("IDLE") {
+        return Ok(false);
+    }
+    session.select(IDLE_FOLDER).map_err(|e| tr!("Failed to select folder: {}", e))?;
+    while !stop.load(Ordering::Relaxed) {
+        let outcome = session
+            .idle()
+            .and_then(|idle| idle.wait_with_timeout(Duration::from_secs(IDLE_WAIT_SECS)))
+            .map_err(|e| tr!("IMAP IDLE failed: {}", e))?;
+        if outcome == WaitOutcome::MailboxChanged && !stop.load(Ordering::Relaxed) {
+            // Fetched on a second connection: this one stays selected for IDLE
+            let result = sync_mailbox(&account.imap, vault_path, IDLE_FOLDER, IDLE_FETCH_LIMIT, 0);
+            warn_failing_account(app, vault_path, &account.id);
+            notify_new_mail(app, &account.id);
+            match result {
+                Ok(emails) => {
+                    let emails = emails.into_iter().map(|email| clean_html(email, false, false)).collect();
+                    let payload = NewMail { account_id: account.id.clone(), folder: IDLE_FOLDER.to_string(), emails };
+                    if let Err(e) = app.emit(NEW_MAIL_EVENT, &payload) {
+                        println!("[WARN] failed to emit {NEW_MAIL_EVENT}: {e}");
+                    }
+                }
+                Err(e) => println!("[WARN] mail sync {}/{IDLE_FOLDER} failed: {e}", account.id),
+            }
+        }
     }
+    Ok(true)
+}
 
-    Err(format!("邮件文件不存在: {}", email_id))
+// ── Auto sync ──────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MailSynced {
+    /// New messages across all accounts
+    pub new: usize,
+    pub reports: Vec<mail::SyncReport>,
 }
 
-/// List available email folders
+/// The running `start_auto_sync` loop, managed as app state
+#[derive(Default)]
+pub struct AutoSync(Mutex<Option<JoinHandle<()>>>);
+
+/// Sync every enabled account's folders now and then every `interval`
+/// minutes, emitting `MAIL_SYNCED_EVENT` with the new-message counts after
+/// each round. Unlike the background agent this ignores per-folder
+/// intervals; accounts are re-read each round.
 #[tauri::command]
-pub fn list_email_folders(vault_path: String) -> Result<Vec<String>, String> {
-    let emails_dir = PathBuf::from(&vault_path).join("Mailbox");
+pub fn start_auto_sync(app: AppHandle, auto_sync: State<'_, AutoSync>, vault_path: String, interval: u64) {
+    let every = Duration::from_secs(interval.max(1) * 60);
+    let task = tauri::async_runtime::spawn(async move {
+        let mut ticks = tokio::time::interval(every);
+        // A round longer than the interval delays the next instead of bunching them
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticks.tick().await;
+            let (app, vault_path) = (app.clone(), vault_path.clone());
+            if let Err(e) = tokio::task::spawn_blocking(move || auto_sync_round(&app, &vault_path)).await {
+                println!("[WARN] auto mail sync: {e}");
+            }
+        }
+    });
+    if let Some(previous) = auto_sync.0.lock().unwrap().replace(task) {
+        previous.abort();
+    }
+}
 
-    if !emails_dir.exists() {
-        return Ok(vec![
-            "INBOX".to_string(),
-            "Sent".to_string(),
-            "Drafts".to_string(),
-            "Trash".to_string(),
-            "Archive".to_string(),
-        ]);
+/// Stop the loop; a round already running finishes
+#[tauri::command]
+pub fn stop_auto_sync(auto_sync: State<'_, AutoSync>) {
+    if let Some(task) = auto_sync.0.lock().unwrap().take() {
+        task.abort();
     }
+}
 
-    let mut folders = Vec::new();
-    if let Ok(entries) = fs::read_dir(&emails_dir) {
-        for entry in entries.flatten() {
-            if entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
-                folders.push(entry.file_name().to_string_lossy().to_string());
+fn auto_sync_round(app: &AppHandle, vault_path: &str) {
+    let accounts = match mail::load_accounts(vault_path) {
+        Ok(accounts) => accounts,
+        Err(e) => return println!("[WARN] auto mail sync: {e}"),
+    };
+    let mut reports = Vec::new();
+    for account in accounts.iter().filter(|a| a.enabled) {
+        for report in mail::sync_account(vault_path, account, AUTO_SYNC_LIMIT) {
+            if let Some(e) = &report.error {
+                println!("[WARN] mail sync {}/{} failed: {e}", report.account_id, report.folder);
             }
+            reports.push(report);
         }
+        warn_failing_account(app, vault_path, &account.id);
+        notify_new_mail(app, &account.id);
     }
-
-    if folders.is_empty() {
-        folders = vec![
-            "INBOX".to_string(),
-            "Sent".to_string(),
-            "Drafts".to_string(),
-            "Trash".to_string(),
-            "Archive".to_string(),
-        ];
+    let payload = MailSynced { new: reports.iter().map(|r| r.new).sum(), reports };
+    if let Err(e) = app.emit(MAIL_SYNCED_EVENT, &payload) {
+        println!("[WARN] failed to emit {MAIL_SYNCED_EVENT}: {e}");
     }
-
-    Ok(folders)
 }
 
 // ── SMTP Send ──────────────────────────────────────────────────────────────
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SmtpConfig {
-    pub from_email: String,
-    pub from_name: String,
-    pub password: String,
-    pub smtp_host: String,
-    pub smtp_port: u16,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SendEmailRequest {
-    pub smtp: SmtpConfig,
-    pub to: String,
-    pub subject: String,
-    pub body: String,
-    pub in_reply_to: Option<String>,
+/// Send an email via SMTP. Offline, with `vault_path` set, the message is
+/// queued in the outbox instead and the queued operation returned.
+#[tauri::command]
+pub async fn send_email(app: AppHandle, request: SendEmailRequest) -> Result<Option<QueuedOperation>, String> {
+    match deliver(&request) {
+        Err(e) if outbox::is_network_error(&e) => match request.vault_path.clone() {
+            Some(vault_path) => outbox_commands::queue(&app, &vault_path, Operation::SendEmail { request }).map(Some),
+            None => Err(e),
+        },
+        result => result.map(|()| None),
+    }
 }
 
-/// Send an email via SMTP
-#[tauri::command]
-pub async fn send_email(request: SendEmailRequest) -> Result<(), String> {
+/// Build and send the message, file the sent copy and track the follow-up.
+/// Blocking; also replays queued messages.
+pub(crate) fn deliver(request: &SendEmailRequest) -> Result<(), String> {
     use lettre::{Message, SmtpTransport, Transport};
     use lettre::transport::smtp::authentication::Credentials;
     use lettre::message::header::ContentType;
+    use lettre::message::{Attachment, MultiPart, SinglePart};
+
+    let identity = match (&request.vault_path, &request.account_id) {
+        (Some(vault_path), Some(account_id)) => mail::resolve_identity(vault_path, account_id, request.identity_id.as_deref())?,
+        _ => None,
+    };
+    let (sender_email, sender_name, body) = match &identity {
+        Some(i) => (i.address.clone(), i.name.clone(), mail::with_signature(&request.body, &i.signature)),
+        None => (request.smtp.from_email.clone(), request.smtp.from_name.clone(), request.body.clone()),
+    };
+    let thread = request.in_reply_to.as_deref().filter(|id| !id.trim().is_empty()).map(|id| reply_thread(request, id));
+    let subject = match thread {
+        Some(_) => mail::reply_subject(&request.subject),
+        None => request.subject.clone(),
+    };
+    let html = request.body_html.as_deref().filter(|h| !h.trim().is_empty()).map(|h| match &identity {
+        Some(i) => mail::html_with_signature(h, &i.signature),
+        None => h.to_string(),
+    });
 
     // 处理发件人地址，如果 from_name 为空或与 from_email 相同则直接使用邮箱地址
-    let from_name_trimmed = request.smtp.from_name.trim();
-    let from_address = if from_name_trimmed.is_empty() || from_name_trimmed == &request.smtp.from_email {
+    let from_name_trimmed = sender_name.trim();
+    let from_address = if from_name_trimmed.is_empty() || from_name_trimmed == sender_email {
         // 名称为空或与邮箱相同，直接使用邮箱地址
-        request.smtp.from_email.clone()
+        sender_email.clone()
     } else {
-        format!("{} <{}>", request.smtp.from_name, request.smtp.from_email)
+        format!("{} <{}>", sender_name, sender_email)
     };
 
     // 调试日志
@@ -1108,15 +954,40 @@ pub async fn send_email(request: SendEmailRequest) -> Result<(), String> {
     println!("[DEBUG send_email] from_name: {:?}", request.smtp.from_name);
     println!("[DEBUG send_email] from_address: {:?}", from_address);
 
-    let email = Message::builder()
+    // Set here rather than by lettre so a follow-up can refer to it
+    let domain = sender_email.rsplit_once('@').map_or("lifeos.local", |(_, d)| d);
+    let message_id = format!("<{}@{}>", uuid::Uuid::new_v4(), domain);
+
+    let mut builder = Message::builder()
+        .message_id(Some(message_id.clone()))
         .from(from_address
             .parse()
-            .map_err(|e| format!("发件人地址无效: {} (from_address: {:?})", e, from_address))?)
-        .to(request.to.parse().map_err(|e| format!("收件人地址无效: {}", e))?)
-        .subject(&request.subject)
-        .header(ContentType::TEXT_PLAIN)
-        .body(request.body)
-        .map_err(|e| format!("构建邮件失败: {}", e))?;
+            .map_err(|e| tr!("Invalid sender address: {} (from_address: {})", e, format!("{:?}", from_address)))?)
+        .to(request.to.parse().map_err(|e| tr!("Invalid recipient address: {}", e))?)
+        .subject(subject.clone());
+    if let Some((in_reply_to, references)) = thread.as_ref().and_then(|t| t.headers.clone()) {
+        builder = builder.in_reply_to(in_reply_to).references(references);
+    }
+    let email = if request.attachments.is_empty() {
+        match html {
+            Some(html) => builder.multipart(MultiPart::alternative_plain_html(body, html)),
+            None => builder.header(ContentType::TEXT_PLAIN).body(body),
+        }
+    } else {
+        let mut parts = match html {
+            Some(html) => MultiPart::mixed().multipart(MultiPart::alternative_plain_html(body, html)),
+            None => MultiPart::mixed().singlepart(SinglePart::plain(body)),
+        };
+        for file in &request.attachments {
+            let path = attachment_path(request.vault_path.as_deref(), file)?;
+            let bytes = fs::read(&path).map_err(|e| tr!("Failed to read attachment {}: {}", path.display(), e))?;
+            let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "attachment".into());
+            let content_type = ContentType::parse(mail::content_type_for(&path)).map_err(|e| tr!("Failed to build email: {}", e))?;
+            parts = parts.singlepart(Attachment::new(name).body(bytes, content_type));
+        }
+        builder.multipart(parts)
+    }
+    .map_err(|e| tr!("Failed to build email: {}", e))?;
 
     let creds = Credentials::new(
         request.smtp.from_email.clone(),
@@ -1124,16 +995,128 @@ pub async fn send_email(request: SendEmailRequest) -> Result<(), String> {
     );
 
     let mailer = SmtpTransport::relay(&request.smtp.smtp_host)
-        .map_err(|e| format!("SMTP 连接失败: {}", e))?
+        .map_err(|e| tr!("SMTP connection failed: {}", e))?
         .port(request.smtp.smtp_port)
         .credentials(creds)
         .build();
 
-    mailer.send(&email).map_err(|e| format!("发送失败: {}", e))?;
+    mailer.send(&email).map_err(|e| tr!("Failed to send: {}", e))?;
+
+    // The message is out; failing to file a copy only warrants a warning
+    if let (Some(folder), Some(vault_path), Some(account_id)) =
+        (identity.as_ref().and_then(|i| i.folder.as_deref()), &request.vault_path, &request.account_id)
+    {
+        if let Err(e) = store_eml(vault_path, account_id, folder, &email.formatted()) {
+            println!("[WARN] failed to file sent copy in {}: {}", folder, e);
+        }
+    }
+
+    if let (Some((account_id, email_id)), Some(vault_path)) = (thread.and_then(|t| t.original), &request.vault_path) {
+        if let Err(e) = record_reply(vault_path, &account_id, &email_id, &message_id) {
+            println!("[WARN] failed to record reply to {}: {}", email_id, e);
+        }
+    }
+
+    if let (Some(due), Some(vault_path)) = (request.follow_up_by.clone(), &request.vault_path) {
+        let followup = FollowUp {
+            message_id,
+            account_id: request.account_id.clone().unwrap_or_default(),
+            subject,
+            to: request.to.clone(),
+            from: sender_email,
+            since: chrono::Local::now().to_rfc3339(),
+            due,
+            notified: Vec::new(),
+        };
+        if let Err(e) = followup_commands::track(Path::new(vault_path), followup) {
+            println!("[WARN] failed to track follow-up: {}", e);
+        }
+    }
 
     Ok(())
 }
 
+/// Where a reply belongs: the message it answers and the headers that put
+/// it in that message's thread
+struct Thread {
+    /// (account, email id) of the original in the local cache
+    original: Option<(String, String)>,
+    /// (In-Reply-To, References)
+    headers: Option<(String, String)>,
+}
+
+/// `id` is an email id from the account's index or a Message-ID; the cached
+/// original supplies the References chain. Without one, a Message-ID still
+/// makes a one-link chain.
+fn reply_thread(request: &SendEmailRequest, id: &str) -> Thread {
+    let cached = request.vault_path.as_deref().and_then(|vault_path| {
+        let (account_id, email_id) = match &request.account_id {
+            Some(account_id) if mailbox_file(vault_path, account_id, id).exists() => (account_id.clone(), id.to_string()),
+            _ => {
+                let found = mail::find_message(vault_path, id)?;
+                (found.account, found.email_id)
+            }
+        };
+        let raw = fs::read(mailbox_file(vault_path, &account_id, &email_id)).ok();
+        Some(((account_id, email_id), raw))
+    });
+    let headers = cached.as_ref().and_then(|(_, raw)| raw.as_deref()).and_then(mail::reply_headers).or_else(|| {
+        let id = id.trim().trim_start_matches('<').trim_end_matches('>');
+        id.contains('@').then(|| (format!("<{id}>"), format!("<{id}>")))
+    });
+    Thread { original: cached.map(|(original, _)| original), headers }
+}
+
+/// Attachments come from the vault: a path under it, absolute (as the file
+/// picker returns) or vault-relative
+fn attachment_path(vault_path: Option<&str>, file: &str) -> Result<PathBuf, String> {
+    let vault = Path::new(vault_path.ok_or_else(|| tr!("Path must stay inside the vault: {}", file))?);
+    let path = Path::new(file);
+    let rel = match path.strip_prefix(vault) {
+        Ok(rel) => rel,
+        Err(_) if path.is_absolute() => return Err(tr!("Path must stay inside the vault: {}", file)),
+        Err(_) => path,
+    };
+    automations::vault_file(vault, &rel.to_string_lossy())
+}
+
+/// The cached .eml of a message
+fn mailbox_file(vault_path: &str, account_id: &str, email_id: &str) -> PathBuf {
+    mail::eml_path(&PathBuf::from(vault_path).join("Mailbox").join(account_id), email_id)
+}
+
+/// Mark the original answered and note the reply's Message-ID in index.json
+fn record_reply(vault_path: &str, account_id: &str, email_id: &str, reply_id: &str) -> Result<(), String> {
+    let mut emails = load_existing_emails(vault_path, account_id)?;
+    let Some(original) = emails.iter_mut().find(|e| e.id == email_id) else { return Ok(()) };
+    if !original.flags.iter().any(|f| f == "Answered") {
+        original.flags.push("Answered".to_string());
+    }
+    let reply_id = reply_id.trim_start_matches('<').trim_end_matches('>').to_string();
+    if !original.replies.contains(&reply_id) {
+        original.replies.push(reply_id);
+    }
+    save_index_json(&PathBuf::from(vault_path).join("Mailbox").join(account_id), &emails)
+}
+
+// ── Sending identities ─────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn list_email_identities(vault_path: String, account_id: String) -> Result<Vec<EmailIdentity>, String> {
+    Ok(mail::load_identities(&vault_path, &account_id))
+}
+
+/// Add or update (by id) an address the account can send as
+#[tauri::command]
+pub fn save_email_identity(vault_path: String, account_id: String, identity: EmailIdentity) -> Result<EmailIdentity, String> {
+    mail::save_identity(&vault_path, &account_id, identity)
+}
+
+#[tauri::command]
+pub fn delete_email_identity(vault_path: String, account_id: String, identity_id: String) -> Result<(), String> {
+    mail::delete_identity(&vault_path, &account_id, &identity_id)
+}
+
 /// Delete an email from local cache and optionally from IMAP server
 #[tauri::command]
 pub async fn delete_email(
@@ -1198,50 +1181,59 @@ pub async fn delete_email(
             let tls = native_tls::TlsConnector::builder()
                 .danger_accept_invalid_certs(true)
                 .build()
-                .map_err(|e| format!("TLS 创建失败: {}", e))?;
+                .map_err(|e| tr!("Failed to create TLS connector: {}", e))?;
 
             let client = if use_tls {
                 imap::connect((host.as_str(), *port), host.as_str(), &tls)
-                    .map_err(|e| format!("IMAP 连接失败: {}", e))?
+                    .map_err(|e| tr!("IMAP connection failed: {}", e))?
             } else {
                 let stream = TcpStream::connect((host.as_str(), *port))
-                    .map_err(|e| format!("连接失败: {}", e))?;
+                    .map_err(|e| tr!("Connection failed: {}", e))?;
                 imap::Client::new(stream)
                     .secure(host.as_str(), &tls)
-                    .map_err(|e| format!("STARTTLS 失败: {}", e))?
+                    .map_err(|e| tr!("STARTTLS failed: {}", e))?
             };
 
             let mut session = client
                 .login(&email_addr, &password)
-                .map_err(|e| format!("登录失败: {}", e.0))?;
+                .map_err(|e| tr!("Login failed: {}", e.0))?;
 
             // Select mailbox
-            session.select(&folder_name).map_err(|e| format!("选择文件夹失败: {}", e))?;
+            session.select(&folder_name).map_err(|e| tr!("Failed to select folder: {}", e))?;
 
             // Store +FLAGS (\Deleted) to mark as deleted using UID
             session
                 .store(format!("{}", uid), "+FLAGS (\\Deleted)")
-                .map_err(|e| format!("标记删除失败: {}", e))?;
+                .map_err(|e| tr!("Failed to mark as deleted: {}", e))?;
 
             // Expunge to permanently delete
-            session.expunge().map_err(|e| format!("永久删除失败: {}", e))?;
+            session.expunge().map_err(|e| tr!("Failed to expunge: {}", e))?;
 
             session.logout().ok();
         }
+    } else if protocol == "gmail" {
+        let (vault_path, email_id) = (vault_path.clone(), email_id.clone());
+        tokio::task::spawn_blocking(move || gmail::trash(&vault_path, &email_id))
+            .await
+            .map_err(|e| tr!("Task execution failed: {}", e))??;
     }
 
-    // Delete from local cache
-    let emails_dir = PathBuf::from(&vault_path)
+    remove_cached(&vault_path, &account_id, &email_id, uid)
+}
+
+/// Drop a message from the account's index.json and its cached .eml
+fn remove_cached(vault_path: &str, account_id: &str, email_id: &str, uid: u32) -> Result<(), String> {
+    let emails_dir = PathBuf::from(vault_path)
         .join("Mailbox")
-        .join(&account_id);
+        .join(account_id);
 
     // Load index.json
     let index_path = emails_dir.join("index.json");
     if index_path.exists() {
         let content = fs::read_to_string(&index_path)
-            .map_err(|e| format!("读取索引失败: {}", e))?;
+            .map_err(|e| tr!("Failed to read index: {}", e))?;
         let mut emails: Vec<EmailMessage> = serde_json::from_str(&content)
-            .map_err(|e| format!("解析索引失败: {}", e))?;
+            .map_err(|e| tr!("Failed to parse index: {}", e))?;
 
         // Find and remove the email
         let original_len = emails.len();
@@ -1250,16 +1242,16 @@ pub async fn delete_email(
         if emails.len() < original_len {
             // Save updated index
             let index_json = serde_json::to_string_pretty(&emails)
-                .map_err(|e| format!("序列化失败: {}", e))?;
-            fs::write(&index_path, index_json)
-                .map_err(|e| format!("写入索引失败: {}", e))?;
+                .map_err(|e| tr!("Failed to serialize: {}", e))?;
+            durable::write(&index_path, index_json)
+                .map_err(|e| tr!("Failed to write index: {}", e))?;
         }
     }
 
     // Try to delete EML file if exists
     // The EML filename is based on the email's id (message_id or folder_uid)
     let eml_files = vec![
-        emails_dir.join(format!("{}.eml", email_id)),
+        mail::eml_path(&emails_dir, email_id),
         emails_dir.join(format!("{}.eml", uid)),
     ];
 
@@ -1272,9 +1264,11 @@ pub async fn delete_email(
     Ok(())
 }
 
-/// Mark an email as read or unread
+/// Mark an email as read or unread, in the local cache and on the server.
+/// Offline, the server change is queued in the outbox.
 #[tauri::command]
 pub async fn mark_email_read(
+    app: AppHandle,
     vault_path: String,
     account_id: String,
     email_id: String,
@@ -1285,17 +1279,69 @@ pub async fn mark_email_read(
     imap_password: Option<String>,
     email: Option<String>,
 ) -> Result<(), String> {
-    // Parse email_id to extract uid
+    let message = RemoteMessage { account_id: account_id.clone(), email_id: email_id.clone(), folder, imap_host, imap_port, imap_password, email };
+    let (vault, remote) = (vault_path.clone(), message.clone());
+    match tokio::task::spawn_blocking(move || remote_set_read(&vault, &remote, read))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+    {
+        Err(e) if outbox::is_network_error(&e) => {
+            outbox_commands::queue(&app, &vault_path, Operation::SetRead { message, read })?;
+        }
+        result => result?,
+    }
+
+    // Update local cache
+    let emails_dir = PathBuf::from(&vault_path)
+        .join("Mailbox")
+        .join(&account_id);
+
+    let index_path = emails_dir.join("index.json");
+    if index_path.exists() {
+        let content = fs::read_to_string(&index_path)
+            .map_err(|e| tr!("Failed to read index: {}", e))?;
+        let mut emails: Vec<EmailMessage> = serde_json::from_str(&content)
+            .map_err(|e| tr!("Failed to parse index: {}", e))?;
+
+        // Find and update the email's flags
+        for email in emails.iter_mut() {
+            if email.id == email_id {
+                if read {
+                    // Add Seen flag if not present
+                    if !email.flags.contains(&"Seen".to_string()) {
+                        email.flags.push("Seen".to_string());
+                    }
+                } else {
+                    // Remove Seen flag
+                    email.flags.retain(|f| f != "Seen");
+                }
+                break;
+            }
+        }
+
+        // Save updated index
+        let index_json = serde_json::to_string_pretty(&emails)
+            .map_err(|e| tr!("Failed to serialize: {}", e))?;
+        durable::write(&index_path, index_json)
+            .map_err(|e| tr!("Failed to write index: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Set or clear \Seen on the server, where the account allows it (IMAP
+/// credentials given, or Gmail). Blocking; also replays queued changes.
+pub(crate) fn remote_set_read(vault_path: &str, message: &RemoteMessage, read: bool) -> Result<(), String> {
     // email_id format: "FOLDER_UID" (e.g., "INBOX_123")
-    let uid: u32 = email_id
+    let uid: u32 = message.email_id
         .split('_')
         .last()
         .and_then(|s| s.parse().ok())
         .unwrap_or(0);
 
     // Use the provided folder parameter, or fall back to parsing from email_id
-    let folder_name = folder.unwrap_or_else(|| {
-        let parts: Vec<&str> = email_id.split('_').collect();
+    let folder_name = message.folder.clone().unwrap_or_else(|| {
+        let parts: Vec<&str> = message.email_id.split('_').collect();
         if parts.len() >= 2 {
             parts[..parts.len() - 1].join("_")
         } else {
@@ -1303,109 +1349,225 @@ pub async fn mark_email_read(
         }
     });
 
-    // Load account info to get protocol
-    let account_path = PathBuf::from(&vault_path)
-        .join(".lifeos")
-        .join("emails")
-        .join(format!("{}.json", account_id));
-
-    let protocol = if account_path.exists() {
-        if let Ok(content) = fs::read_to_string(&account_path) {
-            if let Ok(data) = serde_json::from_str::<serde_json::Value>(&content) {
-                data.get("protocol")
-                    .and_then(|p| p.as_str())
-                    .unwrap_or("imap")
-                    .to_string()
-            } else {
-                "imap".to_string()
-            }
-        } else {
-            "imap".to_string()
-        }
-    } else {
-        "imap".to_string()
-    };
-
-    // First, try to mark as read/unread on IMAP server if it's IMAP protocol
+    let protocol = account_protocol(vault_path, &message.account_id);
     if protocol == "imap" {
         if let (Some(host), Some(port), Some(password), Some(email_addr)) =
-            (&imap_host, &imap_port, &imap_password, &email)
+            (&message.imap_host, &message.imap_port, &message.imap_password, &message.email)
         {
             let use_tls = *port == 993;
 
             let tls = native_tls::TlsConnector::builder()
                 .danger_accept_invalid_certs(true)
                 .build()
-                .map_err(|e| format!("TLS 创建失败: {}", e))?;
+                .map_err(|e| tr!("Failed to create TLS connector: {}", e))?;
 
             let client = if use_tls {
                 imap::connect((host.as_str(), *port), host.as_str(), &tls)
-                    .map_err(|e| format!("IMAP 连接失败: {}", e))?
+                    .map_err(|e| tr!("IMAP connection failed: {}", e))?
             } else {
                 let stream = TcpStream::connect((host.as_str(), *port))
-                    .map_err(|e| format!("连接失败: {}", e))?;
+                    .map_err(|e| tr!("Connection failed: {}", e))?;
                 imap::Client::new(stream)
                     .secure(host.as_str(), &tls)
-                    .map_err(|e| format!("STARTTLS 失败: {}", e))?
+                    .map_err(|e| tr!("STARTTLS failed: {}", e))?
             };
 
             let mut session = client
-                .login(&email_addr, &password)
-                .map_err(|e| format!("登录失败: {}", e.0))?;
+                .login(email_addr, password)
+                .map_err(|e| tr!("Login failed: {}", e.0))?;
 
             // Select mailbox
-            session.select(&folder_name).map_err(|e| format!("选择文件夹失败: {}", e))?;
+            session.select(&folder_name).map_err(|e| tr!("Failed to select folder: {}", e))?;
 
             // Store flags to mark as read/unread using UID
             let flag_action = if read { "+FLAGS (\\Seen)" } else { "-FLAGS (\\Seen)" };
             session
                 .store(format!("{}", uid), flag_action)
-                .map_err(|e| format!("标记已读/未读失败: {}", e))?;
+                .map_err(|e| tr!("Failed to mark as read/unread: {}", e))?;
 
             session.logout().ok();
         }
+    } else if protocol == "gmail" {
+        gmail::set_read(vault_path, &message.email_id, read)?;
+    }
+    Ok(())
+}
+
+/// Move an email to the Archive folder, on the server when the account
+/// allows it (IMAP credentials given, or Gmail) and in the local cache.
+/// Offline, the server move is queued in the outbox.
+#[tauri::command]
+pub async fn archive_email(
+    app: AppHandle,
+    vault_path: String,
+    account_id: String,
+    email_id: String,
+    folder: Option<String>,
+    imap_host: Option<String>,
+    imap_port: Option<u16>,
+    imap_password: Option<String>,
+    email: Option<String>,
+) -> Result<(), String> {
+    let message = RemoteMessage { account_id: account_id.clone(), email_id: email_id.clone(), folder, imap_host, imap_port, imap_password, email };
+    let (vault, remote) = (vault_path.clone(), message.clone());
+    match tokio::task::spawn_blocking(move || remote_archive(&vault, &remote))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+    {
+        Err(e) if outbox::is_network_error(&e) => {
+            outbox_commands::queue(&app, &vault_path, Operation::Archive { message })?;
+        }
+        result => result?,
     }
 
     // Update local cache
-    let emails_dir = PathBuf::from(&vault_path)
-        .join("Mailbox")
-        .join(&account_id);
-
-    let index_path = emails_dir.join("index.json");
+    let index_path = PathBuf::from(&vault_path).join("Mailbox").join(&account_id).join("index.json");
     if index_path.exists() {
-        let content = fs::read_to_string(&index_path)
-            .map_err(|e| format!("读取索引失败: {}", e))?;
-        let mut emails: Vec<EmailMessage> = serde_json::from_str(&content)
-            .map_err(|e| format!("解析索引失败: {}", e))?;
-
-        // Find and update the email's flags
-        for email in emails.iter_mut() {
-            if email.id == email_id {
-                if read {
-                    // Add Seen flag if not present
-                    if !email.flags.contains(&"Seen".to_string()) {
-                        email.flags.push("Seen".to_string());
-                    }
-                } else {
-                    // Remove Seen flag
-                    email.flags.retain(|f| f != "Seen");
-                }
-                break;
+        let content = fs::read_to_string(&index_path).map_err(|e| tr!("Failed to read index: {}", e))?;
+        let mut emails: Vec<EmailMessage> = serde_json::from_str(&content).map_err(|e| tr!("Failed to parse index: {}", e))?;
+        if let Some(email) = emails.iter_mut().find(|e| e.id == email_id) {
+            email.folder = ARCHIVE_FOLDER.to_string();
+            if !email.flags.iter().any(|f| f == "Seen") {
+                email.flags.push("Seen".to_string());
             }
         }
+        let index_json = serde_json::to_string_pretty(&emails).map_err(|e| tr!("Failed to serialize: {}", e))?;
+        durable::write(&index_path, index_json).map_err(|e| tr!("Failed to write index: {}", e))?;
+    }
 
-        // Save updated index
-        let index_json = serde_json::to_string_pretty(&emails)
-            .map_err(|e| format!("序列化失败: {}", e))?;
-        fs::write(&index_path, index_json)
-            .map_err(|e| format!("写入索引失败: {}", e))?;
+    Ok(())
+}
+
+/// The server half of `archive_email`. Blocking; also replays queued moves.
+pub(crate) fn remote_archive(vault_path: &str, message: &RemoteMessage) -> Result<(), String> {
+    // email_id format: "FOLDER_UID" (e.g., "INBOX_123")
+    let uid: u32 = message.email_id.split('_').last().and_then(|s| s.parse().ok()).unwrap_or(0);
+    let folder_name = message.folder.clone().unwrap_or_else(|| message.email_id.rsplit_once('_').map_or("INBOX", |(f, _)| f).to_string());
+
+    let protocol = account_protocol(vault_path, &message.account_id);
+    if protocol == "imap" {
+        if let (Some(host), Some(port), Some(password), Some(email_addr)) =
+            (&message.imap_host, message.imap_port, &message.imap_password, &message.email)
+        {
+            let tls = imap_tls_connector()?;
+            if port == 993 {
+                let mut session = imap_tls_client(host, port, &tls)?
+                    .login(email_addr, password)
+                    .map_err(|e| tr!("Login failed: {}", e.0))?;
+                let result = imap_archive(&mut session, &folder_name, uid);
+                session.logout().ok();
+                result?;
+            } else {
+                let mut session = imap_starttls_client(host, port, &tls)?
+                    .login(email_addr, password)
+                    .map_err(|e| tr!("Login failed: {}", e.0))?;
+                let result = imap_archive(&mut session, &folder_name, uid);
+                session.logout().ok();
+                result?;
+            }
+        }
+    } else if protocol == "gmail" {
+        gmail::archive(vault_path, &message.email_id)?;
     }
+    Ok(())
+}
+
+/// The account's "protocol" from its settings file, "imap" when unset
+fn account_protocol(vault_path: &str, account_id: &str) -> String {
+    let account_path = PathBuf::from(vault_path).join(".lifeos").join("emails").join(format!("{}.json", account_id));
+    fs::read_to_string(&account_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|data| data.get("protocol").and_then(|p| p.as_str()).map(str::to_string))
+        .unwrap_or_else(|| "imap".to_string())
+}
 
+/// MOVE where the server has it, else copy and expunge the original
+fn imap_archive<T: Read + Write>(session: &mut imap::Session<T>, folder: &str, uid: u32) -> Result<(), String> {
+    session.select(folder).map_err(|e| tr!("Failed to select folder: {}", e))?;
+    let uid = uid.to_string();
+    if session.uid_mv(&uid, ARCHIVE_FOLDER).is_err() {
+        session.uid_copy(&uid, ARCHIVE_FOLDER).map_err(|e| tr!("Failed to archive email: {}", e))?;
+        session.uid_store(&uid, "+FLAGS (\\Deleted)").map_err(|e| tr!("Failed to mark as deleted: {}", e))?;
+        session.expunge().map_err(|e| tr!("Failed to expunge: {}", e))?;
+    }
     Ok(())
 }
 
 /// Open URL in external browser
 #[tauri::command]
 pub async fn open_external_url(url: String) -> Result<(), String> {
-    open::that(&url).map_err(|e| format!("打开链接失败: {}", e))
+    open::that(&url).map_err(|e| tr!("Failed to open link: {}", e))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attachments_stay_in_vault() {
+        let vault = "/vault";
+        assert_eq!(attachment_path(Some(vault), "/vault/docs/a.pdf").unwrap(), Path::new("/vault/docs/a.pdf"));
+        assert_eq!(attachment_path(Some(vault), "docs/a.pdf").unwrap(), Path::new("/vault/docs/a.pdf"));
+        for file in ["/home/me/.ssh/id_ed25519", "../.ssh/id_ed25519", "/vault/../etc/passwd"] {
+            assert!(attachment_path(Some(vault), file).is_err(), "{file}");
+        }
+        assert!(attachment_path(None, "docs/a.pdf").is_err());
+    }
+
+    #[test]
+    fn test_nested_folder_eml_removed() {
+        let vault = tempfile::tempdir().unwrap();
+        let vault_path = vault.path().to_str().unwrap();
+        let raw = b"From: a@example.com\r\nSubject: Sent\r\n\r\nbody";
+        let (email, added) = store_eml(vault_path, "acct", "[Gmail]/Sent Mail", raw).unwrap();
+        assert!(added);
+        assert_eq!(email.id, "[Gmail]/Sent Mail_1");
+        let dir = vault.path().join("Mailbox/acct");
+        assert!(dir.join("[Gmail]_Sent Mail_1.eml").exists());
+
+        remove_cached(vault_path, "acct", &email.id, email.uid).unwrap();
+        assert!(load_existing_emails(vault_path, "acct").unwrap().is_empty());
+        let left: Vec<_> = fs::read_dir(&dir).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(left, vec!["index.json"]);
+    }
+
+    #[test]
+    fn test_eml_import_dry_run() {
+        let vault = tempfile::tempdir().unwrap();
+        let vault_path = vault.path().to_str().unwrap();
+        let files = tempfile::tempdir().unwrap();
+        let mut paths = Vec::new();
+        for (name, id) in [("a.eml", "a@x"), ("b.eml", "b@x"), ("b-copy.eml", "b@x")] {
+            let path = files.path().join(name);
+            fs::write(&path, format!("From: a@example.com\r\nMessage-ID: <{id}>\r\nSubject: Hi\r\n\r\nbody")).unwrap();
+            paths.push(path.to_string_lossy().to_string());
+        }
+        store_eml(vault_path, "acct", "INBOX", &fs::read(&paths[0]).unwrap()).unwrap();
+        let before = fs::read(vault.path().join("Mailbox/acct/index.json")).unwrap();
+
+        paths.push(files.path().join("missing.eml").to_string_lossy().to_string());
+        let report = import_eml_paths(vault_path, "acct", "INBOX", paths.clone(), true);
+        assert!(report.dry_run);
+        assert_eq!(report.imported.iter().map(|e| e.id.as_str()).collect::<Vec<_>>(), vec!["b@x"]);
+        assert_eq!((report.duplicates, report.failed.len()), (2, 1));
+        assert_eq!(fs::read(vault.path().join("Mailbox/acct/index.json")).unwrap(), before);
+        assert!(!vault.path().join("Mailbox/acct/b@x.eml").exists());
+
+        let report = import_eml_paths(vault_path, "acct", "INBOX", paths, false);
+        assert_eq!((report.imported.len(), report.duplicates), (1, 2));
+        assert!(vault.path().join("Mailbox/acct/b@x.eml").exists());
+    }
+
+    #[test]
+    fn test_sender_name() {
+        assert_eq!(sender_name("\"Alice Chen\" <alice@example.com>"), "Alice Chen");
+        assert_eq!(sender_name("Bob <bob@example.com>"), "Bob");
+        assert_eq!(sender_name("<carol@example.com>"), "carol@example.com");
+        assert_eq!(sender_name("dave@example.com"), "dave@example.com");
+    }
 }
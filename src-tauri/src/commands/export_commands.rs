@@ -0,0 +1,460 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use walkdir::WalkDir;
+
+use crate::services::automations::glob_match;
+use crate::services::diary_book::{self, BookReport};
+use crate::services::periodic::Periodic;
+use crate::services::{durable, embeds, secrets};
+
+/// Profiles, edited from settings
+const SETTINGS_FILE: &str = ".lifeos/export-profiles.yaml";
+/// Last run per profile, kept apart so saving settings never loses it
+const STATE_FILE: &str = ".lifeos/export-state.yaml";
+/// Written into folder targets: the files a previous run put there, so a
+/// refresh only ever removes its own output
+const FOLDER_MANIFEST: &str = ".lifeos-export.json";
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ExportSettings {
+    #[serde(default)]
+    pub profiles: Vec<ExportProfile>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExportProfile {
+    /// e.g. "share with coach"
+    pub name: String,
+    /// Vault-relative globs; dot-folders (.lifeos, .git) are never exported
+    #[serde(default = "default_include")]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Absolute path outside the vault: a folder, or the .zip to write
+    pub target: String,
+    /// "folder" | "zip"
+    #[serde(default = "default_format")]
+    pub format: String,
+    /// Zip only: AES-256 encrypts the archive. Saved as `keychain:<name>`;
+    /// a plaintext password is moved into the Keychain on save
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Write notes with their `![[note#section]]` embeds expanded, so the
+    /// copy reads on its own
+    #[serde(default)]
+    pub expand_embeds: bool,
+    /// Refresh automatically this often; 0 = only when run by hand
+    #[serde(default)]
+    pub interval_hours: u64,
+}
+
+fn default_include() -> Vec<String> {
+    vec!["**".to_string()]
+}
+fn default_format() -> String {
+    "folder".to_string()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ExportReport {
+    pub profile: String,
+    pub target: String,
+    /// Files selected by the profile
+    pub files: usize,
+    /// Folder targets: files copied because they were new or changed
+    pub copied: usize,
+    /// Folder targets: earlier output no longer selected
+    pub removed: usize,
+    pub encrypted: bool,
+    /// RFC 3339
+    pub finished: String,
+}
+
+// Only one scheduler loop, bound to the open vault
+static SCHEDULER: Periodic = Periodic::new();
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn get_export_profiles(vault_path: String) -> ExportSettings {
+    load_settings(Path::new(&vault_path))
+}
+
+#[tauri::command]
+pub fn save_export_profiles(vault_path: String, mut settings: ExportSettings) -> Result<ExportSettings, String> {
+    let vault = PathBuf::from(&vault_path);
+    let mut names = BTreeSet::new();
+    for profile in &settings.profiles {
+        if profile.name.trim().is_empty() {
+            return Err(tr!("Profile name is required"));
+        }
+        if !names.insert(profile.name.trim()) {
+            return Err(tr!("Duplicate profile: {}", profile.name));
+        }
+        target_path(&vault, profile)?;
+    }
+    // The settings file syncs with the vault, so it never holds a password
+    for profile in &mut settings.profiles {
+        keep_password_in_keychain(&vault, profile)?;
+    }
+    let yaml = serde_yaml::to_string(&settings).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    write_file(&vault.join(SETTINGS_FILE), yaml.as_bytes())?;
+    Ok(settings)
+}
+
+/// Produce or refresh the profile's copy now
+#[tauri::command]
+pub async fn run_export(vault_path: String, name: String) -> Result<ExportReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let vault = PathBuf::from(&vault_path);
+        let profile = load_settings(&vault)
+            .profiles
+            .into_iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| tr!("Export profile not found: {}", name))?;
+        export(&vault, &profile)
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Compile `year`'s diary into one book at `dest` (absolute; `.epub`, or
+/// `.html` to print to PDF); see `services::diary_book`
+#[tauri::command]
+pub async fn export_diary_book(vault_path: String, year: i32, dest: String) -> Result<BookReport, String> {
+    tokio::task::spawn_blocking(move || diary_book::export(Path::new(&vault_path), year, Path::new(&dest)))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Last report per profile name
+#[tauri::command]
+pub fn get_export_status(vault_path: String) -> BTreeMap<String, ExportReport> {
+    load_state(Path::new(&vault_path))
+}
+
+/// Refresh profiles with an `interval_hours` once they are due; settings and
+/// last runs are re-read each minute
+#[tauri::command]
+pub fn start_export_scheduler(vault_path: String) {
+    let vault = PathBuf::from(vault_path);
+    SCHEDULER.start(Duration::from_secs(60), move || {
+        let state = load_state(&vault);
+        for profile in load_settings(&vault).profiles.iter().filter(|p| is_due(p, state.get(&p.name), Local::now())) {
+            if let Err(e) = export(&vault, profile) {
+                println!("[WARN] export '{}' failed: {e}", profile.name);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn stop_export_scheduler() {
+    SCHEDULER.stop();
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn export(vault: &Path, profile: &ExportProfile) -> Result<ExportReport, String> {
+    let target = target_path(vault, profile)?;
+    let files = select(vault, profile);
+    let mut report = ExportReport { profile: profile.name.clone(), target: target.to_string_lossy().to_string(), files: files.len(), ..Default::default() };
+    match profile.format.as_str() {
+        "folder" => (report.copied, report.removed) = export_folder(vault, &files, &target, profile.expand_embeds)?,
+        "zip" => {
+            let password = profile.password.as_deref().map(str::trim).filter(|p| !p.is_empty()).map(secrets::resolve).transpose()?;
+            report.encrypted = password.is_some();
+            export_zip(vault, &files, &target, password.as_deref(), profile.expand_embeds)?;
+        }
+        other => return Err(tr!("Unknown export format: {}", other)),
+    }
+    report.finished = Local::now().to_rfc3339();
+
+    let mut state = load_state(vault);
+    state.insert(profile.name.clone(), report.clone());
+    let yaml = serde_yaml::to_string(&state).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    write_file(&vault.join(STATE_FILE), yaml.as_bytes())?;
+    Ok(report)
+}
+
+/// Vault-relative paths matched by the profile, sorted
+fn select(vault: &Path, profile: &ExportProfile) -> Vec<String> {
+    WalkDir::new(vault)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| Some(e.path().strip_prefix(vault).ok()?.to_string_lossy().replace('\\', "/")))
+        .filter(|rel| is_selected(profile, rel))
+        .collect()
+}
+
+fn is_selected(profile: &ExportProfile, rel: &str) -> bool {
+    profile.include.iter().any(|p| glob_match(p, rel)) && !profile.exclude.iter().any(|p| glob_match(p, rel))
+}
+
+/// Copy new or changed files, then drop earlier output that is no longer
+/// selected. Returns (copied, removed).
+fn export_folder(vault: &Path, files: &[String], target: &Path, expand_embeds: bool) -> Result<(usize, usize), String> {
+    fs::create_dir_all(target).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    let manifest_path = target.join(FOLDER_MANIFEST);
+    let previous: Vec<String> = fs::read_to_string(&manifest_path).ok().and_then(|raw| serde_json::from_str(&raw).ok()).unwrap_or_default();
+
+    let mut copied = 0;
+    for rel in files {
+        let (src, dest) = (vault.join(rel), target.join(rel));
+        // An expanded note changes with the notes it embeds, so compare content
+        let expanded = expanded_note(vault, rel, expand_embeds)?;
+        let unchanged = match &expanded {
+            Some(text) => fs::read(&dest).is_ok_and(|old| old == text.as_bytes()),
+            None => same_file(&src, &dest),
+        };
+        if unchanged {
+            continue;
+        }
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+        }
+        match expanded {
+            Some(text) => fs::write(&dest, text).map_err(|e| tr!("write_file failed: {}", e))?,
+            None => {
+                fs::copy(&src, &dest).map_err(|e| tr!("Failed to copy: {}", e))?;
+            }
+        }
+        copied += 1;
+    }
+    let keep: BTreeSet<&String> = files.iter().collect();
+    let mut removed = 0;
+    for rel in previous.iter().filter(|rel| !keep.contains(rel)) {
+        if fs::remove_file(target.join(rel)).is_ok() {
+            removed += 1;
+        }
+    }
+
+    let json = serde_json::to_string_pretty(files).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    write_file(&manifest_path, json.as_bytes())?;
+    Ok((copied, removed))
+}
+
+/// The note's text with embeds expanded, when the profile asks for it
+fn expanded_note(vault: &Path, rel: &str, expand_embeds: bool) -> Result<Option<String>, String> {
+    if !expand_embeds || !rel.ends_with(".md") {
+        return Ok(None);
+    }
+    embeds::resolve(vault, &vault.join(rel)).map(|r| Some(r.content))
+}
+
+/// Same size and the copy is not older than the source
+fn same_file(src: &Path, dest: &Path) -> bool {
+    match (fs::metadata(src), fs::metadata(dest)) {
+        (Ok(a), Ok(b)) => a.len() == b.len() && matches!((a.modified(), b.modified()), (Ok(ma), Ok(mb)) if mb >= ma),
+        _ => false,
+    }
+}
+
+/// Written beside the target and renamed into place, so a reader never sees
+/// a half-written archive
+fn export_zip(vault: &Path, files: &[String], target: &Path, password: Option<&str>, expand_embeds: bool) -> Result<(), String> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let partial = target.with_extension("zip.partial");
+    let file = fs::File::create(&partial).map_err(|e| tr!("write_file failed: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let options = match password {
+        Some(password) => options.with_aes_encryption(zip::AesMode::Aes256, password),
+        None => options,
+    };
+    let result = files.iter().try_for_each(|rel| {
+        let bytes = match expanded_note(vault, rel, expand_embeds)? {
+            Some(text) => text.into_bytes(),
+            None => fs::read(vault.join(rel)).map_err(|e| tr!("Failed to read: {}", e))?,
+        };
+        zip.start_file(rel.as_str(), options).map_err(|e| tr!("Failed to write archive: {}", e))?;
+        zip.write_all(&bytes).map_err(|e| tr!("Failed to write archive: {}", e))
+    });
+    let result = result.and_then(|_| zip.finish().map(|_| ()).map_err(|e| tr!("Failed to write archive: {}", e)));
+    if let Err(e) = result {
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+    fs::rename(&partial, target).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+/// Absolute and outside the vault, so exports never end up in the vault
+/// (or in its sync) themselves
+fn target_path(vault: &Path, profile: &ExportProfile) -> Result<PathBuf, String> {
+    let target = PathBuf::from(profile.target.trim());
+    if !target.is_absolute() {
+        return Err(tr!("Export target must be an absolute path: {}", profile.target));
+    }
+    let vault = vault.canonicalize().unwrap_or_else(|_| vault.to_path_buf());
+    if nearest_existing(&target).starts_with(&vault) {
+        return Err(tr!("Export target must be outside the vault: {}", profile.target));
+    }
+    Ok(target)
+}
+
+/// Canonical form of the deepest existing ancestor, with the rest re-appended
+fn nearest_existing(path: &Path) -> PathBuf {
+    let mut missing = Vec::new();
+    let mut current = path;
+    loop {
+        if let Ok(found) = current.canonicalize() {
+            return missing.iter().rev().fold(found, |acc: PathBuf, part| acc.join(part));
+        }
+        match (current.parent(), current.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name.to_os_string());
+                current = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+/// Replace a plaintext password with a `keychain:` reference to it
+fn keep_password_in_keychain(vault: &Path, profile: &mut ExportProfile) -> Result<(), String> {
+    let Some(password) = profile.password.as_deref().filter(|p| !p.trim().is_empty()) else {
+        profile.password = None;
+        return Ok(());
+    };
+    if password.trim().starts_with(secrets::REFERENCE_PREFIX) {
+        return Ok(());
+    }
+    // Profile names may be any text; secret names are ASCII
+    let digest = Sha256::digest(profile.name.trim().as_bytes());
+    let name = format!("export-{}", digest[..6].iter().map(|b| format!("{b:02x}")).collect::<String>());
+    secrets::store(vault, &name, password, Some(&tr!("Export profile {}", profile.name.trim())))
+        .map_err(|e| tr!("Export password must be kept in the Keychain: {}", e))?;
+    profile.password = Some(format!("{}{name}", secrets::REFERENCE_PREFIX));
+    Ok(())
+}
+
+fn is_due(profile: &ExportProfile, last: Option<&ExportReport>, now: DateTime<Local>) -> bool {
+    if profile.interval_hours == 0 {
+        return false;
+    }
+    let last_run = last.and_then(|r| DateTime::parse_from_rfc3339(&r.finished).ok());
+    last_run.is_none_or(|t| now.signed_duration_since(t).num_minutes() >= profile.interval_hours as i64 * 60)
+}
+
+fn load_settings(vault: &Path) -> ExportSettings {
+    fs::read_to_string(vault.join(SETTINGS_FILE))
+        .ok()
+        .and_then(|raw| serde_yaml::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn load_state(vault: &Path) -> BTreeMap<String, ExportReport> {
+    fs::read_to_string(vault.join(STATE_FILE))
+        .ok()
+        .and_then(|raw| serde_yaml::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    durable::write(path, bytes).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(target: &Path) -> ExportProfile {
+        serde_yaml::from_str(&format!("name: 教练\ninclude: [\"planning/**\"]\nexclude: [\"**/private-*\"]\ntarget: {:?}", target)).unwrap()
+    }
+
+    #[test]
+    fn test_folder_export_refresh() {
+        let vault = tempfile::tempdir().unwrap();
+        let out = tempfile::tempdir().unwrap();
+        for rel in ["planning/goals/a.md", "planning/reviews/w1.md", "planning/private-b.md", "diary/2025/x.md", ".lifeos/connectors.yaml"] {
+            write_file(&vault.path().join(rel), b"x").unwrap();
+        }
+        let mut p = profile(&out.path().join("coach"));
+        assert_eq!(select(vault.path(), &p), vec!["planning/goals/a.md", "planning/reviews/w1.md"]);
+
+        let first = export(vault.path(), &p).unwrap();
+        assert_eq!((first.files, first.copied, first.removed), (2, 2, 0));
+        assert_eq!(export(vault.path(), &p).unwrap().copied, 0);
+
+        // Output from earlier runs goes; unrelated files in the target stay
+        write_file(&out.path().join("coach/notes-from-coach.md"), b"hi").unwrap();
+        p.include = vec!["planning/reviews/**".into()];
+        let third = export(vault.path(), &p).unwrap();
+        assert_eq!((third.files, third.removed), (1, 1));
+        assert!(!out.path().join("coach/planning/goals/a.md").exists());
+        assert!(out.path().join("coach/notes-from-coach.md").exists());
+        assert!(load_state(vault.path()).contains_key("教练"));
+
+        // Expanded notes refresh when an embedded note changes
+        write_file(&vault.path().join("planning/reviews/w1.md"), b"review: ![[a]]").unwrap();
+        p.expand_embeds = true;
+        assert_eq!(export(vault.path(), &p).unwrap().copied, 1);
+        assert_eq!(export(vault.path(), &p).unwrap().copied, 0);
+        write_file(&vault.path().join("planning/goals/a.md"), b"goal v2").unwrap();
+        assert_eq!(export(vault.path(), &p).unwrap().copied, 1);
+        assert_eq!(fs::read_to_string(out.path().join("coach/planning/reviews/w1.md")).unwrap(), "review: goal v2");
+    }
+
+    #[test]
+    fn test_targets_and_schedule() {
+        let vault = tempfile::tempdir().unwrap();
+        assert!(target_path(vault.path(), &profile(&vault.path().join("export"))).is_err());
+        assert!(target_path(vault.path(), &profile(Path::new("relative/dir"))).is_err());
+
+        let mut p = profile(Path::new("/tmp/x"));
+        let now = Local::now();
+        assert!(!is_due(&p, None, now));
+        p.interval_hours = 24;
+        assert!(is_due(&p, None, now));
+        let recent = ExportReport { finished: (now - chrono::Duration::hours(2)).to_rfc3339(), ..Default::default() };
+        assert!(!is_due(&p, Some(&recent), now));
+    }
+
+    #[test]
+    fn test_password_never_saved_in_plaintext() {
+        let vault = tempfile::tempdir().unwrap();
+        let vault_path = vault.path().to_string_lossy().to_string();
+        let mut p = profile(Path::new("/tmp/coach.zip"));
+        p.format = "zip".into();
+
+        p.password = Some("keychain:coach-zip".into());
+        let saved = save_export_profiles(vault_path.clone(), ExportSettings { profiles: vec![p.clone()] }).unwrap();
+        assert_eq!(saved.profiles[0].password.as_deref(), Some("keychain:coach-zip"));
+
+        // Without a Keychain the plaintext is refused rather than written
+        #[cfg(not(target_os = "macos"))]
+        {
+            p.password = Some("hunter2".into());
+            assert!(save_export_profiles(vault_path, ExportSettings { profiles: vec![p] }).is_err());
+            let raw = fs::read_to_string(vault.path().join(SETTINGS_FILE)).unwrap();
+            assert!(raw.contains("keychain:coach-zip") && !raw.contains("hunter2"));
+        }
+    }
+}
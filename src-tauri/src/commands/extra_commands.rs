@@ -1,10 +1,34 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
+#[cfg(desktop)]
+use std::path::Path;
 use std::path::PathBuf;
+#[cfg(desktop)]
 use std::process::Command;
+#[cfg(target_os = "macos")]
 use tokio::process::Command as AsyncCommand;
 use walkdir::WalkDir;
 
+#[cfg(target_os = "macos")]
+use super::background_commands::LOGIN_AGENT_ID;
+#[cfg(target_os = "macos")]
+use super::platform_commands::apple_script_error;
+#[cfg(desktop)]
+use crate::services::{self, audit, git};
+#[cfg(not(target_os = "macos"))]
+use crate::services::unsupported;
+
+/// `program` of a task that runs a LifeOS job through the `lifeos` CLI;
+/// the job name is the task's first argument
+#[cfg(target_os = "macos")]
+const INTERNAL_PROGRAM: &str = "internal";
+/// Internal jobs: name → CLI arguments
+const INTERNAL_JOBS: [(&str, &[&str]); 2] = [
+    // Two-way sync with the remote configured under `sync:` in connectors.yaml
+    ("backup", &["sync"]),
+    ("mail-sync", &["mail", "sync"]),
+];
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Types
 // ─────────────────────────────────────────────────────────────────────────────
@@ -61,39 +85,39 @@ pub struct AppleNotesResult {
 // System: Open in Finder
 // ─────────────────────────────────────────────────────────────────────────────
 
+#[cfg(target_os = "macos")]
 #[tauri::command]
 pub fn open_in_finder(path: String) -> Result<(), String> {
     Command::new("open")
         .arg(&path)
         .spawn()
-        .map_err(|e| format!("Failed to open in Finder: {e}"))?;
+        .map_err(|e| tr!("Failed to open in Finder: {}", e))?;
     Ok(())
 }
 
+/// Windows/Linux: hand the path to the desktop's default file manager
+#[cfg(all(desktop, not(target_os = "macos")))]
+#[tauri::command]
+pub fn open_in_finder(path: String) -> Result<(), String> {
+    open::that(&path).map_err(|e| tr!("Failed to open in file manager: {}", e))
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Git Scanner
 // ─────────────────────────────────────────────────────────────────────────────
 
+#[cfg(desktop)]
 #[tauri::command]
 pub fn scan_git_repos(root: String, max_depth: u32) -> Result<Vec<GitRepo>, String> {
     let root_path = PathBuf::from(&root);
     if !root_path.exists() {
-        return Err(format!("Path does not exist: {}", root));
+        return Err(tr!("Path does not exist: {}", root));
     }
 
-    let depth = max_depth as usize;
     let mut repos = Vec::new();
 
-    for entry in WalkDir::new(&root_path)
-        .max_depth(depth)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_dir() && e.file_name() == ".git")
-    {
-        let repo_path = match entry.path().parent() {
-            Some(p) => p.to_string_lossy().to_string(),
-            None => continue,
-        };
+    for repo in git::find_repos(&root_path, max_depth as usize) {
+        let repo_path = repo.to_string_lossy().to_string();
 
         let name = PathBuf::from(&repo_path)
             .file_name()
@@ -145,6 +169,24 @@ pub fn scan_git_repos(root: String, max_depth: u32) -> Result<Vec<GitRepo>, Stri
     Ok(repos)
 }
 
+/// TODO/FIXME/HACK comments (or `patterns`) in the repositories at or under
+/// `root`, skipping .gitignore'd files, each with its line's blame author
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn scan_code_todos(root: String, patterns: Option<Vec<String>>, max_depth: Option<u32>) -> Result<Vec<git::CodeTodo>, String> {
+    let root_path = PathBuf::from(&root);
+    if !root_path.exists() {
+        return Err(tr!("Path does not exist: {}", root));
+    }
+    tokio::task::spawn_blocking(move || {
+        let patterns: Vec<String> = patterns.unwrap_or_default().into_iter().map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect();
+        let markers: Vec<&str> = if patterns.is_empty() { git::DEFAULT_TODO_MARKERS.to_vec() } else { patterns.iter().map(String::as_str).collect() };
+        git::scan_todos(&root_path, &markers, max_depth.unwrap_or(5) as usize)
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Skills Manager
 // ─────────────────────────────────────────────────────────────────────────────
@@ -265,17 +307,21 @@ pub fn list_skill_files(paths: Vec<String>) -> Result<Vec<SkillFile>, String> {
 // Shell command runner (for Claude AI analysis etc.)
 // ─────────────────────────────────────────────────────────────────────────────
 
+#[cfg(desktop)]
 #[tauri::command]
 pub async fn run_shell_command(command: String, args: Vec<String>) -> Result<String, String> {
     let output = tokio::process::Command::new(&command)
         .args(&args)
         .output()
         .await
-        .map_err(|e| format!("Failed to run '{}': {e}", command))?;
+        .map_err(|e| tr!("Failed to run '{}': {}", command, e))?;
+    if let Some(vault) = services::configured_vault() {
+        audit::ran(Path::new(&vault), &command);
+    }
 
     if output.status.success() {
         String::from_utf8(output.stdout)
-            .map_err(|e| format!("Invalid UTF-8 output: {e}"))
+            .map_err(|e| tr!("Invalid UTF-8 output: {}", e))
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
@@ -287,17 +333,18 @@ pub async fn run_shell_command(command: String, args: Vec<String>) -> Result<Str
 // macOS Shortcuts
 // ─────────────────────────────────────────────────────────────────────────────
 
+#[cfg(target_os = "macos")]
 #[tauri::command]
 pub async fn run_shortcut(name: String) -> Result<String, String> {
     let output = tokio::process::Command::new("shortcuts")
         .args(["run", &name, "--output-format", "json"])
         .output()
         .await
-        .map_err(|e| format!("Failed to run shortcut '{}': {e}", name))?;
+        .map_err(|e| tr!("Failed to run shortcut '{}': {}", name, e))?;
 
     if output.status.success() {
         let stdout = String::from_utf8(output.stdout)
-            .map_err(|e| format!("Invalid UTF-8 output: {e}"))?;
+            .map_err(|e| tr!("Invalid UTF-8 output: {}", e))?;
         // Shortcuts might return empty or newlines for some shortcuts
         if stdout.trim().is_empty() {
             return Ok("{}".to_string());
@@ -314,14 +361,16 @@ pub async fn run_shortcut(name: String) -> Result<String, String> {
 // macOS launchd scheduler
 // ─────────────────────────────────────────────────────────────────────────────
 
+#[cfg(target_os = "macos")]
 #[tauri::command]
 pub fn create_launchd_task(task: LaunchdTask) -> Result<(), String> {
     let home = std::env::var("HOME").map_err(|e| e.to_string())?;
     let agents_dir = format!("{}/Library/LaunchAgents", home);
     let plist_path = format!("{}/com.lifeos.{}.plist", agents_dir, task.id);
 
-    let args_xml: String = task.args.iter()
-        .map(|a| format!("        <string>{}</string>\n", a))
+    let args_xml: String = program_arguments(&task)?
+        .iter()
+        .map(|a| format!("        <string>{}</string>\n", xml_escape(a)))
         .collect();
 
     let plist = format!(
@@ -333,7 +382,6 @@ pub fn create_launchd_task(task: LaunchdTask) -> Result<(), String> {
     <string>com.lifeos.{id}</string>
     <key>ProgramArguments</key>
     <array>
-        <string>{program}</string>
 {args}    </array>
     <key>StartInterval</key>
     <integer>{interval}</integer>
@@ -342,24 +390,24 @@ pub fn create_launchd_task(task: LaunchdTask) -> Result<(), String> {
 </dict>
 </plist>"#,
         id = task.id,
-        program = task.program,
         args = args_xml,
         interval = task.interval_seconds,
     );
 
     fs::create_dir_all(&agents_dir).map_err(|e| e.to_string())?;
-    fs::write(&plist_path, plist).map_err(|e| format!("Failed to write plist: {e}"))?;
+    fs::write(&plist_path, plist).map_err(|e| tr!("Failed to write plist: {}", e))?;
 
     if task.enabled {
         Command::new("launchctl")
             .args(["load", &plist_path])
             .output()
-            .map_err(|e| format!("Failed to load task: {e}"))?;
+            .map_err(|e| tr!("Failed to load task: {}", e))?;
     }
 
     Ok(())
 }
 
+#[cfg(target_os = "macos")]
 #[tauri::command]
 pub fn list_launchd_tasks() -> Result<Vec<LaunchdTask>, String> {
     let home = std::env::var("HOME").map_err(|e| e.to_string())?;
@@ -389,6 +437,10 @@ pub fn list_launchd_tasks() -> Result<Vec<LaunchdTask>, String> {
         let id = stem.strip_prefix("com.lifeos.")
             .unwrap_or(&stem)
             .to_string();
+        // The launch-at-login item isn't a scheduled task
+        if id == LOGIN_AGENT_ID {
+            continue;
+        }
 
         let label = stem.clone();
 
@@ -400,12 +452,16 @@ pub fn list_launchd_tasks() -> Result<Vec<LaunchdTask>, String> {
             .map(|o| o.status.success())
             .unwrap_or(false);
 
+        let plist = fs::read_to_string(&path).unwrap_or_default();
+        let (program, args) = split_program(plist_strings(&plist, "ProgramArguments"));
+        let interval_seconds = plist_integer(&plist, "StartInterval").unwrap_or(3600);
+
         tasks.push(LaunchdTask {
             id,
             label,
-            program: String::new(),
-            args: vec![],
-            interval_seconds: 3600,
+            program,
+            args,
+            interval_seconds,
             enabled,
         });
     }
@@ -413,6 +469,7 @@ pub fn list_launchd_tasks() -> Result<Vec<LaunchdTask>, String> {
     Ok(tasks)
 }
 
+#[cfg(target_os = "macos")]
 #[tauri::command]
 pub fn delete_launchd_task(id: String) -> Result<(), String> {
     let home = std::env::var("HOME").map_err(|e| e.to_string())?;
@@ -425,23 +482,101 @@ pub fn delete_launchd_task(id: String) -> Result<(), String> {
 
     if PathBuf::from(&plist_path).exists() {
         fs::remove_file(&plist_path)
-            .map_err(|e| format!("Failed to delete plist: {e}"))?;
+            .map_err(|e| tr!("Failed to delete plist: {}", e))?;
     }
 
     Ok(())
 }
 
+/// Names accepted as the first argument of an `internal` task
+#[tauri::command]
+pub fn list_internal_jobs() -> Vec<String> {
+    INTERNAL_JOBS.iter().map(|(name, _)| name.to_string()).collect()
+}
+
+/// What goes in ProgramArguments: `internal` tasks become the `lifeos` CLI
+/// next to the app binary with the job's arguments
+#[cfg(target_os = "macos")]
+fn program_arguments(task: &LaunchdTask) -> Result<Vec<String>, String> {
+    if task.program != INTERNAL_PROGRAM {
+        return Ok(std::iter::once(task.program.clone()).chain(task.args.iter().cloned()).collect());
+    }
+    let job = task.args.first().map(String::as_str).unwrap_or_default();
+    let (_, cli_args) = INTERNAL_JOBS.iter().find(|(name, _)| *name == job).ok_or_else(|| tr!("Unknown internal job: {}", job))?;
+    let cli = std::env::current_exe()
+        .map_err(|e| e.to_string())?
+        .with_file_name("lifeos");
+    if !cli.exists() {
+        return Err(tr!("lifeos CLI not found: {}", cli.display()));
+    }
+    Ok(std::iter::once(cli.to_string_lossy().to_string())
+        .chain(cli_args.iter().map(|a| a.to_string()))
+        .chain(task.args.iter().skip(1).cloned())
+        .collect())
+}
+
+/// Inverse of `program_arguments`, so internal tasks list as `internal <job>`
+#[cfg(target_os = "macos")]
+fn split_program(arguments: Vec<String>) -> (String, Vec<String>) {
+    let mut arguments = arguments.into_iter();
+    let program = arguments.next().unwrap_or_default();
+    let rest: Vec<String> = arguments.collect();
+    if PathBuf::from(&program).file_stem().is_some_and(|s| s == "lifeos") {
+        for (name, cli_args) in INTERNAL_JOBS {
+            if rest.len() >= cli_args.len() && rest.iter().zip(cli_args).all(|(a, b)| a == b) {
+                let args = std::iter::once(name.to_string()).chain(rest[cli_args.len()..].iter().cloned()).collect();
+                return (INTERNAL_PROGRAM.to_string(), args);
+            }
+        }
+    }
+    (program, rest)
+}
+
+/// `<string>` values of the array under `key`
+#[cfg(target_os = "macos")]
+fn plist_strings(plist: &str, key: &str) -> Vec<String> {
+    let Some(after) = plist.split(&format!("<key>{key}</key>")).nth(1) else { return Vec::new() };
+    let array = after.split("</array>").next().unwrap_or_default();
+    array
+        .split("<string>")
+        .skip(1)
+        .filter_map(|s| s.split_once("</string>"))
+        .map(|(value, _)| xml_unescape(value))
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn plist_integer(plist: &str, key: &str) -> Option<u64> {
+    let after = plist.split(&format!("<key>{key}</key>")).nth(1)?;
+    after.split_once("<integer>")?.1.split_once("</integer>")?.0.trim().parse().ok()
+}
+
+#[cfg(target_os = "macos")]
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(target_os = "macos")]
+fn xml_unescape(s: &str) -> String {
+    s.replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&")
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Apple Notes (备忘录)
 // ─────────────────────────────────────────────────────────────────────────────
 
 // 缓存备忘录数据，避免每次都调用 AppleScript
+#[cfg(target_os = "macos")]
 use std::sync::Mutex;
+#[cfg(target_os = "macos")]
 use once_cell::sync::Lazy;
 
+#[cfg(target_os = "macos")]
 static NOTES_CACHE: Lazy<Mutex<Vec<AppleNote>>> = Lazy::new(|| Mutex::new(Vec::new()));
+#[cfg(target_os = "macos")]
 static CACHE_TIMESTAMP: Lazy<Mutex<u64>> = Lazy::new(|| Mutex::new(0));
 
+#[cfg(target_os = "macos")]
 fn get_cache_age() -> u64 {
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -451,11 +586,13 @@ fn get_cache_age() -> u64 {
     now.saturating_sub(timestamp)
 }
 
+#[cfg(target_os = "macos")]
 fn invalidate_cache() {
     NOTES_CACHE.lock().unwrap().clear();
     *CACHE_TIMESTAMP.lock().unwrap() = 0;
 }
 
+#[cfg(target_os = "macos")]
 #[tauri::command]
 pub async fn get_apple_notes(query: Option<String>, offset: Option<usize>, limit: Option<usize>) -> Result<AppleNotesResult, String> {
     let query = query.unwrap_or_default().to_lowercase();
@@ -509,7 +646,8 @@ pub async fn get_apple_notes(query: Option<String>, offset: Option<usize>, limit
     })
 }
 
-fn load_notes_from_apple() -> Result<Vec<AppleNote>, String> {
+#[cfg(target_os = "macos")]
+pub(crate) fn load_notes_from_apple() -> Result<Vec<AppleNote>, String> {
     // 直接使用 osascript，避免 Python 开销
     let script = r#"
 tell application "Notes"
@@ -533,11 +671,10 @@ end tell
         .arg("-e")
         .arg(script)
         .output()
-        .map_err(|e| format!("Failed to run AppleScript: {}", e))?;
+        .map_err(|e| tr!("Failed to run AppleScript: {}", e))?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("AppleScript error: {}", stderr));
+        return Err(apple_script_error("Notes", &String::from_utf8_lossy(&output.stderr)));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -577,6 +714,7 @@ end tell
     Ok(notes)
 }
 
+#[cfg(target_os = "macos")]
 fn parse_note_item(item: &str) -> Option<AppleNote> {
     // 移除大括号
     let item = item.trim().trim_start_matches('{').trim_end_matches('}');
@@ -598,6 +736,7 @@ fn parse_note_item(item: &str) -> Option<AppleNote> {
 }
 
 /// Create a new Apple Note
+#[cfg(target_os = "macos")]
 #[tauri::command]
 pub async fn create_apple_note(folder: String, title: String, body: String) -> Result<String, String> {
     invalidate_cache(); // 使缓存失效
@@ -622,16 +761,17 @@ end tell"#,
         .arg(&script)
         .output()
         .await
-        .map_err(|e| format!("Failed to run AppleScript: {}", e))?;
+        .map_err(|e| tr!("Failed to run AppleScript: {}", e))?;
 
     if output.status.success() {
         Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
     } else {
-        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+        Err(apple_script_error("Notes", &String::from_utf8_lossy(&output.stderr)))
     }
 }
 
 /// Update an existing Apple Note
+#[cfg(target_os = "macos")]
 #[tauri::command]
 pub async fn update_apple_note(note_id: String, body: String) -> Result<(), String> {
     invalidate_cache(); // 使缓存失效
@@ -652,11 +792,94 @@ end tell"#,
         .arg(&script)
         .output()
         .await
-        .map_err(|e| format!("Failed to run AppleScript: {}", e))?;
+        .map_err(|e| tr!("Failed to run AppleScript: {}", e))?;
 
     if output.status.success() {
         Ok(())
     } else {
-        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+        Err(apple_script_error("Notes", &String::from_utf8_lossy(&output.stderr)))
     }
 }
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Unsupported-platform fallbacks
+// ─────────────────────────────────────────────────────────────────────────────
+// Same signatures as the real commands so the invoke handler and frontend
+// argument names stay identical; they only report that the feature is missing.
+
+#[cfg(mobile)]
+#[tauri::command]
+pub fn open_in_finder(path: String) -> Result<(), String> {
+    let _ = path;
+    Err(unsupported("open_in_finder"))
+}
+
+#[cfg(mobile)]
+#[tauri::command]
+pub fn scan_git_repos(root: String, max_depth: u32) -> Result<Vec<GitRepo>, String> {
+    let _ = (root, max_depth);
+    Err(unsupported("scan_git_repos"))
+}
+
+#[cfg(mobile)]
+#[tauri::command]
+pub async fn scan_code_todos(root: String, patterns: Option<Vec<String>>, max_depth: Option<u32>) -> Result<Vec<crate::services::git::CodeTodo>, String> {
+    let _ = (root, patterns, max_depth);
+    Err(unsupported("scan_code_todos"))
+}
+
+#[cfg(mobile)]
+#[tauri::command]
+pub async fn run_shell_command(command: String, args: Vec<String>) -> Result<String, String> {
+    let _ = (command, args);
+    Err(unsupported("run_shell_command"))
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub async fn run_shortcut(name: String) -> Result<String, String> {
+    let _ = name;
+    Err(unsupported("run_shortcut"))
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub fn create_launchd_task(task: LaunchdTask) -> Result<(), String> {
+    let _ = task;
+    Err(unsupported("create_launchd_task"))
+}
+
+/// Nothing can be scheduled here, so there is nothing to list
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub fn list_launchd_tasks() -> Result<Vec<LaunchdTask>, String> {
+    Ok(Vec::new())
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub fn delete_launchd_task(id: String) -> Result<(), String> {
+    let _ = id;
+    Err(unsupported("delete_launchd_task"))
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub async fn get_apple_notes(query: Option<String>, offset: Option<usize>, limit: Option<usize>) -> Result<AppleNotesResult, String> {
+    let _ = (query, offset, limit);
+    Err(unsupported("get_apple_notes"))
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub async fn create_apple_note(folder: String, title: String, body: String) -> Result<String, String> {
+    let _ = (folder, title, body);
+    Err(unsupported("create_apple_note"))
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub async fn update_apple_note(note_id: String, body: String) -> Result<(), String> {
+    let _ = (note_id, body);
+    Err(unsupported("update_apple_note"))
+}
@@ -0,0 +1,640 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Transactions live in life/finance/transactions/{YYYY}/{YYYY-MM}.csv
+const FINANCE_DIR: &str = "life/finance";
+const RULES_FILE: &str = "life/finance/rules.yaml";
+const CSV_HEADER: [&str; 7] = ["id", "date", "amount", "currency", "description", "category", "account"];
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Transaction {
+    pub id: String,
+    /// YYYY-MM-DD
+    pub date: String,
+    /// Negative = money out
+    pub amount: f64,
+    #[serde(default)]
+    pub currency: String,
+    pub description: String,
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub account: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub duplicates: usize,
+    /// Rows that had no parseable date/amount
+    pub skipped: usize,
+    /// YYYY-MM files touched
+    pub months: Vec<String>,
+    /// Planned only; the monthly files were left alone
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MonthSummary {
+    pub month: String,
+    pub income: f64,
+    /// Positive total of money out
+    pub expense: f64,
+    pub net: f64,
+    pub count: usize,
+    /// Spending per category (expenses only, positive); "" = uncategorized
+    pub by_category: BTreeMap<String, f64>,
+}
+
+/// life/finance/rules.yaml — first matching rule wins
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct RuleSet {
+    #[serde(default)]
+    rules: Vec<CategoryRule>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct CategoryRule {
+    /// Case-insensitive substring of the description
+    #[serde(rename = "match")]
+    pattern: String,
+    category: String,
+    /// Restrict to "income" or "expense"
+    #[serde(rename = "type")]
+    kind: Option<String>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Import a bank/credit-card export (CSV, OFX/QFX or QIF, picked by extension
+/// unless `format` is given), categorize it by rules and merge it into the
+/// monthly files. Rows already imported are skipped by id. `dry_run` reports
+/// what would be imported without touching the monthly files.
+#[tauri::command]
+pub fn import_transactions(
+    vault_path: String,
+    file_path: String,
+    account: Option<String>,
+    format: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<ImportReport, String> {
+    let dry_run = dry_run.unwrap_or(false);
+    let bytes = fs::read(&file_path).map_err(|e| tr!("Failed to read: {}", e))?;
+    let text = decode_text(&bytes);
+    let account = account.unwrap_or_else(|| {
+        Path::new(&file_path).file_stem().unwrap_or_default().to_string_lossy().to_string()
+    });
+    let format = format
+        .or_else(|| Path::new(&file_path).extension().map(|e| e.to_string_lossy().to_lowercase()))
+        .unwrap_or_default();
+
+    let (mut parsed, skipped) = match format.as_str() {
+        "ofx" | "qfx" => parse_ofx(&text),
+        "qif" => parse_qif(&text),
+        "csv" | "txt" => parse_csv(&text)?,
+        other => return Err(tr!("Unsupported import format: {}", other)),
+    };
+
+    let rules = load_rules(Path::new(&vault_path));
+    for tx in &mut parsed {
+        tx.account = account.clone();
+        if tx.id.is_empty() {
+            tx.id = transaction_id(tx);
+        }
+        if tx.category.is_empty() {
+            tx.category = categorize(&rules, tx);
+        }
+    }
+
+    let mut report = ImportReport { skipped, dry_run, ..Default::default() };
+    let mut by_month: BTreeMap<String, Vec<Transaction>> = BTreeMap::new();
+    for tx in parsed {
+        by_month.entry(tx.date[..7].to_string()).or_default().push(tx);
+    }
+    for (month, incoming) in by_month {
+        let mut existing = read_month(Path::new(&vault_path), &month)?;
+        let mut seen: HashSet<String> = existing.iter().map(|t| t.id.clone()).collect();
+        let before = existing.len();
+        for tx in incoming {
+            if seen.insert(tx.id.clone()) {
+                existing.push(tx);
+            } else {
+                report.duplicates += 1;
+            }
+        }
+        if existing.len() > before {
+            report.imported += existing.len() - before;
+            if !dry_run {
+                write_month(Path::new(&vault_path), &month, &mut existing)?;
+            }
+            report.months.push(month);
+        }
+    }
+    Ok(report)
+}
+
+/// Transactions of one month (YYYY-MM), oldest first
+#[tauri::command]
+pub fn list_transactions(vault_path: String, month: String) -> Result<Vec<Transaction>, String> {
+    read_month(Path::new(&vault_path), &month)
+}
+
+#[tauri::command]
+pub fn set_transaction_category(vault_path: String, month: String, id: String, category: String) -> Result<(), String> {
+    let vault = Path::new(&vault_path);
+    let mut txs = read_month(vault, &month)?;
+    let tx = txs.iter_mut().find(|t| t.id == id).ok_or_else(|| tr!("Transaction not found: {}", id))?;
+    tx.category = category;
+    write_month(vault, &month, &mut txs)
+}
+
+/// Re-run rules.yaml over stored transactions. Only uncategorized ones are
+/// touched unless `overwrite`. Returns how many changed, or with `dry_run`
+/// how many would, without saving.
+#[tauri::command]
+pub fn apply_category_rules(vault_path: String, overwrite: bool, dry_run: Option<bool>) -> Result<usize, String> {
+    let vault = Path::new(&vault_path);
+    let rules = load_rules(vault);
+    let mut changed = 0;
+    for month in stored_months(vault) {
+        let mut txs = read_month(vault, &month)?;
+        let mut dirty = false;
+        for tx in txs.iter_mut().filter(|t| overwrite || t.category.is_empty()) {
+            let category = categorize(&rules, tx);
+            if !category.is_empty() && category != tx.category {
+                tx.category = category;
+                dirty = true;
+                changed += 1;
+            }
+        }
+        if dirty && !dry_run.unwrap_or(false) {
+            write_month(vault, &month, &mut txs)?;
+        }
+    }
+    Ok(changed)
+}
+
+/// Income/expense totals and per-category spending for the last `months`
+/// months that have data (default 12), newest first
+#[tauri::command]
+pub fn get_spending_summary(vault_path: String, months: Option<usize>) -> Result<Vec<MonthSummary>, String> {
+    let vault = Path::new(&vault_path);
+    let mut all = stored_months(vault);
+    all.reverse();
+    all.truncate(months.unwrap_or(12));
+
+    all.into_iter()
+        .map(|month| {
+            let txs = read_month(vault, &month)?;
+            let mut s = MonthSummary { month, count: txs.len(), ..Default::default() };
+            for tx in &txs {
+                if tx.amount >= 0.0 {
+                    s.income += tx.amount;
+                } else {
+                    s.expense -= tx.amount;
+                    *s.by_category.entry(tx.category.clone()).or_default() -= tx.amount;
+                }
+            }
+            s.income = round2(s.income);
+            s.expense = round2(s.expense);
+            s.net = round2(s.income - s.expense);
+            s.by_category.values_mut().for_each(|v| *v = round2(*v));
+            Ok(s)
+        })
+        .collect()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Storage
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn month_path(vault: &Path, month: &str) -> Result<PathBuf, String> {
+    if NaiveDate::parse_from_str(&format!("{month}-01"), "%Y-%m-%d").is_err() {
+        return Err(tr!("Invalid month (expected YYYY-MM): {}", month));
+    }
+    Ok(vault.join(FINANCE_DIR).join("transactions").join(&month[..4]).join(format!("{month}.csv")))
+}
+
+fn read_month(vault: &Path, month: &str) -> Result<Vec<Transaction>, String> {
+    let path = month_path(vault, month)?;
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let mut reader = csv::Reader::from_path(&path).map_err(|e| tr!("Failed to read: {}", e))?;
+    reader
+        .deserialize()
+        .collect::<Result<Vec<Transaction>, _>>()
+        .map_err(|e| tr!("Failed to parse: {}", e))
+}
+
+fn write_month(vault: &Path, month: &str, txs: &mut [Transaction]) -> Result<(), String> {
+    let path = month_path(vault, month)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    txs.sort_by(|a, b| a.date.cmp(&b.date));
+    let mut writer = csv::Writer::from_path(&path).map_err(|e| tr!("write_file failed: {}", e))?;
+    writer.write_record(CSV_HEADER).map_err(|e| tr!("write_file failed: {}", e))?;
+    for tx in txs.iter() {
+        writer
+            .write_record([
+                tx.id.as_str(),
+                tx.date.as_str(),
+                &format!("{:.2}", tx.amount),
+                tx.currency.as_str(),
+                tx.description.as_str(),
+                tx.category.as_str(),
+                tx.account.as_str(),
+            ])
+            .map_err(|e| tr!("write_file failed: {}", e))?;
+    }
+    writer.flush().map_err(|e| tr!("write_file failed: {}", e))
+}
+
+/// Every YYYY-MM with a transactions file, oldest first
+fn stored_months(vault: &Path) -> Vec<String> {
+    let root = vault.join(FINANCE_DIR).join("transactions");
+    let mut months: Vec<String> = walkdir::WalkDir::new(root)
+        .max_depth(2)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "csv"))
+        .filter_map(|e| e.path().file_stem().map(|s| s.to_string_lossy().to_string()))
+        .filter(|m| m.len() == 7)
+        .collect();
+    months.sort();
+    months
+}
+
+fn load_rules(vault: &Path) -> Vec<CategoryRule> {
+    fs::read_to_string(vault.join(RULES_FILE))
+        .ok()
+        .and_then(|raw| serde_yaml::from_str::<RuleSet>(&raw).ok())
+        .map(|set| set.rules)
+        .unwrap_or_default()
+}
+
+fn categorize(rules: &[CategoryRule], tx: &Transaction) -> String {
+    let desc = tx.description.to_lowercase();
+    rules
+        .iter()
+        .find(|r| {
+            let kind_ok = match r.kind.as_deref() {
+                Some("income") => tx.amount >= 0.0,
+                Some("expense") => tx.amount < 0.0,
+                _ => true,
+            };
+            kind_ok && !r.pattern.is_empty() && desc.contains(&r.pattern.to_lowercase())
+        })
+        .map(|r| r.category.clone())
+        .unwrap_or_default()
+}
+
+/// Stable id for formats without one, so re-importing the same export is a no-op
+fn transaction_id(tx: &Transaction) -> String {
+    let key = [tx.date.as_str(), &format!("{:.2}", tx.amount), &tx.description, &tx.account].join("\0");
+    Sha256::digest(key.as_bytes())[..8].iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Parsers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// UTF-8 with or without BOM
+fn decode_text(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    String::from_utf8_lossy(bytes).to_string()
+}
+
+/// `-1,234.50`, `¥12.00`, `(45.00)`, `+3` → f64
+fn parse_amount(raw: &str) -> Option<f64> {
+    let s = raw.trim();
+    let negative = s.starts_with('(') && s.ends_with(')');
+    let cleaned: String = s.chars().filter(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+')).collect();
+    if cleaned.is_empty() {
+        return None;
+    }
+    let v: f64 = cleaned.parse().ok()?;
+    Some(if negative { -v.abs() } else { v })
+}
+
+/// First token of the cell, in the date formats banks commonly export
+fn parse_date(raw: &str) -> Option<NaiveDate> {
+    let token = raw.split_whitespace().next()?;
+    ["%Y-%m-%d", "%Y/%m/%d", "%Y%m%d", "%m/%d/%Y", "%d.%m.%Y", "%Y.%m.%d"]
+        .iter()
+        .find_map(|f| NaiveDate::parse_from_str(token, f).ok())
+}
+
+#[derive(Default)]
+struct Columns {
+    date: Option<usize>,
+    amount: Option<usize>,
+    debit: Option<usize>,
+    credit: Option<usize>,
+    direction: Option<usize>,
+    description: Vec<usize>,
+    currency: Option<usize>,
+}
+
+fn detect_columns(header: &csv::StringRecord) -> Option<Columns> {
+    let mut cols = Columns::default();
+    for (i, cell) in header.iter().enumerate() {
+        let name: String = cell.trim().to_lowercase().split_whitespace().collect();
+        let name = name.as_str();
+        match name {
+            "date" | "transactiondate" | "posteddate" | "postingdate" | "bookingdate" | "日期" | "交易日期"
+            | "交易时间" | "记账日期" | "入账日期" | "交易创建时间" => {
+                cols.date.get_or_insert(i);
+            }
+            "amount" | "transactionamount" | "交易金额" => { cols.amount.get_or_insert(i); }
+            _ if name.starts_with("金额") => { cols.amount.get_or_insert(i); }
+            "debit" | "withdrawal" | "withdrawals" | "支出" | "支出金额" | "借方金额" => { cols.debit.get_or_insert(i); }
+            "credit" | "deposit" | "deposits" | "收入" | "收入金额" | "存入金额" | "贷方金额" => { cols.credit.get_or_insert(i); }
+            "收/支" | "收支" | "收支类型" | "debit/credit" => { cols.direction.get_or_insert(i); }
+            "description" | "payee" | "memo" | "details" | "narrative" | "摘要" | "交易对方" | "商品" | "商品说明"
+            | "商品名称" | "备注" | "交易描述" | "对方户名" | "交易摘要" => cols.description.push(i),
+            "currency" | "币种" | "币别" => { cols.currency.get_or_insert(i); }
+            _ => {}
+        }
+    }
+    let has_amount = cols.amount.is_some() || cols.debit.is_some() || cols.credit.is_some();
+    (cols.date.is_some() && has_amount).then_some(cols)
+}
+
+/// Generic bank CSV: the header row is found among the first rows (many
+/// exports start with account info), columns are matched by common English
+/// and Chinese names
+fn parse_csv(text: &str) -> Result<(Vec<Transaction>, usize), String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes());
+    let rows: Vec<csv::StringRecord> = reader.records().filter_map(|r| r.ok()).collect();
+
+    let (header_at, cols) = rows
+        .iter()
+        .take(30)
+        .enumerate()
+        .find_map(|(i, row)| detect_columns(row).map(|c| (i, c)))
+        .ok_or_else(|| tr!("Could not find date and amount columns in CSV"))?;
+
+    let cell = |row: &csv::StringRecord, i: Option<usize>| i.and_then(|i| row.get(i)).unwrap_or("").to_string();
+    let mut txs = Vec::new();
+    let mut skipped = 0;
+    for row in &rows[header_at + 1..] {
+        let date = parse_date(&cell(row, cols.date));
+        let amount = match (parse_amount(&cell(row, cols.amount)), cols.debit.or(cols.credit)) {
+            (Some(a), _) => Some(a),
+            (None, Some(_)) => {
+                let out = parse_amount(&cell(row, cols.debit)).unwrap_or(0.0).abs();
+                let inc = parse_amount(&cell(row, cols.credit)).unwrap_or(0.0).abs();
+                (out != 0.0 || inc != 0.0).then_some(inc - out)
+            }
+            _ => None,
+        };
+        let (Some(date), Some(mut amount)) = (date, amount) else {
+            skipped += 1;
+            continue;
+        };
+        // 支付宝/微信 style: positive amounts plus a 收/支 column
+        let direction = cell(row, cols.direction).to_lowercase();
+        if ["支出", "支", "debit", "expense"].contains(&direction.as_str()) {
+            amount = -amount.abs();
+        }
+        let description = cols
+            .description
+            .iter()
+            .filter_map(|i| row.get(*i))
+            .filter(|s| !s.is_empty() && *s != "/")
+            .collect::<Vec<_>>()
+            .join(" · ");
+        txs.push(Transaction {
+            id: String::new(),
+            date: date.format("%Y-%m-%d").to_string(),
+            amount,
+            currency: cell(row, cols.currency),
+            description,
+            category: String::new(),
+            account: String::new(),
+        });
+    }
+    Ok((txs, skipped))
+}
+
+/// OFX 1.x (SGML, unclosed tags) and 2.x (XML): one transaction per <STMTTRN>
+fn parse_ofx(text: &str) -> (Vec<Transaction>, usize) {
+    // ASCII only, so offsets found in `upper` line up with `text`
+    let upper = text.to_ascii_uppercase();
+    let currency = ofx_field(text, &upper, "CURDEF").unwrap_or_default();
+    let mut txs = Vec::new();
+    let mut skipped = 0;
+    let mut rest = 0;
+    while let Some(start) = upper[rest..].find("<STMTTRN>").map(|i| rest + i) {
+        let end = upper[start..].find("</STMTTRN>").map_or(upper.len(), |i| start + i);
+        let (block, block_upper) = (&text[start..end], &upper[start..end]);
+        rest = end.max(start + 1);
+
+        let date = ofx_field(block, block_upper, "DTPOSTED")
+            .and_then(|d| NaiveDate::parse_from_str(d.get(..8)?, "%Y%m%d").ok());
+        let amount = ofx_field(block, block_upper, "TRNAMT").and_then(|a| parse_amount(&a));
+        let (Some(date), Some(amount)) = (date, amount) else {
+            skipped += 1;
+            continue;
+        };
+        let name = ofx_field(block, block_upper, "NAME").unwrap_or_default();
+        let memo = ofx_field(block, block_upper, "MEMO").unwrap_or_default();
+        let description = match (name.is_empty(), memo.is_empty() || memo == name) {
+            (false, false) => format!("{name} · {memo}"),
+            (false, true) => name,
+            _ => memo,
+        };
+        txs.push(Transaction {
+            id: ofx_field(block, block_upper, "FITID").unwrap_or_default(),
+            date: date.format("%Y-%m-%d").to_string(),
+            amount,
+            currency: currency.clone(),
+            description,
+            category: String::new(),
+            account: String::new(),
+        });
+    }
+    (txs, skipped)
+}
+
+/// Value after `<TAG>` up to the next tag or line end
+fn ofx_field(text: &str, upper: &str, tag: &str) -> Option<String> {
+    let open = format!("<{tag}>");
+    let start = upper.find(&open)? + open.len();
+    let value = &text[start..];
+    let end = value.find(['<', '\n', '\r']).unwrap_or(value.len());
+    let value = value[..end].trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// QIF: `D` date, `T`/`U` amount, `P` payee, `M` memo, `L` category, `^` ends a record
+fn parse_qif(text: &str) -> (Vec<Transaction>, usize) {
+    let mut txs = Vec::new();
+    let mut skipped = 0;
+    let (mut date, mut amount, mut payee, mut memo, mut category) = (None, None, String::new(), String::new(), String::new());
+
+    for line in text.lines() {
+        let line = line.trim_end();
+        let (code, value) = line.split_at(line.chars().next().map_or(0, char::len_utf8));
+        match code {
+            "D" => date = parse_qif_date(value),
+            "T" | "U" => amount = amount.or(parse_amount(value)),
+            "P" => payee = value.trim().to_string(),
+            "M" => memo = value.trim().to_string(),
+            "L" => category = value.trim().trim_matches(['[', ']']).to_string(),
+            "^" => {
+                match (date.take(), amount.take()) {
+                    (Some(d), Some(a)) => txs.push(Transaction {
+                        id: String::new(),
+                        date: d.format("%Y-%m-%d").to_string(),
+                        amount: a,
+                        currency: String::new(),
+                        description: if memo.is_empty() { payee.clone() } else if payee.is_empty() { memo.clone() } else { format!("{payee} · {memo}") },
+                        category: category.clone(),
+                        account: String::new(),
+                    }),
+                    _ => skipped += 1,
+                }
+                payee.clear();
+                memo.clear();
+                category.clear();
+            }
+            _ => {}
+        }
+    }
+    (txs, skipped)
+}
+
+/// `12/31/2024`, `12/31'24`, `1/5/24`, `2024-12-31`
+fn parse_qif_date(raw: &str) -> Option<NaiveDate> {
+    let s = raw.trim().replace('\'', "/").replace(' ', "");
+    let parts: Vec<&str> = s.split(['/', '-', '.']).collect();
+    let [m, d, y] = parts.as_slice() else { return None };
+    if m.len() == 4 {
+        return parse_date(&s);
+    }
+    let mut year: i32 = y.parse().ok()?;
+    if year < 100 {
+        year += if year < 70 { 2000 } else { 1900 };
+    }
+    NaiveDate::from_ymd_opt(year, m.parse().ok()?, d.parse().ok()?)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("-1,234.50"), Some(-1234.5));
+        assert_eq!(parse_amount("¥12.00"), Some(12.0));
+        assert_eq!(parse_amount("(45.00)"), Some(-45.0));
+        assert_eq!(parse_amount(""), None);
+    }
+
+    #[test]
+    fn test_parse_csv_with_preamble_and_direction() {
+        let csv = "支付宝交易记录明细查询\n账号:[x]\n交易创建时间,交易对方,商品名称,金额（元）,收/支\n2024-03-05 12:01:00,星巴克,拿铁,32.00,支出\n2024-03-06 09:00:00,公司,工资,8000.00,收入\n,,,,\n";
+        let (txs, skipped) = parse_csv(csv).unwrap();
+        assert_eq!(txs.len(), 2);
+        assert_eq!(skipped, 1);
+        assert_eq!(txs[0].date, "2024-03-05");
+        assert_eq!(txs[0].amount, -32.0);
+        assert_eq!(txs[0].description, "星巴克 · 拿铁");
+        assert_eq!(txs[1].amount, 8000.0);
+    }
+
+    #[test]
+    fn test_parse_csv_debit_credit_columns() {
+        let csv = "Date,Description,Debit,Credit\n01/05/2024,Coffee,4.50,\n01/06/2024,Refund,,10.00\n";
+        let (txs, _) = parse_csv(csv).unwrap();
+        assert_eq!(txs.iter().map(|t| t.amount).collect::<Vec<_>>(), vec![-4.5, 10.0]);
+    }
+
+    #[test]
+    fn test_parse_ofx_sgml() {
+        let ofx = "OFXHEADER:100\n<OFX><CURDEF>USD\n<STMTTRN>\n<TRNTYPE>DEBIT\n<DTPOSTED>20240105120000\n<TRNAMT>-12.34\n<FITID>abc1\n<NAME>GROCERY\n</STMTTRN>\n</OFX>";
+        let (txs, skipped) = parse_ofx(ofx);
+        assert_eq!(skipped, 0);
+        assert_eq!(txs, vec![Transaction {
+            id: "abc1".into(),
+            date: "2024-01-05".into(),
+            amount: -12.34,
+            currency: "USD".into(),
+            description: "GROCERY".into(),
+            category: String::new(),
+            account: String::new(),
+        }]);
+    }
+
+    #[test]
+    fn test_parse_ofx_non_ascii() {
+        // Unicode uppercasing shortens ﬁ (FI) and ı (I), shifting every later offset
+        let ofx = "<OFX><CURDEF>EUR\n<STMTTRN>\n<NAME>ﬁsh ﬁllet · Kırmızı\n<MEMO>早餐\n<DTPOSTED>20240105\n<TRNAMT>-3.20\n<FITID>x1\n</STMTTRN>\n</OFX>";
+        let (txs, skipped) = parse_ofx(ofx);
+        assert_eq!(skipped, 0);
+        assert_eq!((txs[0].id.as_str(), txs[0].amount), ("x1", -3.2));
+        assert_eq!(txs[0].description, "ﬁsh ﬁllet · Kırmızı · 早餐");
+    }
+
+    #[test]
+    fn test_parse_qif() {
+        let qif = "!Type:Bank\nD12/31'24\nT-20.00\nPGas Station\nLAuto:Fuel\n^\nD1/2/2025\nT100\nPPaycheck\n^\n";
+        let (txs, _) = parse_qif(qif);
+        assert_eq!(txs.len(), 2);
+        assert_eq!(txs[0].date, "2024-12-31");
+        assert_eq!(txs[0].category, "Auto:Fuel");
+        assert_eq!(txs[1].date, "2025-01-02");
+    }
+
+    #[test]
+    fn test_categorize_respects_type() {
+        let rules = vec![
+            CategoryRule { pattern: "refund".into(), category: "退款".into(), kind: Some("income".into()) },
+            CategoryRule { pattern: "amazon".into(), category: "购物".into(), kind: None },
+        ];
+        let tx = |desc: &str, amount| Transaction {
+            id: String::new(), date: String::new(), amount, currency: String::new(),
+            description: desc.into(), category: String::new(), account: String::new(),
+        };
+        assert_eq!(categorize(&rules, &tx("AMAZON refund", 5.0)), "退款");
+        assert_eq!(categorize(&rules, &tx("Amazon refund", -5.0)), "购物");
+    }
+
+    #[test]
+    fn test_transaction_id_is_stable() {
+        let mut tx = Transaction {
+            id: String::new(), date: "2025-01-02".into(), amount: -20.0, currency: String::new(),
+            description: "Gas Station".into(), category: String::new(), account: "checking".into(),
+        };
+        // Pinned: ids written by earlier imports must keep matching
+        assert_eq!(transaction_id(&tx), "4e33582fff2a2d7f");
+        tx.account = String::new();
+        assert_ne!(transaction_id(&tx), "4e33582fff2a2d7f");
+    }
+}
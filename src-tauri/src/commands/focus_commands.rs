@@ -0,0 +1,332 @@
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveDate};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::services::automations::run_shortcut;
+use crate::services::durable;
+
+/// Emitted with the `FocusSession` when a session starts
+pub const FOCUS_STARTED_EVENT: &str = "focus-started";
+/// Emitted with the logged `TimeEntry` when a session ends or is stopped
+pub const FOCUS_ENDED_EVENT: &str = "focus-ended";
+
+const SETTINGS_FILE: &str = ".lifeos/focus.yaml";
+/// Time tracking: one JSON `TimeEntry` per line, appended as sessions end
+const TIME_LOG: &str = "life/time/sessions.jsonl";
+const DEFAULT_PROFILE: &str = "default";
+const DEFAULT_MINUTES: u64 = 25;
+const MAX_MINUTES: u64 = 24 * 60;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// .lifeos/focus.yaml
+/// ```yaml
+/// profiles:
+///   deep-work:
+///     minutes: 90
+///     start_shortcut: "Focus On"
+///     end_shortcut: "Focus Off"
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FocusSettings {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, FocusProfile>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FocusProfile {
+    /// Length used when start_focus is given none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minutes: Option<u64>,
+    /// Hold back LifeOS reminders while the session runs
+    #[serde(default = "default_true")]
+    pub mute_notifications: bool,
+    /// macOS Shortcut run at the start, e.g. one that turns a Focus on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_shortcut: Option<String>,
+    /// macOS Shortcut run when the session ends or is stopped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_shortcut: Option<String>,
+}
+
+impl Default for FocusProfile {
+    fn default() -> Self {
+        Self { minutes: None, mute_notifications: true, start_shortcut: None, end_shortcut: None }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FocusSession {
+    pub profile: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// RFC 3339
+    pub start: String,
+    /// RFC 3339; the session ends by itself at this time
+    pub ends_at: String,
+    pub minutes: u64,
+    pub mute_notifications: bool,
+}
+
+/// A line of life/time/sessions.jsonl
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TimeEntry {
+    /// "focus"
+    pub kind: String,
+    pub profile: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// RFC 3339
+    pub start: String,
+    /// RFC 3339
+    pub end: String,
+    pub minutes: u64,
+    /// Ran its full length rather than being stopped early
+    pub completed: bool,
+}
+
+struct Active {
+    session: FocusSession,
+    vault: PathBuf,
+    end_shortcut: Option<String>,
+}
+
+static ACTIVE: Lazy<Mutex<Option<Active>>> = Lazy::new(|| Mutex::new(None));
+
+fn default_true() -> bool {
+    true
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn get_focus_settings(vault_path: String) -> Result<FocusSettings, String> {
+    Ok(load_settings(Path::new(&vault_path)))
+}
+
+#[tauri::command]
+pub fn save_focus_settings(vault_path: String, settings: FocusSettings) -> Result<(), String> {
+    let path = PathBuf::from(&vault_path).join(SETTINGS_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let raw = serde_yaml::to_string(&settings).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(&path, raw).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+/// Start a session, ending (and logging) any one already running. `minutes`
+/// falls back to the profile's, then 25; unknown profiles act as the default
+/// one: reminders muted, no shortcuts
+#[tauri::command]
+pub fn start_focus(
+    app: AppHandle,
+    vault_path: String,
+    minutes: Option<u64>,
+    profile: Option<String>,
+    label: Option<String>,
+) -> Result<FocusSession, String> {
+    let vault = PathBuf::from(&vault_path);
+    let name = profile.map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+    let settings = load_settings(&vault);
+    let config = settings.profiles.get(&name).cloned().unwrap_or_default();
+    let minutes = minutes.or(config.minutes).unwrap_or(DEFAULT_MINUTES);
+    if minutes == 0 || minutes > MAX_MINUTES {
+        return Err(tr!("Focus length must be between 1 and {} minutes", MAX_MINUTES));
+    }
+
+    finish(&app, None, false);
+    let now = Local::now();
+    let session = FocusSession {
+        profile: name,
+        label: label.map(|l| l.trim().to_string()).filter(|l| !l.is_empty()),
+        start: now.to_rfc3339(),
+        ends_at: (now + ChronoDuration::minutes(minutes as i64)).to_rfc3339(),
+        minutes,
+        mute_notifications: config.mute_notifications,
+    };
+    *ACTIVE.lock().unwrap() =
+        Some(Active { session: session.clone(), vault, end_shortcut: config.end_shortcut.filter(|s| !s.trim().is_empty()) });
+
+    if let Some(shortcut) = config.start_shortcut.filter(|s| !s.trim().is_empty()) {
+        spawn_shortcut(shortcut);
+    }
+    if let Err(e) = app.emit(FOCUS_STARTED_EVENT, &session) {
+        println!("[WARN] failed to emit {FOCUS_STARTED_EVENT}: {e}");
+    }
+
+    let start = session.start.clone();
+    let ends_at = now + ChronoDuration::minutes(minutes as i64);
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_secs(1));
+        let current = ACTIVE.lock().unwrap().as_ref().is_some_and(|a| a.session.start == start);
+        if !current {
+            return;
+        }
+        if Local::now() >= ends_at {
+            finish(&app, Some(&start), true);
+            return;
+        }
+    });
+    Ok(session)
+}
+
+/// End the running session early; None when there was none
+#[tauri::command]
+pub fn stop_focus(app: AppHandle) -> Option<TimeEntry> {
+    finish(&app, None, false)
+}
+
+#[tauri::command]
+pub fn get_focus_status() -> Option<FocusSession> {
+    ACTIVE.lock().unwrap().as_ref().map(|a| a.session.clone())
+}
+
+/// Logged sessions that started on `date` (YYYY-MM-DD, default today)
+#[tauri::command]
+pub fn get_time_entries(vault_path: String, date: Option<String>) -> Result<Vec<TimeEntry>, String> {
+    let date = match date {
+        Some(d) => NaiveDate::parse_from_str(&d, "%Y-%m-%d").map_err(|_| tr!("Invalid date (expected YYYY-MM-DD): {}", d))?,
+        None => Local::now().date_naive(),
+    };
+    Ok(load_entries(Path::new(&vault_path))
+        .into_iter()
+        .filter(|e| DateTime::parse_from_rfc3339(&e.start).is_ok_and(|t| t.with_timezone(&Local).date_naive() == date))
+        .collect())
+}
+
+/// Reminder senders skip showing while this is true
+pub(crate) fn notifications_muted() -> bool {
+    ACTIVE.lock().unwrap().as_ref().is_some_and(|a| a.session.mute_notifications)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// End the active session (only if it started at `only`, when given), log
+/// it and tell the UI
+fn finish(app: &AppHandle, only: Option<&str>, completed: bool) -> Option<TimeEntry> {
+    let active = {
+        let mut guard = ACTIVE.lock().unwrap();
+        if only.is_some_and(|start| guard.as_ref().is_none_or(|a| a.session.start != start)) {
+            return None;
+        }
+        guard.take()?
+    };
+    let entry = time_entry(&active.session, Local::now(), completed);
+    if let Err(e) = append_entry(&active.vault, &entry) {
+        println!("[WARN] failed to log focus session: {e}");
+    }
+    if let Some(shortcut) = active.end_shortcut {
+        spawn_shortcut(shortcut);
+    }
+    if let Err(e) = app.emit(FOCUS_ENDED_EVENT, &entry) {
+        println!("[WARN] failed to emit {FOCUS_ENDED_EVENT}: {e}");
+    }
+    Some(entry)
+}
+
+fn time_entry(session: &FocusSession, end: DateTime<Local>, completed: bool) -> TimeEntry {
+    let minutes = DateTime::parse_from_rfc3339(&session.start)
+        .map(|start| (end.fixed_offset() - start).num_minutes().max(0) as u64)
+        .unwrap_or_default();
+    TimeEntry {
+        kind: "focus".to_string(),
+        profile: session.profile.clone(),
+        label: session.label.clone(),
+        start: session.start.clone(),
+        end: end.to_rfc3339(),
+        minutes: if completed { session.minutes } else { minutes.min(session.minutes) },
+        completed,
+    }
+}
+
+fn append_entry(vault: &Path, entry: &TimeEntry) -> Result<(), String> {
+    let path = vault.join(TIME_LOG);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let line = serde_json::to_string(entry).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    let mut file = OpenOptions::new().create(true).append(true).open(&path).map_err(|e| tr!("write_file failed: {}", e))?;
+    writeln!(file, "{line}").map_err(|e| tr!("write_file failed: {}", e))
+}
+
+/// Unparseable lines are skipped so a hand edit can't hide the whole log
+fn load_entries(vault: &Path) -> Vec<TimeEntry> {
+    fs::read_to_string(vault.join(TIME_LOG))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect()
+}
+
+fn load_settings(vault: &Path) -> FocusSettings {
+    fs::read_to_string(vault.join(SETTINGS_FILE))
+        .ok()
+        .and_then(|raw| serde_yaml::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// Shortcuts can take seconds; don't hold up the command or timer on them
+fn spawn_shortcut(name: String) {
+    std::thread::spawn(move || {
+        if let Err(e) = run_shortcut(&name) {
+            println!("[WARN] focus shortcut '{name}' failed: {e}");
+        }
+    });
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_log() {
+        let vault = tempfile::tempdir().unwrap();
+        let start = Local::now() - ChronoDuration::minutes(10);
+        let session = FocusSession {
+            profile: "deep-work".into(),
+            label: Some("写周报".into()),
+            start: start.to_rfc3339(),
+            ends_at: (start + ChronoDuration::minutes(50)).to_rfc3339(),
+            minutes: 50,
+            mute_notifications: true,
+        };
+
+        // Stopped early: actual minutes; run out: the planned length
+        let stopped = time_entry(&session, Local::now(), false);
+        assert_eq!((stopped.minutes, stopped.completed), (10, false));
+        assert_eq!(time_entry(&session, start + ChronoDuration::minutes(50), true).minutes, 50);
+
+        append_entry(vault.path(), &stopped).unwrap();
+        fs::write(vault.path().join(TIME_LOG), format!("{}oops\n", fs::read_to_string(vault.path().join(TIME_LOG)).unwrap())).unwrap();
+        append_entry(vault.path(), &stopped).unwrap();
+        let v = vault.path().to_string_lossy().to_string();
+        let day = start.format("%Y-%m-%d").to_string();
+        assert_eq!(get_time_entries(v.clone(), Some(day)).unwrap(), vec![stopped.clone(), stopped]);
+        assert!(get_time_entries(v, Some("2000-01-01".into())).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_settings_defaults() {
+        let settings: FocusSettings = serde_yaml::from_str("profiles:\n  reading:\n    start_shortcut: Reading\n").unwrap();
+        let reading = &settings.profiles["reading"];
+        assert!(reading.mute_notifications && reading.minutes.is_none());
+        assert_eq!(reading.start_shortcut.as_deref(), Some("Reading"));
+    }
+}
@@ -0,0 +1,292 @@
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+use super::focus_commands::notifications_muted;
+use crate::services::durable;
+use crate::services::periodic::Periodic;
+use crate::services::mail::{self, CachedMessage, EmailMessage};
+
+/// Sent messages waiting for a reply; answered ones are dropped
+const FOLLOWUPS_FILE: &str = ".lifeos/followups.json";
+const CHECK_EVERY: Duration = Duration::from_secs(60 * 60);
+
+// Only one reminder loop, bound to the open vault
+static REMINDERS: Periodic = Periodic::new();
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FollowUp {
+    /// Without the `<>`
+    pub message_id: String,
+    #[serde(default)]
+    pub account_id: String,
+    pub subject: String,
+    pub to: String,
+    /// Our address; our own later messages in the thread aren't replies
+    pub from: String,
+    /// When tracking started, RFC 3339
+    pub since: String,
+    /// Reply wanted by, YYYY-MM-DD
+    pub due: String,
+    /// Reminders already shown: "due", "overdue"
+    #[serde(default)]
+    pub notified: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PendingFollowUp {
+    #[serde(flatten)]
+    pub followup: FollowUp,
+    /// Negative once overdue
+    pub days_left: i64,
+    pub overdue: bool,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Wait for a reply to a cached sent message by `due` (YYYY-MM-DD). Tracking
+/// the same message again moves the date.
+#[tauri::command]
+pub fn track_followup(vault_path: String, account_id: String, email_id: String, due: String) -> Result<FollowUp, String> {
+    let dir = PathBuf::from(&vault_path).join("Mailbox").join(&account_id);
+    let index: Vec<EmailMessage> = fs::read_to_string(dir.join("index.json"))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default();
+    let email = index.into_iter().find(|m| m.id == email_id).ok_or_else(|| tr!("Email not found: {}", email_id))?;
+    let safe_id = email_id.replace(['/', '\\'], "_");
+    let message_id = fs::read(dir.join(format!("{safe_id}.eml")))
+        .ok()
+        .and_then(|raw| mail::header_message_id(&raw))
+        .ok_or_else(|| tr!("Email has no Message-ID: {}", email.subject))?;
+    let followup = FollowUp {
+        message_id,
+        account_id,
+        subject: email.subject,
+        to: email.to,
+        from: address_of(&email.from),
+        since: Local::now().to_rfc3339(),
+        due,
+        notified: Vec::new(),
+    };
+    track(Path::new(&vault_path), followup)
+}
+
+/// Stop waiting, e.g. after a reply came some other way
+#[tauri::command]
+pub fn cancel_followup(vault_path: String, message_id: String) -> Result<(), String> {
+    let vault = Path::new(&vault_path);
+    let mut all = load(vault);
+    let wanted = message_id.trim_start_matches('<').trim_end_matches('>');
+    all.retain(|f| f.message_id != wanted);
+    save(vault, &all)
+}
+
+/// Follow-ups still without a reply, soonest due first. Replies synced since
+/// the last call are looked for first, and answered follow-ups dropped.
+#[tauri::command]
+pub fn get_pending_followups(vault_path: String) -> Result<Vec<PendingFollowUp>, String> {
+    let vault = Path::new(&vault_path);
+    let pending = refresh(vault)?;
+    Ok(with_status(pending, Local::now().date_naive()))
+}
+
+/// Check hourly for replies, and notify when a follow-up falls due and again
+/// once it is overdue. Calling again (e.g. after switching vaults) restarts it.
+#[tauri::command]
+pub fn start_followup_reminders(app: AppHandle, vault_path: String) {
+    let vault = PathBuf::from(vault_path);
+    REMINDERS.start(CHECK_EVERY, move || send_due_reminders(&app, &vault));
+}
+
+#[tauri::command]
+pub fn stop_followup_reminders() {
+    REMINDERS.stop();
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Add or update a follow-up, keyed by Message-ID
+pub(crate) fn track(vault: &Path, mut followup: FollowUp) -> Result<FollowUp, String> {
+    NaiveDate::parse_from_str(&followup.due, "%Y-%m-%d").map_err(|_| tr!("Invalid date (expected YYYY-MM-DD): {}", followup.due))?;
+    followup.message_id = followup.message_id.trim_start_matches('<').trim_end_matches('>').to_string();
+    let mut all = load(vault);
+    match all.iter_mut().find(|f| f.message_id == followup.message_id) {
+        // A new date earns new reminders
+        Some(existing) => *existing = FollowUp { since: existing.since.clone(), ..followup.clone() },
+        None => all.push(followup.clone()),
+    }
+    save(vault, &all)?;
+    Ok(followup)
+}
+
+/// `Name <a@b>` → `a@b`
+fn address_of(from: &str) -> String {
+    match (from.rfind('<'), from.rfind('>')) {
+        (Some(start), Some(end)) if start < end => from[start + 1..end].trim().to_string(),
+        _ => from.trim().to_string(),
+    }
+}
+
+/// Drop answered follow-ups and return the rest
+fn refresh(vault: &Path) -> Result<Vec<FollowUp>, String> {
+    let mut all = load(vault);
+    if all.is_empty() {
+        return Ok(all);
+    }
+    // Replies can't predate the oldest message being waited on
+    let since = all
+        .iter()
+        .filter_map(|f| chrono::DateTime::parse_from_rfc3339(&f.since).ok())
+        .min()
+        .map_or(SystemTime::UNIX_EPOCH, SystemTime::from);
+    let sent: BTreeMap<String, String> = all.iter().map(|f| (f.message_id.clone(), f.from.clone())).collect();
+    let replies: BTreeMap<String, CachedMessage> = mail::find_replies(&vault.to_string_lossy(), &sent, since);
+    if !replies.is_empty() {
+        all.retain(|f| !replies.contains_key(&f.message_id));
+        save(vault, &all)?;
+    }
+    Ok(all)
+}
+
+fn with_status(followups: Vec<FollowUp>, today: NaiveDate) -> Vec<PendingFollowUp> {
+    let mut pending: Vec<PendingFollowUp> = followups
+        .into_iter()
+        .filter_map(|f| {
+            let due = NaiveDate::parse_from_str(&f.due, "%Y-%m-%d").ok()?;
+            let days_left = (due - today).num_days();
+            Some(PendingFollowUp { followup: f, days_left, overdue: days_left < 0 })
+        })
+        .collect();
+    pending.sort_by(|a, b| a.days_left.cmp(&b.days_left).then_with(|| a.followup.subject.cmp(&b.followup.subject)));
+    pending
+}
+
+fn load(vault: &Path) -> Vec<FollowUp> {
+    fs::read_to_string(vault.join(FOLLOWUPS_FILE)).ok().and_then(|raw| serde_json::from_str(&raw).ok()).unwrap_or_default()
+}
+
+fn save(vault: &Path, followups: &[FollowUp]) -> Result<(), String> {
+    let path = vault.join(FOLLOWUPS_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(followups).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(path, json).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+fn send_due_reminders(app: &AppHandle, vault: &Path) {
+    let pending = match refresh(vault) {
+        Ok(pending) => pending,
+        Err(e) => {
+            println!("[WARN] follow-ups: {e}");
+            return;
+        }
+    };
+    // Held back, not dropped: they go out on the first check after the focus
+    // session ends
+    if notifications_muted() {
+        return;
+    }
+    let mut shown: Vec<(String, &'static str)> = Vec::new();
+    for p in with_status(pending, Local::now().date_naive()) {
+        let stage = match p.days_left {
+            0 => "due",
+            n if n < 0 => "overdue",
+            _ => continue,
+        };
+        if p.followup.notified.iter().any(|s| s == stage) {
+            continue;
+        }
+        let title = tr!("Waiting for reply: {}", p.followup.subject);
+        let body = match stage {
+            "due" => format!("{} · {}", tr!("Today"), p.followup.to),
+            _ => format!("{} · {}", tr!("Overdue since {}", p.followup.due), p.followup.to),
+        };
+        match app.notification().builder().title(&title).body(&body).show() {
+            Ok(()) => shown.push((p.followup.message_id, stage)),
+            Err(e) => println!("[WARN] failed to show reminder: {e}"),
+        }
+    }
+    if shown.is_empty() {
+        return;
+    }
+    // Reloaded, so a follow-up tracked meanwhile isn't lost
+    let mut all = load(vault);
+    for (id, stage) in shown {
+        if let Some(f) = all.iter_mut().find(|f| f.message_id == id) {
+            f.notified.push(stage.to_string());
+        }
+    }
+    if let Err(e) = save(vault, &all) {
+        println!("[WARN] follow-ups: {e}");
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, rel: &str, text: &str) {
+        let path = dir.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    fn followup(id: &str, due: &str) -> FollowUp {
+        FollowUp {
+            message_id: id.into(),
+            account_id: "work".into(),
+            subject: format!("Quote {id}"),
+            to: "client@example.com".into(),
+            from: "me@example.com".into(),
+            since: "2026-01-01T00:00:00+00:00".into(),
+            due: due.into(),
+            notified: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_replies_and_status() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        track(v, followup("<a@example.com>", "2026-03-01")).unwrap();
+        track(v, followup("b@example.com", "2026-03-05")).unwrap();
+        track(v, followup("c@example.com", "2026-03-10")).unwrap();
+        assert!(track(v, followup("d@example.com", "next week")).is_err());
+
+        // A reply to a, and our own nudge in c's thread
+        write(v, "Mailbox/work/index.json", r#"[
+            {"id":"r1","uid":1,"uidString":null,"from":"Client <client@example.com>","to":"me@example.com","subject":"Re: Quote","date":"","bodyText":null,"bodyHtml":null,"attachments":[],"flags":[],"folder":"INBOX"},
+            {"id":"r2","uid":2,"uidString":null,"from":"Me <me@example.com>","to":"client@example.com","subject":"Re: Quote","date":"","bodyText":null,"bodyHtml":null,"attachments":[],"flags":[],"folder":"Sent"}
+        ]"#);
+        write(v, "Mailbox/work/r1.eml", "Message-ID: <r1@example.com>\r\nIn-Reply-To: <a@example.com>\r\nReferences: <x@example.com>\r\n <a@example.com>\r\n\r\nThanks");
+        write(v, "Mailbox/work/r2.eml", "Message-ID: <r2@example.com>\r\nReferences: <c@example.com>\r\n\r\nAny news?");
+
+        let pending = with_status(refresh(v).unwrap(), NaiveDate::from_ymd_opt(2026, 3, 5).unwrap());
+        let summary: Vec<(&str, i64, bool)> = pending.iter().map(|p| (p.followup.message_id.as_str(), p.days_left, p.overdue)).collect();
+        assert_eq!(summary, [("b@example.com", 0, false), ("c@example.com", 5, false)]);
+        assert_eq!(load(v).len(), 2);
+
+        cancel_followup(v.to_string_lossy().to_string(), "<b@example.com>".into()).unwrap();
+        assert_eq!(load(v).len(), 1);
+        assert_eq!(address_of("Me <me@example.com>"), "me@example.com");
+    }
+}
@@ -1,8 +1,31 @@
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
 use walkdir::WalkDir;
 
+use crate::services;
+use crate::services::batch::{self, BatchOp, BatchReport};
+use crate::services::durable;
+use crate::services::embeds::Resolved;
+use crate::services::journal::{self, Operation};
+use crate::services::note_ids;
+use crate::services::note_locks::{self, NoteLock};
+use crate::services::notes::{split_frontmatter, NoteMatch};
+use crate::services::schemas::{self, Violation};
+use crate::services::transfer::{self, Collision};
+
+/// Emitted with a `MoveProgress` while `move_file` copies across filesystems
+pub const MOVE_PROGRESS_EVENT: &str = "move-progress";
+/// Smaller moves finish too quickly to be worth reporting
+const PROGRESS_MIN_BYTES: u64 = 8 << 20;
+
+/// Guarded writes check and write under this, so two can't both pass the
+/// check before either has written
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Types
 // ─────────────────────────────────────────────────────────────────────────────
@@ -14,6 +37,61 @@ pub struct NoteFile {
     pub frontmatter: serde_json::Value,
     pub content: String,
     pub modified: String,
+    /// See `FileVersion::hash`
+    #[serde(default)]
+    pub hash: String,
+}
+
+/// What a reader saw of a file; handed back on write to detect that it
+/// changed in between
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FileVersion {
+    /// As in `NoteFile::modified`, to the second
+    pub modified: String,
+    /// SHA-256 of the file's bytes, hex
+    pub hash: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VersionedFile {
+    pub content: String,
+    #[serde(flatten)]
+    pub version: FileVersion,
+}
+
+/// Why `write_file`/`write_note` failed. A conflict serializes as an object
+/// tagged `"kind": "conflict"` holding what is on disk now, so the caller
+/// can merge or retry; frontmatter breaking its folder's schema is tagged
+/// `"invalid"`; any other failure is the usual message string.
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WriteError {
+    Conflict {
+        path: String,
+        /// None when the file has since been deleted
+        content: Option<String>,
+        version: Option<FileVersion>,
+    },
+    Invalid {
+        path: String,
+        violations: Vec<Violation>,
+    },
+    #[serde(untagged)]
+    Failed(String),
+}
+
+impl From<String> for WriteError {
+    fn from(message: String) -> Self {
+        WriteError::Failed(message)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MoveProgress {
+    pub src: String,
+    pub dest: String,
+    pub bytes: u64,
+    pub total: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -31,21 +109,46 @@ pub struct DirEntry {
 
 #[tauri::command]
 pub fn read_file(path: String) -> Result<String, String> {
-    fs::read_to_string(&path).map_err(|e| format!("read_file failed: {e}"))
+    fs::read_to_string(&path).map_err(|e| tr!("read_file failed: {}", e))
 }
 
+/// The content with its version, to pass back to `write_file`
 #[tauri::command]
-pub fn write_file(path: String, content: String) -> Result<(), String> {
+pub fn read_file_versioned(path: String) -> Result<VersionedFile, String> {
+    let bytes = fs::read(&path).map_err(|e| tr!("read_file failed: {}", e))?;
+    let version = FileVersion { modified: modified_at(&path), hash: content_hash(&bytes) };
+    let content = String::from_utf8(bytes).map_err(|e| tr!("read_file failed: {}", e))?;
+    Ok(VersionedFile { content, version })
+}
+
+/// With `expected_hash` and/or `expected_modified` from the read, fails
+/// with `WriteError::Conflict` rather than overwrite a file changed since;
+/// an `expected_hash` of "" means the file must not exist yet
+#[tauri::command]
+pub fn write_file(
+    path: String,
+    content: String,
+    expected_hash: Option<String>,
+    expected_modified: Option<String>,
+) -> Result<FileVersion, WriteError> {
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    check_version(&path, expected_hash.as_deref(), expected_modified.as_deref())?;
     // Ensure parent dirs exist
     if let Some(parent) = PathBuf::from(&path).parent() {
-        fs::create_dir_all(parent).map_err(|e| format!("create_dir_all failed: {e}"))?;
+        fs::create_dir_all(parent).map_err(|e| tr!("create_dir_all failed: {}", e))?;
     }
-    fs::write(&path, content).map_err(|e| format!("write_file failed: {e}"))
+    durable::write(&path, &content).map_err(|e| tr!("write_file failed: {}", e))?;
+    Ok(FileVersion { modified: modified_at(&path), hash: content_hash(content.as_bytes()) })
 }
 
+/// Inside the vault this goes to the trash so `undo_last_operation` can
+/// bring it back; elsewhere it is a plain delete
 #[tauri::command]
 pub fn delete_file(path: String) -> Result<(), String> {
     let p = PathBuf::from(&path);
+    if let Some(vault) = journaled_vault(&p) {
+        return journal::delete(&vault, &p).map(|_| ());
+    }
     if p.is_dir() {
         fs::remove_dir_all(&p).map_err(|e| e.to_string())
     } else {
@@ -63,12 +166,57 @@ pub fn create_dir_all(path: String) -> Result<(), String> {
     fs::create_dir_all(&path).map_err(|e| e.to_string())
 }
 
+/// Falls back to a checksum-verified copy when `dest` is on another
+/// filesystem, emitting `MOVE_PROGRESS_EVENT` for large transfers. Returns
+/// where it ended up, or None when skipped.
 #[tauri::command]
-pub fn move_file(src: String, dest: String) -> Result<(), String> {
-    if let Some(parent) = PathBuf::from(&dest).parent() {
-        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
-    fs::rename(&src, &dest).map_err(|e| e.to_string())
+pub async fn move_file(app: AppHandle, src: String, dest: String, on_collision: Option<Collision>) -> Result<Option<String>, String> {
+    tokio::task::spawn_blocking(move || {
+        let on_collision = on_collision.unwrap_or_default();
+        let (src_path, dest_path) = (PathBuf::from(&src), PathBuf::from(&dest));
+        // An overwritten vault file can be brought back from the trash
+        if on_collision == Collision::Overwrite && dest_path.exists() {
+            if let Some(vault) = journaled_vault(&dest_path) {
+                journal::delete(&vault, &dest_path)?;
+            }
+        }
+
+        let mut last = 0u64;
+        let mut progress = |bytes: u64, total: u64| {
+            if total < PROGRESS_MIN_BYTES || (bytes < total && bytes - last < total / 100) {
+                return;
+            }
+            last = bytes;
+            let payload = MoveProgress { src: src.clone(), dest: dest.clone(), bytes, total };
+            if let Err(e) = app.emit(MOVE_PROGRESS_EVENT, &payload) {
+                println!("[WARN] failed to emit {MOVE_PROGRESS_EVENT}: {e}");
+            }
+        };
+        let Some(moved) = transfer::move_path(&src_path, &dest_path, on_collision, &mut progress)? else {
+            return Ok(None);
+        };
+        if let Some(vault) = journaled_vault(&src_path).filter(|v| moved.starts_with(v)) {
+            // The move itself went through; only its undo is lost
+            if let Err(e) = journal::record_move(&vault, &src_path, &moved) {
+                println!("[WARN] journal: {e}");
+            }
+        }
+        Ok(Some(moved.to_string_lossy().to_string()))
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Apply moves, copies, deletes and creates in order as one change: when one
+/// fails, those before it are reversed. See `services::batch`.
+#[tauri::command]
+pub async fn batch_fs_operations(ops: Vec<BatchOp>) -> Result<BatchReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let vault = services::configured_vault().map(PathBuf::from);
+        batch::run(vault.as_deref(), &ops)
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))
 }
 
 #[tauri::command]
@@ -106,6 +254,71 @@ pub fn list_dir(path: String, recursive: bool) -> Result<Vec<DirEntry>, String>
     Ok(entries)
 }
 
+// ─────────────────────────────────────────────────────────────────────────────
+// Undo journal
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Deletes and moves made in the vault, newest first
+#[tauri::command]
+pub fn list_recent_operations(vault_path: String, limit: Option<usize>) -> Vec<Operation> {
+    journal::recent(Path::new(&vault_path), limit.unwrap_or(20))
+}
+
+/// Reverse the newest delete or move; None when there is nothing to undo
+#[tauri::command]
+pub fn undo_last_operation(vault_path: String) -> Result<Option<Operation>, String> {
+    journal::undo_last(Path::new(&vault_path))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Note locks
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Take or renew an advisory lock on a note for `ttl` seconds (default 5
+/// minutes); fails while another owner holds it
+#[tauri::command]
+pub fn lock_note(vault_path: String, path: String, owner: String, ttl: Option<u64>) -> Result<NoteLock, String> {
+    note_locks::acquire(Path::new(&vault_path), Path::new(&path), &owner, ttl.unwrap_or(note_locks::DEFAULT_TTL_SECS))
+}
+
+/// Release `owner`'s lock; `force` breaks someone else's
+#[tauri::command]
+pub fn unlock_note(vault_path: String, path: String, owner: String, force: Option<bool>) -> Result<bool, String> {
+    note_locks::release(Path::new(&vault_path), Path::new(&path), &owner, force.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn get_note_lock(vault_path: String, path: String) -> Result<Option<NoteLock>, String> {
+    note_locks::holder(Path::new(&vault_path), Path::new(&path))
+}
+
+#[tauri::command]
+pub fn list_note_locks(vault_path: String) -> Vec<NoteLock> {
+    note_locks::list(Path::new(&vault_path))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Note IDs
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Where the note with stable ID `id` is now, wherever it was moved
+#[tauri::command]
+pub async fn resolve_note_id(vault_path: String, id: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        note_ids::resolve(Path::new(&vault_path), &id)
+            .map(|p| p.to_string_lossy().to_string())
+            .ok_or_else(|| tr!("No note with ID {}", id))
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// The note's stable ID, written into its frontmatter when it has none yet
+#[tauri::command]
+pub fn assign_note_id(vault_path: String, path: String) -> Result<String, String> {
+    note_ids::assign(Path::new(&vault_path), Path::new(&path))
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Parsed Markdown note commands
 // ─────────────────────────────────────────────────────────────────────────────
@@ -117,16 +330,36 @@ pub fn read_note(path: String) -> Result<NoteFile, String> {
     parse_note(&path, &raw)
 }
 
-/// Write a note: accepts frontmatter as JSON + body string, serialises to file
+/// Write a note: accepts frontmatter as JSON + body string, serialises to file.
+/// `expected_hash`/`expected_modified` guard it as in `write_file`, against
+/// the `NoteFile` it was read as. Frontmatter is checked against the schema
+/// for the note's folder in the open vault first. A new note gets a stable
+/// `uid`, and an existing one keeps its own when `frontmatter` leaves it out.
 #[tauri::command]
-pub fn write_note(path: String, frontmatter: serde_json::Value, content: String) -> Result<(), String> {
+pub fn write_note(
+    path: String,
+    mut frontmatter: serde_json::Value,
+    content: String,
+    expected_hash: Option<String>,
+    expected_modified: Option<String>,
+) -> Result<FileVersion, WriteError> {
+    if let Some(vault) = services::configured_vault() {
+        let violations = schemas::check_note(Path::new(&vault), Path::new(&path), &frontmatter);
+        if !violations.is_empty() {
+            return Err(WriteError::Invalid { path, violations });
+        }
+    }
+    note_ids::fill(Path::new(&path), &mut frontmatter);
     let fm_str = json_to_yaml(&frontmatter);
     let full = format!("---\n{fm_str}---\n\n{content}");
 
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    check_version(&path, expected_hash.as_deref(), expected_modified.as_deref())?;
     if let Some(parent) = PathBuf::from(&path).parent() {
         fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
-    fs::write(&path, full).map_err(|e| e.to_string())
+    durable::write(&path, &full).map_err(|e| tr!("write_file failed: {}", e))?;
+    Ok(FileVersion { modified: modified_at(&path), hash: content_hash(full.as_bytes()) })
 }
 
 /// List all .md files under a directory, returning parsed notes
@@ -162,10 +395,39 @@ pub fn list_notes(dir: String, recursive: bool) -> Result<Vec<NoteFile>, String>
     Ok(notes)
 }
 
+/// Full-text search across the vault's notes
+#[tauri::command]
+pub fn search_notes(vault_path: String, query: String, limit: Option<usize>) -> Result<Vec<NoteMatch>, String> {
+    services::notes::search_notes(&vault_path, &query, limit.unwrap_or(100))
+}
+
+/// The note with its `![[note#section]]` embeds expanded, for rendering
+/// composite documents
+#[tauri::command]
+pub fn resolve_embeds(vault_path: String, path: String) -> Result<Resolved, String> {
+    services::embeds::resolve(Path::new(&vault_path), Path::new(&path))
+}
+
+/// Append a task to a day file (today when `date` is omitted); returns the file path
+#[tauri::command]
+pub fn add_task(vault_path: String, text: String, date: Option<String>) -> Result<String, String> {
+    let date = date.unwrap_or_else(|| chrono::Local::now().format("%Y-%m-%d").to_string());
+    services::tasks::add_task(&vault_path, &date, &text).map(|p| p.to_string_lossy().to_string())
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Helpers
 // ─────────────────────────────────────────────────────────────────────────────
 
+/// The configured vault when `path` is a note or folder in it. LifeOS's own
+/// files under .lifeos (trash included) aren't journaled.
+fn journaled_vault(path: &Path) -> Option<PathBuf> {
+    let vault = PathBuf::from(services::configured_vault()?);
+    let rel = path.strip_prefix(&vault).ok()?;
+    let first = rel.components().next()?;
+    (first.as_os_str() != ".lifeos").then_some(vault)
+}
+
 fn parse_note(path: &str, raw: &str) -> Result<NoteFile, String> {
     let p = PathBuf::from(path);
     let filename = p
@@ -173,14 +435,7 @@ fn parse_note(path: &str, raw: &str) -> Result<NoteFile, String> {
         .map(|n| n.to_string_lossy().to_string())
         .unwrap_or_default();
 
-    let meta = fs::metadata(path).ok();
-    let modified = meta
-        .and_then(|m| m.modified().ok())
-        .map(|t| {
-            let dt: chrono::DateTime<chrono::Local> = t.into();
-            dt.format("%Y-%m-%dT%H:%M:%S").to_string()
-        })
-        .unwrap_or_default();
+    let modified = modified_at(path);
 
     // Parse frontmatter
     let (frontmatter, content) = extract_frontmatter(raw);
@@ -191,33 +446,63 @@ fn parse_note(path: &str, raw: &str) -> Result<NoteFile, String> {
         frontmatter,
         content,
         modified,
+        hash: content_hash(raw.as_bytes()),
+    })
+}
+
+fn modified_at(path: &str) -> String {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(|t| {
+            let dt: chrono::DateTime<chrono::Local> = t.into();
+            dt.format("%Y-%m-%dT%H:%M:%S").to_string()
+        })
+        .unwrap_or_default()
+}
+
+fn content_hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Conflict unless the file is still the version the caller read; no
+/// expectations means an unguarded write
+fn check_version(path: &str, expected_hash: Option<&str>, expected_modified: Option<&str>) -> Result<(), WriteError> {
+    if expected_hash.is_none() && expected_modified.is_none() {
+        return Ok(());
+    }
+    let current = fs::read(path).ok();
+    let version = current.as_ref().map(|bytes| FileVersion { modified: modified_at(path), hash: content_hash(bytes) });
+    let unchanged = match &version {
+        Some(v) => expected_hash.is_none_or(|h| h == v.hash) && expected_modified.is_none_or(|m| m == v.modified),
+        None => expected_hash == Some(""),
+    };
+    if unchanged {
+        return Ok(());
+    }
+    Err(WriteError::Conflict {
+        path: path.to_string(),
+        content: current.map(|bytes| String::from_utf8_lossy(&bytes).into_owned()),
+        version,
     })
 }
 
 fn extract_frontmatter(raw: &str) -> (serde_json::Value, String) {
-    if raw.starts_with("---") {
-        let rest = &raw[3..];
-        if let Some(end) = rest.find("\n---") {
-            let yaml_str = &rest[..end];
-            let body = rest[end + 4..].trim_start().to_string();
-            // Simple YAML key:value parser (covers our needs without full yaml dep)
-            let mut map = serde_json::Map::new();
-            for line in yaml_str.lines() {
-                if let Some(colon) = line.find(':') {
-                    let key = line[..colon].trim().to_string();
-                    let val = line[colon + 1..].trim().trim_matches('"').to_string();
-                    if !key.is_empty() {
-                        map.insert(key, serde_json::Value::String(val));
-                    }
-                }
+    let (yaml, body) = split_frontmatter(raw);
+    // Simple YAML key:value parser (covers our needs without full yaml dep)
+    let mut map = serde_json::Map::new();
+    for line in yaml.unwrap_or_default().lines() {
+        if let Some(colon) = line.find(':') {
+            let key = line[..colon].trim().to_string();
+            let val = line[colon + 1..].trim().trim_matches('"').to_string();
+            if !key.is_empty() {
+                map.insert(key, serde_json::Value::String(val));
             }
-            return (serde_json::Value::Object(map), body);
         }
     }
-    (serde_json::Value::Object(serde_json::Map::new()), raw.to_string())
+    (serde_json::Value::Object(map), body.to_string())
 }
 
-fn json_to_yaml(val: &serde_json::Value) -> String {
+pub(crate) fn json_to_yaml(val: &serde_json::Value) -> String {
     match val {
         serde_json::Value::Object(map) => map
             .iter()
@@ -294,6 +579,36 @@ Real content here.";
         assert!(yaml.contains("optional: ~"));
     }
 
+    #[test]
+    fn test_write_file_conflict() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("note.md").to_string_lossy().to_string();
+        let created = write_file(path.clone(), "v1".into(), Some(String::new()), None).unwrap();
+        assert!(matches!(write_file(path.clone(), "again".into(), Some(String::new()), None), Err(WriteError::Conflict { .. })));
+
+        let read = read_file_versioned(path.clone()).unwrap();
+        assert_eq!(read.version, created);
+        // Someone else writes in between; the stale write gets their content back
+        write_file(path.clone(), "v2 from the agent".into(), Some(read.version.hash.clone()), None).unwrap();
+        match write_file(path.clone(), "v2 from the editor".into(), Some(read.version.hash), Some(read.version.modified)) {
+            Err(WriteError::Conflict { content, version, .. }) => {
+                assert_eq!(content.as_deref(), Some("v2 from the agent"));
+                assert_eq!(version.map(|v| v.hash), Some(content_hash(b"v2 from the agent")));
+            }
+            other => panic!("expected a conflict, got {other:?}"),
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap(), "v2 from the agent");
+        write_file(path, "unguarded".into(), None, None).unwrap();
+    }
+
+    #[test]
+    fn test_write_error_serialization() {
+        let failed = WriteError::Failed("write_file failed: denied".into());
+        assert_eq!(serde_json::to_value(&failed).unwrap(), serde_json::json!("write_file failed: denied"));
+        let conflict = WriteError::Conflict { path: "a.md".into(), content: None, version: None };
+        assert_eq!(serde_json::to_value(&conflict).unwrap()["kind"], "conflict");
+    }
+
     #[test]
     fn test_json_to_yaml_non_object() {
         let json = serde_json::json!("just a string");
@@ -0,0 +1,142 @@
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::services::habits::{self, HabitPause, PauseKind};
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HabitStreak {
+    pub id: String,
+    pub name: String,
+    pub current: u32,
+    pub longest: u32,
+    /// Under a vacation or freeze on the day asked about
+    pub paused: bool,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Streaks as of `date` (YYYY-MM-DD, default today), respecting pauses
+#[tauri::command]
+pub fn get_habit_streaks(vault_path: String, date: Option<String>) -> Result<Vec<HabitStreak>, String> {
+    let vault = Path::new(&vault_path);
+    let today = match date {
+        Some(d) => parse_date(&d)?,
+        None => Local::now().date_naive(),
+    };
+    let store = habits::load(vault);
+    let pauses = habits::load_pauses(vault);
+    Ok(store
+        .habits
+        .iter()
+        .map(|h| {
+            let streak = habits::streak(&store, &pauses, h, today);
+            HabitStreak {
+                id: h.id.clone(),
+                name: h.name.clone(),
+                current: streak.current,
+                longest: streak.longest,
+                paused: habits::paused_on(&pauses, &h.id, today),
+            }
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub fn list_habit_pauses(vault_path: String) -> Result<Vec<HabitPause>, String> {
+    Ok(habits::load_pauses(Path::new(&vault_path)))
+}
+
+/// Pause `habits` (all when empty) from `from` to `to` inclusive
+#[tauri::command]
+pub fn set_habit_vacation(
+    vault_path: String,
+    from: String,
+    to: String,
+    habits: Option<Vec<String>>,
+    note: Option<String>,
+) -> Result<HabitPause, String> {
+    if parse_date(&to)? < parse_date(&from)? {
+        return Err(tr!("Vacation ends before it starts: {} – {}", from, to));
+    }
+    add_pause(&vault_path, HabitPause { kind: PauseKind::Vacation, from, to, habits: habits.unwrap_or_default(), note: note.unwrap_or_default() })
+}
+
+/// Spend a freeze on one missed day
+#[tauri::command]
+pub fn freeze_habit_day(vault_path: String, date: String, habits: Option<Vec<String>>) -> Result<HabitPause, String> {
+    parse_date(&date)?;
+    add_pause(&vault_path, HabitPause { kind: PauseKind::Freeze, from: date.clone(), to: date, habits: habits.unwrap_or_default(), note: String::new() })
+}
+
+/// Remove the pauses of `kind` starting on `from`
+#[tauri::command]
+pub fn remove_habit_pause(vault_path: String, kind: PauseKind, from: String) -> Result<(), String> {
+    let vault = Path::new(&vault_path);
+    let mut pauses = habits::load_pauses(vault);
+    let before = pauses.len();
+    pauses.retain(|p| !(p.kind == kind && p.from == from));
+    if pauses.len() == before {
+        return Err(tr!("Habit pause not found: {}", from));
+    }
+    habits::save_pauses(vault, &pauses)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// An identical record already present is not duplicated
+fn add_pause(vault_path: &str, pause: HabitPause) -> Result<HabitPause, String> {
+    let vault = Path::new(vault_path);
+    let mut pauses = habits::load_pauses(vault);
+    if !pauses.contains(&pause) {
+        pauses.push(pause.clone());
+        habits::save_pauses(vault, &pauses)?;
+    }
+    Ok(pause)
+}
+
+fn parse_date(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| tr!("Invalid date (expected YYYY-MM-DD): {}", s))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_vacation_keeps_streak() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path().to_string_lossy().to_string();
+        let path = vault.path().join(habits::HABITS_FILE);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "habits:\n  - id: read\n    name: 阅读\n    created: \"2025-07-01\"\ncheckins:\n  2025-07-01: [read]\n  2025-07-02: [read]\n  2025-07-10: [read]\n").unwrap();
+
+        assert_eq!(get_habit_streaks(v.clone(), Some("2025-07-10".into())).unwrap()[0].current, 1);
+        assert!(set_habit_vacation(v.clone(), "2025-07-09".into(), "2025-07-03".into(), None, None).is_err());
+        set_habit_vacation(v.clone(), "2025-07-03".into(), "2025-07-08".into(), None, Some("京都".into())).unwrap();
+        freeze_habit_day(v.clone(), "2025-07-09".into(), Some(vec!["read".into()])).unwrap();
+        freeze_habit_day(v.clone(), "2025-07-09".into(), Some(vec!["read".into()])).unwrap();
+        assert_eq!(list_habit_pauses(v.clone()).unwrap().len(), 2);
+
+        let streak = &get_habit_streaks(v.clone(), Some("2025-07-10".into())).unwrap()[0];
+        assert_eq!((streak.current, streak.longest, streak.paused), (3, 3, false));
+        assert!(get_habit_streaks(v.clone(), Some("2025-07-05".into())).unwrap()[0].paused);
+
+        remove_habit_pause(v.clone(), PauseKind::Freeze, "2025-07-09".into()).unwrap();
+        assert!(remove_habit_pause(v.clone(), PauseKind::Freeze, "2025-07-09".into()).is_err());
+        assert_eq!(get_habit_streaks(v, Some("2025-07-10".into())).unwrap()[0].current, 1);
+    }
+}
@@ -0,0 +1,65 @@
+use std::path::{Path, PathBuf};
+
+use crate::services::automations::vault_file;
+use crate::services::history::{self, NoteDiff, NoteVersion};
+use crate::services::notes::vault_rel;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Git commits touching the note plus its snapshots, newest first
+#[tauri::command]
+pub async fn list_note_versions(vault_path: String, path: String) -> Result<Vec<NoteVersion>, String> {
+    tokio::task::spawn_blocking(move || {
+        let rel = note_rel(&vault_path, &path)?;
+        Ok(history::versions(Path::new(&vault_path), &rel))
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Snapshot the note as it is now; None when nothing changed since the last one
+#[tauri::command]
+pub fn snapshot_note(vault_path: String, path: String) -> Result<Option<NoteVersion>, String> {
+    let rel = note_rel(&vault_path, &path)?;
+    history::snapshot(Path::new(&vault_path), &rel)
+}
+
+/// Text of the note at a version id from `list_note_versions` (or "current")
+#[tauri::command]
+pub async fn get_note_version(vault_path: String, path: String, version: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let rel = note_rel(&vault_path, &path)?;
+        history::content_at(Path::new(&vault_path), &rel, &version)
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Line diff of the note between two versions; `to` defaults to "current"
+#[tauri::command]
+pub async fn get_note_diff(vault_path: String, path: String, from: String, to: Option<String>) -> Result<NoteDiff, String> {
+    tokio::task::spawn_blocking(move || {
+        let vault = PathBuf::from(&vault_path);
+        let rel = note_rel(&vault_path, &path)?;
+        let to = to.unwrap_or_else(|| history::CURRENT.to_string());
+        let old = history::content_at(&vault, &rel, &from)?;
+        let new = history::content_at(&vault, &rel, &to)?;
+        Ok(NoteDiff { from, to, ..history::diff(&old, &new) })
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Vault-relative path with forward slashes, from an absolute or relative input
+fn note_rel(vault_path: &str, path: &str) -> Result<String, String> {
+    let vault = Path::new(vault_path);
+    let rel = vault_rel(vault, Path::new(path));
+    vault_file(vault, &rel)?;
+    Ok(rel.trim_start_matches('/').to_string())
+}
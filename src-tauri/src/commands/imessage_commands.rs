@@ -0,0 +1,169 @@
+use std::path::{Path, PathBuf};
+
+use super::chat_import_commands::{file_messages, ChatImportReport, ChatMessage};
+#[cfg(not(target_os = "macos"))]
+use super::platform_commands::open_permission_settings;
+use crate::services::imessage::{self, Conversation};
+use crate::services::people::{load_people, Person};
+use crate::services::unsupported;
+
+/// Messages shown per conversation on a person's page
+const RECENT_MESSAGES: usize = 5;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// "granted" | "denied" | "missing", or "unsupported" off macOS. On "denied"
+/// the app needs Full Disk Access (see `open_full_disk_access_settings`).
+#[tauri::command]
+pub fn get_imessage_access() -> String {
+    match chat_db() {
+        Ok(db) => imessage::access(&db).to_string(),
+        Err(_) => "unsupported".to_string(),
+    }
+}
+
+/// Open System Settings at Privacy & Security → Full Disk Access
+#[tauri::command]
+pub fn open_full_disk_access_settings() -> Result<(), String> {
+    open_permission_settings("full_disk_access".to_string())
+}
+
+/// Conversations in Messages, most recent first, with the people their
+/// handles belong to
+#[tauri::command]
+pub async fn list_imessage_conversations(vault_path: String, limit: Option<usize>) -> Result<Vec<Conversation>, String> {
+    tokio::task::spawn_blocking(move || {
+        let people = load_people(Path::new(&vault_path));
+        let mut conversations = imessage::conversations(&chat_db()?)?;
+        conversations.truncate(limit.unwrap_or(50));
+        for conversation in &mut conversations {
+            conversation.people = linked_people(&people, &conversation.handles).iter().map(|p| p.slug.clone()).collect();
+        }
+        Ok(conversations)
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// The person's conversations (matched on their `emails` and `phones`),
+/// each with its latest few messages, for their People page
+#[tauri::command]
+pub async fn get_person_imessages(vault_path: String, slug: String, limit: Option<usize>) -> Result<Vec<Conversation>, String> {
+    tokio::task::spawn_blocking(move || {
+        let people = load_people(Path::new(&vault_path));
+        let person = people.iter().find(|p| p.slug == slug).ok_or_else(|| tr!("Person not found: {}", slug))?;
+        let handles = handles_of(person);
+        if handles.is_empty() {
+            return Ok(Vec::new());
+        }
+        let db = chat_db()?;
+        let mut conversations: Vec<Conversation> = imessage::conversations(&db)?
+            .into_iter()
+            .filter(|c| c.handles.iter().any(|h| handles.iter().any(|own| imessage::same_handle(h, own))))
+            .take(limit.unwrap_or(5))
+            .collect();
+        for conversation in &mut conversations {
+            conversation.people = linked_people(&people, &conversation.handles).iter().map(|p| p.slug.clone()).collect();
+            conversation.recent = imessage::messages(&db, conversation.chat_id, Some(RECENT_MESSAGES))?;
+        }
+        Ok(conversations)
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Copy whole threads into connectors/chats/ day notes, the same way
+/// `import_chat` files exports. Archiving a thread again only adds messages
+/// that came in since; `dry_run` reports without writing.
+#[tauri::command]
+pub async fn archive_imessage_threads(vault_path: String, chat_ids: Vec<i64>, dry_run: Option<bool>) -> Result<Vec<ChatImportReport>, String> {
+    tokio::task::spawn_blocking(move || {
+        let vault = Path::new(&vault_path);
+        let db = chat_db()?;
+        let people = load_people(vault);
+        let conversations = imessage::conversations(&db)?;
+        let mut reports = Vec::new();
+        for chat_id in chat_ids {
+            let conversation = conversations.iter().find(|c| c.chat_id == chat_id).ok_or_else(|| tr!("Conversation not found: {}", chat_id))?;
+            let messages = imessage::messages(&db, chat_id, None)?;
+            let messages = messages
+                .into_iter()
+                .map(|m| ChatMessage { at: m.at, sender: sender_name(&people, m.from_me, &m.handle), text: m.text })
+                .collect();
+            reports.push(file_messages(vault, &title(&people, conversation), "imessage", messages, dry_run.unwrap_or(false))?);
+        }
+        Ok(reports)
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(target_os = "macos")]
+fn chat_db() -> Result<PathBuf, String> {
+    let home = std::env::var_os("HOME").ok_or_else(|| tr!("Cannot find home dir"))?;
+    Ok(PathBuf::from(home).join(imessage::CHAT_DB))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn chat_db() -> Result<PathBuf, String> {
+    Err(unsupported("iMessage"))
+}
+
+/// The person's `emails` and `phones`
+fn handles_of(person: &Person) -> Vec<String> {
+    let phones = person.meta.extra.get("phones").and_then(|v| v.as_sequence());
+    let phones = phones.into_iter().flatten().filter_map(|p| p.as_str()).map(str::to_string);
+    person.meta.emails.iter().cloned().chain(phones).collect()
+}
+
+fn linked_people<'a>(people: &'a [Person], handles: &[String]) -> Vec<&'a Person> {
+    people.iter().filter(|p| handles_of(p).iter().any(|own| handles.iter().any(|h| imessage::same_handle(h, own)))).collect()
+}
+
+/// The person's name when the handle is theirs, so the day notes link them
+fn sender_name(people: &[Person], from_me: bool, handle: &str) -> String {
+    if from_me {
+        return tr!("Me");
+    }
+    linked_people(people, &[handle.to_string()]).first().map_or_else(|| handle.to_string(), |p| p.meta.name.clone())
+}
+
+/// Group name, else the participants' names (or handles)
+fn title(people: &[Person], conversation: &Conversation) -> String {
+    if !conversation.name.trim().is_empty() {
+        return conversation.name.trim().to_string();
+    }
+    let names: Vec<String> = conversation.handles.iter().map(|h| sender_name(people, false, h)).collect();
+    names.join(", ")
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_names_from_handles() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("people")).unwrap();
+        fs::write(dir.path().join("people/alice-chen.md"), "---\nname: Alice Chen\nemails:\n- alice@example.com\nphones:\n- +86 138 0013 8000\n---\n").unwrap();
+        let people = load_people(dir.path());
+
+        assert_eq!(sender_name(&people, false, "13800138000"), "Alice Chen");
+        assert_eq!(sender_name(&people, false, "+14155551234"), "+14155551234");
+        let chat = Conversation { handles: vec!["ALICE@example.com".into(), "+14155551234".into()], ..Default::default() };
+        assert_eq!(title(&people, &chat), "Alice Chen, +14155551234");
+        assert_eq!(linked_people(&people, &chat.handles).len(), 1);
+        assert_eq!(title(&people, &Conversation { name: " Family ".into(), ..chat }), "Family");
+    }
+}
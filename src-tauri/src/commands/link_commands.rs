@@ -0,0 +1,506 @@
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::screenshot_commands::relative_link;
+use crate::services::journal;
+use crate::services::notes::{split_frontmatter, vault_rel};
+
+/// Frontmatter keys naming other notes: (key, kind, folder the notes live in)
+const REFERENCE_KEYS: &[(&str, &str, &str)] = &[
+    ("project", "project", "projects"),
+    ("projects", "project", "projects"),
+    ("goal", "goal", "planning/goals"),
+    ("goals", "goal", "planning/goals"),
+];
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BrokenLink {
+    /// Vault-relative note
+    pub path: String,
+    /// 1-based; the line `target` is written on
+    pub line: usize,
+    /// "note" | "asset" | "project" | "goal"
+    pub kind: String,
+    /// As written: inside `[[…]]` (before `|` or `#`), inside `(…)`, or the
+    /// frontmatter value without its brackets
+    pub target: String,
+    /// Where the target is now, written the same way, when it was moved or
+    /// renamed. `fix_links` puts it in place of `target`.
+    pub fix: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LinkReport {
+    /// Notes checked
+    pub notes: usize,
+    /// Links found in them
+    pub links: usize,
+    /// By note, then line
+    pub broken: Vec<BrokenLink>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Style {
+    /// `[[target]]` and `![[target]]`
+    Wiki,
+    /// `[text](target)` and `![alt](target)`
+    Markdown,
+    /// (kind, folder)
+    Frontmatter(&'static str, &'static str),
+}
+
+struct Link {
+    /// 1-based
+    line: usize,
+    style: Style,
+    target: String,
+    /// As written after `#`
+    heading: Option<String>,
+}
+
+/// Every file in the vault by lowercased file name, vault-relative
+type FileIndex = HashMap<String, Vec<String>>;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Broken wikilinks, markdown links and embeds (notes or assets), and
+/// `project`/`goal` frontmatter naming notes that don't exist, in the notes
+/// under `scope` (a vault-relative folder or note; default the whole vault).
+/// A target that was moved or renamed gets a `fix`: moves in the undo
+/// journal are followed first, else a single file elsewhere in the vault
+/// with the same name is taken.
+#[tauri::command]
+pub async fn check_links(vault_path: String, scope: Option<String>) -> Result<LinkReport, String> {
+    tokio::task::spawn_blocking(move || check(Path::new(&vault_path), scope.as_deref()))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Write the `fix` of each link into its note; returns how many changed.
+/// A link whose line no longer holds its target is left alone. `dry_run`
+/// counts the links that would change without writing.
+#[tauri::command]
+pub fn fix_links(vault_path: String, links: Vec<BrokenLink>, dry_run: Option<bool>) -> Result<usize, String> {
+    let vault = Path::new(&vault_path);
+    let mut by_note: BTreeMap<&str, Vec<&BrokenLink>> = BTreeMap::new();
+    for link in links.iter().filter(|l| l.fix.is_some()) {
+        by_note.entry(link.path.as_str()).or_default().push(link);
+    }
+    let mut fixed = 0;
+    for (rel, links) in by_note {
+        let path = vault.join(rel);
+        let raw = fs::read_to_string(&path).map_err(|e| tr!("Failed to read: {}", e))?;
+        let mut lines: Vec<String> = raw.split('\n').map(str::to_string).collect();
+        let mut changed = false;
+        for link in links {
+            let Some(line) = link.line.checked_sub(1).and_then(|i| lines.get_mut(i)) else { continue };
+            if let Some(updated) = replace_target(line, &link.target, link.fix.as_deref().unwrap_or_default()) {
+                *line = updated;
+                fixed += 1;
+                changed = true;
+            }
+        }
+        if changed && !dry_run.unwrap_or(false) {
+            fs::write(&path, lines.join("\n")).map_err(|e| tr!("write_file failed: {}", e))?;
+        }
+    }
+    Ok(fixed)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Link rewriting
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Point every link at a note in `moved` (vault-relative, old → new) at its
+/// new place, written the way the link was; returns how many changed. A key
+/// can name a section, `note.md#heading` with the heading lowercased, to
+/// move only the links to it (and drop their `#heading`). Links are matched
+/// by the file they resolve to, so this runs while the old notes still
+/// exist.
+pub(crate) fn retarget_links(vault: &Path, moved: &BTreeMap<String, String>) -> Result<usize, String> {
+    let files = file_index(vault);
+    let mut retargeted = 0;
+    for entry in WalkDir::new(vault)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|x| x == "md"))
+    {
+        let Ok(raw) = fs::read_to_string(entry.path()) else { continue };
+        let note = entry.path().strip_prefix(vault).unwrap_or(entry.path());
+        let dir = note.parent().unwrap_or(Path::new(""));
+        let mut lines: Vec<String> = raw.split('\n').map(str::to_string).collect();
+        let mut changed = 0;
+        for link in links_in(&raw) {
+            let Some(rel) = resolve(vault, dir, &link, &files) else { continue };
+            let section = link.heading.as_ref().and_then(|h| Some((format!("{}#{h}", link.target), moved.get(&format!("{rel}#{}", h.replace("%20", " ").trim().to_lowercase()))?)));
+            let Some((old, now)) = section.or_else(|| Some((link.target.clone(), moved.get(&rel)?))) else { continue };
+            let Some(line) = link.line.checked_sub(1).and_then(|i| lines.get_mut(i)) else { continue };
+            if let Some(updated) = replace_target(line, &old, &written_as(&link, dir, now)) {
+                *line = updated;
+                changed += 1;
+            }
+        }
+        if changed > 0 {
+            fs::write(entry.path(), lines.join("\n")).map_err(|e| tr!("write_file failed: {}", e))?;
+            retargeted += changed;
+        }
+    }
+    Ok(retargeted)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn check(vault: &Path, scope: Option<&str>) -> Result<LinkReport, String> {
+    let root = match scope.map(|s| s.trim().trim_matches('/')).filter(|s| !s.is_empty()) {
+        Some(scope) => vault.join(scope),
+        None => vault.to_path_buf(),
+    };
+    if !root.exists() {
+        return Err(tr!("File not found: {}", root.display()));
+    }
+    let files = file_index(vault);
+    let moves = journaled_moves(vault);
+    let mut report = LinkReport::default();
+    for entry in WalkDir::new(&root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|x| x == "md"))
+    {
+        let Ok(raw) = fs::read_to_string(entry.path()) else { continue };
+        let note = entry.path().strip_prefix(vault).unwrap_or(entry.path()).to_path_buf();
+        report.notes += 1;
+        for link in links_in(&raw) {
+            report.links += 1;
+            if let Some(broken) = check_link(vault, &note, link, &files, &moves) {
+                report.broken.push(broken);
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Links in the body outside code fences, plus project/goal frontmatter
+fn links_in(raw: &str) -> Vec<Link> {
+    let lines: Vec<&str> = raw.lines().collect();
+    let mut links = Vec::new();
+    let mut body_start = 0;
+    if let (Some(yaml), true) = (split_frontmatter(raw).0, raw.starts_with("---")) {
+        let close = lines.iter().skip(1).position(|l| l.trim_end() == "---").map_or(0, |i| i + 1);
+        body_start = close + 1;
+        let fm: Value = serde_yaml::from_str(yaml).unwrap_or_default();
+        for &(key, kind, folder) in REFERENCE_KEYS {
+            let values: Vec<&str> = match fm.get(key) {
+                Some(Value::String(s)) => vec![s.as_str()],
+                Some(Value::Sequence(items)) => items.iter().filter_map(|v| v.as_str()).collect(),
+                _ => continue,
+            };
+            for value in values {
+                let name = value.trim().trim_start_matches("[[").trim_end_matches("]]");
+                let name = name.split('|').next().unwrap_or_default().trim();
+                if name.is_empty() {
+                    continue;
+                }
+                if let Some(line) = lines[..close].iter().position(|l| l.contains(name)) {
+                    links.push(Link { line: line + 1, style: Style::Frontmatter(kind, folder), target: name.to_string(), heading: None });
+                }
+            }
+        }
+    }
+
+    let mut in_fence = false;
+    for (i, line) in lines.iter().enumerate().skip(body_start) {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        for (open, close, style) in [("[[", "]]", Style::Wiki), ("](", ")", Style::Markdown)] {
+            let mut rest = *line;
+            while let Some(start) = rest.find(open) {
+                rest = &rest[start + open.len()..];
+                let Some(end) = rest.find(close) else { break };
+                let inner = &rest[..end];
+                rest = &rest[end + close.len()..];
+                let inner = match style {
+                    Style::Wiki => inner.split('|').next().unwrap_or_default().trim(),
+                    _ => {
+                        let inner = inner.trim().trim_start_matches('<');
+                        inner.split(" \"").next().unwrap_or(inner).trim_end_matches('>')
+                    }
+                };
+                let (target, heading) = match inner.split_once('#') {
+                    Some((target, heading)) => (target, Some(heading.to_string())),
+                    None => (inner, None),
+                };
+                let target = if style == Style::Wiki { target.trim() } else { target.split('?').next().unwrap_or_default() };
+                let external = target.contains("://") || target.starts_with("mailto:");
+                if !target.is_empty() && !external {
+                    links.push(Link { line: i + 1, style, target: target.to_string(), heading });
+                }
+            }
+        }
+    }
+    links
+}
+
+fn check_link(vault: &Path, note: &Path, link: Link, files: &FileIndex, moves: &[(String, String)]) -> Option<BrokenLink> {
+    let dir = note.parent().unwrap_or(Path::new(""));
+    let Located { target, is_note, with_ext, candidates } = locate(dir, &link);
+    let by_name = || files.contains_key(&file_name(&with_ext).to_lowercase());
+    let found = candidates.iter().any(|c| vault.join(c).exists());
+    let by_name_ok = matches!(link.style, Style::Wiki | Style::Frontmatter(..)) && !target.contains('/') && by_name();
+    if found || by_name_ok {
+        return None;
+    }
+
+    // Moved per the journal, else the one file in the vault with that name
+    let bare = !target.contains('/') && !matches!(link.style, Style::Markdown);
+    let moved = candidates.iter().find_map(|c| follow_moves(vault, &c.to_string_lossy().replace('\\', "/"), moves)).or_else(|| {
+        let name = file_name(&with_ext).to_lowercase();
+        let from = moves.iter().rev().find(|(from, _)| bare && file_name(from).to_lowercase() == name)?;
+        follow_moves(vault, &from.0, moves)
+    });
+    let moved = moved.or_else(|| match files.get(&file_name(&with_ext).to_lowercase()).map(Vec::as_slice) {
+        Some([only]) => Some(only.clone()),
+        _ => None,
+    });
+    let fix = moved.map(|now| written_as(&link, dir, &now));
+
+    let kind = match link.style {
+        Style::Frontmatter(kind, _) => kind,
+        _ if is_note => "note",
+        _ => "asset",
+    };
+    Some(BrokenLink { path: note.to_string_lossy().replace('\\', "/"), line: link.line, kind: kind.into(), target: link.target, fix })
+}
+
+/// A link's target as a path: `%20` decoded, and `.md` added for notes
+struct Located {
+    target: String,
+    is_note: bool,
+    with_ext: String,
+    /// Vault-relative places the target could be
+    candidates: Vec<PathBuf>,
+}
+
+fn locate(dir: &Path, link: &Link) -> Located {
+    let target = link.target.replace("%20", " ");
+    let is_note = Path::new(&target).extension().is_none_or(|x| x == "md");
+    let with_ext = if is_note && !target.ends_with(".md") { format!("{target}.md") } else { target.clone() };
+    let candidates = match link.style {
+        Style::Wiki => vec![dir.join(&with_ext), PathBuf::from(&with_ext)],
+        Style::Markdown if target.starts_with('/') => vec![PathBuf::from(target.trim_start_matches('/'))],
+        Style::Markdown => vec![dir.join(&target), PathBuf::from(target.trim_start_matches("./"))],
+        Style::Frontmatter(_, folder) => vec![Path::new(folder).join(&with_ext)],
+    };
+    let candidates = candidates.iter().map(|c| lexical(c)).collect();
+    Located { target, is_note, with_ext, candidates }
+}
+
+/// The vault-relative file `link`, in a note in `dir`, points at now
+fn resolve(vault: &Path, dir: &Path, link: &Link, files: &FileIndex) -> Option<String> {
+    let Located { target, with_ext, candidates, .. } = locate(dir, link);
+    if let Some(found) = candidates.iter().find(|c| vault.join(c).exists()) {
+        return Some(found.to_string_lossy().replace('\\', "/"));
+    }
+    let by_name = matches!(link.style, Style::Wiki | Style::Frontmatter(..)) && !target.contains('/');
+    match files.get(&file_name(&with_ext).to_lowercase()).map(Vec::as_slice) {
+        Some([only]) if by_name => Some(only.clone()),
+        _ => None,
+    }
+}
+
+/// How `link`, in a note in `dir`, would name the vault-relative `now`
+fn written_as(link: &Link, dir: &Path, now: &str) -> String {
+    let target = link.target.replace("%20", " ");
+    match link.style {
+        Style::Markdown if link.target.starts_with('/') => encode(&format!("/{now}"), &link.target),
+        Style::Markdown => encode(&relative_link(dir, Path::new(now)), &link.target),
+        _ if target.contains('/') => strip_md(now, &target),
+        _ => strip_md(&file_name(now), &target),
+    }
+}
+
+/// Where `rel` ended up after the journaled moves (of it or a folder above
+/// it), if that still exists
+fn follow_moves(vault: &Path, rel: &str, moves: &[(String, String)]) -> Option<String> {
+    let mut current = rel.to_string();
+    for (from, to) in moves {
+        if current == *from {
+            current = to.clone();
+        } else if let Some(rest) = current.strip_prefix(&format!("{from}/")) {
+            current = format!("{to}/{rest}");
+        }
+    }
+    (current != rel && vault.join(&current).exists()).then_some(current)
+}
+
+/// (from, to) of every journaled move, oldest first
+fn journaled_moves(vault: &Path) -> Vec<(String, String)> {
+    let mut moves: Vec<(String, String)> = journal::recent(vault, usize::MAX)
+        .into_iter()
+        .filter(|op| op.kind == "move")
+        .filter_map(|op| Some((op.path, op.dest?)))
+        .collect();
+    moves.reverse();
+    moves
+}
+
+fn file_index(vault: &Path) -> FileIndex {
+    let mut index = FileIndex::new();
+    for entry in WalkDir::new(vault)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        let rel = vault_rel(vault, entry.path());
+        index.entry(entry.file_name().to_string_lossy().to_lowercase()).or_default().push(rel);
+    }
+    index
+}
+
+/// `a/./b/../c` → `a/c`, without touching the filesystem
+fn lexical(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn file_name(path: &str) -> String {
+    path.rsplit('/').next().unwrap_or(path).to_string()
+}
+
+/// Drop `.md` when the original target was written without it
+fn strip_md(path: &str, original: &str) -> String {
+    match path.strip_suffix(".md") {
+        Some(stem) if !original.ends_with(".md") => stem.to_string(),
+        _ => path.to_string(),
+    }
+}
+
+/// Spaces as `%20` when the original target wrote them that way
+fn encode(path: &str, original: &str) -> String {
+    if original.contains("%20") {
+        path.replace(' ', "%20")
+    } else {
+        path.to_string()
+    }
+}
+
+/// `target` replaced right after `[[` or `(` when it appears that way, so
+/// link text that happens to match is not touched
+fn replace_target(line: &str, target: &str, fix: &str) -> Option<String> {
+    ["[[", "(", ""].iter().find_map(|open| {
+        let needle = format!("{open}{target}");
+        let at = line.find(&needle)?;
+        Some(format!("{}{open}{fix}{}", &line[..at], &line[at + needle.len()..]))
+    })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, rel: &str, text: &str) {
+        let path = dir.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    #[test]
+    fn test_check_and_fix_links() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        let path = v.to_string_lossy().to_string();
+        write(v, "projects/官网改版.md", "# 官网改版\n");
+        write(v, "projects/old-name.md", "# 旧名\n");
+        write(v, "assets/pic.png", "png");
+        write(v, "assets/images/cat one.png", "png");
+        fs::rename(v.join("projects/old-name.md"), v.join("projects/new-name.md")).unwrap();
+        journal::record_move(v, &v.join("projects/old-name.md"), &v.join("projects/new-name.md")).unwrap();
+        write(
+            v,
+            "diary/2026/a.md",
+            "---\ndate: 2026-03-10\nproject: '[[官网改版]]'\ngoals:\n  - 跑半马\n---\n见 [[官网改版|改版]] 和 [[old-name#进度]]、[[不存在]]\n![图](../../assets/pic.png) ![[cat%20one.png]]\n[猫](../../assets/cat%20one.png) [站点](https://example.com) [[#本页]]\n```\n[[代码里的]]\n```\n",
+        );
+
+        let report = check(v, None).unwrap();
+        assert_eq!((report.notes, report.links), (3, 8));
+        let broken: Vec<(usize, &str, &str, Option<&str>)> =
+            report.broken.iter().map(|b| (b.line, b.kind.as_str(), b.target.as_str(), b.fix.as_deref())).collect();
+        assert_eq!(
+            broken,
+            vec![
+                (5, "goal", "跑半马", None),
+                (7, "note", "old-name", Some("new-name")),
+                (7, "note", "不存在", None),
+                (9, "asset", "../../assets/cat%20one.png", Some("../../assets/images/cat%20one.png")),
+            ]
+        );
+
+        let before = fs::read_to_string(v.join("diary/2026/a.md")).unwrap();
+        assert_eq!(fix_links(path.clone(), report.broken.clone(), Some(true)).unwrap(), 2);
+        assert_eq!(fs::read_to_string(v.join("diary/2026/a.md")).unwrap(), before);
+        assert_eq!(fix_links(path.clone(), report.broken.clone(), None).unwrap(), 2);
+        let note = fs::read_to_string(v.join("diary/2026/a.md")).unwrap();
+        assert!(note.contains("[[new-name#进度]]") && note.contains("[猫](../../assets/images/cat%20one.png)"));
+        // Applied once; the stale report no longer matches
+        assert_eq!(fix_links(path, report.broken, None).unwrap(), 0);
+        let report = check(v, Some("diary")).unwrap();
+        assert_eq!((report.notes, report.broken.len()), (1, 2));
+        assert!(check(v, Some("nope")).is_err());
+    }
+
+    #[test]
+    fn test_retarget_links() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        write(v, "meetings/sync-a.md", "# A\n");
+        write(v, "meetings/sync-b.md", "# B\n");
+        write(v, "meetings/weekly.md", "# Weekly\n");
+        write(v, "diary/2026/a.md", "见 [[sync-a#决定]]、[B](../../meetings/sync-b.md) 和 [[weekly]]\n");
+        write(v, "projects/x.md", "[[meetings/sync-a|A]]\n");
+
+        let moved = BTreeMap::from([
+            ("meetings/sync-a.md".to_string(), "meetings/weekly.md".to_string()),
+            ("meetings/sync-b.md".to_string(), "meetings/weekly.md".to_string()),
+        ]);
+        assert_eq!(retarget_links(v, &moved).unwrap(), 3);
+        let diary = fs::read_to_string(v.join("diary/2026/a.md")).unwrap();
+        assert_eq!(diary, "见 [[weekly#决定]]、[B](../../meetings/weekly.md) 和 [[weekly]]\n");
+        assert_eq!(fs::read_to_string(v.join("projects/x.md")).unwrap(), "[[meetings/weekly|A]]\n");
+    }
+}
@@ -0,0 +1,530 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::services::periodic::Periodic;
+use crate::services::{durable, tasks};
+
+/// Opt-in switch and capture settings
+const SETTINGS_FILE: &str = ".lifeos/location.yaml";
+/// Raw fixes, one file per day: .lifeos/location/{YYYY-MM-DD}.yaml
+const SAMPLES_DIR: &str = ".lifeos/location";
+/// Lines appended by a Shortcuts automation (phone or Mac):
+/// `<RFC 3339 time>,<lat>,<lon>[,<place name>]`
+const INBOX_FILE: &str = ".lifeos/location-inbox.txt";
+/// Daily-note frontmatter key holding the day's places
+const PLACES_KEY: &str = "places";
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LocationSettings {
+    /// Nothing is recorded, imported or written unless this is on
+    #[serde(default)]
+    pub enabled: bool,
+    /// "shortcut" (macOS Shortcuts printing `lat,lon`), "command" (e.g.
+    /// CoreLocationCLI) or "inbox" (only read the inbox file)
+    #[serde(default = "default_source")]
+    pub source: String,
+    #[serde(default = "default_shortcut")]
+    pub shortcut: String,
+    #[serde(default = "default_command")]
+    pub command: String,
+    #[serde(default = "default_interval")]
+    pub interval_minutes: u64,
+    /// Shortest stay that counts as a place
+    #[serde(default = "default_min_stay")]
+    pub min_stay_minutes: i64,
+    /// Fixes within this distance belong to the same place
+    #[serde(default = "default_radius")]
+    pub radius_m: f64,
+    /// Named places, e.g. 家 / 公司; matched before falling back to coordinates
+    #[serde(default)]
+    pub places: Vec<NamedPlace>,
+}
+
+fn default_source() -> String {
+    "inbox".to_string()
+}
+fn default_shortcut() -> String {
+    "LifeOS 定位".to_string()
+}
+fn default_command() -> String {
+    "CoreLocationCLI -once -format \"%latitude,%longitude\"".to_string()
+}
+fn default_interval() -> u64 {
+    10
+}
+fn default_min_stay() -> i64 {
+    15
+}
+fn default_radius() -> f64 {
+    200.0
+}
+
+impl Default for LocationSettings {
+    fn default() -> Self {
+        serde_yaml::from_str("{}").expect("defaults")
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NamedPlace {
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Sample {
+    /// HH:MM:SS local time
+    pub time: String,
+    pub lat: f64,
+    pub lon: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Visit {
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    /// HH:MM
+    pub arrived: String,
+    pub left: String,
+    pub minutes: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PurgeReport {
+    pub days: usize,
+    pub notes: usize,
+    /// Counted only; nothing was deleted
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+// Only one logger loop, bound to the open vault
+static LOGGER: Periodic = Periodic::new();
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn get_location_settings(vault_path: String) -> LocationSettings {
+    load_settings(Path::new(&vault_path))
+}
+
+/// Saving with `enabled: false` also stops the logger; recorded history is
+/// kept until `purge_location_history`
+#[tauri::command]
+pub fn save_location_settings(vault_path: String, settings: LocationSettings) -> Result<LocationSettings, String> {
+    let path = Path::new(&vault_path).join(SETTINGS_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let yaml = serde_yaml::to_string(&settings).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(&path, yaml).map_err(|e| tr!("write_file failed: {}", e))?;
+    if !settings.enabled {
+        stop_location_logger();
+    }
+    Ok(settings)
+}
+
+/// Add one fix (e.g. from the webview's geolocation on mobile); `at`
+/// defaults to now. Returns that day's places.
+#[tauri::command]
+pub fn record_location(
+    vault_path: String,
+    lat: f64,
+    lon: f64,
+    at: Option<String>,
+    name: Option<String>,
+) -> Result<Vec<Visit>, String> {
+    let vault = Path::new(&vault_path);
+    let settings = enabled_settings(vault)?;
+    let at = match at.as_deref() {
+        Some(raw) => parse_time(raw).ok_or_else(|| tr!("Invalid date/time: {}", raw))?,
+        None => Local::now().naive_local(),
+    };
+    add_samples(vault, &settings, vec![(at, sample(at, lat, lon, name)?)])?;
+    Ok(visits(&read_samples(vault, at.date()), &settings))
+}
+
+/// Move the inbox lines into the history; returns how many were recorded,
+/// or with `dry_run` how many would be, leaving the inbox as it is
+#[tauri::command]
+pub fn import_location_inbox(vault_path: String, dry_run: Option<bool>) -> Result<usize, String> {
+    let vault = Path::new(&vault_path);
+    let settings = enabled_settings(vault)?;
+    if dry_run.unwrap_or(false) {
+        return Ok(read_inbox(vault).map_or(0, |parsed| parsed.len()));
+    }
+    import_inbox(vault, &settings)
+}
+
+#[tauri::command]
+pub fn get_location_day(vault_path: String, date: String) -> Result<Vec<Visit>, String> {
+    let vault = Path::new(&vault_path);
+    let date = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|_| tr!("Invalid date (expected YYYY-MM-DD): {}", date))?;
+    Ok(visits(&read_samples(vault, date), &load_settings(vault)))
+}
+
+/// Delete recorded fixes (all, or only days before `before`), the inbox,
+/// and the `places` written into those days' daily notes. `dry_run` counts
+/// what would go without deleting it.
+#[tauri::command]
+pub fn purge_location_history(vault_path: String, before: Option<String>, dry_run: Option<bool>) -> Result<PurgeReport, String> {
+    let dry_run = dry_run.unwrap_or(false);
+    let vault = Path::new(&vault_path);
+    let before = match before.as_deref() {
+        Some(raw) => Some(
+            NaiveDate::parse_from_str(raw.trim(), "%Y-%m-%d").map_err(|_| tr!("Invalid date (expected YYYY-MM-DD): {}", raw))?,
+        ),
+        None => None,
+    };
+    let mut report = PurgeReport { dry_run, ..Default::default() };
+    if let Ok(entries) = fs::read_dir(vault.join(SAMPLES_DIR)) {
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            let Some(date) = path.file_stem().and_then(|s| NaiveDate::parse_from_str(&s.to_string_lossy(), "%Y-%m-%d").ok()) else {
+                continue;
+            };
+            if before.is_some_and(|b| date >= b) {
+                continue;
+            }
+            if !dry_run {
+                fs::remove_file(&path).map_err(|e| tr!("Failed to delete: {}", e))?;
+            }
+            report.days += 1;
+        }
+    }
+    if let Ok(entries) = fs::read_dir(vault.join("daily/tasks")) {
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            let Some(date) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else { continue };
+            let Ok(day) = NaiveDate::parse_from_str(&date, "%Y-%m-%d") else { continue };
+            if before.is_some_and(|b| day >= b) {
+                continue;
+            }
+            let had = fs::read_to_string(&path).is_ok_and(|raw| raw.lines().any(|l| l.starts_with(&format!("{PLACES_KEY}:"))));
+            if had {
+                if !dry_run {
+                    tasks::set_day_field(&vault_path, &date, PLACES_KEY, None)?;
+                }
+                report.notes += 1;
+            }
+        }
+    }
+    if before.is_none() && !dry_run {
+        let _ = fs::remove_file(vault.join(INBOX_FILE));
+    }
+    Ok(report)
+}
+
+/// Background capture: every `interval_minutes` read the inbox and, on
+/// macOS, ask the configured helper for a fix. Does nothing while disabled.
+#[tauri::command]
+pub fn start_location_logger(vault_path: String) {
+    let vault = PathBuf::from(vault_path);
+    let mut last_check: Option<Instant> = None;
+    LOGGER.start(Duration::from_secs(1), move || {
+        let settings = load_settings(&vault);
+        let every = Duration::from_secs(settings.interval_minutes.max(1) * 60);
+        if settings.enabled && last_check.is_none_or(|t| t.elapsed() >= every) {
+            last_check = Some(Instant::now());
+            if let Err(e) = capture(&vault, &settings) {
+                println!("[WARN] location capture failed: {e}");
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn stop_location_logger() {
+    LOGGER.stop();
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn load_settings(vault: &Path) -> LocationSettings {
+    fs::read_to_string(vault.join(SETTINGS_FILE))
+        .ok()
+        .and_then(|raw| serde_yaml::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn enabled_settings(vault: &Path) -> Result<LocationSettings, String> {
+    let settings = load_settings(vault);
+    if settings.enabled {
+        Ok(settings)
+    } else {
+        Err(tr!("Location history is disabled in settings"))
+    }
+}
+
+fn capture(vault: &Path, settings: &LocationSettings) -> Result<(), String> {
+    import_inbox(vault, settings)?;
+    let output = match settings.source.as_str() {
+        "shortcut" => crate::services::automations::run_shortcut(&settings.shortcut)?,
+        "command" => run_helper(&settings.command)?,
+        _ => return Ok(()),
+    };
+    let (lat, lon) = parse_coords(&output).ok_or_else(|| tr!("No coordinates in helper output: {}", output))?;
+    let now = Local::now().naive_local();
+    add_samples(vault, settings, vec![(now, sample(now, lat, lon, None)?)])
+}
+
+#[cfg(desktop)]
+fn run_helper(command: &str) -> Result<String, String> {
+    let output = std::process::Command::new("sh")
+        .args(["-c", command])
+        .output()
+        .map_err(|e| tr!("Failed to run '{}': {}", command, e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[cfg(mobile)]
+fn run_helper(command: &str) -> Result<String, String> {
+    let _ = command;
+    Err(crate::services::unsupported("location helper"))
+}
+
+fn import_inbox(vault: &Path, settings: &LocationSettings) -> Result<usize, String> {
+    let Some(parsed) = read_inbox(vault) else { return Ok(0) };
+    let count = parsed.len();
+    add_samples(vault, settings, parsed)?;
+    durable::write(vault.join(INBOX_FILE), "").map_err(|e| tr!("write_file failed: {}", e))?;
+    Ok(count)
+}
+
+/// The inbox lines that parse; None without an inbox
+fn read_inbox(vault: &Path) -> Option<Vec<(NaiveDateTime, Sample)>> {
+    let raw = fs::read_to_string(vault.join(INBOX_FILE)).ok()?;
+    Some(raw.lines().filter_map(parse_inbox_line).collect())
+}
+
+/// `2025-03-01T08:30:00+08:00,31.2304,121.4737[,name]`
+fn parse_inbox_line(line: &str) -> Option<(NaiveDateTime, Sample)> {
+    let mut parts = line.trim().splitn(4, ',');
+    let at = parse_time(parts.next()?)?;
+    let lat = parts.next()?.trim().parse().ok()?;
+    let lon = parts.next()?.trim().parse().ok()?;
+    let name = parts.next().map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    Some((at, sample(at, lat, lon, name).ok()?))
+}
+
+fn sample(at: NaiveDateTime, lat: f64, lon: f64, name: Option<String>) -> Result<Sample, String> {
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return Err(tr!("Invalid coordinates: {}, {}", lat, lon));
+    }
+    Ok(Sample { time: at.format("%H:%M:%S").to_string(), lat, lon, name })
+}
+
+fn parse_time(raw: &str) -> Option<NaiveDateTime> {
+    let raw = raw.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(raw) {
+        return Some(dt.with_timezone(&Local).naive_local());
+    }
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(raw, f).ok())
+}
+
+/// First two numbers in the helper output, e.g. `31.2304,121.4737`
+fn parse_coords(output: &str) -> Option<(f64, f64)> {
+    let mut numbers = output
+        .split(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+        .filter_map(|s| s.parse::<f64>().ok());
+    Some((numbers.next()?, numbers.next()?))
+}
+
+fn samples_path(vault: &Path, date: NaiveDate) -> PathBuf {
+    vault.join(SAMPLES_DIR).join(format!("{}.yaml", date.format("%Y-%m-%d")))
+}
+
+fn read_samples(vault: &Path, date: NaiveDate) -> Vec<Sample> {
+    fs::read_to_string(samples_path(vault, date))
+        .ok()
+        .and_then(|raw| serde_yaml::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// Store fixes per day and refresh each touched day's `places` frontmatter
+fn add_samples(vault: &Path, settings: &LocationSettings, new: Vec<(NaiveDateTime, Sample)>) -> Result<(), String> {
+    let mut by_day: BTreeMap<NaiveDate, Vec<Sample>> = BTreeMap::new();
+    for (at, s) in new {
+        by_day.entry(at.date()).or_default().push(s);
+    }
+    for (date, new) in by_day {
+        let mut samples = read_samples(vault, date);
+        samples.extend(new);
+        samples.sort_by(|a, b| a.time.cmp(&b.time));
+        samples.dedup_by(|a, b| a.time == b.time);
+        let path = samples_path(vault, date);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+        }
+        let yaml = serde_yaml::to_string(&samples).map_err(|e| tr!("Failed to serialize: {}", e))?;
+        durable::write(&path, yaml).map_err(|e| tr!("write_file failed: {}", e))?;
+
+        let names = place_names(&visits(&samples, settings));
+        if !names.is_empty() {
+            let value = serde_yaml::to_value(names).map_err(|e| tr!("Failed to serialize: {}", e))?;
+            tasks::set_day_field(&vault.to_string_lossy(), &date.format("%Y-%m-%d").to_string(), PLACES_KEY, Some(&value))?;
+        }
+    }
+    Ok(())
+}
+
+/// Group consecutive fixes within `radius_m` of where the stay began and
+/// keep the stays of at least `min_stay_minutes`. Back-to-back stays with
+/// the same name are merged.
+fn visits(samples: &[Sample], settings: &LocationSettings) -> Vec<Visit> {
+    let time = |s: &Sample| NaiveTime::parse_from_str(&s.time, "%H:%M:%S").ok();
+    let mut groups: Vec<Vec<&Sample>> = Vec::new();
+    for s in samples {
+        match groups.last_mut() {
+            Some(group) if distance_m(group[0].lat, group[0].lon, s.lat, s.lon) <= settings.radius_m => group.push(s),
+            _ => groups.push(vec![s]),
+        }
+    }
+    let mut out: Vec<Visit> = Vec::new();
+    for group in groups {
+        let (first, last) = (group[0], group[group.len() - 1]);
+        let (Some(start), Some(end)) = (time(first), time(last)) else { continue };
+        let minutes = (end - start).num_minutes();
+        if minutes < settings.min_stay_minutes {
+            continue;
+        }
+        let n = group.len() as f64;
+        let (lat, lon) = (group.iter().map(|s| s.lat).sum::<f64>() / n, group.iter().map(|s| s.lon).sum::<f64>() / n);
+        let name = place_name(&group, lat, lon, settings);
+        match out.last_mut() {
+            Some(prev) if prev.name == name => {
+                prev.left = end.format("%H:%M").to_string();
+                prev.minutes += minutes;
+            }
+            _ => out.push(Visit {
+                name,
+                lat,
+                lon,
+                arrived: start.format("%H:%M").to_string(),
+                left: end.format("%H:%M").to_string(),
+                minutes,
+            }),
+        }
+    }
+    out
+}
+
+/// Nearest named place in range, else a name given with the fixes, else
+/// the coordinates rounded to ~100 m
+fn place_name(group: &[&Sample], lat: f64, lon: f64, settings: &LocationSettings) -> String {
+    let known = settings
+        .places
+        .iter()
+        .map(|p| (distance_m(lat, lon, p.lat, p.lon), p))
+        .filter(|(d, _)| *d <= settings.radius_m)
+        .min_by(|a, b| a.0.total_cmp(&b.0));
+    if let Some((_, place)) = known {
+        return place.name.clone();
+    }
+    group
+        .iter()
+        .find_map(|s| s.name.clone())
+        .unwrap_or_else(|| format!("{lat:.3},{lon:.3}"))
+}
+
+/// Names in visit order, each listed once
+fn place_names(visits: &[Visit]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for v in visits {
+        if !names.contains(&v.name) {
+            names.push(v.name.clone());
+        }
+    }
+    names
+}
+
+/// Haversine distance in metres
+fn distance_m(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (p1, p2) = (lat1.to_radians(), lat2.to_radians());
+    let (dp, dl) = ((lat2 - lat1).to_radians(), (lon2 - lon1).to_radians());
+    let a = (dp / 2.0).sin().powi(2) + p1.cos() * p2.cos() * (dl / 2.0).sin().powi(2);
+    6_371_000.0 * 2.0 * a.sqrt().asin()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fix(time: &str, lat: f64, lon: f64) -> Sample {
+        Sample { time: time.into(), lat, lon, name: None }
+    }
+
+    #[test]
+    fn test_visits() {
+        let settings = LocationSettings {
+            places: vec![NamedPlace { name: "家".into(), lat: 31.2304, lon: 121.4737 }],
+            ..Default::default()
+        };
+        let samples = vec![
+            fix("07:00:00", 31.2304, 121.4737),
+            fix("08:00:00", 31.2305, 121.4738),
+            // Passing through, too short to count
+            fix("08:20:00", 31.2400, 121.4900),
+            fix("09:00:00", 31.2200, 121.5000),
+            fix("12:00:00", 31.2201, 121.5001),
+        ];
+        let v = visits(&samples, &settings);
+        assert_eq!(v.len(), 2);
+        assert_eq!((v[0].name.as_str(), v[0].arrived.as_str(), v[0].minutes), ("家", "07:00", 60));
+        assert_eq!(v[1].name, "31.220,121.500");
+        assert_eq!(place_names(&v), vec!["家", "31.220,121.500"]);
+    }
+
+    #[test]
+    fn test_inbox_dry_run() {
+        let vault = tempfile::tempdir().unwrap();
+        let vault_path = vault.path().to_string_lossy().to_string();
+        save_location_settings(vault_path.clone(), LocationSettings { enabled: true, ..load_settings(vault.path()) }).unwrap();
+        let inbox = "2025-03-01T08:30:00,31.23,121.47,公司\n2025-03-01T09:00:00,31.23,121.47\nnot a fix\n";
+        fs::write(vault.path().join(INBOX_FILE), inbox).unwrap();
+
+        assert_eq!(import_location_inbox(vault_path.clone(), Some(true)).unwrap(), 2);
+        assert_eq!(fs::read_to_string(vault.path().join(INBOX_FILE)).unwrap(), inbox);
+        assert!(!vault.path().join(SAMPLES_DIR).exists());
+
+        assert_eq!(import_location_inbox(vault_path, None).unwrap(), 2);
+        assert_eq!(fs::read_to_string(vault.path().join(INBOX_FILE)).unwrap(), "");
+        assert_eq!(read_samples(vault.path(), NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()).len(), 2);
+    }
+
+    #[test]
+    fn test_parse_inputs() {
+        let (at, s) = parse_inbox_line("2025-03-01T08:30:00,31.23,121.47,公司").unwrap();
+        assert_eq!(at.format("%Y-%m-%d %H:%M").to_string(), "2025-03-01 08:30");
+        assert_eq!(s.name.as_deref(), Some("公司"));
+        assert!(parse_inbox_line("2025-03-01T08:30:00,95,121.47").is_none());
+        assert_eq!(parse_coords("31.2304,121.4737\n"), Some((31.2304, 121.4737)));
+        assert_eq!(parse_coords("lat: -33.86 lon: 151.2"), Some((-33.86, 151.2)));
+        assert!((distance_m(0.0, 0.0, 0.0, 1.0) - 111_195.0).abs() < 10.0);
+    }
+}
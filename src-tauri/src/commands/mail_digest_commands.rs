@@ -0,0 +1,361 @@
+use chrono::{DateTime, Days, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::services::people::{bare_address, load_people};
+use crate::services::periodic::Periodic;
+use crate::services::{ai, connectors, durable, mail};
+
+/// One note per morning: daily/mail/<date>.md
+const DIGEST_DIR: &str = "daily/mail";
+const DEFAULT_AT: &str = "07:30";
+/// Messages sent to the AI for summaries; the rest are listed without one
+const MAX_AI_MESSAGES: usize = 40;
+const AI_BODY_CHARS: usize = 400;
+/// Sender addresses that mark bulk mail
+const BULK_MARKERS: &[&str] = &["noreply", "no-reply", "donotreply", "newsletter", "notification", "digest", "marketing", "mailer"];
+
+static DIGEST_LOOP: Periodic = Periodic::new();
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub enabled: bool,
+    /// Local time the digest is written; it covers the 24 hours before
+    pub at: NaiveTime,
+    /// One-line summaries from the AI CLI configured in settings
+    pub ai: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Importance {
+    /// From someone in people/, or flagged
+    Important,
+    Other,
+    /// Newsletters and notifications
+    Bulk,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DigestEntry {
+    /// Mailbox/<account> directory name
+    pub account: String,
+    pub email_id: String,
+    pub from: String,
+    pub subject: String,
+    /// Local RFC 3339
+    pub received: String,
+    pub importance: Importance,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MailDigest {
+    /// YYYY-MM-DD
+    pub date: String,
+    /// Vault-relative note path
+    pub path: String,
+    /// Start of the period covered, local RFC 3339
+    pub since: String,
+    /// Newest first within each importance
+    pub messages: Vec<DigestEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct State {
+    last_date: Option<NaiveDate>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Write the digest of `date` (default today) now, replacing an earlier one.
+/// `ai` overrides `mail_digest.ai` from connectors.yaml.
+#[tauri::command]
+pub async fn generate_mail_digest(vault_path: String, date: Option<String>, ai: Option<bool>) -> Result<MailDigest, String> {
+    let day = match &date {
+        Some(d) => NaiveDate::parse_from_str(d, "%Y-%m-%d").map_err(|_| tr!("Invalid date (expected YYYY-MM-DD): {}", d))?,
+        None => Local::now().date_naive(),
+    };
+    tokio::task::spawn_blocking(move || {
+        let vault = PathBuf::from(&vault_path);
+        let settings = settings(&vault);
+        generate(&vault, day, ai.unwrap_or(settings.ai), &settings, Local::now().naive_local())
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Write each morning's digest once `mail_digest.at` has passed while
+/// `mail_digest.enabled` is on; settings are re-read each minute
+#[tauri::command]
+pub fn start_mail_digest_loop(vault_path: String) {
+    let vault = PathBuf::from(vault_path);
+    DIGEST_LOOP.start(Duration::from_secs(60), move || {
+        if let Err(e) = run_due(&vault, Local::now().naive_local()) {
+            println!("[WARN] mail digest failed: {e}");
+        }
+    });
+}
+
+#[tauri::command]
+pub fn stop_mail_digest_loop() {
+    DIGEST_LOOP.stop();
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// `mail_digest:` in connectors.yaml
+fn settings(vault: &Path) -> Settings {
+    let section = connectors::section(vault, "mail_digest").unwrap_or_default();
+    let at = section.get("at").and_then(|v| v.as_str()).unwrap_or(DEFAULT_AT);
+    Settings {
+        enabled: section.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false),
+        at: NaiveTime::parse_from_str(at.trim(), "%H:%M").unwrap_or_else(|_| NaiveTime::parse_from_str(DEFAULT_AT, "%H:%M").expect("default")),
+        ai: section.get("ai").and_then(|v| v.as_bool()).unwrap_or(false),
+    }
+}
+
+/// Today's digest once `at` has passed, unless already written. Missed
+/// mornings aren't caught up: yesterday's mail is in today's.
+fn run_due(vault: &Path, now: NaiveDateTime) -> Result<Option<MailDigest>, String> {
+    let settings = settings(vault);
+    let mut state = load_state(vault);
+    if !settings.enabled || now.time() < settings.at || state.last_date.is_some_and(|d| d >= now.date()) {
+        return Ok(None);
+    }
+    let digest = generate(vault, now.date(), settings.ai, &settings, now)?;
+    state.last_date = Some(now.date());
+    save_state(vault, &state)?;
+    Ok(Some(digest))
+}
+
+/// INBOX mail received from `at` the day before `date` until `at` on it (or
+/// until now, for a digest of today made later), duplicates across accounts
+/// merged
+fn generate(vault: &Path, date: NaiveDate, use_ai: bool, settings: &Settings, now: NaiveDateTime) -> Result<MailDigest, String> {
+    let end = date.and_time(settings.at);
+    let until = if date == now.date() { end.max(now) } else { end };
+    let since = date.checked_sub_days(Days::new(1)).unwrap_or(date).and_time(settings.at);
+
+    let known: HashSet<String> = load_people(vault).iter().flat_map(|p| p.meta.emails.iter().map(|e| bare_address(e))).collect();
+    let vault_str = vault.to_string_lossy();
+    let mut rows: Vec<(DigestEntry, String)> = mail::unified_inbox(&vault_str, "INBOX", true)
+        .into_iter()
+        .filter_map(|row| {
+            let received = received_at(&row.email.date)?;
+            if received < since || received > until {
+                return None;
+            }
+            let flagged = row.email.flags.iter().any(|f| f.trim_start_matches('\\') == "Flagged");
+            let entry = DigestEntry {
+                importance: importance(&row.email.from, flagged, &known),
+                account: row.account,
+                email_id: row.email.id,
+                from: row.email.from.trim().to_string(),
+                subject: row.email.subject.trim().to_string(),
+                received: received.and_local_timezone(Local).single().map(|d| d.to_rfc3339()).unwrap_or_default(),
+                summary: None,
+            };
+            Some((entry, row.email.body_text.unwrap_or_default()))
+        })
+        .collect();
+    rows.sort_by(|(a, _), (b, _)| a.importance.cmp(&b.importance).then_with(|| b.received.cmp(&a.received)));
+
+    if use_ai && !rows.is_empty() {
+        match ai::ask(vault, &summary_prompt(&rows)) {
+            Ok(reply) => {
+                let mut summaries = parse_summaries(&reply);
+                for (i, (entry, _)) in rows.iter_mut().enumerate() {
+                    entry.summary = summaries.remove(&(i + 1));
+                }
+            }
+            Err(e) => println!("[WARN] mail digest summaries failed: {e}"),
+        }
+    }
+
+    let digest = MailDigest {
+        date: date.format("%Y-%m-%d").to_string(),
+        path: format!("{DIGEST_DIR}/{}.md", date.format("%Y-%m-%d")),
+        since: since.and_local_timezone(Local).single().map(|d| d.to_rfc3339()).unwrap_or_default(),
+        messages: rows.into_iter().map(|(entry, _)| entry).collect(),
+    };
+    let path = vault.join(&digest.path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    durable::write(&path, render(&digest, since)).map_err(|e| tr!("write_file failed: {}", e))?;
+    Ok(digest)
+}
+
+/// Mail dates (RFC 2822, maybe with a "(CST)" comment) in local time
+fn received_at(raw: &str) -> Option<NaiveDateTime> {
+    let raw = raw.split(" (").next().unwrap_or_default().trim();
+    DateTime::parse_from_rfc2822(raw)
+        .or_else(|_| DateTime::parse_from_rfc3339(raw))
+        .ok()
+        .map(|d| d.with_timezone(&Local).naive_local())
+}
+
+fn importance(from: &str, flagged: bool, known: &HashSet<String>) -> Importance {
+    let address = bare_address(from);
+    if flagged || known.contains(&address) {
+        Importance::Important
+    } else if BULK_MARKERS.iter().any(|m| address.split('@').next().unwrap_or_default().contains(m)) {
+        Importance::Bulk
+    } else {
+        Importance::Other
+    }
+}
+
+fn summary_prompt(rows: &[(DigestEntry, String)]) -> String {
+    let mut prompt = String::from(
+        "Summarize each email below in one short line, in the language it is written in. \
+         Reply with exactly one line per email, formatted as `<number>. <summary>`, and nothing else.\n\n",
+    );
+    for (i, (entry, body)) in rows.iter().take(MAX_AI_MESSAGES).enumerate() {
+        let body: String = body.split_whitespace().collect::<Vec<_>>().join(" ").chars().take(AI_BODY_CHARS).collect();
+        prompt.push_str(&format!("{}. From: {}\nSubject: {}\n{}\n\n", i + 1, entry.from, entry.subject, body));
+    }
+    prompt
+}
+
+/// `1. summary` lines of the AI reply, by number
+fn parse_summaries(reply: &str) -> BTreeMap<usize, String> {
+    reply
+        .lines()
+        .filter_map(|line| {
+            let (number, text) = line.trim().split_once(['.', ')'])?;
+            let text = text.trim();
+            Some((number.trim().parse().ok()?, text.to_string())).filter(|_| !text.is_empty())
+        })
+        .collect()
+}
+
+fn render(digest: &MailDigest, since: NaiveDateTime) -> String {
+    let mut out = format!(
+        "---\ntitle: 邮件摘要 {date}\ndate: {date}\ntype: mail-digest\nmessages: {count}\n---\n\n# 邮件摘要 · {date}\n\n",
+        date = digest.date,
+        count = digest.messages.len()
+    );
+    if digest.messages.is_empty() {
+        out.push_str(&format!("{} 以来没有新邮件。\n", since.format("%m-%d %H:%M")));
+        return out;
+    }
+    out.push_str(&format!("{} 以来 {} 封新邮件。\n", since.format("%m-%d %H:%M"), digest.messages.len()));
+
+    for (importance, heading) in [(Importance::Important, "重要"), (Importance::Other, "其他"), (Importance::Bulk, "订阅与通知")] {
+        let entries: Vec<&DigestEntry> = digest.messages.iter().filter(|m| m.importance == importance).collect();
+        if entries.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("\n## {heading}\n"));
+        // By sender, in order of their newest message
+        let mut senders: Vec<&str> = Vec::new();
+        for entry in &entries {
+            if !senders.contains(&entry.from.as_str()) {
+                senders.push(&entry.from);
+            }
+        }
+        for sender in senders {
+            out.push_str(&format!("\n### {}\n\n", if sender.is_empty() { "(未知发件人)" } else { sender }));
+            for entry in entries.iter().filter(|m| m.from == sender) {
+                let subject = if entry.subject.is_empty() { "(无主题)".to_string() } else { entry.subject.replace('[', "\\[").replace(']', "\\]") };
+                // Relative to daily/mail/, so it opens the cached message
+                let link = format!("../../Mailbox/{}/{}.eml", entry.account, entry.email_id.replace(['/', '\\'], "_"));
+                let time = DateTime::parse_from_rfc3339(&entry.received).map(|d| d.format("%H:%M").to_string()).unwrap_or_default();
+                out.push_str(&format!("- [{subject}](<{link}>) · {time} · {}", entry.account));
+                if let Some(summary) = &entry.summary {
+                    out.push_str(&format!(" — {summary}"));
+                }
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+fn load_state(vault: &Path) -> State {
+    fs::read_to_string(vault.join(mail::DIGEST_STATE_FILE)).ok().and_then(|raw| serde_json::from_str(&raw).ok()).unwrap_or_default()
+}
+
+fn save_state(vault: &Path, state: &State) -> Result<(), String> {
+    let path = vault.join(mail::DIGEST_STATE_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(state).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(path, json).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(vault: &Path, rel: &str, text: &str) {
+        let path = vault.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    fn local(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    /// RFC 2822 for a local time, as a mail client would write it
+    fn rfc2822(s: &str) -> String {
+        local(s).and_local_timezone(Local).single().unwrap().to_rfc2822()
+    }
+
+    #[test]
+    fn test_generate_digest() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path();
+        write(vault, "people/jane.md", "---\nname: Jane\nemails: [jane@x.org]\n---\n");
+        let index = serde_json::json!([
+            {"id": "1", "uid": 1, "from": "Jane <jane@x.org>", "to": "me", "subject": "Dinner [Fri]", "date": rfc2822("2026-03-10 06:00"),
+             "attachments": [], "flags": [], "folder": "INBOX"},
+            {"id": "2", "uid": 2, "from": "Shop <noreply@shop.com>", "to": "me", "subject": "Sale", "date": rfc2822("2026-03-09 20:00"),
+             "attachments": [], "flags": [], "folder": "INBOX"},
+            {"id": "3", "uid": 3, "from": "Bob <bob@y.org>", "to": "me", "subject": "Old", "date": rfc2822("2026-03-09 07:00"),
+             "attachments": [], "flags": [], "folder": "INBOX"},
+            {"id": "4", "uid": 4, "from": "Bob <bob@y.org>", "to": "me", "subject": "Hi", "date": rfc2822("2026-03-10 07:00"),
+             "attachments": [], "flags": [], "folder": "INBOX"}
+        ]);
+        write(vault, "Mailbox/home/index.json", &index.to_string());
+
+        let settings = Settings { enabled: true, at: NaiveTime::from_hms_opt(7, 30, 0).unwrap(), ai: false };
+        let digest = generate(vault, local("2026-03-10 07:30").date(), false, &settings, local("2026-03-10 07:30")).unwrap();
+        let ids: Vec<(&str, Importance)> = digest.messages.iter().map(|m| (m.email_id.as_str(), m.importance)).collect();
+        assert_eq!(ids, vec![("1", Importance::Important), ("4", Importance::Other), ("2", Importance::Bulk)]);
+
+        let note = fs::read_to_string(vault.join("daily/mail/2026-03-10.md")).unwrap();
+        assert!(note.contains("## 重要\n\n### Jane <jane@x.org>\n\n- [Dinner \\[Fri\\]](<../../Mailbox/home/1.eml>) · 06:00 · home\n"));
+        assert!(note.contains("## 订阅与通知"));
+        assert!(!note.contains("Old"));
+    }
+
+    #[test]
+    fn test_parse_summaries() {
+        let summaries = parse_summaries("1. Jane asks about Friday dinner\n2) 促销活动\n\nnoise\n3.   \n");
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[&2], "促销活动");
+        assert_eq!(importance("News <newsletter@x.com>", false, &HashSet::new()), Importance::Bulk);
+        assert_eq!(importance("News <newsletter@x.com>", true, &HashSet::new()), Importance::Important);
+    }
+}
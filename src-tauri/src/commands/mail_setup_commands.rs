@@ -0,0 +1,500 @@
+use native_tls::{TlsConnector, TlsStream};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use super::email_commands::imap_list_folders;
+use crate::services::mail::{imap_starttls_client, imap_tls_client, imap_tls_connector, read_response, ImapAccount};
+use crate::services::http;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Mozilla's ISP database, consulted after the domain's own autoconfig
+const ISPDB_URL: &str = "https://autoconfig.thunderbird.net/v1.1/";
+/// DNS-over-HTTPS JSON API, for the SRV lookups std can't do
+const DOH_URL: &str = "https://dns.google/resolve";
+
+/// Host, port and security of a known server
+type Server = (&'static str, u16, &'static str);
+
+/// Providers that need no lookup: domains, IMAP, SMTP
+const PROVIDERS: [(&[&str], Server, Server); 6] = [
+    (&["gmail.com", "googlemail.com"], ("imap.gmail.com", 993, "ssl"), ("smtp.gmail.com", 465, "ssl")),
+    (&["163.com"], ("imap.163.com", 993, "ssl"), ("smtp.163.com", 465, "ssl")),
+    (&["126.com"], ("imap.126.com", 993, "ssl"), ("smtp.126.com", 465, "ssl")),
+    (&["qq.com", "foxmail.com", "vip.qq.com"], ("imap.qq.com", 993, "ssl"), ("smtp.qq.com", 465, "ssl")),
+    (
+        &["outlook.com", "hotmail.com", "live.com", "msn.com"],
+        ("outlook.office365.com", 993, "ssl"),
+        ("smtp.office365.com", 587, "starttls"),
+    ),
+    (&["icloud.com", "me.com", "mac.com"], ("imap.mail.me.com", 993, "ssl"), ("smtp.mail.me.com", 587, "starttls")),
+];
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum StepStatus {
+    Ok,
+    /// Works, but worth fixing (untrusted certificate, no encryption)
+    Warning,
+    Failed,
+    /// Not probed on its own, e.g. STARTTLS happens as part of login
+    Skipped,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DiagnosticStep {
+    /// "dns" | "tcp" | "tls" | "auth" | "folders"
+    pub step: String,
+    pub status: StepStatus,
+    pub detail: String,
+    pub millis: u64,
+}
+
+/// Best-effort read of the server certificate
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct CertificateInfo {
+    /// Common names
+    pub subject: Option<String>,
+    pub issuer: Option<String>,
+    /// YYYY-MM-DD
+    pub not_after: Option<String>,
+    /// SHA-256 of the DER, colon-separated hex
+    pub sha256: String,
+    /// Verified against the system trust store for this host
+    pub trusted: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AccountDiagnostics {
+    /// Every step passed, perhaps with warnings
+    pub ok: bool,
+    /// In order, up to and including the first failure
+    pub steps: Vec<DiagnosticStep>,
+    pub certificate: Option<CertificateInfo>,
+    /// Folder names (IMAP) once logged in
+    pub folders: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ServerSettings {
+    pub host: String,
+    pub port: u16,
+    /// "ssl" | "starttls" | "none"
+    pub security: String,
+    pub username: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct EmailSettings {
+    /// "builtin" | "autoconfig" | "srv"
+    pub source: String,
+    pub imap: Option<ServerSettings>,
+    pub pop3: Option<ServerSettings>,
+    pub smtp: Option<ServerSettings>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Walk through what a sync does, one step at a time: resolve the host,
+/// connect, TLS handshake (with the certificate), log in, list folders.
+/// Stops at the first failure, so the last step says what is wrong.
+#[tauri::command]
+pub async fn test_email_account(account: ImapAccount) -> Result<AccountDiagnostics, String> {
+    tokio::task::spawn_blocking(move || diagnose(&account))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))
+}
+
+/// Server settings for an address, so account setup needs only the address:
+/// the built-in providers, else autoconfig (the domain's own, then Mozilla's
+/// ISP database), else DNS SRV records. None when nothing turns up.
+#[tauri::command]
+pub async fn discover_email_settings(address: String) -> Result<Option<EmailSettings>, String> {
+    let address = address.trim().to_lowercase();
+    let Some((_, domain)) = address.split_once('@').filter(|(user, domain)| !user.is_empty() && domain.contains('.')) else {
+        return Err(tr!("Invalid email address: {}", address));
+    };
+    let domain = domain.to_string();
+    tokio::task::spawn_blocking(move || {
+        builtin_settings(&address, &domain)
+            .or_else(|| autoconfig_settings(&address, &domain))
+            .or_else(|| srv_settings(&address, &domain))
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Records steps; `run` returns None once one has failed
+struct Report {
+    diagnostics: AccountDiagnostics,
+}
+
+impl Report {
+    fn run<T>(&mut self, step: &str, f: impl FnOnce() -> Result<(T, StepStatus, String), String>) -> Option<T> {
+        let started = Instant::now();
+        let result = f();
+        let millis = started.elapsed().as_millis() as u64;
+        let (value, status, detail) = match result {
+            Ok((value, status, detail)) => (Some(value), status, detail),
+            Err(e) => (None, StepStatus::Failed, e),
+        };
+        self.diagnostics.steps.push(DiagnosticStep { step: step.to_string(), status, detail, millis });
+        value
+    }
+
+    fn skip(&mut self, step: &str, status: StepStatus, detail: String) {
+        self.diagnostics.steps.push(DiagnosticStep { step: step.to_string(), status, detail, millis: 0 });
+    }
+}
+
+fn diagnose(account: &ImapAccount) -> AccountDiagnostics {
+    let mut report = Report { diagnostics: AccountDiagnostics::default() };
+    report.diagnostics.ok = run_steps(&mut report, account).is_some();
+    report.diagnostics
+}
+
+fn run_steps(report: &mut Report, account: &ImapAccount) -> Option<()> {
+    let host = account.imap_host.trim();
+    let port = account.imap_port;
+    let pop3 = account.protocol.as_deref() == Some("pop3");
+    // Same rule as sync: implicit TLS on the standard secure ports, else STARTTLS (IMAP) or plain (POP3)
+    let implicit_tls = port == 993 || port == 995;
+
+    let addrs = report.run("dns", || {
+        let addrs: Vec<SocketAddr> = (host, port).to_socket_addrs().map_err(|e| tr!("DNS lookup failed: {}", e))?.collect();
+        if addrs.is_empty() {
+            return Err(tr!("DNS lookup failed: {}", host));
+        }
+        let detail = addrs.iter().map(|a| a.ip().to_string()).collect::<Vec<_>>().join(", ");
+        Ok((addrs, StepStatus::Ok, detail))
+    })?;
+    let (tcp, addr) = report.run("tcp", || {
+        let mut last = String::new();
+        for addr in &addrs {
+            match TcpStream::connect_timeout(addr, CONNECT_TIMEOUT) {
+                Ok(stream) => return Ok(((stream, *addr), StepStatus::Ok, addr.to_string())),
+                Err(e) => last = e.to_string(),
+            }
+        }
+        Err(tr!("Connection failed: {}", last))
+    })?;
+
+    if implicit_tls {
+        report.diagnostics.certificate = Some(report.run("tls", || probe_tls(host, addr, tcp))?);
+    } else if pop3 {
+        report.skip("tls", StepStatus::Warning, tr!("Unencrypted connection"));
+    } else {
+        report.skip("tls", StepStatus::Skipped, tr!("Negotiated with STARTTLS at login"));
+    }
+
+    report.diagnostics.folders = if pop3 {
+        let messages = report.run("auth", || {
+            let messages = pop3_login(host, port, implicit_tls, &account.email, &account.password)?;
+            Ok((messages, StepStatus::Ok, account.email.clone()))
+        })?;
+        report.skip("folders", StepStatus::Ok, tr!("{} messages in INBOX", messages));
+        vec!["INBOX".to_string()]
+    } else if implicit_tls {
+        imap_auth_and_list(report, account, || imap_tls_client(host, port, &imap_tls_connector()?))?
+    } else {
+        imap_auth_and_list(report, account, || imap_starttls_client(host, port, &imap_tls_connector()?))?
+    };
+    Some(())
+}
+
+/// Verified handshake on the probe connection; when the certificate isn't
+/// trusted, retry the way sync connects (accepting it) and warn
+fn probe_tls(host: &str, addr: SocketAddr, tcp: TcpStream) -> Result<(CertificateInfo, StepStatus, String), String> {
+    tcp.set_read_timeout(Some(CONNECT_TIMEOUT)).ok();
+    let verified = TlsConnector::new().map_err(|e| tr!("Failed to create TLS connector: {}", e))?;
+    let (stream, trusted, detail) = match verified.connect(host, tcp) {
+        Ok(stream) => (stream, true, String::new()),
+        Err(verify_error) => {
+            let tcp = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).map_err(|e| tr!("Connection failed: {}", e))?;
+            tcp.set_read_timeout(Some(CONNECT_TIMEOUT)).ok();
+            let stream = imap_tls_connector()?.connect(host, tcp).map_err(|e| tr!("TLS handshake failed: {}", e))?;
+            (stream, false, tr!("Certificate not trusted: {}", verify_error))
+        }
+    };
+    let der = peer_der(&stream);
+    let mut certificate = der.as_deref().map(certificate_info).unwrap_or_default();
+    certificate.trusted = trusted;
+    let detail = if trusted { certificate.subject.clone().unwrap_or_default() } else { detail };
+    Ok((certificate, if trusted { StepStatus::Ok } else { StepStatus::Warning }, detail))
+}
+
+fn peer_der(stream: &TlsStream<TcpStream>) -> Option<Vec<u8>> {
+    stream.peer_certificate().ok().flatten().and_then(|c| c.to_der().ok())
+}
+
+/// The `auth` and `folders` steps over the same client sync uses
+fn imap_auth_and_list<T: Read + Write>(
+    report: &mut Report,
+    account: &ImapAccount,
+    connect: impl FnOnce() -> Result<imap::Client<T>, String>,
+) -> Option<Vec<String>> {
+    let mut session = report.run("auth", || {
+        let session = connect()?.login(&account.email, &account.password).map_err(|e| tr!("Login failed: {}", e.0))?;
+        Ok((session, StepStatus::Ok, account.email.clone()))
+    })?;
+    let folders = report.run("folders", || {
+        let names: Vec<String> = imap_list_folders(&mut session)?.into_iter().map(|f| f.name).collect();
+        let detail = tr!("{} folders", names.len());
+        Ok((names, StepStatus::Ok, detail))
+    });
+    session.logout().ok();
+    folders
+}
+
+/// USER/PASS then STAT; returns how many messages are waiting
+fn pop3_login(host: &str, port: u16, implicit_tls: bool, email: &str, password: &str) -> Result<usize, String> {
+    let tcp = TcpStream::connect((host, port)).map_err(|e| tr!("Connection failed: {}", e))?;
+    tcp.set_read_timeout(Some(CONNECT_TIMEOUT)).ok();
+    if implicit_tls {
+        let mut stream = imap_tls_connector()?.connect(host, tcp).map_err(|e| tr!("TLS handshake failed: {}", e))?;
+        pop3_stat(&mut stream, email, password)
+    } else {
+        let mut stream = tcp;
+        pop3_stat(&mut stream, email, password)
+    }
+}
+
+fn pop3_stat<T: Read + Write>(stream: &mut T, email: &str, password: &str) -> Result<usize, String> {
+    read_response(stream)?;
+    stream.write_all(format!("USER {}\r\n", email).as_bytes()).map_err(|e| tr!("Failed to send: {}", e))?;
+    let user = read_response(stream)?;
+    if !user.starts_with("+OK") {
+        return Err(tr!("USER command failed: {}", user.trim()));
+    }
+    stream.write_all(format!("PASS {}\r\n", password).as_bytes()).map_err(|e| tr!("Failed to send: {}", e))?;
+    let pass = read_response(stream)?;
+    if !pass.starts_with("+OK") {
+        return Err(tr!("Login failed: {}", pass.trim()));
+    }
+    stream.write_all(b"STAT\r\n").map_err(|e| tr!("Failed to send: {}", e))?;
+    let stat = read_response(stream)?;
+    stream.write_all(b"QUIT\r\n").ok();
+    Ok(stat.split_whitespace().nth(1).and_then(|n| n.parse().ok()).unwrap_or(0))
+}
+
+fn builtin_settings(address: &str, domain: &str) -> Option<EmailSettings> {
+    let (_, imap, smtp) = PROVIDERS.iter().find(|(domains, _, _)| domains.contains(&domain))?;
+    let server = |(host, port, security): Server| ServerSettings {
+        host: host.to_string(),
+        port,
+        security: security.to_string(),
+        username: address.to_string(),
+    };
+    Some(EmailSettings { source: "builtin".into(), imap: Some(server(*imap)), pop3: None, smtp: Some(server(*smtp)) })
+}
+
+fn autoconfig_settings(address: &str, domain: &str) -> Option<EmailSettings> {
+    let urls = [
+        format!("https://autoconfig.{domain}/mail/config-v1.1.xml?emailaddress={address}"),
+        format!("{ISPDB_URL}{domain}"),
+    ];
+    urls.iter()
+        .filter_map(|url| http::get_text(url).ok())
+        .map(|xml| parse_autoconfig(&xml, address))
+        .find(|s| s.imap.is_some() || s.pop3.is_some())
+}
+
+/// First server of each kind in a Thunderbird config-v1.1.xml, with the
+/// %EMAILADDRESS% style placeholders filled in
+fn parse_autoconfig(xml: &str, address: &str) -> EmailSettings {
+    let local = address.split('@').next().unwrap_or_default();
+    let domain = address.split('@').nth(1).unwrap_or_default();
+    let mut settings = EmailSettings { source: "autoconfig".into(), ..Default::default() };
+    for block in xml.split("<incomingServer").skip(1).chain(xml.split("<outgoingServer").skip(1)) {
+        let Some((open, body)) = block.split_once('>') else { continue };
+        let body = body.split("</incomingServer>").next().unwrap_or(body).split("</outgoingServer>").next().unwrap_or(body);
+        let text = |tag: &str| {
+            let start = body.find(&format!("<{tag}>"))? + tag.len() + 2;
+            let end = body[start..].find(&format!("</{tag}>"))? + start;
+            Some(body[start..end].trim().to_string())
+        };
+        let (Some(host), Some(port)) = (text("hostname"), text("port").and_then(|p| p.parse().ok())) else { continue };
+        let security = match text("socketType").unwrap_or_default().to_uppercase().as_str() {
+            "SSL" => "ssl",
+            "STARTTLS" => "starttls",
+            _ => "none",
+        };
+        let username = text("username")
+            .unwrap_or_else(|| "%EMAILADDRESS%".into())
+            .replace("%EMAILADDRESS%", address)
+            .replace("%EMAILLOCALPART%", local)
+            .replace("%EMAILDOMAIN%", domain);
+        let host = host.replace("%EMAILDOMAIN%", domain);
+        let server = Some(ServerSettings { host, port, security: security.to_string(), username });
+        let slot = match open {
+            o if o.contains("\"imap\"") => &mut settings.imap,
+            o if o.contains("\"pop3\"") => &mut settings.pop3,
+            o if o.contains("\"smtp\"") => &mut settings.smtp,
+            _ => continue,
+        };
+        if slot.is_none() {
+            *slot = server;
+        }
+    }
+    settings
+}
+
+/// RFC 6186: _imaps/_imap, _pop3s/_pop3 and _submission(s) records
+fn srv_settings(address: &str, domain: &str) -> Option<EmailSettings> {
+    let lookup = |service: &str, security: &str| {
+        let url = format!("{DOH_URL}?name={service}._tcp.{domain}&type=SRV");
+        let json = http::get_json(&url).ok()?;
+        let records: Vec<&str> = json.get("Answer")?.as_array()?.iter().filter_map(|a| a.get("data")?.as_str()).collect();
+        let (host, port) = best_srv(&records)?;
+        Some(ServerSettings { host, port, security: security.to_string(), username: address.to_string() })
+    };
+    let settings = EmailSettings {
+        source: "srv".into(),
+        imap: lookup("_imaps", "ssl").or_else(|| lookup("_imap", "starttls")),
+        pop3: lookup("_pop3s", "ssl").or_else(|| lookup("_pop3", "starttls")),
+        smtp: lookup("_submissions", "ssl").or_else(|| lookup("_submission", "starttls")),
+    };
+    (settings.imap.is_some() || settings.pop3.is_some()).then_some(settings)
+}
+
+/// Lowest priority of `priority weight port target.` records; a target of
+/// "." means the service is deliberately unavailable
+fn best_srv(records: &[&str]) -> Option<(String, u16)> {
+    records
+        .iter()
+        .filter_map(|r| {
+            let parts: Vec<&str> = r.split_whitespace().collect();
+            let [priority, _, port, target] = parts[..] else { return None };
+            let target = target.trim_end_matches('.');
+            if target.is_empty() {
+                return None;
+            }
+            Some((priority.parse::<u16>().ok()?, target.to_string(), port.parse::<u16>().ok()?))
+        })
+        .min_by_key(|(priority, _, _)| *priority)
+        .map(|(_, host, port)| (host, port))
+}
+
+/// Common names, expiry and fingerprint from an X.509 DER, without a full
+/// ASN.1 parse: issuer comes before subject, notBefore before notAfter
+fn certificate_info(der: &[u8]) -> CertificateInfo {
+    const CN_OID: [u8; 5] = [0x06, 0x03, 0x55, 0x04, 0x03];
+    let names: Vec<String> = der
+        .windows(CN_OID.len())
+        .enumerate()
+        .filter(|(_, w)| *w == CN_OID)
+        .filter_map(|(i, _)| {
+            let at = i + CN_OID.len();
+            let (tag, len) = (*der.get(at)?, *der.get(at + 1)? as usize);
+            if ![0x0c, 0x13, 0x16, 0x14].contains(&tag) || len >= 0x80 {
+                return None;
+            }
+            der.get(at + 2..at + 2 + len).map(|s| String::from_utf8_lossy(s).to_string())
+        })
+        .collect();
+    let times: Vec<String> = der
+        .windows(2)
+        .enumerate()
+        .filter_map(|(i, w)| {
+            let raw = match w {
+                [0x17, 0x0d] => der.get(i + 2..i + 15).map(|t| format!("20{}", String::from_utf8_lossy(&t[..6]))),
+                [0x18, 0x0f] => der.get(i + 2..i + 17).map(|t| String::from_utf8_lossy(&t[..8]).to_string()),
+                _ => None,
+            }?;
+            raw.chars().all(|c| c.is_ascii_digit()).then(|| format!("{}-{}-{}", &raw[..4], &raw[4..6], &raw[6..8]))
+        })
+        .collect();
+
+    let digest = Sha256::digest(der);
+    CertificateInfo {
+        subject: names.last().cloned(),
+        issuer: (names.len() > 1).then(|| names[0].clone()),
+        not_after: times.get(1).cloned(),
+        sha256: digest.iter().map(|b| format!("{b:02X}")).collect::<Vec<_>>().join(":"),
+        trusted: false,
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_autoconfig() {
+        let xml = r#"<?xml version="1.0"?>
+<clientConfig version="1.1">
+  <emailProvider id="example.com">
+    <incomingServer type="imap">
+      <hostname>imap.%EMAILDOMAIN%</hostname>
+      <port>993</port>
+      <socketType>SSL</socketType>
+      <username>%EMAILLOCALPART%</username>
+    </incomingServer>
+    <incomingServer type="imap">
+      <hostname>imap2.example.com</hostname>
+      <port>143</port>
+      <socketType>STARTTLS</socketType>
+    </incomingServer>
+    <incomingServer type="pop3">
+      <hostname>pop.example.com</hostname>
+      <port>995</port>
+      <socketType>SSL</socketType>
+      <username>%EMAILADDRESS%</username>
+    </incomingServer>
+    <outgoingServer type="smtp">
+      <hostname>smtp.example.com</hostname>
+      <port>587</port>
+      <socketType>STARTTLS</socketType>
+      <username>%EMAILADDRESS%</username>
+    </outgoingServer>
+  </emailProvider>
+</clientConfig>"#;
+        let settings = parse_autoconfig(xml, "jane@example.com");
+        let imap = settings.imap.unwrap();
+        assert_eq!((imap.host.as_str(), imap.port, imap.security.as_str(), imap.username.as_str()), ("imap.example.com", 993, "ssl", "jane"));
+        assert_eq!(settings.pop3.unwrap().port, 995);
+        let smtp = settings.smtp.unwrap();
+        assert_eq!((smtp.port, smtp.security.as_str(), smtp.username.as_str()), (587, "starttls", "jane@example.com"));
+
+        assert_eq!(builtin_settings("me@qq.com", "qq.com").unwrap().imap.unwrap().host, "imap.qq.com");
+        assert!(builtin_settings("me@example.com", "example.com").is_none());
+        assert_eq!(best_srv(&["10 0 993 imap2.example.com.", "0 1 993 imap.example.com."]), Some(("imap.example.com".into(), 993)));
+        assert_eq!(best_srv(&["0 0 0 ."]), None);
+    }
+
+    #[test]
+    fn test_certificate_info() {
+        // The pieces certificate_info looks for, in certificate order
+        let mut der = vec![0x30, 0x82];
+        der.extend([0x06, 0x03, 0x55, 0x04, 0x03, 0x13, 0x07]);
+        der.extend(b"Test CA");
+        der.extend([0x17, 0x0d]);
+        der.extend(b"250101000000Z");
+        der.extend([0x17, 0x0d]);
+        der.extend(b"260401120000Z");
+        der.extend([0x06, 0x03, 0x55, 0x04, 0x03, 0x0c, 0x10]);
+        der.extend(b"imap.example.com");
+
+        let info = certificate_info(&der);
+        assert_eq!(info.subject.as_deref(), Some("imap.example.com"));
+        assert_eq!(info.issuer.as_deref(), Some("Test CA"));
+        assert_eq!(info.not_after.as_deref(), Some("2026-04-01"));
+        assert_eq!(info.sha256.len(), 32 * 3 - 1);
+    }
+}
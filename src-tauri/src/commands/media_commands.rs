@@ -0,0 +1,340 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::{self, Locale};
+use crate::services::notes::slugify;
+use crate::services::{connectors, http, records};
+
+const MEDIA_DIR: &str = "life/media";
+const TMDB_API: &str = "https://api.themoviedb.org/3";
+const TMDB_IMAGES: &str = "https://image.tmdb.org/t/p/w342";
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Typed frontmatter of life/media/<slug>.md; unknown keys are kept in `extra`
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MediaMeta {
+    #[serde(default)]
+    pub title: String,
+    /// "movie" | "series"
+    #[serde(default = "default_kind")]
+    pub kind: String,
+    /// "want" | "watching" | "watched" | "dropped"
+    #[serde(default = "default_status")]
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub year: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tmdb_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overview: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poster: Option<String>,
+    #[serde(default)]
+    pub genres: Vec<String>,
+    /// TMDB audience score, 0–10
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tmdb_rating: Option<f64>,
+    /// Own rating, 1–5
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+    /// Series only: where you are, e.g. season 2 episode 5
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub season: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub episode: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seasons: Option<u32>,
+    /// `YYYY-MM-DD`, set when marked watched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watched: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_yaml::Value>,
+}
+
+fn default_kind() -> String {
+    "movie".to_string()
+}
+
+fn default_status() -> String {
+    "want".to_string()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MediaItem {
+    /// File stem; empty on create to derive it from the title
+    #[serde(default)]
+    pub slug: String,
+    #[serde(default)]
+    pub path: String,
+    #[serde(flatten)]
+    pub meta: MediaMeta,
+    /// Markdown body: review / rating notes
+    #[serde(default)]
+    pub notes: String,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Watchlist sorted by title, optionally filtered by status and/or kind
+#[tauri::command]
+pub fn list_media(vault_path: String, status: Option<String>, kind: Option<String>) -> Vec<MediaItem> {
+    load_media(Path::new(&vault_path))
+        .into_iter()
+        .filter(|m| status.as_ref().is_none_or(|s| &m.meta.status == s))
+        .filter(|m| kind.as_ref().is_none_or(|k| &m.meta.kind == k))
+        .collect()
+}
+
+#[tauri::command]
+pub fn get_media(vault_path: String, slug: String) -> Result<MediaItem, String> {
+    read_item(&item_path(Path::new(&vault_path), &slug)?).ok_or_else(|| tr!("Watchlist item not found: {}", slug))
+}
+
+#[tauri::command]
+pub fn save_media(vault_path: String, item: MediaItem) -> Result<MediaItem, String> {
+    let vault = PathBuf::from(&vault_path);
+    let mut item = item;
+    item.meta.title = item.meta.title.trim().to_string();
+    if item.meta.title.is_empty() {
+        return Err(tr!("Title is required"));
+    }
+    if item.slug.is_empty() {
+        let base = match item.meta.year {
+            Some(year) => slugify(&format!("{} {year}", item.meta.title)),
+            None => slugify(&item.meta.title),
+        };
+        item.slug = records::unique_slug(&vault, MEDIA_DIR, &base);
+    }
+    let path = item_path(&vault, &item.slug)?;
+    write_item(&path, &item)?;
+    item.path = path.to_string_lossy().to_string();
+    Ok(item)
+}
+
+#[tauri::command]
+pub fn delete_media(vault_path: String, slug: String) -> Result<(), String> {
+    fs::remove_file(item_path(Path::new(&vault_path), &slug)?).map_err(|e| e.to_string())
+}
+
+/// Search TMDB for movies and series. Needs `tmdb.api_key` in connectors.yaml.
+#[tauri::command]
+pub async fn search_tmdb(vault_path: String, query: String) -> Result<Vec<MediaMeta>, String> {
+    tokio::task::spawn_blocking(move || {
+        let key = tmdb_key(Path::new(&vault_path))?;
+        let json = http::call_json(tmdb_request(&key, "/search/multi")?.query("query", &query))?;
+        Ok(json
+            .get("results")
+            .and_then(|r| r.as_array())
+            .map(|results| results.iter().filter_map(parse_tmdb).collect())
+            .unwrap_or_default())
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Fetch full TMDB details and add the title to the watchlist ("want"). A
+/// title already on the list is returned as is.
+#[tauri::command]
+pub async fn add_media_from_tmdb(vault_path: String, tmdb_id: u64, kind: String) -> Result<MediaItem, String> {
+    let vault = PathBuf::from(&vault_path);
+    if let Some(existing) = load_media(&vault).into_iter().find(|m| m.meta.tmdb_id == Some(tmdb_id) && m.meta.kind == kind) {
+        return Ok(existing);
+    }
+    let meta = tokio::task::spawn_blocking(move || {
+        let key = tmdb_key(&vault)?;
+        let path = if kind == "series" { format!("/tv/{tmdb_id}") } else { format!("/movie/{tmdb_id}") };
+        let mut json = http::call_json(tmdb_request(&key, &path)?)?;
+        if let Some(obj) = json.as_object_mut() {
+            obj.insert("media_type".into(), if kind == "series" { "tv" } else { "movie" }.into());
+        }
+        parse_tmdb(&json).ok_or_else(|| tr!("Failed to parse: {}", "TMDB"))
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))??;
+    save_media(vault_path, MediaItem { slug: String::new(), path: String::new(), meta, notes: String::new() })
+}
+
+/// Change status, optionally with a rating and a dated note appended to the
+/// body. Marking "watched" records today's date.
+#[tauri::command]
+pub fn set_media_status(
+    vault_path: String,
+    slug: String,
+    status: String,
+    rating: Option<u8>,
+    note: Option<String>,
+) -> Result<MediaItem, String> {
+    if !["want", "watching", "watched", "dropped"].contains(&status.as_str()) {
+        return Err(tr!("Invalid status: {}", status));
+    }
+    let mut item = get_media(vault_path.clone(), slug)?;
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    if status == "watched" && item.meta.status != "watched" {
+        item.meta.watched = Some(today.clone());
+    }
+    item.meta.status = status;
+    if let Some(rating) = rating {
+        item.meta.rating = Some(rating.clamp(1, 5));
+    }
+    if let Some(note) = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()) {
+        item.notes = format!("{}\n\n### {today}\n\n{note}\n", item.notes.trim_end());
+    }
+    save_media(vault_path, item)
+}
+
+/// Series progress: current season/episode; implies "watching"
+#[tauri::command]
+pub fn update_media_progress(vault_path: String, slug: String, season: u32, episode: u32) -> Result<MediaItem, String> {
+    let mut item = get_media(vault_path.clone(), slug)?;
+    item.meta.season = Some(season);
+    item.meta.episode = Some(episode);
+    if item.meta.status == "want" {
+        item.meta.status = "watching".to_string();
+    }
+    save_media(vault_path, item)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn load_media(vault: &Path) -> Vec<MediaItem> {
+    let mut items: Vec<MediaItem> = records::list(vault, MEDIA_DIR).iter().filter_map(|p| read_item(p)).collect();
+    items.sort_by(|a, b| a.meta.title.cmp(&b.meta.title));
+    items
+}
+
+fn item_path(vault: &Path, slug: &str) -> Result<PathBuf, String> {
+    records::path(vault, MEDIA_DIR, slug)
+}
+
+fn read_item(path: &Path) -> Option<MediaItem> {
+    let record = records::read::<MediaMeta>(path)?;
+    let mut meta = record.meta.unwrap_or_else(|| MediaMeta { kind: default_kind(), status: default_status(), ..Default::default() });
+    if meta.title.is_empty() {
+        meta.title = record.slug.clone();
+    }
+    Some(MediaItem { slug: record.slug, path: record.path, meta, notes: record.body })
+}
+
+fn write_item(path: &Path, item: &MediaItem) -> Result<(), String> {
+    let body = match item.notes.trim_start() {
+        "" => format!("# {}\n", item.meta.title),
+        notes => notes.to_string(),
+    };
+    records::write(path, &item.meta, &body)
+}
+
+fn tmdb_key(vault: &Path) -> Result<String, String> {
+    connectors::value(vault, "tmdb", "api_key").ok_or_else(|| tr!("TMDB API key is not set (tmdb.api_key in connectors.yaml)"))
+}
+
+/// v3 keys go in the query string; v4 read tokens (JWTs) as a bearer header
+fn tmdb_request(key: &str, path: &str) -> Result<ureq::Request, String> {
+    let language = match i18n::locale() {
+        Locale::Zh => "zh-CN",
+        Locale::En => "en-US",
+    };
+    let request = http::agent()?.get(&format!("{TMDB_API}{path}")).query("language", language);
+    Ok(if key.starts_with("eyJ") {
+        request.set("Authorization", &format!("Bearer {key}"))
+    } else {
+        request.query("api_key", key)
+    })
+}
+
+/// A search result or details object; people and other types yield None
+fn parse_tmdb(v: &serde_json::Value) -> Option<MediaMeta> {
+    let kind = match v.get("media_type").and_then(|t| t.as_str())? {
+        "movie" => "movie",
+        "tv" => "series",
+        _ => return None,
+    };
+    let str_at = |key: &str| v.get(key).and_then(|s| s.as_str()).map(str::to_string).filter(|s| !s.is_empty());
+    let (title_key, original_key, date_key) = match kind {
+        "movie" => ("title", "original_title", "release_date"),
+        _ => ("name", "original_name", "first_air_date"),
+    };
+    let title = str_at(title_key)?;
+    let original_title = str_at(original_key).filter(|o| *o != title);
+    Some(MediaMeta {
+        title,
+        kind: kind.to_string(),
+        status: default_status(),
+        year: str_at(date_key).and_then(|d| d.get(..4)?.parse().ok()),
+        tmdb_id: v.get("id").and_then(|i| i.as_u64()),
+        original_title,
+        overview: str_at("overview"),
+        poster: str_at("poster_path").map(|p| format!("{TMDB_IMAGES}{p}")),
+        genres: v
+            .get("genres")
+            .and_then(|g| g.as_array())
+            .map(|g| g.iter().filter_map(|x| x.get("name")?.as_str().map(str::to_string)).collect())
+            .unwrap_or_default(),
+        tmdb_rating: v.get("vote_average").and_then(|r| r.as_f64()).filter(|r| *r > 0.0),
+        seasons: v.get("number_of_seasons").and_then(|n| n.as_u64()).map(|n| n as u32),
+        ..Default::default()
+    })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tmdb_results() {
+        let movie = serde_json::json!({
+            "media_type": "movie", "id": 603, "title": "黑客帝国", "original_title": "The Matrix",
+            "release_date": "1999-03-30", "poster_path": "/p.jpg", "vote_average": 8.2
+        });
+        let meta = parse_tmdb(&movie).unwrap();
+        assert_eq!((meta.kind.as_str(), meta.year, meta.tmdb_id), ("movie", Some(1999), Some(603)));
+        assert_eq!(meta.original_title.as_deref(), Some("The Matrix"));
+        assert_eq!(meta.poster.as_deref(), Some("https://image.tmdb.org/t/p/w342/p.jpg"));
+
+        let tv = serde_json::json!({"media_type": "tv", "id": 1, "name": "Dark", "original_name": "Dark", "number_of_seasons": 3});
+        let meta = parse_tmdb(&tv).unwrap();
+        assert_eq!((meta.kind.as_str(), meta.original_title, meta.seasons), ("series", None, Some(3)));
+
+        assert!(parse_tmdb(&serde_json::json!({"media_type": "person", "name": "x"})).is_none());
+    }
+
+    #[test]
+    fn test_slug_stays_in_media() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().to_string_lossy().to_string();
+        fs::write(dir.path().join("x.md"), "keep").unwrap();
+        assert_eq!(delete_media(vault.clone(), "../../x".into()).unwrap_err(), tr!("Invalid slug: {}", "../../x"));
+        assert!(get_media(vault, "../../x".into()).is_err());
+        assert!(dir.path().join("x.md").exists());
+    }
+
+    #[test]
+    fn test_item_round_trip_keeps_extra_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dark.md");
+        fs::write(&path, "---\ntitle: Dark\nkind: series\nstatus: watching\nwatched_with: 小明\n---\n\n好看\n").unwrap();
+        let item = read_item(&path).unwrap();
+        assert_eq!(item.meta.kind, "series");
+        write_item(&path, &item).unwrap();
+        let raw = fs::read_to_string(&path).unwrap();
+        assert!(raw.contains("watched_with: 小明"));
+        assert!(raw.ends_with("好看\n"));
+    }
+}
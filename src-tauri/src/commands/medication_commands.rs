@@ -0,0 +1,410 @@
+use chrono::{Datelike, Duration as Days, Local, NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::services::durable;
+use crate::services::notes::slugify;
+use crate::services::periodic::Periodic;
+
+use super::focus_commands::notifications_muted;
+/// Definitions, like daily/habits/habits.yaml for habits
+const MEDICATIONS_FILE: &str = "life/health/medications.yaml";
+/// date → doses taken or skipped
+const LOG_FILE: &str = "life/health/medication-log.yaml";
+/// A dose is announced once, within this many minutes after its time
+const REMIND_WINDOW_MIN: i64 = 15;
+const CHECK_EVERY: Duration = Duration::from_secs(30);
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Medication {
+    /// Empty on create to derive it from the name
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    /// Free text, e.g. "1 粒" or "500mg"
+    #[serde(default)]
+    pub dose: String,
+    /// HH:MM, one per daily dose
+    #[serde(default)]
+    pub times: Vec<String>,
+    /// 1=Mon..7=Sun like habits' target_days; empty = every day
+    #[serde(default)]
+    pub days: Vec<u32>,
+    /// YYYY-MM-DD; doses before `start` or after `end` are not expected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+    #[serde(default = "default_true")]
+    pub active: bool,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct MedicationFile {
+    #[serde(default)]
+    medications: Vec<Medication>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DoseLog {
+    pub id: String,
+    /// Scheduled HH:MM this entry answers
+    pub slot: String,
+    /// HH:MM actually taken (or marked skipped)
+    pub at: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
+}
+
+/// date (YYYY-MM-DD) → entries
+type MedicationLog = BTreeMap<String, Vec<DoseLog>>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DoseStatus {
+    pub id: String,
+    pub name: String,
+    pub dose: String,
+    pub slot: String,
+    /// HH:MM when taken/skipped
+    pub at: Option<String>,
+    pub skipped: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Adherence {
+    pub id: String,
+    pub name: String,
+    pub scheduled: usize,
+    pub taken: usize,
+    pub skipped: usize,
+    /// taken / scheduled, 0–1
+    pub rate: f64,
+    /// Consecutive fully-taken days ending at the range end
+    pub streak: usize,
+}
+
+// Only one reminder loop, bound to the open vault
+static REMINDERS: Periodic = Periodic::new();
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn list_medications(vault_path: String) -> Vec<Medication> {
+    load_medications(Path::new(&vault_path))
+}
+
+#[tauri::command]
+pub fn save_medication(vault_path: String, medication: Medication) -> Result<Medication, String> {
+    let vault = Path::new(&vault_path);
+    let mut med = medication;
+    med.name = med.name.trim().to_string();
+    if med.name.is_empty() {
+        return Err(tr!("Name is required"));
+    }
+    for t in &med.times {
+        parse_time(t)?;
+    }
+    med.times.sort();
+    let mut meds = load_medications(vault);
+    if med.id.is_empty() {
+        let base = slugify(&med.name);
+        med.id = base.clone();
+        let mut n = 2;
+        while meds.iter().any(|m| m.id == med.id) {
+            med.id = format!("{base}-{n}");
+            n += 1;
+        }
+    }
+    match meds.iter_mut().find(|m| m.id == med.id) {
+        Some(existing) => *existing = med.clone(),
+        None => meds.push(med.clone()),
+    }
+    write_yaml(&vault.join(MEDICATIONS_FILE), &MedicationFile { medications: meds })?;
+    Ok(med)
+}
+
+/// Remove the definition; its log entries are kept for history
+#[tauri::command]
+pub fn delete_medication(vault_path: String, id: String) -> Result<(), String> {
+    let vault = Path::new(&vault_path);
+    let mut meds = load_medications(vault);
+    let before = meds.len();
+    meds.retain(|m| m.id != id);
+    if meds.len() == before {
+        return Err(tr!("Medication not found: {}", id));
+    }
+    write_yaml(&vault.join(MEDICATIONS_FILE), &MedicationFile { medications: meds })
+}
+
+/// Mark a dose taken (or skipped). `slot` defaults to the scheduled time of
+/// `date` closest to now; logging the same slot again replaces the entry.
+#[tauri::command]
+pub fn log_medication_dose(
+    vault_path: String,
+    id: String,
+    slot: Option<String>,
+    date: Option<String>,
+    skipped: Option<bool>,
+) -> Result<DoseLog, String> {
+    let vault = Path::new(&vault_path);
+    let med = load_medications(vault).into_iter().find(|m| m.id == id).ok_or_else(|| tr!("Medication not found: {}", id))?;
+    let now = Local::now().naive_local();
+    let date = match date {
+        Some(d) => NaiveDate::parse_from_str(&d, "%Y-%m-%d").map_err(|_| tr!("Invalid date (expected YYYY-MM-DD): {}", d))?,
+        None => now.date(),
+    };
+    let slot = match slot {
+        Some(s) => s,
+        None => closest_slot(&med, now.time()).unwrap_or_else(|| now.format("%H:%M").to_string()),
+    };
+    let entry = DoseLog { id, slot, at: now.format("%H:%M").to_string(), skipped: skipped.unwrap_or(false) };
+
+    let mut log = load_log(vault);
+    let day = log.entry(date.format("%Y-%m-%d").to_string()).or_default();
+    day.retain(|e| !(e.id == entry.id && e.slot == entry.slot));
+    day.push(entry.clone());
+    write_yaml(&vault.join(LOG_FILE), &log)?;
+    Ok(entry)
+}
+
+/// Every dose due on `date` (default today) with what was logged for it
+#[tauri::command]
+pub fn get_medication_day(vault_path: String, date: Option<String>) -> Result<Vec<DoseStatus>, String> {
+    let vault = Path::new(&vault_path);
+    let date = match date {
+        Some(d) => NaiveDate::parse_from_str(&d, "%Y-%m-%d").map_err(|_| tr!("Invalid date (expected YYYY-MM-DD): {}", d))?,
+        None => Local::now().date_naive(),
+    };
+    Ok(day_status(&load_medications(vault), &load_log(vault), date))
+}
+
+/// Per-medication adherence over the last `days` days up to today (default 30)
+#[tauri::command]
+pub fn get_medication_adherence(vault_path: String, days: Option<i64>) -> Vec<Adherence> {
+    let vault = Path::new(&vault_path);
+    let end = Local::now().date_naive();
+    let start = end - Days::days(days.unwrap_or(30).max(1) - 1);
+    adherence(&load_medications(vault), &load_log(vault), start, end)
+}
+
+/// Notify at each scheduled dose that has not been logged yet. Calling again
+/// restarts it.
+#[tauri::command]
+pub fn start_medication_reminders(app: AppHandle, vault_path: String) {
+    let vault = PathBuf::from(vault_path);
+    let mut notified: HashSet<String> = HashSet::new();
+    REMINDERS.start(CHECK_EVERY, move || send_due_reminders(&app, &vault, &mut notified));
+}
+
+#[tauri::command]
+pub fn stop_medication_reminders() {
+    REMINDERS.stop();
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn load_medications(vault: &Path) -> Vec<Medication> {
+    fs::read_to_string(vault.join(MEDICATIONS_FILE))
+        .ok()
+        .and_then(|raw| serde_yaml::from_str::<MedicationFile>(&raw).ok())
+        .map(|f| f.medications)
+        .unwrap_or_default()
+}
+
+fn load_log(vault: &Path) -> MedicationLog {
+    fs::read_to_string(vault.join(LOG_FILE))
+        .ok()
+        .and_then(|raw| serde_yaml::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn write_yaml<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let yaml = serde_yaml::to_string(value).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    durable::write(path, yaml).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+fn parse_time(raw: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(raw.trim(), "%H:%M").map_err(|_| tr!("Invalid time (expected HH:MM): {}", raw))
+}
+
+/// Whether `med` has doses on `date`
+fn scheduled_on(med: &Medication, date: NaiveDate) -> bool {
+    let within = |bound: &Option<String>, after: bool| {
+        bound
+            .as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+            .is_none_or(|b| if after { date >= b } else { date <= b })
+    };
+    med.active
+        && (med.days.is_empty() || med.days.contains(&date.weekday().number_from_monday()))
+        && within(&med.start, true)
+        && within(&med.end, false)
+}
+
+fn closest_slot(med: &Medication, now: NaiveTime) -> Option<String> {
+    med.times
+        .iter()
+        .filter_map(|t| parse_time(t).ok().map(|parsed| (t, (parsed - now).num_minutes().abs())))
+        .min_by_key(|(_, diff)| *diff)
+        .map(|(t, _)| t.clone())
+}
+
+fn day_status(meds: &[Medication], log: &MedicationLog, date: NaiveDate) -> Vec<DoseStatus> {
+    let entries = log.get(&date.format("%Y-%m-%d").to_string()).map(Vec::as_slice).unwrap_or_default();
+    let mut out: Vec<DoseStatus> = meds
+        .iter()
+        .filter(|m| scheduled_on(m, date))
+        .flat_map(|m| {
+            m.times.iter().map(move |slot| {
+                let logged = entries.iter().find(|e| e.id == m.id && &e.slot == slot);
+                DoseStatus {
+                    id: m.id.clone(),
+                    name: m.name.clone(),
+                    dose: m.dose.clone(),
+                    slot: slot.clone(),
+                    at: logged.map(|e| e.at.clone()),
+                    skipped: logged.is_some_and(|e| e.skipped),
+                }
+            })
+        })
+        .collect();
+    out.sort_by(|a, b| a.slot.cmp(&b.slot).then(a.name.cmp(&b.name)));
+    out
+}
+
+fn adherence(meds: &[Medication], log: &MedicationLog, start: NaiveDate, end: NaiveDate) -> Vec<Adherence> {
+    meds.iter()
+        .map(|m| {
+            let mut a = Adherence { id: m.id.clone(), name: m.name.clone(), ..Default::default() };
+            let mut streak_open = true;
+            let mut date = end;
+            while date >= start {
+                if scheduled_on(m, date) && !m.times.is_empty() {
+                    let doses: Vec<DoseStatus> = day_status(std::slice::from_ref(m), log, date);
+                    let taken = doses.iter().filter(|d| d.at.is_some() && !d.skipped).count();
+                    a.scheduled += doses.len();
+                    a.taken += taken;
+                    a.skipped += doses.iter().filter(|d| d.skipped).count();
+                    // Today's later doses may simply not be due yet
+                    if taken == doses.len() {
+                        if streak_open {
+                            a.streak += 1;
+                        }
+                    } else if date != end {
+                        streak_open = false;
+                    }
+                }
+                date -= Days::days(1);
+            }
+            if a.scheduled > 0 {
+                a.rate = (a.taken as f64 / a.scheduled as f64 * 100.0).round() / 100.0;
+            }
+            a
+        })
+        .collect()
+}
+
+fn send_due_reminders(app: &AppHandle, vault: &Path, notified: &mut HashSet<String>) {
+    // Doses whose window passes during a focus session get no reminder
+    if notifications_muted() {
+        return;
+    }
+    let now = Local::now().naive_local();
+    let today = now.date();
+    for dose in day_status(&load_medications(vault), &load_log(vault), today) {
+        let Ok(slot) = parse_time(&dose.slot) else { continue };
+        let late = (now.time() - slot).num_minutes();
+        if dose.at.is_some() || !(0..REMIND_WINDOW_MIN).contains(&late) {
+            continue;
+        }
+        let key = format!("{today}|{}|{}", dose.id, dose.slot);
+        if !notified.insert(key) {
+            continue;
+        }
+        let title = tr!("Time to take {}", dose.name);
+        let body = if dose.dose.is_empty() { dose.slot.clone() } else { format!("{} · {}", dose.slot, dose.dose) };
+        if let Err(e) = app.notification().builder().title(&title).body(&body).show() {
+            println!("[WARN] failed to show reminder: {e}");
+        }
+    }
+    notified.retain(|k| k.starts_with(&today.to_string()));
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn med() -> Medication {
+        Medication {
+            id: "vd".into(),
+            name: "维生素D".into(),
+            times: vec!["08:00".into(), "20:00".into()],
+            active: true,
+            ..Default::default()
+        }
+    }
+
+    fn taken(slot: &str) -> DoseLog {
+        DoseLog { id: "vd".into(), slot: slot.into(), at: slot.into(), skipped: false }
+    }
+
+    #[test]
+    fn test_scheduled_on_days_and_bounds() {
+        let mut m = med();
+        m.days = vec![1, 3, 5];
+        m.end = Some("2025-03-31".into());
+        assert!(scheduled_on(&m, ymd(2025, 3, 3))); // Monday
+        assert!(!scheduled_on(&m, ymd(2025, 3, 4)));
+        assert!(!scheduled_on(&m, ymd(2025, 4, 7)));
+    }
+
+    #[test]
+    fn test_closest_slot() {
+        let m = med();
+        assert_eq!(closest_slot(&m, NaiveTime::from_hms_opt(9, 30, 0).unwrap()).as_deref(), Some("08:00"));
+        assert_eq!(closest_slot(&m, NaiveTime::from_hms_opt(18, 0, 0).unwrap()).as_deref(), Some("20:00"));
+    }
+
+    #[test]
+    fn test_adherence_and_streak() {
+        let mut log = MedicationLog::new();
+        log.insert("2025-03-01".into(), vec![taken("08:00"), taken("20:00")]);
+        log.insert("2025-03-02".into(), vec![taken("08:00")]);
+        log.insert("2025-03-03".into(), vec![taken("08:00"), taken("20:00")]);
+        log.insert("2025-03-04".into(), vec![taken("08:00"), taken("20:00")]);
+        let a = &adherence(&[med()], &log, ymd(2025, 3, 1), ymd(2025, 3, 4))[0];
+        assert_eq!((a.scheduled, a.taken), (8, 7));
+        assert_eq!(a.rate, 0.88);
+        assert_eq!(a.streak, 2);
+    }
+}
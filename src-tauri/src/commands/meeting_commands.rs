@@ -0,0 +1,234 @@
+use chrono::Local;
+use serde_yaml::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::template_commands::fill_missing;
+use crate::services::calendar::{ics_text, ics_time, vevents, IcsProperty, CALENDAR_DIR};
+use crate::services::people::{load_people, Person};
+use crate::services::notes::{self, slugify, split_frontmatter, vault_rel};
+use crate::services::templates::{self, Context};
+
+const MEETINGS_DIR: &str = "meetings";
+/// Optional; renders the body. `{{title}}`, `{{date}}`, `{{time}}`,
+/// `{{location}}`, `{{attendees}}` (a list) and `{{agenda}}` are filled in.
+const MEETING_TEMPLATE: &str = "templates/meeting.md";
+const DEFAULT_TEMPLATE: &str = "# {{title}}\n\n## 参会人\n\n{{attendees}}\n\n## 议程\n\n{{agenda}}\n\n## 记录\n\n\n## 待办\n\n- [ ] \n";
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+struct CalendarEvent {
+    uid: String,
+    /// Vault-relative .ics file
+    source: String,
+    title: String,
+    date: String,
+    time: Option<String>,
+    location: String,
+    description: String,
+    /// (display name, address) of the organizer and attendees
+    people: Vec<(String, String)>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Meeting note for calendar event `event_id` (its UID) under meetings/:
+/// attendees linked to their people/ notes, the description as agenda, and
+/// `event_id` in the frontmatter pointing back at the event. An existing
+/// note for the event is returned instead of making a second one.
+#[tauri::command]
+pub fn create_meeting_note(vault_path: String, event_id: String) -> Result<String, String> {
+    let vault = PathBuf::from(&vault_path);
+    if let Some(existing) = notes_by_event(&vault).remove(&event_id) {
+        return Ok(existing);
+    }
+    let event = find_event(&vault, &event_id).ok_or_else(|| tr!("Calendar event not found: {}", event_id))?;
+    let dest = vault.join(MEETINGS_DIR).join(format!("{}-{}.md", event.date, slugify(&event.title)));
+    if dest.exists() {
+        return Err(tr!("Note already exists: {}", dest.display()));
+    }
+    let content = render(&vault, &event, &load_people(&vault))?;
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    fs::write(&dest, content).map_err(|e| tr!("write_file failed: {}", e))?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// The meeting note created for `event_id`, if any
+#[tauri::command]
+pub fn find_meeting_note(vault_path: String, event_id: String) -> Option<String> {
+    notes_by_event(Path::new(&vault_path)).remove(&event_id)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Event UID → meeting note path, from the `event_id` frontmatter under meetings/
+pub(crate) fn notes_by_event(vault: &Path) -> HashMap<String, String> {
+    let Ok(entries) = fs::read_dir(vault.join(MEETINGS_DIR)) else { return HashMap::new() };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|x| x == "md"))
+        .filter_map(|p| {
+            let raw = fs::read_to_string(&p).ok()?;
+            let fm: Value = serde_yaml::from_str(split_frontmatter(&raw).0?).ok()?;
+            let id = fm.get("event_id")?.as_str()?.to_string();
+            Some((id, p.to_string_lossy().to_string()))
+        })
+        .collect()
+}
+
+fn find_event(vault: &Path, uid: &str) -> Option<CalendarEvent> {
+    let entries = fs::read_dir(vault.join(CALENDAR_DIR)).ok()?;
+    for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+        if path.extension().is_none_or(|x| !x.eq_ignore_ascii_case("ics")) {
+            continue;
+        }
+        let raw = fs::read_to_string(&path).unwrap_or_default();
+        let Some(props) = vevents(&raw).into_iter().find(|props| props.iter().any(|p| p.name == "UID" && p.value == uid)) else { continue };
+        let source = vault_rel(vault, &path);
+        return parse_event(&props, source);
+    }
+    None
+}
+
+fn parse_event(props: &[IcsProperty], source: String) -> Option<CalendarEvent> {
+    let first = |name: &str| props.iter().find(|p| p.name == name).map(|p| p.value.as_str());
+    let (start, all_day) = ics_time(first("DTSTART")?)?;
+    let mut people: Vec<(String, String)> = Vec::new();
+    for p in props.iter().filter(|p| p.name == "ORGANIZER" || p.name == "ATTENDEE") {
+        let address = p.value.trim().trim_start_matches("mailto:").trim_start_matches("MAILTO:").to_lowercase();
+        let name = p
+            .params
+            .split(';')
+            .find_map(|param| param.strip_prefix("CN="))
+            .map(|cn| cn.trim_matches('"').to_string())
+            .unwrap_or_default();
+        if !address.is_empty() && !people.iter().any(|(_, a)| *a == address) {
+            people.push((name, address));
+        }
+    }
+    Some(CalendarEvent {
+        uid: first("UID")?.to_string(),
+        source,
+        title: first("SUMMARY").map(|s| ics_text(s, false)).unwrap_or_default(),
+        date: start.format("%Y-%m-%d").to_string(),
+        time: (!all_day).then(|| start.format("%H:%M").to_string()),
+        location: first("LOCATION").map(|s| ics_text(s, false)).unwrap_or_default(),
+        description: first("DESCRIPTION").map(|s| ics_text(s, true)).unwrap_or_default(),
+        people,
+    })
+}
+
+fn render(vault: &Path, event: &CalendarEvent, people: &[Person]) -> Result<String, String> {
+    // People notes by address; those without one stay plain addresses
+    let attendees: Vec<(String, Option<&Person>)> = event
+        .people
+        .iter()
+        .map(|(name, address)| {
+            let person = people.iter().find(|p| p.meta.emails.iter().any(|e| e.trim().eq_ignore_ascii_case(address)));
+            let label = if name.is_empty() { address.clone() } else { name.clone() };
+            (label, person)
+        })
+        .collect();
+    let list = attendees
+        .iter()
+        .map(|(label, person)| match person {
+            Some(p) => format!("- [[{}]]", p.slug),
+            None => format!("- {label}"),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let agenda = event
+        .description
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| if l.starts_with(['-', '*']) { l.to_string() } else { format!("- {l}") })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let template = fs::read_to_string(vault.join(MEETING_TEMPLATE)).unwrap_or_else(|_| DEFAULT_TEMPLATE.to_string());
+    let mut ctx = Context::new(Local::now().naive_local()).with_user_vars(vault);
+    let vars = [
+        ("title", event.title.clone()),
+        ("date", event.date.clone()),
+        ("time", event.time.clone().unwrap_or_default()),
+        ("location", event.location.clone()),
+        ("attendees", list),
+        ("agenda", agenda),
+    ];
+    ctx.vars.extend(vars.map(|(k, v)| (k.to_string(), v)));
+    let body = templates::render(&template, &ctx)?;
+
+    // `attendees` holds people slugs, or addresses, as link_people expects
+    let keys: Vec<Value> = event
+        .people
+        .iter()
+        .zip(&attendees)
+        .map(|((_, address), (_, person))| Value::from(person.map_or(address.as_str(), |p| p.slug.as_str())))
+        .collect();
+    let mut defaults = vec![("title", Value::from(event.title.as_str())), ("date", Value::from(event.date.as_str()))];
+    if let Some(time) = &event.time {
+        defaults.push(("time", Value::from(time.as_str())));
+    }
+    if !event.location.is_empty() {
+        defaults.push(("location", Value::from(event.location.as_str())));
+    }
+    defaults.push(("attendees", Value::Sequence(keys)));
+    let content = fill_missing(&body, &defaults)?;
+    let content = notes::set_field(&content, "event_id", Some(&Value::from(event.uid.as_str())))?;
+    notes::set_field(&content, "event", Some(&Value::from(event.source.as_str())))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, rel: &str, text: &str) {
+        let path = dir.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    #[test]
+    fn test_create_meeting_note() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        write(v, "people/zhang-san.md", "---\nname: 张三\nemails: [zhang@example.com]\n---\n");
+        write(
+            v,
+            "connectors/calendar/work.ics",
+            "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:evt-42@example.com\r\nDTSTART:20260310T140000\r\nSUMMARY:Roadmap review\r\nDESCRIPTION:Q2 priorities\\nHiring plan\r\nORGANIZER;CN=\"Zhang San\":mailto:ZHANG@example.com\r\nATTENDEE;CN=Bob;ROLE=REQ-PARTICIPANT:mailto:bob@example.com\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+        );
+        let path = v.to_string_lossy().to_string();
+
+        let note_path = create_meeting_note(path.clone(), "evt-42@example.com".into()).unwrap();
+        assert!(note_path.ends_with("meetings/2026-03-10-roadmap-review.md"));
+        let note = fs::read_to_string(&note_path).unwrap();
+        let fm: Value = serde_yaml::from_str(split_frontmatter(&note).0.unwrap()).unwrap();
+        assert_eq!(fm["event_id"].as_str(), Some("evt-42@example.com"));
+        assert_eq!(fm["event"].as_str(), Some("connectors/calendar/work.ics"));
+        assert_eq!(fm["time"].as_str(), Some("14:00"));
+        assert_eq!(fm["attendees"], serde_yaml::from_str::<Value>("[zhang-san, bob@example.com]").unwrap());
+        assert!(note.contains("- [[zhang-san]]\n- Bob"));
+        assert!(note.contains("- Q2 priorities\n- Hiring plan"));
+
+        // Asked again, the same note comes back
+        assert_eq!(create_meeting_note(path.clone(), "evt-42@example.com".into()).unwrap(), note_path);
+        assert_eq!(find_meeting_note(path.clone(), "evt-42@example.com".into()), Some(note_path));
+        assert!(create_meeting_note(path, "nope".into()).is_err());
+    }
+}
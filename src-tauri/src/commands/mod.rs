@@ -2,3 +2,61 @@ pub mod fs_commands;
 pub mod vault_commands;
 pub mod extra_commands;
 pub mod email_commands;
+pub mod platform_commands;
+pub mod watch_commands;
+pub mod automation_commands;
+pub mod people_commands;
+pub mod occasion_commands;
+pub mod finance_commands;
+pub mod subscription_commands;
+pub mod book_commands;
+pub mod media_commands;
+pub mod nutrition_commands;
+pub mod medication_commands;
+pub mod sleep_commands;
+pub mod mood_commands;
+pub mod trip_commands;
+pub mod location_commands;
+pub mod weather_commands;
+pub mod secret_commands;
+pub mod asset_commands;
+pub mod pdf_commands;
+pub mod screenshot_commands;
+pub mod template_commands;
+pub mod history_commands;
+pub mod sync_commands;
+pub mod export_commands;
+pub mod board_commands;
+pub mod agenda_commands;
+pub mod focus_commands;
+pub mod habit_commands;
+pub mod activity_commands;
+pub mod triage_commands;
+pub mod task_commands;
+pub mod editor_commands;
+pub mod drop_commands;
+pub mod mail_setup_commands;
+pub mod background_commands;
+pub mod state_commands;
+pub mod spotlight_commands;
+pub mod switcher_commands;
+pub mod followup_commands;
+pub mod meeting_commands;
+pub mod planner_commands;
+pub mod prompt_commands;
+pub mod link_commands;
+pub mod task_export_commands;
+pub mod calendar_sync_commands;
+pub mod contact_sync_commands;
+pub mod chat_import_commands;
+pub mod imessage_commands;
+pub mod commit_summary_commands;
+pub mod dev_env_commands;
+pub mod storage_commands;
+pub mod mail_digest_commands;
+pub mod reader_commands;
+pub mod outbox_commands;
+pub mod audit_commands;
+pub mod schema_commands;
+pub mod note_commands;
+pub mod canvas_commands;
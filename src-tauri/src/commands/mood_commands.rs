@@ -0,0 +1,188 @@
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use super::sleep_commands;
+use crate::services::{mood, stats, tasks};
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct MoodDay {
+    pub date: String,
+    /// Average 1–5 score over the day's diary entries and daily note
+    pub mood: Option<f64>,
+    pub energy: Option<f64>,
+    /// Raw `mood` values as written, in file order
+    pub moods: Vec<String>,
+    pub sleep_minutes: Option<u32>,
+    pub tasks_done: usize,
+    pub tasks_total: usize,
+    /// done / total, None without tasks
+    pub completion: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct MoodCorrelations {
+    pub mood_sleep: Option<f64>,
+    pub mood_tasks: Option<f64>,
+    pub mood_energy: Option<f64>,
+    pub energy_sleep: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MoodTrends {
+    pub from: String,
+    pub to: String,
+    /// Every day in the range, including ones with nothing logged
+    pub days: Vec<MoodDay>,
+    pub average_mood: Option<f64>,
+    pub average_energy: Option<f64>,
+    /// Raw mood value → number of entries
+    pub distribution: BTreeMap<String, usize>,
+    /// Average mood Monday..Sunday
+    pub by_weekday: Vec<Option<f64>>,
+    /// Pearson r per pair of series, over days that have both
+    pub correlations: MoodCorrelations,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Mood/energy time-series for `range` (see `stats::parse_range`) from
+/// diary and daily-note frontmatter, joined with logged sleep and the daily
+/// note's task completion
+#[tauri::command]
+pub fn get_mood_trends(vault_path: String, range: String) -> Result<MoodTrends, String> {
+    let vault = Path::new(&vault_path);
+    let (from, to) = stats::parse_range(&range, Local::now().date_naive())?;
+    let frontmatter = mood::dated_frontmatter(vault);
+    let sleep: BTreeMap<String, u32> =
+        sleep_commands::load_range(vault, from, to)?.into_iter().map(|e| (e.date, e.minutes)).collect();
+
+    let mut days = Vec::new();
+    let mut date = from;
+    while date <= to {
+        let key = date.format("%Y-%m-%d").to_string();
+        let fms = frontmatter.get(&date).map(Vec::as_slice).unwrap_or_default();
+        let (done, total) = fs::read_to_string(vault.join("daily/tasks").join(format!("{key}.md")))
+            .map(|raw| tasks::count_tasks(&raw))
+            .unwrap_or((0, 0));
+        let mut day = collect_day(key, fms, done, total);
+        day.sleep_minutes = sleep.get(&day.date).copied();
+        days.push(day);
+        date += Duration::days(1);
+    }
+    Ok(trends(from, to, days))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn collect_day(date: String, fms: &[serde_yaml::Value], tasks_done: usize, tasks_total: usize) -> MoodDay {
+    let moods: Vec<String> = fms.iter().filter_map(|fm| mood::field(fm, "mood")).filter(|m| !m.trim().is_empty()).collect();
+    let scores: Vec<f64> = moods.iter().filter_map(|m| mood::score(m)).collect();
+    let energies: Vec<f64> = fms.iter().filter_map(|fm| mood::energy_score(&mood::field(fm, "energy")?)).collect();
+    MoodDay {
+        date,
+        mood: stats::mean(&scores).map(|m| stats::round(m, 2)),
+        energy: stats::mean(&energies).map(|m| stats::round(m, 2)),
+        moods,
+        sleep_minutes: None,
+        tasks_done,
+        tasks_total,
+        completion: (tasks_total > 0).then(|| stats::round(tasks_done as f64 / tasks_total as f64, 2)),
+    }
+}
+
+fn trends(from: NaiveDate, to: NaiveDate, days: Vec<MoodDay>) -> MoodTrends {
+    let series = |get: &dyn Fn(&MoodDay) -> Option<f64>| days.iter().filter_map(get).collect::<Vec<_>>();
+    let pairs = |a: &dyn Fn(&MoodDay) -> Option<f64>, b: &dyn Fn(&MoodDay) -> Option<f64>| {
+        stats::pearson(&days.iter().filter_map(|d| Some((a(d)?, b(d)?))).collect::<Vec<_>>())
+    };
+    let mood = |d: &MoodDay| d.mood;
+    let energy = |d: &MoodDay| d.energy;
+    let sleep = |d: &MoodDay| d.sleep_minutes.map(f64::from);
+    let completion = |d: &MoodDay| d.completion;
+
+    let mut distribution: BTreeMap<String, usize> = BTreeMap::new();
+    let mut weekday: [Vec<f64>; 7] = Default::default();
+    for day in &days {
+        for m in &day.moods {
+            *distribution.entry(m.trim().to_string()).or_default() += 1;
+        }
+        if let (Some(score), Ok(date)) = (day.mood, NaiveDate::parse_from_str(&day.date, "%Y-%m-%d")) {
+            weekday[date.weekday().num_days_from_monday() as usize].push(score);
+        }
+    }
+
+    MoodTrends {
+        from: from.format("%Y-%m-%d").to_string(),
+        to: to.format("%Y-%m-%d").to_string(),
+        average_mood: stats::mean(&series(&mood)).map(|m| stats::round(m, 2)),
+        average_energy: stats::mean(&series(&energy)).map(|m| stats::round(m, 2)),
+        distribution,
+        by_weekday: weekday.iter().map(|s| stats::mean(s).map(|m| stats::round(m, 2))).collect(),
+        correlations: MoodCorrelations {
+            mood_sleep: pairs(&mood, &sleep),
+            mood_tasks: pairs(&mood, &completion),
+            mood_energy: pairs(&mood, &energy),
+            energy_sleep: pairs(&energy, &sleep),
+        },
+        days,
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fm(yaml: &str) -> serde_yaml::Value {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_collect_day() {
+        let day = collect_day("2025-03-03".into(), &[fm("mood: 😊\nenergy: high"), fm("mood: 2\nenergy: low"), fm("title: x")], 3, 4);
+        assert_eq!(day.moods, vec!["😊", "2"]);
+        assert_eq!(day.mood, Some(3.0));
+        assert_eq!(day.energy, Some(3.0));
+        assert_eq!(day.completion, Some(0.75));
+        assert_eq!(collect_day("2025-03-04".into(), &[], 0, 0).completion, None);
+    }
+
+    #[test]
+    fn test_trends_correlations() {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let days: Vec<MoodDay> = [(5.0, 480, 1.0), (4.0, 420, 0.8), (2.0, 300, 0.2), (3.0, 360, 0.5)]
+            .iter()
+            .enumerate()
+            .map(|(i, &(m, sleep, done))| MoodDay {
+                date: (start + Duration::days(i as i64)).format("%Y-%m-%d").to_string(),
+                mood: Some(m),
+                moods: vec![m.to_string()],
+                sleep_minutes: Some(sleep),
+                completion: Some(done),
+                ..Default::default()
+            })
+            .collect();
+        let t = trends(start, start + Duration::days(3), days);
+        assert_eq!(t.average_mood, Some(3.5));
+        assert_eq!(t.by_weekday[0], Some(5.0));
+        assert_eq!(t.by_weekday[6], None);
+        assert_eq!(t.distribution["5"], 1);
+        assert!(t.correlations.mood_sleep.unwrap() > 0.9);
+        assert!(t.correlations.mood_tasks.unwrap() > 0.9);
+        assert_eq!(t.correlations.mood_energy, None);
+    }
+}
@@ -0,0 +1,523 @@
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use super::link_commands::retarget_links;
+use crate::services::notes::{slugify, split_frontmatter};
+use crate::services::{journal, transfer};
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeStrategy {
+    /// The first note as it is, then each other under a heading with its title
+    #[default]
+    Concatenate,
+    /// Sections with the same heading brought together, in the order they
+    /// first appear: the "Decisions" of every meeting note under one heading
+    Interleave,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MergeReport {
+    /// Vault-relative
+    pub target: String,
+    /// Vault-relative notes merged into the target and moved to the trash
+    pub merged: Vec<String>,
+    /// Links in other notes pointed at the target
+    pub links: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SplitReport {
+    /// Vault-relative; the original note, now an index of the pieces
+    pub index: String,
+    /// Vault-relative, in the order they appeared
+    pub pieces: Vec<String>,
+    /// Links in other notes pointed at the piece with their heading
+    pub links: usize,
+}
+
+/// A heading and the ones under it
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct OutlineHeading {
+    pub text: String,
+    pub level: usize,
+    /// 1-based, in the file (frontmatter counted)
+    pub line: usize,
+    /// Last line of the section, subsections included
+    pub end_line: usize,
+    pub children: Vec<OutlineHeading>,
+}
+
+struct Note {
+    rel: String,
+    frontmatter: Option<Value>,
+    body: String,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Merge `sources` into `target`, which may be one of them, an existing note
+/// (taken first) or a new one. Frontmatter is the first note's, plus keys
+/// only later notes have, with lists combined. Links to the sources are
+/// pointed at the target and the sources go to the trash, one undoable
+/// delete each. Relative markdown links inside the merged text are kept as
+/// written, so they only still work when the notes share a folder.
+#[tauri::command]
+pub async fn merge_notes(vault_path: String, sources: Vec<String>, target: String, strategy: Option<MergeStrategy>) -> Result<MergeReport, String> {
+    tokio::task::spawn_blocking(move || merge(Path::new(&vault_path), &sources, &target, strategy.unwrap_or_default()))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Break `path` into one note per heading of `level`, written to `dest_dir`
+/// (default: a folder named after the note next to it). Each piece gets a
+/// title, the note's tags and a `parent` link back; the original keeps its
+/// frontmatter and any text outside those sections, with a list of links
+/// where the sections were. Links to `note#heading` elsewhere are pointed at
+/// the piece; links to the note itself still land on the index.
+#[tauri::command]
+pub async fn split_note(vault_path: String, path: String, level: usize, dest_dir: Option<String>) -> Result<SplitReport, String> {
+    tokio::task::spawn_blocking(move || split(Path::new(&vault_path), &path, level, dest_dir.as_deref()))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// The note's headings as a tree, skipping `#` lines in code blocks
+#[tauri::command]
+pub fn get_note_outline(path: String) -> Result<Vec<OutlineHeading>, String> {
+    let raw = fs::read_to_string(&path).map_err(|e| tr!("Failed to read: {}", e))?;
+    Ok(outline(&raw))
+}
+
+/// The section under the first heading matching `heading` (case-insensitive,
+/// with or without its `#`s): the heading line and everything up to the next
+/// heading of its level or above
+#[tauri::command]
+pub fn get_section(path: String, heading: String) -> Result<String, String> {
+    let raw = fs::read_to_string(&path).map_err(|e| tr!("Failed to read: {}", e))?;
+    section(&raw, &heading).ok_or_else(|| tr!("Heading not found: {}", heading))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn merge(vault: &Path, sources: &[String], target: &str, strategy: MergeStrategy) -> Result<MergeReport, String> {
+    let target = relative(vault, target)?;
+    let mut rels: Vec<String> = Vec::new();
+    if vault.join(&target).exists() && !sources.iter().any(|s| relative(vault, s).as_ref() == Ok(&target)) {
+        rels.push(target.clone());
+    }
+    for source in sources {
+        let rel = relative(vault, source)?;
+        if !rels.contains(&rel) {
+            rels.push(rel);
+        }
+    }
+    if rels.iter().all(|rel| *rel == target) {
+        return Err(tr!("Nothing to merge"));
+    }
+
+    let mut notes = Vec::new();
+    for rel in &rels {
+        let raw = fs::read_to_string(vault.join(rel)).map_err(|e| tr!("Failed to read: {}", e))?;
+        let (yaml, body) = split_frontmatter(&raw);
+        let frontmatter = yaml.and_then(|y| serde_yaml::from_str::<Value>(y).ok()).filter(Value::is_mapping);
+        notes.push(Note { rel: rel.clone(), frontmatter, body: body.to_string() });
+    }
+    let frontmatter = merge_frontmatter(notes.iter().filter_map(|n| n.frontmatter.as_ref()));
+    let body = match strategy {
+        MergeStrategy::Concatenate => concatenate(&notes),
+        MergeStrategy::Interleave => interleave(&notes),
+    };
+    let content = if frontmatter.is_empty() {
+        body
+    } else {
+        let yaml = serde_yaml::to_string(&frontmatter).map_err(|e| tr!("Failed to serialize: {}", e))?;
+        format!("---\n{yaml}---\n\n{body}")
+    };
+    let path = vault.join(&target);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    fs::write(&path, content).map_err(|e| tr!("write_file failed: {}", e))?;
+
+    let merged: Vec<String> = rels.into_iter().filter(|rel| *rel != target).collect();
+    let moved: BTreeMap<String, String> = merged.iter().map(|rel| (rel.clone(), target.clone())).collect();
+    let links = retarget_links(vault, &moved)?;
+    for rel in &merged {
+        journal::delete(vault, &vault.join(rel))?;
+    }
+    Ok(MergeReport { target, merged, links })
+}
+
+fn split(vault: &Path, path: &str, level: usize, dest_dir: Option<&str>) -> Result<SplitReport, String> {
+    let rel = relative(vault, path)?;
+    let raw = fs::read_to_string(vault.join(&rel)).map_err(|e| tr!("Failed to read: {}", e))?;
+    let (yaml, body) = split_frontmatter(&raw);
+    let lines: Vec<&str> = body.lines().collect();
+    // A section ends at the next heading of its level or above
+    let bounds: Vec<(usize, usize)> = headings(body).into_iter().filter(|(_, l)| *l <= level).collect();
+    if !bounds.iter().any(|(_, l)| *l == level) {
+        return Err(tr!("No headings at level {} in {}", level, rel));
+    }
+
+    let note = Path::new(&rel).with_extension("");
+    let parent = note.to_string_lossy().replace('\\', "/");
+    let dest = match dest_dir {
+        Some(dir) => relative(vault, dir)?,
+        None => parent.clone(),
+    };
+    fs::create_dir_all(vault.join(&dest)).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    let tags = yaml.and_then(|y| serde_yaml::from_str::<Value>(y).ok()).and_then(|fm| fm.get("tags").cloned());
+
+    let mut index: Vec<String> = Vec::new();
+    let mut pieces = Vec::new();
+    let mut moved = BTreeMap::new();
+    let mut cursor = 0;
+    let mut after_link = false;
+    for (n, &(start, l)) in bounds.iter().enumerate() {
+        if l != level {
+            continue;
+        }
+        let end = bounds.get(n + 1).map(|b| b.0).unwrap_or(lines.len());
+        let between = lines[cursor..start].join("\n");
+        if !between.trim().is_empty() {
+            index.push(between.trim().to_string());
+            after_link = false;
+        }
+        cursor = end;
+
+        let title = heading_text(lines[start], level);
+        let slug = if title.chars().any(char::is_alphanumeric) { slugify(&title) } else { "section".to_string() };
+        let mut file = vault.join(&dest).join(format!("{:02}-{slug}.md", pieces.len() + 1));
+        if file.exists() {
+            file = transfer::free_name(&file);
+        }
+        let mut frontmatter = Mapping::new();
+        frontmatter.insert("title".into(), title.clone().into());
+        frontmatter.insert("parent".into(), format!("[[{parent}]]").into());
+        if let Some(tags) = &tags {
+            frontmatter.insert("tags".into(), tags.clone());
+        }
+        let yaml = serde_yaml::to_string(&frontmatter).map_err(|e| tr!("Failed to serialize: {}", e))?;
+        let text = lines[start + 1..end].join("\n");
+        fs::write(&file, format!("---\n{yaml}---\n\n# {title}\n\n{}\n", text.trim())).map_err(|e| tr!("write_file failed: {}", e))?;
+
+        let piece = relative(vault, &file.to_string_lossy())?;
+        let link = format!("- [[{}|{title}]]", piece.trim_end_matches(".md"));
+        match index.last_mut() {
+            Some(list) if after_link => *list = format!("{list}\n{link}"),
+            _ => index.push(link),
+        }
+        after_link = true;
+        moved.insert(format!("{rel}#{}", title.to_lowercase()), piece.clone());
+        pieces.push(piece);
+    }
+    let rest = lines[cursor..].join("\n");
+    if !rest.trim().is_empty() {
+        index.push(rest.trim().to_string());
+    }
+    let body = format!("{}\n", index.join("\n\n"));
+    let content = match yaml {
+        Some(yaml) => format!("---\n{}\n---\n\n{body}", yaml.trim_matches(['\r', '\n'])),
+        None => body,
+    };
+    fs::write(vault.join(&rel), content).map_err(|e| tr!("write_file failed: {}", e))?;
+
+    let links = retarget_links(vault, &moved)?;
+    Ok(SplitReport { index: rel, pieces, links })
+}
+
+fn outline(raw: &str) -> Vec<OutlineHeading> {
+    let (_, body) = split_frontmatter(raw);
+    let offset = raw[..raw.len() - body.len()].matches('\n').count();
+    let lines: Vec<&str> = body.lines().collect();
+    let found = headings(body);
+
+    let mut roots = Vec::new();
+    let mut open: Vec<OutlineHeading> = Vec::new();
+    for (n, &(i, level)) in found.iter().enumerate() {
+        let end = found[n + 1..].iter().find(|(_, l)| *l <= level).map(|(j, _)| *j).unwrap_or(lines.len());
+        while open.last().is_some_and(|h| h.level >= level) {
+            close(&mut open, &mut roots);
+        }
+        let text = heading_text(lines[i], level);
+        open.push(OutlineHeading { text, level, line: offset + i + 1, end_line: offset + end, children: Vec::new() });
+    }
+    while !open.is_empty() {
+        close(&mut open, &mut roots);
+    }
+    roots
+}
+
+/// Move the innermost open heading into its parent
+fn close(open: &mut Vec<OutlineHeading>, roots: &mut Vec<OutlineHeading>) {
+    let Some(done) = open.pop() else { return };
+    match open.last_mut() {
+        Some(parent) => parent.children.push(done),
+        None => roots.push(done),
+    }
+}
+
+fn section(raw: &str, heading: &str) -> Option<String> {
+    let (_, body) = split_frontmatter(raw);
+    let lines: Vec<&str> = body.lines().collect();
+    let wanted = heading.trim().trim_start_matches('#').trim().to_lowercase();
+    let found = headings(body);
+    let n = found.iter().position(|&(i, level)| heading_text(lines[i], level).to_lowercase() == wanted)?;
+    let (start, level) = found[n];
+    let end = found[n + 1..].iter().find(|(_, l)| *l <= level).map(|(j, _)| *j).unwrap_or(lines.len());
+    Some(format!("{}\n", lines[start..end].join("\n").trim_end()))
+}
+
+/// The text of a heading line, closing `#`s dropped
+fn heading_text(line: &str, level: usize) -> String {
+    line[level..].trim().trim_end_matches('#').trim().to_string()
+}
+
+/// The first frontmatter, plus keys only later ones have; lists (tags,
+/// attendees, …) get the items they were missing
+fn merge_frontmatter<'a>(all: impl Iterator<Item = &'a Value>) -> Mapping {
+    let mut merged = Mapping::new();
+    for map in all.filter_map(Value::as_mapping) {
+        for (key, value) in map {
+            match (merged.get_mut(key), value) {
+                (None, _) => {
+                    merged.insert(key.clone(), value.clone());
+                }
+                (Some(Value::Sequence(have)), Value::Sequence(more)) => {
+                    for item in more {
+                        if !have.contains(item) {
+                            have.push(item.clone());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    merged
+}
+
+fn concatenate(notes: &[Note]) -> String {
+    let mut parts = Vec::new();
+    for (i, note) in notes.iter().enumerate() {
+        let body = note.body.trim();
+        if i == 0 {
+            parts.push(body.to_string());
+            continue;
+        }
+        let (h1, rest) = split_title(body);
+        let title = h1.map(str::to_string).unwrap_or_else(|| title_of(note));
+        parts.push(format!("## {title}\n\n{}", rest.trim()));
+    }
+    format!("{}\n", parts.join("\n\n").trim())
+}
+
+fn interleave(notes: &[Note]) -> String {
+    let bodies: Vec<&str> = notes
+        .iter()
+        .enumerate()
+        .map(|(i, note)| if i == 0 { note.body.trim() } else { split_title(note.body.trim()).1 })
+        .collect();
+    // Split at the outermost heading below the title
+    let level = bodies.iter().flat_map(|b| headings(b)).map(|(_, level)| level).filter(|l| *l >= 2).min().unwrap_or(2);
+
+    let mut preambles = Vec::new();
+    let mut order: Vec<String> = Vec::new();
+    let mut sections: BTreeMap<String, (String, Vec<String>)> = BTreeMap::new();
+    for body in bodies {
+        let lines: Vec<&str> = body.lines().collect();
+        let starts: Vec<usize> = headings(body).into_iter().filter(|(_, l)| *l == level).map(|(i, _)| i).collect();
+        let preamble = lines[..starts.first().copied().unwrap_or(lines.len())].join("\n");
+        if !preamble.trim().is_empty() {
+            preambles.push(preamble.trim().to_string());
+        }
+        for (n, &start) in starts.iter().enumerate() {
+            let end = starts.get(n + 1).copied().unwrap_or(lines.len());
+            let heading = lines[start].trim().to_string();
+            let key = heading.trim_start_matches('#').trim().to_lowercase();
+            let text = lines[start + 1..end].join("\n").trim().to_string();
+            let entry = sections.entry(key.clone()).or_insert_with(|| {
+                order.push(key);
+                (heading, Vec::new())
+            });
+            if !text.is_empty() {
+                entry.1.push(text);
+            }
+        }
+    }
+
+    let mut parts = preambles;
+    for key in order {
+        let (heading, texts) = &sections[&key];
+        let mut section = heading.clone();
+        if !texts.is_empty() {
+            section = format!("{section}\n\n{}", texts.join("\n\n"));
+        }
+        parts.push(section);
+    }
+    format!("{}\n", parts.join("\n\n").trim())
+}
+
+/// (line index, level) of the ATX headings outside code fences
+fn headings(body: &str) -> Vec<(usize, usize)> {
+    let mut in_fence = false;
+    let mut found = Vec::new();
+    for (i, line) in body.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        let level = line.chars().take_while(|c| *c == '#').count();
+        if !in_fence && (1..=6).contains(&level) && line[level..].starts_with(' ') {
+            found.push((i, level));
+        }
+    }
+    found
+}
+
+/// A leading `# Title` line and what follows it
+fn split_title(body: &str) -> (Option<&str>, &str) {
+    match body.split_once('\n') {
+        Some((first, rest)) if first.starts_with("# ") => (Some(first[2..].trim()), rest),
+        None if body.starts_with("# ") => (Some(body[2..].trim()), ""),
+        _ => (None, body),
+    }
+}
+
+/// Frontmatter title, else the file name
+fn title_of(note: &Note) -> String {
+    note.frontmatter
+        .as_ref()
+        .and_then(|fm| fm.get("title"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| Path::new(&note.rel).file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default())
+}
+
+/// Vault-relative form of an absolute or vault-relative path in the vault
+fn relative(vault: &Path, path: &str) -> Result<String, String> {
+    let full = vault.join(path);
+    full.strip_prefix(vault)
+        .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+        .map_err(|_| tr!("Path is outside the vault: {}", path))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, rel: &str, text: &str) {
+        let path = dir.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    #[test]
+    fn test_merge_concatenate() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        write(v, "meetings/kickoff.md", "---\ntitle: Kickoff\nuid: k1\ntags:\n- launch\n---\n\n# Kickoff\n\nScope agreed.\n");
+        write(v, "meetings/kickoff-2.md", "---\ntitle: Kickoff (copy)\ntags:\n- launch\n- q3\nattendees:\n- 老王\n---\n\nBudget too.\n");
+        write(v, "diary/a.md", "See [[kickoff-2]] and [[kickoff]].\n");
+
+        let sources = vec!["meetings/kickoff.md".to_string(), v.join("meetings/kickoff-2.md").to_string_lossy().to_string()];
+        let report = merge(v, &sources, "meetings/kickoff.md", MergeStrategy::Concatenate).unwrap();
+        assert_eq!((report.merged, report.links), (vec!["meetings/kickoff-2.md".to_string()], 1));
+
+        let merged = fs::read_to_string(v.join("meetings/kickoff.md")).unwrap();
+        let (yaml, body) = split_frontmatter(&merged);
+        let fm: Value = serde_yaml::from_str(yaml.unwrap()).unwrap();
+        assert_eq!((fm["title"].as_str(), fm["uid"].as_str()), (Some("Kickoff"), Some("k1")));
+        assert_eq!(fm["tags"], serde_yaml::from_str::<Value>("[launch, q3]").unwrap());
+        assert_eq!(fm["attendees"][0].as_str(), Some("老王"));
+        assert_eq!(body, "# Kickoff\n\nScope agreed.\n\n## Kickoff (copy)\n\nBudget too.\n");
+
+        assert_eq!(fs::read_to_string(v.join("diary/a.md")).unwrap(), "See [[kickoff]] and [[kickoff]].\n");
+        assert!(!v.join("meetings/kickoff-2.md").exists());
+        assert_eq!(journal::recent(v, 1)[0].path, "meetings/kickoff-2.md");
+        assert!(merge(v, &["meetings/kickoff.md".to_string()], "meetings/kickoff.md", MergeStrategy::Concatenate).is_err());
+    }
+
+    #[test]
+    fn test_merge_interleave() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        write(v, "a.md", "# Standup Mon\n\nIntro\n\n## Decisions\n\n- ship\n\n## Actions\n\n- [ ] write docs\n");
+        write(v, "b.md", "# Standup Tue\n\n## Actions\n\n- [ ] fix bug\n\n## Risks\n\n```\n## not a heading\n```\n");
+
+        let sources = vec!["a.md".to_string(), "b.md".to_string()];
+        merge(v, &sources, "standups.md", MergeStrategy::Interleave).unwrap();
+        let merged = fs::read_to_string(v.join("standups.md")).unwrap();
+        assert_eq!(
+            merged,
+            "# Standup Mon\n\nIntro\n\n## Decisions\n\n- ship\n\n## Actions\n\n- [ ] write docs\n\n- [ ] fix bug\n\n## Risks\n\n```\n## not a heading\n```\n"
+        );
+        assert!(!v.join("a.md").exists() && !v.join("b.md").exists());
+    }
+
+    #[test]
+    fn test_split_note() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        write(
+            v,
+            "projects/launch.md",
+            "---\ntitle: Launch\ntags:\n- q3\n---\n\n# Launch\n\nOverview.\n\n## Budget\n\n100k\n\n### Details\n\nmore\n\n## 时间线\n\nMay\n\n# Appendix\n\nRefs\n",
+        );
+        write(v, "diary/a.md", "See [[launch#Budget|the budget]], [[launch]], [[launch#时间线]] and [plan](../projects/launch.md#budget).\n");
+
+        let report = split(v, "projects/launch.md", 2, None).unwrap();
+        assert_eq!(report.pieces, vec!["projects/launch/01-budget.md".to_string(), "projects/launch/02-时间线.md".to_string()]);
+        assert_eq!(report.links, 3);
+
+        let index = fs::read_to_string(v.join("projects/launch.md")).unwrap();
+        assert_eq!(
+            index,
+            "---\ntitle: Launch\ntags:\n- q3\n---\n\n# Launch\n\nOverview.\n\n- [[projects/launch/01-budget|Budget]]\n- [[projects/launch/02-时间线|时间线]]\n\n# Appendix\n\nRefs\n"
+        );
+        let budget = fs::read_to_string(v.join("projects/launch/01-budget.md")).unwrap();
+        let (yaml, body) = split_frontmatter(&budget);
+        let fm: Value = serde_yaml::from_str(yaml.unwrap()).unwrap();
+        assert_eq!((fm["title"].as_str(), fm["parent"].as_str()), (Some("Budget"), Some("[[projects/launch]]")));
+        assert_eq!(fm["tags"][0].as_str(), Some("q3"));
+        assert_eq!(body, "# Budget\n\n100k\n\n### Details\n\nmore\n");
+
+        assert_eq!(
+            fs::read_to_string(v.join("diary/a.md")).unwrap(),
+            "See [[01-budget|the budget]], [[launch]], [[02-时间线]] and [plan](../projects/launch/01-budget.md).\n"
+        );
+        assert!(split(v, "projects/launch.md", 4, None).is_err());
+    }
+
+    #[test]
+    fn test_outline_and_section() {
+        let raw = "---\ntitle: Plan\n---\n\n# Plan\n\nIntro\n\n## Goals ##\n\n- ship\n\n### Stretch\n\n```\n# not a heading\n```\n\n## Risks\n\nNone\n";
+        let tree = outline(raw);
+        assert_eq!(tree.len(), 1);
+        let plan = &tree[0];
+        assert_eq!((plan.text.as_str(), plan.line, plan.end_line), ("Plan", 5, 21));
+        let children: Vec<(&str, usize, usize)> = plan.children.iter().map(|h| (h.text.as_str(), h.line, h.end_line)).collect();
+        assert_eq!(children, vec![("Goals", 9, 18), ("Risks", 19, 21)]);
+        assert_eq!((plan.children[0].children[0].text.as_str(), plan.children[0].children[0].level), ("Stretch", 3));
+
+        assert_eq!(section(raw, "goals").unwrap(), "## Goals ##\n\n- ship\n\n### Stretch\n\n```\n# not a heading\n```\n");
+        assert_eq!(section(raw, "## Risks").unwrap(), "## Risks\n\nNone\n");
+        assert!(section(raw, "not a heading").is_none());
+    }
+}
@@ -0,0 +1,301 @@
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::services::{ai, durable};
+
+/// One YAML file per day: life/nutrition/{YYYY-MM-DD}.yaml
+const NUTRITION_DIR: &str = "life/nutrition";
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Meal {
+    /// HH:MM
+    pub time: String,
+    /// "breakfast" | "lunch" | "dinner" | "snack"
+    pub meal: String,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calories: Option<u32>,
+    /// Calories came from the AI estimate rather than the user
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub estimated: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NutritionDay {
+    #[serde(default)]
+    pub date: String,
+    #[serde(default)]
+    pub meals: Vec<Meal>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DaySummary {
+    pub date: String,
+    pub meals: usize,
+    pub calories: u32,
+    /// Meals without a calorie figure
+    pub unknown: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct WeekSummary {
+    /// Monday, YYYY-MM-DD
+    pub start: String,
+    pub end: String,
+    pub days: Vec<DaySummary>,
+    pub total_calories: u32,
+    /// Over the days that have any meal logged
+    pub average_calories: u32,
+    pub by_meal: BTreeMap<String, u32>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Append a meal to its day file. `datetime` defaults to now; the meal slot
+/// is inferred from the time of day. With `estimate` and no `calories`, the
+/// AI CLI is asked for a figure — if that fails the meal is still logged.
+#[tauri::command]
+pub async fn log_meal(
+    vault_path: String,
+    text: String,
+    datetime: Option<String>,
+    calories: Option<u32>,
+    estimate: Option<bool>,
+) -> Result<Meal, String> {
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err(tr!("Meal text is empty"));
+    }
+    let at = match datetime.as_deref() {
+        Some(raw) => parse_datetime(raw).ok_or_else(|| tr!("Invalid date/time: {}", raw))?,
+        None => Local::now().naive_local(),
+    };
+    let vault = PathBuf::from(&vault_path);
+
+    let mut meal = Meal {
+        time: at.format("%H:%M").to_string(),
+        meal: meal_slot(at.hour()).to_string(),
+        text,
+        calories,
+        estimated: false,
+    };
+    if meal.calories.is_none() && estimate.unwrap_or(false) {
+        let (v, t) = (vault.clone(), meal.text.clone());
+        match tokio::task::spawn_blocking(move || estimate_calories(&v, &t)).await {
+            Ok(Ok(kcal)) => {
+                meal.calories = Some(kcal);
+                meal.estimated = true;
+            }
+            Ok(Err(e)) => println!("[WARN] calorie estimate failed: {e}"),
+            Err(e) => println!("[WARN] calorie estimate failed: {e}"),
+        }
+    }
+
+    let date = at.date();
+    let mut day = read_day(&vault, date)?;
+    day.meals.push(meal.clone());
+    write_day(&vault, date, &mut day)?;
+    Ok(meal)
+}
+
+#[tauri::command]
+pub fn list_meals(vault_path: String, date: String) -> Result<NutritionDay, String> {
+    read_day(Path::new(&vault_path), parse_date(&date)?)
+}
+
+/// Remove the meal at `index` (as returned by `list_meals`)
+#[tauri::command]
+pub fn delete_meal(vault_path: String, date: String, index: usize) -> Result<NutritionDay, String> {
+    let vault = Path::new(&vault_path);
+    let date = parse_date(&date)?;
+    let mut day = read_day(vault, date)?;
+    if index >= day.meals.len() {
+        return Err(tr!("Meal not found: {}", index));
+    }
+    day.meals.remove(index);
+    write_day(vault, date, &mut day)?;
+    Ok(day)
+}
+
+/// Ask the AI for every meal of `date` that has no calories yet; returns
+/// the updated day
+#[tauri::command]
+pub async fn estimate_missing_calories(vault_path: String, date: String) -> Result<NutritionDay, String> {
+    let date = parse_date(&date)?;
+    tokio::task::spawn_blocking(move || {
+        let vault = PathBuf::from(&vault_path);
+        let mut day = read_day(&vault, date)?;
+        for meal in day.meals.iter_mut().filter(|m| m.calories.is_none()) {
+            meal.calories = Some(estimate_calories(&vault, &meal.text)?);
+            meal.estimated = true;
+        }
+        write_day(&vault, date, &mut day)?;
+        Ok(day)
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Monday–Sunday totals for the week containing `date` (default today)
+#[tauri::command]
+pub fn get_nutrition_summary(vault_path: String, date: Option<String>) -> Result<WeekSummary, String> {
+    let date = match date {
+        Some(d) => parse_date(&d)?,
+        None => Local::now().date_naive(),
+    };
+    let vault = Path::new(&vault_path);
+    let start = date - Duration::days(i64::from(date.weekday().num_days_from_monday()));
+    let days = (0..7)
+        .map(|i| {
+            let d = start + Duration::days(i);
+            read_day(vault, d).map(|day| (d, day))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(summarize(start, days))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn day_path(vault: &Path, date: NaiveDate) -> PathBuf {
+    vault.join(NUTRITION_DIR).join(format!("{}.yaml", date.format("%Y-%m-%d")))
+}
+
+fn read_day(vault: &Path, date: NaiveDate) -> Result<NutritionDay, String> {
+    let path = day_path(vault, date);
+    let mut day: NutritionDay = match fs::read_to_string(&path) {
+        Ok(raw) => serde_yaml::from_str(&raw).map_err(|e| tr!("Failed to parse: {}", e))?,
+        Err(_) => NutritionDay::default(),
+    };
+    day.date = date.format("%Y-%m-%d").to_string();
+    Ok(day)
+}
+
+fn write_day(vault: &Path, date: NaiveDate, day: &mut NutritionDay) -> Result<(), String> {
+    let path = day_path(vault, date);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    day.meals.sort_by(|a, b| a.time.cmp(&b.time));
+    let yaml = serde_yaml::to_string(day).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(&path, yaml).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+fn parse_date(raw: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(raw.trim(), "%Y-%m-%d").map_err(|_| tr!("Invalid date (expected YYYY-MM-DD): {}", raw))
+}
+
+/// RFC 3339, `YYYY-MM-DD HH:MM[:SS]`, `YYYY-MM-DDTHH:MM[:SS]`, or a bare date (noon)
+fn parse_datetime(raw: &str) -> Option<NaiveDateTime> {
+    let raw = raw.trim();
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(raw) {
+        return Some(dt.with_timezone(&Local).naive_local());
+    }
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(raw, f).ok())
+        .or_else(|| NaiveDate::parse_from_str(raw, "%Y-%m-%d").ok()?.and_hms_opt(12, 0, 0))
+}
+
+fn meal_slot(hour: u32) -> &'static str {
+    match hour {
+        5..=9 => "breakfast",
+        11..=13 => "lunch",
+        17..=20 => "dinner",
+        _ => "snack",
+    }
+}
+
+fn estimate_calories(vault: &Path, text: &str) -> Result<u32, String> {
+    let prompt = format!(
+        "Estimate the total calories (kcal) of this meal: \"{text}\". \
+         Assume typical portions. Reply with a single integer and nothing else."
+    );
+    let reply = ai::ask(vault, &prompt)?;
+    first_integer(&reply).ok_or_else(|| tr!("Could not read a calorie estimate from: {}", reply))
+}
+
+/// `"约 650 kcal"` → 650
+fn first_integer(text: &str) -> Option<u32> {
+    let start = text.find(|c: char| c.is_ascii_digit())?;
+    let digits: String = text[start..].chars().take_while(|c| c.is_ascii_digit() || *c == ',').filter(|c| *c != ',').collect();
+    digits.parse().ok()
+}
+
+fn summarize(start: NaiveDate, days: Vec<(NaiveDate, NutritionDay)>) -> WeekSummary {
+    let mut week = WeekSummary {
+        start: start.format("%Y-%m-%d").to_string(),
+        end: (start + Duration::days(6)).format("%Y-%m-%d").to_string(),
+        ..Default::default()
+    };
+    for (date, day) in days {
+        let mut s = DaySummary { date: date.format("%Y-%m-%d").to_string(), meals: day.meals.len(), ..Default::default() };
+        for meal in &day.meals {
+            match meal.calories {
+                Some(kcal) => {
+                    s.calories += kcal;
+                    *week.by_meal.entry(meal.meal.clone()).or_default() += kcal;
+                }
+                None => s.unknown += 1,
+            }
+        }
+        week.total_calories += s.calories;
+        week.days.push(s);
+    }
+    let logged = week.days.iter().filter(|d| d.meals > 0).count() as u32;
+    week.average_calories = week.total_calories.checked_div(logged).unwrap_or(0);
+    week
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_datetime_and_slot() {
+        let dt = parse_datetime("2025-03-01 12:30").unwrap();
+        assert_eq!(meal_slot(dt.hour()), "lunch");
+        assert_eq!(parse_datetime("2025-03-01").unwrap().hour(), 12);
+        assert!(parse_datetime("yesterday").is_none());
+        assert_eq!(meal_slot(7), "breakfast");
+        assert_eq!(meal_slot(23), "snack");
+    }
+
+    #[test]
+    fn test_first_integer() {
+        assert_eq!(first_integer("650"), Some(650));
+        assert_eq!(first_integer("约 1,200 kcal"), Some(1200));
+        assert_eq!(first_integer("unknown"), None);
+    }
+
+    #[test]
+    fn test_summarize_week() {
+        let meal = |slot: &str, kcal| Meal { time: "12:00".into(), meal: slot.into(), text: "x".into(), calories: kcal, estimated: false };
+        let start = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let days = vec![
+            (start, NutritionDay { date: String::new(), meals: vec![meal("lunch", Some(600)), meal("dinner", None)] }),
+            (start + Duration::days(1), NutritionDay { date: String::new(), meals: vec![meal("lunch", Some(800))] }),
+            (start + Duration::days(2), NutritionDay::default()),
+        ];
+        let week = summarize(start, days);
+        assert_eq!(week.end, "2025-03-09");
+        assert_eq!(week.total_calories, 1400);
+        assert_eq!(week.average_calories, 700);
+        assert_eq!(week.by_meal["lunch"], 1400);
+        assert_eq!(week.days[0].unknown, 1);
+    }
+}
@@ -0,0 +1,273 @@
+use chrono::{Datelike, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+use walkdir::WalkDir;
+
+use super::focus_commands::notifications_muted;
+use crate::services::notes::split_frontmatter;
+use crate::services::periodic::Periodic;
+use crate::services::{durable, lunar};
+/// Days before an occasion on which a reminder is shown
+const LEAD_DAYS: [i64; 3] = [7, 1, 0];
+/// Reminders already shown, so restarts do not repeat them
+const REMINDER_STATE: &str = ".lifeos/reminders.json";
+const CHECK_EVERY: Duration = Duration::from_secs(60 * 60);
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Occasion {
+    /// "birthday" | "anniversary"
+    pub kind: String,
+    pub name: String,
+    pub path: String,
+    /// Next occurrence (Gregorian), YYYY-MM-DD
+    pub date: String,
+    pub days_until: i64,
+    pub lunar: bool,
+    /// Age / years married etc., when the original year is known
+    pub years: Option<i32>,
+    /// Frontmatter value as written
+    pub source: String,
+}
+
+/// A parsed `birthday:` / `anniversary:` value
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DateSpec {
+    year: Option<i32>,
+    month: u32,
+    day: u32,
+    lunar: bool,
+}
+
+// Only one reminder loop, bound to the open vault
+static REMINDERS: Periodic = Periodic::new();
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Birthdays and anniversaries falling within the next `days` days, soonest
+/// first. Any note's frontmatter may carry them (people/ is the usual place):
+/// `birthday: 1990-05-12`, `birthday: 05-12`, `birthday: lunar:1990-08-15`,
+/// `lunar_birthday: 08-15`, `anniversary: [2015-10-01]`.
+#[tauri::command]
+pub fn get_upcoming_occasions(vault_path: String, days: Option<i64>) -> Vec<Occasion> {
+    upcoming(Path::new(&vault_path), Local::now().date_naive(), days.unwrap_or(30))
+}
+
+/// Check hourly and show a system notification 7 days, 1 day and on the day
+/// of each occasion. Calling again (e.g. after switching vaults) restarts it.
+#[tauri::command]
+pub fn start_occasion_reminders(app: AppHandle, vault_path: String) {
+    let vault = PathBuf::from(vault_path);
+    REMINDERS.start(CHECK_EVERY, move || send_due_reminders(&app, &vault));
+}
+
+#[tauri::command]
+pub fn stop_occasion_reminders() {
+    REMINDERS.stop();
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub(crate) fn upcoming(vault: &Path, today: NaiveDate, days: i64) -> Vec<Occasion> {
+    let mut occasions: Vec<Occasion> = WalkDir::new(vault)
+        .min_depth(1)
+        .max_depth(10)
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            !name.starts_with('.') && name != "Mailbox"
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext == "md"))
+        .flat_map(|e| occasions_in(e.path(), today))
+        .filter(|o| o.days_until <= days)
+        .collect();
+    occasions.sort_by(|a, b| a.days_until.cmp(&b.days_until).then(a.name.cmp(&b.name)));
+    occasions
+}
+
+fn occasions_in(path: &Path, today: NaiveDate) -> Vec<Occasion> {
+    let Ok(raw) = fs::read_to_string(path) else { return vec![] };
+    let (Some(yaml), _) = split_frontmatter(&raw) else { return vec![] };
+    let Ok(fm) = serde_yaml::from_str::<serde_yaml::Value>(yaml) else { return vec![] };
+
+    let name = ["name", "title"]
+        .iter()
+        .find_map(|k| fm.get(*k).and_then(|v| v.as_str()))
+        .map(str::to_string)
+        .unwrap_or_else(|| path.file_stem().unwrap_or_default().to_string_lossy().to_string());
+
+    let fields = [("birthday", "birthday", false), ("lunar_birthday", "birthday", true), ("anniversary", "anniversary", false)];
+    let mut out = Vec::new();
+    for (key, kind, force_lunar) in fields {
+        let values: Vec<&str> = match fm.get(key) {
+            Some(serde_yaml::Value::String(s)) => vec![s.as_str()],
+            Some(serde_yaml::Value::Sequence(seq)) => seq.iter().filter_map(|v| v.as_str()).collect(),
+            _ => continue,
+        };
+        for value in values {
+            let Some(mut spec) = parse_spec(value) else { continue };
+            spec.lunar |= force_lunar;
+            let Some((date, years)) = next_occurrence(spec, today) else { continue };
+            out.push(Occasion {
+                kind: kind.to_string(),
+                name: name.clone(),
+                path: path.to_string_lossy().to_string(),
+                date: date.format("%Y-%m-%d").to_string(),
+                days_until: (date - today).num_days(),
+                lunar: spec.lunar,
+                years,
+                source: value.to_string(),
+            });
+        }
+    }
+    out
+}
+
+/// `[lunar:|农历]YYYY-MM-DD` or `[lunar:|农历]MM-DD`
+fn parse_spec(value: &str) -> Option<DateSpec> {
+    let value = value.trim();
+    let (lunar, rest) = match value.strip_prefix("lunar:").or_else(|| value.strip_prefix("农历")) {
+        Some(rest) => (true, rest.trim()),
+        None => (false, value),
+    };
+    let parts: Vec<&str> = rest.split(['-', '/']).collect();
+    let (year, month, day) = match parts.as_slice() {
+        [y, m, d] => (Some(y.parse().ok()?), m.parse().ok()?, d.parse().ok()?),
+        [m, d] => (None, m.parse().ok()?, d.parse().ok()?),
+        _ => return None,
+    };
+    let max_day = if lunar { 30 } else { 31 };
+    if !(1..=12).contains(&month) || !(1..=max_day).contains(&day) {
+        return None;
+    }
+    Some(DateSpec { year, month, day, lunar })
+}
+
+/// First occurrence on or after `today`, plus the anniversary count
+fn next_occurrence(spec: DateSpec, today: NaiveDate) -> Option<(NaiveDate, Option<i32>)> {
+    let candidates: Vec<(i32, NaiveDate)> = if spec.lunar {
+        // A lunar 12th month of last lunar year can still be ahead of us in January
+        let ly = lunar::lunar_year_of(today)?;
+        (ly - 1..=ly + 1)
+            .filter_map(|y| lunar::to_solar(y, spec.month, spec.day, false).map(|d| (y, d)))
+            .collect()
+    } else {
+        (today.year()..=today.year() + 1)
+            .filter_map(|y| {
+                // Feb 29 is celebrated on Feb 28 in common years
+                NaiveDate::from_ymd_opt(y, spec.month, spec.day)
+                    .or_else(|| NaiveDate::from_ymd_opt(y, spec.month, spec.day - 1))
+                    .map(|d| (y, d))
+            })
+            .collect()
+    };
+    let (year, date) = candidates.into_iter().find(|(_, d)| *d >= today)?;
+    let years = spec.year.map(|origin| year - origin).filter(|n| *n > 0);
+    Some((date, years))
+}
+
+fn send_due_reminders(app: &AppHandle, vault: &Path) {
+    // Held back, not dropped: unsent reminders go out on the first check after
+    // the focus session ends
+    if notifications_muted() {
+        return;
+    }
+    let today = Local::now().date_naive();
+    let state_path = vault.join(REMINDER_STATE);
+    let mut sent: BTreeSet<String> = fs::read_to_string(&state_path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default();
+    // Keys start with the occasion date, so past ones can be dropped
+    let today_str = today.format("%Y-%m-%d").to_string();
+    sent.retain(|k| k.as_str() >= today_str.as_str());
+
+    let max_lead = LEAD_DAYS.iter().copied().max().unwrap_or(0);
+    for o in upcoming(vault, today, max_lead) {
+        if !LEAD_DAYS.contains(&o.days_until) {
+            continue;
+        }
+        let key = format!("{}|{}|{}|{}", o.date, o.days_until, o.kind, o.path);
+        if sent.contains(&key) {
+            continue;
+        }
+        let title = match o.kind.as_str() {
+            "birthday" => tr!("Birthday: {}", o.name),
+            _ => tr!("Anniversary: {}", o.name),
+        };
+        let when = match o.days_until {
+            0 => tr!("Today"),
+            1 => tr!("Tomorrow"),
+            n => tr!("In {} days ({})", n, o.date),
+        };
+        let body = match o.years {
+            Some(n) => format!("{when} · {}", tr!("{} years", n)),
+            None => when,
+        };
+        match app.notification().builder().title(&title).body(&body).show() {
+            Ok(()) => {
+                sent.insert(key);
+            }
+            Err(e) => println!("[WARN] failed to show reminder: {e}"),
+        }
+    }
+
+    if let Ok(json) = serde_json::to_string_pretty(&sent) {
+        if let Err(e) = durable::write(&state_path, json) {
+            println!("[WARN] failed to write {}: {e}", state_path.display());
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse_spec() {
+        assert_eq!(parse_spec("1990-05-12"), Some(DateSpec { year: Some(1990), month: 5, day: 12, lunar: false }));
+        assert_eq!(parse_spec("lunar:08-15"), Some(DateSpec { year: None, month: 8, day: 15, lunar: true }));
+        assert_eq!(parse_spec("农历1990-12-30").map(|s| s.lunar), Some(true));
+        assert_eq!(parse_spec("13-01"), None);
+    }
+
+    #[test]
+    fn test_next_solar_occurrence() {
+        let spec = parse_spec("1990-05-12").unwrap();
+        assert_eq!(next_occurrence(spec, ymd(2025, 5, 12)), Some((ymd(2025, 5, 12), Some(35))));
+        assert_eq!(next_occurrence(spec, ymd(2025, 5, 13)), Some((ymd(2026, 5, 12), Some(36))));
+        let leap_day = parse_spec("02-29").unwrap();
+        assert_eq!(next_occurrence(leap_day, ymd(2025, 1, 1)).unwrap().0, ymd(2025, 2, 28));
+    }
+
+    #[test]
+    fn test_next_lunar_occurrence() {
+        // Lunar 8/15 (Mid-Autumn) 2025 is Oct 6
+        let spec = parse_spec("lunar:08-15").unwrap();
+        assert_eq!(next_occurrence(spec, ymd(2025, 9, 1)).unwrap().0, ymd(2025, 10, 6));
+        // Lunar 12/20 of lunar 2024 lands in January 2025
+        let spec = parse_spec("lunar:12-20").unwrap();
+        assert_eq!(next_occurrence(spec, ymd(2025, 1, 2)).unwrap().0, ymd(2025, 1, 19));
+    }
+}
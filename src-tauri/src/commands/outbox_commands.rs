@@ -0,0 +1,156 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use super::email_commands;
+use crate::services::google_calendar;
+use crate::services::outbox::{self, Operation, QueuedOperation};
+use crate::services::periodic::Periodic;
+
+/// Emitted with the pending `QueuedOperation`s whenever the queue changes
+pub const OUTBOX_CHANGED_EVENT: &str = "outbox-changed";
+/// How often the loop looks for a connection while something is queued
+const RETRY_EVERY: Duration = Duration::from_secs(60);
+/// Failed replays after which the loop leaves an operation to the user
+const MAX_ATTEMPTS: u32 = 5;
+
+static OUTBOX_LOOP: Periodic = Periodic::new();
+/// One replay at a time, from the loop or the UI, so nothing is sent twice
+static FLUSHING: Mutex<()> = Mutex::new(());
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FlushReport {
+    pub sent: usize,
+    /// Rejected for another reason than the network; kept with `last_error`
+    pub failed: usize,
+    pub remaining: usize,
+    /// The network went away mid-replay; the rest waits for the next try
+    pub offline: bool,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Operations waiting for the network, oldest first
+#[tauri::command]
+pub fn get_outbox(vault_path: String) -> Vec<QueuedOperation> {
+    outbox::pending(Path::new(&vault_path))
+}
+
+/// Replay the queue now, including operations the loop gave up on
+#[tauri::command]
+pub async fn flush_outbox(app: AppHandle, vault_path: String) -> Result<FlushReport, String> {
+    tokio::task::spawn_blocking(move || flush(&app, Path::new(&vault_path), true))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Drop a queued operation without replaying it
+#[tauri::command]
+pub fn discard_outbox_operation(app: AppHandle, vault_path: String, id: String) -> Result<bool, String> {
+    let vault = Path::new(&vault_path);
+    let removed = outbox::remove(vault, &id)?;
+    if removed {
+        notify(&app, vault);
+    }
+    Ok(removed)
+}
+
+/// Replay the queue whenever something is waiting and the network is back
+#[tauri::command]
+pub fn start_outbox_loop(app: AppHandle, vault_path: String) {
+    let vault = PathBuf::from(vault_path);
+    OUTBOX_LOOP.start(RETRY_EVERY, move || {
+        let waiting = outbox::pending(&vault).iter().any(|q| q.attempts < MAX_ATTEMPTS);
+        if waiting && outbox::is_online() {
+            match flush(&app, &vault, false) {
+                Ok(report) if report.sent + report.failed > 0 => {
+                    println!("[outbox] replayed {} operations, {} failed, {} left", report.sent, report.failed, report.remaining)
+                }
+                Ok(_) => {}
+                Err(e) => println!("[WARN] outbox replay failed: {e}"),
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn stop_outbox_loop() {
+    OUTBOX_LOOP.stop();
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Queue an operation that failed for want of a network and tell the UI
+pub(crate) fn queue(app: &AppHandle, vault_path: &str, operation: Operation) -> Result<QueuedOperation, String> {
+    let vault = Path::new(vault_path);
+    let queued = outbox::enqueue(vault, operation)?;
+    notify(app, vault);
+    Ok(queued)
+}
+
+/// Replay in order, stopping at the first network error so later operations
+/// never overtake earlier ones on the same message
+fn flush(app: &AppHandle, vault: &Path, include_exhausted: bool) -> Result<FlushReport, String> {
+    let _guard = FLUSHING.lock().unwrap_or_else(|e| e.into_inner());
+    let mut report = FlushReport::default();
+    for queued in outbox::pending(vault) {
+        if !include_exhausted && queued.attempts >= MAX_ATTEMPTS {
+            continue;
+        }
+        match replay(vault, &queued.operation) {
+            Ok(()) => {
+                outbox::remove(vault, &queued.id)?;
+                report.sent += 1;
+            }
+            Err(e) if outbox::is_network_error(&e) => {
+                report.offline = true;
+                break;
+            }
+            Err(e) => {
+                println!("[WARN] outbox operation {} failed: {e}", queued.id);
+                outbox::record_failure(vault, &queued.id, &e)?;
+                report.failed += 1;
+            }
+        }
+    }
+    report.remaining = outbox::pending(vault).len();
+    if report.sent + report.failed > 0 {
+        notify(app, vault);
+    }
+    Ok(report)
+}
+
+fn replay(vault: &Path, operation: &Operation) -> Result<(), String> {
+    let vault_path = vault.to_string_lossy();
+    match operation {
+        Operation::SendEmail { request } => email_commands::deliver(request),
+        Operation::SetRead { message, read } => email_commands::remote_set_read(&vault_path, message, *read),
+        Operation::Archive { message } => email_commands::remote_archive(&vault_path, message),
+        Operation::CalendarSync => {
+            // Per-event errors are retried by the next sync anyway; only a
+            // lost connection keeps this queued
+            let report = google_calendar::sync(vault)?;
+            match report.errors.into_iter().find(|e| outbox::is_network_error(e)) {
+                Some(e) => Err(e),
+                None => Ok(()),
+            }
+        }
+    }
+}
+
+fn notify(app: &AppHandle, vault: &Path) {
+    if let Err(e) = app.emit(OUTBOX_CHANGED_EVENT, outbox::pending(vault)) {
+        println!("[WARN] failed to emit {OUTBOX_CHANGED_EVENT}: {e}");
+    }
+}
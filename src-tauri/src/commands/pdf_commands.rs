@@ -0,0 +1,156 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::services::automations::vault_file;
+use crate::services::notes::{slugify, vault_rel};
+use crate::services::pdf::{self, PdfText};
+
+/// One annotation note per PDF: annotations/<slug>.md
+const ANNOTATIONS_DIR: &str = "annotations";
+const ANNOTATIONS_HEADING: &str = "## 批注";
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PdfIndexReport {
+    pub indexed: usize,
+    pub failed: Vec<String>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Per-page text of a PDF; the result is cached and included in note search
+#[tauri::command]
+pub async fn extract_pdf_text(vault_path: String, path: String) -> Result<PdfText, String> {
+    tokio::task::spawn_blocking(move || pdf::extract(Path::new(&vault_path), &pdf_file(&vault_path, &path)?))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Extract every new or changed PDF under assets/ so search covers them
+#[tauri::command]
+pub async fn index_pdfs(vault_path: String) -> Result<PdfIndexReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let vault = PathBuf::from(&vault_path);
+        let (indexed, failed) = pdf::index_dir(&vault, &vault.join("assets"));
+        PdfIndexReport { indexed, failed }
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))
+}
+
+/// Create (or return) the annotation note linked to a PDF. Returns the
+/// note's path.
+#[tauri::command]
+pub async fn create_pdf_annotation_note(vault_path: String, path: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let vault = PathBuf::from(&vault_path);
+        let pdf_path = pdf_file(&vault_path, &path)?;
+        let note = note_path(&vault, &pdf_path);
+        if !note.exists() {
+            // Page count is nice to have; an unreadable PDF still gets a note
+            let pages = pdf::extract(&vault, &pdf_path).map(|t| t.pages.len()).ok();
+            let content = new_note(&vault_rel(&vault, &pdf_path), pages, &Local::now().format("%Y-%m-%d").to_string());
+            if let Some(parent) = note.parent() {
+                fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+            }
+            fs::write(&note, content).map_err(|e| tr!("write_file failed: {}", e))?;
+        }
+        Ok(note.to_string_lossy().to_string())
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Append `- [p.N](pdf#page=N) text` to the PDF's annotation note,
+/// creating the note first when needed
+#[tauri::command]
+pub async fn add_pdf_annotation(vault_path: String, path: String, page: usize, text: String) -> Result<String, String> {
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err(tr!("Annotation is empty"));
+    }
+    let note = create_pdf_annotation_note(vault_path.clone(), path.clone()).await?;
+    let source = vault_rel(Path::new(&vault_path), &pdf_file(&vault_path, &path)?);
+    let raw = fs::read_to_string(&note).map_err(|e| tr!("Failed to read: {}", e))?;
+    let line = format!("- [p.{page}]({}#page={page}) {text}", note_link(&source));
+    fs::write(&note, append_annotation(&raw, &line)).map_err(|e| tr!("write_file failed: {}", e))?;
+    Ok(note)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Absolute or vault-relative input, which must be an existing PDF
+fn pdf_file(vault_path: &str, path: &str) -> Result<PathBuf, String> {
+    let p = Path::new(path);
+    let full = if p.is_absolute() { p.to_path_buf() } else { vault_file(Path::new(vault_path), path)? };
+    if !full.is_file() || full.extension().is_none_or(|ext| !ext.eq_ignore_ascii_case("pdf")) {
+        return Err(tr!("Not a PDF file: {}", path));
+    }
+    Ok(full)
+}
+
+fn note_path(vault: &Path, pdf: &Path) -> PathBuf {
+    let stem = pdf.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    vault.join(ANNOTATIONS_DIR).join(format!("{}.md", slugify(&stem)))
+}
+
+/// Link from annotations/ to the PDF, spaces escaped for Markdown
+fn note_link(source: &str) -> String {
+    format!("../{}", source.replace(' ', "%20"))
+}
+
+fn new_note(source: &str, pages: Option<usize>, today: &str) -> String {
+    let title = Path::new(source).file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let pages = pages.map(|n| format!("pages: {n}\n")).unwrap_or_default();
+    format!(
+        "---\ntitle: \"{}\"\ntype: pdf-annotation\nsource: \"{source}\"\n{pages}created: {today}\ntags: []\n---\n\n# {title}\n\n[打开 PDF]({})\n\n{ANNOTATIONS_HEADING}\n\n",
+        title.replace('"', "'"),
+        note_link(source)
+    )
+}
+
+/// Add `line` at the end of the 批注 section (which is created if missing)
+fn append_annotation(raw: &str, line: &str) -> String {
+    let Some(start) = raw.find(ANNOTATIONS_HEADING) else {
+        return format!("{}\n\n{ANNOTATIONS_HEADING}\n\n{line}\n", raw.trim_end());
+    };
+    let body_start = start + ANNOTATIONS_HEADING.len();
+    let end = raw[body_start..].find("\n## ").map_or(raw.len(), |i| body_start + i + 1);
+    let section = raw[body_start..end].trim_end();
+    let rest = &raw[end..];
+    let sep = if section.trim().is_empty() { "\n\n" } else { "\n" };
+    let tail = if rest.is_empty() { String::new() } else { format!("\n{rest}") };
+    format!("{}{section}{sep}{line}\n{tail}", &raw[..body_start])
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annotation_note() {
+        let note = new_note("assets/papers/Deep Work.pdf", Some(12), "2025-03-01");
+        assert!(note.contains("source: \"assets/papers/Deep Work.pdf\"\npages: 12\n"));
+        assert!(note.contains("[打开 PDF](../assets/papers/Deep%20Work.pdf)"));
+
+        let once = append_annotation(&note, "- [p.3](x#page=3) one");
+        let twice = append_annotation(&once, "- [p.5](x#page=5) two");
+        assert!(twice.ends_with("## 批注\n\n- [p.3](x#page=3) one\n- [p.5](x#page=5) two\n"));
+
+        let with_next = append_annotation("## 批注\n\n- a\n\n## 其他\n\ntext\n", "- b");
+        assert_eq!(with_next, "## 批注\n\n- a\n- b\n\n## 其他\n\ntext\n");
+    }
+}
@@ -0,0 +1,313 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::services::mail::EmailMessage;
+use crate::services::notes::{slugify, split_frontmatter};
+use crate::services::people::{bare_address, load_people, person_path, read_person, unique_slug, write_person, Person, PEOPLE_DIR};
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TimelineItem {
+    /// "email" | "note" | "meeting" | "task"
+    pub kind: String,
+    /// ISO date(-time) when known, used for ordering
+    pub date: String,
+    pub title: String,
+    pub path: String,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// CRUD commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn list_people(vault_path: String) -> Result<Vec<Person>, String> {
+    Ok(load_people(Path::new(&vault_path)))
+}
+
+#[tauri::command]
+pub fn get_person(vault_path: String, slug: String) -> Result<Person, String> {
+    let path = person_path(Path::new(&vault_path), &slug)?;
+    read_person(&path).ok_or_else(|| tr!("Person not found: {}", slug))
+}
+
+/// Create or update a person. An empty slug creates a new file named after
+/// `name`, adding a numeric suffix if that slug is taken.
+#[tauri::command]
+pub fn save_person(vault_path: String, person: Person) -> Result<Person, String> {
+    let vault = PathBuf::from(&vault_path);
+    let mut person = person;
+    person.meta.name = person.meta.name.trim().to_string();
+    if person.meta.name.is_empty() {
+        return Err(tr!("Name is required"));
+    }
+    if person.slug.is_empty() {
+        person.slug = unique_slug(&vault, &slugify(&person.meta.name));
+    }
+    let path = person_path(&vault, &person.slug)?;
+    write_person(&path, &person)?;
+    person.path = path.to_string_lossy().to_string();
+    Ok(person)
+}
+
+#[tauri::command]
+pub fn delete_person(vault_path: String, slug: String) -> Result<(), String> {
+    let path = person_path(Path::new(&vault_path), &slug)?;
+    fs::remove_file(&path).map_err(|e| e.to_string())
+}
+
+/// Person whose `emails` contain this address (case-insensitive)
+#[tauri::command]
+pub fn find_person_by_email(vault_path: String, email: String) -> Option<Person> {
+    let wanted = bare_address(&email);
+    load_people(Path::new(&vault_path))
+        .into_iter()
+        .find(|p| p.meta.emails.iter().any(|e| bare_address(e) == wanted))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Linking & timeline
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Bump `last_contacted` from cached mail (sent or received) and meeting notes
+/// listing the person in `attendees`. Returns the slugs that changed.
+#[tauri::command]
+pub fn link_people(vault_path: String) -> Result<Vec<String>, String> {
+    let vault = PathBuf::from(&vault_path);
+    let emails = load_cached_emails(&vault);
+    let notes = load_notes(&vault);
+    let mut updated = Vec::new();
+
+    for mut person in load_people(&vault) {
+        let latest = email_items(&person, &emails)
+            .into_iter()
+            .chain(note_items(&person, &notes).into_iter().filter(|i| i.kind == "meeting"))
+            .map(|i| i.date.chars().take(10).collect::<String>())
+            .filter(|d| d.len() == 10)
+            .max();
+        let Some(latest) = latest else { continue };
+        if person.meta.last_contacted.as_ref().is_some_and(|d| *d >= latest) {
+            continue;
+        }
+        person.meta.last_contacted = Some(latest);
+        write_person(&PathBuf::from(&person.path), &person)?;
+        updated.push(person.slug);
+    }
+    Ok(updated)
+}
+
+/// Emails, notes, meeting notes and tasks related to a person, newest first.
+/// Notes and tasks count when they mention `[[slug]]`, `[[Name]]` or `@slug`.
+#[tauri::command]
+pub fn get_person_timeline(vault_path: String, slug: String, limit: Option<usize>) -> Result<Vec<TimelineItem>, String> {
+    let vault = PathBuf::from(&vault_path);
+    let person = read_person(&person_path(&vault, &slug)?).ok_or_else(|| tr!("Person not found: {}", slug))?;
+
+    let mut items = email_items(&person, &load_cached_emails(&vault));
+    items.extend(note_items(&person, &load_notes(&vault)));
+    items.extend(task_items(&vault, &person));
+    items.sort_by(|a, b| b.date.cmp(&a.date));
+    items.truncate(limit.unwrap_or(200));
+    Ok(items)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn load_cached_emails(vault: &Path) -> Vec<(String, EmailMessage)> {
+    let Ok(accounts) = fs::read_dir(vault.join("Mailbox")) else { return vec![] };
+    let mut emails = Vec::new();
+    for account in accounts.filter_map(|e| e.ok()) {
+        let index = account.path().join("index.json");
+        let Ok(raw) = fs::read_to_string(&index) else { continue };
+        let Ok(list) = serde_json::from_str::<Vec<EmailMessage>>(&raw) else { continue };
+        let account_id = account.file_name().to_string_lossy().to_string();
+        emails.extend(list.into_iter().map(|m| (account_id.clone(), m)));
+    }
+    emails
+}
+
+fn email_items(person: &Person, emails: &[(String, EmailMessage)]) -> Vec<TimelineItem> {
+    let addresses: Vec<String> = person.meta.emails.iter().map(|e| bare_address(e)).filter(|e| !e.is_empty()).collect();
+    if addresses.is_empty() {
+        return vec![];
+    }
+    emails
+        .iter()
+        .filter(|(_, m)| {
+            let from = m.from.to_lowercase();
+            let to = m.to.to_lowercase();
+            addresses.iter().any(|a| from.contains(a.as_str()) || to.contains(a.as_str()))
+        })
+        .map(|(account, m)| TimelineItem {
+            kind: "email".to_string(),
+            date: normalize_date(&m.date),
+            title: m.subject.clone(),
+            path: format!("Mailbox/{}/{}", account, m.id),
+        })
+        .collect()
+}
+
+struct NoteDoc {
+    path: PathBuf,
+    date: String,
+    title: String,
+    attendees: Vec<String>,
+    text: String,
+}
+
+/// Every .md outside dot-dirs, Mailbox, people/ and daily/tasks (tasks are scanned line by line)
+fn load_notes(vault: &Path) -> Vec<NoteDoc> {
+    let skip = [PEOPLE_DIR, "Mailbox", "daily/tasks"];
+    WalkDir::new(vault)
+        .min_depth(1)
+        .max_depth(10)
+        .into_iter()
+        .filter_entry(|e| {
+            let rel = e.path().strip_prefix(vault).unwrap_or(e.path());
+            !e.file_name().to_string_lossy().starts_with('.') && !skip.iter().any(|s| rel == Path::new(s))
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|e| {
+            let raw = fs::read_to_string(e.path()).ok()?;
+            let (yaml, body) = split_frontmatter(&raw);
+            let fm: serde_yaml::Value = yaml.and_then(|y| serde_yaml::from_str(y).ok()).unwrap_or_default();
+            let attendees = fm
+                .get("attendees")
+                .and_then(|v| v.as_sequence())
+                .map(|seq| seq.iter().filter_map(|a| a.as_str().map(|s| s.to_lowercase())).collect())
+                .unwrap_or_default();
+            let date = fm
+                .get("date")
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+                .or_else(|| modified_date(e.path()))
+                .unwrap_or_default();
+            let title = fm
+                .get("title")
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+                .unwrap_or_else(|| e.path().file_stem().unwrap_or_default().to_string_lossy().to_string());
+            Some(NoteDoc { path: e.into_path(), date, title, attendees, text: body.to_string() })
+        })
+        .collect()
+}
+
+fn note_items(person: &Person, notes: &[NoteDoc]) -> Vec<TimelineItem> {
+    let mut keys = vec![person.slug.to_lowercase(), person.meta.name.to_lowercase()];
+    keys.extend(person.meta.emails.iter().map(|e| bare_address(e)));
+    notes
+        .iter()
+        .filter_map(|n| {
+            let kind = if n.attendees.iter().any(|a| keys.contains(a)) {
+                "meeting"
+            } else if mentions(&n.text, person) {
+                "note"
+            } else {
+                return None;
+            };
+            Some(TimelineItem {
+                kind: kind.to_string(),
+                date: n.date.clone(),
+                title: n.title.clone(),
+                path: n.path.to_string_lossy().to_string(),
+            })
+        })
+        .collect()
+}
+
+fn task_items(vault: &Path, person: &Person) -> Vec<TimelineItem> {
+    let Ok(entries) = fs::read_dir(vault.join("daily/tasks")) else { return vec![] };
+    let mut items = Vec::new();
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "md") {
+            continue;
+        }
+        let Ok(raw) = fs::read_to_string(&path) else { continue };
+        let date = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        for line in raw.lines().map(str::trim_start) {
+            if line.starts_with("- [") && mentions(line, person) {
+                items.push(TimelineItem {
+                    kind: "task".to_string(),
+                    date: date.clone(),
+                    title: line.to_string(),
+                    path: path.to_string_lossy().to_string(),
+                });
+            }
+        }
+    }
+    items
+}
+
+fn mentions(text: &str, person: &Person) -> bool {
+    let lower = text.to_lowercase();
+    let slug = person.slug.to_lowercase();
+    let name = person.meta.name.to_lowercase();
+    lower.contains(&format!("[[{slug}]]"))
+        || lower.contains(&format!("[[{name}]]"))
+        || lower
+            .match_indices(&format!("@{slug}"))
+            .any(|(i, m)| !lower[i + m.len()..].starts_with(|c: char| c.is_alphanumeric() || c == '-'))
+}
+
+/// RFC 3339 / RFC 2822 → `YYYY-MM-DDTHH:MM:SS` local; unparseable dates pass through
+fn normalize_date(raw: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(raw)
+        .or_else(|_| chrono::DateTime::parse_from_rfc2822(raw))
+        .map(|d| d.with_timezone(&chrono::Local).format("%Y-%m-%dT%H:%M:%S").to_string())
+        .unwrap_or_else(|_| raw.to_string())
+}
+
+fn modified_date(path: &Path) -> Option<String> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    let dt: chrono::DateTime<chrono::Local> = modified.into();
+    Some(dt.format("%Y-%m-%dT%H:%M:%S").to_string())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::people::PersonMeta;
+
+    fn person(slug: &str, name: &str) -> Person {
+        Person {
+            slug: slug.into(),
+            path: String::new(),
+            meta: PersonMeta { name: name.into(), ..Default::default() },
+            notes: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_mentions() {
+        let jane = person("jane", "Jane Doe");
+        assert!(mentions("lunch with @jane today", &jane));
+        assert!(mentions("see [[Jane Doe]]", &jane));
+        assert!(!mentions("ask @janet", &jane));
+    }
+
+    #[test]
+    fn test_delete_person_stays_in_people() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().to_string_lossy().to_string();
+        fs::write(dir.path().join("notes.md"), "keep").unwrap();
+        for slug in ["../notes", "a/b", "..", ""] {
+            assert_eq!(delete_person(vault.clone(), slug.into()).unwrap_err(), tr!("Invalid slug: {}", slug));
+        }
+        assert!(dir.path().join("notes.md").exists());
+        let saved = save_person(vault.clone(), Person { slug: "../x".into(), ..person("", "X") });
+        assert!(saved.is_err() && !dir.path().join("x.md").exists());
+    }
+}
@@ -0,0 +1,196 @@
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::agenda_commands::calendar_events;
+use crate::services::calendar::AgendaEvent;
+use crate::services::planner::{load, save, TimeBlock};
+use crate::services::tasks;
+
+/// Calendar events without a DTEND
+const DEFAULT_EVENT_MINUTES: u32 = 30;
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Conflict {
+    /// "event" | "block"
+    pub kind: String,
+    pub title: String,
+    /// HH:MM
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScheduledBlock {
+    #[serde(flatten)]
+    pub block: TimeBlock,
+    /// HH:MM
+    pub end: String,
+    /// Calendar events and other blocks it overlaps
+    pub conflicts: Vec<Conflict>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DayPlan {
+    /// YYYY-MM-DD
+    pub date: String,
+    /// In start order
+    pub blocks: Vec<ScheduledBlock>,
+    /// The day's timed calendar events, for the timeline
+    pub events: Vec<AgendaEvent>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Put a task on the timeline at `start` (`YYYY-MM-DDTHH:MM`, or `HH:MM` for
+/// today) for `duration` minutes. A task already on that day is moved.
+/// Overlaps don't stop it; they come back as `conflicts`.
+#[tauri::command]
+pub fn schedule_task(vault_path: String, task_ref: String, start: String, duration: u32) -> Result<ScheduledBlock, String> {
+    let vault = Path::new(&vault_path);
+    let (date, time) = parse_start(&start)?;
+    let begin = minutes(time);
+    if duration == 0 || begin + duration > MINUTES_PER_DAY {
+        return Err(tr!("Invalid duration: {}", duration));
+    }
+    let (_, task) = tasks::find_task(&vault_path, &task_ref).ok_or_else(|| tr!("Task not found: {}", task_ref))?;
+    let block = TimeBlock { task_ref: task_ref.clone(), title: task.text, start: time.format("%H:%M").to_string(), duration };
+
+    let mut blocks = load(vault, &date);
+    blocks.retain(|b| b.task_ref != task_ref);
+    blocks.push(block.clone());
+    blocks.sort_by(|a, b| a.start.cmp(&b.start));
+    save(vault, &date, &blocks)?;
+
+    let events = timed_events(vault, &date);
+    Ok(with_conflicts(block, &blocks, &events))
+}
+
+/// Take a task off the day's timeline
+#[tauri::command]
+pub fn unschedule_task(vault_path: String, date: String, task_ref: String) -> Result<(), String> {
+    let vault = Path::new(&vault_path);
+    let mut blocks = load(vault, &date);
+    blocks.retain(|b| b.task_ref != task_ref);
+    save(vault, &date, &blocks)
+}
+
+/// Time blocks and calendar events of `date` (default today)
+#[tauri::command]
+pub fn get_day_plan(vault_path: String, date: Option<String>) -> Result<DayPlan, String> {
+    let vault = Path::new(&vault_path);
+    let day = match &date {
+        Some(d) => NaiveDate::parse_from_str(d, "%Y-%m-%d").map_err(|_| tr!("Invalid date (expected YYYY-MM-DD): {}", d))?,
+        None => Local::now().date_naive(),
+    };
+    let date = day.format("%Y-%m-%d").to_string();
+    let blocks = load(vault, &date);
+    let events = timed_events(vault, &date);
+    let scheduled = blocks.iter().map(|b| with_conflicts(b.clone(), &blocks, &events)).collect();
+    Ok(DayPlan { date, blocks: scheduled, events })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn parse_start(start: &str) -> Result<(String, NaiveTime), String> {
+    let start = start.trim();
+    if let Ok(at) = NaiveDateTime::parse_from_str(start, "%Y-%m-%dT%H:%M") {
+        return Ok((at.date().format("%Y-%m-%d").to_string(), at.time()));
+    }
+    let time = NaiveTime::parse_from_str(start, "%H:%M").map_err(|_| tr!("Invalid time (expected HH:MM): {}", start))?;
+    Ok((Local::now().format("%Y-%m-%d").to_string(), time))
+}
+
+fn with_conflicts(block: TimeBlock, blocks: &[TimeBlock], events: &[AgendaEvent]) -> ScheduledBlock {
+    let (start, end) = span(&block);
+    let mut conflicts: Vec<Conflict> = events
+        .iter()
+        .filter_map(|e| {
+            let from = minutes(NaiveTime::parse_from_str(e.time.as_deref()?, "%H:%M").ok()?);
+            let to = e.end.as_deref().and_then(|t| NaiveTime::parse_from_str(t, "%H:%M").ok()).map_or(from + DEFAULT_EVENT_MINUTES, minutes);
+            (from < end && start < to).then(|| Conflict { kind: "event".into(), title: e.title.clone(), start: clock(from), end: clock(to) })
+        })
+        .collect();
+    conflicts.extend(blocks.iter().filter(|b| b.task_ref != block.task_ref).filter_map(|b| {
+        let (from, to) = span(b);
+        (from < end && start < to).then(|| Conflict { kind: "block".into(), title: b.title.clone(), start: clock(from), end: clock(to) })
+    }));
+    ScheduledBlock { end: clock(end), block, conflicts }
+}
+
+/// Timed events only: all-day ones don't take up the timeline
+fn timed_events(vault: &Path, date: &str) -> Vec<AgendaEvent> {
+    let Ok(day) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else { return Vec::new() };
+    let mut events: Vec<AgendaEvent> = calendar_events(vault, day).into_iter().filter(|e| e.time.is_some()).collect();
+    events.sort_by(|a, b| a.time.cmp(&b.time));
+    events
+}
+
+/// Minutes since midnight
+fn span(block: &TimeBlock) -> (u32, u32) {
+    let start = NaiveTime::parse_from_str(&block.start, "%H:%M").map_or(0, minutes);
+    (start, start + block.duration)
+}
+
+fn minutes(time: NaiveTime) -> u32 {
+    time.hour() * 60 + time.minute()
+}
+
+fn clock(minutes: u32) -> String {
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::planner::schedule_path;
+    use std::fs;
+
+    fn write(dir: &Path, rel: &str, text: &str) {
+        let path = dir.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    #[test]
+    fn test_schedule_and_conflicts() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        let path = v.to_string_lossy().to_string();
+        write(v, "daily/tasks/2026-03-10.md", "## 今日任务\n\n- [ ] 写周报\n- [ ] 发布 🆔ship\n");
+        write(v, "connectors/calendar/work.ics", "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART:20260310T100000\r\nDTEND:20260310T110000\r\nSUMMARY:站会\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20260310\r\nSUMMARY:假期\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n");
+
+        let report = schedule_task(path.clone(), "2026-03-10:2".into(), "2026-03-10T09:00".into(), 90).unwrap();
+        assert_eq!((report.block.title.as_str(), report.end.as_str()), ("写周报", "10:30"));
+        assert_eq!(report.conflicts, vec![Conflict { kind: "event".into(), title: "站会".into(), start: "10:00".into(), end: "11:00".into() }]);
+
+        let report = schedule_task(path.clone(), "ship".into(), "2026-03-10T11:00".into(), 60).unwrap();
+        assert!(report.conflicts.is_empty());
+        // Moving a block replaces it rather than adding a second one
+        let report = schedule_task(path.clone(), "ship".into(), "2026-03-10T10:15".into(), 30).unwrap();
+        assert_eq!(report.conflicts.iter().map(|c| c.kind.as_str()).collect::<Vec<_>>(), ["event", "block"]);
+
+        let plan = get_day_plan(path.clone(), Some("2026-03-10".into())).unwrap();
+        assert_eq!(plan.blocks.iter().map(|b| b.block.start.as_str()).collect::<Vec<_>>(), ["09:00", "10:15"]);
+        assert_eq!(plan.events.len(), 1);
+
+        assert!(schedule_task(path.clone(), "2026-03-10:9".into(), "2026-03-10T09:00".into(), 30).is_err());
+        assert!(schedule_task(path.clone(), "ship".into(), "2026-03-10T23:30".into(), 60).is_err());
+        unschedule_task(path.clone(), "2026-03-10".into(), "ship".into()).unwrap();
+        unschedule_task(path.clone(), "2026-03-10".into(), "2026-03-10:2".into()).unwrap();
+        assert!(!schedule_path(v, "2026-03-10").exists());
+    }
+}
@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+use tauri::plugin::PermissionState;
+use tauri_plugin_notification::NotificationExt;
+
+#[cfg(not(target_os = "macos"))]
+use crate::services::unsupported;
+
+/// Pane of System Settings → Privacy & Security (or Notifications) for each
+/// permission `check_system_permissions` reports
+const SETTINGS: &[(&str, &str)] = &[
+    ("automation_notes", "x-apple.systempreferences:com.apple.preference.security?Privacy_Automation"),
+    ("automation_calendar", "x-apple.systempreferences:com.apple.preference.security?Privacy_Automation"),
+    ("full_disk_access", "x-apple.systempreferences:com.apple.preference.security?Privacy_AllFiles"),
+    ("notifications", "x-apple.systempreferences:com.apple.preference.notifications"),
+    ("accessibility", "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility"),
+];
+
+/// How long an Automation probe may wait, e.g. on the consent dialog
+#[cfg(target_os = "macos")]
+const PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Which optional integrations the running build can actually service.
+/// The frontend uses this to hide menu entries instead of surfacing errors.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlatformInfo {
+    pub os: String,
+    pub mobile: bool,
+    pub finder: bool,
+    pub shell: bool,
+    pub git: bool,
+    pub shortcuts: bool,
+    pub launchd: bool,
+    pub apple_notes: bool,
+    pub keychain: bool,
+    pub screenshot: bool,
+    pub spotlight: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PermissionStatus {
+    /// "automation_notes" | "automation_calendar" | "full_disk_access" | "notifications" | "accessibility"
+    pub id: String,
+    /// "granted" | "denied" | "not_determined" | "unknown" | "unsupported"
+    pub status: String,
+    /// What to do about it; empty once granted
+    pub hint: String,
+    /// Deep link into System Settings for `open_permission_settings`
+    pub settings_url: String,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn get_platform_info() -> PlatformInfo {
+    let macos = cfg!(target_os = "macos");
+    let desktop = cfg!(desktop);
+
+    PlatformInfo {
+        os: std::env::consts::OS.to_string(),
+        mobile: cfg!(mobile),
+        finder: desktop,
+        shell: desktop,
+        git: desktop,
+        shortcuts: macos,
+        launchd: macos,
+        apple_notes: macos,
+        keychain: macos,
+        screenshot: macos,
+        spotlight: macos,
+    }
+}
+
+/// Preflight for the macOS permissions integrations depend on, so the UI can
+/// ask for them up front. The Automation probes send Notes and Calendar a
+/// harmless event, which shows macOS's consent dialog the first time.
+/// Notifications are as the notification plugin reports them.
+#[tauri::command]
+pub async fn check_system_permissions(app: tauri::AppHandle) -> Vec<PermissionStatus> {
+    let notifications = match app.notification().permission_state() {
+        Ok(PermissionState::Granted) => "granted",
+        Ok(PermissionState::Denied) => "denied",
+        Ok(_) => "not_determined",
+        Err(_) => "unknown",
+    };
+    let statuses = [
+        ("automation_notes", automation("Notes", "count folders").await),
+        ("automation_calendar", automation("Calendar", "count calendars").await),
+        ("full_disk_access", full_disk_access()),
+        ("notifications", notifications),
+        ("accessibility", accessibility()),
+    ];
+    statuses.into_iter().map(|(id, status)| permission(id, status)).collect()
+}
+
+/// Open System Settings where the permission is granted
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn open_permission_settings(permission: String) -> Result<(), String> {
+    let url = settings_url(&permission).ok_or_else(|| tr!("Unknown permission: {}", permission))?;
+    open::that(url).map_err(|e| tr!("Failed to open link: {}", e))
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub fn open_permission_settings(permission: String) -> Result<(), String> {
+    let _ = permission;
+    Err(unsupported("open_permission_settings"))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub(crate) fn settings_url(permission: &str) -> Option<&'static str> {
+    SETTINGS.iter().find(|(id, _)| *id == permission).map(|(_, url)| *url)
+}
+
+fn permission(id: &str, status: &str) -> PermissionStatus {
+    let hint = match (status, id) {
+        ("granted", _) => String::new(),
+        ("unsupported", _) => tr!("Only needed on macOS"),
+        (_, "automation_notes") => tr!("Allow Life OS to control {} in System Settings → Privacy & Security → Automation", "Notes"),
+        (_, "automation_calendar") => tr!("Allow Life OS to control {} in System Settings → Privacy & Security → Automation", "Calendar"),
+        (_, "full_disk_access") => tr!("Add Life OS under System Settings → Privacy & Security → Full Disk Access to read Messages"),
+        (_, "notifications") => tr!("Turn on notifications for Life OS in System Settings → Notifications"),
+        _ => tr!("Add Life OS under System Settings → Privacy & Security → Accessibility"),
+    };
+    PermissionStatus { id: id.to_string(), status: status.to_string(), hint, settings_url: settings_url(id).unwrap_or_default().to_string() }
+}
+
+/// AppleScript error for `app`, spelling out the fix when the app is not
+/// allowed to control it (-1743) instead of passing osascript's text through
+#[cfg(target_os = "macos")]
+pub(crate) fn apple_script_error(app: &str, stderr: &str) -> String {
+    if stderr.contains("-1743") {
+        return tr!("Allow Life OS to control {} in System Settings → Privacy & Security → Automation", app);
+    }
+    tr!("AppleScript error: {}", stderr.trim())
+}
+
+#[cfg(target_os = "macos")]
+async fn automation(app: &str, probe: &str) -> &'static str {
+    let run = tokio::process::Command::new("osascript")
+        .arg("-e")
+        .arg(format!("tell application \"{app}\" to {probe}"))
+        .kill_on_drop(true)
+        .output();
+    match tokio::time::timeout(PROBE_TIMEOUT, run).await {
+        Ok(Ok(output)) if output.status.success() => "granted",
+        Ok(Ok(output)) if String::from_utf8_lossy(&output.stderr).contains("-1743") => "denied",
+        // Still waiting on the consent dialog
+        Err(_) => "not_determined",
+        Ok(_) => "unknown",
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+async fn automation(app: &str, probe: &str) -> &'static str {
+    let _ = (app, probe);
+    "unsupported"
+}
+
+/// Only apps with Full Disk Access can open the user's TCC database
+#[cfg(target_os = "macos")]
+fn full_disk_access() -> &'static str {
+    let Some(home) = std::env::var_os("HOME") else { return "unknown" };
+    let tcc = std::path::Path::new(&home).join("Library/Application Support/com.apple.TCC/TCC.db");
+    match std::fs::File::open(tcc) {
+        Ok(_) => "granted",
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => "unknown",
+        Err(_) => "denied",
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn full_disk_access() -> &'static str {
+    "unsupported"
+}
+
+#[cfg(target_os = "macos")]
+fn accessibility() -> &'static str {
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrusted() -> bool;
+    }
+    // SAFETY: takes no arguments and only reads the process's trust state
+    if unsafe { AXIsProcessTrusted() } {
+        "granted"
+    } else {
+        "denied"
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn accessibility() -> &'static str {
+    "unsupported"
+}
@@ -0,0 +1,214 @@
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use crate::services::notes::{split_frontmatter, vault_rel};
+use crate::services::{durable, notes};
+
+/// One question per `- ` list item, added to the built-in ones
+const VAULT_PROMPTS: &str = "diary/templates/prompts.md";
+/// Every prompt handed out, oldest first
+const HISTORY_FILE: &str = ".lifeos/prompts.json";
+/// Replaced by the prompt when a template puts it somewhere; otherwise it
+/// goes at the end of the note
+const PLACEHOLDER: &str = "{{prompt}}";
+
+const BUILTIN_PROMPTS: &[&str] = &[
+    "今天最让你感激的一件事是什么？",
+    "今天有什么事让你意外？",
+    "如果今天可以重来，你会做哪件不同的事？",
+    "最近一直在想的问题是什么？",
+    "今天在哪件事上花的精力最值得？",
+    "有什么事你一直在拖延？为什么？",
+    "今天和谁的交流让你印象最深？",
+    "这周你学到了什么新东西？",
+    "现在最让你担心的是什么？它有多大可能发生？",
+    "一年后的你会怎么看今天的烦恼？",
+    "今天你为自己做了什么？",
+    "最近有什么习惯在悄悄改变你？",
+    "你现在最想对谁说声谢谢？",
+    "今天有哪个时刻让你觉得很有活力？",
+    "如果明天只能完成一件事，你会选哪件？",
+];
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JournalPrompt {
+    pub text: String,
+    /// "builtin" | "vault"
+    pub source: String,
+    pub uses: usize,
+    /// YYYY-MM-DD
+    pub last_used: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct PromptUse {
+    prompt: String,
+    /// YYYY-MM-DD
+    date: String,
+    /// Vault-relative note
+    path: String,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// The prompt pool (built-in questions plus diary/templates/prompts.md)
+/// with how often each has been handed out
+#[tauri::command]
+pub fn list_journal_prompts(vault_path: String) -> Vec<JournalPrompt> {
+    let vault = Path::new(&vault_path);
+    let history = load_history(vault);
+    pool(vault)
+        .into_iter()
+        .map(|(text, source)| {
+            let used: Vec<&PromptUse> = history.iter().filter(|u| u.prompt == text).collect();
+            let last_used = used.iter().map(|u| u.date.clone()).max();
+            JournalPrompt { text, source: source.into(), uses: used.len(), last_used }
+        })
+        .collect()
+}
+
+/// Give a new diary note a reflection question: `prompt` in its
+/// frontmatter and the question in its body. Prompts used least come
+/// first, so none repeats until the whole pool has been seen. Returns the
+/// question, or None when the note already had one.
+#[tauri::command]
+pub fn insert_journal_prompt(vault_path: String, path: String) -> Result<Option<String>, String> {
+    let vault = Path::new(&vault_path);
+    let path = PathBuf::from(path);
+    let raw = fs::read_to_string(&path).map_err(|e| tr!("Failed to read: {}", e))?;
+    let fm: serde_yaml::Value = split_frontmatter(&raw).0.and_then(|y| serde_yaml::from_str(y).ok()).unwrap_or_default();
+    if fm.get("prompt").and_then(|p| p.as_str()).is_some_and(|p| !p.trim().is_empty()) {
+        return Ok(None);
+    }
+    let date = note_date(&fm, &path).unwrap_or_else(|| Local::now().date_naive()).format("%Y-%m-%d").to_string();
+    let rel = vault_rel(vault, &path);
+
+    let mut history = load_history(vault);
+    let Some(prompt) = pick(&pool(vault), &history, &rel) else { return Ok(None) };
+    let content = notes::set_field(&raw, "prompt", Some(&serde_yaml::Value::String(prompt.clone())))?;
+    let quote = format!("> 💭 {prompt}");
+    let content = if content.contains(PLACEHOLDER) {
+        content.replacen(PLACEHOLDER, &quote, 1)
+    } else {
+        format!("{}\n\n{quote}\n\n", content.trim_end())
+    };
+    durable::write(&path, content).map_err(|e| tr!("write_file failed: {}", e))?;
+
+    history.push(PromptUse { prompt: prompt.clone(), date, path: rel });
+    save_history(vault, &history)?;
+    Ok(Some(prompt))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// (text, source), without duplicates
+fn pool(vault: &Path) -> Vec<(String, &'static str)> {
+    let mut prompts: Vec<(String, &'static str)> = BUILTIN_PROMPTS.iter().map(|p| (p.to_string(), "builtin")).collect();
+    let raw = fs::read_to_string(vault.join(VAULT_PROMPTS)).unwrap_or_default();
+    let body = split_frontmatter(&raw).1;
+    for line in body.lines() {
+        let Some(text) = line.trim().strip_prefix("- ").map(str::trim) else { continue };
+        if !text.is_empty() && !prompts.iter().any(|(p, _)| p == text) {
+            prompts.push((text.to_string(), "vault"));
+        }
+    }
+    prompts
+}
+
+/// Least-used prompt; ties are broken by the note, so the order is not the
+/// same on every pass through the pool
+fn pick(pool: &[(String, &str)], history: &[PromptUse], seed: &str) -> Option<String> {
+    let uses = |text: &str| history.iter().filter(|u| u.prompt == text).count();
+    let fewest = pool.iter().map(|(text, _)| uses(text)).min()?;
+    let candidates: Vec<&String> = pool.iter().map(|(text, _)| text).filter(|text| uses(text) == fewest).collect();
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    Some(candidates[hasher.finish() as usize % candidates.len()].clone())
+}
+
+fn note_date(fm: &serde_yaml::Value, path: &Path) -> Option<NaiveDate> {
+    let from_fm = fm.get("date").and_then(|d| d.as_str()).map(str::to_string);
+    let from_name = path.file_name().map(|n| n.to_string_lossy().to_string());
+    [from_fm, from_name].into_iter().flatten().find_map(|d| NaiveDate::parse_from_str(d.get(..10)?, "%Y-%m-%d").ok())
+}
+
+fn load_history(vault: &Path) -> Vec<PromptUse> {
+    fs::read_to_string(vault.join(HISTORY_FILE)).ok().and_then(|raw| serde_json::from_str(&raw).ok()).unwrap_or_default()
+}
+
+fn save_history(vault: &Path, history: &[PromptUse]) -> Result<(), String> {
+    let path = vault.join(HISTORY_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(history).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(path, json).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, rel: &str, text: &str) {
+        let path = dir.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    #[test]
+    fn test_insert_journal_prompt() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        let path = v.to_string_lossy().to_string();
+        write(v, VAULT_PROMPTS, "# 我的问题\n\n- 今天读了什么？\n- 今天有什么事让你意外？\n");
+        assert_eq!(pool(v).len(), BUILTIN_PROMPTS.len() + 1);
+
+        let note = v.join("diary/2026/2026-03-10-0900.md");
+        write(v, "diary/2026/2026-03-10-0900.md", "---\ndate: 2026-03-10\nmood: 😊\n---\n# 2026-03-10\n\n");
+        let prompt = insert_journal_prompt(path.clone(), note.to_string_lossy().to_string()).unwrap().unwrap();
+        let raw = fs::read_to_string(&note).unwrap();
+        let fm: serde_yaml::Value = serde_yaml::from_str(split_frontmatter(&raw).0.unwrap()).unwrap();
+        assert_eq!(fm["prompt"].as_str(), Some(prompt.as_str()));
+        assert!(raw.ends_with(&format!("# 2026-03-10\n\n> 💭 {prompt}\n\n")));
+        // Once is enough
+        assert_eq!(insert_journal_prompt(path.clone(), note.to_string_lossy().to_string()).unwrap(), None);
+
+        write(v, "diary/2026/t.md", "---\ndate: 2026-03-11\n---\n## 今日一问\n\n{{prompt}}\n\n## 记录\n");
+        let second = insert_journal_prompt(path.clone(), v.join("diary/2026/t.md").to_string_lossy().to_string()).unwrap().unwrap();
+        assert_ne!(second, prompt);
+        assert!(fs::read_to_string(v.join("diary/2026/t.md")).unwrap().contains(&format!("## 今日一问\n\n> 💭 {second}\n\n## 记录")));
+
+        let listed = list_journal_prompts(path);
+        let used: Vec<&JournalPrompt> = listed.iter().filter(|p| p.uses > 0).collect();
+        assert_eq!(used.len(), 2);
+        assert!(used.iter().any(|p| p.text == second && p.last_used.as_deref() == Some("2026-03-11")));
+    }
+
+    #[test]
+    fn test_pick_cycles_through_pool() {
+        let pool: Vec<(String, &str)> = ["a", "b", "c"].iter().map(|p| (p.to_string(), "builtin")).collect();
+        let mut history = Vec::new();
+        for n in 0..3 {
+            let prompt = pick(&pool, &history, &format!("note-{n}")).unwrap();
+            assert!(!history.iter().any(|u: &PromptUse| u.prompt == prompt));
+            history.push(PromptUse { prompt, date: "2026-03-10".into(), path: String::new() });
+        }
+        assert!(pick(&[], &history, "x").is_none());
+    }
+}
@@ -0,0 +1,193 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use super::email_commands::read_email_content;
+use crate::services::{durable, mail_html};
+
+/// Read position per "account/email_id"
+const STATE_FILE: &str = ".lifeos/reader.json";
+/// Positions kept, most recently read first
+const MAX_POSITIONS: usize = 500;
+/// Reading speed for the estimate: words of spaced scripts, characters of CJK
+const WORDS_PER_MINUTE: usize = 230;
+const CJK_PER_MINUTE: usize = 400;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingProgress {
+    /// Fraction of the article scrolled past, 0–1
+    pub position: f64,
+    pub finished: bool,
+    /// RFC 3339
+    pub updated_at: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ReaderView {
+    pub account_id: String,
+    pub email_id: String,
+    pub subject: String,
+    pub from: String,
+    pub date: String,
+    /// Set for mailing-list mail (newsletters)
+    pub list_id: Option<String>,
+    /// Text structure only; see `mail_html::reader_html`
+    pub html: String,
+    pub markdown: String,
+    /// Remote images left out of `html`
+    pub remote_blocked: usize,
+    pub minutes: usize,
+    pub progress: ReadingProgress,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// A cached message reduced to its article: clean HTML, markdown, reading
+/// time and where the reader left off. Meant for newsletters but works for
+/// any message; plain-text mail is split into paragraphs.
+#[tauri::command]
+pub fn get_reader_view(vault_path: String, account_id: String, email_id: String, allow_remote: Option<bool>) -> Result<ReaderView, String> {
+    let email = read_email_content(vault_path.clone(), account_id.clone(), email_id.clone())?;
+    let html = match (email.body_html, email.body_text) {
+        (Some(html), _) if !html.trim().is_empty() => html,
+        (_, Some(text)) => text_html(&text),
+        _ => String::new(),
+    };
+    let reader = mail_html::reader_html(&html, allow_remote.unwrap_or(false));
+    let markdown = mail_html::to_markdown(&reader.html);
+    let progress = load_state(Path::new(&vault_path)).remove(&key(&account_id, &email_id)).unwrap_or_default();
+
+    Ok(ReaderView {
+        minutes: reading_minutes(&markdown),
+        account_id,
+        email_id,
+        subject: email.subject,
+        from: email.from,
+        date: email.date,
+        list_id: email.list_id,
+        html: reader.html,
+        markdown,
+        remote_blocked: reader.remote_blocked,
+        progress,
+    })
+}
+
+/// Remember how far into a message the reader got; `finished` marks it read
+/// to the end (archiving is left to `archive_email`)
+#[tauri::command]
+pub fn save_reading_position(
+    vault_path: String,
+    account_id: String,
+    email_id: String,
+    position: f64,
+    finished: Option<bool>,
+) -> Result<ReadingProgress, String> {
+    let vault = Path::new(&vault_path);
+    let mut state = load_state(vault);
+    let key = key(&account_id, &email_id);
+    let was_finished = state.get(&key).is_some_and(|p| p.finished);
+    let progress = ReadingProgress {
+        position: position.clamp(0.0, 1.0),
+        finished: finished.unwrap_or(was_finished),
+        updated_at: Local::now().to_rfc3339(),
+    };
+    state.insert(key, progress.clone());
+    if state.len() > MAX_POSITIONS {
+        let mut by_age: Vec<(String, String)> = state.iter().map(|(k, p)| (p.updated_at.clone(), k.clone())).collect();
+        by_age.sort();
+        for (_, stale) in by_age.into_iter().take(state.len() - MAX_POSITIONS) {
+            state.remove(&stale);
+        }
+    }
+    save_state(vault, &state)?;
+    Ok(progress)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn key(account_id: &str, email_id: &str) -> String {
+    format!("{account_id}/{email_id}")
+}
+
+/// Plain-text body as paragraphs, escaped
+fn text_html(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let escaped = p.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+            format!("<p>{}</p>", escaped.replace('\n', "<br>"))
+        })
+        .collect()
+}
+
+/// Minutes to read `text`, at least one
+fn reading_minutes(text: &str) -> usize {
+    let cjk = text.chars().filter(|c| is_cjk(*c)).count();
+    let words = text.split(|c: char| c.is_whitespace() || is_cjk(c)).filter(|w| w.chars().any(char::is_alphanumeric)).count();
+    (words / WORDS_PER_MINUTE + cjk / CJK_PER_MINUTE).max(1)
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30ff}' | '\u{3400}'..='\u{4dbf}' | '\u{4e00}'..='\u{9fff}' | '\u{ac00}'..='\u{d7af}')
+}
+
+fn load_state(vault: &Path) -> BTreeMap<String, ReadingProgress> {
+    fs::read_to_string(vault.join(STATE_FILE)).ok().and_then(|raw| serde_json::from_str(&raw).ok()).unwrap_or_default()
+}
+
+fn save_state(vault: &Path, state: &BTreeMap<String, ReadingProgress>) -> Result<(), String> {
+    let path = vault.join(STATE_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(state).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(path, json).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_html() {
+        assert_eq!(text_html("Hi <you>,\r\nline two\r\n\r\n\r\nBye & thanks"), "<p>Hi &lt;you&gt;,<br>line two</p><p>Bye &amp; thanks</p>");
+    }
+
+    #[test]
+    fn test_reading_minutes() {
+        assert_eq!(reading_minutes(""), 1);
+        assert_eq!(reading_minutes(&"word ".repeat(700)), 3);
+        assert_eq!(reading_minutes(&"阅读".repeat(400)), 2);
+        assert_eq!(reading_minutes("- [x](y) — *"), 1);
+    }
+
+    #[test]
+    fn test_reading_position() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().to_string_lossy().to_string();
+        let saved = save_reading_position(vault.clone(), "work".into(), "INBOX_7".into(), 1.4, None).unwrap();
+        assert_eq!((saved.position, saved.finished), (1.0, false));
+        save_reading_position(vault.clone(), "work".into(), "INBOX_7".into(), 1.0, Some(true)).unwrap();
+        let later = save_reading_position(vault.clone(), "work".into(), "INBOX_7".into(), 0.2, None).unwrap();
+        assert!(later.finished);
+        assert_eq!(load_state(dir.path()).get("work/INBOX_7").map(|p| p.position), Some(0.2));
+    }
+}
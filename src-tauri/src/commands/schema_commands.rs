@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::services::schemas::{self, Schema, Violation};
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SchemaReport {
+    /// Notes a schema applies to
+    pub notes: usize,
+    /// By note, then field
+    pub violations: Vec<Violation>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// The schemas in effect: .lifeos/schemas/ over the built-in ones
+#[tauri::command]
+pub fn list_schemas(vault_path: String) -> Vec<Schema> {
+    schemas::load(Path::new(&vault_path))
+}
+
+/// Frontmatter that breaks its folder's schema, in the notes under `scope`
+/// (a vault-relative folder or note; default the whole vault)
+#[tauri::command]
+pub async fn validate_notes(vault_path: String, scope: Option<String>) -> Result<SchemaReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let (notes, violations) = schemas::validate(Path::new(&vault_path), scope.as_deref())?;
+        Ok(SchemaReport { notes, violations })
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Component, Path, PathBuf};
+
+#[cfg(not(target_os = "macos"))]
+use crate::services::unsupported;
+
+/// Content-addressed: the same capture twice is stored once
+const SCREENSHOTS_DIR: &str = "assets/images/screenshots";
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Screenshot {
+    pub path: String,
+    /// `![截图](...)`, relative to `note_path` when given, else to the vault
+    pub markdown: String,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Capture the screen (`mode`: "full" | "window" | "selection") into
+/// assets/images/screenshots/<hash>.png. None when the user cancels the
+/// window/selection picker.
+#[tauri::command]
+pub async fn capture_screenshot(vault_path: String, mode: String, note_path: Option<String>) -> Result<Option<Screenshot>, String> {
+    let tmp = std::env::temp_dir().join(format!("lifeos-capture-{}.png", uuid::Uuid::new_v4()));
+    if !take_screenshot(&mode, &tmp).await? {
+        return Ok(None);
+    }
+    let bytes = fs::read(&tmp).map_err(|e| tr!("Failed to read: {}", e));
+    let _ = fs::remove_file(&tmp);
+    let vault = PathBuf::from(&vault_path);
+    let path = store(&vault, &bytes?)?;
+    let base = match note_path.as_deref() {
+        Some(note) => Path::new(note).parent().map(Path::to_path_buf).unwrap_or_else(|| vault.clone()),
+        None => vault.clone(),
+    };
+    Ok(Some(Screenshot {
+        markdown: format!("![截图]({})", relative_link(&base, &path).replace(' ', "%20")),
+        path: path.to_string_lossy().to_string(),
+    }))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// `screencapture` writes nothing when the picker is cancelled → false
+#[cfg(target_os = "macos")]
+async fn take_screenshot(mode: &str, dest: &Path) -> Result<bool, String> {
+    let flags: &[&str] = match mode {
+        "full" => &["-x"],
+        "window" => &["-x", "-i", "-W"],
+        "selection" => &["-x", "-i", "-s"],
+        _ => return Err(tr!("Unknown screenshot mode: {}", mode)),
+    };
+    let output = tokio::process::Command::new("screencapture")
+        .args(flags)
+        .arg(dest)
+        .output()
+        .await
+        .map_err(|e| tr!("Failed to run '{}': {}", "screencapture", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(dest.exists())
+}
+
+#[cfg(not(target_os = "macos"))]
+async fn take_screenshot(mode: &str, dest: &Path) -> Result<bool, String> {
+    let _ = (mode, dest);
+    Err(unsupported("capture_screenshot"))
+}
+
+fn store(vault: &Path, png: &[u8]) -> Result<PathBuf, String> {
+    let mut hasher = DefaultHasher::new();
+    png.hash(&mut hasher);
+    let dir = vault.join(SCREENSHOTS_DIR);
+    fs::create_dir_all(&dir).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    let path = dir.join(format!("{:016x}.png", hasher.finish()));
+    if !path.exists() {
+        fs::write(&path, png).map_err(|e| tr!("write_file failed: {}", e))?;
+    }
+    Ok(path)
+}
+
+/// Path of `target` as seen from directory `base`, forward slashes
+pub(crate) fn relative_link(base: &Path, target: &Path) -> String {
+    let base: Vec<Component> = base.components().collect();
+    let target: Vec<Component> = target.components().collect();
+    let common = base.iter().zip(&target).take_while(|(a, b)| a == b).count();
+    let ups = std::iter::repeat_n("..".to_string(), base.len() - common);
+    let downs = target[common..].iter().map(|c| c.as_os_str().to_string_lossy().to_string());
+    ups.chain(downs).collect::<Vec<_>>().join("/")
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_and_link() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = store(dir.path(), b"png-bytes").unwrap();
+        assert_eq!(store(dir.path(), b"png-bytes").unwrap(), first);
+        assert_ne!(store(dir.path(), b"other").unwrap(), first);
+
+        let vault = Path::new("/v");
+        let shot = Path::new("/v/assets/images/screenshots/ab.png");
+        assert_eq!(relative_link(&vault.join("daily/tasks"), shot), "../../assets/images/screenshots/ab.png");
+        assert_eq!(relative_link(vault, shot), "assets/images/screenshots/ab.png");
+    }
+}
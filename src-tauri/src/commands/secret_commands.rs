@@ -0,0 +1,30 @@
+use std::path::Path;
+
+use crate::services::secrets::{self, SecretMeta};
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Save `value` in the Keychain under `name`. Config files can then use
+/// `keychain:<name>` instead of the plain value.
+#[tauri::command]
+pub fn store_secret(vault_path: String, name: String, value: String, note: Option<String>) -> Result<SecretMeta, String> {
+    secrets::store(Path::new(&vault_path), name.trim(), &value, note.as_deref())
+}
+
+#[tauri::command]
+pub fn get_secret(name: String) -> Result<String, String> {
+    secrets::get(name.trim())
+}
+
+#[tauri::command]
+pub fn delete_secret(vault_path: String, name: String) -> Result<(), String> {
+    secrets::delete(Path::new(&vault_path), name.trim())
+}
+
+/// Names and notes of the stored secrets; values are never listed
+#[tauri::command]
+pub fn list_secrets(vault_path: String) -> Vec<SecretMeta> {
+    secrets::list(Path::new(&vault_path))
+}
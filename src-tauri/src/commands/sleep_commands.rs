@@ -0,0 +1,461 @@
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use crate::services::{durable, mood, stats};
+
+/// One YAML map per year: life/health/sleep/{YYYY}.yaml, keyed by wake date
+const SLEEP_DIR: &str = "life/health/sleep";
+const DEFAULT_TARGET_HOURS: f64 = 8.0;
+/// Health segments closer than this belong to the same night
+const SESSION_GAP_MIN: i64 = 120;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SleepEntry {
+    /// Wake date, YYYY-MM-DD; the map key is authoritative when reading
+    #[serde(default)]
+    pub date: String,
+    /// HH:MM
+    pub bedtime: String,
+    /// HH:MM
+    pub wake: String,
+    /// Minutes asleep (less than bedtime→wake when awake segments are known)
+    pub minutes: u32,
+    /// 1–5, manual entries only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality: Option<u8>,
+    /// "manual" | "health" | "csv"
+    #[serde(default)]
+    pub source: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SleepImportReport {
+    pub imported: usize,
+    /// Nights already present, left untouched
+    pub existing: usize,
+    pub skipped: usize,
+    /// Counted only; nothing was written
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SleepTrends {
+    pub from: String,
+    pub to: String,
+    pub nights: Vec<SleepEntry>,
+    pub average_minutes: Option<u32>,
+    /// HH:MM, averaged around midnight
+    pub average_bedtime: Option<String>,
+    pub average_wake: Option<String>,
+    pub weekday_minutes: Option<u32>,
+    pub weekend_minutes: Option<u32>,
+    pub target_minutes: u32,
+    /// Shortfall against the target summed over logged nights
+    pub debt_minutes: u32,
+    /// Pearson r of sleep minutes vs. that day's diary mood (1–5)
+    pub mood_correlation: Option<f64>,
+    pub mood_samples: usize,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Record a night by hand. `date` is the wake-up day; bedtime after wake
+/// time means it started the evening before.
+#[tauri::command]
+pub fn log_sleep(
+    vault_path: String,
+    date: String,
+    bedtime: String,
+    wake: String,
+    quality: Option<u8>,
+    note: Option<String>,
+) -> Result<SleepEntry, String> {
+    let vault = Path::new(&vault_path);
+    let day = parse_date(&date)?;
+    let (bed, up) = (parse_time(&bedtime)?, parse_time(&wake)?);
+    let mut minutes = (up - bed).num_minutes();
+    if minutes <= 0 {
+        minutes += 24 * 60;
+    }
+    let entry = SleepEntry {
+        date: day.format("%Y-%m-%d").to_string(),
+        bedtime: bed.format("%H:%M").to_string(),
+        wake: up.format("%H:%M").to_string(),
+        minutes: minutes as u32,
+        quality: quality.map(|q| q.clamp(1, 5)),
+        source: "manual".to_string(),
+        note: note.unwrap_or_default().trim().to_string(),
+    };
+    let mut year = read_year(vault, day.year())?;
+    year.insert(entry.date.clone(), entry.clone());
+    write_year(vault, day.year(), &year)?;
+    Ok(entry)
+}
+
+#[tauri::command]
+pub fn delete_sleep(vault_path: String, date: String) -> Result<(), String> {
+    let vault = Path::new(&vault_path);
+    let day = parse_date(&date)?;
+    let mut year = read_year(vault, day.year())?;
+    if year.remove(&date).is_none() {
+        return Err(tr!("No sleep entry for {}", date));
+    }
+    write_year(vault, day.year(), &year)
+}
+
+/// Import an Apple Health `export.xml` (sleep analysis records) or a CSV with
+/// `date,bedtime,wake[,minutes]` columns. Nights already recorded are kept;
+/// `dry_run` only counts what would be imported.
+#[tauri::command]
+pub async fn import_sleep(vault_path: String, file_path: String, dry_run: Option<bool>) -> Result<SleepImportReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let file = fs::File::open(&file_path).map_err(|e| tr!("Failed to read: {}", e))?;
+        let reader = BufReader::new(file);
+        let (nights, skipped) = if file_path.to_lowercase().ends_with(".xml") {
+            parse_health_export(reader)
+        } else {
+            parse_sleep_csv(reader)
+        };
+        let dry_run = dry_run.unwrap_or(false);
+        let mut report = SleepImportReport { skipped, dry_run, ..Default::default() };
+        let vault = PathBuf::from(&vault_path);
+        let mut by_year: BTreeMap<i32, Vec<SleepEntry>> = BTreeMap::new();
+        for night in nights {
+            let year = night.date.get(..4).and_then(|y| y.parse().ok()).unwrap_or(0);
+            by_year.entry(year).or_default().push(night);
+        }
+        for (y, nights) in by_year {
+            let mut year = read_year(&vault, y)?;
+            let before = year.len();
+            for night in nights {
+                if year.contains_key(&night.date) {
+                    report.existing += 1;
+                } else {
+                    year.insert(night.date.clone(), night);
+                }
+            }
+            if year.len() > before {
+                report.imported += year.len() - before;
+                if !dry_run {
+                    write_year(&vault, y, &year)?;
+                }
+            }
+        }
+        Ok(report)
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Nights between two dates (inclusive), oldest first
+#[tauri::command]
+pub fn list_sleep(vault_path: String, from: String, to: String) -> Result<Vec<SleepEntry>, String> {
+    load_range(Path::new(&vault_path), parse_date(&from)?, parse_date(&to)?)
+}
+
+/// Averages, sleep debt and mood correlation over `range` (`7d`, `30d`,
+/// `3m`, `2025-03`, `2025-01-01..2025-03-31`; see `stats::parse_range`)
+#[tauri::command]
+pub fn get_sleep_trends(vault_path: String, range: String, target_hours: Option<f64>) -> Result<SleepTrends, String> {
+    let vault = Path::new(&vault_path);
+    let (from, to) = stats::parse_range(&range, Local::now().date_naive())?;
+    let nights = load_range(vault, from, to)?;
+    let moods = mood::daily_scores(vault);
+    Ok(trends(from, to, nights, &moods, target_hours.unwrap_or(DEFAULT_TARGET_HOURS)))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Storage
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn year_path(vault: &Path, year: i32) -> PathBuf {
+    vault.join(SLEEP_DIR).join(format!("{year}.yaml"))
+}
+
+fn read_year(vault: &Path, year: i32) -> Result<BTreeMap<String, SleepEntry>, String> {
+    let path = year_path(vault, year);
+    let Ok(raw) = fs::read_to_string(&path) else { return Ok(BTreeMap::new()) };
+    let mut map: BTreeMap<String, SleepEntry> = serde_yaml::from_str(&raw).map_err(|e| tr!("Failed to parse: {}", e))?;
+    for (date, entry) in map.iter_mut() {
+        entry.date = date.clone();
+    }
+    Ok(map)
+}
+
+fn write_year(vault: &Path, year: i32, map: &BTreeMap<String, SleepEntry>) -> Result<(), String> {
+    let path = year_path(vault, year);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let yaml = serde_yaml::to_string(map).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(&path, yaml).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+pub(crate) fn load_range(vault: &Path, from: NaiveDate, to: NaiveDate) -> Result<Vec<SleepEntry>, String> {
+    let (lo, hi) = (from.format("%Y-%m-%d").to_string(), to.format("%Y-%m-%d").to_string());
+    let mut out = Vec::new();
+    for year in from.year()..=to.year() {
+        out.extend(read_year(vault, year)?.into_iter().filter(|(d, _)| *d >= lo && *d <= hi).map(|(_, e)| e));
+    }
+    Ok(out)
+}
+
+fn parse_date(raw: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(raw.trim(), "%Y-%m-%d").map_err(|_| tr!("Invalid date (expected YYYY-MM-DD): {}", raw))
+}
+
+fn parse_time(raw: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(raw.trim(), "%H:%M").map_err(|_| tr!("Invalid time (expected HH:MM): {}", raw))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Importers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Stream export.xml line by line (it is often gigabytes) and merge the
+/// asleep segments into nights. "In bed" is only used for nights without
+/// any asleep segment (older iPhones without a watch).
+fn parse_health_export(reader: impl BufRead) -> (Vec<SleepEntry>, usize) {
+    let mut asleep: Vec<(NaiveDateTime, NaiveDateTime)> = Vec::new();
+    let mut in_bed: Vec<(NaiveDateTime, NaiveDateTime)> = Vec::new();
+    let mut skipped = 0;
+    for line in reader.lines().map_while(Result::ok) {
+        if !line.contains("HKCategoryTypeIdentifierSleepAnalysis") {
+            continue;
+        }
+        let span = xml_attr(&line, "startDate").and_then(health_time).zip(xml_attr(&line, "endDate").and_then(health_time));
+        let Some((start, end)) = span.filter(|(s, e)| e > s) else {
+            skipped += 1;
+            continue;
+        };
+        match xml_attr(&line, "value").unwrap_or_default() {
+            v if v.contains("Asleep") => asleep.push((start, end)),
+            v if v.ends_with("InBed") => in_bed.push((start, end)),
+            _ => {}
+        }
+    }
+    let mut nights = sessions(asleep);
+    for night in sessions(in_bed) {
+        if !nights.iter().any(|n| n.date == night.date) {
+            nights.push(night);
+        }
+    }
+    nights.sort_by(|a, b| a.date.cmp(&b.date));
+    (nights, skipped)
+}
+
+fn xml_attr<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let key = format!(" {name}=\"");
+    let start = line.find(&key)? + key.len();
+    let end = line[start..].find('"')?;
+    Some(&line[start..start + end])
+}
+
+/// `2024-01-01 23:10:00 +0800`, kept in the recorded local time
+fn health_time(raw: &str) -> Option<NaiveDateTime> {
+    DateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S %z").ok().map(|dt| dt.naive_local())
+}
+
+/// Group segments into nights (gaps under SESSION_GAP_MIN), attributed to the
+/// wake date. Overlapping segments (phone + watch) are not double-counted.
+fn sessions(mut segments: Vec<(NaiveDateTime, NaiveDateTime)>) -> Vec<SleepEntry> {
+    segments.sort();
+    let mut nights: Vec<SleepEntry> = Vec::new();
+    let mut current: Option<(NaiveDateTime, NaiveDateTime, i64)> = None;
+    let mut finish = |(start, end, minutes): (NaiveDateTime, NaiveDateTime, i64)| {
+        // Naps under an hour are not a night
+        if minutes >= 60 {
+            nights.push(SleepEntry {
+                date: end.date().format("%Y-%m-%d").to_string(),
+                bedtime: start.format("%H:%M").to_string(),
+                wake: end.format("%H:%M").to_string(),
+                minutes: minutes as u32,
+                quality: None,
+                source: "health".to_string(),
+                note: String::new(),
+            });
+        }
+    };
+    for (start, end) in segments {
+        current = match current {
+            Some((s, e, m)) if (start - e).num_minutes() < SESSION_GAP_MIN => {
+                let counted_from = start.max(e);
+                let extra = if end > counted_from { (end - counted_from).num_minutes() } else { 0 };
+                Some((s, e.max(end), m + extra))
+            }
+            Some(done) => {
+                finish(done);
+                Some((start, end, (end - start).num_minutes()))
+            }
+            None => Some((start, end, (end - start).num_minutes())),
+        };
+    }
+    if let Some(done) = current {
+        finish(done);
+    }
+    // Two sessions ending on the same day: keep the longer one as the night
+    let mut by_date: BTreeMap<String, SleepEntry> = BTreeMap::new();
+    for night in nights {
+        match by_date.get(&night.date) {
+            Some(existing) if existing.minutes >= night.minutes => {}
+            _ => {
+                by_date.insert(night.date.clone(), night);
+            }
+        }
+    }
+    by_date.into_values().collect()
+}
+
+/// `date,bedtime,wake[,minutes]` with an optional header row
+fn parse_sleep_csv(reader: impl BufRead) -> (Vec<SleepEntry>, usize) {
+    let mut csv = csv::ReaderBuilder::new().has_headers(false).flexible(true).trim(csv::Trim::All).from_reader(reader);
+    let mut nights = Vec::new();
+    let mut skipped = 0;
+    for (i, row) in csv.records().enumerate() {
+        let Ok(row) = row else {
+            skipped += 1;
+            continue;
+        };
+        let cell = |n: usize| row.get(n).unwrap_or("");
+        let parsed = parse_date(cell(0)).ok().zip(parse_time(cell(1)).ok()).zip(parse_time(cell(2)).ok());
+        let Some(((date, bed), wake)) = parsed else {
+            if i > 0 {
+                skipped += 1;
+            }
+            continue;
+        };
+        let span = (wake - bed).num_minutes();
+        let minutes = cell(3).parse::<u32>().unwrap_or(if span <= 0 { span + 24 * 60 } else { span } as u32);
+        nights.push(SleepEntry {
+            date: date.format("%Y-%m-%d").to_string(),
+            bedtime: bed.format("%H:%M").to_string(),
+            wake: wake.format("%H:%M").to_string(),
+            minutes,
+            quality: None,
+            source: "csv".to_string(),
+            note: String::new(),
+        });
+    }
+    (nights, skipped)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Trends
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn trends(from: NaiveDate, to: NaiveDate, nights: Vec<SleepEntry>, moods: &BTreeMap<NaiveDate, f64>, target_hours: f64) -> SleepTrends {
+    let target = (target_hours * 60.0).round() as u32;
+    let avg_minutes = |xs: Vec<f64>| stats::mean(&xs).map(|m| m.round() as u32);
+    let is_weekend = |n: &SleepEntry| {
+        NaiveDate::parse_from_str(&n.date, "%Y-%m-%d").is_ok_and(|d| d.weekday().number_from_monday() >= 6)
+    };
+
+    let mood_pairs: Vec<(f64, f64)> = nights
+        .iter()
+        .filter_map(|n| {
+            let date = NaiveDate::parse_from_str(&n.date, "%Y-%m-%d").ok()?;
+            moods.get(&date).map(|m| (f64::from(n.minutes), *m))
+        })
+        .collect();
+
+    SleepTrends {
+        from: from.format("%Y-%m-%d").to_string(),
+        to: to.format("%Y-%m-%d").to_string(),
+        average_minutes: avg_minutes(nights.iter().map(|n| f64::from(n.minutes)).collect()),
+        average_bedtime: average_clock(nights.iter().map(|n| n.bedtime.as_str())),
+        average_wake: average_clock(nights.iter().map(|n| n.wake.as_str())),
+        weekday_minutes: avg_minutes(nights.iter().filter(|n| !is_weekend(n)).map(|n| f64::from(n.minutes)).collect()),
+        weekend_minutes: avg_minutes(nights.iter().filter(|n| is_weekend(n)).map(|n| f64::from(n.minutes)).collect()),
+        target_minutes: target,
+        debt_minutes: nights.iter().map(|n| target.saturating_sub(n.minutes)).sum(),
+        mood_correlation: stats::pearson(&mood_pairs),
+        mood_samples: mood_pairs.len(),
+        nights,
+    }
+}
+
+/// Mean clock time, treating times before noon as after midnight so that
+/// 23:30 and 00:30 average to 00:00 rather than 12:00
+fn average_clock<'a>(times: impl Iterator<Item = &'a str>) -> Option<String> {
+    let minutes: Vec<f64> = times
+        .filter_map(|t| parse_time(t).ok())
+        .map(|t| {
+            let m = f64::from(t.hour() * 60 + t.minute());
+            if m < 12.0 * 60.0 { m + 24.0 * 60.0 } else { m }
+        })
+        .collect();
+    let avg = stats::mean(&minutes)?.round() as u32 % (24 * 60);
+    let time = NaiveTime::from_hms_opt(avg / 60, avg % 60, 0)?;
+    Some(time.format("%H:%M").to_string())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse_health_export_merges_segments() {
+        let xml = r#"<HealthData>
+ <Record type="HKCategoryTypeIdentifierSleepAnalysis" sourceName="Watch" startDate="2025-03-01 23:00:00 +0800" endDate="2025-03-02 03:00:00 +0800" value="HKCategoryValueSleepAnalysisAsleepCore"/>
+ <Record type="HKCategoryTypeIdentifierSleepAnalysis" sourceName="Watch" startDate="2025-03-02 03:20:00 +0800" endDate="2025-03-02 07:00:00 +0800" value="HKCategoryValueSleepAnalysisAsleepREM"/>
+ <Record type="HKCategoryTypeIdentifierSleepAnalysis" sourceName="Phone" startDate="2025-03-02 22:30:00 +0800" endDate="2025-03-03 06:30:00 +0800" value="HKCategoryValueSleepAnalysisInBed"/>
+ <Record type="HKQuantityTypeIdentifierStepCount" startDate="2025-03-02 10:00:00 +0800" endDate="2025-03-02 11:00:00 +0800" value="100"/>
+</HealthData>"#;
+        let (nights, skipped) = parse_health_export(xml.as_bytes());
+        assert_eq!(skipped, 0);
+        assert_eq!(nights.len(), 2);
+        assert_eq!((nights[0].date.as_str(), nights[0].bedtime.as_str(), nights[0].wake.as_str()), ("2025-03-02", "23:00", "07:00"));
+        assert_eq!(nights[0].minutes, 460);
+        assert_eq!((nights[1].date.as_str(), nights[1].minutes), ("2025-03-03", 480));
+    }
+
+    #[test]
+    fn test_parse_sleep_csv() {
+        let csv = "date,bedtime,wake\n2025-03-02,23:30,07:00\n2025-03-03,01:00,08:00,400\nbad,row\n";
+        let (nights, skipped) = parse_sleep_csv(csv.as_bytes());
+        assert_eq!(nights.iter().map(|n| n.minutes).collect::<Vec<_>>(), vec![450, 400]);
+        assert_eq!(skipped, 1);
+    }
+
+    #[test]
+    fn test_trends_debt_clock_and_mood() {
+        let night = |date: &str, bed: &str, minutes| SleepEntry {
+            date: date.into(),
+            bedtime: bed.into(),
+            wake: "07:00".into(),
+            minutes,
+            quality: None,
+            source: "manual".into(),
+            note: String::new(),
+        };
+        let nights = vec![night("2025-03-03", "23:30", 420), night("2025-03-04", "00:30", 360), night("2025-03-05", "23:00", 480)];
+        let moods = BTreeMap::from([(ymd(2025, 3, 3), 3.0), (ymd(2025, 3, 4), 2.0), (ymd(2025, 3, 5), 5.0)]);
+        let t = trends(ymd(2025, 3, 3), ymd(2025, 3, 5), nights, &moods, 8.0);
+        assert_eq!(t.average_minutes, Some(420));
+        assert_eq!(t.average_bedtime.as_deref(), Some("23:40"));
+        assert_eq!(t.debt_minutes, 60 + 120);
+        assert_eq!(t.mood_samples, 3);
+        assert!(t.mood_correlation.unwrap() > 0.9);
+    }
+}
@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+#[cfg(target_os = "macos")]
+use std::process::Command;
+
+#[cfg(target_os = "macos")]
+use crate::services::notes::vault_rel;
+use crate::services::spotlight;
+#[cfg(not(target_os = "macos"))]
+use crate::services::unsupported;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SpotlightReport {
+    /// Notes given metadata (or, when turning indexing off, cleared)
+    pub indexed: usize,
+    /// Unchanged since the last run
+    pub unchanged: usize,
+    /// [vault-relative path, error]
+    pub failed: Vec<(String, String)>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn get_spotlight_settings(vault_path: String) -> spotlight::Settings {
+    spotlight::settings(Path::new(&vault_path))
+}
+
+/// Give notes added or changed since the last run (all of them with `full`)
+/// a Spotlight title, keywords and description. A no-op when turned off.
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub async fn reindex_spotlight(vault_path: String, full: Option<bool>) -> Result<SpotlightReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let vault = Path::new(&vault_path);
+        if !spotlight::settings(vault).enabled {
+            return Ok(SpotlightReport::default());
+        }
+        if full.unwrap_or(false) {
+            spotlight::save_indexed(vault, &Default::default())?;
+        }
+        Ok(reindex(vault))
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Turning it off removes the metadata LifeOS added; Spotlight still finds
+/// notes by their text as it does any file
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub async fn set_spotlight_enabled(vault_path: String, enabled: bool) -> Result<SpotlightReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let vault = Path::new(&vault_path);
+        spotlight::save_settings(vault, &spotlight::Settings { enabled })?;
+        if enabled {
+            return Ok(reindex(vault));
+        }
+        let indexed = spotlight::indexed(vault);
+        for rel in indexed.keys() {
+            for name in spotlight::ATTRIBUTES {
+                // Absent attributes (a note edited elsewhere) are fine
+                let _ = Command::new("xattr").args(["-d", name]).arg(vault.join(rel)).output();
+            }
+        }
+        spotlight::save_indexed(vault, &Default::default())?;
+        Ok(SpotlightReport { indexed: indexed.len(), ..Default::default() })
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub async fn reindex_spotlight(vault_path: String, full: Option<bool>) -> Result<SpotlightReport, String> {
+    let _ = (vault_path, full);
+    Err(unsupported("reindex_spotlight"))
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub async fn set_spotlight_enabled(vault_path: String, enabled: bool) -> Result<SpotlightReport, String> {
+    let _ = (vault_path, enabled);
+    Err(unsupported("set_spotlight_enabled"))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(target_os = "macos")]
+fn reindex(vault: &Path) -> SpotlightReport {
+    let (stale, mut current) = spotlight::stale_notes(vault);
+    let mut report = SpotlightReport { unchanged: current.len() - stale.len(), ..Default::default() };
+    for path in &stale {
+        let rel = vault_rel(vault, path);
+        match write_attributes(path) {
+            Ok(()) => report.indexed += 1,
+            Err(e) => {
+                current.remove(&rel);
+                report.failed.push((rel, e));
+            }
+        }
+    }
+    if let Err(e) = spotlight::save_indexed(vault, &current) {
+        println!("[WARN] spotlight state: {e}");
+    }
+    // Ask Spotlight to pick the new attributes up now rather than eventually
+    if report.indexed > 0 {
+        let _ = Command::new("mdimport").arg(vault).spawn();
+    }
+    report
+}
+
+#[cfg(target_os = "macos")]
+fn write_attributes(path: &Path) -> Result<(), String> {
+    let raw = std::fs::read_to_string(path).map_err(|e| tr!("Failed to read: {}", e))?;
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    for (name, value) in spotlight::attributes(&spotlight::metadata(&raw, &stem)) {
+        let hex: String = value.iter().map(|b| format!("{b:02x}")).collect();
+        let output = Command::new("xattr")
+            .args(["-wx", name, &hex])
+            .arg(path)
+            .output()
+            .map_err(|e| tr!("Failed to run '{}': {}", "xattr", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,285 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+#[cfg(desktop)]
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, Window, WindowEvent};
+
+#[cfg(desktop)]
+use crate::services;
+
+const STATE_FILE: &str = ".lifeos/state.json";
+/// Writes wait for this long without further changes, so dragging a window
+/// or typing in a view doesn't write on every event
+const DEBOUNCE: Duration = Duration::from_millis(800);
+#[cfg(desktop)]
+const MAIN_WINDOW: &str = "main";
+
+/// The open vault's state, ahead of the file while a write is pending
+static STORE: Lazy<Mutex<Store>> = Lazy::new(|| Mutex::new(Store::default()));
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// .lifeos/state.json: where the app was when it last closed
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AppState {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window: Option<WindowGeometry>,
+    /// Vault-relative
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_note: Option<String>,
+    #[serde(default)]
+    pub tabs: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_tab: Option<String>,
+    /// Plugin id → whatever that view wants back (scroll, filters, selection)
+    #[serde(default)]
+    pub views: BTreeMap<String, Value>,
+}
+
+/// Physical pixels, as the window reports them
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub maximized: bool,
+}
+
+#[derive(Default)]
+struct Store {
+    vault: Option<PathBuf>,
+    state: AppState,
+    /// When the pending write is due; None when the file is current
+    due: Option<Instant>,
+    writer: bool,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn get_app_state(vault_path: String) -> AppState {
+    with_state(Path::new(&vault_path), |state| state.clone())
+}
+
+/// Merge `patch`: each top-level key present replaces that field, `null`
+/// clears it. `views` is merged per plugin rather than replaced.
+#[tauri::command]
+pub fn set_app_state(vault_path: String, patch: Value) -> Result<AppState, String> {
+    let vault = PathBuf::from(&vault_path);
+    let merged = with_state(&vault, |state| merge(state, patch))?;
+    update(&vault, |state| *state = merged.clone());
+    Ok(merged)
+}
+
+/// Replace one plugin's view state; `null` removes it
+#[tauri::command]
+pub fn set_view_state(vault_path: String, view: String, state: Value) {
+    update(Path::new(&vault_path), |app| {
+        if state.is_null() {
+            app.views.remove(&view);
+        } else {
+            app.views.insert(view, state);
+        }
+    });
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// App events
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Put the main window back where it was
+#[cfg(desktop)]
+pub(crate) fn restore_window(app: &AppHandle) {
+    let (Some(vault), Some(window)) = (services::configured_vault(), app.get_webview_window(MAIN_WINDOW)) else { return };
+    let Some(geometry) = with_state(Path::new(&vault), |state| state.window) else { return };
+    let _ = window.set_size(PhysicalSize::new(geometry.width, geometry.height));
+    let _ = window.set_position(PhysicalPosition::new(geometry.x, geometry.y));
+    if geometry.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// Record the main window's geometry as it moves and resizes. While
+/// maximized the last normal size is kept, so un-maximizing restores it.
+#[cfg(desktop)]
+pub(crate) fn on_window_event(window: &Window, event: &WindowEvent) {
+    if window.label() != MAIN_WINDOW || !matches!(event, WindowEvent::Moved(_) | WindowEvent::Resized(_)) {
+        return;
+    }
+    let Some(vault) = services::configured_vault() else { return };
+    let maximized = window.is_maximized().unwrap_or(false);
+    let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else { return };
+    // Minimizing reports a zero size
+    if size.width == 0 || size.height == 0 {
+        return;
+    }
+    update(Path::new(&vault), |state| match (&mut state.window, maximized) {
+        (Some(geometry), true) => geometry.maximized = true,
+        (slot, _) => {
+            *slot = Some(WindowGeometry { x: position.x, y: position.y, width: size.width, height: size.height, maximized });
+        }
+    });
+}
+
+/// Write a pending change now; called on exit
+pub(crate) fn flush() {
+    let mut store = STORE.lock().unwrap();
+    if store.due.take().is_some() {
+        write(&store);
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Run `f` on the vault's state, loading it first when another vault (or
+/// none) is cached
+fn with_state<T>(vault: &Path, f: impl FnOnce(&AppState) -> T) -> T {
+    let mut store = STORE.lock().unwrap();
+    switch_vault(&mut store, vault);
+    f(&store.state)
+}
+
+/// Change the state and schedule a write
+fn update(vault: &Path, f: impl FnOnce(&mut AppState)) {
+    let mut store = STORE.lock().unwrap();
+    switch_vault(&mut store, vault);
+    f(&mut store.state);
+    store.due = Some(Instant::now() + DEBOUNCE);
+    if !store.writer {
+        store.writer = true;
+        std::thread::spawn(write_when_due);
+    }
+}
+
+fn switch_vault(store: &mut Store, vault: &Path) {
+    if store.vault.as_deref() == Some(vault) {
+        return;
+    }
+    // A pending write belongs to the previous vault
+    if store.due.take().is_some() {
+        write(store);
+    }
+    store.state = load(vault);
+    store.vault = Some(vault.to_path_buf());
+}
+
+/// Sleeps until the latest change has been quiet for DEBOUNCE, then writes
+fn write_when_due() {
+    loop {
+        let wait = {
+            let mut store = STORE.lock().unwrap();
+            match store.due {
+                Some(due) if due > Instant::now() => due.saturating_duration_since(Instant::now()),
+                Some(_) => {
+                    store.due = None;
+                    write(&store);
+                    store.writer = false;
+                    return;
+                }
+                None => {
+                    store.writer = false;
+                    return;
+                }
+            }
+        };
+        std::thread::sleep(wait);
+    }
+}
+
+fn load(vault: &Path) -> AppState {
+    fs::read_to_string(vault.join(STATE_FILE))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn write(store: &Store) {
+    let Some(vault) = &store.vault else { return };
+    let path = vault.join(STATE_FILE);
+    let result = serde_json::to_string_pretty(&store.state)
+        .map_err(|e| e.to_string())
+        .and_then(|json| {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            services::durable::write(&path, json).map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        println!("[WARN] saving {}: {e}", path.display());
+    }
+}
+
+fn merge(state: &AppState, patch: Value) -> Result<AppState, String> {
+    let Value::Object(patch) = patch else { return Err(tr!("Invalid state: {}", patch)) };
+    let mut current = serde_json::to_value(state).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    let object = current.as_object_mut().expect("AppState serializes to an object");
+    for (key, value) in patch {
+        match (key.as_str(), value) {
+            ("views", Value::Object(views)) => {
+                let merged = object.entry("views").or_insert_with(|| Value::Object(Default::default()));
+                if let Value::Object(existing) = merged {
+                    for (view, value) in views {
+                        if value.is_null() {
+                            existing.remove(&view);
+                        } else {
+                            existing.insert(view, value);
+                        }
+                    }
+                }
+            }
+            (_, Value::Null) => {
+                object.remove(&key);
+            }
+            (_, value) => {
+                object.insert(key, value);
+            }
+        }
+    }
+    serde_json::from_value(current).map_err(|e| tr!("Invalid state: {}", e))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_and_debounced_write() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path().to_string_lossy().to_string();
+
+        set_view_state(v.clone(), "kanban".into(), json!({ "filter": "open" }));
+        let state = set_app_state(v.clone(), json!({ "last_note": "diary/2026-10-15.md", "tabs": ["daily", "mail"], "views": { "mail": { "folder": "INBOX" } } })).unwrap();
+        assert_eq!(state.last_note.as_deref(), Some("diary/2026-10-15.md"));
+        assert_eq!(state.views.keys().collect::<Vec<_>>(), ["kanban", "mail"]);
+
+        let state = set_app_state(v.clone(), json!({ "last_note": null, "views": { "kanban": null } })).unwrap();
+        assert_eq!((state.last_note, state.tabs.len()), (None, 2));
+        assert_eq!(state.views.keys().collect::<Vec<_>>(), ["mail"]);
+        assert!(set_app_state(v.clone(), json!({ "tabs": "daily" })).is_err());
+
+        // Nothing on disk until the changes settle
+        let file = vault.path().join(STATE_FILE);
+        assert!(!file.exists());
+        flush();
+        let saved: AppState = serde_json::from_str(&fs::read_to_string(&file).unwrap()).unwrap();
+        assert_eq!(saved, get_app_state(v));
+    }
+}
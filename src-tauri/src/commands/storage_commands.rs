@@ -0,0 +1,217 @@
+use chrono::Local;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+
+/// Walking a large vault takes a while and sizes rarely move much in minutes
+const CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+const LARGEST_FILES: usize = 20;
+/// Folders are reported this deep, e.g. `life/books`
+const FOLDER_DEPTH: usize = 2;
+const MAX_FOLDERS: usize = 40;
+/// Cached mail: the Mailbox notes and the per-account message store
+const MAIL_DIRS: &[&str] = &["Mailbox", ".lifeos/emails"];
+const ASSETS_DIR: &str = "assets";
+/// LifeOS's own state: history, trash, thumbnails, extracted PDF text…
+const APP_DIR: &str = ".lifeos";
+const ASSET_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "webp", "heic", "svg", "pdf", "mp3", "m4a", "wav", "mp4", "mov", "zip",
+];
+
+static CACHE: Lazy<Mutex<HashMap<PathBuf, (Instant, StorageReport)>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageCategory {
+    Notes,
+    Mail,
+    Assets,
+    /// .lifeos: history, trash, caches
+    App,
+    Other,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SizeEntry {
+    /// Relative to the analyzed root
+    pub path: String,
+    pub bytes: u64,
+    pub files: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CategorySize {
+    pub category: StorageCategory,
+    pub bytes: u64,
+    pub files: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct StorageReport {
+    pub root: String,
+    pub bytes: u64,
+    pub files: usize,
+    /// Folders up to two levels deep, largest first
+    pub folders: Vec<SizeEntry>,
+    /// Largest first
+    pub largest_files: Vec<SizeEntry>,
+    /// Largest first; empty categories left out
+    pub categories: Vec<CategorySize>,
+    /// RFC 3339
+    pub scanned_at: String,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Size breakdown of `root` (usually the vault) by folder, category and
+/// largest files. Cached for ten minutes per root unless `refresh` is set.
+#[tauri::command]
+pub async fn analyze_storage(root: String, refresh: Option<bool>) -> Result<StorageReport, String> {
+    let root = PathBuf::from(root);
+    if !root.is_dir() {
+        return Err(tr!("Path does not exist: {}", root.display()));
+    }
+    if !refresh.unwrap_or(false) {
+        if let Some((at, report)) = CACHE.lock().unwrap().get(&root) {
+            if at.elapsed() < CACHE_TTL {
+                return Ok(report.clone());
+            }
+        }
+    }
+    let walk_root = root.clone();
+    let report = tokio::task::spawn_blocking(move || analyze(&walk_root)).await.map_err(|e| tr!("Task execution failed: {}", e))?;
+    CACHE.lock().unwrap().insert(root, (Instant::now(), report.clone()));
+    Ok(report)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn analyze(root: &Path) -> StorageReport {
+    let mut folders: HashMap<String, (u64, usize)> = HashMap::new();
+    let mut categories: HashMap<StorageCategory, (u64, usize)> = HashMap::new();
+    let mut files = Vec::new();
+    let mut total = (0, 0);
+
+    // Symlinks aren't followed, so linked folders count once (as links)
+    for entry in WalkDir::new(root).into_iter().filter_map(|e| e.ok()).filter(|e| e.file_type().is_file()) {
+        let Ok(meta) = entry.metadata() else { continue };
+        let Ok(rel) = entry.path().strip_prefix(root) else { continue };
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        let bytes = meta.len();
+
+        total = (total.0 + bytes, total.1 + 1);
+        let slot = categories.entry(category(&rel)).or_default();
+        *slot = (slot.0 + bytes, slot.1 + 1);
+        // Every enclosing folder up to FOLDER_DEPTH; files at the top go under "."
+        let parts: Vec<&str> = rel.split('/').collect();
+        let dirs = &parts[..parts.len() - 1];
+        let mut prefixes: Vec<String> = (1..=dirs.len().min(FOLDER_DEPTH)).map(|n| dirs[..n].join("/")).collect();
+        if prefixes.is_empty() {
+            prefixes.push(".".to_string());
+        }
+        for prefix in prefixes {
+            let slot = folders.entry(prefix).or_default();
+            *slot = (slot.0 + bytes, slot.1 + 1);
+        }
+        files.push(SizeEntry { path: rel, bytes, files: 1 });
+    }
+
+    files.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    files.truncate(LARGEST_FILES);
+    let mut folders: Vec<SizeEntry> = folders.into_iter().map(|(path, (bytes, files))| SizeEntry { path, bytes, files }).collect();
+    folders.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    folders.truncate(MAX_FOLDERS);
+    let mut categories: Vec<CategorySize> = categories.into_iter().map(|(category, (bytes, files))| CategorySize { category, bytes, files }).collect();
+    categories.sort_by_key(|c| std::cmp::Reverse(c.bytes));
+
+    StorageReport {
+        root: root.to_string_lossy().to_string(),
+        bytes: total.0,
+        files: total.1,
+        folders,
+        largest_files: files,
+        categories,
+        scanned_at: Local::now().to_rfc3339(),
+    }
+}
+
+/// Which kind of data a root-relative path holds; the folder wins over
+/// the extension, so an image attached to a mail counts as mail
+fn category(rel: &str) -> StorageCategory {
+    let under = |dir: &str| rel == dir || rel.starts_with(&format!("{dir}/"));
+    if MAIL_DIRS.iter().any(|d| under(d)) {
+        return StorageCategory::Mail;
+    }
+    if under(APP_DIR) {
+        return StorageCategory::App;
+    }
+    let ext = Path::new(rel).extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    if under(ASSETS_DIR) || ASSET_EXTENSIONS.contains(&ext.as_str()) {
+        StorageCategory::Assets
+    } else if ext == "md" {
+        StorageCategory::Notes
+    } else {
+        StorageCategory::Other
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn write(root: &Path, rel: &str, bytes: usize) {
+        let path = root.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, vec![b'x'; bytes]).unwrap();
+    }
+
+    #[test]
+    fn test_category() {
+        assert_eq!(category("Mailbox/2026/msg.md"), StorageCategory::Mail);
+        assert_eq!(category(".lifeos/emails/work/1.eml"), StorageCategory::Mail);
+        assert_eq!(category(".lifeos/history/a.md"), StorageCategory::App);
+        assert_eq!(category("assets/images/a.png"), StorageCategory::Assets);
+        assert_eq!(category("life/books/scan.PDF"), StorageCategory::Assets);
+        assert_eq!(category("projects/lifeos.md"), StorageCategory::Notes);
+        assert_eq!(category("Mailboxes.md"), StorageCategory::Notes);
+        assert_eq!(category("life/finance/2026.csv"), StorageCategory::Other);
+    }
+
+    #[test]
+    fn test_analyze() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "README.md", 10);
+        write(dir.path(), "life/books/a.md", 100);
+        write(dir.path(), "life/books/deep/b.md", 50);
+        write(dir.path(), "Mailbox/work/m.md", 1000);
+        write(dir.path(), "assets/images/p.png", 500);
+
+        let report = analyze(dir.path());
+        assert_eq!((report.bytes, report.files), (1660, 5));
+        assert_eq!(report.largest_files[0].path, "Mailbox/work/m.md");
+        let folder = |p: &str| report.folders.iter().find(|f| f.path == p).map(|f| (f.bytes, f.files));
+        assert_eq!(folder("life"), Some((150, 2)));
+        assert_eq!(folder("life/books"), Some((150, 2)));
+        assert_eq!(folder("life/books/deep"), None);
+        assert_eq!(folder("."), Some((10, 1)));
+        assert_eq!(report.categories[0], CategorySize { category: StorageCategory::Mail, bytes: 1000, files: 1 });
+        assert_eq!(report.categories.iter().find(|c| c.category == StorageCategory::Notes).map(|c| c.bytes), Some(160));
+    }
+}
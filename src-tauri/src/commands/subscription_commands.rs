@@ -0,0 +1,443 @@
+use chrono::{Duration as Days, Local, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+use super::focus_commands::notifications_muted;
+use super::fs_commands::json_to_yaml;
+use crate::services::notes::{check_slug, slugify, split_frontmatter};
+use crate::services::periodic::Periodic;
+use crate::services::{durable, records};
+
+/// Same folder and frontmatter keys as the Subscriptions view
+const SUBS_DIR: &str = "subscriptions";
+/// Renewal reminders already shown
+const REMINDER_STATE: &str = ".lifeos/renewal-reminders.json";
+const DEFAULT_REMIND_DAYS: i64 = 3;
+const CHECK_EVERY: Duration = Duration::from_secs(60 * 60);
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// subscriptions/<slug>.md. The computed fields are filled on load and
+/// ignored on save.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Subscription {
+    /// Stable id; empty on create to derive it from the name
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub amount: f64,
+    #[serde(default)]
+    pub currency: String,
+    /// "weekly" | "monthly" | "quarterly" | "yearly"
+    #[serde(default)]
+    pub cycle: String,
+    #[serde(default)]
+    pub start_date: String,
+    /// As stored; may be in the past when the note was not touched for a while
+    #[serde(default)]
+    pub renewal_date: String,
+    #[serde(default)]
+    pub payment_method: String,
+    #[serde(default)]
+    pub app_type: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Days before renewal to notify (0 = only on the day)
+    #[serde(default)]
+    pub remind_days: Option<i64>,
+    /// Markdown body
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub path: String,
+
+    /// Next renewal on or after today, YYYY-MM-DD
+    #[serde(default)]
+    pub next_renewal: Option<String>,
+    #[serde(default)]
+    pub days_until: Option<i64>,
+    /// `amount` normalized to a month
+    #[serde(default)]
+    pub monthly_amount: f64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SubscriptionBurn {
+    /// Per currency, enabled subscriptions only
+    pub monthly: BTreeMap<String, f64>,
+    pub yearly: BTreeMap<String, f64>,
+    pub active: usize,
+    /// Monthly burn per currency of subscriptions started before `since`,
+    /// so the difference to `monthly` is the creep over the period
+    pub monthly_before: BTreeMap<String, f64>,
+    /// Started on or after `since`
+    pub added: Vec<Subscription>,
+    /// Renewing within 30 days, soonest first
+    pub renewing: Vec<Subscription>,
+}
+
+// Only one reminder loop, bound to the open vault
+static REMINDERS: Periodic = Periodic::new();
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// All subscriptions, soonest renewal first
+#[tauri::command]
+pub fn list_subscriptions(vault_path: String) -> Vec<Subscription> {
+    load_subscriptions(Path::new(&vault_path), Local::now().date_naive())
+}
+
+#[tauri::command]
+pub fn save_subscription(vault_path: String, subscription: Subscription) -> Result<Subscription, String> {
+    let vault = Path::new(&vault_path);
+    let mut sub = subscription;
+    sub.name = sub.name.trim().to_string();
+    if sub.name.is_empty() {
+        return Err(tr!("Name is required"));
+    }
+    let path = if sub.id.is_empty() {
+        sub.id = records::unique_slug(vault, SUBS_DIR, &slugify(&sub.name));
+        records::path(vault, SUBS_DIR, &sub.id)?
+    } else {
+        check_slug(&sub.id)?;
+        match find_path(vault, &sub.id) {
+            Some(existing) => existing,
+            None => records::path(vault, SUBS_DIR, &sub.id)?,
+        }
+    };
+    write_subscription(&path, &sub)?;
+    read_subscription(&path, Local::now().date_naive()).ok_or_else(|| tr!("Failed to read: {}", path.display()))
+}
+
+#[tauri::command]
+pub fn delete_subscription(vault_path: String, id: String) -> Result<(), String> {
+    let path = find_path(Path::new(&vault_path), &id).ok_or_else(|| tr!("Subscription not found: {}", id))?;
+    fs::remove_file(&path).map_err(|e| e.to_string())
+}
+
+/// Monthly/yearly burn and what changed since `since` (YYYY-MM-DD, default
+/// 30 days ago) — the numbers a weekly or monthly review needs
+#[tauri::command]
+pub fn get_subscription_burn(vault_path: String, since: Option<String>) -> SubscriptionBurn {
+    let today = Local::now().date_naive();
+    let since = since
+        .and_then(|s| NaiveDate::parse_from_str(&s, "%Y-%m-%d").ok())
+        .unwrap_or(today - Days::days(30));
+    burn(load_subscriptions(Path::new(&vault_path), today), since)
+}
+
+/// Check hourly and notify `remindDays` (default 3) days before and on the
+/// day of each renewal. Calling again restarts it.
+#[tauri::command]
+pub fn start_renewal_reminders(app: AppHandle, vault_path: String) {
+    let vault = PathBuf::from(vault_path);
+    REMINDERS.start(CHECK_EVERY, move || send_due_reminders(&app, &vault));
+}
+
+#[tauri::command]
+pub fn stop_renewal_reminders() {
+    REMINDERS.stop();
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub(crate) fn load_subscriptions(vault: &Path, today: NaiveDate) -> Vec<Subscription> {
+    let Ok(entries) = fs::read_dir(vault.join(SUBS_DIR)) else { return vec![] };
+    let mut subs: Vec<Subscription> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|p| read_subscription(&p, today))
+        .collect();
+    subs.sort_by(|a, b| a.days_until.unwrap_or(i64::MAX).cmp(&b.days_until.unwrap_or(i64::MAX)).then(a.name.cmp(&b.name)));
+    subs
+}
+
+fn find_path(vault: &Path, id: &str) -> Option<PathBuf> {
+    let today = Local::now().date_naive();
+    load_subscriptions(vault, today).into_iter().find(|s| s.id == id).map(|s| PathBuf::from(s.path))
+}
+
+/// The view writes every value as a quoted string, so numbers and booleans
+/// are accepted in either form
+fn read_subscription(path: &Path, today: NaiveDate) -> Option<Subscription> {
+    let raw = fs::read_to_string(path).ok()?;
+    let (Some(yaml), body) = split_frontmatter(&raw) else { return None };
+    let fm: serde_yaml::Mapping = serde_yaml::from_str(yaml).ok()?;
+    let text = |key: &str| match fm.get(key) {
+        Some(serde_yaml::Value::String(s)) => s.trim().to_string(),
+        Some(serde_yaml::Value::Number(n)) => n.to_string(),
+        Some(serde_yaml::Value::Bool(b)) => b.to_string(),
+        _ => String::new(),
+    };
+
+    let name = text("name");
+    if name.is_empty() {
+        return None;
+    }
+    let mut sub = Subscription {
+        id: Some(text("id")).filter(|s| !s.is_empty()).unwrap_or_else(|| {
+            path.file_stem().unwrap_or_default().to_string_lossy().to_string()
+        }),
+        name,
+        amount: text("amount").parse().unwrap_or(0.0),
+        currency: Some(text("currency")).filter(|s| !s.is_empty()).unwrap_or_else(|| "CNY".to_string()),
+        cycle: Some(text("cycle")).filter(|s| !s.is_empty()).unwrap_or_else(|| "monthly".to_string()),
+        start_date: text("startDate"),
+        renewal_date: text("renewalDate"),
+        payment_method: text("paymentMethod"),
+        app_type: text("appType"),
+        tags: text("tags").split(',').map(str::trim).filter(|t| !t.is_empty()).map(str::to_string).collect(),
+        enabled: text("enabled") != "false",
+        remind_days: text("remindDays").parse().ok(),
+        notes: body.to_string(),
+        path: path.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+    sub.monthly_amount = monthly_amount(sub.amount, &sub.cycle);
+    if let Some(next) = next_renewal(&sub, today) {
+        sub.next_renewal = Some(next.format("%Y-%m-%d").to_string());
+        sub.days_until = Some((next - today).num_days());
+    }
+    Some(sub)
+}
+
+/// Rewrites the known keys in the view's format, keeping any others
+fn write_subscription(path: &Path, sub: &Subscription) -> Result<(), String> {
+    let mut fm = serde_json::Map::new();
+    if let Ok(raw) = fs::read_to_string(path) {
+        if let (Some(yaml), _) = split_frontmatter(&raw) {
+            if let Ok(serde_json::Value::Object(existing)) = serde_yaml::from_str::<serde_json::Value>(yaml) {
+                fm = existing;
+            }
+        }
+    }
+    let mut set = |key: &str, value: String| {
+        fm.insert(key.to_string(), serde_json::Value::String(value));
+    };
+    set("id", sub.id.clone());
+    set("name", sub.name.clone());
+    set("amount", sub.amount.to_string());
+    set("currency", sub.currency.clone());
+    set("cycle", sub.cycle.clone());
+    set("startDate", sub.start_date.clone());
+    set("renewalDate", sub.renewal_date.clone());
+    set("paymentMethod", sub.payment_method.clone());
+    set("appType", sub.app_type.clone());
+    set("tags", sub.tags.join(", "));
+    set("enabled", sub.enabled.to_string());
+    match sub.remind_days {
+        Some(days) => set("remindDays", days.to_string()),
+        None => {
+            fm.remove("remindDays");
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let body = match sub.notes.trim_start() {
+        "" => format!("# {}\n\n## 备注\n", sub.name),
+        notes => notes.to_string(),
+    };
+    let yaml = json_to_yaml(&serde_json::Value::Object(fm));
+    durable::write(path, format!("---\n{yaml}---\n\n{body}")).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+fn monthly_amount(amount: f64, cycle: &str) -> f64 {
+    match cycle {
+        "weekly" => amount * 52.0 / 12.0,
+        "quarterly" => amount / 3.0,
+        "yearly" => amount / 12.0,
+        _ => amount,
+    }
+}
+
+/// Roll the stored renewal date (or the start date) forward by whole cycles
+/// from that anchor, so a renewal on the 31st stays at month end
+fn next_renewal(sub: &Subscription, today: NaiveDate) -> Option<NaiveDate> {
+    let anchor = [&sub.renewal_date, &sub.start_date]
+        .iter()
+        .find_map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())?;
+    let nth = |n: u32| -> Option<NaiveDate> {
+        match sub.cycle.as_str() {
+            "weekly" => anchor.checked_add_signed(Days::weeks(i64::from(n))),
+            "quarterly" => anchor.checked_add_months(Months::new(3 * n)),
+            "yearly" => anchor.checked_add_months(Months::new(12 * n)),
+            _ => anchor.checked_add_months(Months::new(n)),
+        }
+    };
+    (0..10_000).map_while(nth).find(|d| *d >= today)
+}
+
+fn burn(subs: Vec<Subscription>, since: NaiveDate) -> SubscriptionBurn {
+    let mut out = SubscriptionBurn::default();
+    let since_str = since.format("%Y-%m-%d").to_string();
+    for sub in subs.into_iter().filter(|s| s.enabled) {
+        out.active += 1;
+        *out.monthly.entry(sub.currency.clone()).or_default() += sub.monthly_amount;
+        let is_new = !sub.start_date.is_empty() && sub.start_date >= since_str;
+        if !is_new {
+            *out.monthly_before.entry(sub.currency.clone()).or_default() += sub.monthly_amount;
+        }
+        if sub.days_until.is_some_and(|d| d <= 30) {
+            out.renewing.push(sub.clone());
+        }
+        if is_new {
+            out.added.push(sub);
+        }
+    }
+    for map in [&mut out.monthly, &mut out.monthly_before] {
+        map.values_mut().for_each(|v| *v = (*v * 100.0).round() / 100.0);
+    }
+    out.yearly = out.monthly.iter().map(|(c, v)| (c.clone(), (v * 1200.0).round() / 100.0)).collect();
+    out
+}
+
+fn send_due_reminders(app: &AppHandle, vault: &Path) {
+    // Held back, not dropped: unsent reminders go out on the first check after
+    // the focus session ends
+    if notifications_muted() {
+        return;
+    }
+    let today = Local::now().date_naive();
+    let state_path = vault.join(REMINDER_STATE);
+    let mut sent: BTreeSet<String> = fs::read_to_string(&state_path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default();
+    // Keys start with the renewal date, so past ones can be dropped
+    let today_str = today.format("%Y-%m-%d").to_string();
+    sent.retain(|k| k.as_str() >= today_str.as_str());
+
+    for sub in load_subscriptions(vault, today).into_iter().filter(|s| s.enabled) {
+        let (Some(date), Some(days)) = (&sub.next_renewal, sub.days_until) else { continue };
+        if days != 0 && days != sub.remind_days.unwrap_or(DEFAULT_REMIND_DAYS) {
+            continue;
+        }
+        let key = format!("{date}|{days}|{}", sub.id);
+        if sent.contains(&key) {
+            continue;
+        }
+        let title = tr!("Renewal: {}", sub.name);
+        let when = match days {
+            0 => tr!("Today"),
+            1 => tr!("Tomorrow"),
+            n => tr!("In {} days ({})", n, date),
+        };
+        let body = format!("{when} · {} {}", sub.amount, sub.currency);
+        match app.notification().builder().title(&title).body(&body).show() {
+            Ok(()) => {
+                sent.insert(key);
+            }
+            Err(e) => println!("[WARN] failed to show reminder: {e}"),
+        }
+    }
+
+    if let Ok(json) = serde_json::to_string_pretty(&sent) {
+        if let Err(e) = durable::write(&state_path, json) {
+            println!("[WARN] failed to write {}: {e}", state_path.display());
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn sub(cycle: &str, renewal: &str) -> Subscription {
+        Subscription { name: "x".into(), cycle: cycle.into(), renewal_date: renewal.into(), ..Default::default() }
+    }
+
+    #[test]
+    fn test_save_keeps_same_named_subscriptions_apart() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().to_string_lossy().to_string();
+        let new = |amount: f64| Subscription { name: "Netflix".into(), amount, cycle: "monthly".into(), ..Default::default() };
+        let first = save_subscription(vault.clone(), new(10.0)).unwrap();
+        let second = save_subscription(vault.clone(), new(20.0)).unwrap();
+        assert_eq!((first.id.as_str(), second.id.as_str()), ("netflix", "netflix-2"));
+        assert_eq!(list_subscriptions(vault.clone()).len(), 2);
+
+        let escape = Subscription { id: "../x".into(), ..new(1.0) };
+        assert_eq!(save_subscription(vault, escape).unwrap_err(), tr!("Invalid slug: {}", "../x"));
+        assert!(!dir.path().join("x.md").exists());
+    }
+
+    #[test]
+    fn test_next_renewal_rolls_forward_from_anchor() {
+        let today = ymd(2025, 3, 15);
+        assert_eq!(next_renewal(&sub("monthly", "2025-01-31"), today), Some(ymd(2025, 3, 31)));
+        assert_eq!(next_renewal(&sub("yearly", "2023-03-15"), today), Some(ymd(2025, 3, 15)));
+        assert_eq!(next_renewal(&sub("weekly", "2025-03-01"), today), Some(ymd(2025, 3, 15)));
+        assert_eq!(next_renewal(&sub("monthly", ""), today), None);
+    }
+
+    #[test]
+    fn test_read_and_write_keep_view_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("subscriptions/netflix.md");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "---\nid: \"netflix\"\nname: \"Netflix\"\namount: \"120\"\ncycle: \"yearly\"\nrenewalDate: \"2025-06-01\"\ntags: \"video, fun\"\nenabled: \"true\"\nowner: \"me\"\n---\n\n# Netflix\n").unwrap();
+
+        let today = ymd(2025, 5, 30);
+        let mut s = read_subscription(&path, today).unwrap();
+        assert_eq!(s.monthly_amount, 10.0);
+        assert_eq!(s.tags, vec!["video", "fun"]);
+        assert_eq!(s.days_until, Some(2));
+
+        s.enabled = false;
+        write_subscription(&path, &s).unwrap();
+        let raw = fs::read_to_string(&path).unwrap();
+        assert!(raw.contains("enabled: \"false\""));
+        assert!(raw.contains("owner: \"me\""));
+        assert!(!read_subscription(&path, today).unwrap().enabled);
+    }
+
+    #[test]
+    fn test_burn_separates_new_subscriptions() {
+        let mut a = sub("monthly", "2025-01-10");
+        a.amount = 30.0;
+        a.monthly_amount = 30.0;
+        a.currency = "CNY".into();
+        a.enabled = true;
+        a.start_date = "2024-01-10".into();
+        let mut b = a.clone();
+        b.start_date = "2025-05-20".into();
+        let mut off = a.clone();
+        off.enabled = false;
+
+        let r = burn(vec![a, b, off], ymd(2025, 5, 1));
+        assert_eq!(r.active, 2);
+        assert_eq!(r.monthly["CNY"], 60.0);
+        assert_eq!(r.monthly_before["CNY"], 30.0);
+        assert_eq!(r.yearly["CNY"], 720.0);
+        assert_eq!(r.added.len(), 1);
+    }
+}
@@ -0,0 +1,250 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+
+use crate::services::fuzzy;
+use crate::services::notes::{split_frontmatter, vault_rel};
+
+const PROJECTS_DIR: &str = "projects";
+const MENU_FILE: &str = ".lifeos/menu.yaml";
+/// After this the index is rebuilt in the background; the old one keeps
+/// answering meanwhile
+const MAX_AGE: Duration = Duration::from_secs(60);
+const DEFAULT_LIMIT: usize = 50;
+
+static INDEX: Lazy<Mutex<Option<Index>>> = Lazy::new(|| Mutex::new(None));
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    Note,
+    Heading,
+    Project,
+    /// A menu view to switch to
+    Command,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SwitcherEntry {
+    pub kind: EntryKind,
+    pub title: String,
+    /// Vault-relative note path; the view id for commands
+    pub target: String,
+    /// 1-based, for headings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// Note title of a heading, folder of a note
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SwitcherResult {
+    #[serde(flatten)]
+    pub entry: SwitcherEntry,
+    pub score: i64,
+    /// Char positions of the title that matched, for highlighting
+    pub matched: Vec<usize>,
+}
+
+struct Index {
+    vault: PathBuf,
+    entries: Vec<SwitcherEntry>,
+    built: Instant,
+    rebuilding: bool,
+}
+
+#[derive(Deserialize)]
+struct MenuConfig {
+    #[serde(default)]
+    plugins: Vec<MenuPlugin>,
+}
+
+#[derive(Deserialize)]
+struct MenuPlugin {
+    id: String,
+    name: String,
+    #[serde(default = "enabled_by_default")]
+    enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Ranked matches for the Cmd+P switcher. `scope` is note, heading, project
+/// or command; everything when omitted. Served from memory: the vault is
+/// walked once, then again in the background once the index is a minute old.
+#[tauri::command]
+pub fn fuzzy_find(vault_path: String, query: String, scope: Option<String>, limit: Option<usize>) -> Result<Vec<SwitcherResult>, String> {
+    let scope = match scope.as_deref().map(str::trim).unwrap_or_default() {
+        "" | "all" => None,
+        "note" => Some(EntryKind::Note),
+        "heading" => Some(EntryKind::Heading),
+        "project" => Some(EntryKind::Project),
+        "command" => Some(EntryKind::Command),
+        other => return Err(tr!("Unknown scope: {}", other)),
+    };
+    let vault = PathBuf::from(&vault_path);
+    let mut guard = INDEX.lock().unwrap();
+    match guard.as_mut() {
+        Some(index) if index.vault == vault => {
+            if index.built.elapsed() > MAX_AGE && !index.rebuilding {
+                index.rebuilding = true;
+                std::thread::spawn(move || {
+                    let entries = build(&vault);
+                    if let Some(index) = INDEX.lock().unwrap().as_mut().filter(|i| i.vault == vault) {
+                        *index = Index { vault, entries, built: Instant::now(), rebuilding: false };
+                    }
+                });
+            }
+        }
+        _ => *guard = Some(Index { entries: build(&vault), vault, built: Instant::now(), rebuilding: false }),
+    }
+    let entries = &guard.as_ref().expect("index was just built").entries;
+    Ok(search(entries, &query, scope, limit.unwrap_or(DEFAULT_LIMIT)))
+}
+
+/// Drop the index so the next search sees changes right away
+#[tauri::command]
+pub fn refresh_fuzzy_index() {
+    *INDEX.lock().unwrap() = None;
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn search(entries: &[SwitcherEntry], query: &str, scope: Option<EntryKind>, limit: usize) -> Vec<SwitcherResult> {
+    let mut results: Vec<SwitcherResult> = entries
+        .iter()
+        .filter(|e| scope.is_none_or(|s| e.kind == s))
+        .filter_map(|entry| {
+            let (score, matched) = fuzzy::score(query, &entry.title)?;
+            // Headings crowd out the notes they belong to otherwise
+            let score = if entry.kind == EntryKind::Heading { score - 10 } else { score };
+            Some(SwitcherResult { entry: entry.clone(), score, matched })
+        })
+        .collect();
+    results.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.entry.title.cmp(&b.entry.title)));
+    results.truncate(limit);
+    results
+}
+
+fn build(vault: &Path) -> Vec<SwitcherEntry> {
+    let mut entries = commands(vault);
+    let notes = WalkDir::new(vault)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|x| x == "md"));
+    for entry in notes {
+        let Ok(raw) = fs::read_to_string(entry.path()) else { continue };
+        let rel = vault_rel(vault, entry.path());
+        let stem = entry.path().file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let (frontmatter, _) = split_frontmatter(&raw);
+        let title = frontmatter
+            .and_then(|f| serde_yaml::from_str::<serde_yaml::Value>(f).ok())
+            .and_then(|doc| doc.get("title").and_then(|t| t.as_str()).map(str::to_string))
+            .unwrap_or_else(|| stem.clone());
+        // projects/<slug>.md, possibly in a status folder
+        let is_project = rel.starts_with(&format!("{PROJECTS_DIR}/")) && entry.path().parent().is_some_and(|dir| !is_project_folder(dir));
+        let folder = rel.rsplit_once('/').map(|(dir, _)| dir.to_string());
+
+        let mut in_code = false;
+        for (i, line) in raw.lines().enumerate() {
+            if line.trim_start().starts_with("```") {
+                in_code = !in_code;
+            }
+            let hashes = line.chars().take_while(|c| *c == '#').count();
+            if in_code || !(1..=6).contains(&hashes) || !line[hashes..].starts_with(' ') {
+                continue;
+            }
+            let heading = line[hashes..].trim();
+            if !heading.is_empty() && heading != title {
+                entries.push(SwitcherEntry { kind: EntryKind::Heading, title: heading.to_string(), target: rel.clone(), line: Some(i + 1), detail: Some(title.clone()) });
+            }
+        }
+        let kind = if is_project { EntryKind::Project } else { EntryKind::Note };
+        entries.push(SwitcherEntry { kind, title, target: rel, line: None, detail: folder });
+    }
+    entries
+}
+
+/// projects/<slug>/ next to projects/<slug>.md holds that project's notes
+fn is_project_folder(dir: &Path) -> bool {
+    let name = dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    dir.with_file_name(format!("{name}.md")).is_file()
+}
+
+/// Enabled views from the menu config
+fn commands(vault: &Path) -> Vec<SwitcherEntry> {
+    let menu: Option<MenuConfig> = fs::read_to_string(vault.join(MENU_FILE)).ok().and_then(|raw| serde_yaml::from_str(&raw).ok());
+    menu.map(|m| m.plugins)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|p| p.enabled)
+        .map(|p| SwitcherEntry { kind: EntryKind::Command, title: p.name, target: p.id, line: None, detail: None })
+        .collect()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, rel: &str, text: &str) {
+        let path = dir.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    #[test]
+    fn test_index_and_search() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        write(v, ".lifeos/menu.yaml", "plugins:\n  - id: mail\n    name: 邮箱\n  - id: finance\n    name: 财务\n    enabled: false\n");
+        write(v, "projects/active/launch.md", "---\ntitle: Product Launch\n---\n# Product Launch\n## Checklist\n");
+        write(v, "projects/active/launch/retro.md", "# Retro\n");
+        write(v, "diary/2026/review.md", "# Weekly review\n```\n# not a heading\n```\n## Launch notes\n");
+
+        let entries = build(v);
+        let summary: Vec<(EntryKind, &str, &str)> = entries.iter().map(|e| (e.kind, e.title.as_str(), e.target.as_str())).collect();
+        assert_eq!(
+            summary,
+            [
+                (EntryKind::Command, "邮箱", "mail"),
+                (EntryKind::Heading, "Weekly review", "diary/2026/review.md"),
+                (EntryKind::Heading, "Launch notes", "diary/2026/review.md"),
+                (EntryKind::Note, "review", "diary/2026/review.md"),
+                (EntryKind::Heading, "Retro", "projects/active/launch/retro.md"),
+                (EntryKind::Note, "retro", "projects/active/launch/retro.md"),
+                (EntryKind::Heading, "Checklist", "projects/active/launch.md"),
+                (EntryKind::Project, "Product Launch", "projects/active/launch.md"),
+            ]
+        );
+        assert_eq!(entries[2].line, Some(5));
+
+        let titles = |results: Vec<SwitcherResult>| results.into_iter().map(|r| r.entry.title).collect::<Vec<_>>();
+        assert_eq!(titles(search(&entries, "launch", None, 10)), ["Product Launch", "Launch notes"]);
+        assert_eq!(titles(search(&entries, "launch", Some(EntryKind::Project), 10)), ["Product Launch"]);
+        assert_eq!(search(&entries, "", None, 3).len(), 3);
+    }
+}
@@ -0,0 +1,50 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::services::periodic::Periodic;
+use crate::services::sync::{self, SyncReport};
+
+static SYNC_LOOP: Periodic = Periodic::new();
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Sync now with the remote under `sync:` in connectors.yaml
+#[tauri::command]
+pub async fn sync_vault(vault_path: String) -> Result<SyncReport, String> {
+    tokio::task::spawn_blocking(move || sync::sync(Path::new(&vault_path)))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Report of the last finished sync, if any
+#[tauri::command]
+pub fn get_sync_status(vault_path: String) -> Option<SyncReport> {
+    sync::last_report(Path::new(&vault_path))
+}
+
+/// Sync every `sync.interval_minutes` while `sync.enabled` is on; settings
+/// are re-read each tick so edits apply without a restart
+#[tauri::command]
+pub fn start_sync_loop(vault_path: String) {
+    let vault = PathBuf::from(vault_path);
+    let mut last_run: Option<Instant> = None;
+    SYNC_LOOP.start(Duration::from_secs(1), move || {
+        let settings = sync::settings(&vault);
+        let every = Duration::from_secs(settings.interval_minutes.max(1) * 60);
+        if settings.enabled && last_run.is_none_or(|t| t.elapsed() >= every) {
+            last_run = Some(Instant::now());
+            match sync::sync(&vault) {
+                Ok(report) if !report.errors.is_empty() => println!("[WARN] sync finished with {} errors", report.errors.len()),
+                Ok(_) => {}
+                Err(e) => println!("[WARN] sync failed: {e}"),
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn stop_sync_loop() {
+    SYNC_LOOP.stop();
+}
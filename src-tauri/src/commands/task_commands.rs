@@ -0,0 +1,286 @@
+use chrono::{Days, Local, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::Path;
+
+use super::agenda_commands::calendar_events;
+use crate::services::dependencies::{self, DependencyReport};
+use crate::services::mood::energy_score;
+use crate::services::notes::split_frontmatter;
+use crate::services::tasks::{self, DayTask};
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NextAction {
+    /// Day file the task is in (YYYY-MM-DD)
+    pub date: String,
+    pub path: String,
+    #[serde(flatten)]
+    pub task: DayTask,
+    pub overdue: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NextActions {
+    /// Energy filtered on: the one asked for, else the day note's `energy`
+    pub energy: Option<String>,
+    /// Every context on an open task, for filter chips
+    pub contexts: Vec<String>,
+    /// Overdue and soonest due first, then timed, then most recently planned
+    pub actions: Vec<NextAction>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TaskSuggestion {
+    #[serde(flatten)]
+    pub action: NextAction,
+    /// Minutes, from a `#30m` / `#1h` / `#1h30m` tag
+    pub effort: Option<u32>,
+    pub score: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TaskSuggestions {
+    /// The day note's `energy`
+    pub energy: Option<String>,
+    /// Minutes until `next_event`; None when nothing else is on today
+    pub available_minutes: Option<u32>,
+    pub next_event: Option<String>,
+    /// Best first
+    pub suggestions: Vec<TaskSuggestion>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Open, unblocked tasks from day files up to `date` (default today) that
+/// fit the moment: tagged `@context` when one is given, needing no more
+/// `⚡energy` than is available, and, with `due_within`, due within that many
+/// days.
+#[tauri::command]
+pub fn get_next_actions(
+    vault_path: String,
+    context: Option<String>,
+    energy: Option<String>,
+    due_within: Option<u64>,
+    date: Option<String>,
+) -> Result<NextActions, String> {
+    let today = match date {
+        Some(d) => NaiveDate::parse_from_str(&d, "%Y-%m-%d").map_err(|_| tr!("Invalid date (expected YYYY-MM-DD): {}", d))?,
+        None => Local::now().date_naive(),
+    };
+    let today_str = today.format("%Y-%m-%d").to_string();
+    let open = tasks::open_tasks(&vault_path, &today_str);
+
+    let energy = energy.filter(|e| !e.trim().is_empty()).or_else(|| day_energy(Path::new(&vault_path), &today_str));
+    let available = energy.as_deref().and_then(energy_score);
+    let context = context.map(|c| c.trim().trim_start_matches('@').to_lowercase()).filter(|c| !c.is_empty());
+    let horizon = due_within.and_then(|n| today.checked_add_days(Days::new(n))).map(|d| d.format("%Y-%m-%d").to_string());
+    let contexts: BTreeSet<String> = open.iter().flat_map(|(_, t)| t.contexts.iter().cloned()).collect();
+    let blocked: HashSet<(String, usize)> =
+        dependencies::analyze(Path::new(&vault_path)).blocked.into_iter().filter_map(|b| Some((b.path, b.line?))).collect();
+
+    let mut actions: Vec<NextAction> = open
+        .into_iter()
+        .filter(|(_, t)| context.as_ref().is_none_or(|c| t.contexts.iter().any(|tc| tc.to_lowercase() == *c)))
+        .filter(|(_, t)| {
+            let needed = t.energy.as_deref().and_then(energy_score);
+            !matches!((needed, available), (Some(n), Some(a)) if n > a)
+        })
+        .filter(|(_, t)| horizon.as_ref().is_none_or(|h| t.due.as_ref().is_some_and(|d| d <= h)))
+        .map(|(date, task)| NextAction {
+            path: Path::new(&vault_path).join("daily/tasks").join(format!("{date}.md")).to_string_lossy().to_string(),
+            overdue: task.due.as_ref().is_some_and(|d| *d < today_str),
+            date,
+            task,
+        })
+        .filter(|a| !blocked.contains(&(a.path.clone(), a.task.line)))
+        .collect();
+    actions.sort_by_key(|a| (a.task.due.is_none(), a.task.due.clone(), a.task.time.is_none(), a.task.time.clone(), Reverse(a.date.clone())));
+    Ok(NextActions { energy, contexts: contexts.into_iter().collect(), actions })
+}
+
+/// A short list of next actions for `now` (`YYYY-MM-DDTHH:MM`, default the
+/// current time): ones that suit the day note's `energy` best and, going by
+/// their effort tags, fit in the time left before the next calendar event.
+/// Urgent tasks rank first either way.
+#[tauri::command]
+pub fn suggest_tasks(vault_path: String, now: Option<String>, limit: Option<usize>) -> Result<TaskSuggestions, String> {
+    let now = match &now {
+        Some(n) => NaiveDateTime::parse_from_str(n.trim(), "%Y-%m-%dT%H:%M").map_err(|_| tr!("Invalid date/time: {}", n))?,
+        None => Local::now().naive_local(),
+    };
+    let today = now.date().format("%Y-%m-%d").to_string();
+    let next = get_next_actions(vault_path.clone(), None, None, None, Some(today.clone()))?;
+
+    let next_event = calendar_events(Path::new(&vault_path), now.date())
+        .into_iter()
+        .filter_map(|e| Some((NaiveTime::parse_from_str(e.time.as_deref()?, "%H:%M").ok()?, e.title)))
+        .filter(|(at, _)| *at > now.time())
+        .min();
+    let available = next_event.as_ref().map(|(at, _)| minutes_of(*at) - minutes_of(now.time()));
+    let energy = next.energy.as_deref().and_then(energy_score);
+
+    let mut suggestions: Vec<TaskSuggestion> = next
+        .actions
+        .into_iter()
+        .filter_map(|action| {
+            let effort = effort_minutes(&action.task.tags);
+            if let (Some(effort), Some(available)) = (effort, available) {
+                if effort > available {
+                    return None;
+                }
+            }
+            let score = suggestion_score(&action, effort, available, energy, &today);
+            Some(TaskSuggestion { action, effort, score })
+        })
+        .collect();
+    suggestions.sort_by_key(|s| Reverse(s.score));
+    suggestions.truncate(limit.unwrap_or(5));
+    Ok(TaskSuggestions { energy: next.energy, available_minutes: available, next_event: next_event.map(|(_, title)| title), suggestions })
+}
+
+/// Unfinished tasks and projects waiting on incomplete `depends_on` links,
+/// plus references that are missing or circular
+#[tauri::command]
+pub fn get_blocked_tasks(vault_path: String) -> Result<DependencyReport, String> {
+    Ok(dependencies::analyze(Path::new(&vault_path)))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn suggestion_score(action: &NextAction, effort: Option<u32>, available: Option<u32>, energy: Option<f64>, today: &str) -> i64 {
+    let mut score = 0.0;
+    match action.task.due.as_deref() {
+        _ if action.overdue => score += 30.0,
+        Some(due) if due == today => score += 20.0,
+        Some(_) => score += 5.0,
+        None => {}
+    }
+    // Hard tasks while energy is high, easy ones when it's low
+    if let (Some(needed), Some(energy)) = (action.task.energy.as_deref().and_then(energy_score), energy) {
+        score += 10.0 - 2.5 * (needed - energy).abs();
+    }
+    // A task that uses the gap well beats one that leaves most of it idle;
+    // unknown effort is a gamble when the gap is short
+    match (effort, available) {
+        (Some(effort), Some(available)) if available > 0 => score += 10.0 * effort as f64 / available as f64,
+        (None, Some(available)) if available < 30 => score -= 5.0,
+        _ => {}
+    }
+    score.round() as i64
+}
+
+/// `#30m`, `#45min`, `#2h`, `#1h30m`
+fn effort_minutes(tags: &[String]) -> Option<u32> {
+    tags.iter().find_map(|tag| {
+        let tag = tag.to_lowercase();
+        let (hours, rest) = match tag.split_once('h') {
+            Some((h, rest)) => (h.parse::<u32>().ok()?, rest),
+            None => (0, tag.as_str()),
+        };
+        let mins = match rest {
+            "" => 0,
+            _ => rest.strip_suffix("min").or_else(|| rest.strip_suffix('m'))?.parse::<u32>().ok()?,
+        };
+        Some(hours * 60 + mins).filter(|m| *m > 0)
+    })
+}
+
+fn minutes_of(time: NaiveTime) -> u32 {
+    time.hour() * 60 + time.minute()
+}
+
+/// `energy` from the day note's frontmatter
+fn day_energy(vault: &Path, date: &str) -> Option<String> {
+    let raw = fs::read_to_string(vault.join("daily/tasks").join(format!("{date}.md"))).ok()?;
+    let doc: serde_yaml::Value = serde_yaml::from_str(split_frontmatter(&raw).0?).ok()?;
+    match doc.get("energy")? {
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(vault: &Path, rel: &str, text: &str) {
+        let path = vault.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    #[test]
+    fn test_next_actions() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        write(v, "daily/tasks/2025-03-02.md", "- [ ] 报税 @computer ⚡high 📅2025-03-03\n- [x] 买菜 @errands\n");
+        write(v, "daily/tasks/2025-03-04.md", "---\ndate: 2025-03-04\nenergy: low\n---\n- [ ] 打电话给房东 @phone ⚡low\n- [ ] 整理照片 @computer\n- [ ] 写方案 @computer ⚡high 📅2025-03-10\n");
+        write(v, "daily/tasks/2025-03-05.md", "- [ ] 明天的事 @computer\n");
+        let v = v.to_string_lossy().to_string();
+        let day = Some("2025-03-04".to_string());
+
+        // Low energy from the day note hides the ⚡high tasks
+        let next = get_next_actions(v.clone(), None, None, None, day.clone()).unwrap();
+        assert_eq!(next.energy.as_deref(), Some("low"));
+        assert_eq!(next.contexts, vec!["computer", "phone"]);
+        let texts: Vec<&str> = next.actions.iter().map(|a| a.task.text.as_str()).collect();
+        assert_eq!(texts, vec!["打电话给房东", "整理照片"]);
+
+        let next = get_next_actions(v.clone(), Some("@Computer".into()), Some("high".into()), None, day.clone()).unwrap();
+        let texts: Vec<&str> = next.actions.iter().map(|a| a.task.text.as_str()).collect();
+        assert_eq!(texts, vec!["报税", "写方案", "整理照片"]);
+        assert!(next.actions[0].overdue && !next.actions[1].overdue);
+
+        let next = get_next_actions(v.clone(), None, Some("high".into()), Some(3), day.clone()).unwrap();
+        assert_eq!(next.actions.len(), 1);
+
+        // Waiting on an open task keeps it out until that one is checked
+        let file = vault.path().join("daily/tasks/2025-03-04.md");
+        let raw = fs::read_to_string(&file).unwrap();
+        fs::write(&file, raw.replace("@phone", "@phone 🆔call").replace("整理照片", "整理照片 ⛔call")).unwrap();
+        let next = get_next_actions(v.clone(), None, None, None, day.clone()).unwrap();
+        assert_eq!(next.actions.len(), 1);
+        assert_eq!(get_blocked_tasks(v.clone()).unwrap().blocked[0].name, "整理照片");
+        fs::write(&file, fs::read_to_string(&file).unwrap().replace("- [ ] 打电话", "- [x] 打电话")).unwrap();
+        assert_eq!(get_next_actions(v, None, None, None, day).unwrap().actions[0].task.text, "整理照片");
+    }
+
+    #[test]
+    fn test_suggest_tasks() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        write(v, "daily/tasks/2026-03-10.md", "---\nenergy: high\n---\n- [ ] 回邮件 ⚡low #15m\n- [ ] 写方案 ⚡high #1h30m\n- [ ] 改简历 ⚡high #45min\n- [ ] 报销 📅2026-03-10\n");
+        write(v, "connectors/calendar/work.ics", "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART:20260310T080000\r\nSUMMARY:早会\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nDTSTART:20260310T100000\r\nSUMMARY:站会\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n");
+        let v = v.to_string_lossy().to_string();
+
+        // An hour until 站会: the 90-minute task doesn't fit, and high energy favors the hard one
+        let picks = suggest_tasks(v.clone(), Some("2026-03-10T09:00".into()), None).unwrap();
+        assert_eq!((picks.available_minutes, picks.next_event.as_deref()), (Some(60), Some("站会")));
+        let texts: Vec<&str> = picks.suggestions.iter().map(|s| s.action.task.text.as_str()).collect();
+        assert_eq!(texts, vec!["报销", "改简历", "回邮件"]);
+        assert_eq!(picks.suggestions[1].effort, Some(45));
+
+        let picks = suggest_tasks(v.clone(), Some("2026-03-10T11:00".into()), Some(2)).unwrap();
+        assert_eq!(picks.available_minutes, None);
+        assert_eq!(picks.suggestions.iter().map(|s| s.action.task.text.as_str()).collect::<Vec<_>>(), vec!["报销", "写方案"]);
+        assert!(suggest_tasks(v, Some("tomorrow".into()), None).is_err());
+
+        assert_eq!(effort_minutes(&["health".into(), "2h".into()]), Some(120));
+        assert_eq!(effort_minutes(&["1h30".into()]), None);
+    }
+}
@@ -0,0 +1,30 @@
+use std::path::Path;
+
+use crate::services::task_export::{self, ExportedTask, Provider, PullReport};
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Send tasks (`YYYY-MM-DD:line` or 🆔 id) to Todoist or TickTick, per
+/// `todoist:` / `ticktick:` in connectors.yaml
+#[tauri::command]
+pub async fn push_tasks(vault_path: String, provider: Provider, task_refs: Vec<String>) -> Result<Vec<ExportedTask>, String> {
+    tokio::task::spawn_blocking(move || task_export::push(Path::new(&vault_path), provider, &task_refs))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Tick off pushed tasks that were completed in Todoist or TickTick
+#[tauri::command]
+pub async fn pull_task_status(vault_path: String) -> Result<PullReport, String> {
+    tokio::task::spawn_blocking(move || task_export::pull(Path::new(&vault_path)))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Tasks pushed so far, oldest first
+#[tauri::command]
+pub fn list_exported_tasks(vault_path: String) -> Vec<ExportedTask> {
+    task_export::list(Path::new(&vault_path))
+}
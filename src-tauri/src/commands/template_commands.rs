@@ -0,0 +1,296 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::services::automations::vault_file;
+use crate::services::notes::{self, split_frontmatter};
+use crate::services::templates::{self, Context, TEMPLATES_DIR};
+
+/// One folder per project template: `project.md` becomes the project note,
+/// every other `.md` a sub-note (checklists, retrospective, ...)
+const PROJECT_TEMPLATES_DIR: &str = "templates/projects";
+const PROJECT_NOTE: &str = "project.md";
+const PROJECTS_DIR: &str = "projects";
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TemplateInfo {
+    pub name: String,
+    pub path: String,
+    /// Labels of the `{{prompt:...}}` placeholders the caller must answer
+    pub prompts: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProjectTemplateInfo {
+    pub name: String,
+    /// Sub-notes besides project.md, relative to the template folder
+    pub notes: Vec<String>,
+    /// Prompt labels across all of the template's files
+    pub prompts: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScaffoldedProject {
+    /// projects/<slug>.md
+    pub path: String,
+    /// Sub-notes, under projects/<slug>/
+    pub notes: Vec<String>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// User templates under templates/, by name
+#[tauri::command]
+pub fn list_templates(vault_path: String) -> Vec<TemplateInfo> {
+    let Ok(entries) = fs::read_dir(Path::new(&vault_path).join(TEMPLATES_DIR)) else { return vec![] };
+    let mut all: Vec<TemplateInfo> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|p| {
+            let raw = fs::read_to_string(&p).ok()?;
+            Some(TemplateInfo {
+                name: p.file_stem()?.to_string_lossy().to_string(),
+                prompts: templates::prompts(&raw),
+                path: p.to_string_lossy().to_string(),
+            })
+        })
+        .collect();
+    all.sort_by(|a, b| a.name.cmp(&b.name));
+    all
+}
+
+/// Render template `name` into the vault-relative `target` (`.md` added when
+/// missing). `answers` fills the prompt placeholders by label. Returns the
+/// new note's path; an existing note is never overwritten.
+#[tauri::command]
+pub fn instantiate_template(vault_path: String, name: String, target: String, answers: Option<HashMap<String, String>>) -> Result<String, String> {
+    let vault = PathBuf::from(&vault_path);
+    let raw = fs::read_to_string(template_path(&vault, &name)?).map_err(|_| tr!("Template not found: {}", name))?;
+    let mut dest = vault_file(&vault, target.trim())?;
+    if dest.extension().is_none() {
+        dest.set_extension("md");
+    }
+    if dest.exists() {
+        return Err(tr!("Note already exists: {}", dest.display()));
+    }
+
+    let mut ctx = Context::new(Local::now().naive_local()).with_user_vars(&vault);
+    ctx.answers = answers.unwrap_or_default();
+    if let Some(stem) = dest.file_stem() {
+        ctx.vars.entry("title".to_string()).or_insert_with(|| stem.to_string_lossy().to_string());
+    }
+    let content = templates::render(&raw, &ctx)?;
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    fs::write(&dest, content).map_err(|e| tr!("write_file failed: {}", e))?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// Project templates under templates/projects/, by name
+#[tauri::command]
+pub fn list_project_templates(vault_path: String) -> Vec<ProjectTemplateInfo> {
+    let Ok(entries) = fs::read_dir(Path::new(&vault_path).join(PROJECT_TEMPLATES_DIR)) else { return vec![] };
+    let mut all: Vec<ProjectTemplateInfo> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.join(PROJECT_NOTE).is_file())
+        .filter_map(|dir| {
+            let files = template_files(&dir);
+            let mut prompts: Vec<String> = Vec::new();
+            for label in files.iter().flat_map(|(_, raw)| templates::prompts(raw)) {
+                if !prompts.contains(&label) {
+                    prompts.push(label);
+                }
+            }
+            Some(ProjectTemplateInfo {
+                name: dir.file_name()?.to_string_lossy().to_string(),
+                notes: files.into_iter().map(|(rel, _)| rel).filter(|rel| rel != PROJECT_NOTE).collect(),
+                prompts,
+            })
+        })
+        .collect();
+    all.sort_by(|a, b| a.name.cmp(&b.name));
+    all
+}
+
+/// Scaffold project `name` from templates/projects/<template>/: the project
+/// note at projects/<slug>.md with its frontmatter filled in (title, status,
+/// dates, progress), and the sub-notes under projects/<slug>/ linked back to
+/// it. `{{title}}`/`{{project}}` are the name, `{{slug}}` the file name.
+/// Nothing is written unless every file renders and none already exists.
+#[tauri::command]
+pub fn create_project_from_template(vault_path: String, template: String, name: String, answers: Option<HashMap<String, String>>) -> Result<ScaffoldedProject, String> {
+    let vault = PathBuf::from(&vault_path);
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(tr!("Project name is required"));
+    }
+    let dir = project_template_dir(&vault, &template)?;
+    if !dir.join(PROJECT_NOTE).is_file() {
+        return Err(tr!("Template not found: {}", template));
+    }
+    let now = Local::now().naive_local();
+    let today = now.format("%Y-%m-%d").to_string();
+    let slug = project_slug(name, now.and_utc().timestamp_millis());
+    let note_path = vault.join(PROJECTS_DIR).join(format!("{slug}.md"));
+
+    let mut ctx = Context::new(now).with_user_vars(&vault);
+    ctx.answers = answers.unwrap_or_default();
+    for key in ["title", "project"] {
+        ctx.vars.insert(key.to_string(), name.to_string());
+    }
+    ctx.vars.insert("slug".to_string(), slug.clone());
+
+    let mut out: Vec<(PathBuf, String)> = Vec::new();
+    for (rel, raw) in template_files(&dir) {
+        let rendered = templates::render(&raw, &ctx)?;
+        let (dest, defaults) = if rel == PROJECT_NOTE {
+            let defaults = [
+                ("title", Value::from(name)),
+                ("status", Value::from("backlog")),
+                ("priority", Value::from("medium")),
+                ("created", Value::from(today.as_str())),
+                ("updated", Value::from(today.as_str())),
+                ("progress", Value::from(0)),
+            ];
+            (note_path.clone(), defaults.to_vec())
+        } else {
+            let defaults = [("project", Value::from(format!("[[{slug}]]"))), ("created", Value::from(today.as_str()))];
+            (vault.join(PROJECTS_DIR).join(&slug).join(&rel), defaults.to_vec())
+        };
+        if dest.exists() {
+            return Err(tr!("Note already exists: {}", dest.display()));
+        }
+        out.push((dest, fill_missing(&rendered, &defaults)?));
+    }
+
+    let mut sub_notes = Vec::new();
+    for (dest, content) in out {
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+        }
+        fs::write(&dest, content).map_err(|e| tr!("write_file failed: {}", e))?;
+        if dest != note_path {
+            sub_notes.push(dest.to_string_lossy().to_string());
+        }
+    }
+    Ok(ScaffoldedProject { path: note_path.to_string_lossy().to_string(), notes: sub_notes })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// A bare name only: templates/<name>.md
+fn template_path(vault: &Path, name: &str) -> Result<PathBuf, String> {
+    let name = name.trim().trim_end_matches(".md");
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(tr!("Template not found: {}", name));
+    }
+    Ok(vault.join(TEMPLATES_DIR).join(format!("{name}.md")))
+}
+
+/// A bare name only: templates/projects/<name>/
+fn project_template_dir(vault: &Path, name: &str) -> Result<PathBuf, String> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(tr!("Template not found: {}", name));
+    }
+    Ok(vault.join(PROJECT_TEMPLATES_DIR).join(name))
+}
+
+/// (template-relative path, raw text) of every `.md` in the folder, sorted
+fn template_files(dir: &Path) -> Vec<(String, String)> {
+    WalkDir::new(dir)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|x| x == "md"))
+        .filter_map(|e| {
+            let rel = e.path().strip_prefix(dir).ok()?.to_string_lossy().replace('\\', "/");
+            Some((rel, fs::read_to_string(e.path()).ok()?))
+        })
+        .collect()
+}
+
+/// Same rule as the kanban view's new-project form, so both produce the
+/// same file names
+fn project_slug(name: &str, millis: i64) -> String {
+    let slug: String = name
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '_' || *c == '-')
+        .take(40)
+        .collect();
+    if slug.is_empty() { format!("proj-{millis}") } else { slug }
+}
+
+/// Add each default the template's own frontmatter does not set
+pub(crate) fn fill_missing(content: &str, defaults: &[(&str, Value)]) -> Result<String, String> {
+    let existing: serde_yaml::Mapping = split_frontmatter(content)
+        .0
+        .and_then(|fm| serde_yaml::from_str(fm).ok())
+        .unwrap_or_default();
+    let mut out = content.to_string();
+    for (key, value) in defaults.iter().filter(|(key, _)| !existing.contains_key(*key)) {
+        out = notes::set_field(&out, key, Some(value))?;
+    }
+    Ok(out)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_project_from_template() {
+        let vault = tempfile::tempdir().unwrap();
+        let dir = vault.path().join(PROJECT_TEMPLATES_DIR).join("发布");
+        fs::create_dir_all(dir.join("清单")).unwrap();
+        fs::write(dir.join(PROJECT_NOTE), "---\npriority: high\n---\n\n# {{title}}\n\n上线日期: {{prompt:上线日期}}\n").unwrap();
+        fs::write(dir.join("清单/发布清单.md"), "# {{project}} 发布清单\n\n- [ ] 回滚方案\n").unwrap();
+        fs::write(dir.join("复盘.md"), "---\ntitle: \"{{title}} 复盘\"\n---\n").unwrap();
+        let v = vault.path().to_string_lossy().to_string();
+
+        let listed = list_project_templates(v.clone());
+        assert_eq!(listed[0].notes, vec!["复盘.md", "清单/发布清单.md"]);
+        assert_eq!(listed[0].prompts, vec!["上线日期"]);
+
+        // A missing answer fails before anything is written
+        assert!(create_project_from_template(v.clone(), "发布".into(), "App v2".into(), None).is_err());
+        assert!(!vault.path().join(PROJECTS_DIR).exists());
+
+        let answers = HashMap::from([("上线日期".to_string(), "2025-06-01".to_string())]);
+        let made = create_project_from_template(v.clone(), "发布".into(), "App v2".into(), Some(answers.clone())).unwrap();
+        let note = fs::read_to_string(&made.path).unwrap();
+        assert!(made.path.ends_with("projects/App-v2.md"));
+        assert!(note.contains("priority: high") && !note.contains("priority: medium"));
+        assert!(note.contains("status: backlog") && note.contains("title: App v2") && note.contains("上线日期: 2025-06-01"));
+        assert_eq!(made.notes.len(), 2);
+        let checklist = fs::read_to_string(vault.path().join("projects/App-v2/清单/发布清单.md")).unwrap();
+        assert!(checklist.contains("project: '[[App-v2]]'") && checklist.contains("# App v2 发布清单"));
+
+        assert!(create_project_from_template(v, "发布".into(), "App v2".into(), Some(answers)).is_err());
+    }
+}
@@ -0,0 +1,392 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use crate::services::mail::EmailMessage;
+use crate::services::automations::vault_file;
+use crate::services::notes::split_frontmatter;
+use crate::services::tasks;
+
+/// Quick captures: any note dropped at the top level of this folder
+const INBOX_DIR: &str = "inbox";
+/// Where archived and task-ified captures are moved
+const ARCHIVE_DIR: &str = "archive/inbox";
+const MAILBOX_DIR: &str = "Mailbox";
+/// Apple Notes in a folder of this name, or containing the tag, are queued
+const APPLE_NOTES_FOLDER: &str = "LifeOS";
+const APPLE_NOTES_TAG: &str = "#lifeos";
+/// One JSON `TriageDecision` per line; mail and notes decided here leave the queue
+const DECISIONS_LOG: &str = ".lifeos/triage.jsonl";
+const PREVIEW_CHARS: usize = 200;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum TriageSource {
+    /// Unread mail in a synced INBOX
+    Email,
+    /// inbox/*.md
+    Capture,
+    /// Apple Notes marked for import (macOS)
+    AppleNote,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TriageAction {
+    /// Add to a day's tasks
+    Taskify,
+    Archive,
+    Delete,
+    /// Add a waiting-for task naming who it went to
+    Delegate,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TriageItem {
+    pub source: TriageSource,
+    /// `<account>/<email id>`, the capture's vault-relative path, or the Apple Notes id
+    pub id: String,
+    pub title: String,
+    pub preview: String,
+    /// RFC 3339 when known; the queue is oldest first, undated last
+    pub date: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TriageQueue {
+    /// Next item to process; None when everything is triaged
+    pub item: Option<TriageItem>,
+    /// Items left, `item` included
+    pub remaining: usize,
+    pub by_source: BTreeMap<String, usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TriageOptions {
+    /// Task day (YYYY-MM-DD) for taskify/delegate; default today
+    #[serde(default)]
+    pub date: Option<String>,
+    /// Task text instead of the item's title
+    #[serde(default)]
+    pub text: Option<String>,
+    /// Required for delegate
+    #[serde(default)]
+    pub delegate_to: Option<String>,
+}
+
+/// A line of .lifeos/triage.jsonl
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TriageDecision {
+    pub source: TriageSource,
+    pub id: String,
+    pub title: String,
+    pub action: TriageAction,
+    /// RFC 3339
+    pub at: String,
+    /// The task line added, for taskify/delegate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delegate_to: Option<String>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// The oldest untriaged item across sources, with what is left
+#[tauri::command]
+pub async fn get_triage_queue(vault_path: String, sources: Option<Vec<TriageSource>>) -> Result<TriageQueue, String> {
+    tokio::task::spawn_blocking(move || {
+        let vault = Path::new(&vault_path);
+        let items = pending_items(vault, sources.as_deref());
+        let mut by_source = BTreeMap::new();
+        for item in &items {
+            let key = serde_json::to_value(item.source).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default();
+            *by_source.entry(key).or_insert(0) += 1;
+        }
+        Ok(TriageQueue { remaining: items.len(), item: items.into_iter().next(), by_source })
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Act on an item and record the decision. Captures are vault files and are
+/// moved or deleted; mail and Apple Notes stay as they are in their apps and
+/// only leave the queue.
+#[tauri::command]
+pub async fn triage_item(
+    vault_path: String,
+    source: TriageSource,
+    id: String,
+    action: TriageAction,
+    options: Option<TriageOptions>,
+) -> Result<TriageDecision, String> {
+    tokio::task::spawn_blocking(move || {
+        let vault = Path::new(&vault_path);
+        let options = options.unwrap_or_default();
+        let item = items_from(vault, source)
+            .into_iter()
+            .find(|i| i.id == id)
+            .ok_or_else(|| tr!("Triage item not found: {}", id))?;
+        decide(vault, &item, action, &options)
+    })
+    .await
+    .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Decisions on record, newest first
+#[tauri::command]
+pub fn get_triage_history(vault_path: String, limit: Option<usize>) -> Result<Vec<TriageDecision>, String> {
+    let mut decisions = load_decisions(Path::new(&vault_path));
+    decisions.reverse();
+    decisions.truncate(limit.unwrap_or(50));
+    Ok(decisions)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn decide(vault: &Path, item: &TriageItem, action: TriageAction, options: &TriageOptions) -> Result<TriageDecision, String> {
+    let delegate_to = options.delegate_to.as_deref().map(str::trim).filter(|d| !d.is_empty()).map(str::to_string);
+    if action == TriageAction::Delegate && delegate_to.is_none() {
+        return Err(tr!("Delegating needs someone to delegate to"));
+    }
+    let text = options.text.as_deref().map(str::trim).filter(|t| !t.is_empty()).unwrap_or(&item.title).to_string();
+    let task = match (action, &delegate_to) {
+        (TriageAction::Taskify, _) => Some(text),
+        (TriageAction::Delegate, Some(who)) => Some(format!("{text} → {who} @waiting")),
+        _ => None,
+    };
+    if let Some(task) = &task {
+        let date = options.date.clone().unwrap_or_else(|| Local::now().format("%Y-%m-%d").to_string());
+        tasks::add_task(&vault.to_string_lossy(), &date, task)?;
+    }
+
+    if item.source == TriageSource::Capture {
+        let path = vault_file(vault, &item.id)?;
+        if action == TriageAction::Delete {
+            fs::remove_file(&path).map_err(|e| tr!("Failed to delete: {}", e))?;
+        } else {
+            archive_capture(vault, &path)?;
+        }
+    }
+
+    let decision = TriageDecision {
+        source: item.source,
+        id: item.id.clone(),
+        title: item.title.clone(),
+        action,
+        at: Local::now().to_rfc3339(),
+        task,
+        delegate_to,
+    };
+    append_decision(vault, &decision)?;
+    Ok(decision)
+}
+
+/// Into archive/inbox/, suffixing the name rather than overwriting
+fn archive_capture(vault: &Path, path: &Path) -> Result<(), String> {
+    let dir = vault.join(ARCHIVE_DIR);
+    fs::create_dir_all(&dir).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let ext = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    let mut target = dir.join(format!("{stem}{ext}"));
+    let mut n = 2;
+    while target.exists() {
+        target = dir.join(format!("{stem}-{n}{ext}"));
+        n += 1;
+    }
+    fs::rename(path, &target).map_err(|e| tr!("Failed to move {}: {}", path.display(), e))
+}
+
+fn pending_items(vault: &Path, sources: Option<&[TriageSource]>) -> Vec<TriageItem> {
+    let decided: HashSet<(TriageSource, String)> = load_decisions(vault).into_iter().map(|d| (d.source, d.id)).collect();
+    let all = [TriageSource::Email, TriageSource::Capture, TriageSource::AppleNote];
+    let mut items: Vec<TriageItem> = all
+        .into_iter()
+        .filter(|s| sources.is_none_or(|wanted| wanted.contains(s)))
+        .flat_map(|s| items_from(vault, s))
+        // A capture leaves the inbox when decided on, so a file there now is new
+        .filter(|i| i.source == TriageSource::Capture || !decided.contains(&(i.source, i.id.clone())))
+        .collect();
+    // Dates are all local RFC 3339, so they sort as strings; undated last
+    items.sort_by(|a, b| (a.date.is_none(), &a.date).cmp(&(b.date.is_none(), &b.date)));
+    items
+}
+
+fn items_from(vault: &Path, source: TriageSource) -> Vec<TriageItem> {
+    match source {
+        TriageSource::Email => email_items(vault),
+        TriageSource::Capture => capture_items(vault),
+        TriageSource::AppleNote => apple_note_items(),
+    }
+}
+
+fn email_items(vault: &Path) -> Vec<TriageItem> {
+    let Ok(accounts) = fs::read_dir(vault.join(MAILBOX_DIR)) else { return Vec::new() };
+    let mut items = Vec::new();
+    for account in accounts.filter_map(|e| e.ok()).filter(|e| e.path().is_dir()) {
+        let name = account.file_name().to_string_lossy().to_string();
+        let Some(index) = fs::read_to_string(account.path().join("index.json"))
+            .ok()
+            .and_then(|raw| serde_json::from_str::<Vec<EmailMessage>>(&raw).ok())
+        else {
+            continue;
+        };
+        items.extend(
+            index
+                .into_iter()
+                .filter(|m| m.folder.eq_ignore_ascii_case("INBOX") && !m.flags.iter().any(|f| f.trim_start_matches('\\') == "Seen"))
+                .map(|m| TriageItem {
+                    source: TriageSource::Email,
+                    id: format!("{name}/{}", m.id),
+                    title: if m.subject.trim().is_empty() { m.from.clone() } else { m.subject.trim().to_string() },
+                    preview: preview(&format!("{} · {}", m.from, m.body_text.as_deref().unwrap_or_default())),
+                    date: local_rfc3339(&m.date),
+                }),
+        );
+    }
+    items
+}
+
+fn capture_items(vault: &Path) -> Vec<TriageItem> {
+    let Ok(entries) = fs::read_dir(vault.join(INBOX_DIR)) else { return Vec::new() };
+    entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file() && e.path().extension().is_some_and(|x| x == "md" || x == "txt"))
+        .filter_map(|e| {
+            let path = e.path();
+            let raw = fs::read_to_string(&path).ok()?;
+            let body = split_frontmatter(&raw).1;
+            let stem = path.file_stem()?.to_string_lossy().to_string();
+            let first = body.lines().map(|l| l.trim().trim_start_matches('#').trim()).find(|l| !l.is_empty());
+            let modified = e.metadata().ok().and_then(|m| m.modified().ok()).map(|t| DateTime::<Local>::from(t).to_rfc3339());
+            Some(TriageItem {
+                source: TriageSource::Capture,
+                id: format!("{INBOX_DIR}/{}", e.file_name().to_string_lossy()),
+                title: first.map(str::to_string).unwrap_or(stem),
+                preview: preview(body),
+                date: modified,
+            })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn apple_note_items() -> Vec<TriageItem> {
+    let notes = match super::extra_commands::load_notes_from_apple() {
+        Ok(notes) => notes,
+        Err(e) => {
+            println!("[WARN] triage could not read Apple Notes: {e}");
+            return Vec::new();
+        }
+    };
+    notes
+        .into_iter()
+        .filter(|n| n.folder == APPLE_NOTES_FOLDER || n.content.to_lowercase().contains(APPLE_NOTES_TAG))
+        .map(|n| TriageItem { source: TriageSource::AppleNote, id: n.id, title: n.name, preview: preview(&n.content), date: n.modified.or(n.created) })
+        .collect()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn apple_note_items() -> Vec<TriageItem> {
+    let _ = (APPLE_NOTES_FOLDER, APPLE_NOTES_TAG);
+    Vec::new()
+}
+
+fn preview(text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match flat.char_indices().nth(PREVIEW_CHARS) {
+        Some((i, _)) => format!("{}…", &flat[..i]),
+        None => flat,
+    }
+}
+
+/// Mail dates (RFC 2822, maybe with a "(CST)" comment) as local RFC 3339
+fn local_rfc3339(raw: &str) -> Option<String> {
+    let raw = raw.split(" (").next().unwrap_or_default().trim();
+    DateTime::parse_from_rfc2822(raw)
+        .or_else(|_| DateTime::parse_from_rfc3339(raw))
+        .ok()
+        .map(|d| d.with_timezone(&Local).to_rfc3339())
+}
+
+fn append_decision(vault: &Path, decision: &TriageDecision) -> Result<(), String> {
+    let path = vault.join(DECISIONS_LOG);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let line = serde_json::to_string(decision).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    let mut file = OpenOptions::new().create(true).append(true).open(&path).map_err(|e| tr!("write_file failed: {}", e))?;
+    writeln!(file, "{line}").map_err(|e| tr!("write_file failed: {}", e))
+}
+
+fn load_decisions(vault: &Path) -> Vec<TriageDecision> {
+    fs::read_to_string(vault.join(DECISIONS_LOG))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(vault: &Path, rel: &str, text: &str) {
+        let path = vault.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    #[test]
+    fn test_triage_flow() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        write(v, "inbox/idea.md", "# 给妈妈买生日礼物\n\n要提前一周\n");
+        write(v, "inbox/junk.md", "随手记\n");
+        write(
+            v,
+            "Mailbox/work/index.json",
+            r#"[{"id":"INBOX_7","uid":7,"uidString":null,"from":"老王","to":"me","subject":"合同确认","date":"Mon, 3 Mar 2025 09:00:00 +0800","bodyText":"请回复","bodyHtml":null,"attachments":[],"flags":[],"folder":"INBOX"},
+                {"id":"INBOX_8","uid":8,"uidString":null,"from":"x","to":"me","subject":"已读","date":"Mon, 3 Mar 2025 10:00:00 +0800","bodyText":null,"bodyHtml":null,"attachments":[],"flags":["Seen"],"folder":"INBOX"}]"#,
+        );
+
+        let sources = [TriageSource::Email, TriageSource::Capture];
+        let items = pending_items(v, Some(&sources));
+        assert_eq!(items.len(), 3);
+        // The 2025 mail is older than today's captures
+        assert_eq!(items[0].id, "work/INBOX_7");
+
+        let opts = TriageOptions { date: Some("2025-03-03".into()), delegate_to: Some("小李".into()), ..Default::default() };
+        assert!(decide(v, &items[0], TriageAction::Delegate, &TriageOptions::default()).is_err());
+        let d = decide(v, &items[0], TriageAction::Delegate, &opts).unwrap();
+        assert_eq!(d.task.as_deref(), Some("合同确认 → 小李 @waiting"));
+
+        let idea = items.iter().find(|i| i.id == "inbox/idea.md").unwrap();
+        assert_eq!(idea.title, "给妈妈买生日礼物");
+        decide(v, idea, TriageAction::Taskify, &opts).unwrap();
+        assert!(v.join("archive/inbox/idea.md").exists() && !v.join("inbox/idea.md").exists());
+        let day = fs::read_to_string(v.join("daily/tasks/2025-03-03.md")).unwrap();
+        assert!(day.contains("- [ ] 给妈妈买生日礼物") && day.contains("- [ ] 合同确认 → 小李 @waiting"));
+
+        let junk = pending_items(v, Some(&sources));
+        assert_eq!(junk.len(), 1);
+        decide(v, &junk[0], TriageAction::Delete, &opts).unwrap();
+        assert!(pending_items(v, Some(&sources)).is_empty() && !v.join("inbox/junk.md").exists());
+        assert_eq!(get_triage_history(v.to_string_lossy().to_string(), None).unwrap()[0].action, TriageAction::Delete);
+    }
+}
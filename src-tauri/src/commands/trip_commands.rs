@@ -0,0 +1,404 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::services::notes::slugify;
+use crate::services::{mail, records};
+
+const TRIPS_DIR: &str = "life/trips";
+/// Packing templates, one checklist per file: life/trips/templates/<name>.md
+const TEMPLATES_DIR: &str = "life/trips/templates";
+/// Body section holding the packing checklist
+const PACKING_HEADING: &str = "## 行李清单";
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Typed frontmatter of life/trips/<slug>.md; unknown keys are kept in `extra`
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TripMeta {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub destination: String,
+    /// `YYYY-MM-DD`
+    #[serde(default)]
+    pub start: String,
+    /// `YYYY-MM-DD`, inclusive
+    #[serde(default)]
+    pub end: String,
+    /// "planning" | "booked" | "done" | "cancelled"
+    #[serde(default = "default_status")]
+    pub status: String,
+    #[serde(default)]
+    pub bookings: Vec<Booking>,
+    /// Confirmation emails linked from the mail cache
+    #[serde(default)]
+    pub emails: Vec<mail::CachedMessage>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_yaml::Value>,
+}
+
+fn default_status() -> String {
+    "planning".to_string()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Booking {
+    /// "flight" | "train" | "hotel" | "car" | "activity" | ...
+    #[serde(default)]
+    pub kind: String,
+    #[serde(default)]
+    pub title: String,
+    /// `YYYY-MM-DD` or `YYYY-MM-DD HH:MM`
+    #[serde(default)]
+    pub start: String,
+    /// Arrival or check-out, same forms as `start`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmation: Option<String>,
+    /// Message-ID of the confirmation email
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PackingItem {
+    pub text: String,
+    #[serde(default)]
+    pub done: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Trip {
+    /// File stem; empty on create to derive it from the title
+    #[serde(default)]
+    pub slug: String,
+    #[serde(default)]
+    pub path: String,
+    #[serde(flatten)]
+    pub meta: TripMeta,
+    /// Parsed from the 行李清单 section of the body
+    #[serde(default)]
+    pub packing: Vec<PackingItem>,
+    /// Markdown body without the packing section
+    #[serde(default)]
+    pub notes: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ItineraryEvent {
+    /// HH:MM, None for all-day
+    pub time: Option<String>,
+    pub kind: String,
+    pub title: String,
+    /// "start" | "end" — departure/check-in or arrival/check-out
+    pub phase: String,
+    pub location: Option<String>,
+    pub confirmation: Option<String>,
+    /// Index into the trip's `bookings`
+    pub booking: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ItineraryDay {
+    pub date: String,
+    /// 1-based day of the trip; None for bookings outside start..end
+    pub day: Option<i64>,
+    pub events: Vec<ItineraryEvent>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Trips by start date, newest first
+#[tauri::command]
+pub fn list_trips(vault_path: String) -> Vec<Trip> {
+    let mut trips: Vec<Trip> = records::list(Path::new(&vault_path), TRIPS_DIR).iter().filter_map(|p| read_trip(p)).collect();
+    trips.sort_by(|a, b| b.meta.start.cmp(&a.meta.start));
+    trips
+}
+
+#[tauri::command]
+pub fn get_trip(vault_path: String, slug: String) -> Result<Trip, String> {
+    read_trip(&trip_path(Path::new(&vault_path), &slug)?).ok_or_else(|| tr!("Trip not found: {}", slug))
+}
+
+#[tauri::command]
+pub fn save_trip(vault_path: String, trip: Trip) -> Result<Trip, String> {
+    let vault = PathBuf::from(&vault_path);
+    let mut trip = trip;
+    trip.meta.title = trip.meta.title.trim().to_string();
+    if trip.meta.title.is_empty() {
+        return Err(tr!("Title is required"));
+    }
+    if let (Some(start), Some(end)) = (parse_day(&trip.meta.start), parse_day(&trip.meta.end)) {
+        if end < start {
+            return Err(tr!("Trip ends before it starts"));
+        }
+    }
+    if trip.slug.is_empty() {
+        trip.slug = records::unique_slug(&vault, TRIPS_DIR, &slugify(&trip.meta.title));
+    }
+    let path = trip_path(&vault, &trip.slug)?;
+    write_trip(&path, &trip)?;
+    trip.path = path.to_string_lossy().to_string();
+    Ok(trip)
+}
+
+#[tauri::command]
+pub fn delete_trip(vault_path: String, slug: String) -> Result<(), String> {
+    fs::remove_file(trip_path(Path::new(&vault_path), &slug)?).map_err(|e| e.to_string())
+}
+
+/// Attach a synced email by Message-ID. With `booking`, that booking's
+/// `message_id` is set too.
+#[tauri::command]
+pub fn link_trip_email(vault_path: String, slug: String, message_id: String, booking: Option<usize>) -> Result<Trip, String> {
+    let message = mail::find_message(&vault_path, &message_id).ok_or_else(|| tr!("Email not in the mail cache: {}", message_id))?;
+    let mut trip = get_trip(vault_path.clone(), slug)?;
+    if let Some(i) = booking {
+        let booking = trip.meta.bookings.get_mut(i).ok_or_else(|| tr!("Booking not found: {}", i))?;
+        booking.message_id = Some(message.message_id.clone());
+    }
+    if !trip.meta.emails.iter().any(|e| e.message_id == message.message_id) {
+        trip.meta.emails.push(message);
+    }
+    save_trip(vault_path, trip)
+}
+
+#[tauri::command]
+pub fn unlink_trip_email(vault_path: String, slug: String, message_id: String) -> Result<Trip, String> {
+    let id = message_id.trim().trim_start_matches('<').trim_end_matches('>');
+    let mut trip = get_trip(vault_path.clone(), slug)?;
+    trip.meta.emails.retain(|e| e.message_id != id);
+    for booking in trip.meta.bookings.iter_mut().filter(|b| b.message_id.as_deref() == Some(id)) {
+        booking.message_id = None;
+    }
+    save_trip(vault_path, trip)
+}
+
+/// Template names under life/trips/templates
+#[tauri::command]
+pub fn list_packing_templates(vault_path: String) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(Path::new(&vault_path).join(TEMPLATES_DIR)) else { return vec![] };
+    let mut names: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|p| Some(p.file_stem()?.to_string_lossy().to_string()))
+        .collect();
+    names.sort();
+    names
+}
+
+/// Merge the checklists of `templates` into the trip's packing list. Items
+/// already on the list keep their state; `{nights}` and `{days}` in an item
+/// are filled from the trip dates.
+#[tauri::command]
+pub fn generate_packing_list(vault_path: String, slug: String, templates: Vec<String>) -> Result<Trip, String> {
+    let vault = Path::new(&vault_path);
+    let mut trip = get_trip(vault_path.clone(), slug)?;
+    let days = match (parse_day(&trip.meta.start), parse_day(&trip.meta.end)) {
+        (Some(start), Some(end)) => (end - start).num_days() + 1,
+        _ => 1,
+    };
+    for name in &templates {
+        let path = vault.join(TEMPLATES_DIR).join(format!("{name}.md"));
+        let raw = fs::read_to_string(&path).map_err(|_| tr!("Packing template not found: {}", name))?;
+        for item in parse_checklist(&raw) {
+            let text = item.text.replace("{nights}", &(days - 1).max(1).to_string()).replace("{days}", &days.to_string());
+            if !trip.packing.iter().any(|p| p.text == text) {
+                trip.packing.push(PackingItem { text, done: false });
+            }
+        }
+    }
+    save_trip(vault_path, trip)
+}
+
+#[tauri::command]
+pub fn set_packing_item(vault_path: String, slug: String, index: usize, done: bool) -> Result<Trip, String> {
+    let mut trip = get_trip(vault_path.clone(), slug)?;
+    trip.packing.get_mut(index).ok_or_else(|| tr!("Packing item not found: {}", index))?.done = done;
+    save_trip(vault_path, trip)
+}
+
+/// Day-by-day timeline: every day of the trip, with bookings placed on
+/// their start (and end) dates in time order
+#[tauri::command]
+pub fn get_trip_itinerary(vault_path: String, slug: String) -> Result<Vec<ItineraryDay>, String> {
+    Ok(itinerary(&get_trip(vault_path, slug)?.meta))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn trip_path(vault: &Path, slug: &str) -> Result<PathBuf, String> {
+    records::path(vault, TRIPS_DIR, slug)
+}
+
+fn read_trip(path: &Path) -> Option<Trip> {
+    let record = records::read::<TripMeta>(path)?;
+    let mut meta = record.meta.unwrap_or_else(|| TripMeta { status: default_status(), ..Default::default() });
+    if meta.title.is_empty() {
+        meta.title = record.slug.clone();
+    }
+    let (notes, packing) = split_packing(&record.body);
+    Some(Trip { slug: record.slug, path: record.path, meta, packing, notes })
+}
+
+fn write_trip(path: &Path, trip: &Trip) -> Result<(), String> {
+    let mut body = match trip.notes.trim() {
+        "" => format!("# {}\n", trip.meta.title),
+        notes => format!("{notes}\n"),
+    };
+    if !trip.packing.is_empty() {
+        body.push_str(&format!("\n{PACKING_HEADING}\n\n"));
+        for item in &trip.packing {
+            body.push_str(&format!("- [{}] {}\n", if item.done { "x" } else { " " }, item.text));
+        }
+    }
+    records::write(path, &trip.meta, &body)
+}
+
+/// Split the 行李清单 section (up to the next `## ` heading) off the body
+fn split_packing(body: &str) -> (String, Vec<PackingItem>) {
+    let Some(start) = body.find(PACKING_HEADING) else { return (body.trim().to_string(), vec![]) };
+    let section_start = start + PACKING_HEADING.len();
+    let end = body[section_start..].find("\n## ").map_or(body.len(), |i| section_start + i + 1);
+    let notes = format!("{}{}", &body[..start], &body[end..]).trim().to_string();
+    (notes, parse_checklist(&body[section_start..end]))
+}
+
+/// `- [ ] item` / `- [x] item` lines; empty items are skipped
+fn parse_checklist(text: &str) -> Vec<PackingItem> {
+    text.lines()
+        .filter_map(|line| {
+            let rest = line.trim_start().strip_prefix("- [")?;
+            let (mark, item) = rest.split_once(']')?;
+            let item = item.trim();
+            (!item.is_empty()).then(|| PackingItem { text: item.to_string(), done: mark.eq_ignore_ascii_case("x") })
+        })
+        .collect()
+}
+
+fn parse_day(raw: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(raw.trim().get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// `YYYY-MM-DD HH:MM` / `YYYY-MM-DDTHH:MM` → HH:MM
+fn parse_time(raw: &str) -> Option<String> {
+    let time = raw.trim().get(11..16)?;
+    chrono::NaiveTime::parse_from_str(time, "%H:%M").ok().map(|_| time.to_string())
+}
+
+pub(crate) fn itinerary(meta: &TripMeta) -> Vec<ItineraryDay> {
+    let first = parse_day(&meta.start);
+    let mut days: BTreeMap<NaiveDate, Vec<ItineraryEvent>> = BTreeMap::new();
+    if let (Some(start), Some(end)) = (first, parse_day(&meta.end)) {
+        let mut d = start;
+        while d <= end {
+            days.entry(d).or_default();
+            d += Duration::days(1);
+        }
+    }
+    for (i, b) in meta.bookings.iter().enumerate() {
+        let ends = b.end.as_deref().map(|e| (e, "end"));
+        for (at, phase) in std::iter::once((b.start.as_str(), "start")).chain(ends) {
+            let Some(date) = parse_day(at) else { continue };
+            days.entry(date).or_default().push(ItineraryEvent {
+                time: parse_time(at),
+                kind: b.kind.clone(),
+                title: b.title.clone(),
+                phase: phase.to_string(),
+                location: b.location.clone(),
+                confirmation: b.confirmation.clone(),
+                booking: i,
+            });
+        }
+    }
+    let last = parse_day(&meta.end);
+    days.into_iter()
+        .map(|(date, mut events)| {
+            // All-day entries first, then by time
+            events.sort_by(|a, b| a.time.cmp(&b.time));
+            let in_trip = first.is_some_and(|f| date >= f) && last.is_none_or(|l| date <= l);
+            ItineraryDay {
+                date: date.format("%Y-%m-%d").to_string(),
+                day: first.filter(|_| in_trip).map(|f| (date - f).num_days() + 1),
+                events,
+            }
+        })
+        .collect()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slug_stays_in_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().to_string_lossy().to_string();
+        fs::write(dir.path().join("x.md"), "keep").unwrap();
+        assert_eq!(delete_trip(vault.clone(), "../../x".into()).unwrap_err(), tr!("Invalid slug: {}", "../../x"));
+        assert!(get_trip_itinerary(vault, "../../x".into()).is_err());
+        assert!(dir.path().join("x.md").exists());
+    }
+
+    #[test]
+    fn test_packing_roundtrip() {
+        let body = "# 东京\n\n## 行李清单\n\n- [x] 护照\n- [ ] 充电器\n- [ ]\n\n## 备注\n\n早点出发\n";
+        let (notes, packing) = split_packing(body);
+        assert_eq!(packing, vec![
+            PackingItem { text: "护照".into(), done: true },
+            PackingItem { text: "充电器".into(), done: false },
+        ]);
+        assert_eq!(notes, "# 东京\n\n## 备注\n\n早点出发");
+    }
+
+    #[test]
+    fn test_itinerary() {
+        let booking = |kind: &str, start: &str, end: Option<&str>| Booking {
+            kind: kind.into(),
+            title: kind.into(),
+            start: start.into(),
+            end: end.map(str::to_string),
+            ..Default::default()
+        };
+        let meta = TripMeta {
+            start: "2025-04-01".into(),
+            end: "2025-04-03".into(),
+            bookings: vec![
+                booking("hotel", "2025-04-01", Some("2025-04-03")),
+                booking("flight", "2025-04-01 08:30", Some("2025-04-01 12:45")),
+                booking("flight", "2025-04-04 09:00", None),
+            ],
+            ..Default::default()
+        };
+        let days = itinerary(&meta);
+        assert_eq!(days.len(), 4);
+        assert_eq!(days[0].day, Some(1));
+        let first: Vec<_> = days[0].events.iter().map(|e| (e.kind.as_str(), e.time.as_deref(), e.phase.as_str())).collect();
+        assert_eq!(first, vec![("hotel", None, "start"), ("flight", Some("08:30"), "start"), ("flight", Some("12:45"), "end")]);
+        assert!(days[1].events.is_empty());
+        assert_eq!(days[2].events[0].phase, "end");
+        assert_eq!(days[3].day, None);
+    }
+}
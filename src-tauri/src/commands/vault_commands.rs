@@ -2,13 +2,8 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
-const CONFIG_FILE_NAME: &str = ".life-os-vault";
-
-/// Returns the path to the global config file stored in the user's home dir
-fn global_config_path() -> PathBuf {
-    let home = dirs_next::home_dir().expect("cannot find home dir");
-    home.join(CONFIG_FILE_NAME)
-}
+use crate::i18n;
+use crate::services::{self, durable};
 
 #[derive(Serialize, Deserialize)]
 pub struct VaultConfig {
@@ -18,18 +13,31 @@ pub struct VaultConfig {
 /// Read the configured vault path, if any
 #[tauri::command]
 pub fn get_vault_path() -> Option<String> {
-    let cfg = global_config_path();
-    if cfg.exists() {
-        fs::read_to_string(&cfg).ok().map(|s| s.trim().to_string())
-    } else {
-        None
-    }
+    services::configured_vault()
 }
 
 /// Persist a new vault path
 #[tauri::command]
 pub fn set_vault_path(path: String) -> Result<(), String> {
-    fs::write(global_config_path(), &path).map_err(|e| e.to_string())
+    durable::write(services::config_file()?, &path).map_err(|e| e.to_string())
+}
+
+/// Suggest a vault location for first-run setup.
+/// Desktop: ~/LifeOS. Mobile: inside the app's documents dir, the only
+/// place the sandbox lets us create a folder tree without a picker.
+#[tauri::command]
+pub fn get_default_vault_path(app: tauri::AppHandle) -> Result<String, String> {
+    #[cfg(mobile)]
+    let base = {
+        use tauri::Manager;
+        app.path().document_dir().map_err(|e| e.to_string())?
+    };
+    #[cfg(desktop)]
+    let base = {
+        let _ = app;
+        services::home_dir().ok_or_else(|| tr!("Cannot find home dir"))?
+    };
+    Ok(base.join("LifeOS").to_string_lossy().to_string())
 }
 
 /// Scaffold the full vault directory structure and seed sample files
@@ -42,8 +50,10 @@ pub fn init_vault(path: String) -> Result<(), String> {
         ".lifeos/servers",
         ".lifeos/emails",
         ".lifeos/skills",
+        ".lifeos/automations",
         "daily/tasks",
         "daily/habits",
+        "inbox",
         "projects/backlog",
         "projects/todo",
         "projects/active",
@@ -53,10 +63,20 @@ pub fn init_vault(path: String) -> Result<(), String> {
         "diary/2025",
         "diary/templates",
         "decisions",
+        "people",
+        "life/finance/transactions",
+        "life/books",
+        "life/media",
+        "life/nutrition",
+        "life/health",
+        "life/trips/templates",
         "connectors/github",
         "connectors/gmail",
         "connectors/calendar",
         "assets/images",
+        "annotations",
+        "templates",
+        "templates/projects/产品发布",
     ];
 
     for dir in &dirs {
@@ -278,6 +298,11 @@ mood: 😊
   - id: done
     name: "✅ 已完成"
     color: "#00ffa3"
+rules:
+  - to: done
+    set:
+      progress: 100
+    stamp: [completed]
 "##;
     write_if_not_exists(&root.join(".lifeos/board.yaml"), board_content)?;
 
@@ -307,22 +332,220 @@ tags: []
         diary_template,
     )?;
 
+    // Seed a disabled example automation
+    let automation_example = r#"# 自动化规则示例：把今天完成的 #project 任务记到收件箱
+# trigger: schedule | file-change | new-email | task-done
+# action:  run-shortcut | move-file | create-note | webhook
+name: 完成的项目任务
+enabled: false
+trigger:
+  type: task-done
+  tag: project
+actions:
+  - type: create-note
+    path: "inbox/{{date}}.md"
+    content: "- [x] {{task}}\n"
+    append: true
+"#;
+    write_if_not_exists(&root.join(".lifeos/automations/example.yaml"), automation_example)?;
+
+    // Seed finance categorization rules
+    let finance_rules = r#"# 交易分类规则：按描述（不区分大小写）包含 match 归类，先匹配者优先
+# type 可选 income | expense
+rules:
+  - match: 星巴克
+    category: 餐饮
+  - match: 滴滴
+    category: 交通
+  - match: 工资
+    category: 工资
+    type: income
+"#;
+    write_if_not_exists(&root.join("life/finance/rules.yaml"), finance_rules)?;
+
+    // Seed packing templates; {nights} and {days} are filled from the trip dates
+    let packing_basic = r#"# 基础行李
+
+- [ ] 身份证/护照
+- [ ] 手机充电器
+- [ ] 充电宝
+- [ ] 换洗衣物 ×{nights}
+- [ ] 内衣袜子 ×{days}
+- [ ] 洗漱用品
+- [ ] 常用药品
+- [ ] 雨伞
+"#;
+    write_if_not_exists(&root.join("life/trips/templates/基础.md"), packing_basic)?;
+
+    let packing_abroad = r#"# 出境
+
+- [ ] 护照/签证
+- [ ] 转换插头
+- [ ] 外币/信用卡
+- [ ] 境外流量卡
+- [ ] 酒店与机票确认单
+- [ ] 旅行保险
+"#;
+    write_if_not_exists(&root.join("life/trips/templates/出境.md"), packing_abroad)?;
+
+    // Seed a user template; see services/templates.rs for the placeholders
+    let weekly_template = r#"---
+title: "{{title}}"
+type: weekly-plan
+week: {{date:%G-W%V}}
+due: {{date+6d}}
+tags: []
+---
+
+# {{date}} ~ {{date+6d}} 周计划
+
+## 本周重点
+
+- [ ] {{prompt:本周最重要的事}}
+
+## 复盘时间
+
+{{date+6d}} {{prompt:复盘时间|20:00}}
+"#;
+    write_if_not_exists(&root.join("templates/周计划.md"), weekly_template)?;
+
+    // Seed a project template: project.md becomes projects/<slug>.md, the
+    // rest land under projects/<slug>/ (see create_project_from_template)
+    let launch_project = r#"---
+priority: high
+due: {{prompt:上线日期}}
+---
+
+{{prompt:一句话目标|}}
+
+## 待规划
+
+- [ ] 确定范围与里程碑
+- [ ] 准备发布清单
+
+## 进行中
+
+## 已完成
+"#;
+    write_if_not_exists(&root.join("templates/projects/产品发布/project.md"), launch_project)?;
+    let launch_checklist = r#"# {{project}} 发布清单
+
+- [ ] 功能冻结
+- [ ] 回归测试
+- [ ] 更新日志与文档
+- [ ] 回滚方案
+- [ ] 发布公告
+"#;
+    write_if_not_exists(&root.join("templates/projects/产品发布/发布清单.md"), launch_checklist)?;
+    let launch_retro = r#"# {{project}} 复盘
+
+## 做得好的
+
+## 可以改进的
+
+## 下次行动
+
+- [ ] 行动项
+"#;
+    write_if_not_exists(&root.join("templates/projects/产品发布/复盘.md"), launch_retro)?;
+
     // Seed connectors config
     let connectors_content = r#"# Life OS Connectors Configuration
 # DO NOT commit this file to public repositories (add to .gitignore)
+# Any value can be `keychain:<name>` to read it from the macOS Keychain instead
 
 github:
   enabled: false
   token: ""
   username: ""
 
-gmail:
+git:
+  # Append each day's commits to its daily note once the day is over
+  commit_summary: false
+  at: "22:00"
+  # Folders searched for repositories, like the Git scanner
+  roots: []
+  max_depth: 5
+
+mail_digest:
+  # Morning note in daily/mail summarizing the last day's inbox
   enabled: false
-  # OAuth handled separately
+  at: "07:30"
+  # One-line summaries from the AI CLI set up in Settings
+  ai: false
+
+google:
+  # OAuth client of type "Desktop app" from console.cloud.google.com, used
+  # by Google Calendar below and by Gmail accounts added in the Mail view
+  # ("Sign in with Google"; enable the Calendar and Gmail APIs)
+  client_id: ""
+  client_secret: ""
 
 calendar:
+  # Google Calendar, through the google: client above
   enabled: false
-  # OAuth handled separately
+  interval_minutes: 15
+  # Calendar ids to pull into connectors/calendar
+  calendars:
+    - primary
+  # Where planner time blocks go; empty to keep them local
+  push_to: ""
+
+carddav:
+  # Address book collection, synced with people/ by "Sync contacts", e.g.
+  # https://cloud.example.com/remote.php/dav/addressbooks/users/<user>/contacts/
+  # (iCloud: an app-specific password)
+  url: ""
+  username: ""
+  password: ""
+
+tmdb:
+  # https://www.themoviedb.org/settings/api — used by the watchlist
+  api_key: ""
+
+weather:
+  # Open-Meteo, no key needed; fills `weather` in new diary/daily notes
+  enabled: true
+  city: ""
+  # Coordinates take precedence over the city name
+  latitude: ""
+  longitude: ""
+
+todoist:
+  # Settings → Integrations → Developer → API token
+  token: ""
+  # Optional; the Inbox when empty
+  project_id: ""
+  # LifeOS task field → Todoist field (text, tags, contexts, due, energy, note)
+  fields:
+    text: content
+    tags: labels
+    contexts: labels
+    due: due_date
+    energy: priority
+    note: description
+
+ticktick:
+  # OAuth access token from developer.ticktick.com
+  token: ""
+  project_id: ""
+  fields:
+    text: title
+    tags: tags
+    contexts: tags
+    due: dueDate
+    energy: priority
+    note: content
+
+sync:
+  # Two-way sync with a WebDAV server or S3-compatible bucket
+  enabled: false
+  interval_minutes: 30
+  backend: webdav # webdav | s3
+  url: ""
+  username: ""
+  password: ""
+  # S3 (path-style): endpoint, region, bucket, prefix, access_key, secret_key
 "#;
     write_if_not_exists(
         &root.join(".lifeos/connectors.yaml"),
@@ -330,7 +553,7 @@ calendar:
     )?;
 
     // Write vault path to global config
-    fs::write(global_config_path(), &path).map_err(|e| e.to_string())?;
+    durable::write(services::config_file()?, &path).map_err(|e| e.to_string())?;
 
     // Write skills to vault
     write_skills(&root)?;
@@ -427,6 +650,7 @@ due: 2025-12-31
 ### 移动项目
 1. 移动文件到新的状态目录
 2. 更新 frontmatter 中的 status 字段
+3. 遵守 .lifeos/board.yaml 中列的 wip_limit（在制上限），并按 rules 写入字段（如移到 done 时 progress: 100、completed: 当天日期）
 "#;
     fs::write(skills_dir.join("kanban.md"), kanban_skill).map_err(|e| e.to_string())?;
 
@@ -453,6 +677,9 @@ description: 用于管理 LifeOS 日常任务和 habits 模块的斜杠命令技
 ### Habits 配置
 {vault}/daily/habits/habits.yaml
 
+### 习惯暂停（假期 / 冻结）
+{vault}/daily/habits/pauses.yaml，期间未打卡不会中断连续天数
+
 ### Frontmatter 格式
 ---
 date: 2025-01-15
@@ -570,7 +797,7 @@ pub fn load_menu_config(vault_path: String) -> Result<String, String> {
     if menu_path.exists() {
         fs::read_to_string(&menu_path).map_err(|e| e.to_string())
     } else {
-        Err("Menu config not found".to_string())
+        Err(tr!("Menu config not found"))
     }
 }
 
@@ -578,7 +805,7 @@ pub fn load_menu_config(vault_path: String) -> Result<String, String> {
 #[tauri::command]
 pub fn save_menu_config(vault_path: String, content: String) -> Result<(), String> {
     let menu_path = PathBuf::from(&vault_path).join(".lifeos/menu.yaml");
-    fs::write(&menu_path, content).map_err(|e| e.to_string())
+    durable::write(&menu_path, content).map_err(|e| e.to_string())
 }
 
 /// Load board config from vault
@@ -588,7 +815,7 @@ pub fn load_board_config(vault_path: String) -> Result<String, String> {
     if board_path.exists() {
         fs::read_to_string(&board_path).map_err(|e| e.to_string())
     } else {
-        Err("Board config not found".to_string())
+        Err(tr!("Board config not found"))
     }
 }
 
@@ -596,25 +823,35 @@ pub fn load_board_config(vault_path: String) -> Result<String, String> {
 #[tauri::command]
 pub fn save_board_config(vault_path: String, content: String) -> Result<(), String> {
     let board_path = PathBuf::from(&vault_path).join(".lifeos/board.yaml");
-    fs::write(&board_path, content).map_err(|e| e.to_string())
+    durable::write(&board_path, content).map_err(|e| e.to_string())
 }
 
 /// Load app settings from vault
 #[tauri::command]
 pub fn load_app_settings(vault_path: String) -> Result<String, String> {
-    let settings_path = PathBuf::from(&vault_path).join(".lifeos/settings.yaml");
-    if settings_path.exists() {
-        fs::read_to_string(&settings_path).map_err(|e| e.to_string())
-    } else {
-        Ok(String::new())
-    }
+    services::load_settings(&vault_path)
 }
 
 /// Save app settings to vault
 #[tauri::command]
 pub fn save_app_settings(vault_path: String, content: String) -> Result<(), String> {
     let settings_path = PathBuf::from(&vault_path).join(".lifeos/settings.yaml");
-    fs::write(&settings_path, content).map_err(|e| e.to_string())
+    i18n::apply_settings(&content);
+    durable::write(&settings_path, content).map_err(|e| e.to_string())
+}
+
+/// Current language for backend messages ("zh" | "en")
+#[tauri::command]
+pub fn get_locale() -> String {
+    i18n::locale().as_str().to_string()
+}
+
+/// Switch backend messages without waiting for a settings save
+#[tauri::command]
+pub fn set_locale(locale: String) -> Result<(), String> {
+    let parsed = i18n::Locale::parse(&locale).ok_or_else(|| tr!("Unsupported language: {}", locale))?;
+    i18n::set_locale(parsed);
+    Ok(())
 }
 
 /// Regenerate skills in vault
@@ -630,12 +867,3 @@ fn write_if_not_exists(path: &PathBuf, content: &str) -> Result<(), String> {
     }
     Ok(())
 }
-
-// Re-export dirs_next for home_dir
-mod dirs_next {
-    pub fn home_dir() -> Option<std::path::PathBuf> {
-        std::env::var_os("HOME")
-            .or_else(|| std::env::var_os("USERPROFILE"))
-            .map(std::path::PathBuf::from)
-    }
-}
@@ -0,0 +1,273 @@
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+/// Event emitted to the frontend whenever a watched config file changes
+pub const CONFIG_CHANGED_EVENT: &str = "config-changed";
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigKind {
+    Settings,
+    Menu,
+    Board,
+    Connectors,
+}
+
+impl ConfigKind {
+    const ALL: [ConfigKind; 4] = [
+        ConfigKind::Settings,
+        ConfigKind::Menu,
+        ConfigKind::Board,
+        ConfigKind::Connectors,
+    ];
+
+    fn file_name(self) -> &'static str {
+        match self {
+            ConfigKind::Settings => "settings.yaml",
+            ConfigKind::Menu => "menu.yaml",
+            ConfigKind::Board => "board.yaml",
+            ConfigKind::Connectors => "connectors.yaml",
+        }
+    }
+
+    fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        Self::ALL.into_iter().find(|k| k.file_name() == name)
+    }
+}
+
+/// Validation result for one config file; also the `config-changed` payload
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigStatus {
+    pub kind: ConfigKind,
+    pub path: String,
+    pub exists: bool,
+    pub valid: bool,
+    pub error: Option<String>,
+}
+
+// Only one vault is open at a time, so a single watcher is enough
+static CONFIG_WATCHER: Lazy<Mutex<Option<RecommendedWatcher>>> = Lazy::new(|| Mutex::new(None));
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Watch {vault}/.lifeos for edits to settings/menu/board/connectors.yaml and
+/// emit a validated `config-changed` event for each real content change.
+/// Calling again (e.g. after switching vaults) replaces the previous watcher.
+#[tauri::command]
+pub fn start_config_watch(app: AppHandle, vault_path: String) -> Result<(), String> {
+    let config_dir = PathBuf::from(&vault_path).join(".lifeos");
+    if !config_dir.exists() {
+        return Err(tr!("Config dir does not exist: {}", config_dir.display()));
+    }
+
+    // Seed with current contents so the initial scan does not fire events
+    let mut last_seen: HashMap<ConfigKind, Option<u64>> = ConfigKind::ALL
+        .into_iter()
+        .map(|k| (k, content_hash(&config_dir.join(k.file_name()))))
+        .collect();
+
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let event = match res {
+            Ok(event) => event,
+            Err(e) => {
+                println!("[WARN] config watcher error: {e}");
+                return;
+            }
+        };
+        for path in &event.paths {
+            let Some(kind) = ConfigKind::from_path(path) else { continue };
+            // Editors and our own save commands fire several events per
+            // write; only forward when the bytes actually changed.
+            let hash = content_hash(path);
+            if last_seen.get(&kind) == Some(&hash) {
+                continue;
+            }
+            last_seen.insert(kind, hash);
+            let status = check_config(kind, path);
+            if let Err(e) = app.emit(CONFIG_CHANGED_EVENT, &status) {
+                println!("[WARN] failed to emit {CONFIG_CHANGED_EVENT}: {e}");
+            }
+        }
+    })
+    .map_err(|e| tr!("Failed to create config watcher: {}", e))?;
+
+    watcher
+        .watch(&config_dir, RecursiveMode::NonRecursive)
+        .map_err(|e| tr!("Failed to watch {}: {}", config_dir.display(), e))?;
+
+    *CONFIG_WATCHER.lock().unwrap() = Some(watcher);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn stop_config_watch() {
+    CONFIG_WATCHER.lock().unwrap().take();
+}
+
+/// Validate every known config file without waiting for a change
+#[tauri::command]
+pub fn validate_configs(vault_path: String) -> Vec<ConfigStatus> {
+    let config_dir = PathBuf::from(&vault_path).join(".lifeos");
+    ConfigKind::ALL
+        .into_iter()
+        .map(|k| check_config(k, &config_dir.join(k.file_name())))
+        .collect()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn content_hash(path: &Path) -> Option<u64> {
+    let bytes = fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+fn check_config(kind: ConfigKind, path: &Path) -> ConfigStatus {
+    let (exists, result) = match fs::read_to_string(path) {
+        Ok(content) => (true, validate_config(kind, &content)),
+        // A missing file is fine: the frontend falls back to its defaults
+        Err(_) => (false, Ok(())),
+    };
+    ConfigStatus {
+        kind,
+        path: path.to_string_lossy().to_string(),
+        exists,
+        valid: result.is_ok(),
+        error: result.err(),
+    }
+}
+
+fn validate_config(kind: ConfigKind, content: &str) -> Result<(), String> {
+    let doc: serde_yaml::Value =
+        serde_yaml::from_str(content).map_err(|e| tr!("Invalid YAML: {}", e))?;
+    if doc.is_null() {
+        return match kind {
+            ConfigKind::Settings | ConfigKind::Connectors => Ok(()),
+            _ => Err(tr!("File is empty")),
+        };
+    }
+    let map = doc.as_mapping().ok_or_else(|| tr!("Top level must be a mapping"))?;
+
+    match kind {
+        ConfigKind::Settings => {
+            if let Some(theme) = map.get("theme") {
+                if !theme.is_string() {
+                    return Err(tr!("`theme` must be a string"));
+                }
+            }
+        }
+        ConfigKind::Menu => {
+            require_items_with_id(map, "groups")?;
+            require_items_with_id(map, "plugins")?;
+        }
+        ConfigKind::Board => {
+            let columns = require_items_with_id(map, "columns")?;
+            if let Some(i) = columns.iter().position(|c| c.get("name").is_none()) {
+                return Err(tr!("columns[{}] is missing `name`", i));
+            }
+            if let Some(i) = columns.iter().position(|c| c.get("wip_limit").is_some_and(|w| !w.is_u64())) {
+                return Err(tr!("columns[{}].wip_limit must be a whole number", i));
+            }
+            if let Some(rules) = map.get("rules") {
+                if !rules.is_sequence() {
+                    return Err(tr!("`rules` must be a list"));
+                }
+            }
+        }
+        ConfigKind::Connectors => {
+            for (name, section) in map {
+                let name = name.as_str().unwrap_or("?");
+                if !section.is_mapping() {
+                    return Err(tr!("`{}` must be a mapping", name));
+                }
+                if let Some(enabled) = section.get("enabled") {
+                    if !enabled.is_bool() {
+                        return Err(tr!("`{}.enabled` must be true or false", name));
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `key` must be a list whose entries all carry an `id`
+fn require_items_with_id<'a>(
+    map: &'a serde_yaml::Mapping,
+    key: &str,
+) -> Result<&'a Vec<serde_yaml::Value>, String> {
+    let items = map
+        .get(key)
+        .and_then(|v| v.as_sequence())
+        .ok_or_else(|| tr!("`{}` must be a list", key))?;
+    if let Some(i) = items.iter().position(|item| item.get("id").is_none()) {
+        return Err(tr!("{}[{}] is missing `id`", key, i));
+    }
+    Ok(items)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_from_path() {
+        let p = PathBuf::from("/vault/.lifeos/board.yaml");
+        assert_eq!(ConfigKind::from_path(&p), Some(ConfigKind::Board));
+        assert_eq!(ConfigKind::from_path(&PathBuf::from("/vault/.lifeos/config.yaml")), None);
+    }
+
+    #[test]
+    fn test_validate_menu() {
+        let ok = "groups:\n  - id: work\nplugins:\n  - id: dashboard\n";
+        assert!(validate_config(ConfigKind::Menu, ok).is_ok());
+
+        let missing_id = "groups:\n  - name: work\nplugins: []\n";
+        let err = validate_config(ConfigKind::Menu, missing_id).unwrap_err();
+        assert!(err.contains("groups[0]"));
+    }
+
+    #[test]
+    fn test_validate_board_requires_names() {
+        let cfg = "columns:\n  - id: todo\n";
+        assert!(validate_config(ConfigKind::Board, cfg).is_err());
+        let cfg = "columns:\n  - id: todo\n    name: 计划中\n    wip_limit: three\n";
+        assert!(validate_config(ConfigKind::Board, cfg).is_err());
+    }
+
+    #[test]
+    fn test_validate_connectors() {
+        assert!(validate_config(ConfigKind::Connectors, "github:\n  enabled: false\n").is_ok());
+        assert!(validate_config(ConfigKind::Connectors, "github:\n  enabled: yes please\n").is_err());
+        assert!(validate_config(ConfigKind::Connectors, "# only comments\n").is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_broken_yaml() {
+        let err = validate_config(ConfigKind::Settings, "theme: [unclosed").unwrap_err();
+        // Locale-independent: both catalogs keep the word "YAML"
+        assert!(err.contains("YAML"));
+    }
+}
@@ -0,0 +1,86 @@
+use chrono::{Local, NaiveDate};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::services::notes::split_frontmatter;
+use crate::services::{notes, weather};
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Weather for `date` (default today) at the location in connectors.yaml
+#[tauri::command]
+pub async fn get_weather(vault_path: String, date: Option<String>) -> Result<weather::Weather, String> {
+    let date = match date {
+        Some(d) => parse_date(&d)?,
+        None => Local::now().date_naive(),
+    };
+    tokio::task::spawn_blocking(move || weather::for_date(Path::new(&vault_path), date))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+/// Fill the `weather` frontmatter of a diary or daily note when it is
+/// empty, using the note's `date`. Returns the value written, or None when
+/// the note already had one.
+#[tauri::command]
+pub async fn fill_weather(vault_path: String, path: String) -> Result<Option<String>, String> {
+    tokio::task::spawn_blocking(move || fill_note(Path::new(&vault_path), &PathBuf::from(path)))
+        .await
+        .map_err(|e| tr!("Task execution failed: {}", e))?
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn fill_note(vault: &Path, path: &Path) -> Result<Option<String>, String> {
+    let raw = fs::read_to_string(path).map_err(|e| tr!("Failed to read: {}", e))?;
+    let fm: serde_yaml::Value = split_frontmatter(&raw).0.and_then(|y| serde_yaml::from_str(y).ok()).unwrap_or_default();
+    if !needs_weather(&fm) {
+        return Ok(None);
+    }
+    let from_fm = fm.get("date").and_then(|d| d.as_str()).map(str::to_string);
+    let from_name = path.file_name().map(|n| n.to_string_lossy().to_string());
+    let date = [from_fm, from_name]
+        .into_iter()
+        .flatten()
+        .find_map(|d| NaiveDate::parse_from_str(d.get(..10)?, "%Y-%m-%d").ok())
+        .ok_or_else(|| tr!("Note has no date: {}", path.display()))?;
+
+    let text = weather::for_date(vault, date)?.text();
+    notes::write_field(path, "weather", Some(&serde_yaml::Value::String(text.clone())))?;
+    Ok(Some(text))
+}
+
+/// Missing, `~` or blank
+fn needs_weather(fm: &serde_yaml::Value) -> bool {
+    match fm.get("weather") {
+        None | Some(serde_yaml::Value::Null) => true,
+        Some(serde_yaml::Value::String(s)) => s.trim().is_empty(),
+        Some(_) => false,
+    }
+}
+
+fn parse_date(raw: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(raw.trim(), "%Y-%m-%d").map_err(|_| tr!("Invalid date (expected YYYY-MM-DD): {}", raw))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_needs_weather() {
+        let fm = |y: &str| serde_yaml::from_str::<serde_yaml::Value>(y).unwrap();
+        assert!(needs_weather(&fm("date: 2025-03-01\nweather: ~")));
+        assert!(needs_weather(&fm("date: 2025-03-01\nweather: ''")));
+        assert!(needs_weather(&fm("date: 2025-03-01")));
+        assert!(!needs_weather(&fm("weather: sunny")));
+    }
+}
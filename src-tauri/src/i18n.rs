@@ -0,0 +1,501 @@
+//! Message catalog for backend-originated text (errors, status) shown in the UI.
+//!
+//! Messages are written in English at the call site via `tr!`, gettext style:
+//! the English template is the lookup key, and `translate` swaps in the
+//! catalog entry for the active locale. Untranslated messages fall back to
+//! English, so new commands never need a catalog entry to work.
+
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    Zh,
+    En,
+}
+
+impl Locale {
+    /// Accepts `zh`, `zh-CN`, `en`, `en_US`, ... (case-insensitive)
+    pub fn parse(tag: &str) -> Option<Self> {
+        let lang = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        match lang.as_str() {
+            "zh" => Some(Locale::Zh),
+            "en" => Some(Locale::En),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Locale::Zh => "zh",
+            Locale::En => "en",
+        }
+    }
+}
+
+// The UI ships in Chinese, so that is the default until settings say otherwise
+static LOCALE: AtomicU8 = AtomicU8::new(0);
+
+pub fn locale() -> Locale {
+    match LOCALE.load(Ordering::Relaxed) {
+        1 => Locale::En,
+        _ => Locale::Zh,
+    }
+}
+
+pub fn set_locale(locale: Locale) {
+    LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+/// Pick up `language:` from the raw settings.yaml text, if present
+pub fn apply_settings(settings_yaml: &str) {
+    let Ok(doc) = serde_yaml::from_str::<serde_yaml::Value>(settings_yaml) else { return };
+    if let Some(locale) = doc.get("language").and_then(|v| v.as_str()).and_then(Locale::parse) {
+        set_locale(locale);
+    }
+}
+
+/// Format a message template in the active locale.
+/// `tr!("Connection failed: {}", e)` — placeholders are positional `{}` only.
+macro_rules! tr {
+    ($msg:literal) => {
+        $crate::i18n::translate($msg).to_string()
+    };
+    ($msg:literal, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::fill(
+            $crate::i18n::translate($msg),
+            &[$(&$arg as &dyn ::std::fmt::Display),+],
+        )
+    };
+}
+
+pub fn translate(msg: &'static str) -> &'static str {
+    match locale() {
+        Locale::En => msg,
+        Locale::Zh => zh(msg).unwrap_or(msg),
+    }
+}
+
+/// Substitute each `{}` in order; surplus placeholders are left as-is
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::with_capacity(template.len() + 16);
+    let mut args = args.iter();
+    let mut rest = template;
+    while let Some(idx) = rest.find("{}") {
+        out.push_str(&rest[..idx]);
+        match args.next() {
+            Some(arg) => out.push_str(&arg.to_string()),
+            None => out.push_str("{}"),
+        }
+        rest = &rest[idx + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Catalog
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn zh(msg: &str) -> Option<&'static str> {
+    Some(match msg {
+        // Generic
+        "Failed to create directory: {}" => "创建目录失败: {}",
+        "Failed to read: {}" => "读取失败: {}",
+        "Failed to parse: {}" => "解析失败: {}",
+        "Request failed: {}" => "请求失败: {}",
+        "Failed to serialize: {}" => "序列化失败: {}",
+        "Task execution failed: {}" => "任务执行失败: {}",
+        "{} is not supported on {}" => "{} 在 {} 平台上不可用",
+        "Path does not exist: {}" => "路径不存在: {}",
+        "Cannot find home dir" => "找不到用户主目录",
+        "Invalid UTF-8 output: {}" => "输出不是有效的 UTF-8: {}",
+        "Unsupported language: {}" => "不支持的语言: {}",
+        "Invalid date (expected YYYY-MM-DD): {}" => "无效的日期（应为 YYYY-MM-DD）: {}",
+        "Task text is empty" => "任务内容为空",
+        "No password saved for {}" => "{} 未保存密码",
+        "AI is disabled in settings" => "设置中已关闭 AI",
+        "Invalid range: {}" => "无效的时间范围: {}",
+
+        // File system / vault
+        "read_file failed: {}" => "读取文件失败: {}",
+        "write_file failed: {}" => "写入文件失败: {}",
+        "create_dir_all failed: {}" => "创建目录失败: {}",
+        "Menu config not found" => "未找到菜单配置",
+        "Board config not found" => "未找到看板配置",
+
+        // Config watcher
+        "Config dir does not exist: {}" => "配置目录不存在: {}",
+        "Failed to create config watcher: {}" => "创建配置监听失败: {}",
+        "Failed to watch {}: {}" => "监听 {} 失败: {}",
+        "Invalid YAML: {}" => "YAML 格式错误: {}",
+        "File is empty" => "文件为空",
+        "Top level must be a mapping" => "顶层必须是键值映射",
+        "`theme` must be a string" => "`theme` 必须是字符串",
+        "`{}` must be a list" => "`{}` 必须是列表",
+        "{}[{}] is missing `id`" => "{}[{}] 缺少 `id`",
+        "columns[{}] is missing `name`" => "columns[{}] 缺少 `name`",
+        "columns[{}].wip_limit must be a whole number" => "columns[{}].wip_limit 必须是整数",
+        "`rules` must be a list" => "`rules` 必须是列表",
+        "`{}` must be a mapping" => "`{}` 必须是键值映射",
+        "`{}.enabled` must be true or false" => "`{}.enabled` 必须是 true 或 false",
+
+        // People
+        "Person not found: {}" => "未找到联系人: {}",
+        "Invalid slug: {}" => "无效的标识: {}",
+        "Name is required" => "姓名不能为空",
+
+        // Occasion reminders
+        "Birthday: {}" => "生日提醒：{}",
+        "Anniversary: {}" => "纪念日提醒：{}",
+        "Today" => "今天",
+        "Tomorrow" => "明天",
+        "In {} days ({})" => "{} 天后（{}）",
+        "{} years" => "{} 周年",
+
+        // Finance
+        "Unsupported import format: {}" => "不支持的导入格式: {}",
+        "Transaction not found: {}" => "未找到交易记录: {}",
+        "Invalid month (expected YYYY-MM): {}" => "月份格式无效（应为 YYYY-MM）: {}",
+        "Could not find date and amount columns in CSV" => "CSV 中未找到日期和金额列",
+
+        // Books
+        "Book not found: {}" => "未找到书籍: {}",
+        "Title is required" => "标题不能为空",
+        "Highlight is empty" => "摘录内容为空",
+        "Invalid ISBN: {}" => "无效的 ISBN: {}",
+        "No book found for ISBN {}" => "未找到 ISBN 为 {} 的书",
+        "Unrecognized highlights file: {}" => "无法识别的摘录文件: {}",
+        "Could not find title and highlight columns in CSV" => "CSV 中未找到书名和摘录列",
+        "Not an EPUB or PDF file: {}" => "不是 EPUB 或 PDF 文件: {}",
+        "Invalid EPUB: {}" => "无效的 EPUB: {}",
+
+        // Watchlist
+        "Watchlist item not found: {}" => "未找到片单条目: {}",
+        "Invalid status: {}" => "无效的状态: {}",
+        "TMDB API key is not set (tmdb.api_key in connectors.yaml)" => "未设置 TMDB API Key（connectors.yaml 中的 tmdb.api_key）",
+
+        // Nutrition
+        "Meal text is empty" => "餐食内容为空",
+        "Invalid date/time: {}" => "无效的日期时间: {}",
+        "Meal not found: {}" => "未找到餐食记录: {}",
+        "Could not read a calorie estimate from: {}" => "无法从以下内容读取热量估算: {}",
+
+        // Medication
+        "Medication not found: {}" => "未找到药品: {}",
+        "Time to take {}" => "该服用{}了",
+
+        // Sleep
+        "No sleep entry for {}" => "{} 没有睡眠记录",
+
+        // Trips
+        "Trip not found: {}" => "未找到行程: {}",
+        "Trip ends before it starts" => "行程结束日期早于开始日期",
+        "Email not in the mail cache: {}" => "邮件缓存中没有该邮件: {}",
+        "Booking not found: {}" => "未找到预订: {}",
+        "Packing template not found: {}" => "未找到行李模板: {}",
+        "Packing item not found: {}" => "未找到行李项: {}",
+
+        // Location
+        "Location history is disabled in settings" => "位置记录已在设置中关闭",
+        "No coordinates in helper output: {}" => "定位输出中没有坐标: {}",
+        "Invalid coordinates: {}, {}" => "无效的坐标: {}, {}",
+        "Failed to delete: {}" => "删除失败: {}",
+
+        // Weather
+        "Weather is disabled in connectors.yaml" => "天气已在 connectors.yaml 中关闭",
+        "Set weather.city or latitude/longitude in connectors.yaml" => "请在 connectors.yaml 中设置 weather.city 或经纬度",
+        "Unknown city: {}" => "未找到城市: {}",
+        "No weather data for {}" => "{} 没有天气数据",
+        "Note has no date: {}" => "笔记缺少日期: {}",
+        "Clear" => "晴",
+        "Partly cloudy" => "多云",
+        "Overcast" => "阴",
+        "Fog" => "雾",
+        "Drizzle" => "毛毛雨",
+        "Rain" => "雨",
+        "Heavy rain" => "大雨",
+        "Snow" => "雪",
+        "Showers" => "阵雨",
+        "Thunderstorm" => "雷阵雨",
+        "Unknown" => "未知",
+
+        // Secrets
+        "Secret value is empty" => "密钥内容为空",
+        "Secret value can't contain line breaks" => "密钥内容不能包含换行",
+        "Invalid secret name: {}" => "无效的密钥名称: {}",
+        "Secret not found: {}" => "未找到密钥: {}",
+        "Keychain error: {}" => "钥匙串错误: {}",
+        "Export profile {}" => "导出配置 {}",
+        "Export password must be kept in the Keychain: {}" => "导出密码须保存在钥匙串中: {}",
+
+        // PDF
+        "Failed to extract PDF text: {}" => "提取 PDF 文本失败: {}",
+        "Not a PDF file: {}" => "不是 PDF 文件: {}",
+        "Annotation is empty" => "批注内容为空",
+
+        // Screenshots
+        "Unknown screenshot mode: {}" => "未知的截图模式: {}",
+
+        // Templates
+        "Template not found: {}" => "未找到模板: {}",
+        "Project name is required" => "项目名称不能为空",
+        "Missing answers: {}" => "缺少回答: {}",
+
+        // Note history
+        "Unknown version: {}" => "未知的版本: {}",
+
+        // Vault sync
+        "Sync is already running" => "同步正在进行中",
+        "Sync is not configured: {}" => "同步未配置: {}",
+        "Unknown sync backend: {}" => "未知的同步后端: {}",
+        "Missing on remote: {}" => "远端缺少文件: {}",
+        "The vault is empty; refusing to sync deletions" => "仓库为空，已拒绝同步删除",
+
+        // Board
+        "WIP limit reached for {}: {}/{}" => "{} 已达到在制上限: {}/{}",
+        "Project not found: {}" => "未找到项目: {}",
+        "Project has no repo: {}" => "项目未设置 repo: {}",
+        "Not a git repository: {}" => "不是 Git 仓库: {}",
+
+        // Focus
+        "Focus length must be between 1 and {} minutes" => "专注时长须在 1 到 {} 分钟之间",
+
+        // Habits
+        "Vacation ends before it starts: {} – {}" => "假期结束早于开始: {} – {}",
+        "Habit pause not found: {}" => "未找到习惯暂停记录: {}",
+
+        // Activity
+        "Range starts after it ends: {} – {}" => "起始日期晚于结束日期: {} – {}",
+
+        // Triage
+        "Triage item not found: {}" => "未找到待处理项: {}",
+        "Delegating needs someone to delegate to" => "委派需要指定委派对象",
+
+        // Drop
+        "Not a calendar file: {}" => "不是日历文件: {}",
+        "Unsupported file type: {}" => "不支持的文件类型: {}",
+        "{} events" => "{} 个日程",
+
+        // Mail accounts
+        "Invalid email address: {}" => "邮箱地址无效: {}",
+        "Identity not found: {}" => "未找到发件身份: {}",
+        "Account not found: {}" => "未找到邮箱账户: {}",
+        "Failed to list folders: {}" => "获取文件夹列表失败: {}",
+        "DNS lookup failed: {}" => "域名解析失败: {}",
+        "Certificate not trusted: {}" => "证书不受信任: {}",
+        "Unencrypted connection" => "未加密连接",
+        "Negotiated with STARTTLS at login" => "登录时通过 STARTTLS 加密",
+        "{} messages in INBOX" => "收件箱中有 {} 封邮件",
+        "{} folders" => "{} 个文件夹",
+
+        // Session state
+        "Invalid state: {}" => "无效的状态: {}",
+
+        // Quick switcher
+        "Unknown scope: {}" => "未知的搜索范围: {}",
+
+        // Undo journal
+        "Cannot undo: {}" => "无法撤销: {}",
+        "Cannot undo, file is gone: {}" => "无法撤销，文件已不存在: {}",
+        "Cannot undo, path is in use: {}" => "无法撤销，路径已被占用: {}",
+        "Path is outside the vault: {}" => "路径不在仓库内: {}",
+        "Path already exists: {}" => "路径已存在: {}",
+        "Nothing to merge" => "没有可合并的笔记",
+        "No headings at level {} in {}" => "没有 {} 级标题: {}",
+        "Heading not found: {}" => "未找到标题: {}",
+        "Not a canvas file: {}" => "不是画布文件: {}",
+        "Invalid canvas: {}" => "画布无效: {}",
+        "Duplicate id: {}" => "重复的 ID: {}",
+        "Node {} has an invalid size" => "节点 {} 的尺寸无效",
+        "Node {} has no {}" => "节点 {} 缺少 {}",
+        "Edge {} points at missing node {}" => "连线 {} 指向不存在的节点 {}",
+        "Invalid color: {}" => "无效的颜色: {}",
+        "Unsupported book format: {}" => "不支持的书籍格式: {}",
+        "No diary entries in {}" => "{} 年没有日记",
+        "Diary {}" => "{} 年日记",
+        "Energy: {}" => "精力: {}",
+        "Rollback failed: {}" => "回滚失败: {}",
+        "Note is locked by {} until {}" => "笔记已被 {} 锁定，直到 {}",
+        "No note with ID {}" => "找不到 ID 为 {} 的笔记",
+        "Checksum mismatch after copying: {}" => "复制后校验失败: {}",
+
+        // Frontmatter schemas
+        "Invalid frontmatter: {}" => "无效的 frontmatter: {}",
+        "Missing required field: {}" => "缺少必填字段: {}",
+        "{} must be a number" => "{} 必须是数字",
+        "{} must be a whole number" => "{} 必须是整数",
+        "{} must be a date (YYYY-MM-DD)" => "{} 必须是日期 (YYYY-MM-DD)",
+        "{} must be true or false" => "{} 必须是 true 或 false",
+        "{} must be a list" => "{} 必须是列表",
+        "{} must be one of: {}" => "{} 必须是以下之一: {}",
+        "{} must be at least {}" => "{} 不能小于 {}",
+        "{} must be at most {}" => "{} 不能大于 {}",
+
+        // Email follow-ups
+        "Email not found: {}" => "未找到邮件: {}",
+        "Email has no Message-ID: {}" => "邮件缺少 Message-ID: {}",
+        "Waiting for reply: {}" => "等待回复：{}",
+        "Overdue since {}" => "已于 {} 逾期",
+
+        // Meeting notes
+        "Calendar event not found: {}" => "未找到日程: {}",
+
+        // Time blocking
+        "Task not found: {}" => "未找到任务: {}",
+        "Invalid duration: {}" => "无效的时长: {}",
+
+        // Task export
+        "Task service is not configured: {}" => "任务服务未配置: {}",
+
+        // Google
+        "Google is not configured: {}" => "Google 未配置: {}",
+        "Google sign-in failed: {}" => "Google 登录失败: {}",
+        "Not signed in to Google" => "尚未登录 Google",
+
+        // CardDAV
+        "CardDAV is not configured: {}" => "CardDAV 未配置: {}",
+
+        // Chat import
+        "Unrecognized chat export: {}" => "无法识别的聊天记录导出: {}",
+
+        // iMessage
+        "Full Disk Access is required to read Messages" => "读取“信息”需要“完全磁盘访问权限”",
+        "Failed to read Messages: {}" => "读取“信息”失败: {}",
+        "Conversation not found: {}" => "未找到会话: {}",
+        "[Attachment]" => "[附件]",
+        "Me" => "我",
+
+        // Export profiles
+        "Profile name is required" => "配置名称不能为空",
+        "Duplicate profile: {}" => "配置重名: {}",
+        "Export profile not found: {}" => "未找到导出配置: {}",
+        "Unknown export format: {}" => "未知的导出格式: {}",
+        "Export target must be an absolute path: {}" => "导出目标必须是绝对路径: {}",
+        "Export target must be outside the vault: {}" => "导出目标必须在仓库之外: {}",
+        "Failed to copy: {}" => "复制失败: {}",
+        "Failed to write archive: {}" => "写入压缩包失败: {}",
+
+        // Subscriptions
+        "Subscription not found: {}" => "未找到订阅: {}",
+        "Renewal: {}" => "续费提醒：{}",
+
+        // Automations
+        "Failed to create file watcher: {}" => "创建文件监听失败: {}",
+        "Rule has no actions" => "规则没有任何动作",
+        "Invalid interval: {}" => "无效的间隔: {}",
+        "Invalid time (expected HH:MM): {}" => "无效的时间（应为 HH:MM）: {}",
+        "Schedule needs exactly one of `every` or `at`" => "定时触发需要且只能设置 `every` 或 `at` 之一",
+        "move-file needs `from` for this trigger" => "该触发器下 move-file 需要指定 `from`",
+        "Failed to move {}: {}" => "移动 {} 失败: {}",
+        "Note already exists: {}" => "笔记已存在: {}",
+        "Path must stay inside the vault: {}" => "路径必须位于仓库内: {}",
+        "Refused remote path: {}" => "已拒绝远端路径: {}",
+        "Webhook failed: {}" => "Webhook 请求失败: {}",
+        "Automation not found: {}" => "未找到自动化规则: {}",
+
+        // System tools
+        "Failed to open in Finder: {}" => "在访达中打开失败: {}",
+        "Failed to open in file manager: {}" => "在文件管理器中打开失败: {}",
+        "Failed to open in {}: {}" => "用 {} 打开失败: {}",
+        "Unknown editor: {}" => "未知的编辑器: {}",
+        "File not found: {}" => "文件不存在: {}",
+        "Failed to run '{}': {}" => "运行 '{}' 失败: {}",
+        "Failed to run shortcut '{}': {}" => "运行快捷指令 '{}' 失败: {}",
+        "Failed to write plist: {}" => "写入 plist 失败: {}",
+        "Failed to load task: {}" => "加载定时任务失败: {}",
+        "Failed to delete plist: {}" => "删除 plist 失败: {}",
+        "Unknown internal job: {}" => "未知的内置任务: {}",
+        "lifeos CLI not found: {}" => "找不到 lifeos 命令行工具: {}",
+        "Failed to run AppleScript: {}" => "运行 AppleScript 失败: {}",
+        "AppleScript error: {}" => "AppleScript 错误: {}",
+
+        // Permissions
+        "Unknown permission: {}" => "未知的权限: {}",
+        "Only needed on macOS" => "仅 macOS 需要",
+        "Allow Life OS to control {} in System Settings → Privacy & Security → Automation" => "请在“系统设置 → 隐私与安全性 → 自动化”中允许 Life OS 控制 {}",
+        "Add Life OS under System Settings → Privacy & Security → Full Disk Access to read Messages" => "请在“系统设置 → 隐私与安全性 → 完全磁盘访问权限”中添加 Life OS 以读取“信息”",
+        "Turn on notifications for Life OS in System Settings → Notifications" => "请在“系统设置 → 通知”中为 Life OS 打开通知",
+        "Add Life OS under System Settings → Privacy & Security → Accessibility" => "请在“系统设置 → 隐私与安全性 → 辅助功能”中添加 Life OS",
+
+        // Mail: connection & protocol
+        "Failed to create TLS connector: {}" => "TLS 创建失败: {}",
+        "Connection failed: {}" => "连接失败: {}",
+        "IMAP connection failed: {}" => "IMAP 连接失败: {}",
+        "TLS handshake failed: {}" => "TLS 握手失败: {}",
+        "STARTTLS failed: {}" => "STARTTLS 失败: {}",
+        "Failed to read IMAP response: {}" => "读取 IMAP 响应失败: {}",
+        "IMAP IDLE failed: {}" => "IMAP IDLE 失败: {}",
+        "Failed to send ID command: {}" => "发送 ID 命令失败: {}",
+        "Flush failed: {}" => "flush 失败: {}",
+        "Login failed: {}" => "登录失败: {}",
+        "Login failed" => "登录失败",
+        "USER command failed: {}" => "USER 命令失败: {}",
+        "USER command failed" => "USER 命令失败",
+        "Failed to send: {}" => "发送失败: {}",
+        "Failed to select folder: {}" => "选择文件夹失败: {}",
+        "Failed to fetch emails: {}" => "拉取邮件失败: {}",
+
+        // Mail: local cache
+        "Failed to save EML file: {}" => "保存 EML 文件失败: {}",
+        "Failed to write sync_state: {}" => "写入 sync_state 失败: {}",
+        "Failed to write index file: {}" => "写入索引文件失败: {}",
+        "Failed to read index: {}" => "读取索引失败: {}",
+        "Failed to parse index: {}" => "解析索引失败: {}",
+        "Failed to write index: {}" => "写入索引失败: {}",
+        "Failed to read email: {}" => "读取邮件失败: {}",
+        "Failed to parse email: {}" => "解析邮件失败: {}",
+        "Email file not found: {}" => "邮件文件不存在: {}",
+        "No attachment {} in this email" => "邮件中没有第 {} 个附件",
+        "Unrecognized mail archive: {}" => "无法识别的邮件归档: {}",
+        "Invalid .emlx file" => "无效的 .emlx 文件",
+        "Not an Outlook message" => "不是 Outlook 邮件",
+
+        // Mail: actions
+        "Invalid sender address: {} (from_address: {})" => "发件人地址无效: {} (from_address: {})",
+        "Invalid recipient address: {}" => "收件人地址无效: {}",
+        "Failed to build email: {}" => "构建邮件失败: {}",
+        "Failed to read attachment {}: {}" => "读取附件 {} 失败: {}",
+        "SMTP connection failed: {}" => "SMTP 连接失败: {}",
+        "Failed to mark as deleted: {}" => "标记删除失败: {}",
+        "Failed to expunge: {}" => "永久删除失败: {}",
+        "Failed to archive email: {}" => "归档邮件失败: {}",
+        "Mail sync failing: {}" => "邮箱同步失败: {}",
+        "{} new messages" => "{} 封新邮件",
+        "The server rejected the login; check the password or app password" => "服务器拒绝登录，请检查密码或授权码",
+        "Failed to mark as read/unread: {}" => "标记已读/未读失败: {}",
+        "Failed to open link: {}" => "打开链接失败: {}",
+        _ => return None,
+    })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_parse() {
+        assert_eq!(Locale::parse("en-US"), Some(Locale::En));
+        assert_eq!(Locale::parse("zh_CN"), Some(Locale::Zh));
+        assert_eq!(Locale::parse("fr"), None);
+    }
+
+    #[test]
+    fn test_fill_placeholders() {
+        assert_eq!(fill("{} of {}", &[&1, &"two"]), "1 of two");
+        assert_eq!(fill("missing {}", &[]), "missing {}");
+    }
+
+    #[test]
+    fn test_catalog_templates_keep_placeholder_count() {
+        // A translation with fewer `{}` than its source would silently drop details
+        for src in ["Connection failed: {}", "Failed to watch {}: {}", "Invalid sender address: {} (from_address: {})"] {
+            let zh = zh(src).unwrap();
+            assert_eq!(src.matches("{}").count(), zh.matches("{}").count(), "{src}");
+        }
+    }
+}
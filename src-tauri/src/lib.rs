@@ -1,17 +1,48 @@
+#[macro_use]
+mod i18n;
 mod commands;
+pub mod services;
 
-use commands::{fs_commands, vault_commands, extra_commands, email_commands};
+use commands::{fs_commands, vault_commands, extra_commands, email_commands, platform_commands, watch_commands, automation_commands, people_commands, occasion_commands, finance_commands, subscription_commands, book_commands, media_commands, nutrition_commands, medication_commands, sleep_commands, mood_commands, trip_commands, location_commands, weather_commands, secret_commands, asset_commands, pdf_commands, screenshot_commands, template_commands, history_commands, sync_commands, export_commands, board_commands, agenda_commands, focus_commands, habit_commands, activity_commands, triage_commands, task_commands, editor_commands, drop_commands, mail_setup_commands, background_commands, state_commands, spotlight_commands, switcher_commands, followup_commands, meeting_commands, planner_commands, prompt_commands, link_commands, task_export_commands, calendar_sync_commands, contact_sync_commands, chat_import_commands, imessage_commands, commit_summary_commands, dev_env_commands, storage_commands, mail_digest_commands, reader_commands, outbox_commands, audit_commands, schema_commands, note_commands, canvas_commands};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    let builder = tauri::Builder::default()
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
-        .invoke_handler(tauri::generate_handler![
+        .plugin(tauri_plugin_notification::init())
+        .manage(email_commands::AutoSync::default());
+
+    // Mobile sandboxes have no writable $HOME: keep the vault pointer in the app config dir
+    #[cfg(mobile)]
+    let builder = builder.setup(|app| {
+        use tauri::Manager;
+        let dir = app.path().app_config_dir()?;
+        std::fs::create_dir_all(&dir)?;
+        services::set_config_dir(dir);
+        Ok(())
+    });
+
+    // Restore the window, start hidden from the login item, hide instead of close
+    #[cfg(desktop)]
+    let builder = builder
+        .setup(|app| {
+            state_commands::restore_window(app.handle());
+            background_commands::setup(app.handle());
+            Ok(())
+        })
+        .on_window_event(|window, event| {
+            state_commands::on_window_event(window, event);
+            background_commands::on_window_event(window, event);
+        });
+
+    builder
+        .invoke_handler(audit_commands::audited(tauri::generate_handler![
             // Vault / config
             vault_commands::get_vault_path,
             vault_commands::set_vault_path,
+            vault_commands::get_default_vault_path,
             vault_commands::init_vault,
             vault_commands::load_menu_config,
             vault_commands::save_menu_config,
@@ -20,24 +51,318 @@ pub fn run() {
             vault_commands::regenerate_skills,
             vault_commands::load_app_settings,
             vault_commands::save_app_settings,
+            vault_commands::get_locale,
+            vault_commands::set_locale,
+            // Config hot-reload
+            watch_commands::start_config_watch,
+            watch_commands::stop_config_watch,
+            watch_commands::validate_configs,
             // Generic file system
             fs_commands::read_file,
+            fs_commands::read_file_versioned,
             fs_commands::write_file,
             fs_commands::delete_file,
             fs_commands::list_dir,
             fs_commands::file_exists,
             fs_commands::create_dir_all,
             fs_commands::move_file,
+            fs_commands::batch_fs_operations,
+            fs_commands::list_recent_operations,
+            fs_commands::undo_last_operation,
+            fs_commands::lock_note,
+            fs_commands::unlock_note,
+            fs_commands::get_note_lock,
+            fs_commands::list_note_locks,
+            fs_commands::resolve_note_id,
+            fs_commands::assign_note_id,
             // Parsed note access
             fs_commands::read_note,
             fs_commands::write_note,
             fs_commands::list_notes,
+            fs_commands::search_notes,
+            fs_commands::resolve_embeds,
+            fs_commands::add_task,
+            // People
+            people_commands::list_people,
+            people_commands::get_person,
+            people_commands::save_person,
+            people_commands::delete_person,
+            people_commands::find_person_by_email,
+            people_commands::link_people,
+            people_commands::get_person_timeline,
+            // Birthdays & anniversaries
+            occasion_commands::get_upcoming_occasions,
+            occasion_commands::start_occasion_reminders,
+            occasion_commands::stop_occasion_reminders,
+            // Finance
+            finance_commands::import_transactions,
+            finance_commands::list_transactions,
+            finance_commands::set_transaction_category,
+            finance_commands::apply_category_rules,
+            finance_commands::get_spending_summary,
+            // Subscriptions
+            subscription_commands::list_subscriptions,
+            subscription_commands::save_subscription,
+            subscription_commands::delete_subscription,
+            subscription_commands::get_subscription_burn,
+            subscription_commands::start_renewal_reminders,
+            subscription_commands::stop_renewal_reminders,
+            // Books
+            book_commands::list_books,
+            book_commands::get_book,
+            book_commands::save_book,
+            book_commands::delete_book,
+            book_commands::lookup_isbn,
+            book_commands::add_book_by_isbn,
+            book_commands::update_book_progress,
+            book_commands::add_book_highlight,
+            book_commands::register_reading_file,
+            book_commands::save_book_position,
+            book_commands::import_highlights,
+            book_commands::get_reading_stats,
+            // Watchlist
+            media_commands::list_media,
+            media_commands::get_media,
+            media_commands::save_media,
+            media_commands::delete_media,
+            media_commands::search_tmdb,
+            media_commands::add_media_from_tmdb,
+            media_commands::set_media_status,
+            media_commands::update_media_progress,
+            // Nutrition
+            nutrition_commands::log_meal,
+            nutrition_commands::list_meals,
+            nutrition_commands::delete_meal,
+            nutrition_commands::estimate_missing_calories,
+            nutrition_commands::get_nutrition_summary,
+            // Medication
+            medication_commands::list_medications,
+            medication_commands::save_medication,
+            medication_commands::delete_medication,
+            medication_commands::log_medication_dose,
+            medication_commands::get_medication_day,
+            medication_commands::get_medication_adherence,
+            medication_commands::start_medication_reminders,
+            medication_commands::stop_medication_reminders,
+            // Sleep
+            sleep_commands::log_sleep,
+            sleep_commands::delete_sleep,
+            sleep_commands::import_sleep,
+            sleep_commands::list_sleep,
+            sleep_commands::get_sleep_trends,
+            // Mood
+            mood_commands::get_mood_trends,
+            // Trips
+            trip_commands::list_trips,
+            trip_commands::get_trip,
+            trip_commands::save_trip,
+            trip_commands::delete_trip,
+            trip_commands::link_trip_email,
+            trip_commands::unlink_trip_email,
+            trip_commands::list_packing_templates,
+            trip_commands::generate_packing_list,
+            trip_commands::set_packing_item,
+            trip_commands::get_trip_itinerary,
+            // Location
+            location_commands::get_location_settings,
+            location_commands::save_location_settings,
+            location_commands::record_location,
+            location_commands::import_location_inbox,
+            location_commands::get_location_day,
+            location_commands::purge_location_history,
+            location_commands::start_location_logger,
+            location_commands::stop_location_logger,
+            // Weather
+            weather_commands::get_weather,
+            weather_commands::fill_weather,
+            // Secrets
+            secret_commands::store_secret,
+            secret_commands::get_secret,
+            secret_commands::delete_secret,
+            secret_commands::list_secrets,
+            // Assets
+            asset_commands::rebuild_asset_index,
+            asset_commands::list_assets,
+            asset_commands::find_orphaned_assets,
+            // PDF
+            pdf_commands::extract_pdf_text,
+            pdf_commands::index_pdfs,
+            pdf_commands::create_pdf_annotation_note,
+            pdf_commands::add_pdf_annotation,
+            // Screenshots
+            screenshot_commands::capture_screenshot,
+            // Templates
+            template_commands::list_templates,
+            template_commands::instantiate_template,
+            template_commands::list_project_templates,
+            template_commands::create_project_from_template,
+            // Note history
+            history_commands::list_note_versions,
+            history_commands::snapshot_note,
+            history_commands::get_note_version,
+            history_commands::get_note_diff,
+            // Vault sync
+            sync_commands::sync_vault,
+            sync_commands::get_sync_status,
+            sync_commands::start_sync_loop,
+            sync_commands::stop_sync_loop,
+            // Export profiles
+            export_commands::get_export_profiles,
+            export_commands::save_export_profiles,
+            export_commands::run_export,
+            export_commands::get_export_status,
+            export_commands::start_export_scheduler,
+            export_commands::stop_export_scheduler,
+            export_commands::export_diary_book,
+            // Board
+            board_commands::move_project,
+            board_commands::get_project_dev_status,
+            // Agenda
+            agenda_commands::get_daily_agenda,
+            // Focus
+            focus_commands::get_focus_settings,
+            focus_commands::save_focus_settings,
+            focus_commands::start_focus,
+            focus_commands::stop_focus,
+            focus_commands::get_focus_status,
+            focus_commands::get_time_entries,
+            // Habits
+            habit_commands::get_habit_streaks,
+            habit_commands::list_habit_pauses,
+            habit_commands::set_habit_vacation,
+            habit_commands::freeze_habit_day,
+            habit_commands::remove_habit_pause,
+            // Activity
+            activity_commands::get_activity_heatmap,
+            // Triage
+            triage_commands::get_triage_queue,
+            triage_commands::triage_item,
+            triage_commands::get_triage_history,
+            // Tasks
+            task_commands::get_next_actions,
+            task_commands::get_blocked_tasks,
+            task_commands::suggest_tasks,
+            // Drop
+            drop_commands::ingest_dropped_files,
+            // Background mode
+            background_commands::get_background_settings,
+            background_commands::save_background_settings,
+            background_commands::start_background_agent,
+            background_commands::stop_background_agent,
+            background_commands::get_launch_at_login,
+            background_commands::set_launch_at_login,
+            // Session state
+            state_commands::get_app_state,
+            state_commands::set_app_state,
+            state_commands::set_view_state,
+            // Spotlight
+            spotlight_commands::get_spotlight_settings,
+            spotlight_commands::reindex_spotlight,
+            spotlight_commands::set_spotlight_enabled,
+            // Quick switcher
+            switcher_commands::fuzzy_find,
+            switcher_commands::refresh_fuzzy_index,
+            // Email follow-ups
+            followup_commands::track_followup,
+            followup_commands::cancel_followup,
+            followup_commands::get_pending_followups,
+            followup_commands::start_followup_reminders,
+            followup_commands::stop_followup_reminders,
+            // Meeting notes
+            meeting_commands::create_meeting_note,
+            meeting_commands::find_meeting_note,
+            // Time blocking
+            planner_commands::schedule_task,
+            planner_commands::unschedule_task,
+            planner_commands::get_day_plan,
+            // Journaling prompts
+            prompt_commands::list_journal_prompts,
+            prompt_commands::insert_journal_prompt,
+            // Link integrity
+            link_commands::check_links,
+            link_commands::fix_links,
+            // Frontmatter schemas
+            schema_commands::list_schemas,
+            schema_commands::validate_notes,
+            // Note restructuring
+            note_commands::merge_notes,
+            note_commands::split_note,
+            note_commands::get_note_outline,
+            note_commands::get_section,
+            // Canvas
+            canvas_commands::list_canvases,
+            canvas_commands::read_canvas,
+            canvas_commands::create_canvas,
+            canvas_commands::save_canvas,
+            canvas_commands::delete_canvas,
+            canvas_commands::validate_canvas,
+            canvas_commands::generate_canvas_thumbnail,
+            // Todoist / TickTick
+            task_export_commands::push_tasks,
+            task_export_commands::pull_task_status,
+            task_export_commands::list_exported_tasks,
+            // Google Calendar
+            calendar_sync_commands::google_status,
+            calendar_sync_commands::google_sign_in,
+            calendar_sync_commands::google_sign_out,
+            calendar_sync_commands::sync_google_calendar,
+            calendar_sync_commands::get_calendar_sync_status,
+            calendar_sync_commands::start_calendar_sync_loop,
+            calendar_sync_commands::stop_calendar_sync_loop,
+            // CardDAV
+            contact_sync_commands::sync_contacts,
+            contact_sync_commands::get_contact_sync_status,
+            // Chat import
+            chat_import_commands::import_chat,
+            // iMessage
+            imessage_commands::get_imessage_access,
+            imessage_commands::open_full_disk_access_settings,
+            imessage_commands::list_imessage_conversations,
+            imessage_commands::get_person_imessages,
+            imessage_commands::archive_imessage_threads,
+            // Commit summary
+            commit_summary_commands::append_commit_summary,
+            commit_summary_commands::start_commit_summary_loop,
+            commit_summary_commands::stop_commit_summary_loop,
+            // Mail digest
+            mail_digest_commands::generate_mail_digest,
+            mail_digest_commands::start_mail_digest_loop,
+            mail_digest_commands::stop_mail_digest_loop,
+            // Reader
+            reader_commands::get_reader_view,
+            reader_commands::save_reading_position,
+            // Outbox
+            outbox_commands::get_outbox,
+            outbox_commands::flush_outbox,
+            outbox_commands::discard_outbox_operation,
+            outbox_commands::start_outbox_loop,
+            outbox_commands::stop_outbox_loop,
+            // Audit log
+            audit_commands::query_audit_log,
+            // Dev environment
+            dev_env_commands::get_dev_environment,
+            // Storage
+            storage_commands::analyze_storage,
+            // Automations
+            automation_commands::start_automations,
+            automation_commands::stop_automations,
+            automation_commands::list_automations,
+            automation_commands::run_automation,
+            automation_commands::get_automation_runs,
+            // Platform capabilities
+            platform_commands::get_platform_info,
+            platform_commands::check_system_permissions,
+            platform_commands::open_permission_settings,
             // Extra: system & tools
             extra_commands::open_in_finder,
+            editor_commands::open_in_editor,
+            editor_commands::get_editor_settings,
+            editor_commands::save_editor_settings,
             extra_commands::run_shell_command,
             extra_commands::run_shortcut,
             // Extra: git scanner
             extra_commands::scan_git_repos,
+            extra_commands::scan_code_todos,
             // Extra: skills manager
             extra_commands::get_skill_paths,
             extra_commands::list_skill_files,
@@ -45,20 +370,50 @@ pub fn run() {
             extra_commands::create_launchd_task,
             extra_commands::list_launchd_tasks,
             extra_commands::delete_launchd_task,
+            extra_commands::list_internal_jobs,
             // Apple Notes
             extra_commands::get_apple_notes,
             extra_commands::create_apple_note,
             extra_commands::update_apple_note,
             // Email: IMAP sync
             email_commands::imap_sync,
+            email_commands::imap_sync_all,
             email_commands::get_cached_emails,
             email_commands::get_email_content,
+            email_commands::load_remote_content,
+            email_commands::download_attachment,
             email_commands::list_email_folders,
+            email_commands::import_eml_files,
+            email_commands::scan_mail_archive,
+            email_commands::import_mail_archive,
+            email_commands::discover_email_folders,
+            email_commands::set_synced_folders,
+            email_commands::sync_all_accounts,
+            email_commands::find_duplicate_emails,
+            email_commands::get_unified_inbox,
+            email_commands::get_account_health,
+            email_commands::start_mail_idle,
+            email_commands::stop_mail_idle,
+            email_commands::start_auto_sync,
+            email_commands::stop_auto_sync,
+            email_commands::connect_gmail,
+            mail_setup_commands::test_email_account,
+            mail_setup_commands::discover_email_settings,
             email_commands::send_email,
+            email_commands::list_email_identities,
+            email_commands::save_email_identity,
+            email_commands::delete_email_identity,
             email_commands::delete_email,
             email_commands::mark_email_read,
+            email_commands::archive_email,
             email_commands::open_external_url,
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running Life OS");
+        ]))
+        .build(tauri::generate_context!())
+        .expect("error while running Life OS")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                state_commands::flush();
+            }
+            background_commands::on_run_event(app, event);
+        });
 }
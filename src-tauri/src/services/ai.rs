@@ -0,0 +1,42 @@
+//! One-shot prompts to the AI CLI configured in settings (`claudeCodeEnabled`
+//! / `claudeCodePath`, the same one the chat module uses).
+
+use std::fs;
+use std::path::Path;
+
+/// Run `<cli> -p <prompt>` and return its stdout, trimmed
+pub fn ask(vault: &Path, prompt: &str) -> Result<String, String> {
+    let settings: serde_yaml::Value = fs::read_to_string(vault.join(".lifeos/settings.yaml"))
+        .ok()
+        .and_then(|raw| serde_yaml::from_str(&raw).ok())
+        .unwrap_or_default();
+    if settings.get("claudeCodeEnabled").and_then(|v| v.as_bool()) == Some(false) {
+        return Err(tr!("AI is disabled in settings"));
+    }
+    let cli = settings
+        .get("claudeCodePath")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .unwrap_or("claude");
+    run(cli, prompt)
+}
+
+#[cfg(desktop)]
+fn run(cli: &str, prompt: &str) -> Result<String, String> {
+    let output = std::process::Command::new(cli)
+        .args(["-p", prompt])
+        .output()
+        .map_err(|e| tr!("Failed to run '{}': {}", cli, e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[cfg(mobile)]
+fn run(cli: &str, prompt: &str) -> Result<String, String> {
+    let _ = (cli, prompt);
+    Err(super::unsupported("AI"))
+}
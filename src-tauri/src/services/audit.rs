@@ -0,0 +1,283 @@
+//! Append-only log of changes made to the vault: which command or automation
+//! touched which path, when, on whose behalf. Meant for reviewing what an
+//! automation or an AI session driving the app actually did.
+//!
+//! One JSON object per line in .lifeos/logs/audit.jsonl, oldest first, so
+//! anything else working on the vault can append its own entries:
+//!
+//! ```json
+//! {"at":"2025-01-15T09:30:00+08:00","actor":"app","action":"write_note","path":"diary/2025/2025-01-15-0930.md"}
+//! ```
+//!
+//! Entries are made where files change rather than per command:
+//! `durable::write`, the undo journal's deletes and moves, and the shell
+//! runner call in here, so background loops and the CLI are covered too.
+//! Whoever is doing the change wraps it in `scope` to name itself and the
+//! action; otherwise the process's default actor and a plain "write",
+//! "delete" or "move" are used. Only paths are recorded, never contents or
+//! command arguments (which carry passwords). When the log passes
+//! `MAX_BYTES` it is moved to audit.1.jsonl, replacing the one before.
+
+use chrono::{DateTime, Local};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+use super::notes::vault_rel;
+
+pub const AUDIT_FILE: &str = ".lifeos/logs/audit.jsonl";
+const ROTATED_FILE: &str = ".lifeos/logs/audit.1.jsonl";
+const MAX_BYTES: u64 = 5 << 20;
+const DEFAULT_LIMIT: usize = 200;
+/// Only the .yaml configuration under .lifeos is logged; the rest there is
+/// caches and bookkeeping (this log, the journal, app state)
+const STATE_DIR: &str = ".lifeos/";
+
+/// "app" unless the process says otherwise (the CLI is "cli")
+static DEFAULT_ACTOR: OnceCell<String> = OnceCell::new();
+
+thread_local! {
+    /// Actor and action of the `scope` running on this thread
+    static SCOPE: RefCell<Option<(String, String)>> = const { RefCell::new(None) };
+}
+
+/// Appends from commands and automations don't interleave mid-line
+static LOCK: Mutex<()> = Mutex::new(());
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    /// RFC 3339
+    pub at: String,
+    /// "app" for the main window, "app:<window>" for others,
+    /// "automation:<rule>" for automation rules, "cli" for the `lifeos`
+    /// binary, or what an agent calls itself
+    pub actor: String,
+    /// Command name or automation action, else "write", "delete", "move" or
+    /// "run" for changes made outside a `scope`
+    pub action: String,
+    /// Vault-relative when inside the vault
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dest: Option<String>,
+    /// The program `run_shell_command` ran, without its arguments
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditQuery {
+    /// Exact actor, or a prefix ending in ':' ("automation:")
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    /// Substring of `path` or `dest`
+    pub path: Option<String>,
+    /// RFC 3339 or YYYY-MM-DD, inclusive
+    pub since: Option<String>,
+    pub until: Option<String>,
+    /// Default 200
+    pub limit: Option<usize>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Set once at startup by binaries other than the app
+pub fn set_default_actor(actor: &str) {
+    let _ = DEFAULT_ACTOR.set(actor.to_string());
+}
+
+/// Run `f` with the changes it makes on this thread logged as `action` by
+/// `actor`. Scopes nest; the innermost one wins.
+pub fn scope<T>(actor: &str, action: &str, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<(String, String)>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            SCOPE.with(|s| *s.borrow_mut() = previous);
+        }
+    }
+    let _restore = Restore(SCOPE.with(|s| s.replace(Some((actor.to_string(), action.to_string())))));
+    f()
+}
+
+/// `path` was written
+pub fn wrote(path: &Path) {
+    log("write", path, None, None);
+}
+
+/// `path` was deleted (or moved to the trash)
+pub fn removed(path: &Path) {
+    log("delete", path, None, None);
+}
+
+/// `from` was moved to `to`
+pub fn moved(from: &Path, to: &Path) {
+    log("move", from, Some(to), None);
+}
+
+/// `program` was run on behalf of the vault. Its arguments aren't kept.
+pub fn ran(vault: &Path, program: &str) {
+    log("run", vault, None, Some(program));
+}
+
+/// Append an entry. Failing to log is only printed: auditing must never
+/// fail the change it records.
+pub fn record(vault: &Path, entry: &AuditEntry) {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = vault.join(AUDIT_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    if fs::metadata(&path).is_ok_and(|m| m.len() > MAX_BYTES) {
+        if let Err(e) = fs::rename(&path, vault.join(ROTATED_FILE)) {
+            println!("[WARN] failed to rotate audit log: {e}");
+        }
+    }
+    let Ok(line) = serde_json::to_string(entry) else { return };
+    let file = fs::OpenOptions::new().create(true).append(true).open(&path);
+    if let Err(e) = file.and_then(|mut f| writeln!(f, "{line}")) {
+        println!("[WARN] failed to write {}: {e}", path.display());
+    }
+}
+
+/// Matching entries, newest first, across the current and rotated log
+pub fn query(vault: &Path, filter: &AuditQuery) -> Vec<AuditEntry> {
+    let since = filter.since.as_deref().and_then(|s| bound(s, false));
+    let until = filter.until.as_deref().and_then(|s| bound(s, true));
+    [AUDIT_FILE, ROTATED_FILE]
+        .iter()
+        .filter_map(|file| fs::read_to_string(vault.join(file)).ok())
+        .flat_map(|raw| raw.lines().rev().map(str::to_string).collect::<Vec<_>>())
+        .filter_map(|line| serde_json::from_str::<AuditEntry>(&line).ok())
+        .filter(|e| match filter.actor.as_deref() {
+            Some(actor) if actor.ends_with(':') => e.actor.starts_with(actor),
+            Some(actor) => e.actor == actor,
+            None => true,
+        })
+        .filter(|e| filter.action.as_deref().is_none_or(|a| e.action == a))
+        .filter(|e| {
+            filter.path.as_deref().is_none_or(|p| [&e.path, &e.dest].iter().any(|v| v.as_deref().is_some_and(|v| v.contains(p))))
+        })
+        .filter(|e| {
+            let at = DateTime::parse_from_rfc3339(&e.at).ok();
+            since.is_none_or(|s| at.is_some_and(|t| t >= s)) && until.is_none_or(|u| at.is_some_and(|t| t <= u))
+        })
+        .take(filter.limit.unwrap_or(DEFAULT_LIMIT))
+        .collect()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Record a change to `path` against the vault holding it, if any
+fn log(default_action: &str, path: &Path, dest: Option<&Path>, detail: Option<&str>) {
+    let Some(vault) = path.ancestors().find(|dir| dir.join(".lifeos").is_dir()) else { return };
+    let rel = vault_rel(vault, path);
+    let dest = dest.map(|d| vault_rel(vault, d));
+    let bookkeeping = |rel: &str| rel.starts_with(STATE_DIR) && !rel.ends_with(".yaml");
+    // Restoring from the trash still counts
+    if bookkeeping(&rel) && dest.as_deref().is_none_or(bookkeeping) {
+        return;
+    }
+    let (actor, action) = SCOPE.with(|s| s.borrow().clone()).unwrap_or_else(|| {
+        (DEFAULT_ACTOR.get().map(String::as_str).unwrap_or("app").to_string(), default_action.to_string())
+    });
+    let entry = AuditEntry {
+        at: Local::now().to_rfc3339(),
+        actor,
+        action,
+        path: (path != vault).then_some(rel),
+        dest,
+        detail: detail.map(str::to_string),
+    };
+    record(vault, &entry);
+}
+
+/// A timestamp, or a date taken as its first (or, for `end`, last) second
+fn bound(s: &str, end: bool) -> Option<DateTime<chrono::FixedOffset>> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Some(t);
+    }
+    let date = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()?;
+    let time = if end { date.and_hms_opt(23, 59, 59)? } else { date.and_hms_opt(0, 0, 0)? };
+    Some(time.and_local_timezone(Local).single()?.fixed_offset())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::durable;
+
+    #[test]
+    fn test_changes_are_logged_where_they_happen() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path();
+        fs::create_dir_all(vault.join(".lifeos")).unwrap();
+        fs::create_dir_all(vault.join("diary")).unwrap();
+
+        durable::write(vault.join("diary/a.md"), "secret").unwrap();
+        scope("automation:file-done", "move-file", || moved(&vault.join("diary/a.md"), &vault.join("archive/a.md")));
+        // Bookkeeping under .lifeos isn't, its configuration is
+        durable::write(vault.join(".lifeos/state.json"), "{}").unwrap();
+        durable::write(vault.join(".lifeos/settings.yaml"), "locale: en").unwrap();
+        ran(vault, "git");
+        // Outside any vault nothing is logged
+        let elsewhere = tempfile::tempdir().unwrap();
+        durable::write(elsewhere.path().join("x.md"), "x").unwrap();
+
+        let entries = query(vault, &AuditQuery::default());
+        let summary: Vec<_> = entries.iter().map(|e| (e.actor.as_str(), e.action.as_str(), e.path.as_deref(), e.dest.as_deref(), e.detail.as_deref())).collect();
+        assert_eq!(
+            summary,
+            [
+                ("app", "run", None, None, Some("git")),
+                ("app", "write", Some(".lifeos/settings.yaml"), None, None),
+                ("automation:file-done", "move-file", Some("diary/a.md"), Some("archive/a.md"), None),
+                ("app", "write", Some("diary/a.md"), None, None),
+            ]
+        );
+        assert!(!elsewhere.path().join(AUDIT_FILE).exists());
+    }
+
+    #[test]
+    fn test_record_and_query() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path();
+        let entry = |at: &str, actor: &str, action: &str, path: &str| AuditEntry {
+            at: at.into(),
+            actor: actor.into(),
+            action: action.into(),
+            path: Some(path.into()),
+            dest: None,
+            detail: None,
+        };
+        record(vault, &entry("2025-01-14T10:00:00+00:00", "app", "write_note", "diary/a.md"));
+        record(vault, &entry("2025-01-15T10:00:00+00:00", "automation:file-done", "create-note", "inbox/b.md"));
+        record(vault, &entry("2025-01-16T10:00:00+00:00", "agent", "delete_file", "diary/c.md"));
+
+        let all = query(vault, &AuditQuery::default());
+        assert_eq!(all.iter().map(|e| e.action.as_str()).collect::<Vec<_>>(), ["delete_file", "create-note", "write_note"]);
+        let automations = query(vault, &AuditQuery { actor: Some("automation:".into()), ..Default::default() });
+        assert_eq!(automations.len(), 1);
+        let diary = query(vault, &AuditQuery { path: Some("diary/".into()), limit: Some(1), ..Default::default() });
+        assert_eq!(diary[0].path.as_deref(), Some("diary/c.md"));
+        let window = query(vault, &AuditQuery { since: Some("2025-01-15T00:00:00+00:00".into()), until: Some("2025-01-15T23:00:00+00:00".into()), ..Default::default() });
+        assert_eq!(window.len(), 1);
+    }
+}
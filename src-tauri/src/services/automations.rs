@@ -0,0 +1,602 @@
+//! User-defined automation rules stored in {vault}/.lifeos/automations/*.yaml.
+//!
+//! ```yaml
+//! name: File finished projects
+//! enabled: true
+//! trigger:
+//!   type: task-done          # schedule | file-change | new-email | task-done
+//!   tag: project
+//! actions:
+//!   - type: create-note      # run-shortcut | move-file | create-note | webhook
+//!     path: "inbox/{{date}}.md"
+//!     content: "- finished {{task}}\n"
+//!     append: true
+//! ```
+//!
+//! This module is the Tauri-free half: parsing, matching and running actions.
+//! `automation_commands` owns the watcher/scheduler that feeds it events.
+
+use chrono::{DateTime, Datelike, Local, NaiveTime, TimeDelta};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use super::audit;
+use super::durable;
+
+pub const RULES_DIR: &str = ".lifeos/automations";
+/// One JSON line per run, newest last
+const RUN_LOG: &str = ".lifeos/automations/runs.jsonl";
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Rule {
+    /// Defaults to the file stem
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub trigger: Trigger,
+    pub actions: Vec<Action>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Trigger {
+    /// `every: 30m|2h|1d`, or `at: "HH:MM"` once a day; `days` are ISO
+    /// weekdays (1 = Monday) like habit `target_days`, empty = every day
+    Schedule {
+        every: Option<String>,
+        at: Option<String>,
+        #[serde(default)]
+        days: Vec<u32>,
+    },
+    /// Vault-relative glob: `*` stays within a folder, `**` crosses folders
+    FileChange { path: String },
+    /// Case-insensitive substring filters; all given ones must match
+    NewEmail {
+        from: Option<String>,
+        subject: Option<String>,
+        account: Option<String>,
+    },
+    /// A checkbox in daily/tasks/ flipped to done
+    TaskDone {
+        contains: Option<String>,
+        tag: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Action {
+    /// macOS Shortcuts app
+    RunShortcut { name: String },
+    /// `from` defaults to the file that fired a file-change trigger
+    MoveFile { from: Option<String>, to: String },
+    CreateNote {
+        path: String,
+        #[serde(default)]
+        content: String,
+        /// Append to an existing note instead of failing
+        #[serde(default)]
+        append: bool,
+    },
+    /// `body` defaults to the trigger variables as a JSON object
+    Webhook {
+        url: String,
+        #[serde(default = "default_method")]
+        method: String,
+        body: Option<String>,
+    },
+}
+
+/// Something that happened in the vault, fed to every rule
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// Manual run from the UI; matches any trigger
+    Manual,
+    Tick,
+    FileChanged { path: PathBuf },
+    NewEmail { path: PathBuf, account: String, from: String, subject: String },
+    TaskDone { path: PathBuf, task: String },
+}
+
+/// A rules file that failed to parse, surfaced so the UI can point at it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RuleFile {
+    pub path: String,
+    pub rule: Option<Rule>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionOutcome {
+    pub action: String,
+    pub ok: bool,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RunReport {
+    pub rule_id: String,
+    pub trigger: String,
+    pub started: String,
+    pub ok: bool,
+    pub actions: Vec<ActionOutcome>,
+    /// Planned only: nothing was moved, written, sent or logged
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Vault files the actions wrote; the watcher ignores their echo events
+    #[serde(skip)]
+    pub touched: Vec<PathBuf>,
+}
+
+pub type Vars = HashMap<&'static str, String>;
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_method() -> String {
+    "POST".to_string()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Loading
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Parse every rule file, keeping broken ones as errors rather than failing the lot
+pub fn load_rule_files(vault_path: &Path) -> Vec<RuleFile> {
+    let Ok(entries) = fs::read_dir(vault_path.join(RULES_DIR)) else { return vec![] };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "yaml" || ext == "yml"))
+        .collect();
+    files.sort();
+
+    files
+        .into_iter()
+        .map(|path| {
+            let parsed = fs::read_to_string(&path)
+                .map_err(|e| tr!("Failed to read: {}", e))
+                .and_then(|raw| serde_yaml::from_str::<Rule>(&raw).map_err(|e| tr!("Invalid YAML: {}", e)))
+                .and_then(|mut rule| {
+                    if rule.id.is_empty() {
+                        rule.id = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+                    }
+                    validate_rule(&rule).map(|_| rule)
+                });
+            let (rule, error) = match parsed {
+                Ok(rule) => (Some(rule), None),
+                Err(e) => (None, Some(e)),
+            };
+            RuleFile { path: path.to_string_lossy().to_string(), rule, error }
+        })
+        .collect()
+}
+
+pub fn load_rules(vault_path: &Path) -> Vec<Rule> {
+    load_rule_files(vault_path).into_iter().filter_map(|f| f.rule).collect()
+}
+
+fn validate_rule(rule: &Rule) -> Result<(), String> {
+    if rule.actions.is_empty() {
+        return Err(tr!("Rule has no actions"));
+    }
+    if let Trigger::Schedule { every, at, .. } = &rule.trigger {
+        match (every, at) {
+            (Some(every), None) => {
+                parse_interval(every).ok_or_else(|| tr!("Invalid interval: {}", every))?;
+            }
+            (None, Some(at)) => {
+                NaiveTime::parse_from_str(at, "%H:%M").map_err(|_| tr!("Invalid time (expected HH:MM): {}", at))?;
+            }
+            _ => return Err(tr!("Schedule needs exactly one of `every` or `at`")),
+        }
+    }
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Matching
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Template variables for `event` if it fires `trigger`, else None.
+/// Schedule triggers match every tick; whether they are due is `schedule_due`.
+pub fn match_event(vault_path: &Path, trigger: &Trigger, event: &Event) -> Option<Vars> {
+    let mut vars = Vars::new();
+    match (trigger, event) {
+        (_, Event::Manual) => {}
+        (Trigger::Schedule { .. }, Event::Tick) => {}
+        (Trigger::FileChange { path: pattern }, Event::FileChanged { path }) => {
+            let rel = relative(vault_path, path)?;
+            if !glob_match(pattern, &rel) {
+                return None;
+            }
+            insert_path_vars(&mut vars, &rel);
+        }
+        (Trigger::NewEmail { from: want_from, subject: want_subject, account: want_account }, Event::NewEmail { path, account, from, subject }) => {
+            if !contains_ci(from, want_from) || !contains_ci(subject, want_subject) || !contains_ci(account, want_account) {
+                return None;
+            }
+            vars.insert("from", from.clone());
+            vars.insert("subject", subject.clone());
+            vars.insert("account", account.clone());
+            if let Some(rel) = relative(vault_path, path) {
+                vars.insert("email_path", rel);
+            }
+        }
+        (Trigger::TaskDone { contains, tag }, Event::TaskDone { path, task }) => {
+            if !contains_ci(task, contains) {
+                return None;
+            }
+            if let Some(tag) = tag {
+                let tag = format!("#{}", tag.trim_start_matches('#'));
+                if !task.split_whitespace().any(|w| w == tag) {
+                    return None;
+                }
+            }
+            vars.insert("task", task.clone());
+            if let Some(rel) = relative(vault_path, path) {
+                insert_path_vars(&mut vars, &rel);
+            }
+        }
+        _ => return None,
+    }
+    Some(vars)
+}
+
+/// Whether a schedule trigger should fire at `now` given its previous run.
+/// A rule seen for the first time only starts its clock (`last_run` None
+/// returns false), so adding a rule never fires it immediately.
+pub fn schedule_due(trigger: &Trigger, last_run: Option<DateTime<Local>>, now: DateTime<Local>) -> bool {
+    let Trigger::Schedule { every, at, days } = trigger else { return false };
+    let Some(last_run) = last_run else { return false };
+    if !days.is_empty() && !days.contains(&now.weekday().number_from_monday()) {
+        return false;
+    }
+    if let Some(interval) = every.as_deref().and_then(parse_interval) {
+        return now - last_run >= interval;
+    }
+    if let Some(at) = at.as_deref().and_then(|a| NaiveTime::parse_from_str(a, "%H:%M").ok()) {
+        let Some(slot) = now.date_naive().and_time(at).and_local_timezone(Local).earliest() else { return false };
+        return now >= slot && last_run < slot;
+    }
+    false
+}
+
+/// `15m`, `2h`, `1d`; minimum one minute
+fn parse_interval(s: &str) -> Option<TimeDelta> {
+    let s = s.trim();
+    let (at, _) = s.char_indices().last()?;
+    let (num, unit) = s.split_at(at);
+    let n: i64 = num.trim().parse().ok().filter(|n| *n > 0)?;
+    match unit {
+        "m" => TimeDelta::try_minutes(n),
+        "h" => TimeDelta::try_hours(n),
+        "d" => TimeDelta::try_days(n),
+        _ => None,
+    }
+}
+
+/// Minimal glob: `**` matches across `/`, `*` and `?` do not
+pub(crate) fn glob_match(pattern: &str, path: &str) -> bool {
+    fn go(p: &[u8], s: &[u8]) -> bool {
+        match p {
+            [] => s.is_empty(),
+            [b'*', b'*', rest @ ..] => {
+                let rest = rest.strip_prefix(b"/").unwrap_or(rest);
+                (0..=s.len()).any(|i| go(rest, &s[i..]))
+            }
+            [b'*', rest @ ..] => (0..=s.len())
+                .take_while(|&i| i == 0 || s[i - 1] != b'/')
+                .any(|i| go(rest, &s[i..])),
+            [b'?', rest @ ..] => !s.is_empty() && s[0] != b'/' && go(rest, &s[1..]),
+            [c, rest @ ..] => s.first() == Some(c) && go(rest, &s[1..]),
+        }
+    }
+    go(pattern.trim_start_matches('/').as_bytes(), path.as_bytes())
+}
+
+fn contains_ci(haystack: &str, needle: &Option<String>) -> bool {
+    needle
+        .as_deref()
+        .is_none_or(|n| haystack.to_lowercase().contains(&n.to_lowercase()))
+}
+
+fn relative(vault_path: &Path, path: &Path) -> Option<String> {
+    let rel = path.strip_prefix(vault_path).ok()?;
+    Some(rel.to_string_lossy().replace('\\', "/"))
+}
+
+fn insert_path_vars(vars: &mut Vars, rel: &str) {
+    let p = Path::new(rel);
+    vars.insert("path", rel.to_string());
+    vars.insert("file_name", p.file_name().unwrap_or_default().to_string_lossy().to_string());
+    vars.insert("file_stem", p.file_stem().unwrap_or_default().to_string_lossy().to_string());
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Running
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Run all actions of `rule` in order, stopping at the first failure, and
+/// append the result to the run log. A dry run resolves and checks each
+/// action and reports what it would do, without doing it or logging the run.
+pub fn run_rule(vault_path: &Path, rule: &Rule, trigger_label: &str, mut vars: Vars, dry_run: bool) -> RunReport {
+    let now = Local::now();
+    vars.insert("date", now.format("%Y-%m-%d").to_string());
+    vars.insert("time", now.format("%H:%M").to_string());
+    vars.insert("datetime", now.format("%Y-%m-%dT%H:%M:%S").to_string());
+    vars.insert("rule", rule.id.clone());
+
+    let mut report = RunReport {
+        rule_id: rule.id.clone(),
+        trigger: trigger_label.to_string(),
+        started: now.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        ok: true,
+        actions: Vec::new(),
+        dry_run,
+        touched: Vec::new(),
+    };
+
+    for action in &rule.actions {
+        let result = if dry_run {
+            run_action(vault_path, action, &vars, true, &mut report.touched)
+        } else {
+            audit::scope(&format!("automation:{}", rule.id), action_label(action), || run_action(vault_path, action, &vars, false, &mut report.touched))
+        };
+        let ok = result.is_ok();
+        report.actions.push(ActionOutcome {
+            action: action_label(action).to_string(),
+            ok,
+            message: result.unwrap_or_else(|e| e),
+        });
+        if !ok {
+            report.ok = false;
+            break;
+        }
+    }
+
+    if !dry_run {
+        append_run_log(vault_path, &report);
+    }
+    report
+}
+
+fn run_action(vault_path: &Path, action: &Action, vars: &Vars, dry_run: bool, touched: &mut Vec<PathBuf>) -> Result<String, String> {
+    match action {
+        Action::RunShortcut { name } if dry_run => Ok(render(name, vars)),
+        Action::RunShortcut { name } => run_shortcut(&render(name, vars)),
+        Action::MoveFile { from, to } => {
+            let from = match from {
+                Some(from) => render(from, vars),
+                None => vars.get("path").cloned().ok_or_else(|| tr!("move-file needs `from` for this trigger"))?,
+            };
+            let src = vault_file(vault_path, &from)?;
+            let dest = vault_file(vault_path, &render(to, vars))?;
+            if dry_run {
+                if !src.exists() {
+                    return Err(tr!("Path does not exist: {}", src.display()));
+                }
+                return Ok(dest.to_string_lossy().to_string());
+            }
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+            }
+            fs::rename(&src, &dest).map_err(|e| tr!("Failed to move {}: {}", from, e))?;
+            audit::moved(&src, &dest);
+            touched.push(src);
+            touched.push(dest.clone());
+            Ok(dest.to_string_lossy().to_string())
+        }
+        Action::CreateNote { path, content, append } => {
+            let dest = vault_file(vault_path, &render(path, vars))?;
+            let body = render(content, vars);
+            if dry_run {
+                if dest.exists() && !append {
+                    return Err(tr!("Note already exists: {}", dest.display()));
+                }
+                return Ok(dest.to_string_lossy().to_string());
+            }
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+            }
+            if dest.exists() {
+                if !append {
+                    return Err(tr!("Note already exists: {}", dest.display()));
+                }
+                let mut existing = fs::read_to_string(&dest).map_err(|e| tr!("Failed to read: {}", e))?;
+                if !existing.is_empty() && !existing.ends_with('\n') {
+                    existing.push('\n');
+                }
+                existing.push_str(&body);
+                durable::write(&dest, existing).map_err(|e| tr!("write_file failed: {}", e))?;
+            } else {
+                durable::write(&dest, body).map_err(|e| tr!("write_file failed: {}", e))?;
+            }
+            touched.push(dest.clone());
+            Ok(dest.to_string_lossy().to_string())
+        }
+        Action::Webhook { url, method, body } => {
+            let body = match body {
+                Some(body) => render(body, vars),
+                None => serde_json::to_string(vars).map_err(|e| tr!("Failed to serialize: {}", e))?,
+            };
+            if dry_run {
+                return Ok(format!("{} {}", method.to_uppercase(), render(url, vars)));
+            }
+            send_webhook(&render(url, vars), method, &body)
+        }
+    }
+}
+
+fn action_label(action: &Action) -> &'static str {
+    match action {
+        Action::RunShortcut { .. } => "run-shortcut",
+        Action::MoveFile { .. } => "move-file",
+        Action::CreateNote { .. } => "create-note",
+        Action::Webhook { .. } => "webhook",
+    }
+}
+
+/// Replace `{{name}}` with its variable; unknown names are left untouched
+pub fn render(template: &str, vars: &Vars) -> String {
+    let mut out = template.to_string();
+    for (key, value) in vars {
+        out = out.replace(&format!("{{{{{key}}}}}"), value);
+    }
+    out
+}
+
+/// Resolve a vault-relative path, refusing anything that would leave the vault
+pub(crate) fn vault_file(vault_path: &Path, rel: &str) -> Result<PathBuf, String> {
+    let rel = Path::new(rel.trim_start_matches('/'));
+    if rel.as_os_str().is_empty() || rel.components().any(|c| !matches!(c, Component::Normal(_))) {
+        return Err(tr!("Path must stay inside the vault: {}", rel.display()));
+    }
+    Ok(vault_path.join(rel))
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) fn run_shortcut(name: &str) -> Result<String, String> {
+    let output = std::process::Command::new("shortcuts")
+        .args(["run", name])
+        .output()
+        .map_err(|e| tr!("Failed to run shortcut '{}': {}", name, e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn run_shortcut(name: &str) -> Result<String, String> {
+    let _ = name;
+    Err(super::unsupported("run-shortcut"))
+}
+
+fn send_webhook(url: &str, method: &str, body: &str) -> Result<String, String> {
+    let response = super::http::agent()?
+        .request(&method.to_uppercase(), url)
+        .set("Content-Type", "application/json")
+        .send_string(body)
+        .map_err(|e| tr!("Webhook failed: {}", e))?;
+    Ok(format!("HTTP {}", response.status()))
+}
+
+fn append_run_log(vault_path: &Path, report: &RunReport) {
+    use std::io::Write;
+    let Ok(line) = serde_json::to_string(report) else { return };
+    let path = vault_path.join(RUN_LOG);
+    let file = fs::OpenOptions::new().create(true).append(true).open(&path);
+    if let Err(e) = file.and_then(|mut f| writeln!(f, "{line}")) {
+        println!("[WARN] failed to write {}: {e}", path.display());
+    }
+}
+
+/// Last `limit` runs from the log, newest first
+pub fn recent_runs(vault_path: &Path, limit: usize) -> Vec<RunReport> {
+    let Ok(raw) = fs::read_to_string(vault_path.join(RUN_LOG)) else { return vec![] };
+    raw.lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .take(limit)
+        .collect()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("inbox/*.md", "inbox/a.md"));
+        assert!(!glob_match("inbox/*.md", "inbox/sub/a.md"));
+        assert!(glob_match("projects/**/*.md", "projects/done/x.md"));
+        assert!(glob_match("projects/**/*.md", "projects/x.md"));
+        assert!(glob_match("**", "anything/at/all"));
+    }
+
+    #[test]
+    fn test_parse_rule_yaml() {
+        let raw = "trigger:\n  type: file-change\n  path: inbox/*.md\nactions:\n  - type: move-file\n    to: archive/{{file_name}}\n";
+        let rule: Rule = serde_yaml::from_str(raw).unwrap();
+        assert!(rule.enabled);
+        assert_eq!(rule.trigger, Trigger::FileChange { path: "inbox/*.md".into() });
+        assert!(validate_rule(&rule).is_ok());
+    }
+
+    #[test]
+    fn test_schedule_due() {
+        let t = |h, m| Local::now().date_naive().and_hms_opt(h, m, 0).unwrap().and_local_timezone(Local).unwrap();
+        let every = Trigger::Schedule { every: Some("30m".into()), at: None, days: vec![] };
+        assert!(!schedule_due(&every, None, t(10, 0)));
+        assert!(!schedule_due(&every, Some(t(10, 0)), t(10, 29)));
+        assert!(schedule_due(&every, Some(t(10, 0)), t(10, 30)));
+
+        let daily = Trigger::Schedule { every: None, at: Some("09:00".into()), days: vec![] };
+        assert!(schedule_due(&daily, Some(t(8, 0)), t(9, 1)));
+        assert!(!schedule_due(&daily, Some(t(9, 1)), t(9, 30)));
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("30m"), TimeDelta::try_minutes(30));
+        assert_eq!(parse_interval(" 2d "), TimeDelta::try_days(2));
+        assert_eq!(parse_interval("30分"), None);
+        assert_eq!(parse_interval("2天"), None);
+        assert_eq!(parse_interval("é"), None);
+        assert_eq!(parse_interval(""), None);
+    }
+
+    #[test]
+    fn test_task_done_tag_filter() {
+        let vault = Path::new("/v");
+        let trigger = Trigger::TaskDone { contains: None, tag: Some("project".into()) };
+        let event = |task: &str| Event::TaskDone { path: PathBuf::from("/v/daily/tasks/2025-01-01.md"), task: task.into() };
+        let vars = match_event(vault, &trigger, &event("Ship v2 #project")).unwrap();
+        assert_eq!(vars["file_stem"], "2025-01-01");
+        assert!(match_event(vault, &trigger, &event("Ship v2 #projects")).is_none());
+    }
+
+    #[test]
+    fn test_vault_file_rejects_escape() {
+        let vault = Path::new("/v");
+        assert!(vault_file(vault, "../etc/passwd").is_err());
+        assert_eq!(vault_file(vault, "/inbox/a.md").unwrap(), PathBuf::from("/v/inbox/a.md"));
+    }
+
+    #[test]
+    fn test_dry_run_changes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path();
+        fs::create_dir_all(vault.join("inbox")).unwrap();
+        fs::write(vault.join("inbox/a.md"), "a").unwrap();
+        let raw = "trigger:\n  type: file-change\n  path: inbox/*.md\nactions:\n  - type: move-file\n    from: inbox/a.md\n    to: archive/a.md\n  - type: create-note\n    path: log/{{rule}}.md\n";
+        let rule = Rule { id: "tidy".into(), ..serde_yaml::from_str(raw).unwrap() };
+
+        let report = run_rule(vault, &rule, "manual", Vars::new(), true);
+        assert!(report.ok && report.dry_run);
+        assert!(report.actions[1].message.ends_with("log/tidy.md"));
+        assert!(vault.join("inbox/a.md").exists() && !vault.join("archive").exists() && !vault.join("log").exists());
+        assert!(recent_runs(vault, 10).is_empty());
+
+        fs::remove_file(vault.join("inbox/a.md")).unwrap();
+        assert!(!run_rule(vault, &rule, "manual", Vars::new(), true).ok);
+    }
+}
@@ -0,0 +1,329 @@
+//! Several file operations applied as one change, for reorganizing a folder
+//! in a single call:
+//!
+//! ```json
+//! [
+//!   {"kind": "create", "path": "/vault/projects/done"},
+//!   {"kind": "move", "src": "/vault/projects/launch.md", "dest": "/vault/projects/done/launch.md"},
+//!   {"kind": "delete", "path": "/vault/projects/old-notes.md"}
+//! ]
+//! ```
+//!
+//! Operations run in order. When one fails, the ones before it are reversed
+//! newest first and the rest are skipped, so the tree ends up as it was.
+//! Nothing is overwritten: a destination that exists fails its operation.
+//! Deletes inside the vault go through the undo journal once the batch has
+//! gone through; elsewhere they are held next to the file until then.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::journal::{self, Operation};
+use super::transfer::{self, Collision};
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BatchOp {
+    Move { src: String, dest: String },
+    Copy { src: String, dest: String },
+    Delete { path: String },
+    /// A file with `content`, or a folder without
+    Create {
+        path: String,
+        #[serde(default)]
+        content: Option<String>,
+    },
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OpStatus {
+    Done,
+    Failed,
+    /// Done, then reversed because a later operation failed
+    RolledBack,
+    /// Not attempted after an earlier failure
+    Skipped,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct OpResult {
+    pub status: OpStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct BatchReport {
+    /// Every operation went through
+    pub ok: bool,
+    /// One per operation, in order
+    pub results: Vec<OpResult>,
+}
+
+/// How to reverse an operation that went through. `created` is the first
+/// parent folder the operation had to create, removed with it.
+enum Applied {
+    Moved { src: PathBuf, dest: PathBuf, created: Option<PathBuf> },
+    Copied { dest: PathBuf, created: Option<PathBuf> },
+    Trashed { path: PathBuf, op: Operation },
+    Held { path: PathBuf, held: PathBuf },
+    Created { path: PathBuf, created: Option<PathBuf> },
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Apply `ops` all or nothing. `vault` is the open vault, whose deletes and
+/// moves are journaled for `undo_last_operation`.
+pub fn run(vault: Option<&Path>, ops: &[BatchOp]) -> BatchReport {
+    let mut applied = Vec::new();
+    let mut results = Vec::new();
+    for op in ops {
+        match apply(vault, op) {
+            Ok(done) => {
+                applied.push(done);
+                results.push(OpResult { status: OpStatus::Done, error: None });
+            }
+            Err(e) => {
+                results.push(OpResult { status: OpStatus::Failed, error: Some(e) });
+                break;
+            }
+        }
+    }
+
+    let ok = applied.len() == ops.len();
+    if ok {
+        commit(vault, applied);
+    } else {
+        for (i, done) in applied.into_iter().enumerate().rev() {
+            results[i] = match rollback(vault, done) {
+                Ok(()) => OpResult { status: OpStatus::RolledBack, error: None },
+                Err(e) => OpResult { status: OpStatus::Done, error: Some(tr!("Rollback failed: {}", e)) },
+            };
+        }
+        results.resize(ops.len(), OpResult { status: OpStatus::Skipped, error: None });
+    }
+    BatchReport { ok, results }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn apply(vault: Option<&Path>, op: &BatchOp) -> Result<Applied, String> {
+    match op {
+        BatchOp::Move { src, dest } => {
+            let (src, dest) = (PathBuf::from(src), PathBuf::from(dest));
+            let created = first_missing(&dest);
+            match transfer::move_path(&src, &dest, Collision::Skip, &mut |_, _| {})? {
+                Some(_) => Ok(Applied::Moved { src, dest, created }),
+                None => Err(tr!("Path already exists: {}", dest.display())),
+            }
+        }
+        BatchOp::Copy { src, dest } => {
+            let dest = PathBuf::from(dest);
+            let created = first_missing(&dest);
+            transfer::copy_path(Path::new(src), &dest)?;
+            Ok(Applied::Copied { dest, created })
+        }
+        BatchOp::Delete { path } => {
+            let path = PathBuf::from(path);
+            if fs::symlink_metadata(&path).is_err() {
+                return Err(tr!("File not found: {}", path.display()));
+            }
+            if let Some(vault) = vault.filter(|v| journaled(v, &path)) {
+                return journal::delete(vault, &path).map(|op| Applied::Trashed { path, op });
+            }
+            let held = held_path(&path);
+            fs::rename(&path, &held).map_err(|e| e.to_string())?;
+            Ok(Applied::Held { path, held })
+        }
+        BatchOp::Create { path, content } => {
+            let path = PathBuf::from(path);
+            if path.exists() {
+                return Err(tr!("Path already exists: {}", path.display()));
+            }
+            let created = first_missing(&path);
+            match content {
+                Some(content) => {
+                    if let Some(parent) = path.parent() {
+                        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+                    }
+                    fs::write(&path, content).map_err(|e| tr!("write_file failed: {}", e))?;
+                }
+                None => fs::create_dir_all(&path).map_err(|e| tr!("Failed to create directory: {}", e))?,
+            }
+            Ok(Applied::Created { path, created })
+        }
+    }
+}
+
+fn rollback(vault: Option<&Path>, done: Applied) -> Result<(), String> {
+    match done {
+        Applied::Moved { src, dest, created } => {
+            transfer::move_path(&dest, &src, Collision::Skip, &mut |_, _| {})?
+                .ok_or_else(|| tr!("Cannot undo, path is in use: {}", src.display()))?;
+            remove_created(created)
+        }
+        Applied::Copied { dest, created } => {
+            remove_all(&dest)?;
+            remove_created(created)
+        }
+        Applied::Trashed { path, op } => {
+            let vault = vault.ok_or_else(|| tr!("Path is outside the vault: {}", path.display()))?;
+            let trash = vault.join(op.trash.as_deref().unwrap_or_default());
+            fs::rename(&trash, &path).map_err(|e| e.to_string())?;
+            journal::forget(vault, &op.id)
+        }
+        Applied::Held { path, held } => fs::rename(&held, &path).map_err(|e| e.to_string()),
+        Applied::Created { path, created } => {
+            remove_all(&path)?;
+            remove_created(created)
+        }
+    }
+}
+
+/// Journal the vault's moves and finish deletes held outside it. The batch
+/// has already happened, so failures here are only printed.
+fn commit(vault: Option<&Path>, applied: Vec<Applied>) {
+    for done in applied {
+        match done {
+            Applied::Moved { src, dest, .. } => {
+                if let Some(vault) = vault.filter(|v| journaled(v, &src) && dest.starts_with(v)) {
+                    if let Err(e) = journal::record_move(vault, &src, &dest) {
+                        println!("[WARN] journal: {e}");
+                    }
+                }
+            }
+            Applied::Held { held, .. } => {
+                if let Err(e) = remove_all(&held) {
+                    println!("[WARN] failed to delete {}: {e}", held.display());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// In the vault but not under .lifeos, as `delete_file` decides
+fn journaled(vault: &Path, path: &Path) -> bool {
+    path.strip_prefix(vault).ok().and_then(|rel| rel.components().next()).is_some_and(|first| first.as_os_str() != ".lifeos")
+}
+
+/// The outermost of `path`'s parent folders that doesn't exist yet
+fn first_missing(path: &Path) -> Option<PathBuf> {
+    path.ancestors().skip(1).take_while(|p| !p.as_os_str().is_empty() && !p.exists()).last().map(Path::to_path_buf)
+}
+
+fn remove_created(created: Option<PathBuf>) -> Result<(), String> {
+    created.map_or(Ok(()), |dir| remove_all(&dir))
+}
+
+/// Hidden sibling a delete outside the vault waits under
+fn held_path(path: &Path) -> PathBuf {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    path.with_file_name(format!(".{name}.lifeos-deleted"))
+}
+
+fn remove_all(path: &Path) -> Result<(), String> {
+    let removed = if fs::symlink_metadata(path).map_err(|e| e.to_string())?.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    removed.map_err(|e| e.to_string())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ops(json: serde_json::Value) -> Vec<BatchOp> {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_batch_commits() {
+        let dir = tempfile::tempdir().unwrap();
+        let v = dir.path();
+        fs::create_dir_all(v.join("projects")).unwrap();
+        fs::write(v.join("projects/launch.md"), "launch").unwrap();
+        fs::write(v.join("projects/old.md"), "old").unwrap();
+        let p = |rel: &str| v.join(rel).to_string_lossy().to_string();
+
+        let report = run(Some(v), &ops(serde_json::json!([
+            { "kind": "create", "path": p("projects/done") },
+            { "kind": "move", "src": p("projects/launch.md"), "dest": p("projects/done/launch.md") },
+            { "kind": "copy", "src": p("projects/done"), "dest": p("archive/2025/done") },
+            { "kind": "delete", "path": p("projects/old.md") },
+            { "kind": "create", "path": p("projects/README.md"), "content": "# Projects" },
+        ])));
+        assert!(report.ok);
+        assert!(report.results.iter().all(|r| r.status == OpStatus::Done));
+        assert_eq!(fs::read_to_string(v.join("archive/2025/done/launch.md")).unwrap(), "launch");
+        assert!(v.join("projects/done/launch.md").exists() && !v.join("projects/old.md").exists());
+        // The delete and the move can be undone like single ones
+        let kinds: Vec<String> = journal::recent(v, 10).into_iter().map(|o| o.kind).collect();
+        assert_eq!(kinds, ["move", "delete"]);
+    }
+
+    #[test]
+    fn test_batch_rolls_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let v = dir.path();
+        fs::create_dir_all(v.join("projects")).unwrap();
+        fs::write(v.join("projects/a.md"), "a").unwrap();
+        fs::write(v.join("projects/b.md"), "b").unwrap();
+        fs::write(v.join("projects/c.md"), "c").unwrap();
+        let p = |rel: &str| v.join(rel).to_string_lossy().to_string();
+
+        let report = run(Some(v), &ops(serde_json::json!([
+            { "kind": "move", "src": p("projects/a.md"), "dest": p("archive/2025/a.md") },
+            { "kind": "delete", "path": p("projects/b.md") },
+            { "kind": "move", "src": p("projects/c.md"), "dest": p("projects/b.md") },
+            { "kind": "move", "src": p("projects/missing.md"), "dest": p("archive/missing.md") },
+            { "kind": "create", "path": p("projects/never") },
+        ])));
+        assert!(!report.ok);
+        let statuses: Vec<OpStatus> = report.results.iter().map(|r| r.status).collect();
+        assert_eq!(statuses, [OpStatus::RolledBack, OpStatus::RolledBack, OpStatus::RolledBack, OpStatus::Failed, OpStatus::Skipped]);
+        assert!(report.results[3].error.is_some());
+
+        for (name, text) in [("a", "a"), ("b", "b"), ("c", "c")] {
+            assert_eq!(fs::read_to_string(v.join(format!("projects/{name}.md"))).unwrap(), text);
+        }
+        assert!(!v.join("archive").exists() && !v.join("projects/never").exists());
+        assert!(journal::recent(v, 10).is_empty());
+    }
+
+    #[test]
+    fn test_batch_outside_vault() {
+        let dir = tempfile::tempdir().unwrap();
+        let d = dir.path();
+        fs::write(d.join("a.txt"), "a").unwrap();
+        fs::write(d.join("b.txt"), "b").unwrap();
+        let p = |rel: &str| d.join(rel).to_string_lossy().to_string();
+
+        // Nothing is overwritten
+        let report = run(None, &ops(serde_json::json!([
+            { "kind": "delete", "path": p("a.txt") },
+            { "kind": "copy", "src": p("b.txt"), "dest": p("b.txt") },
+        ])));
+        assert_eq!(report.results[0].status, OpStatus::RolledBack);
+        assert_eq!(fs::read_dir(d).unwrap().count(), 2);
+
+        assert!(run(None, &ops(serde_json::json!([{ "kind": "delete", "path": p("a.txt") }]))).ok);
+        assert_eq!(fs::read_dir(d).unwrap().count(), 1);
+    }
+}
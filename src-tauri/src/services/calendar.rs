@@ -0,0 +1,171 @@
+//! Calendar events from the .ics files under connectors/calendar, which the
+//! calendar connector, Google Calendar sync and dropped files write. The
+//! agenda, planner and meeting notes read them through here.
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Exported .ics files; the calendar connector syncs into this folder
+pub const CALENDAR_DIR: &str = "connectors/calendar";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AgendaEvent {
+    /// HH:MM; None for all-day events and days inside a multi-day one
+    pub time: Option<String>,
+    /// HH:MM, when the event ends on the day
+    pub end: Option<String>,
+    pub title: String,
+    pub location: Option<String>,
+    /// "calendar" | "trip"
+    pub source: String,
+    /// The .ics file or trip note
+    pub path: String,
+    /// Calendar UID, as taken by `create_meeting_note`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Meeting note created for the event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// One property line of an .ics component
+#[derive(Debug, Clone, PartialEq)]
+pub struct IcsProperty {
+    /// Uppercased
+    pub name: String,
+    /// `;`-separated parameters as written, e.g. `CN=Jane;ROLE=CHAIR`
+    pub params: String,
+    pub value: String,
+}
+
+/// Events of every .ics file in the calendar folder touching `day`
+pub fn events(vault: &Path, day: NaiveDate) -> Vec<AgendaEvent> {
+    let Ok(entries) = fs::read_dir(vault.join(CALENDAR_DIR)) else { return vec![] };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|x| x.eq_ignore_ascii_case("ics")))
+        .flat_map(|p| {
+            let raw = fs::read_to_string(&p).unwrap_or_default();
+            let path = p.to_string_lossy().to_string();
+            ics_events(&raw, day).into_iter().map(move |e| AgendaEvent { path: path.clone(), ..e })
+        })
+        .collect()
+}
+
+/// VEVENTs touching `day`. Recurring events only show on their first
+/// occurrence: RRULE is not expanded.
+fn ics_events(raw: &str, day: NaiveDate) -> Vec<AgendaEvent> {
+    vevents(raw)
+        .into_iter()
+        .filter_map(|props| {
+            let mut fields = HashMap::new();
+            for p in props {
+                // First wins, as a property may only appear once in an event
+                fields.entry(p.name).or_insert(p.value);
+            }
+            ics_event(&fields, day)
+        })
+        .collect()
+}
+
+/// Properties of every VEVENT, in file order. Components nested in an event
+/// (VALARM) are skipped, so they cannot pass for the event's own fields.
+pub fn vevents(raw: &str) -> Vec<Vec<IcsProperty>> {
+    // Unfold continuation lines (RFC 5545 §3.1)
+    let unfolded = raw.replace("\r\n", "\n").replace("\n ", "").replace("\n\t", "");
+    let mut out = Vec::new();
+    let mut current: Option<Vec<IcsProperty>> = None;
+    let mut nested = 0usize;
+    for line in unfolded.lines().map(str::trim_end) {
+        match line {
+            "BEGIN:VEVENT" => current = Some(Vec::new()),
+            "END:VEVENT" => out.extend(current.take()),
+            _ if current.is_none() => {}
+            _ if line.starts_with("BEGIN:") => nested += 1,
+            _ if line.starts_with("END:") => nested = nested.saturating_sub(1),
+            _ if nested > 0 => {}
+            line => {
+                let (Some(props), Some((key, value))) = (current.as_mut(), line.split_once(':')) else { continue };
+                let (name, params) = key.split_once(';').unwrap_or((key, ""));
+                props.push(IcsProperty { name: name.to_ascii_uppercase(), params: params.to_string(), value: value.to_string() });
+            }
+        }
+    }
+    out
+}
+
+/// TEXT value with its escapes undone; line breaks become spaces unless `keep_lines`
+pub fn ics_text(value: &str, keep_lines: bool) -> String {
+    let newline = if keep_lines { "\n" } else { " " };
+    value.replace("\\n", newline).replace("\\N", newline).replace("\\,", ",").replace("\\;", ";").replace("\\\\", "\\")
+}
+
+fn ics_event(fields: &HashMap<String, String>, day: NaiveDate) -> Option<AgendaEvent> {
+    let (start, all_day) = ics_time(fields.get("DTSTART")?)?;
+    let end = fields.get("DTEND").and_then(|v| ics_time(v)).map(|(t, _)| t);
+    let on_day = if all_day {
+        // DTEND of an all-day event is exclusive
+        let last = end.map_or(start.date(), |e| e.date().pred_opt().unwrap_or(e.date()).max(start.date()));
+        (start.date()..=last).contains(&day)
+    } else {
+        (start.date()..=end.unwrap_or(start).date()).contains(&day)
+    };
+    if !on_day {
+        return None;
+    }
+    let text = |key: &str| fields.get(key).map(|v| ics_text(v, false));
+    Some(AgendaEvent {
+        time: (!all_day && start.date() == day).then(|| start.format("%H:%M").to_string()),
+        end: end.filter(|e| !all_day && e.date() == day).map(|e| e.format("%H:%M").to_string()),
+        title: text("SUMMARY").unwrap_or_default(),
+        location: text("LOCATION").filter(|l| !l.is_empty()),
+        source: "calendar".to_string(),
+        path: String::new(),
+        id: fields.get("UID").cloned(),
+        note: None,
+    })
+}
+
+/// `20250301` (all-day), `20250301T090000` (floating/TZID, read as local) or
+/// `20250301T010000Z` (UTC, converted to local)
+pub fn ics_time(value: &str) -> Option<(NaiveDateTime, bool)> {
+    let value = value.trim();
+    if value.len() == 8 {
+        return Some((NaiveDate::parse_from_str(value, "%Y%m%d").ok()?.and_hms_opt(0, 0, 0)?, true));
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let t = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        let local: DateTime<Local> = Utc.from_utc_datetime(&t).into();
+        return Some((local.naive_local(), false));
+    }
+    Some((NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?, false))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_ics_events() {
+        let ics = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;TZID=Asia/Shanghai:20250301T090000\r\nDTEND;TZID=Asia/Shanghai:20250301T100000\r\nSUMMARY:周会\\, 产品\r\nLOCATION:会议室 \r\n A\r\nBEGIN:VALARM\r\nSUMMARY:alarm\r\nEND:VALARM\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250228\r\nDTEND;VALUE=DATE:20250302\r\nSUMMARY:假期\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let events = ics_events(ics, day("2025-03-01"));
+        assert_eq!(events.len(), 2);
+        assert_eq!((events[0].time.as_deref(), events[0].end.as_deref()), (Some("09:00"), Some("10:00")));
+        assert_eq!((events[0].title.as_str(), events[0].location.as_deref()), ("周会, 产品", Some("会议室 A")));
+        assert_eq!((events[1].title.as_str(), events[1].time.clone()), ("假期", None));
+        // The all-day DTEND is exclusive
+        assert!(ics_events(ics, day("2025-03-02")).is_empty());
+    }
+}
@@ -0,0 +1,360 @@
+//! Visual boards stored as `.canvas` files in the vault, in the JSON Canvas
+//! format Obsidian uses, so boards open in either app:
+//!
+//! ```json
+//! {
+//!   "nodes": [
+//!     { "id": "a", "type": "text", "x": 0, "y": 0, "width": 240, "height": 120, "text": "Launch" },
+//!     { "id": "b", "type": "file", "x": 320, "y": 0, "width": 400, "height": 300, "file": "projects/launch.md" },
+//!     { "id": "c", "type": "link", "x": 0, "y": 200, "width": 240, "height": 80, "url": "https://example.com" },
+//!     { "id": "g", "type": "group", "x": -20, "y": -20, "width": 780, "height": 360, "label": "Q3", "color": "4" }
+//!   ],
+//!   "edges": [{ "id": "e", "fromNode": "a", "fromSide": "right", "toNode": "b", "toSide": "left" }]
+//! }
+//! ```
+//!
+//! `file` nodes embed a note (or image) by vault-relative path, `subpath`
+//! optionally naming a `#heading`. Colors are a preset `"1"`–`"6"` (red,
+//! orange, yellow, green, cyan, purple) or `#rrggbb`. Fields this app does
+//! not know are kept as they are.
+
+use image::{Rgb, RgbImage};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+pub const EXTENSION: &str = "canvas";
+
+const PRESETS: [[u8; 3]; 6] = [[233, 49, 71], [236, 117, 0], [224, 172, 0], [8, 185, 78], [0, 191, 188], [120, 82, 238]];
+const DEFAULT_COLOR: [u8; 3] = [120, 120, 120];
+const BACKGROUND: [u8; 3] = [250, 250, 250];
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Canvas {
+    #[serde(default)]
+    pub nodes: Vec<CanvasNode>,
+    #[serde(default)]
+    pub edges: Vec<CanvasEdge>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct CanvasNode {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: NodeKind,
+    pub x: i64,
+    pub y: i64,
+    pub width: i64,
+    pub height: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Markdown, for `text` nodes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// Vault-relative, for `file` nodes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// `#heading` within `file`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subpath: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Title of a `group`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    #[default]
+    Text,
+    File,
+    Link,
+    Group,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasEdge {
+    pub id: String,
+    pub from_node: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_side: Option<Side>,
+    pub to_node: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_side: Option<Side>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Issue {
+    /// The node or edge
+    pub id: String,
+    pub message: String,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub fn parse(raw: &str) -> Result<Canvas, String> {
+    // Obsidian writes an empty file for a new canvas
+    if raw.trim().is_empty() {
+        return Ok(Canvas::default());
+    }
+    serde_json::from_str(raw).map_err(|e| tr!("Failed to parse: {}", e))
+}
+
+pub fn to_json(canvas: &Canvas) -> Result<String, String> {
+    serde_json::to_string_pretty(canvas).map_err(|e| tr!("Failed to serialize: {}", e))
+}
+
+/// What would make the canvas unreadable elsewhere: duplicate IDs, edges to
+/// missing nodes, nodes without a size or their content, bad colors
+pub fn problems(canvas: &Canvas) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut issue = |id: &str, message: String| issues.push(Issue { id: id.to_string(), message });
+    let mut ids = HashSet::new();
+    for node in &canvas.nodes {
+        if !ids.insert(node.id.as_str()) {
+            issue(&node.id, tr!("Duplicate id: {}", node.id));
+        }
+        if node.width <= 0 || node.height <= 0 {
+            issue(&node.id, tr!("Node {} has an invalid size", node.id));
+        }
+        let blank = |value: &Option<String>| value.as_deref().is_none_or(|v| v.trim().is_empty());
+        let missing = match node.kind {
+            // An empty card is fine, a card without the field is not
+            NodeKind::Text => node.text.is_none().then_some("text"),
+            NodeKind::File => blank(&node.file).then_some("file"),
+            NodeKind::Link => blank(&node.url).then_some("url"),
+            NodeKind::Group => None,
+        };
+        if let Some(field) = missing {
+            issue(&node.id, tr!("Node {} has no {}", node.id, field));
+        }
+        if let Some(color) = node.color.as_deref().filter(|c| rgb(c).is_none()) {
+            issue(&node.id, tr!("Invalid color: {}", color));
+        }
+    }
+    let mut edge_ids = HashSet::new();
+    for edge in &canvas.edges {
+        if ids.contains(edge.id.as_str()) || !edge_ids.insert(edge.id.as_str()) {
+            issue(&edge.id, tr!("Duplicate id: {}", edge.id));
+        }
+        for end in [&edge.from_node, &edge.to_node] {
+            if !ids.contains(end.as_str()) {
+                issue(&edge.id, tr!("Edge {} points at missing node {}", edge.id, end));
+            }
+        }
+        if let Some(color) = edge.color.as_deref().filter(|c| rgb(c).is_none()) {
+            issue(&edge.id, tr!("Invalid color: {}", color));
+        }
+    }
+    issues
+}
+
+/// `file` nodes whose file is not in the vault
+pub fn missing_files(vault: &Path, canvas: &Canvas) -> Vec<Issue> {
+    canvas
+        .nodes
+        .iter()
+        .filter_map(|node| Some((node, node.file.as_deref()?)))
+        .filter(|(_, file)| !file.trim().is_empty() && !vault.join(file).is_file())
+        .map(|(node, file)| Issue { id: node.id.clone(), message: tr!("File not found: {}", file) })
+        .collect()
+}
+
+/// Vault-relative files the canvas embeds, in node order without repeats
+pub fn references(canvas: &Canvas) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+    for file in canvas.nodes.iter().filter_map(|n| n.file.as_deref()) {
+        if !files.iter().any(|f| f == file) {
+            files.push(file.to_string());
+        }
+    }
+    files
+}
+
+/// A picture of the board no larger than `size` on its longest side: groups
+/// as outlines, other nodes as filled boxes, edges as lines between them
+pub fn render(canvas: &Canvas, size: u32) -> RgbImage {
+    let nodes: Vec<&CanvasNode> = canvas.nodes.iter().filter(|n| n.width > 0 && n.height > 0).collect();
+    let Some(left) = nodes.iter().map(|n| n.x).min() else {
+        return RgbImage::from_pixel(size, size * 3 / 4, Rgb(BACKGROUND));
+    };
+    let top = nodes.iter().map(|n| n.y).min().unwrap_or(0);
+    let right = nodes.iter().map(|n| n.x + n.width).max().unwrap_or(0);
+    let bottom = nodes.iter().map(|n| n.y + n.height).max().unwrap_or(0);
+
+    let margin = 8.0;
+    let (w, h) = ((right - left) as f64, (bottom - top) as f64);
+    let scale = (size as f64 - 2.0 * margin) / w.max(h);
+    let width = ((w * scale + 2.0 * margin).round() as u32).clamp(1, size);
+    let height = ((h * scale + 2.0 * margin).round() as u32).clamp(1, size);
+    let mut img = RgbImage::from_pixel(width, height, Rgb(BACKGROUND));
+    let at = |x: i64, y: i64| ((x - left) as f64 * scale + margin, (y - top) as f64 * scale + margin);
+
+    // Groups first so the nodes in them stay visible
+    let mut ordered = nodes.clone();
+    ordered.sort_by_key(|n| n.kind != NodeKind::Group);
+    for node in &ordered {
+        let color = node.color.as_deref().and_then(rgb).unwrap_or(DEFAULT_COLOR);
+        let (x0, y0) = at(node.x, node.y);
+        let (x1, y1) = at(node.x + node.width, node.y + node.height);
+        if node.kind != NodeKind::Group {
+            fill(&mut img, (x0, y0), (x1, y1), tint(color));
+        }
+        for (a, b) in [((x0, y0), (x1, y0)), ((x1, y0), (x1, y1)), ((x1, y1), (x0, y1)), ((x0, y1), (x0, y0))] {
+            line(&mut img, a, b, color);
+        }
+    }
+    let by_id: BTreeMap<&str, &CanvasNode> = nodes.iter().map(|n| (n.id.as_str(), *n)).collect();
+    for edge in &canvas.edges {
+        let (Some(from), Some(to)) = (by_id.get(edge.from_node.as_str()), by_id.get(edge.to_node.as_str())) else { continue };
+        let (fx, fy) = anchor(from, edge.from_side);
+        let (tx, ty) = anchor(to, edge.to_side);
+        let color = edge.color.as_deref().and_then(rgb).unwrap_or(DEFAULT_COLOR);
+        line(&mut img, at(fx, fy), at(tx, ty), color);
+    }
+    img
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// A preset number or `#rrggbb`
+fn rgb(color: &str) -> Option<[u8; 3]> {
+    let color = color.trim();
+    if let Ok(n) = color.parse::<usize>() {
+        return PRESETS.get(n.checked_sub(1)?).copied();
+    }
+    let hex = color.strip_prefix('#').filter(|h| h.len() == 6)?;
+    let byte = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some([byte(0)?, byte(2)?, byte(4)?])
+}
+
+/// The color mixed with the background, for box interiors
+fn tint(color: [u8; 3]) -> [u8; 3] {
+    color.map(|c| ((c as u16 + 3 * 255) / 4) as u8)
+}
+
+/// Middle of the given side, else of the node
+fn anchor(node: &CanvasNode, side: Option<Side>) -> (i64, i64) {
+    let (cx, cy) = (node.x + node.width / 2, node.y + node.height / 2);
+    match side {
+        Some(Side::Top) => (cx, node.y),
+        Some(Side::Right) => (node.x + node.width, cy),
+        Some(Side::Bottom) => (cx, node.y + node.height),
+        Some(Side::Left) => (node.x, cy),
+        None => (cx, cy),
+    }
+}
+
+fn fill(img: &mut RgbImage, (x0, y0): (f64, f64), (x1, y1): (f64, f64), color: [u8; 3]) {
+    let (w, h) = (img.width() as f64, img.height() as f64);
+    for y in y0.max(0.0) as u32..y1.min(h) as u32 {
+        for x in x0.max(0.0) as u32..x1.min(w) as u32 {
+            img.put_pixel(x, y, Rgb(color));
+        }
+    }
+}
+
+fn line(img: &mut RgbImage, (x0, y0): (f64, f64), (x1, y1): (f64, f64), color: [u8; 3]) {
+    let steps = (x1 - x0).abs().max((y1 - y0).abs()).ceil().max(1.0) as u32;
+    for i in 0..=steps {
+        let t = i as f64 / steps as f64;
+        let (x, y) = (x0 + (x1 - x0) * t, y0 + (y1 - y0) * t);
+        if x >= 0.0 && y >= 0.0 && (x as u32) < img.width() && (y as u32) < img.height() {
+            img.put_pixel(x as u32, y as u32, Rgb(color));
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOARD: &str = r##"{
+        "nodes": [
+            { "id": "a", "type": "text", "x": 0, "y": 0, "width": 200, "height": 100, "text": "发布计划", "color": "1" },
+            { "id": "b", "type": "file", "x": 300, "y": 0, "width": 200, "height": 100, "file": "projects/launch.md", "subpath": "#Budget" },
+            { "id": "g", "type": "group", "x": -50, "y": -50, "width": 600, "height": 200, "label": "Q3", "color": "#336699" }
+        ],
+        "edges": [{ "id": "e", "fromNode": "a", "fromSide": "right", "toNode": "b", "toSide": "left", "toEnd": "arrow" }]
+    }"##;
+
+    #[test]
+    fn test_round_trip_and_problems() {
+        let canvas = parse(BOARD).unwrap();
+        assert_eq!(canvas.nodes[1].kind, NodeKind::File);
+        assert_eq!(canvas.edges[0].from_side, Some(Side::Right));
+        assert!(problems(&canvas).is_empty());
+        assert_eq!(references(&canvas), vec!["projects/launch.md"]);
+
+        // Unknown fields survive a save
+        let json = to_json(&canvas).unwrap();
+        assert!(json.contains("\"toEnd\": \"arrow\"") && json.contains("\"fromNode\": \"a\""));
+        assert_eq!(parse(&json).unwrap(), canvas);
+        assert_eq!(parse("").unwrap(), Canvas::default());
+        assert!(parse("{\"nodes\": [{\"id\": \"x\", \"type\": \"blob\"}]}").is_err());
+
+        let mut broken = canvas.clone();
+        broken.nodes[1].id = "a".into();
+        broken.nodes[2].width = 0;
+        broken.nodes[0].color = Some("7".into());
+        let found: Vec<(String, String)> = problems(&broken).into_iter().map(|i| (i.id, i.message)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("a".to_string(), tr!("Invalid color: {}", "7")),
+                ("a".to_string(), tr!("Duplicate id: {}", "a")),
+                ("g".to_string(), tr!("Node {} has an invalid size", "g")),
+                ("e".to_string(), tr!("Edge {} points at missing node {}", "e", "b")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_files_and_render() {
+        let dir = tempfile::tempdir().unwrap();
+        let canvas = parse(BOARD).unwrap();
+        assert_eq!(missing_files(dir.path(), &canvas).len(), 1);
+        std::fs::create_dir_all(dir.path().join("projects")).unwrap();
+        std::fs::write(dir.path().join("projects/launch.md"), "# Launch\n").unwrap();
+        assert!(missing_files(dir.path(), &canvas).is_empty());
+
+        let img = render(&canvas, 256);
+        assert_eq!((img.width(), img.height()), (256, 96));
+        // Inside node a: its preset red, tinted
+        assert_eq!(img.get_pixel(60, 50).0, tint(PRESETS[0]));
+        assert_eq!(render(&Canvas::default(), 256).dimensions(), (256, 192));
+    }
+}
@@ -0,0 +1,589 @@
+//! Two-way CardDAV sync between an address book (Nextcloud, iCloud, any
+//! CardDAV server) and the people notes.
+//!
+//! `carddav.url` is the address book collection itself. Cards come in as
+//! people/<slug>.md; a card whose address (or name) matches a person that
+//! already exists, e.g. one created from mail, is merged into that note
+//! instead. Name, emails, phones, birthday, company and tags travel both
+//! ways; the note body never leaves the vault. Edits go back with
+//! `If-Match` on the etag last seen, so a card changed on the server in the
+//! meantime is never overwritten: the server's version wins and the person
+//! is listed under `conflicts`.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use super::calendar::ics_text;
+use super::notes::slugify;
+use super::people::{bare_address, load_people, person_path, read_person, unique_slug, write_person, Person, PersonMeta};
+use super::{connectors, durable, http};
+
+const STATE_FILE: &str = ".lifeos/carddav.json";
+/// Person frontmatter key for phone numbers, which PersonMeta has no field for
+const PHONES_KEY: &str = "phones";
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?><d:propfind xmlns:d="DAV:"><d:prop><d:getetag/><d:resourcetype/></d:prop></d:propfind>"#;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ContactSyncReport {
+    /// RFC 3339
+    pub finished: String,
+    /// Slugs of people created from new cards
+    pub created: Vec<String>,
+    /// Slugs of existing people a new card was merged into
+    pub merged: Vec<String>,
+    /// Slugs updated from changed cards
+    pub updated: Vec<String>,
+    /// Slugs whose edits were written back to the server
+    pub pushed: Vec<String>,
+    /// Slugs changed on both sides; the server's card won
+    pub conflicts: Vec<String>,
+    /// Cards deleted on the server; their notes are kept, unlinked
+    pub unlinked: Vec<String>,
+    /// `card: error`; retried on the next sync
+    pub errors: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct State {
+    /// Card href → what both sides agreed on at the last sync
+    cards: BTreeMap<String, Linked>,
+    last: Option<ContactSyncReport>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Linked {
+    slug: String,
+    /// Without quotes; None when the server did not return one after a PUT
+    etag: Option<String>,
+    /// Hash of the synced fields
+    synced: String,
+}
+
+/// What a card and a person note share
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+struct Fields {
+    name: String,
+    emails: Vec<String>,
+    phones: Vec<String>,
+    /// `YYYY-MM-DD`, or `MM-DD` without a year
+    birthday: Option<String>,
+    company: Option<String>,
+    tags: Vec<String>,
+}
+
+struct Server {
+    url: String,
+    auth: Option<String>,
+}
+
+/// A property line of a card, unfolded
+struct Property {
+    /// Upper case, without any `item1.` group
+    name: String,
+    value: String,
+    /// The line as it was, to write back untouched
+    line: String,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Report of the last finished sync
+pub fn last_report(vault: &Path) -> Option<ContactSyncReport> {
+    load_state(vault).last
+}
+
+/// Pull new and changed cards into people notes and push edited notes back
+pub fn sync(vault: &Path) -> Result<ContactSyncReport, String> {
+    let server = Server::from_config(vault)?;
+    let remote = server.list()?;
+    let mut state = load_state(vault);
+    let mut report = ContactSyncReport::default();
+
+    let gone: Vec<String> = state.cards.keys().filter(|href| !remote.contains_key(*href)).cloned().collect();
+    for href in gone {
+        if let Some(linked) = state.cards.remove(&href) {
+            report.unlinked.push(linked.slug);
+        }
+    }
+
+    for (href, etag) in &remote {
+        if let Err(e) = sync_card(vault, &server, href, etag, &mut state, &mut report) {
+            report.errors.push(format!("{href}: {e}"));
+        }
+    }
+
+    report.finished = Local::now().to_rfc3339();
+    state.last = Some(report.clone());
+    save_state(vault, &state)?;
+    Ok(report)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn sync_card(vault: &Path, server: &Server, href: &str, etag: &str, state: &mut State, report: &mut ContactSyncReport) -> Result<(), String> {
+    let Some(linked) = state.cards.get(href).cloned() else {
+        // New on the server: merge into a matching person or create one
+        let (raw, fetched) = server.get(href)?;
+        let card = parse_card(&raw);
+        let person = match matching_person(vault, &card) {
+            Some(mut person) => {
+                merge(&mut person, &card);
+                report.merged.push(person.slug.clone());
+                person
+            }
+            None => {
+                let slug = unique_slug(vault, &slugify(&card.name));
+                let mut person = Person { slug: slug.clone(), path: String::new(), meta: PersonMeta::default(), notes: String::new() };
+                apply(&mut person, &card);
+                report.created.push(slug);
+                person
+            }
+        };
+        write_person(&person_path(vault, &person.slug)?, &person)?;
+        let mut linked = Linked { slug: person.slug.clone(), etag: fetched.or_else(|| Some(etag.to_string())), synced: hash(&card) };
+        // Whatever the existing note added goes back
+        if hash(&fields_of(&person)) != linked.synced {
+            push(vault, server, href, &person, &mut linked, report)?;
+        }
+        state.cards.insert(href.to_string(), linked);
+        return Ok(());
+    };
+
+    let remote_changed = linked.etag.as_deref() != Some(etag);
+    let Some(mut person) = read_person(&person_path(vault, &linked.slug)?) else {
+        if remote_changed {
+            // Deleted here but edited there: bring it back
+            state.cards.remove(href);
+            report.conflicts.push(linked.slug);
+            return sync_card(vault, server, href, etag, state, report);
+        }
+        server.delete(href, etag)?;
+        state.cards.remove(href);
+        return Ok(());
+    };
+    let local_changed = hash(&fields_of(&person)) != linked.synced;
+
+    let mut linked = linked;
+    if remote_changed {
+        let (raw, fetched) = server.get(href)?;
+        let card = parse_card(&raw);
+        apply(&mut person, &card);
+        write_person(&person_path(vault, &person.slug)?, &person)?;
+        linked.etag = fetched.or_else(|| Some(etag.to_string()));
+        linked.synced = hash(&card);
+        if local_changed {
+            report.conflicts.push(person.slug.clone());
+        } else {
+            report.updated.push(person.slug.clone());
+        }
+    } else if local_changed {
+        push(vault, server, href, &person, &mut linked, report)?;
+    }
+    state.cards.insert(href.to_string(), linked);
+    Ok(())
+}
+
+/// Write the person's fields into the card, only if it is still the one
+/// last seen; otherwise the server's version is pulled over the note
+fn push(vault: &Path, server: &Server, href: &str, person: &Person, linked: &mut Linked, report: &mut ContactSyncReport) -> Result<(), String> {
+    let (raw, etag) = server.get(href)?;
+    let fields = fields_of(person);
+    if linked.etag.is_some() && etag != linked.etag {
+        return pull_over(vault, server, href, person, linked, report);
+    }
+    match server.put(href, &patch_card(&raw, &fields), etag.as_deref())? {
+        Some(new_etag) => linked.etag = new_etag,
+        // 412: changed between the GET and the PUT
+        None => return pull_over(vault, server, href, person, linked, report),
+    }
+    linked.synced = hash(&fields);
+    report.pushed.push(person.slug.clone());
+    Ok(())
+}
+
+fn pull_over(vault: &Path, server: &Server, href: &str, person: &Person, linked: &mut Linked, report: &mut ContactSyncReport) -> Result<(), String> {
+    let (raw, etag) = server.get(href)?;
+    let card = parse_card(&raw);
+    let mut person = person.clone();
+    apply(&mut person, &card);
+    write_person(&person_path(vault, &person.slug)?, &person)?;
+    linked.etag = etag;
+    linked.synced = hash(&card);
+    report.conflicts.push(person.slug);
+    Ok(())
+}
+
+/// The person with one of the card's addresses, else with its exact name
+fn matching_person(vault: &Path, card: &Fields) -> Option<Person> {
+    let people = load_people(vault);
+    let wanted: Vec<String> = card.emails.iter().map(|e| bare_address(e)).collect();
+    let by_email = people.iter().position(|p| p.meta.emails.iter().any(|e| wanted.contains(&bare_address(e))));
+    let by_name = || people.iter().position(|p| !card.name.is_empty() && p.meta.name.trim().eq_ignore_ascii_case(card.name.trim()));
+    by_email.or_else(by_name).map(|i| people[i].clone())
+}
+
+fn fields_of(person: &Person) -> Fields {
+    let phones = person.meta.extra.get(PHONES_KEY).and_then(|v| v.as_sequence());
+    Fields {
+        name: person.meta.name.clone(),
+        emails: person.meta.emails.clone(),
+        phones: phones.into_iter().flatten().filter_map(|p| p.as_str()).map(str::to_string).collect(),
+        birthday: person.meta.birthday.clone(),
+        company: person.meta.company.clone(),
+        tags: person.meta.tags.clone(),
+    }
+}
+
+/// Card wins: every shared field takes the card's value
+fn apply(person: &mut Person, card: &Fields) {
+    if !card.name.is_empty() {
+        person.meta.name = card.name.clone();
+    }
+    person.meta.emails = card.emails.clone();
+    person.meta.birthday = card.birthday.clone();
+    person.meta.company = card.company.clone();
+    person.meta.tags = card.tags.clone();
+    set_phones(person, &card.phones);
+}
+
+/// A new card joining an existing person: lists are combined, the person's
+/// own name and single values are kept where set
+fn merge(person: &mut Person, card: &Fields) {
+    let mut fields = fields_of(person);
+    for email in &card.emails {
+        if !fields.emails.iter().any(|e| bare_address(e) == bare_address(email)) {
+            fields.emails.push(email.clone());
+        }
+    }
+    for (mine, theirs) in [(&mut fields.phones, &card.phones), (&mut fields.tags, &card.tags)] {
+        for value in theirs {
+            if !mine.contains(value) {
+                mine.push(value.clone());
+            }
+        }
+    }
+    fields.birthday = fields.birthday.or_else(|| card.birthday.clone());
+    fields.company = fields.company.or_else(|| card.company.clone());
+    apply(person, &fields);
+}
+
+fn set_phones(person: &mut Person, phones: &[String]) {
+    if phones.is_empty() {
+        person.meta.extra.remove(PHONES_KEY);
+    } else {
+        let list = phones.iter().map(|p| serde_yaml::Value::String(p.clone())).collect();
+        person.meta.extra.insert(PHONES_KEY.to_string(), serde_yaml::Value::Sequence(list));
+    }
+}
+
+fn hash(fields: &Fields) -> String {
+    format!("{:x}", Sha256::digest(serde_json::to_string(fields).unwrap_or_default().as_bytes()))
+}
+
+/// Property lines of a card with continuation lines joined
+fn properties(raw: &str) -> Vec<Property> {
+    let unfolded = raw.replace("\r\n", "\n").replace("\n ", "").replace("\n\t", "");
+    unfolded
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let name = key.split(';').next()?;
+            let name = name.rsplit('.').next().unwrap_or(name).to_ascii_uppercase();
+            Some(Property { name, value: value.to_string(), line: line.to_string() })
+        })
+        .collect()
+}
+
+fn parse_card(raw: &str) -> Fields {
+    let props = properties(raw);
+    let values = |name: &'static str| props.iter().filter(move |p| p.name == name).map(|p| p.value.as_str());
+    let first = |name: &'static str| values(name).next().map(|v| ics_text(v, false).trim().to_string()).filter(|v| !v.is_empty());
+    let name = first("FN").or_else(|| {
+        // N is family;given;additional;prefix;suffix
+        let n = values("N").next()?;
+        let parts: Vec<String> = n.split(';').map(|p| ics_text(p, false).trim().to_string()).collect();
+        let (family, given) = (parts.first().cloned().unwrap_or_default(), parts.get(1).cloned().unwrap_or_default());
+        let cjk = family.chars().any(|c| c > '\u{2e80}');
+        Some(if cjk { format!("{family}{given}") } else { format!("{given} {family}").trim().to_string() })
+    });
+    Fields {
+        name: name.unwrap_or_default(),
+        emails: values("EMAIL").map(|v| v.trim().to_string()).filter(|v| !v.is_empty()).collect(),
+        phones: values("TEL").map(|v| v.trim().trim_start_matches("tel:").to_string()).filter(|v| !v.is_empty()).collect(),
+        birthday: values("BDAY").next().and_then(birthday),
+        company: values("ORG").next().map(|v| ics_text(v.split(';').next().unwrap_or_default(), false).trim().to_string()).filter(|v| !v.is_empty()),
+        tags: values("CATEGORIES").flat_map(|v| v.split(',')).map(|t| ics_text(t, false).trim().to_string()).filter(|t| !t.is_empty()).collect(),
+    }
+}
+
+/// `1990-05-01`, `19900501`, `--0501` or `--05-01` (no year)
+fn birthday(value: &str) -> Option<String> {
+    let digits: String = value.split('T').next()?.chars().filter(|c| c.is_ascii_digit()).collect();
+    match (value.starts_with("--"), digits.len()) {
+        (true, 4) => Some(format!("{}-{}", &digits[..2], &digits[2..])),
+        (false, 8) => Some(format!("{}-{}-{}", &digits[..4], &digits[4..6], &digits[6..])),
+        _ => None,
+    }
+}
+
+/// The card with the shared fields replaced; everything else (photo,
+/// addresses, parameters of unchanged emails and phones) stays as it was
+fn patch_card(raw: &str, fields: &Fields) -> String {
+    let props = properties(raw);
+    let mut lines: Vec<String> = Vec::new();
+    for p in &props {
+        let keep = match p.name.as_str() {
+            "FN" | "BDAY" | "CATEGORIES" | "END" => false,
+            "EMAIL" => fields.emails.iter().any(|e| bare_address(e) == bare_address(&p.value)),
+            "TEL" => fields.phones.iter().any(|t| t == p.value.trim().trim_start_matches("tel:")),
+            "ORG" => fields.company.as_deref().is_some_and(|c| ics_text(p.value.split(';').next().unwrap_or_default(), false).trim() == c),
+            _ => true,
+        };
+        if keep {
+            lines.push(p.line.clone());
+        }
+    }
+    let has = |name: &str, value: &str, same: &dyn Fn(&str, &str) -> bool| props.iter().any(|p| p.name == name && same(&p.value, value));
+    lines.push(format!("FN:{}", escape(&fields.name)));
+    for email in &fields.emails {
+        if !has("EMAIL", email, &|a, b| bare_address(a) == bare_address(b)) {
+            lines.push(format!("EMAIL;TYPE=INTERNET:{email}"));
+        }
+    }
+    for phone in &fields.phones {
+        if !has("TEL", phone, &|a, b| a.trim().trim_start_matches("tel:") == b) {
+            lines.push(format!("TEL:{phone}"));
+        }
+    }
+    if let Some(company) = &fields.company {
+        if !has("ORG", company, &|a, b| ics_text(a.split(';').next().unwrap_or_default(), false).trim() == b) {
+            lines.push(format!("ORG:{}", escape(company)));
+        }
+    }
+    if let Some(day) = &fields.birthday {
+        // vCard 3 writes a missing year as --MMDD
+        lines.push(match day.len() {
+            5 => format!("BDAY:--{}", day.replace('-', "")),
+            _ => format!("BDAY:{day}"),
+        });
+    }
+    if !fields.tags.is_empty() {
+        lines.push(format!("CATEGORIES:{}", fields.tags.iter().map(|t| escape(t)).collect::<Vec<_>>().join(",")));
+    }
+    lines.push("END:VCARD".to_string());
+    lines.join("\r\n") + "\r\n"
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,").replace('\n', "\\n")
+}
+
+/// Inner text of every element with this local name, whatever its prefix
+pub(crate) fn elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let mut out = Vec::new();
+    let mut rest = xml;
+    while let Some(open) = rest.find('<') {
+        rest = &rest[open + 1..];
+        let Some(end) = rest.find('>') else { break };
+        let tag = &rest[..end];
+        let tag_name = tag.split(|c: char| c.is_whitespace() || c == '/').next().unwrap_or_default();
+        if tag.starts_with(['/', '?', '!']) || tag_name.rsplit(':').next() != Some(name) {
+            continue;
+        }
+        rest = &rest[end + 1..];
+        if tag.ends_with('/') {
+            out.push("");
+            continue;
+        }
+        let close = format!("</{tag_name}>");
+        let Some(stop) = rest.find(&close) else { break };
+        out.push(&rest[..stop]);
+        rest = &rest[stop + close.len()..];
+    }
+    out
+}
+
+/// href → etag of the cards in a PROPFIND multistatus, skipping collections
+fn parse_multistatus(xml: &str) -> BTreeMap<String, String> {
+    elements(xml, "response")
+        .into_iter()
+        .filter(|response| elements(response, "collection").is_empty())
+        .filter_map(|response| {
+            let href = elements(response, "href").first()?.trim().replace("&amp;", "&");
+            let etag = elements(response, "getetag").first().map(|e| bare_etag(&e.replace("&quot;", "\"")))?;
+            Some((href, etag))
+        })
+        .collect()
+}
+
+/// `W/"abc"` or `"abc"` → `abc`
+fn bare_etag(etag: &str) -> String {
+    etag.trim().trim_start_matches("W/").trim_matches('"').to_string()
+}
+
+impl Server {
+    fn from_config(vault: &Path) -> Result<Server, String> {
+        let value = |key: &str| connectors::value(vault, "carddav", key);
+        let url = value("url").ok_or_else(|| tr!("CardDAV is not configured: {}", "carddav.url"))?;
+        let auth = value("username").map(|user| format!("Basic {}", STANDARD.encode(format!("{user}:{}", value("password").unwrap_or_default()))));
+        Ok(Server { url: format!("{}/", url.trim_end_matches('/')), auth })
+    }
+
+    /// Absolute URL of an href, which servers give as a path
+    fn resolve(&self, href: &str) -> String {
+        if href.starts_with("http://") || href.starts_with("https://") {
+            return href.to_string();
+        }
+        let origin_end = self.url.find("://").map_or(0, |i| i + 3);
+        let origin = self.url[origin_end..].find('/').map_or(self.url.as_str(), |i| &self.url[..origin_end + i]);
+        format!("{origin}{href}")
+    }
+
+    fn request(&self, method: &str, url: &str) -> Result<ureq::Request, String> {
+        let request = http::agent()?.request(method, url);
+        Ok(match &self.auth {
+            Some(auth) => request.set("Authorization", auth),
+            None => request,
+        })
+    }
+
+    /// href → etag of every card in the address book
+    fn list(&self) -> Result<BTreeMap<String, String>, String> {
+        let response = self
+            .request("PROPFIND", &self.url)?
+            .set("Depth", "1")
+            .set("Content-Type", "application/xml; charset=utf-8")
+            .send_string(PROPFIND_BODY)
+            .map_err(|e| tr!("Request failed: {}", e))?;
+        let xml = response.into_string().map_err(|e| tr!("Request failed: {}", e))?;
+        Ok(parse_multistatus(&xml))
+    }
+
+    /// The card and its etag
+    fn get(&self, href: &str) -> Result<(String, Option<String>), String> {
+        let response = self.request("GET", &self.resolve(href))?.call().map_err(|e| tr!("Request failed: {}", e))?;
+        let etag = response.header("ETag").map(bare_etag);
+        let mut raw = String::new();
+        response.into_reader().read_to_string(&mut raw).map_err(|e| tr!("Request failed: {}", e))?;
+        Ok((raw, etag))
+    }
+
+    /// Some(new etag, if the server sent one) once written; None when the
+    /// card no longer matches `etag` (412)
+    fn put(&self, href: &str, card: &str, etag: Option<&str>) -> Result<Option<Option<String>>, String> {
+        let mut request = self.request("PUT", &self.resolve(href))?.set("Content-Type", "text/vcard; charset=utf-8");
+        if let Some(etag) = etag {
+            request = request.set("If-Match", &format!("\"{etag}\""));
+        }
+        match request.send_string(card) {
+            Ok(response) => Ok(Some(response.header("ETag").map(bare_etag))),
+            Err(ureq::Error::Status(412, _)) => Ok(None),
+            Err(e) => Err(tr!("Request failed: {}", e)),
+        }
+    }
+
+    fn delete(&self, href: &str, etag: &str) -> Result<(), String> {
+        match self.request("DELETE", &self.resolve(href))?.set("If-Match", &format!("\"{etag}\"")).call() {
+            Ok(_) | Err(ureq::Error::Status(404, _)) => Ok(()),
+            Err(e) => Err(tr!("Request failed: {}", e)),
+        }
+    }
+}
+
+fn load_state(vault: &Path) -> State {
+    fs::read_to_string(vault.join(STATE_FILE)).ok().and_then(|raw| serde_json::from_str(&raw).ok()).unwrap_or_default()
+}
+
+fn save_state(vault: &Path, state: &State) -> Result<(), String> {
+    let path = vault.join(STATE_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(state).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(path, json).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARD: &str = "BEGIN:VCARD\r\nVERSION:3.0\r\nUID:42\r\nFN:Jane Doe\r\nN:Doe;Jane;;;\r\nitem1.EMAIL;TYPE=WORK:jane@work.com\r\nTEL;TYPE=CELL:+1 555 0100\r\nORG:Acme\\, Inc.;R&D\r\nBDAY:--0501\r\nCATEGORIES:friends,hiking\r\nPHOTO;ENCODING=b;TYPE=JPEG:AAAA\r\n BBBB\r\nEND:VCARD\r\n";
+
+    #[test]
+    fn test_parse_and_patch_card() {
+        let card = parse_card(CARD);
+        assert_eq!(card.name, "Jane Doe");
+        assert_eq!(card.emails, ["jane@work.com"]);
+        assert_eq!(card.phones, ["+1 555 0100"]);
+        assert_eq!((card.birthday.as_deref(), card.company.as_deref()), (Some("05-01"), Some("Acme, Inc.")));
+        assert_eq!(card.tags, ["friends", "hiking"]);
+        assert_eq!(parse_card("BEGIN:VCARD\r\nN:张;三;;;\r\nEND:VCARD\r\n").name, "张三");
+
+        let mut edited = card.clone();
+        edited.emails.push("jane@home.org".into());
+        edited.company = Some("Globex".into());
+        edited.birthday = Some("1990-05-01".into());
+        let patched = patch_card(CARD, &edited);
+        assert!(patched.contains("item1.EMAIL;TYPE=WORK:jane@work.com\r\n"));
+        assert!(patched.contains("EMAIL;TYPE=INTERNET:jane@home.org\r\n"));
+        assert!(patched.contains("PHOTO;ENCODING=b;TYPE=JPEG:AAAABBBB\r\n"));
+        assert!(patched.contains("ORG:Globex\r\nBDAY:1990-05-01\r\n") && !patched.contains("Acme"));
+        assert!(patched.ends_with("CATEGORIES:friends,hiking\r\nEND:VCARD\r\n"));
+        assert_eq!(parse_card(&patched), edited);
+    }
+
+    #[test]
+    fn test_merge_into_mail_contact() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        fs::create_dir_all(v.join("people")).unwrap();
+        fs::write(v.join("people/jane.md"), "---\nname: Jane D.\nemails: [Jane@Work.com]\ntags: [work]\nlast_contacted: 2026-03-01\n---\n\nMet at PyCon.\n").unwrap();
+
+        let card = parse_card(CARD);
+        let mut person = matching_person(v, &card).unwrap();
+        assert_eq!(person.slug, "jane");
+        merge(&mut person, &card);
+        assert_eq!(person.meta.name, "Jane D.");
+        assert_eq!(person.meta.emails, ["Jane@Work.com"]);
+        assert_eq!(person.meta.tags, ["work", "friends", "hiking"]);
+        assert_eq!(fields_of(&person).phones, ["+1 555 0100"]);
+        assert_ne!(hash(&fields_of(&person)), hash(&card));
+        assert!(matching_person(v, &parse_card("BEGIN:VCARD\r\nFN:Bob\r\nEND:VCARD\r\n")).is_none());
+    }
+
+    #[test]
+    fn test_parse_multistatus() {
+        let xml = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:card="urn:ietf:params:xml:ns:carddav">
+  <d:response><d:href>/dav/contacts/</d:href><d:propstat><d:prop><d:resourcetype><d:collection/><card:addressbook/></d:resourcetype><d:getetag/></d:prop></d:propstat></d:response>
+  <d:response><d:href>/dav/contacts/42.vcf</d:href><d:propstat><d:prop><d:getetag>"e1"</d:getetag><d:resourcetype/></d:prop></d:propstat></d:response>
+  <D:response xmlns:D="DAV:"><D:href>/dav/contacts/a%20b.vcf</D:href><D:propstat><D:prop><D:getetag>W/&quot;e2&quot;</D:getetag></D:prop></D:propstat></D:response>
+</d:multistatus>"#;
+        let cards = parse_multistatus(xml);
+        assert_eq!(cards.len(), 2);
+        assert_eq!(cards["/dav/contacts/42.vcf"], "e1");
+        assert_eq!(cards["/dav/contacts/a%20b.vcf"], "e2");
+
+        let server = Server { url: "https://cloud.example.com/dav/contacts/".into(), auth: None };
+        assert_eq!(server.resolve("/dav/contacts/42.vcf"), "https://cloud.example.com/dav/contacts/42.vcf");
+    }
+}
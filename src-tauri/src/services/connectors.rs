@@ -0,0 +1,30 @@
+//! Read access to `.lifeos/connectors.yaml`, where third-party credentials live.
+
+use std::fs;
+use std::path::Path;
+
+const CONNECTORS_FILE: &str = ".lifeos/connectors.yaml";
+
+/// The `name:` section of connectors.yaml, if present
+pub fn section(vault: &Path, name: &str) -> Option<serde_yaml::Value> {
+    let raw = fs::read_to_string(vault.join(CONNECTORS_FILE)).ok()?;
+    let doc: serde_yaml::Value = serde_yaml::from_str(&raw).ok()?;
+    doc.get(name).cloned()
+}
+
+/// Non-empty string `key` of connector `name`; `keychain:<name>` values
+/// are read from the Keychain
+pub fn value(vault: &Path, name: &str, key: &str) -> Option<String> {
+    let raw = section(vault, name)?
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())?;
+    match super::secrets::resolve(&raw) {
+        Ok(v) => Some(v),
+        Err(e) => {
+            println!("[WARN] {name}.{key}: {e}");
+            None
+        }
+    }
+}
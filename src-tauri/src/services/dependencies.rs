@@ -0,0 +1,231 @@
+//! `depends_on` links between tasks and projects.
+//!
+//! - A task gets an id with `🆔id` and waits on others with `⛔a,b`:
+//!   `- [ ] 发布 🆔ship ⛔review,landing-page`
+//! - A project lists them in frontmatter: `depends_on: [review, landing-page]`
+//!
+//! A reference names a task id, else a project by file name (`[[...]]`,
+//! `projects/` and `.md` optional). Tasks are complete when checked, projects
+//! when `status: done`. Anything waiting on something incomplete is blocked.
+
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use super::notes::split_frontmatter;
+use super::tasks;
+
+const PROJECTS_DIR: &str = "projects";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Dependent {
+    /// "task" | "project"
+    pub kind: String,
+    /// Task text or project title
+    pub name: String,
+    pub path: String,
+    /// 0-based line of a task
+    pub line: Option<usize>,
+    /// References not yet complete
+    pub waiting_on: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DependencyProblem {
+    pub path: String,
+    pub line: Option<usize>,
+    pub reference: String,
+    /// "missing" | "cycle" | "duplicate-id"
+    pub problem: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DependencyReport {
+    /// Unfinished tasks and projects waiting on something incomplete
+    pub blocked: Vec<Dependent>,
+    pub problems: Vec<DependencyProblem>,
+}
+
+struct Node {
+    kind: &'static str,
+    /// How other items refer to it: a task's 🆔, a project's file name
+    key: Option<String>,
+    name: String,
+    path: String,
+    line: Option<usize>,
+    done: bool,
+    depends_on: Vec<String>,
+}
+
+pub fn analyze(vault: &Path) -> DependencyReport {
+    let mut nodes = task_nodes(vault);
+    nodes.extend(project_nodes(vault));
+    let mut report = DependencyReport::default();
+
+    // Task ids first, so a task can shadow a project of the same name
+    let mut by_ref: HashMap<String, usize> = HashMap::new();
+    for (i, node) in nodes.iter().enumerate() {
+        let Some(key) = &node.key else { continue };
+        if node.kind == "task" && by_ref.contains_key(key) {
+            report.problems.push(problem(node, key, "duplicate-id"));
+            continue;
+        }
+        by_ref.entry(key.clone()).or_insert(i);
+    }
+
+    let edges: Vec<Vec<usize>> = nodes
+        .iter()
+        .map(|node| {
+            let mut out = Vec::new();
+            for reference in &node.depends_on {
+                match by_ref.get(&normalize(reference)) {
+                    Some(&j) => out.push(j),
+                    None => report.problems.push(problem(node, reference, "missing")),
+                }
+            }
+            out
+        })
+        .collect();
+
+    for (i, node) in nodes.iter().enumerate() {
+        if in_cycle(i, &edges) {
+            report.problems.push(problem(node, node.key.as_deref().unwrap_or(&node.name), "cycle"));
+        }
+        if node.done {
+            continue;
+        }
+        let waiting_on: Vec<String> = node
+            .depends_on
+            .iter()
+            .filter(|r| by_ref.get(&normalize(r)).is_some_and(|&j| !nodes[j].done))
+            .cloned()
+            .collect();
+        if !waiting_on.is_empty() {
+            report.blocked.push(Dependent {
+                kind: node.kind.to_string(),
+                name: node.name.clone(),
+                path: node.path.clone(),
+                line: node.line,
+                waiting_on,
+            });
+        }
+    }
+    report
+}
+
+/// Every task in every day file, checked or not
+fn task_nodes(vault: &Path) -> Vec<Node> {
+    let vault_path = vault.to_string_lossy().to_string();
+    let mut dates: Vec<String> = fs::read_dir(vault.join("daily/tasks"))
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.file_name().to_string_lossy().strip_suffix(".md").map(str::to_string))
+        .collect();
+    dates.sort();
+    dates
+        .into_iter()
+        .flat_map(|date| {
+            let path = vault.join("daily/tasks").join(format!("{date}.md")).to_string_lossy().to_string();
+            tasks::day_tasks(&vault_path, &date).into_iter().map(move |t| Node {
+                kind: "task",
+                key: t.id.as_deref().map(normalize),
+                name: t.text,
+                path: path.clone(),
+                line: Some(t.line),
+                done: t.done,
+                depends_on: t.depends_on,
+            })
+        })
+        .collect()
+}
+
+fn project_nodes(vault: &Path) -> Vec<Node> {
+    WalkDir::new(vault.join(PROJECTS_DIR))
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|x| x == "md"))
+        .filter_map(|e| {
+            let raw = fs::read_to_string(e.path()).ok()?;
+            let doc: Value = serde_yaml::from_str(split_frontmatter(&raw).0?).ok()?;
+            let stem = e.path().file_stem()?.to_string_lossy().to_string();
+            let depends_on = match doc.get("depends_on") {
+                Some(Value::Sequence(items)) => items.iter().filter_map(|v| v.as_str()).map(str::to_string).collect(),
+                Some(Value::String(s)) => s.split(',').map(|r| r.trim().to_string()).filter(|r| !r.is_empty()).collect(),
+                _ => Vec::new(),
+            };
+            Some(Node {
+                kind: "project",
+                key: Some(normalize(&stem)),
+                name: doc.get("title").and_then(|v| v.as_str()).map(str::to_string).unwrap_or(stem),
+                path: e.path().to_string_lossy().to_string(),
+                line: None,
+                done: doc.get("status").and_then(|v| v.as_str()) == Some("done"),
+                depends_on,
+            })
+        })
+        .collect()
+}
+
+fn normalize(reference: &str) -> String {
+    let r = reference.trim().trim_start_matches("[[").trim_end_matches("]]");
+    let r = r.split('|').next().unwrap_or_default();
+    let r = r.strip_prefix("projects/").unwrap_or(r);
+    r.strip_suffix(".md").unwrap_or(r).trim().to_lowercase()
+}
+
+fn in_cycle(start: usize, edges: &[Vec<usize>]) -> bool {
+    let mut seen = HashSet::new();
+    let mut stack: Vec<usize> = edges[start].clone();
+    while let Some(i) = stack.pop() {
+        if i == start {
+            return true;
+        }
+        if seen.insert(i) {
+            stack.extend(&edges[i]);
+        }
+    }
+    false
+}
+
+fn problem(node: &Node, reference: &str, kind: &str) -> DependencyProblem {
+    DependencyProblem { path: node.path.clone(), line: node.line, reference: reference.to_string(), problem: kind.to_string() }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(vault: &Path, rel: &str, text: &str) {
+        let path = vault.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    #[test]
+    fn test_blocked_and_problems() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        write(v, "daily/tasks/2025-03-03.md", "- [x] 定稿 🆔draft\n- [ ] 评审 🆔review ⛔draft\n- [ ] 发布 ⛔review,[[官网改版]]\n- [ ] 乱引用 ⛔nowhere\n- [ ] A 🆔a ⛔b\n- [ ] B 🆔b ⛔a\n");
+        write(v, "projects/官网改版.md", "---\ntitle: 官网改版\nstatus: active\ndepends_on: [draft]\n---\n");
+        write(v, "projects/季度复盘.md", "---\ntitle: 季度复盘\nstatus: todo\ndepends_on: 官网改版\n---\n");
+
+        let report = analyze(v);
+        let blocked: Vec<(&str, Vec<&str>)> =
+            report.blocked.iter().map(|b| (b.name.as_str(), b.waiting_on.iter().map(String::as_str).collect())).collect();
+        assert_eq!(
+            blocked,
+            vec![("发布", vec!["review", "[[官网改版]]"]), ("A", vec!["b"]), ("B", vec!["a"]), ("季度复盘", vec!["官网改版"])]
+        );
+        let problems: Vec<(&str, &str)> = report.problems.iter().map(|p| (p.reference.as_str(), p.problem.as_str())).collect();
+        assert_eq!(problems, vec![("nowhere", "missing"), ("a", "cycle"), ("b", "cycle")]);
+    }
+}
@@ -0,0 +1,548 @@
+//! A year of diary entries as one book, for printing or archiving. The
+//! format follows the destination's extension:
+//!
+//! - `.epub` — an EPUB 3 with a chapter per month, for e-readers and archives
+//! - `.html` — a single page laid out for paper (A5, a page per month), with
+//!   the photos inlined; print it to PDF from the browser or system dialog
+//!
+//! Entries are the notes under diary/ dated in the year (frontmatter `date`,
+//! else the file name), oldest first. Each gets its title, mood, energy and
+//! weather; `![[note]]` embeds are expanded and photos scaled down to
+//! `MAX_IMAGE` pixels. The markdown is rendered to plain XHTML: headings,
+//! lists, quotes, code, bold, inline code, links and images.
+
+use base64::Engine;
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::embeds;
+use super::notes::{split_frontmatter, vault_rel};
+
+const DIARY_DIR: &str = "diary";
+/// Longest side of a photo in the book, in pixels
+const MAX_IMAGE: u32 = 1200;
+const IMAGE_EXTS: [&str; 5] = ["jpg", "jpeg", "png", "gif", "webp"];
+
+const STYLE: &str = "body { font-family: serif; line-height: 1.6; margin: 0 5%; }
+h1 { text-align: center; margin: 2em 0 1em; }
+h2 { margin: 2em 0 0.2em; font-size: 1.2em; border-bottom: 1px solid #ccc; }
+.meta { color: #666; font-size: 0.9em; margin: 0 0 1em; }
+img { max-width: 100%; height: auto; display: block; margin: 1em auto; }
+blockquote { border-left: 3px solid #ccc; margin-left: 0; padding-left: 1em; color: #444; }
+pre { white-space: pre-wrap; font-size: 0.85em; background: #f5f5f5; padding: 0.5em; }
+.title { text-align: center; margin-top: 40%; }
+";
+/// Added to the single-page version for paper
+const PRINT_STYLE: &str = "@page { size: A5; margin: 18mm 15mm; }
+section.month { page-break-before: always; }
+h2, img { page-break-inside: avoid; }
+";
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct BookReport {
+    /// The file written
+    pub path: String,
+    pub entries: usize,
+    pub images: usize,
+    /// Image references that could not be found or decoded, as written
+    pub missing_images: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Epub,
+    Html,
+}
+
+struct Entry {
+    date: NaiveDate,
+    /// Vault-relative
+    rel: String,
+    frontmatter: Value,
+    body: String,
+}
+
+/// Rendered months and the photos they use
+#[derive(Default)]
+struct Book {
+    /// (month, XHTML of its entries)
+    months: Vec<(u32, String)>,
+    /// (file name under images/, JPEG bytes)
+    images: Vec<(String, Vec<u8>)>,
+    /// Source file → its name under images/
+    image_names: HashMap<PathBuf, String>,
+    missing_images: Vec<String>,
+    /// Lowercased file name → image, built on the first bare-name lookup
+    image_index: Option<HashMap<String, PathBuf>>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Write `year`'s diary to `dest` (`.epub` or `.html`)
+pub fn export(vault: &Path, year: i32, dest: &Path) -> Result<BookReport, String> {
+    let ext = dest.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let format = match ext.as_str() {
+        "epub" => Format::Epub,
+        "html" | "htm" => Format::Html,
+        _ => return Err(tr!("Unsupported book format: {}", dest.display())),
+    };
+    let entries = entries(vault, year);
+    if entries.is_empty() {
+        return Err(tr!("No diary entries in {}", year));
+    }
+
+    let mut book = Book::default();
+    for entry in &entries {
+        let html = book.render_entry(vault, entry);
+        let month = chrono::Datelike::month(&entry.date);
+        match book.months.last_mut() {
+            Some((m, text)) if *m == month => text.push_str(&html),
+            _ => book.months.push((month, html)),
+        }
+    }
+    let title = tr!("Diary {}", year);
+    let bytes = match format {
+        Format::Epub => epub(&book, &title, year)?,
+        Format::Html => single_page(&book, &title, year).into_bytes(),
+    };
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    // Written aside and renamed, so a failed export never leaves half a book
+    let partial = dest.with_extension(format!("{ext}.partial"));
+    fs::write(&partial, bytes).map_err(|e| tr!("write_file failed: {}", e))?;
+    fs::rename(&partial, dest).map_err(|e| tr!("write_file failed: {}", e))?;
+    Ok(BookReport {
+        path: dest.to_string_lossy().to_string(),
+        entries: entries.len(),
+        images: book.images.len(),
+        missing_images: book.missing_images,
+    })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// The year's entries, oldest first (same day: by file name)
+fn entries(vault: &Path, year: i32) -> Vec<Entry> {
+    let files = WalkDir::new(vault.join(DIARY_DIR))
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.file_name() != "templates" && !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|x| x == "md"));
+    let mut out = Vec::new();
+    for file in files {
+        let Ok(raw) = fs::read_to_string(file.path()) else { continue };
+        let frontmatter: Value = split_frontmatter(&raw).0.and_then(|y| serde_yaml::from_str(y).ok()).unwrap_or_default();
+        let from_fm = frontmatter.get("date").and_then(Value::as_str).map(str::to_string);
+        let from_name = Some(file.file_name().to_string_lossy().to_string());
+        let Some(date) = [from_fm, from_name].into_iter().flatten().find_map(|d| NaiveDate::parse_from_str(d.get(..10)?, "%Y-%m-%d").ok())
+        else {
+            continue;
+        };
+        if chrono::Datelike::year(&date) != year {
+            continue;
+        }
+        // Expanded here, so an embedded note reads as part of the entry
+        let expanded = embeds::resolve_text(vault, file.path(), &raw).content;
+        let body = split_frontmatter(&expanded).1.to_string();
+        let rel = vault_rel(vault, file.path());
+        out.push(Entry { date, rel, frontmatter, body });
+    }
+    out.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.rel.cmp(&b.rel)));
+    out
+}
+
+impl Book {
+    fn render_entry(&mut self, vault: &Path, entry: &Entry) -> String {
+        let fm = &entry.frontmatter;
+        let text = |key: &str| match fm.get(key) {
+            Some(Value::String(s)) => Some(s.trim().to_string()).filter(|s| !s.is_empty()),
+            Some(Value::Number(n)) => Some(n.to_string()),
+            _ => None,
+        };
+        let mut heading = entry.date.format("%Y-%m-%d").to_string();
+        if let Some(title) = text("title") {
+            heading = format!("{heading} · {title}");
+        }
+        let meta: Vec<String> = [
+            text("mood"),
+            text("energy").map(|e| tr!("Energy: {}", e)),
+            text("weather"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        let dir = vault.join(&entry.rel).parent().map(Path::to_path_buf).unwrap_or_else(|| vault.to_path_buf());
+        // The date heading stands in for the note's own `# title`
+        let body = match entry.body.trim_start().split_once('\n') {
+            Some((first, rest)) if first.starts_with("# ") => rest,
+            None if entry.body.trim_start().starts_with("# ") => "",
+            _ => entry.body.as_str(),
+        };
+        let mut out = format!("<h2>{}</h2>\n", escape(&heading));
+        if !meta.is_empty() {
+            out.push_str(&format!("<p class=\"meta\">{}</p>\n", escape(&meta.join(" · "))));
+        }
+        out.push_str(&markdown(body, &mut |src| self.image(vault, &dir, src)));
+        out
+    }
+
+    /// `images/NNN.jpg` for a photo the entry refers to, added on first use
+    fn image(&mut self, vault: &Path, dir: &Path, src: &str) -> Option<String> {
+        let src = src.trim().replace("%20", " ");
+        if src.contains("://") || src.starts_with("data:") {
+            return None;
+        }
+        let Some(path) = self.find_image(vault, dir, &src) else {
+            self.missing_images.push(src);
+            return None;
+        };
+        if let Some(name) = self.image_names.get(&path) {
+            return Some(format!("images/{name}"));
+        }
+        let Ok(img) = image::open(&path) else {
+            self.missing_images.push(src);
+            return None;
+        };
+        let img = if img.width().max(img.height()) > MAX_IMAGE { img.thumbnail(MAX_IMAGE, MAX_IMAGE) } else { img };
+        let mut bytes = Cursor::new(Vec::new());
+        if img.into_rgb8().write_to(&mut bytes, image::ImageFormat::Jpeg).is_err() {
+            self.missing_images.push(src);
+            return None;
+        }
+        let name = format!("{:03}.jpg", self.images.len() + 1);
+        self.images.push((name.clone(), bytes.into_inner()));
+        self.image_names.insert(path, name.clone());
+        Some(format!("images/{name}"))
+    }
+
+    /// Relative to the note, else to the vault, else any image with that name
+    fn find_image(&mut self, vault: &Path, dir: &Path, src: &str) -> Option<PathBuf> {
+        let found = [dir.join(src), vault.join(src.trim_start_matches('/'))].into_iter().find(|p| p.is_file());
+        if found.is_some() {
+            return found;
+        }
+        let name = Path::new(src).file_name()?.to_string_lossy().to_lowercase();
+        let index = self.image_index.get_or_insert_with(|| {
+            WalkDir::new(vault)
+                .sort_by_file_name()
+                .into_iter()
+                .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file())
+                .filter(|e| e.path().extension().is_some_and(|x| IMAGE_EXTS.contains(&x.to_string_lossy().to_lowercase().as_str())))
+                .map(|e| (e.file_name().to_string_lossy().to_lowercase(), e.path().to_path_buf()))
+                .collect()
+        });
+        index.get(&name).cloned()
+    }
+}
+
+fn epub(book: &Book, title: &str, year: i32) -> Result<Vec<u8>, String> {
+    let lang = crate::i18n::locale().as_str();
+    let chapter = |month: u32| format!("month-{month:02}.xhtml");
+    let month_title = |month: u32| format!("{year}-{month:02}");
+
+    let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+    files.push((
+        "META-INF/container.xml".into(),
+        b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<container version=\"1.0\" xmlns=\"urn:oasis:names:tc:opendocument:xmlns:container\">\n  <rootfiles>\n    <rootfile full-path=\"OEBPS/content.opf\" media-type=\"application/oebps-package+xml\"/>\n  </rootfiles>\n</container>\n".to_vec(),
+    ));
+    files.push(("OEBPS/style.css".into(), STYLE.as_bytes().to_vec()));
+    files.push(("OEBPS/title.xhtml".into(), xhtml(lang, title, &format!("<h1 class=\"title\">{}</h1>\n", escape(title))).into_bytes()));
+    let toc: String = book.months.iter().map(|(m, _)| format!("      <li><a href=\"{}\">{}</a></li>\n", chapter(*m), month_title(*m))).collect();
+    let nav = format!("<nav epub:type=\"toc\" id=\"toc\">\n  <h1>{}</h1>\n  <ol>\n{toc}  </ol>\n</nav>\n", escape(title));
+    files.push(("OEBPS/nav.xhtml".into(), xhtml(lang, title, &nav).into_bytes()));
+    for (month, html) in &book.months {
+        let body = format!("<h1>{}</h1>\n{html}", month_title(*month));
+        files.push((format!("OEBPS/{}", chapter(*month)), xhtml(lang, &month_title(*month), &body).into_bytes()));
+    }
+    for (name, bytes) in &book.images {
+        files.push((format!("OEBPS/images/{name}"), bytes.clone()));
+    }
+
+    let mut manifest = String::from(
+        "    <item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/>\n    <item id=\"style\" href=\"style.css\" media-type=\"text/css\"/>\n    <item id=\"title\" href=\"title.xhtml\" media-type=\"application/xhtml+xml\"/>\n",
+    );
+    let mut spine = String::from("    <itemref idref=\"title\"/>\n    <itemref idref=\"nav\"/>\n");
+    for (month, _) in &book.months {
+        manifest.push_str(&format!("    <item id=\"m{month:02}\" href=\"{}\" media-type=\"application/xhtml+xml\"/>\n", chapter(*month)));
+        spine.push_str(&format!("    <itemref idref=\"m{month:02}\"/>\n"));
+    }
+    for (i, (name, _)) in book.images.iter().enumerate() {
+        manifest.push_str(&format!("    <item id=\"img{}\" href=\"images/{name}\" media-type=\"image/jpeg\"/>\n", i + 1));
+    }
+    let opf = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" unique-identifier=\"book-id\">\n  <metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n    <dc:identifier id=\"book-id\">urn:uuid:{}</dc:identifier>\n    <dc:title>{}</dc:title>\n    <dc:language>{lang}</dc:language>\n    <meta property=\"dcterms:modified\">{}</meta>\n  </metadata>\n  <manifest>\n{manifest}  </manifest>\n  <spine>\n{spine}  </spine>\n</package>\n",
+        uuid::Uuid::new_v4(),
+        escape(title),
+        chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ"),
+    );
+    files.push(("OEBPS/content.opf".into(), opf.into_bytes()));
+
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let deflated = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    // Readers identify the file by this entry: first, and not compressed
+    let stored = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    let write = |zip: &mut zip::ZipWriter<Cursor<Vec<u8>>>, name: &str, bytes: &[u8], options| {
+        zip.start_file(name, options).map_err(|e| tr!("Failed to write archive: {}", e))?;
+        zip.write_all(bytes).map_err(|e| tr!("Failed to write archive: {}", e))
+    };
+    write(&mut zip, "mimetype", b"application/epub+zip", stored)?;
+    for (name, bytes) in &files {
+        write(&mut zip, name, bytes, deflated)?;
+    }
+    zip.finish().map(Cursor::into_inner).map_err(|e| tr!("Failed to write archive: {}", e))
+}
+
+fn xhtml(lang: &str, title: &str, body: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE html>\n<html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\" xml:lang=\"{lang}\" lang=\"{lang}\">\n<head>\n<meta charset=\"UTF-8\"/>\n<title>{}</title>\n<link rel=\"stylesheet\" type=\"text/css\" href=\"style.css\"/>\n</head>\n<body>\n{body}</body>\n</html>\n",
+        escape(title)
+    )
+}
+
+/// Everything in one file, photos as data URIs
+fn single_page(book: &Book, title: &str, year: i32) -> String {
+    let lang = crate::i18n::locale().as_str();
+    let mut body = format!("<h1 class=\"title\">{}</h1>\n", escape(title));
+    for (month, html) in &book.months {
+        body.push_str(&format!("<section class=\"month\">\n<h1>{year}-{month:02}</h1>\n{html}</section>\n"));
+    }
+    for (name, bytes) in &book.images {
+        let data = format!("data:image/jpeg;base64,{}", base64::engine::general_purpose::STANDARD.encode(bytes));
+        body = body.replace(&format!("src=\"images/{name}\""), &format!("src=\"{data}\""));
+    }
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"{lang}\">\n<head>\n<meta charset=\"UTF-8\"/>\n<title>{}</title>\n<style>\n{STYLE}{PRINT_STYLE}</style>\n</head>\n<body>\n{body}<p class=\"meta\">{}</p>\n</body>\n</html>\n",
+        escape(title),
+        Local::now().format("%Y-%m-%d"),
+    )
+}
+
+/// Block-level markdown to XHTML; `image` maps an image reference to its
+/// `src`, or None to leave it out
+fn markdown(text: &str, image: &mut dyn FnMut(&str) -> Option<String>) -> String {
+    let mut out = String::new();
+    let mut paragraph: Vec<String> = Vec::new();
+    let mut list: Option<&str> = None;
+    let mut fence: Option<Vec<&str>> = None;
+
+    let flush = |out: &mut String, paragraph: &mut Vec<String>, list: &mut Option<&str>| {
+        if !paragraph.is_empty() {
+            out.push_str(&format!("<p>{}</p>\n", paragraph.join("<br/>")));
+            paragraph.clear();
+        }
+        if let Some(tag) = list.take() {
+            out.push_str(&format!("</{tag}>\n"));
+        }
+    };
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(code) = fence.as_mut() {
+            if trimmed.starts_with("```") {
+                out.push_str(&format!("<pre><code>{}</code></pre>\n", escape(&code.join("\n"))));
+                fence = None;
+            } else {
+                code.push(line);
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") {
+            flush(&mut out, &mut paragraph, &mut list);
+            fence = Some(Vec::new());
+            continue;
+        }
+        if trimmed.is_empty() {
+            flush(&mut out, &mut paragraph, &mut list);
+            continue;
+        }
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+        if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+            flush(&mut out, &mut paragraph, &mut list);
+            // Below the entry's own h2
+            let tag = format!("h{}", (level + 2).min(6));
+            out.push_str(&format!("<{tag}>{}</{tag}>\n", inline(trimmed[level..].trim(), image)));
+            continue;
+        }
+        if trimmed.chars().all(|c| c == '-' || c == '*' || c == ' ') && trimmed.chars().filter(|c| *c != ' ').count() >= 3 {
+            flush(&mut out, &mut paragraph, &mut list);
+            out.push_str("<hr/>\n");
+            continue;
+        }
+        if let Some(quote) = trimmed.strip_prefix('>') {
+            flush(&mut out, &mut paragraph, &mut list);
+            out.push_str(&format!("<blockquote><p>{}</p></blockquote>\n", inline(quote.trim(), image)));
+            continue;
+        }
+        let bullet = ["- ", "* ", "+ "].iter().find_map(|b| trimmed.strip_prefix(b));
+        let numbered = trimmed.split_once(". ").filter(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())).map(|(_, rest)| rest);
+        if let Some((tag, item)) = bullet.map(|i| ("ul", i)).or(numbered.map(|i| ("ol", i))) {
+            if !paragraph.is_empty() || list.is_some_and(|t| t != tag) {
+                flush(&mut out, &mut paragraph, &mut list);
+            }
+            if list.is_none() {
+                out.push_str(&format!("<{tag}>\n"));
+                list = Some(tag);
+            }
+            let item = match item.get(..4) {
+                Some("[ ] ") => format!("☐ {}", inline(&item[4..], image)),
+                Some("[x] ") | Some("[X] ") => format!("☑ {}", inline(&item[4..], image)),
+                _ => inline(item, image),
+            };
+            out.push_str(&format!("<li>{item}</li>\n"));
+            continue;
+        }
+        if list.is_some() {
+            flush(&mut out, &mut paragraph, &mut list);
+        }
+        paragraph.push(inline(trimmed, image));
+    }
+    if let Some(code) = fence {
+        out.push_str(&format!("<pre><code>{}</code></pre>\n", escape(&code.join("\n"))));
+    }
+    flush(&mut out, &mut paragraph, &mut list);
+    out
+}
+
+/// Images, links, `**bold**` and `` `code` `` in one line; the rest escaped
+fn inline(text: &str, image: &mut dyn FnMut(&str) -> Option<String>) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    let img = |src: Option<String>, alt: &str| match src {
+        Some(src) => format!("<img src=\"{}\" alt=\"{}\"/>", escape(&src), escape(alt)),
+        None => String::new(),
+    };
+    while let Some(c) = rest.chars().next() {
+        let closed = |open: &str, close: &str| rest.strip_prefix(open).and_then(|after| after.find(close).map(|end| (&after[..end], &after[end + close.len()..])));
+        if let Some((inner, after)) = closed("![[", "]]") {
+            let target = inner.split('|').next().unwrap_or_default().trim();
+            out.push_str(&img(image(target), ""));
+            rest = after;
+        } else if let Some((alt, after)) = rest.strip_prefix('!').and_then(|r| r.strip_prefix('[')).and_then(|r| r.split_once("](")) {
+            let Some((src, after)) = after.split_once(')') else { break };
+            let src = src.split(" \"").next().unwrap_or(src).trim().trim_start_matches('<').trim_end_matches('>');
+            out.push_str(&img(image(src), alt));
+            rest = after;
+        } else if let Some((inner, after)) = closed("[[", "]]") {
+            let shown = inner.split('|').nth(1).unwrap_or_else(|| inner.split('#').next().unwrap_or(inner));
+            out.push_str(&escape(shown.rsplit('/').next().unwrap_or(shown).trim()));
+            rest = after;
+        } else if let Some((label, after)) = rest.strip_prefix('[').and_then(|r| r.split_once("](")).filter(|(l, _)| !l.contains(']')) {
+            let Some((url, after)) = after.split_once(')') else { break };
+            let url = url.split(" \"").next().unwrap_or(url).trim();
+            if url.starts_with("http://") || url.starts_with("https://") || url.starts_with("mailto:") {
+                out.push_str(&format!("<a href=\"{}\">{}</a>", escape(url), escape(label)));
+            } else {
+                out.push_str(&escape(label));
+            }
+            rest = after;
+        } else if let Some((inner, after)) = closed("**", "**").filter(|(i, _)| !i.is_empty()) {
+            out.push_str(&format!("<strong>{}</strong>", escape(inner)));
+            rest = after;
+        } else if let Some((inner, after)) = closed("`", "`") {
+            out.push_str(&format!("<code>{}</code>", escape(inner)));
+            rest = after;
+        } else {
+            out.push_str(&escape(&c.to_string()));
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    out.push_str(&escape(rest));
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn write(dir: &Path, rel: &str, text: &str) {
+        let path = dir.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    #[test]
+    fn test_markdown() {
+        let mut images = |src: &str| (src == "cat.png").then(|| "images/001.jpg".to_string());
+        let html = markdown(
+            "Morning run & **coffee**\nthen `work`\n\n## Notes\n\n- [x] ship\n- see [[people/老王|王]] and [site](https://example.com)\n1. one\n\n> quiet day\n\n![[cat.png|300]] ![gone](dog.png)\n\n```\n<b>\n```",
+            &mut images,
+        );
+        assert_eq!(
+            html,
+            "<p>Morning run &amp; <strong>coffee</strong><br/>then <code>work</code></p>\n<h4>Notes</h4>\n<ul>\n<li>☑ ship</li>\n<li>see 王 and <a href=\"https://example.com\">site</a></li>\n</ul>\n<ol>\n<li>one</li>\n</ol>\n<blockquote><p>quiet day</p></blockquote>\n<p><img src=\"images/001.jpg\" alt=\"\"/> </p>\n<pre><code>&lt;b&gt;</code></pre>\n"
+        );
+    }
+
+    #[test]
+    fn test_export_epub_and_html() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        fs::create_dir_all(v.join("assets/images")).unwrap();
+        image::RgbImage::from_pixel(2400, 1200, image::Rgb([10, 120, 200])).save(v.join("assets/images/sea.png")).unwrap();
+        write(v, "diary/2025/2025-03-02-0900.md", "---\ndate: 2025-03-02\ntitle: 海边\nmood: 😊\nenergy: high\nweather: 晴 18°C\n---\n# 2025-03-02\n\n去了海边。\n\n![[sea.png]]\n");
+        write(v, "diary/2025/2025-01-15-2100.md", "---\ndate: 2025-01-15\n---\nFirst entry ![](missing.jpg)\n");
+        write(v, "diary/2024/2024-12-31-2300.md", "---\ndate: 2024-12-31\n---\nLast year\n");
+        write(v, "diary/templates/daily.md", "---\ndate: 2025-01-01\n---\n{{prompt}}\n");
+
+        let out = tempfile::tempdir().unwrap();
+        let report = export(v, 2025, &out.path().join("book.epub")).unwrap();
+        assert_eq!((report.entries, report.images, report.missing_images), (2, 1, vec!["missing.jpg".to_string()]));
+
+        let mut zip = zip::ZipArchive::new(fs::File::open(out.path().join("book.epub")).unwrap()).unwrap();
+        assert_eq!(zip.by_index(0).unwrap().name(), "mimetype");
+        let mut read = |name: &str| {
+            let mut text = String::new();
+            zip.by_name(name).unwrap().read_to_string(&mut text).unwrap();
+            text
+        };
+        let march = read("OEBPS/month-03.xhtml");
+        let meta = format!("<p class=\"meta\">😊 · {} · 晴 18°C</p>", tr!("Energy: {}", "high"));
+        assert!(march.contains(&format!("<h2>2025-03-02 · 海边</h2>\n{meta}\n<p>去了海边。</p>")), "{march}");
+        assert!(march.contains("<img src=\"images/001.jpg\""));
+        assert!(read("OEBPS/content.opf").contains("href=\"month-01.xhtml\""));
+        let photo = image::load_from_memory(&zip_bytes(&mut zip, "OEBPS/images/001.jpg")).unwrap();
+        assert_eq!((photo.width(), photo.height()), (1200, 600));
+
+        let page = out.path().join("book.html");
+        export(v, 2025, &page).unwrap();
+        let html = fs::read_to_string(&page).unwrap();
+        assert!(html.contains("src=\"data:image/jpeg;base64,") && html.find("2025-01-15") < html.find("2025-03-02"));
+        assert!(!html.contains("Last year") && !html.contains("{{prompt}}"));
+
+        assert!(export(v, 2025, &out.path().join("book.pdf")).is_err());
+        assert!(export(v, 2023, &page).is_err());
+    }
+
+    fn zip_bytes(zip: &mut zip::ZipArchive<fs::File>, name: &str) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        zip.by_name(name).unwrap().read_to_end(&mut bytes).unwrap();
+        bytes
+    }
+}
@@ -0,0 +1,121 @@
+//! Crash-safe replacement of index and state files. The new contents go to
+//! a write-ahead file beside the target (`.<name>.<n>.wal`), are flushed to
+//! disk and then renamed over the target, so a crash or power loss leaves
+//! the old file or the new one — never a truncated file that fails to parse
+//! on the next launch. A `.wal` left behind by a crash is hidden (vault sync
+//! skips it) and ignored.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::audit;
+
+/// Keeps concurrent writes of one file (say two mail syncs) off each other's journal
+static NEXT: AtomicU64 = AtomicU64::new(0);
+
+/// Drop-in for `fs::write`. A symlinked target is written through, and an
+/// existing file keeps its permissions. Writes into a vault are audited.
+pub fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let path = fs::canonicalize(path.as_ref()).unwrap_or_else(|_| path.as_ref().to_path_buf());
+    let wal = wal_path(&path);
+    let result = commit(&path, &wal, contents.as_ref());
+    match &result {
+        Ok(()) => audit::wrote(&path),
+        Err(_) => {
+            let _ = fs::remove_file(&wal);
+        }
+    }
+    result
+}
+
+fn commit(path: &Path, wal: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut file = fs::File::create(wal)?;
+    file.write_all(contents)?;
+    if let Ok(meta) = fs::metadata(path) {
+        file.set_permissions(meta.permissions())?;
+    }
+    file.sync_all()?;
+    drop(file);
+    fs::rename(wal, path)?;
+    sync_dir(path)
+}
+
+fn wal_path(path: &Path) -> PathBuf {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(".{name}.{}-{n}.wal", std::process::id()))
+}
+
+/// Make the rename itself durable
+#[cfg(unix)]
+fn sync_dir(path: &Path) -> io::Result<()> {
+    match path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        Some(dir) => fs::File::open(dir)?.sync_all(),
+        None => Ok(()),
+    }
+}
+
+/// Directories can't be opened for syncing here; NTFS journals the rename
+#[cfg(not(unix))]
+fn sync_dir(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir).unwrap().map(|e| e.unwrap().file_name().to_string_lossy().to_string()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_write_replaces_whole_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.json");
+        write(&path, "[1, 2, 3]").unwrap();
+        write(&path, "[]").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[]");
+        assert_eq!(entries(dir.path()), vec!["index.json"]);
+
+        // A failed write leaves neither a journal nor a changed target
+        assert!(write(dir.path().join("missing/index.json"), "{}").is_err());
+        assert_eq!(entries(dir.path()), vec!["index.json"]);
+
+        // Concurrent writers each land a complete file
+        let bodies: Vec<String> = (0..8).map(|i| format!("[{}]", i.to_string().repeat(100_000))).collect();
+        std::thread::scope(|s| {
+            for body in &bodies {
+                let path = &path;
+                s.spawn(move || write(path, body).unwrap());
+            }
+        });
+        assert!(bodies.contains(&fs::read_to_string(&path).unwrap()));
+        assert_eq!(entries(dir.path()), vec!["index.json"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_keeps_links_and_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().join("secrets.yaml");
+        fs::write(&real, "a: 1\n").unwrap();
+        fs::set_permissions(&real, fs::Permissions::from_mode(0o600)).unwrap();
+        let link = dir.path().join("link.yaml");
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        write(&link, "a: 2\n").unwrap();
+        assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(fs::read_to_string(&real).unwrap(), "a: 2\n");
+        assert_eq!(fs::metadata(&real).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+}
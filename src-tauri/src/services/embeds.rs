@@ -0,0 +1,252 @@
+//! `![[note#section]]` transclusion.
+//!
+//! - `![[note]]` — the whole note, without its frontmatter
+//! - `![[note#Heading]]` — the heading and everything under it, up to the
+//!   next heading of the same or a higher level
+//! - `![[#Heading]]` — a section of the embedding note itself
+//! - `![[note|alias]]` — the alias is ignored
+//!
+//! `note` is a vault-relative path or one relative to the embedding note
+//! (`.md` optional), else any note in the vault with that file name. Embeds
+//! nest; one that would re-enter a note or section already being expanded
+//! is left as written, as are missing targets. Attachments (`![[cat.png]]`)
+//! and code fences are not touched.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::notes::split_frontmatter;
+use crate::services::automations::vault_file;
+
+/// Beyond this even acyclic chains are left unexpanded
+const MAX_DEPTH: usize = 16;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Resolved {
+    /// The note, frontmatter included, with embeds expanded
+    pub content: String,
+    /// Vault-relative notes pulled in, in order of first use
+    pub sources: Vec<String>,
+    /// Embeds left as written: missing note or section, or a cycle
+    pub unresolved: Vec<String>,
+}
+
+/// Expand the embeds in the note at `path`
+pub fn resolve(vault: &Path, path: &Path) -> Result<Resolved, String> {
+    let raw = fs::read_to_string(path).map_err(|e| tr!("Failed to read: {}", e))?;
+    Ok(resolve_text(vault, path, &raw))
+}
+
+/// Same as `resolve` for text already in hand, e.g. an unsaved buffer
+pub fn resolve_text(vault: &Path, path: &Path, raw: &str) -> Resolved {
+    let mut resolver = Resolver { vault, index: None, stack: vec![(path.to_path_buf(), String::new())], out: Resolved::default() };
+    let content = resolver.expand(raw, path);
+    Resolved { content, ..resolver.out }
+}
+
+struct Resolver<'a> {
+    vault: &'a Path,
+    /// Lowercased file name → note, built on the first bare-name lookup
+    index: Option<HashMap<String, PathBuf>>,
+    /// (note, lowercased section) being expanded, outermost first
+    stack: Vec<(PathBuf, String)>,
+    out: Resolved,
+}
+
+impl Resolver<'_> {
+    fn expand(&mut self, text: &str, from: &Path) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut in_fence = false;
+        for line in text.split_inclusive('\n') {
+            if is_fence(line) {
+                in_fence = !in_fence;
+            }
+            if in_fence || !line.contains("![[") {
+                out.push_str(line);
+            } else {
+                out.push_str(&self.expand_line(line, from));
+            }
+        }
+        out
+    }
+
+    fn expand_line(&mut self, line: &str, from: &Path) -> String {
+        let mut out = String::new();
+        let mut rest = line;
+        while let Some(start) = rest.find("![[") {
+            let Some(len) = rest[start + 3..].find("]]") else { break };
+            let end = start + 3 + len + 2;
+            out.push_str(&rest[..start]);
+            match self.embed(&rest[start + 3..end - 2], from) {
+                Some(text) => out.push_str(text.trim_end_matches('\n')),
+                None => out.push_str(&rest[start..end]),
+            }
+            rest = &rest[end..];
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// Expanded text for `inner` (the part between `![[` and `]]`); None
+    /// leaves the embed as written
+    fn embed(&mut self, inner: &str, from: &Path) -> Option<String> {
+        let target = inner.split('|').next().unwrap_or_default().trim();
+        let (name, section) = match target.split_once('#') {
+            // `note#A#B` names B, as nested headings do in Obsidian
+            Some((name, sections)) => (name.trim(), sections.rsplit('#').next().unwrap_or_default().trim()),
+            None => (target, ""),
+        };
+        let path = if name.is_empty() { Some(from.to_path_buf()) } else { self.find(name, from) };
+        let Some(path) = path else {
+            if Path::new(name).extension().is_none_or(|ext| ext == "md") {
+                self.out.unresolved.push(inner.to_string());
+            }
+            return None;
+        };
+
+        let key = (path.clone(), section.to_lowercase());
+        if self.stack.contains(&key) || self.stack.len() > MAX_DEPTH {
+            self.out.unresolved.push(inner.to_string());
+            return None;
+        }
+        let raw = fs::read_to_string(&path).ok()?;
+        let body = split_frontmatter(&raw).1;
+        let text = if section.is_empty() { Some(body.to_string()) } else { section_text(body, section) };
+        let Some(text) = text else {
+            self.out.unresolved.push(inner.to_string());
+            return None;
+        };
+
+        if let Ok(rel) = path.strip_prefix(self.vault) {
+            let rel = rel.to_string_lossy().replace('\\', "/");
+            if !self.out.sources.contains(&rel) {
+                self.out.sources.push(rel);
+            }
+        }
+        self.stack.push(key);
+        let expanded = self.expand(&text, &path);
+        self.stack.pop();
+        Some(expanded)
+    }
+
+    fn find(&mut self, name: &str, from: &Path) -> Option<PathBuf> {
+        let file = if name.ends_with(".md") { name.to_string() } else { format!("{name}.md") };
+        // vault_file also refuses `..`, so relative lookups stay in the vault
+        let in_vault = vault_file(self.vault, &file).ok()?;
+        let beside = from.parent().map(|dir| dir.join(&file));
+        if let Some(found) = [Some(in_vault), beside].into_iter().flatten().find(|p| p.is_file()) {
+            return Some(found);
+        }
+        if file.contains('/') {
+            return None;
+        }
+        let vault = self.vault;
+        let index = self.index.get_or_insert_with(|| note_index(vault));
+        index.get(&file.to_lowercase()).cloned()
+    }
+}
+
+/// Notes by lowercased file name; the first in path order wins
+fn note_index(vault: &Path) -> HashMap<String, PathBuf> {
+    let mut index = HashMap::new();
+    for entry in WalkDir::new(vault)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|x| x == "md"))
+    {
+        index.entry(entry.file_name().to_string_lossy().to_lowercase()).or_insert_with(|| entry.path().to_path_buf());
+    }
+    index
+}
+
+/// The heading line matching `heading` (case-insensitive) and what follows
+/// it up to the next heading of the same or a higher level
+fn section_text(body: &str, heading: &str) -> Option<String> {
+    let mut open: Option<usize> = None;
+    let mut out = String::new();
+    let mut in_fence = false;
+    for line in body.split_inclusive('\n') {
+        let fence = is_fence(line);
+        if fence {
+            in_fence = !in_fence;
+        }
+        let parsed = if in_fence || fence { None } else { heading_of(line) };
+        match (open, parsed) {
+            (Some(level), Some((l, _))) if l <= level => break,
+            (Some(_), _) => out.push_str(line),
+            (None, Some((l, text))) if text.to_lowercase() == heading.to_lowercase() => {
+                open = Some(l);
+                out.push_str(line);
+            }
+            (None, _) => {}
+        }
+    }
+    open.map(|_| out)
+}
+
+/// `## Title` → (2, "Title")
+fn heading_of(line: &str) -> Option<(usize, &str)> {
+    let line = line.trim_end();
+    let level = line.len() - line.trim_start_matches('#').len();
+    let text = &line[level..];
+    if (1..=6).contains(&level) && text.starts_with(' ') {
+        Some((level, text.trim().trim_end_matches('#').trim_end()))
+    } else {
+        None
+    }
+}
+
+fn is_fence(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("```") || line.starts_with("~~~")
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(vault: &Path, rel: &str, text: &str) -> PathBuf {
+        let path = vault.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, text).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_sections_and_names() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        write(v, "planning/goals/健康.md", "---\ntitle: 健康\n---\n\n# 健康\n\n## 目标\n\n跑完半马\n\n### 细节\n\n每周三次\n\n## 复盘\n\n还行\n");
+        let root = write(v, "diary/2025/a.md", "---\ndate: 2025-01-01\n---\n今天：![[健康#目标]]\n\n![[planning/goals/健康|别名]]\n\n```\n![[健康]]\n```\n![[cat.png]] ![[不存在]] ![[健康#没有]]\n");
+
+        let out = resolve(v, &root).unwrap();
+        assert!(out.content.starts_with("---\ndate: 2025-01-01\n---\n今天：## 目标\n\n跑完半马\n\n### 细节\n\n每周三次\n\n# 健康"));
+        assert!(!out.content.contains("title: 健康"));
+        assert!(out.content.contains("```\n![[健康]]\n```\n![[cat.png]] ![[不存在]] ![[健康#没有]]"));
+        assert_eq!(out.sources, vec!["planning/goals/健康.md"]);
+        assert_eq!(out.unresolved, vec!["不存在", "健康#没有"]);
+    }
+
+    #[test]
+    fn test_cycles() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        let a = write(v, "a.md", "A ![[b]]\n## S\nloop ![[#S]]\n");
+        write(v, "b.md", "B ![[a]]\n");
+
+        // Each embed expands once; re-entering it is left as written
+        let out = resolve(v, &a).unwrap();
+        assert_eq!(out.content, "A B ![[a]]\n## S\nloop ## S\nloop ![[#S]]\n");
+        assert_eq!(out.unresolved, vec!["a", "#S"]);
+    }
+}
@@ -0,0 +1,121 @@
+//! Metadata of EPUB files: the package document's Dublin Core title,
+//! creators, ISBN and language, and how many documents the spine has.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use super::carddav::elements;
+use super::mail_html::decode_entities;
+
+const CONTAINER: &str = "META-INF/container.xml";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct EpubInfo {
+    pub title: String,
+    pub authors: Vec<String>,
+    pub isbn: Option<String>,
+    pub language: Option<String>,
+    /// Documents in reading order
+    pub chapters: usize,
+}
+
+/// Read the package document named by META-INF/container.xml
+pub fn info(path: &Path) -> Result<EpubInfo, String> {
+    let file = fs::File::open(path).map_err(|e| tr!("Failed to read: {}", e))?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| tr!("Invalid EPUB: {}", e))?;
+    let mut read = |name: &str| -> Result<String, String> {
+        let mut text = String::new();
+        zip.by_name(name)
+            .map_err(|e| tr!("Invalid EPUB: {}", e))?
+            .read_to_string(&mut text)
+            .map_err(|e| tr!("Invalid EPUB: {}", e))?;
+        Ok(text)
+    };
+    let container = read(CONTAINER)?;
+    let opf_path = attribute(&container, "rootfile", "full-path").ok_or_else(|| tr!("Invalid EPUB: {}", CONTAINER))?;
+    Ok(parse_package(&read(&opf_path)?))
+}
+
+fn parse_package(opf: &str) -> EpubInfo {
+    let text = |name: &str| -> Vec<String> {
+        elements(opf, name).into_iter().map(|t| decode_entities(t.trim())).filter(|t| !t.is_empty()).collect()
+    };
+    EpubInfo {
+        title: text("title").into_iter().next().unwrap_or_default(),
+        authors: text("creator"),
+        isbn: text("identifier").iter().find_map(|id| isbn(id)),
+        language: text("language").into_iter().next(),
+        chapters: elements(opf, "itemref").len(),
+    }
+}
+
+/// `urn:isbn:978-7-…`, or a bare 10/13-digit identifier
+fn isbn(identifier: &str) -> Option<String> {
+    let lower = identifier.to_lowercase();
+    let digits: String = lower.chars().filter(|c| c.is_ascii_digit() || *c == 'x').collect::<String>().to_uppercase();
+    let bare = identifier.chars().all(|c| c.is_ascii_digit() || c == '-' || c == 'X' || c == 'x');
+    ((lower.contains("isbn") || bare) && (digits.len() == 10 || digits.len() == 13)).then_some(digits)
+}
+
+/// Value of `attr` on the first `<tag …>`, whatever its prefix
+fn attribute(xml: &str, tag: &str, attr: &str) -> Option<String> {
+    let mut rest = xml;
+    while let Some(open) = rest.find('<') {
+        rest = &rest[open + 1..];
+        let end = rest.find('>')?;
+        let element = &rest[..end];
+        let name = element.split(|c: char| c.is_whitespace() || c == '/').next().unwrap_or_default();
+        if name.rsplit(':').next() == Some(tag) {
+            let after = element.split(&format!("{attr}=")).nth(1)?;
+            let quote = after.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            return after[1..].split(quote).next().map(decode_entities);
+        }
+        rest = &rest[end + 1..];
+    }
+    None
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_epub_info() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("book.epub");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("mimetype", options).unwrap();
+        zip.write_all(b"application/epub+zip").unwrap();
+        zip.start_file(CONTAINER, options).unwrap();
+        zip.write_all(br#"<container><rootfiles><rootfile full-path="OPS/package.opf" media-type="application/oebps-package+xml"/></rootfiles></container>"#).unwrap();
+        zip.start_file("OPS/package.opf", options).unwrap();
+        zip.write_all(
+            r#"<package xmlns="http://www.idpf.org/2007/opf"><metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <dc:identifier id="uid">urn:uuid:1b2c</dc:identifier>
+  <dc:identifier opf:scheme="ISBN">978-7-5442-9116-6</dc:identifier>
+  <dc:title>Thinking, Fast &amp; Slow</dc:title>
+  <dc:creator opf:role="aut">Daniel Kahneman</dc:creator>
+  <dc:language>en</dc:language>
+</metadata><spine><itemref idref="c1"/><itemref idref="c2"/></spine></package>"#
+                .as_bytes(),
+        )
+        .unwrap();
+        zip.finish().unwrap();
+
+        let info = info(&path).unwrap();
+        assert_eq!(info.title, "Thinking, Fast & Slow");
+        assert_eq!(info.authors, vec!["Daniel Kahneman"]);
+        assert_eq!(info.isbn.as_deref(), Some("9787544291166"));
+        assert_eq!((info.language.as_deref(), info.chapters), (Some("en"), 2));
+        assert_eq!(isbn("urn:uuid:1b2c"), None);
+        assert!(super::info(dir.path().join("missing.epub").as_path()).is_err());
+    }
+}
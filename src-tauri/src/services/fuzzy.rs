@@ -0,0 +1,90 @@
+//! Subsequence matching for the quick switcher.
+//!
+//! Every query character has to appear in order. Matches score higher when
+//! they run together, start words or the text itself, and when the text is
+//! short, so `qrv` finds "Quarterly Review" ahead of a long path that merely
+//! contains those letters.
+
+/// Score of `query` against `text` with the matched char positions, or None
+/// when it doesn't match. Case-insensitive; spaces in the query are ignored.
+pub fn score(query: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+
+    // Greedy from each possible start; keep the best
+    let mut best: Option<(i64, Vec<usize>)> = None;
+    for start in (0..lower.len()).filter(|&i| lower[i] == query[0]) {
+        let mut positions = vec![start];
+        let mut at = start + 1;
+        for q in &query[1..] {
+            match lower[at..].iter().position(|c| c == q) {
+                Some(offset) => {
+                    positions.push(at + offset);
+                    at += offset + 1;
+                }
+                None => break,
+            }
+        }
+        if positions.len() < query.len() {
+            // Later starts only have less text left
+            break;
+        }
+        let score = rate(&chars, &positions);
+        if best.as_ref().is_none_or(|(b, _)| score > *b) {
+            best = Some((score, positions));
+        }
+    }
+    best
+}
+
+fn rate(chars: &[char], positions: &[usize]) -> i64 {
+    let mut score = 0i64;
+    for (n, &p) in positions.iter().enumerate() {
+        score += 10;
+        if p == 0 {
+            score += 15;
+        } else if is_word_start(chars, p) {
+            score += 10;
+        }
+        if n > 0 {
+            let gap = (p - positions[n - 1] - 1) as i64;
+            score += if gap == 0 { 8 } else { -gap.min(10) };
+        }
+    }
+    score - chars.len() as i64 / 4
+}
+
+/// After a separator, or a capital following a lowercase letter (camelCase).
+/// CJK characters count as word starts, since they aren't spaced.
+fn is_word_start(chars: &[char], i: usize) -> bool {
+    let (prev, c) = (chars[i - 1], chars[i]);
+    matches!(prev, ' ' | '-' | '_' | '/' | '.' | '#') || (prev.is_lowercase() && c.is_uppercase()) || (!c.is_ascii() && c.is_alphabetic())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score() {
+        assert_eq!(score("qrv", "Quarterly Review").unwrap().1, [0, 3, 12]);
+        assert!(score("xyz", "Quarterly Review").is_none());
+        assert!(score("季复", "季度复盘").is_some());
+
+        let rank = |q: &str, texts: &[&'static str]| {
+            let mut scored: Vec<(i64, &'static str)> = texts.iter().filter_map(|t| Some((score(q, t)?.0, *t))).collect();
+            scored.sort_by_key(|s| std::cmp::Reverse(s.0));
+            scored.into_iter().map(|(_, t)| t).collect::<Vec<_>>()
+        };
+        assert_eq!(rank("rev", &["diary/2025/archive/everything.md", "Quarterly Review", "review"]), ["review", "Quarterly Review", "diary/2025/archive/everything.md"]);
+        assert_eq!(rank("mail", &["Email settings", "Mail"]), ["Mail", "Email settings"]);
+    }
+}
@@ -0,0 +1,479 @@
+//! Local git repositories: discovery for the Git scanner, the dev status
+//! shown for projects with a `repo`, the TODO/FIXME/HACK comment scan, and
+//! the end-of-day commit summary written into daily notes when
+//! `git.commit_summary` is on in connectors.yaml.
+
+use chrono::{Days, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use walkdir::WalkDir;
+
+use super::{connectors, durable, tasks};
+
+/// Day of the last summary, so restarts neither repeat nor skip one. Kept
+/// per machine like the roots it was made from.
+pub const STATE_FILE: &str = ".lifeos/commit-summary.json";
+/// Daily-note section the summary owns; rewritten on every run
+const SECTION_HEADING: &str = "## 提交记录";
+const DEFAULT_AT: &str = "22:00";
+const DEFAULT_MAX_DEPTH: usize = 5;
+/// Days missed while the app was closed are caught up this far back
+const MAX_CATCH_UP_DAYS: u64 = 7;
+/// Commits listed in a project's dev status
+const RECENT_COMMITS: usize = 10;
+pub const DEFAULT_TODO_MARKERS: &[&str] = &["TODO", "FIXME", "HACK"];
+/// Large repos can hold thousands; the first ones are enough to act on
+const MAX_TODOS: usize = 200;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub commit_summary: bool,
+    /// Local time the day counts as over
+    pub at: NaiveTime,
+    /// Folders searched for repositories, like the Git scanner's root
+    pub roots: Vec<PathBuf>,
+    pub max_depth: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Commit {
+    pub repo: String,
+    /// Abbreviated hash
+    pub hash: String,
+    /// HH:MM, author time
+    pub time: String,
+    pub subject: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CommitSummary {
+    /// YYYY-MM-DD
+    pub date: String,
+    /// Repositories searched
+    pub repos: usize,
+    /// Oldest first, grouped by repository
+    pub commits: Vec<Commit>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecentCommit {
+    pub hash: String,
+    /// YYYY-MM-DD HH:MM, author time
+    pub date: String,
+    pub author: String,
+    pub subject: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CodeTodo {
+    /// Repository the comment is in
+    pub repo: String,
+    /// Repo-relative
+    pub path: String,
+    /// 1-based
+    pub line: usize,
+    /// The marker found, e.g. "TODO"
+    pub kind: String,
+    /// Comment text after the marker
+    pub text: String,
+    /// Who last changed the line, from `git blame`; only filled by
+    /// [`scan_todos`]. "Not Committed Yet" for uncommitted lines.
+    #[serde(default)]
+    pub author: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DevStatus {
+    pub repo: String,
+    /// "HEAD" when detached
+    pub branch: String,
+    /// `git status --porcelain` lines: uncommitted and untracked changes
+    pub changes: Vec<String>,
+    /// Newest first
+    pub recent_commits: Vec<RecentCommit>,
+    /// TODO/FIXME/HACK comments
+    pub todos: Vec<CodeTodo>,
+    /// First paragraph of the README
+    pub readme: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct State {
+    last_date: Option<NaiveDate>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// `git:` in connectors.yaml
+pub fn settings(vault: &Path) -> Settings {
+    let section = connectors::section(vault, "git").unwrap_or_default();
+    let at = section.get("at").and_then(|v| v.as_str()).unwrap_or(DEFAULT_AT);
+    Settings {
+        commit_summary: section.get("commit_summary").and_then(|v| v.as_bool()).unwrap_or(false),
+        at: NaiveTime::parse_from_str(at.trim(), "%H:%M").unwrap_or_else(|_| NaiveTime::parse_from_str(DEFAULT_AT, "%H:%M").expect("default")),
+        roots: section
+            .get("roots")
+            .and_then(|v| v.as_sequence())
+            .map(|list| list.iter().filter_map(|r| r.as_str()).map(str::trim).filter(|r| !r.is_empty()).map(PathBuf::from).collect())
+            .unwrap_or_default(),
+        max_depth: section.get("max_depth").and_then(|v| v.as_u64()).map_or(DEFAULT_MAX_DEPTH, |d| d as usize),
+    }
+}
+
+/// Working trees under `root` (those holding a `.git` folder), by path
+pub fn find_repos(root: &Path, max_depth: usize) -> Vec<PathBuf> {
+    let mut repos: Vec<PathBuf> = WalkDir::new(root)
+        .max_depth(max_depth)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_dir() && e.file_name() == ".git")
+        .filter_map(|e| e.path().parent().map(Path::to_path_buf))
+        .collect();
+    repos.sort();
+    repos
+}
+
+/// Your commits (by the repo's user.email) authored on `date`, on any branch
+pub fn day_commits(repo: &Path, date: NaiveDate) -> Vec<Commit> {
+    let Some(email) = git(repo, &["config", "user.email"]).map(|e| e.trim().to_string()).filter(|e| !e.is_empty()) else {
+        return Vec::new();
+    };
+    let name = repo.file_name().map_or_else(|| repo.to_string_lossy().to_string(), |n| n.to_string_lossy().to_string());
+    let since = format!("--since={date} 00:00:00");
+    let until = format!("--until={date} 23:59:59");
+    let author = format!("--author={email}");
+    let day = date.format("%Y-%m-%d").to_string();
+    git(repo, &["log", "--all", "--reverse", &since, &until, &author, "--date=format-local:%Y-%m-%d %H:%M", "--format=%h%x09%ad%x09%s"])
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let (hash, at, subject) = (fields.next()?, fields.next()?, fields.next()?);
+            // --since/--until go by commit time; keep what was written that day
+            let (on, time) = at.split_once(' ')?;
+            (on == day).then(|| Commit { repo: name.clone(), hash: hash.to_string(), time: time.to_string(), subject: subject.trim().to_string() })
+        })
+        .collect()
+}
+
+/// Branch, working-tree changes, latest commits, open TODO comments and the
+/// README's opening paragraph of the repository at `repo`
+pub fn dev_status(repo: &Path) -> Result<DevStatus, String> {
+    if git(repo, &["rev-parse", "--is-inside-work-tree"]).is_none_or(|out| out.trim() != "true") {
+        return Err(tr!("Not a git repository: {}", repo.display()));
+    }
+    let branch = git(repo, &["branch", "--show-current"]).map(|b| b.trim().to_string()).filter(|b| !b.is_empty());
+    let recent = git(repo, &["log", &format!("-{RECENT_COMMITS}"), "--date=format-local:%Y-%m-%d %H:%M", "--format=%h%x09%ad%x09%an%x09%s"]);
+    Ok(DevStatus {
+        repo: repo.to_string_lossy().to_string(),
+        branch: branch.unwrap_or_else(|| "HEAD".to_string()),
+        changes: git(repo, &["status", "--porcelain"]).unwrap_or_default().lines().filter(|l| !l.trim().is_empty()).map(str::to_string).collect(),
+        recent_commits: recent
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(4, '\t');
+                Some(RecentCommit {
+                    hash: fields.next()?.to_string(),
+                    date: fields.next()?.to_string(),
+                    author: fields.next()?.to_string(),
+                    subject: fields.next()?.trim().to_string(),
+                })
+            })
+            .collect(),
+        todos: code_todos(repo, DEFAULT_TODO_MARKERS),
+        readme: readme_intro(repo),
+    })
+}
+
+/// `markers` comments in the repo's text files, tracked or not but never
+/// .gitignore'd, in path and line order. A marker counts as a whole word.
+pub fn code_todos(repo: &Path, markers: &[&str]) -> Vec<CodeTodo> {
+    if markers.is_empty() {
+        return Vec::new();
+    }
+    let repo_name = repo.to_string_lossy().to_string();
+    let mut args = vec!["grep", "-n", "-I", "--no-color", "--untracked", "-F"];
+    for marker in markers {
+        args.extend(["-e", *marker]);
+    }
+    git(repo, &args)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let (path, rest) = line.split_once(':')?;
+            let (number, text) = rest.split_once(':')?;
+            let (kind, after) = find_marker(text, markers)?;
+            let text = after.trim_start_matches(|c: char| c == ':' || c == '(' || c.is_whitespace());
+            // `TODO(name): …` keeps the text after the owner
+            let text = text.split_once("):").map_or(text, |(_, t)| t).trim();
+            let text = text.trim_end_matches("*/").trim_end_matches("-->").trim();
+            Some(CodeTodo {
+                repo: repo_name.clone(),
+                path: path.to_string(),
+                line: number.parse().ok()?,
+                kind: kind.to_string(),
+                text: text.to_string(),
+                author: None,
+            })
+        })
+        .take(MAX_TODOS)
+        .collect()
+}
+
+/// [`code_todos`] of every repository at or under `root`, each with the
+/// author of its line
+pub fn scan_todos(root: &Path, markers: &[&str], max_depth: usize) -> Vec<CodeTodo> {
+    let repos = if root.join(".git").exists() { vec![root.to_path_buf()] } else { find_repos(root, max_depth) };
+    repos
+        .iter()
+        .flat_map(|repo| {
+            let mut todos = code_todos(repo, markers);
+            blame_authors(repo, &mut todos);
+            todos
+        })
+        .collect()
+}
+
+/// Collect `date`'s commits across the configured roots and write them into
+/// that day's daily note, replacing an earlier summary. A day without
+/// commits leaves the note alone (and drops a stale summary).
+pub fn summarize(vault: &Path, date: NaiveDate) -> Result<CommitSummary, String> {
+    let settings = settings(vault);
+    let mut repos: Vec<PathBuf> = settings.roots.iter().flat_map(|root| find_repos(root, settings.max_depth)).collect();
+    repos.sort();
+    repos.dedup();
+    let summary = CommitSummary {
+        date: date.format("%Y-%m-%d").to_string(),
+        repos: repos.len(),
+        commits: repos.iter().flat_map(|r| day_commits(r, date)).collect(),
+    };
+    let body = (!summary.commits.is_empty()).then(|| render(&summary.commits));
+    tasks::set_day_section(&vault.to_string_lossy(), &summary.date, SECTION_HEADING, body.as_deref())?;
+    Ok(summary)
+}
+
+/// Summaries that are due at `now`: today once `at` has passed, plus days
+/// missed since the last run. Does nothing while `commit_summary` is off.
+pub fn run_due(vault: &Path, now: NaiveDateTime) -> Result<Vec<CommitSummary>, String> {
+    let settings = settings(vault);
+    if !settings.commit_summary {
+        return Ok(Vec::new());
+    }
+    let mut state = load_state(vault);
+    let mut done = Vec::new();
+    for date in due_dates(settings.at, state.last_date, now) {
+        done.push(summarize(vault, date)?);
+        state.last_date = Some(date);
+        save_state(vault, &state)?;
+    }
+    Ok(done)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// `git -C repo <args>` stdout, or None when git fails
+fn git(repo: &Path, args: &[&str]) -> Option<String> {
+    Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+}
+
+/// First marker standing alone as a word, and the text after it
+fn find_marker<'a>(text: &'a str, markers: &[&'a str]) -> Option<(&'a str, &'a str)> {
+    let word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    markers
+        .iter()
+        .filter_map(|m| {
+            text.match_indices(m)
+                .find(|(i, _)| !word(text[..*i].chars().next_back()) && !word(text[i + m.len()..].chars().next()))
+                .map(|(i, _)| (i, *m))
+        })
+        .min_by_key(|(i, _)| *i)
+        .map(|(i, m)| (m, &text[i + m.len()..]))
+}
+
+/// Fill in `author` with one `git blame` per file covering just the TODO lines
+fn blame_authors(repo: &Path, todos: &mut [CodeTodo]) {
+    let mut by_path: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for todo in todos.iter() {
+        by_path.entry(todo.path.clone()).or_default().push(todo.line);
+    }
+    for (path, lines) in by_path {
+        let ranges: Vec<String> = lines.iter().map(|n| format!("{n},{n}")).collect();
+        let mut args = vec!["blame", "--line-porcelain"];
+        for range in &ranges {
+            args.extend(["-L", range.as_str()]);
+        }
+        args.extend(["--", path.as_str()]);
+        // Untracked files have no history to blame
+        let Some(out) = git(repo, &args) else { continue };
+        let authors = parse_blame(&out);
+        for todo in todos.iter_mut().filter(|t| t.path == path) {
+            todo.author = authors.get(&todo.line).cloned();
+        }
+    }
+}
+
+/// Final line number → author from `git blame --line-porcelain` output
+fn parse_blame(out: &str) -> HashMap<usize, String> {
+    let mut authors = HashMap::new();
+    let mut line = None;
+    for row in out.lines() {
+        if let Some(author) = row.strip_prefix("author ") {
+            if let Some(n) = line.take() {
+                authors.insert(n, author.to_string());
+            }
+        } else if !row.starts_with('\t') {
+            // Header: <hash> <original line> <final line> [<group size>]
+            let mut fields = row.split(' ');
+            if fields.next().is_some_and(|h| h.len() >= 40 && h.chars().all(|c| c.is_ascii_hexdigit())) {
+                line = fields.nth(1).and_then(|n| n.parse().ok());
+            }
+        }
+    }
+    authors
+}
+
+/// First paragraph of README.md (or README), skipping headings and badges
+fn readme_intro(repo: &Path) -> Option<String> {
+    let raw = ["README.md", "readme.md", "README"].iter().find_map(|name| fs::read_to_string(repo.join(name)).ok())?;
+    let paragraph: Vec<&str> = raw
+        .lines()
+        .map(str::trim)
+        .skip_while(|l| l.is_empty() || l.starts_with('#') || l.starts_with("[![") || l.starts_with("![") || l.starts_with('<'))
+        .take_while(|l| !l.is_empty())
+        .collect();
+    (!paragraph.is_empty()).then(|| paragraph.join(" "))
+}
+
+fn due_dates(at: NaiveTime, last: Option<NaiveDate>, now: NaiveDateTime) -> Vec<NaiveDate> {
+    let today = now.date();
+    let Some(latest) = (if now.time() >= at { Some(today) } else { today.checked_sub_days(Days::new(1)) }) else { return Vec::new() };
+    let earliest = latest.checked_sub_days(Days::new(MAX_CATCH_UP_DAYS - 1)).unwrap_or(latest);
+    let mut date = last.and_then(|d| d.succ_opt()).unwrap_or(latest).max(earliest);
+    let mut dates = Vec::new();
+    while date <= latest {
+        dates.push(date);
+        let Some(next) = date.succ_opt() else { break };
+        date = next;
+    }
+    dates
+}
+
+/// One `### repo` block per repository, commits oldest first
+fn render(commits: &[Commit]) -> String {
+    let mut out = String::new();
+    let mut repo = None;
+    for c in commits {
+        if repo != Some(&c.repo) {
+            if repo.is_some() {
+                out.push('\n');
+            }
+            out.push_str(&format!("### {}\n\n", c.repo));
+            repo = Some(&c.repo);
+        }
+        out.push_str(&format!("- {} `{}` {}\n", c.time, c.hash, c.subject));
+    }
+    out
+}
+
+fn load_state(vault: &Path) -> State {
+    fs::read_to_string(vault.join(STATE_FILE)).ok().and_then(|raw| serde_json::from_str(&raw).ok()).unwrap_or_default()
+}
+
+fn save_state(vault: &Path, state: &State) -> Result<(), String> {
+    let path = vault.join(STATE_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(state).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(path, json).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_due_dates() {
+        let ten_pm = NaiveTime::from_hms_opt(22, 0, 0).unwrap();
+        assert!(due_dates(ten_pm, Some(date("2026-03-09")), at("2026-03-10 21:59")).is_empty());
+        assert_eq!(due_dates(ten_pm, Some(date("2026-03-09")), at("2026-03-10 22:00")), vec![date("2026-03-10")]);
+        assert!(due_dates(ten_pm, Some(date("2026-03-10")), at("2026-03-10 23:00")).is_empty());
+        // First run: just the latest finished day; a long gap: the last week
+        assert_eq!(due_dates(ten_pm, None, at("2026-03-10 08:00")), vec![date("2026-03-09")]);
+        let caught_up = due_dates(ten_pm, Some(date("2026-02-01")), at("2026-03-10 23:00"));
+        assert_eq!((caught_up.len(), caught_up[0]), (7, date("2026-03-04")));
+    }
+
+    #[test]
+    fn test_find_marker() {
+        let markers = ["TODO", "FIXME"];
+        assert_eq!(find_marker("    // TODO: handle 410", &markers), Some(("TODO", ": handle 410")));
+        assert_eq!(find_marker("# FIXME(ann) and TODO later", &markers), Some(("FIXME", "(ann) and TODO later")));
+        assert_eq!(find_marker("let todos = TODOS;", &markers), None);
+        assert_eq!(find_marker("// HACK until 2.0", DEFAULT_TODO_MARKERS), Some(("HACK", " until 2.0")));
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("README.md"), "# LifeOS\n\n[![ci](x)](y)\n\nA personal\noperating system.\n\nMore.\n").unwrap();
+        assert_eq!(readme_intro(dir.path()).as_deref(), Some("A personal operating system."));
+    }
+
+    #[test]
+    fn test_parse_blame() {
+        let hash = "a".repeat(40);
+        let zero = "0".repeat(40);
+        let out = format!(
+            "{hash} 3 7 1\nauthor Ann\nauthor-mail <ann@x.org>\nsummary Fix\nfilename src/a.rs\n\t// TODO: x\n\
+             {zero} 12 19 1\nauthor Not Committed Yet\nfilename src/a.rs\n\t// author TODO\n"
+        );
+        let authors = parse_blame(&out);
+        assert_eq!(authors.get(&7).map(String::as_str), Some("Ann"));
+        assert_eq!(authors.get(&19).map(String::as_str), Some("Not Committed Yet"));
+        assert_eq!(authors.len(), 2);
+    }
+
+    #[test]
+    fn test_find_repos_and_render() {
+        let root = tempfile::tempdir().unwrap();
+        for rel in ["a/.git", "b/nested/.git", "c"] {
+            fs::create_dir_all(root.path().join(rel)).unwrap();
+        }
+        let repos = find_repos(root.path(), 5);
+        assert_eq!(repos, vec![root.path().join("a"), root.path().join("b/nested")]);
+        assert!(find_repos(root.path(), 2).len() == 1);
+
+        let commit = |repo: &str, time: &str, subject: &str| Commit { repo: repo.into(), hash: "abc1234".into(), time: time.into(), subject: subject.into() };
+        let text = render(&[commit("lifeos", "09:12", "Fix parser"), commit("lifeos", "10:00", "Add tests"), commit("blog", "21:30", "Post")]);
+        assert_eq!(text, "### lifeos\n\n- 09:12 `abc1234` Fix parser\n- 10:00 `abc1234` Add tests\n\n### blog\n\n- 21:30 `abc1234` Post\n");
+    }
+}
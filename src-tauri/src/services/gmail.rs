@@ -0,0 +1,412 @@
+//! Gmail through its REST API, for accounts that would otherwise need an
+//! app password for IMAP.
+//!
+//! A Gmail account is an ordinary Mail account file with `protocol: gmail`,
+//! signed in through the shared Google client. Messages land in the same
+//! Mailbox/<account> cache as IMAP mail (raw .eml plus index.json), so the
+//! Mail view, search and reminders read them unchanged. Labels stand in for
+//! folders: system labels by id (INBOX, SENT, ...), the user's own by name.
+//! Once a folder has been pulled, later syncs only fetch the mailbox
+//! history since the last `historyId`.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::durable;
+use super::google::{self, ApiError};
+use super::http;
+use super::mail::{self, load_existing_emails, parse_pop3_email_with_parser, save_index_json, EmailMessage};
+
+pub const SCOPE: &str = "https://www.googleapis.com/auth/gmail.modify";
+/// `imapHost` of Gmail account files: accounts without a host are not listed
+pub const HOST: &str = "gmail.googleapis.com";
+const API: &str = "https://gmail.googleapis.com/gmail/v1/users/me";
+/// Next to index.json in Mailbox/<account>
+const STATE_FILE: &str = "gmail_state.json";
+/// Cache id of a Gmail message: the prefix and Gmail's message id
+const ID_PREFIX: &str = "gmail_";
+/// System labels offered as folders; the rest (UNREAD, CATEGORY_*) are not
+const SYSTEM_FOLDERS: &[&str] = &["INBOX", "STARRED", "IMPORTANT", "SENT", "DRAFT", "SPAM", "TRASH"];
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct GmailState {
+    /// Where the next history request starts; None before the first pull
+    history_id: Option<String>,
+    /// Folder → label id, for every folder pulled so far
+    folders: BTreeMap<String, String>,
+    /// Gmail message id → label ids, for every cached message
+    messages: BTreeMap<String, Vec<String>>,
+}
+
+/// A message as the API returns it with `format=raw` or `minimal`
+struct Remote {
+    id: String,
+    labels: Vec<String>,
+    raw: Option<Vec<u8>>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Address of the signed-in account
+pub fn profile_email(vault_path: &str) -> Result<String, String> {
+    let profile = google::call(Path::new(vault_path), "GET", &format!("{API}/profile"), &[], None).map_err(|e| e.message)?;
+    profile["emailAddress"].as_str().map(str::to_string).ok_or_else(|| tr!("Failed to parse: {}", "emailAddress"))
+}
+
+/// Bring Mailbox/<account_dir> up to date and return `max_emails` cached
+/// messages of `folder`, newest first, after skipping `skip`. A folder seen
+/// for the first time, or paged past what is cached, is listed directly;
+/// everything else comes from the history.
+pub fn sync(vault_path: &str, account_dir: &str, folder: &str, max_emails: u32, skip: u32) -> Result<Vec<EmailMessage>, String> {
+    let vault = Path::new(vault_path);
+    let mut state = load_state(vault_path, account_dir);
+    let mut cache = Cache::open(vault_path, account_dir)?;
+
+    let label = match state.folders.get(folder) {
+        Some(label) => label.clone(),
+        None => {
+            let label = label_id(vault, folder)?.ok_or_else(|| tr!("Failed to select folder: {}", folder))?;
+            state.folders.insert(folder.to_string(), label.clone());
+            label
+        }
+    };
+    let known = state.messages.values().filter(|labels| labels.contains(&label)).count() as u32;
+
+    let mut relist: Vec<String> = Vec::new();
+    match state.history_id.clone() {
+        None => {
+            state.history_id = Some(history_id(vault)?);
+            relist.push(label.clone());
+        }
+        Some(start) => match apply_history(vault, &start, &mut state, &mut cache) {
+            Ok(next) => state.history_id = Some(next),
+            // Too old to replay (about a week): start over from a fresh list
+            Err(ApiError { status: Some(404), .. }) => {
+                state.history_id = Some(history_id(vault)?);
+                relist.extend(state.folders.values().cloned());
+            }
+            Err(e) => return Err(e.message),
+        },
+    }
+    if known < skip + max_emails && !relist.contains(&label) {
+        relist.push(label.clone());
+    }
+    for label in relist {
+        for id in list_label(vault, &label, skip + max_emails).map_err(|e| e.message)? {
+            if !state.messages.contains_key(&id) {
+                let remote = fetch(vault, &id, true).map_err(|e| e.message)?;
+                cache.upsert(&state.folders, &remote);
+                state.messages.insert(remote.id, remote.labels);
+            }
+        }
+    }
+
+    cache.save()?;
+    save_state(vault_path, account_dir, &state)?;
+    Ok(cache
+        .emails
+        .iter()
+        .filter(|e| e.id.strip_prefix(ID_PREFIX).and_then(|id| state.messages.get(id)).is_some_and(|labels| labels.contains(&label)))
+        .skip(skip as usize)
+        .take(max_emails as usize)
+        .cloned()
+        .collect())
+}
+
+/// Folders the account can pull: the mailbox-like system labels and the
+/// user's own labels
+pub fn folders(vault_path: &str) -> Result<Vec<String>, String> {
+    let labels = google::call(Path::new(vault_path), "GET", &format!("{API}/labels"), &[], None).map_err(|e| e.message)?;
+    let mut names: Vec<String> = labels["labels"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|l| match (l["type"].as_str(), l["id"].as_str()?) {
+            (Some("user"), _) => l["name"].as_str().map(str::to_string),
+            (_, id) if SYSTEM_FOLDERS.contains(&id) => Some(id.to_string()),
+            _ => None,
+        })
+        .collect();
+    names.sort_by_key(|name| (SYSTEM_FOLDERS.iter().position(|f| f == name).unwrap_or(SYSTEM_FOLDERS.len()), name.clone()));
+    Ok(names)
+}
+
+/// Add or remove UNREAD on the message behind a cache id
+pub fn set_read(vault_path: &str, email_id: &str, read: bool) -> Result<(), String> {
+    let id = gmail_id(email_id)?;
+    let change = if read { "removeLabelIds" } else { "addLabelIds" };
+    let url = format!("{API}/messages/{}/modify", http::encode(id));
+    google::call(Path::new(vault_path), "POST", &url, &[], Some(&json!({ change: ["UNREAD"] }))).map(|_| ()).map_err(|e| e.message)
+}
+
+/// Take the message behind a cache id out of the inbox; in Gmail that is
+/// all archiving is
+pub fn archive(vault_path: &str, email_id: &str) -> Result<(), String> {
+    let id = gmail_id(email_id)?;
+    let url = format!("{API}/messages/{}/modify", http::encode(id));
+    google::call(Path::new(vault_path), "POST", &url, &[], Some(&json!({ "removeLabelIds": ["INBOX"] }))).map(|_| ()).map_err(|e| e.message)
+}
+
+/// Move the message behind a cache id to the Gmail trash
+pub fn trash(vault_path: &str, email_id: &str) -> Result<(), String> {
+    let id = gmail_id(email_id)?;
+    let url = format!("{API}/messages/{}/trash", http::encode(id));
+    match google::call(Path::new(vault_path), "POST", &url, &[], None) {
+        Ok(_) | Err(ApiError { status: Some(404), .. }) => Ok(()),
+        Err(e) => Err(e.message),
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Replay the history since `start` onto the cache: messages that left
+/// every pulled folder are dropped, new ones fetched, the rest relabelled.
+/// Returns the history id to start from next time.
+fn apply_history(vault: &Path, start: &str, state: &mut GmailState, cache: &mut Cache) -> Result<String, ApiError> {
+    let url = format!("{API}/history");
+    let mut changed: BTreeSet<String> = BTreeSet::new();
+    let mut deleted: BTreeSet<String> = BTreeSet::new();
+    let mut page_token: Option<String> = None;
+    let next = loop {
+        let mut query: Vec<(&str, &str)> = vec![("startHistoryId", start), ("maxResults", "500")];
+        if let Some(page) = &page_token {
+            query.push(("pageToken", page.as_str()));
+        }
+        let page = google::call(vault, "GET", &url, &query, None)?;
+        for record in page["history"].as_array().into_iter().flatten() {
+            changed.extend(record["messages"].as_array().into_iter().flatten().filter_map(|m| m["id"].as_str()).map(str::to_string));
+            deleted.extend(record["messagesDeleted"].as_array().into_iter().flatten().filter_map(|d| d["message"]["id"].as_str()).map(str::to_string));
+        }
+        match page["nextPageToken"].as_str() {
+            Some(next) => page_token = Some(next.to_string()),
+            None => break page["historyId"].as_str().unwrap_or(start).to_string(),
+        }
+    };
+
+    let pulled: Vec<String> = state.folders.values().cloned().collect();
+    let changed: Vec<String> = changed.difference(&deleted).cloned().collect();
+    for id in &changed {
+        let remote = match fetch(vault, id, false) {
+            Ok(remote) => remote,
+            Err(ApiError { status: Some(404), .. }) => {
+                deleted.insert(id.clone());
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        if !remote.labels.iter().any(|l| pulled.contains(l)) {
+            deleted.insert(id.clone());
+            continue;
+        }
+        let remote = if state.messages.contains_key(id) { remote } else { fetch(vault, id, true)? };
+        cache.upsert(&state.folders, &remote);
+        state.messages.insert(remote.id, remote.labels);
+    }
+    for id in deleted {
+        if state.messages.remove(&id).is_some() {
+            cache.remove(&id);
+        }
+    }
+    Ok(next)
+}
+
+/// Label id of a folder: a system label's id is the folder name itself
+fn label_id(vault: &Path, folder: &str) -> Result<Option<String>, String> {
+    let labels = google::call(vault, "GET", &format!("{API}/labels"), &[], None).map_err(|e| e.message)?;
+    Ok(labels["labels"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|l| l["id"].as_str() == Some(folder) || l["name"].as_str().is_some_and(|n| n.eq_ignore_ascii_case(folder)))
+        .and_then(|l| l["id"].as_str())
+        .map(str::to_string))
+}
+
+fn history_id(vault: &Path) -> Result<String, String> {
+    let profile = google::call(vault, "GET", &format!("{API}/profile"), &[], None).map_err(|e| e.message)?;
+    profile["historyId"].as_str().map(str::to_string).ok_or_else(|| tr!("Failed to parse: {}", "historyId"))
+}
+
+/// Ids of the newest `count` messages under `label`
+fn list_label(vault: &Path, label: &str, count: u32) -> Result<Vec<String>, ApiError> {
+    let url = format!("{API}/messages");
+    let mut ids = Vec::new();
+    let mut page_token: Option<String> = None;
+    while (ids.len() as u32) < count {
+        let max = (count - ids.len() as u32).min(500).to_string();
+        let mut query: Vec<(&str, &str)> = vec![("labelIds", label), ("maxResults", &max)];
+        if let Some(page) = &page_token {
+            query.push(("pageToken", page.as_str()));
+        }
+        let page = google::call(vault, "GET", &url, &query, None)?;
+        ids.extend(page["messages"].as_array().into_iter().flatten().filter_map(|m| m["id"].as_str()).map(str::to_string));
+        match page["nextPageToken"].as_str() {
+            Some(next) => page_token = Some(next.to_string()),
+            None => break,
+        }
+    }
+    Ok(ids)
+}
+
+/// Labels of a message, with the RFC 822 source when `raw`
+fn fetch(vault: &Path, id: &str, raw: bool) -> Result<Remote, ApiError> {
+    let url = format!("{API}/messages/{}", http::encode(id));
+    let message = google::call(vault, "GET", &url, &[("format", if raw { "raw" } else { "minimal" })], None)?;
+    parse_message(&message).ok_or_else(|| tr!("Failed to parse: {}", id).into())
+}
+
+fn parse_message(message: &Value) -> Option<Remote> {
+    let raw = match message["raw"].as_str() {
+        Some(encoded) => Some(URL_SAFE_NO_PAD.decode(encoded.trim_end_matches('=')).ok()?),
+        None => None,
+    };
+    Some(Remote {
+        id: message["id"].as_str()?.to_string(),
+        labels: message["labelIds"].as_array().into_iter().flatten().filter_map(|l| l.as_str()).map(str::to_string).collect(),
+        raw,
+    })
+}
+
+fn gmail_id(email_id: &str) -> Result<&str, String> {
+    email_id.strip_prefix(ID_PREFIX).ok_or_else(|| tr!("Email file not found: {}", email_id))
+}
+
+/// Cache folder of a message: INBOX when it is there, else the first
+/// pulled folder it carries
+fn folder_of(folders: &BTreeMap<String, String>, labels: &[String]) -> String {
+    if labels.iter().any(|l| l == "INBOX") {
+        return "INBOX".to_string();
+    }
+    folders.iter().find(|(_, label)| labels.contains(label)).map_or_else(|| "INBOX".to_string(), |(folder, _)| folder.clone())
+}
+
+/// IMAP-style flags the Mail view understands
+fn flags_of(labels: &[String]) -> Vec<String> {
+    let mut flags = Vec::new();
+    if !labels.iter().any(|l| l == "UNREAD") {
+        flags.push("Seen".to_string());
+    }
+    if labels.iter().any(|l| l == "STARRED") {
+        flags.push("Flagged".to_string());
+    }
+    flags
+}
+
+/// index.json of an account, edited in memory and written once
+struct Cache {
+    dir: PathBuf,
+    emails: Vec<EmailMessage>,
+    dirty: bool,
+}
+
+impl Cache {
+    fn open(vault_path: &str, account_dir: &str) -> Result<Self, String> {
+        let dir = PathBuf::from(vault_path).join("Mailbox").join(account_dir);
+        fs::create_dir_all(&dir).map_err(|e| tr!("Failed to create directory: {}", e))?;
+        Ok(Cache { dir, emails: load_existing_emails(vault_path, account_dir)?, dirty: false })
+    }
+
+    /// Relabel a cached message, or add it when the source came along
+    fn upsert(&mut self, folders: &BTreeMap<String, String>, remote: &Remote) {
+        let email_id = format!("{ID_PREFIX}{}", remote.id);
+        let folder = folder_of(folders, &remote.labels);
+        let flags = flags_of(&remote.labels);
+        self.dirty = true;
+        if let Some(email) = self.emails.iter_mut().find(|e| e.id == email_id) {
+            email.folder = folder;
+            email.flags = flags;
+            return;
+        }
+        let Some(raw) = &remote.raw else { return };
+        let seq = self.emails.iter().map(|e| e.uid).max().unwrap_or(0) + 1;
+        let (mut email, _) = parse_pop3_email_with_parser(raw, &folder, seq, Some(remote.id.clone()));
+        if fs::write(mail::eml_path(&self.dir, &email_id), raw).is_err() {
+            return;
+        }
+        email.id = email_id;
+        email.flags = flags;
+        self.emails.push(email);
+    }
+
+    fn remove(&mut self, id: &str) {
+        let email_id = format!("{ID_PREFIX}{id}");
+        self.emails.retain(|e| e.id != email_id);
+        let _ = fs::remove_file(mail::eml_path(&self.dir, &email_id));
+        self.dirty = true;
+    }
+
+    /// Newest first, like an IMAP page
+    fn save(&mut self) -> Result<(), String> {
+        if !self.dirty {
+            return Ok(());
+        }
+        let stamp = |e: &EmailMessage| DateTime::parse_from_rfc3339(&e.date).map(|d| d.timestamp()).unwrap_or(0);
+        self.emails.sort_by_key(|e| std::cmp::Reverse(stamp(e)));
+        save_index_json(&self.dir, &self.emails)
+    }
+}
+
+fn load_state(vault_path: &str, account_dir: &str) -> GmailState {
+    let path = PathBuf::from(vault_path).join("Mailbox").join(account_dir).join(STATE_FILE);
+    fs::read_to_string(path).ok().and_then(|raw| serde_json::from_str(&raw).ok()).unwrap_or_default()
+}
+
+fn save_state(vault_path: &str, account_dir: &str, state: &GmailState) -> Result<(), String> {
+    let path = PathBuf::from(vault_path).join("Mailbox").join(account_dir).join(STATE_FILE);
+    let json = serde_json::to_string_pretty(state).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(path, json).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages_into_cache() {
+        let vault = tempfile::tempdir().unwrap();
+        let path = vault.path().to_string_lossy().to_string();
+        let raw = "Message-ID: <m1@example.com>\r\nFrom: Alice <alice@example.com>\r\nTo: me@gmail.com\r\nSubject: Lunch\r\nDate: Tue, 10 Mar 2026 09:00:00 +0000\r\n\r\nNoon?\r\n";
+        let message = json!({ "id": "18e1", "labelIds": ["INBOX", "UNREAD", "Label_7"], "raw": URL_SAFE_NO_PAD.encode(raw) });
+        let remote = parse_message(&message).unwrap();
+        assert_eq!(remote.raw.as_deref(), Some(raw.as_bytes()));
+
+        let folders = BTreeMap::from([("INBOX".to_string(), "INBOX".to_string()), ("Work".to_string(), "Label_7".to_string())]);
+        let mut cache = Cache::open(&path, "gmail-me").unwrap();
+        cache.upsert(&folders, &remote);
+        cache.save().unwrap();
+        let email = &load_existing_emails(&path, "gmail-me").unwrap()[0];
+        assert_eq!((email.id.as_str(), email.folder.as_str(), email.subject.as_str()), ("gmail_18e1", "INBOX", "Lunch"));
+        assert!(email.flags.is_empty());
+        assert!(vault.path().join("Mailbox/gmail-me/gmail_18e1.eml").exists());
+
+        // Archived and read: only the label changes, no source needed
+        let relabelled = parse_message(&json!({ "id": "18e1", "labelIds": ["Label_7", "STARRED"] })).unwrap();
+        let mut cache = Cache::open(&path, "gmail-me").unwrap();
+        cache.upsert(&folders, &relabelled);
+        assert_eq!(cache.emails[0].folder, "Work");
+        assert_eq!(cache.emails[0].flags, ["Seen", "Flagged"]);
+        cache.remove("18e1");
+        cache.save().unwrap();
+        assert!(load_existing_emails(&path, "gmail-me").unwrap().is_empty());
+        assert!(!vault.path().join("Mailbox/gmail-me/gmail_18e1.eml").exists());
+        assert!(gmail_id("INBOX_12").is_err());
+    }
+}
@@ -0,0 +1,322 @@
+//! Google sign-in and API calls for the calendar and Gmail connectors.
+//!
+//! OAuth uses the desktop flow: the browser redirects back to a one-off
+//! listener on 127.0.0.1, with PKCE. The client comes from `google:` in
+//! connectors.yaml (a "Desktop app" client of the user's own Google Cloud
+//! project). The refresh token is kept in the Keychain where there is one,
+//! else in .lifeos/google-oauth.json, which never syncs; access tokens stay
+//! in memory until they expire.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::{connectors, durable, http, secrets};
+
+pub const GRANT_FILE: &str = ".lifeos/google-oauth.json";
+pub const CALENDAR_SCOPE: &str = "https://www.googleapis.com/auth/calendar.events";
+const AUTH_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+/// Keychain entry holding the refresh token
+const TOKEN_SECRET: &str = "google-refresh-token";
+/// How long the browser has to come back
+const SIGN_IN_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Access token and when it stops working
+static ACCESS: Mutex<Option<(String, Instant)>> = Mutex::new(None);
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct GoogleStatus {
+    /// `google.client_id` is set
+    pub configured: bool,
+    pub signed_in: bool,
+    /// Granted so far
+    pub scopes: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct Grant {
+    /// The token, or `keychain:<name>`
+    refresh_token: String,
+    scopes: Vec<String>,
+    /// RFC 3339
+    updated: String,
+}
+
+/// A failed API call; `status` tells an expired sync token (410) or a
+/// missing object (404) apart from other failures
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: Option<u16>,
+    pub message: String,
+}
+
+impl From<String> for ApiError {
+    fn from(message: String) -> Self {
+        ApiError { status: None, message }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub fn status(vault: &Path) -> GoogleStatus {
+    let grant = load_grant(vault);
+    GoogleStatus {
+        configured: connectors::value(vault, "google", "client_id").is_some(),
+        signed_in: grant.as_ref().is_some_and(|g| !g.refresh_token.is_empty()),
+        scopes: grant.map(|g| g.scopes).unwrap_or_default(),
+    }
+}
+
+/// Sign in for `scopes` (on top of any granted before): `open_url` shows
+/// Google's consent page, then this waits for the browser to come back.
+/// Returns every scope now granted.
+pub fn sign_in(vault: &Path, scopes: &[&str], open_url: &dyn Fn(&str) -> Result<(), String>) -> Result<Vec<String>, String> {
+    let (client_id, client_secret) = client(vault)?;
+    let listener = TcpListener::bind("127.0.0.1:0").map_err(|e| tr!("Google sign-in failed: {}", e))?;
+    let port = listener.local_addr().map_err(|e| tr!("Google sign-in failed: {}", e))?.port();
+    let redirect = format!("http://127.0.0.1:{port}");
+    let verifier = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+    let state = uuid::Uuid::new_v4().simple().to_string();
+
+    let mut wanted: Vec<String> = load_grant(vault).map(|g| g.scopes).unwrap_or_default();
+    wanted.extend(scopes.iter().map(|s| s.to_string()));
+    wanted.sort();
+    wanted.dedup();
+    let scope = wanted.join(" ");
+    let query: [(&str, &str); 10] = [
+        ("client_id", client_id.as_str()),
+        ("redirect_uri", redirect.as_str()),
+        ("response_type", "code"),
+        ("scope", &scope),
+        ("code_challenge", &challenge),
+        ("code_challenge_method", "S256"),
+        // A refresh token every time, not only on the first consent
+        ("access_type", "offline"),
+        ("prompt", "consent"),
+        ("include_granted_scopes", "true"),
+        ("state", &state),
+    ];
+    let query: Vec<String> = query.iter().map(|(k, v)| format!("{k}={}", http::encode(v))).collect();
+    open_url(&format!("{AUTH_URL}?{}", query.join("&")))?;
+    let code = wait_for_code(&listener, &state)?;
+
+    let form: [(&str, &str); 6] = [
+        ("code", &code),
+        ("client_id", &client_id),
+        ("client_secret", &client_secret),
+        ("redirect_uri", &redirect),
+        ("grant_type", "authorization_code"),
+        ("code_verifier", &verifier),
+    ];
+    let token = token_request(&form)?;
+    let refresh = token["refresh_token"].as_str().filter(|t| !t.is_empty()).ok_or_else(|| tr!("Google sign-in failed: {}", "no refresh token"))?;
+    let granted: Vec<String> = token["scope"].as_str().unwrap_or_default().split_whitespace().map(str::to_string).collect();
+    remember_access(&token);
+
+    let refresh_token = match secrets::store(vault, TOKEN_SECRET, refresh, Some("Google")) {
+        Ok(_) => format!("{}{TOKEN_SECRET}", secrets::REFERENCE_PREFIX),
+        Err(_) => refresh.to_string(),
+    };
+    save_grant(vault, &Grant { refresh_token, scopes: granted.clone(), updated: Local::now().to_rfc3339() })?;
+    Ok(granted)
+}
+
+/// Forget the refresh token; Google keeps the grant until it is revoked
+/// in the account settings
+pub fn sign_out(vault: &Path) -> Result<(), String> {
+    *ACCESS.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    if load_grant(vault).is_some_and(|g| g.refresh_token.starts_with(secrets::REFERENCE_PREFIX)) {
+        let _ = secrets::delete(vault, TOKEN_SECRET);
+    }
+    match fs::remove_file(vault.join(GRANT_FILE)) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e.to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// `method` on a Google API `url` with a JSON `body`, signed in; the
+/// response parsed (`null` when empty)
+pub fn call(vault: &Path, method: &str, url: &str, query: &[(&str, &str)], body: Option<&Value>) -> Result<Value, ApiError> {
+    let token = access_token(vault)?;
+    let mut request = http::agent()?.request(method, url).set("Authorization", &format!("Bearer {token}"));
+    for (key, value) in query {
+        request = request.query(key, value);
+    }
+    let response = match body {
+        Some(body) => request.set("Content-Type", "application/json").send_string(&body.to_string()),
+        None => request.call(),
+    };
+    let text = match response {
+        Ok(response) => response.into_string().map_err(|e| tr!("Request failed: {}", e))?,
+        Err(ureq::Error::Status(code, response)) => {
+            let detail = response.into_string().unwrap_or_default();
+            return Err(ApiError { status: Some(code), message: tr!("Request failed: {}", format!("HTTP {code} {}", detail.trim())) });
+        }
+        Err(e) => return Err(tr!("Request failed: {}", e).into()),
+    };
+    if text.trim().is_empty() {
+        return Ok(Value::Null);
+    }
+    serde_json::from_str(&text).map_err(|e| tr!("Failed to parse: {}", e).into())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn client(vault: &Path) -> Result<(String, String), String> {
+    let id = connectors::value(vault, "google", "client_id").ok_or_else(|| tr!("Google is not configured: {}", "google.client_id"))?;
+    Ok((id, connectors::value(vault, "google", "client_secret").unwrap_or_default()))
+}
+
+fn access_token(vault: &Path) -> Result<String, String> {
+    if let Some((token, until)) = ACCESS.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        if Instant::now() < *until {
+            return Ok(token.clone());
+        }
+    }
+    let grant = load_grant(vault).filter(|g| !g.refresh_token.is_empty()).ok_or_else(|| tr!("Not signed in to Google"))?;
+    let refresh = secrets::resolve(&grant.refresh_token)?;
+    let (client_id, client_secret) = client(vault)?;
+    let form: [(&str, &str); 4] = [("client_id", &client_id), ("client_secret", &client_secret), ("refresh_token", &refresh), ("grant_type", "refresh_token")];
+    let token = token_request(&form)?;
+    remember_access(&token).ok_or_else(|| tr!("Google sign-in failed: {}", "no access token"))
+}
+
+fn token_request(form: &[(&str, &str)]) -> Result<Value, String> {
+    let body = match http::agent()?.post(TOKEN_URL).send_form(form) {
+        Ok(response) => response.into_string(),
+        // invalid_grant and friends come back as 400 with a JSON reason
+        Err(ureq::Error::Status(_, response)) => {
+            let detail: Value = response.into_string().ok().and_then(|body| serde_json::from_str(&body).ok()).unwrap_or_default();
+            let reason = detail["error_description"].as_str().or(detail["error"].as_str()).unwrap_or("unknown").to_string();
+            return Err(tr!("Google sign-in failed: {}", reason));
+        }
+        Err(e) => return Err(tr!("Request failed: {}", e)),
+    };
+    let body = body.map_err(|e| tr!("Request failed: {}", e))?;
+    serde_json::from_str(&body).map_err(|e| tr!("Failed to parse: {}", e))
+}
+
+/// Cache the access token of a token response, a minute short of its expiry
+fn remember_access(token: &Value) -> Option<String> {
+    let access = token["access_token"].as_str()?.to_string();
+    let lifetime = token["expires_in"].as_u64().unwrap_or(3600).saturating_sub(60);
+    *ACCESS.lock().unwrap_or_else(|e| e.into_inner()) = Some((access.clone(), Instant::now() + Duration::from_secs(lifetime)));
+    Some(access)
+}
+
+/// Serve the redirect: the `code` once the browser comes back with our
+/// `state`. Other requests (favicon) get a 404 and are ignored.
+fn wait_for_code(listener: &TcpListener, state: &str) -> Result<String, String> {
+    listener.set_nonblocking(true).map_err(|e| tr!("Google sign-in failed: {}", e))?;
+    let deadline = Instant::now() + SIGN_IN_TIMEOUT;
+    while Instant::now() < deadline {
+        let mut stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(200));
+                continue;
+            }
+            Err(e) => return Err(tr!("Google sign-in failed: {}", e)),
+        };
+        let params = read_query(&mut stream);
+        let param = |name: &str| params.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone());
+        if let Some(error) = param("error") {
+            respond(&mut stream, "200 OK", "登录已取消，可以关闭此页面。");
+            return Err(tr!("Google sign-in failed: {}", error));
+        }
+        match (param("code"), param("state")) {
+            (Some(code), Some(s)) if s == state => {
+                respond(&mut stream, "200 OK", "LifeOS 已连接 Google，可以关闭此页面。");
+                return Ok(code);
+            }
+            _ => respond(&mut stream, "404 Not Found", ""),
+        }
+    }
+    Err(tr!("Google sign-in failed: {}", "timed out"))
+}
+
+/// Query parameters of the request line, decoded
+fn read_query(stream: &mut TcpStream) -> Vec<(String, String)> {
+    let _ = stream.set_nonblocking(false);
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+    let mut buf = [0u8; 8192];
+    let n = stream.read(&mut buf).unwrap_or(0);
+    let request = String::from_utf8_lossy(&buf[..n]);
+    let target = request.split_whitespace().nth(1).unwrap_or_default();
+    let query = target.split_once('?').map_or("", |(_, q)| q);
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (http::decode(k), http::decode(&v.replace('+', " "))))
+        .collect()
+}
+
+fn respond(stream: &mut TcpStream, status: &str, message: &str) {
+    let body = format!("<!doctype html><meta charset=\"utf-8\"><title>LifeOS</title><p style=\"font-family:sans-serif\">{message}</p>");
+    let _ = write!(stream, "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}", body.len());
+}
+
+fn load_grant(vault: &Path) -> Option<Grant> {
+    fs::read_to_string(vault.join(GRANT_FILE)).ok().and_then(|raw| serde_json::from_str(&raw).ok())
+}
+
+fn save_grant(vault: &Path, grant: &Grant) -> Result<(), String> {
+    let path = vault.join(GRANT_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(grant).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(path, json).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redirect_listener() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let browser = std::thread::spawn(move || {
+            let mut replies = Vec::new();
+            for path in ["/favicon.ico", "/?state=wrong&code=x", "/?state=s1&code=4%2F0Ab-c&scope=a+b"] {
+                let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+                write!(stream, "GET {path} HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n").unwrap();
+                let mut reply = String::new();
+                stream.read_to_string(&mut reply).unwrap();
+                replies.push(reply.lines().next().unwrap_or_default().to_string());
+            }
+            replies
+        });
+        assert_eq!(wait_for_code(&listener, "s1").unwrap(), "4/0Ab-c");
+        assert_eq!(browser.join().unwrap(), ["HTTP/1.1 404 Not Found", "HTTP/1.1 404 Not Found", "HTTP/1.1 200 OK"]);
+
+        let vault = tempfile::tempdir().unwrap();
+        let status = status(vault.path());
+        assert!(!status.configured && !status.signed_in);
+        assert!(access_token(vault.path()).is_err());
+    }
+}
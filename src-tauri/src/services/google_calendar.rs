@@ -0,0 +1,473 @@
+//! Two-way Google Calendar sync.
+//!
+//! Events of the calendars listed under `calendar.calendars` are pulled
+//! into connectors/calendar/google-<id>.ics, where the agenda, planner and
+//! meeting notes read every other calendar. After the first full pull only
+//! changes are fetched, with the sync token Google hands back. Planner time
+//! blocks from today on go the other way, to `calendar.push_to`; those
+//! events are tagged with a private `lifeos` property so they are never
+//! pulled back as outside events.
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, TryLockError};
+
+use super::calendar::CALENDAR_DIR;
+use super::google::{self, ApiError};
+use super::planner::{blocks_from, TimeBlock};
+use super::{connectors, durable, http};
+
+const STATE_FILE: &str = ".lifeos/google-calendar.json";
+const API: &str = "https://www.googleapis.com/calendar/v3";
+/// Private extended property naming the time block an event came from
+const ORIGIN_KEY: &str = "lifeos";
+const DEFAULT_INTERVAL_MINUTES: u64 = 15;
+
+/// One sync at a time, whether manual or from the background loop
+static RUNNING: Mutex<()> = Mutex::new(());
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CalendarSyncReport {
+    /// RFC 3339
+    pub finished: String,
+    /// Events added or changed locally
+    pub pulled: usize,
+    /// Events deleted or cancelled in Google
+    pub removed: usize,
+    /// Time blocks created or moved in Google
+    pub pushed: usize,
+    /// Time blocks unscheduled, so their events were deleted
+    pub deleted_remote: usize,
+    /// `calendar: error`; retried on the next sync
+    pub errors: Vec<String>,
+}
+
+pub struct Settings {
+    pub enabled: bool,
+    pub interval_minutes: u64,
+    /// Calendar ids to pull (`primary` is the account's own)
+    pub calendars: Vec<String>,
+    /// Calendar id time blocks go to; none when empty
+    pub push_to: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct State {
+    /// Calendar id → what was pulled so far
+    calendars: BTreeMap<String, CalendarState>,
+    /// `date:task_ref` → the event made for that time block
+    pushed: BTreeMap<String, PushedBlock>,
+    last: Option<CalendarSyncReport>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct CalendarState {
+    /// None until the first full pull finished
+    sync_token: Option<String>,
+    /// Event id → event
+    events: BTreeMap<String, Event>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Event {
+    summary: String,
+    /// ICS value: `YYYYMMDD` (all-day) or `YYYYMMDDTHHMMSSZ`
+    start: String,
+    end: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    location: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    organizer: Option<Person>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    attendees: Vec<Person>,
+    /// RRULE / EXDATE lines of a recurring event
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    recurrence: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Person {
+    name: Option<String>,
+    email: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct PushedBlock {
+    event_id: String,
+    calendar: String,
+    /// YYYY-MM-DD
+    date: String,
+    /// HH:MM
+    start: String,
+    duration: u32,
+    title: String,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// `calendar:` in connectors.yaml
+pub fn settings(vault: &Path) -> Settings {
+    let section = connectors::section(vault, "calendar").unwrap_or_default();
+    let calendars: Vec<String> = section
+        .get("calendars")
+        .and_then(|v| v.as_sequence())
+        .map(|list| list.iter().filter_map(|c| c.as_str()).map(str::trim).filter(|c| !c.is_empty()).map(str::to_string).collect())
+        .unwrap_or_else(|| vec!["primary".to_string()]);
+    Settings {
+        enabled: section.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false),
+        interval_minutes: section.get("interval_minutes").and_then(|v| v.as_u64()).unwrap_or(DEFAULT_INTERVAL_MINUTES),
+        calendars,
+        push_to: section.get("push_to").and_then(|v| v.as_str()).map(str::trim).filter(|c| !c.is_empty()).map(str::to_string),
+    }
+}
+
+/// Report of the last finished sync
+pub fn last_report(vault: &Path) -> Option<CalendarSyncReport> {
+    load_state(vault).last
+}
+
+/// Pull changed events of every configured calendar, then push the
+/// planner's time blocks
+pub fn sync(vault: &Path) -> Result<CalendarSyncReport, String> {
+    let _guard = match RUNNING.try_lock() {
+        Ok(guard) => guard,
+        Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+        Err(TryLockError::WouldBlock) => return Err(tr!("Sync is already running")),
+    };
+    if !google::status(vault).signed_in {
+        return Err(tr!("Not signed in to Google"));
+    }
+    let settings = settings(vault);
+    let mut state = load_state(vault);
+    let mut report = CalendarSyncReport::default();
+
+    // Calendars dropped from the config go away with their file
+    let dropped: Vec<String> = state.calendars.keys().filter(|id| !settings.calendars.contains(id)).cloned().collect();
+    for id in dropped {
+        state.calendars.remove(&id);
+        let _ = fs::remove_file(ics_path(vault, &id));
+    }
+
+    for id in &settings.calendars {
+        let calendar = state.calendars.entry(id.clone()).or_default();
+        match pull(vault, id, calendar) {
+            Ok((pulled, removed)) => {
+                report.pulled += pulled;
+                report.removed += removed;
+            }
+            Err(e) => report.errors.push(format!("{id}: {}", e.message)),
+        }
+        if let Err(e) = write_ics(vault, id, calendar) {
+            report.errors.push(format!("{id}: {e}"));
+        }
+    }
+
+    if let Some(target) = &settings.push_to {
+        push(vault, target, &mut state.pushed, &mut report);
+    }
+
+    report.finished = Local::now().to_rfc3339();
+    state.last = Some(report.clone());
+    save_state(vault, &state)?;
+    Ok(report)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Fetch what changed since the last sync token (everything without one).
+/// An expired token (410) falls back to a full pull.
+/// Returns (added or changed, removed).
+fn pull(vault: &Path, id: &str, calendar: &mut CalendarState) -> Result<(usize, usize), ApiError> {
+    match pull_pages(vault, id, calendar) {
+        Err(ApiError { status: Some(410), .. }) => {
+            calendar.sync_token = None;
+            pull_pages(vault, id, calendar)
+        }
+        result => result,
+    }
+}
+
+fn pull_pages(vault: &Path, id: &str, calendar: &mut CalendarState) -> Result<(usize, usize), ApiError> {
+    let url = format!("{API}/calendars/{}/events", http::encode(id));
+    let full = calendar.sync_token.is_none();
+    let mut fetched: BTreeMap<String, Option<Event>> = BTreeMap::new();
+    let mut page_token: Option<String> = None;
+    let sync_token = loop {
+        let mut query: Vec<(&str, &str)> = vec![("maxResults", "250")];
+        if let Some(token) = &calendar.sync_token {
+            query.push(("syncToken", token.as_str()));
+        }
+        if let Some(page) = &page_token {
+            query.push(("pageToken", page.as_str()));
+        }
+        let page = google::call(vault, "GET", &url, &query, None)?;
+        for item in page["items"].as_array().into_iter().flatten() {
+            if let Some(event_id) = item["id"].as_str() {
+                fetched.insert(event_id.to_string(), parse_event(item));
+            }
+        }
+        match page["nextPageToken"].as_str() {
+            Some(next) => page_token = Some(next.to_string()),
+            None => break page["nextSyncToken"].as_str().map(str::to_string),
+        }
+    };
+
+    let mut events = if full { BTreeMap::new() } else { std::mem::take(&mut calendar.events) };
+    let (mut changed, mut removed) = (0, 0);
+    for (event_id, event) in fetched {
+        match event {
+            Some(event) => {
+                if events.get(&event_id) != Some(&event) {
+                    changed += 1;
+                }
+                events.insert(event_id, event);
+            }
+            None => removed += usize::from(events.remove(&event_id).is_some()),
+        }
+    }
+    if full {
+        removed = calendar.events.keys().filter(|id| !events.contains_key(*id)).count();
+    }
+    calendar.events = events;
+    calendar.sync_token = sync_token;
+    Ok((changed, removed))
+}
+
+/// None for events that are cancelled, unreadable, or time blocks we pushed
+fn parse_event(item: &Value) -> Option<Event> {
+    if item["status"].as_str() == Some("cancelled") || !item["extendedProperties"]["private"][ORIGIN_KEY].is_null() {
+        return None;
+    }
+    let text = |key: &str| item[key].as_str().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+    let person = |p: &Value| {
+        let email = p["email"].as_str()?.to_string();
+        Some(Person { name: p["displayName"].as_str().map(str::to_string), email })
+    };
+    Some(Event {
+        summary: text("summary").unwrap_or_default(),
+        start: ics_value(&item["start"])?,
+        end: ics_value(&item["end"]),
+        location: text("location"),
+        description: text("description"),
+        organizer: person(&item["organizer"]),
+        attendees: item["attendees"].as_array().into_iter().flatten().filter_map(person).collect(),
+        recurrence: item["recurrence"].as_array().into_iter().flatten().filter_map(|r| r.as_str()).map(str::to_string).collect(),
+    })
+}
+
+/// `{"date": ...}` or `{"dateTime": ...}` as an ICS date / UTC date-time
+fn ics_value(time: &Value) -> Option<String> {
+    if let Some(date) = time["date"].as_str() {
+        return Some(NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?.format("%Y%m%d").to_string());
+    }
+    let at = DateTime::parse_from_rfc3339(time["dateTime"].as_str()?).ok()?;
+    Some(at.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ").to_string())
+}
+
+fn ics_path(vault: &Path, id: &str) -> PathBuf {
+    let slug: String = id.chars().map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' }).collect();
+    vault.join(CALENDAR_DIR).join(format!("google-{slug}.ics"))
+}
+
+fn write_ics(vault: &Path, id: &str, calendar: &CalendarState) -> Result<(), String> {
+    let path = ics_path(vault, id);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    durable::write(path, ics(id, calendar)).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+fn ics(id: &str, calendar: &CalendarState) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//LifeOS//Google Calendar//EN".to_string(),
+        format!("X-WR-CALNAME:{}", ics_escape(id)),
+    ];
+    for (event_id, event) in &calendar.events {
+        let time = |name: &str, value: &str| match value.len() {
+            8 => format!("{name};VALUE=DATE:{value}"),
+            _ => format!("{name}:{value}"),
+        };
+        let person = |name: &str, p: &Person| match &p.name {
+            Some(cn) => format!("{name};CN=\"{}\":mailto:{}", cn.replace('"', "'"), p.email),
+            None => format!("{name}:mailto:{}", p.email),
+        };
+        lines.push("BEGIN:VEVENT".into());
+        lines.push(format!("UID:{event_id}"));
+        lines.push(time("DTSTART", &event.start));
+        lines.extend(event.end.as_deref().map(|end| time("DTEND", end)));
+        lines.push(format!("SUMMARY:{}", ics_escape(&event.summary)));
+        lines.extend(event.location.as_deref().map(|l| format!("LOCATION:{}", ics_escape(l))));
+        lines.extend(event.description.as_deref().map(|d| format!("DESCRIPTION:{}", ics_escape(d))));
+        lines.extend(event.organizer.as_ref().map(|p| person("ORGANIZER", p)));
+        lines.extend(event.attendees.iter().map(|p| person("ATTENDEE", p)));
+        lines.extend(event.recurrence.iter().cloned());
+        lines.push("END:VEVENT".into());
+    }
+    lines.push("END:VCALENDAR".into());
+    lines.join("\r\n") + "\r\n"
+}
+
+/// TEXT escaping of RFC 5545 §3.3.11
+fn ics_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,").replace("\r\n", "\\n").replace('\n', "\\n")
+}
+
+/// Create, move or delete events so `target` holds exactly the time blocks
+/// from today on. Blocks of past days are forgotten, their events kept.
+fn push(vault: &Path, target: &str, pushed: &mut BTreeMap<String, PushedBlock>, report: &mut CalendarSyncReport) {
+    let today = Local::now().date_naive();
+    let blocks: BTreeMap<String, (String, TimeBlock)> =
+        blocks_from(vault, today).into_iter().map(|(date, block)| (format!("{date}:{}", block.task_ref), (date, block))).collect();
+
+    let stale: Vec<String> = pushed.keys().filter(|key| !blocks.contains_key(*key)).cloned().collect();
+    for key in stale {
+        let Some(old) = pushed.get(&key) else { continue };
+        if NaiveDate::parse_from_str(&old.date, "%Y-%m-%d").is_ok_and(|d| d < today) {
+            pushed.remove(&key);
+            continue;
+        }
+        let url = format!("{API}/calendars/{}/events/{}", http::encode(&old.calendar), http::encode(&old.event_id));
+        match google::call(vault, "DELETE", &url, &[], None) {
+            Ok(_) | Err(ApiError { status: Some(404 | 410), .. }) => {
+                pushed.remove(&key);
+                report.deleted_remote += 1;
+            }
+            Err(e) => report.errors.push(format!("{target}: {}", e.message)),
+        }
+    }
+
+    for (key, (date, block)) in blocks {
+        let wanted = PushedBlock {
+            event_id: String::new(),
+            calendar: target.to_string(),
+            date: date.clone(),
+            start: block.start.clone(),
+            duration: block.duration,
+            title: block.title.clone(),
+        };
+        let existing = pushed.get(&key).filter(|old| old.calendar == target);
+        if existing.is_some_and(|old| PushedBlock { event_id: old.event_id.clone(), ..wanted.clone() } == *old) {
+            continue;
+        }
+        let Some(body) = block_event(&key, &wanted) else { continue };
+        let events = format!("{API}/calendars/{}/events", http::encode(target));
+        let result = match existing {
+            Some(old) => match google::call(vault, "PATCH", &format!("{events}/{}", http::encode(&old.event_id)), &[], Some(&body)) {
+                Err(ApiError { status: Some(404 | 410), .. }) => google::call(vault, "POST", &events, &[], Some(&body)),
+                result => result,
+            },
+            None => google::call(vault, "POST", &events, &[], Some(&body)),
+        };
+        match result.map(|event| event["id"].as_str().map(str::to_string)) {
+            Ok(Some(event_id)) => {
+                pushed.insert(key, PushedBlock { event_id, ..wanted });
+                report.pushed += 1;
+            }
+            Ok(None) => report.errors.push(format!("{target}: {}", block.title)),
+            Err(e) => report.errors.push(format!("{target}: {}", e.message)),
+        }
+    }
+}
+
+/// Event body for a time block, in local time
+fn block_event(key: &str, block: &PushedBlock) -> Option<Value> {
+    let start = NaiveDateTime::parse_from_str(&format!("{} {}", block.date, block.start), "%Y-%m-%d %H:%M").ok()?;
+    let end = start + chrono::Duration::minutes(block.duration as i64);
+    let local = |at: NaiveDateTime| Local.from_local_datetime(&at).earliest().map(|t| t.to_rfc3339());
+    Some(json!({
+        "summary": block.title,
+        "start": { "dateTime": local(start)? },
+        "end": { "dateTime": local(end)? },
+        "extendedProperties": { "private": { ORIGIN_KEY: key } },
+    }))
+}
+
+fn load_state(vault: &Path) -> State {
+    fs::read_to_string(vault.join(STATE_FILE)).ok().and_then(|raw| serde_json::from_str(&raw).ok()).unwrap_or_default()
+}
+
+fn save_state(vault: &Path, state: &State) -> Result<(), String> {
+    let path = vault.join(STATE_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(state).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(path, json).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::calendar;
+
+    #[test]
+    fn test_events_to_ics() {
+        let items = json!([
+            {
+                "id": "abc123",
+                "status": "confirmed",
+                "summary": "Roadmap review, Q2",
+                "location": "Room 3",
+                "description": "Agenda:\nHiring",
+                "start": { "dateTime": "2026-03-10T14:00:00Z" },
+                "end": { "dateTime": "2026-03-10T15:00:00Z" },
+                "organizer": { "email": "zhang@example.com", "displayName": "Zhang San" },
+                "attendees": [{ "email": "bob@example.com" }]
+            },
+            { "id": "allday", "summary": "Offsite", "start": { "date": "2026-03-11" }, "end": { "date": "2026-03-13" } },
+            { "id": "gone", "status": "cancelled" },
+            {
+                "id": "mine",
+                "summary": "Write report",
+                "start": { "dateTime": "2026-03-10T09:00:00+08:00" },
+                "extendedProperties": { "private": { "lifeos": "2026-03-10:3" } }
+            }
+        ]);
+        let items = items.as_array().unwrap();
+        assert!(parse_event(&items[2]).is_none());
+        assert!(parse_event(&items[3]).is_none());
+        let review = parse_event(&items[0]).unwrap();
+        assert_eq!(review.start, "20260310T140000Z");
+        assert_eq!(review.organizer.as_ref().and_then(|p| p.name.as_deref()), Some("Zhang San"));
+        assert_eq!(parse_event(&items[1]).unwrap().end.as_deref(), Some("20260313"));
+
+        let calendar = CalendarState {
+            sync_token: Some("t1".into()),
+            events: items.iter().filter_map(|i| Some((i["id"].as_str()?.to_string(), parse_event(i)?))).collect(),
+        };
+        let raw = ics("primary", &calendar);
+        assert!(raw.contains("SUMMARY:Roadmap review\\, Q2\r\n"));
+        assert!(raw.contains("DESCRIPTION:Agenda:\\nHiring\r\n"));
+        assert!(raw.contains("DTSTART;VALUE=DATE:20260311\r\n"));
+        assert!(raw.contains("ORGANIZER;CN=\"Zhang San\":mailto:zhang@example.com\r\n"));
+
+        // The agenda reads it like any other calendar file
+        let vault = tempfile::tempdir().unwrap();
+        write_ics(vault.path(), "primary", &calendar).unwrap();
+        assert!(vault.path().join("connectors/calendar/google-primary.ics").exists());
+        let offsite = calendar::events(vault.path(), NaiveDate::from_ymd_opt(2026, 3, 12).unwrap());
+        assert_eq!(offsite.len(), 1);
+        assert_eq!(offsite[0].title, "Offsite");
+        assert_eq!(offsite[0].id.as_deref(), Some("allday"));
+    }
+}
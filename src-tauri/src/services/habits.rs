@@ -0,0 +1,210 @@
+//! Reader for daily/habits/habits.yaml, the file the dashboard writes:
+//!
+//! ```yaml
+//! habits:
+//!   - id: reading
+//!     name: "阅读"
+//!     icon: "📖"
+//!     target_days: [1,2,3,4,5,6,7]
+//!     created: "2025-01-01"
+//! checkins:
+//!   2025-01-02: [reading]
+//! ```
+//!
+//! Vacations and streak freezes live beside it in pauses.yaml, since the
+//! dashboard rewrites habits.yaml with only the two keys above.
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use super::durable;
+
+pub const HABITS_FILE: &str = "daily/habits/habits.yaml";
+pub const PAUSES_FILE: &str = "daily/habits/pauses.yaml";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct HabitDef {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub icon: String,
+    /// 1=Mon..7=Sun; empty = every day
+    #[serde(default)]
+    pub target_days: Vec<u32>,
+    #[serde(default)]
+    pub created: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct HabitStore {
+    #[serde(default)]
+    pub habits: Vec<HabitDef>,
+    /// date (YYYY-MM-DD) → ids checked in; a bare `checkins:` is empty
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub checkins: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PauseKind {
+    Vacation,
+    Freeze,
+}
+
+/// Days on which missing a check-in doesn't break a streak
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HabitPause {
+    pub kind: PauseKind,
+    /// YYYY-MM-DD, inclusive
+    pub from: String,
+    /// YYYY-MM-DD, inclusive
+    pub to: String,
+    /// Habit ids; empty = all habits
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub habits: Vec<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PauseLog {
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub pauses: Vec<HabitPause>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct Streak {
+    pub current: u32,
+    pub longest: u32,
+}
+
+impl HabitDef {
+    pub fn due_on(&self, date: NaiveDate) -> bool {
+        self.target_days.is_empty() || self.target_days.contains(&date.weekday().number_from_monday())
+    }
+}
+
+impl HabitStore {
+    pub fn done_on(&self, id: &str, date: NaiveDate) -> bool {
+        self.checkins.get(&date.format("%Y-%m-%d").to_string()).is_some_and(|ids| ids.iter().any(|i| i == id))
+    }
+}
+
+impl HabitPause {
+    pub fn covers(&self, id: &str, date: NaiveDate) -> bool {
+        let date = date.format("%Y-%m-%d").to_string();
+        (self.from.as_str()..=self.to.as_str()).contains(&date.as_str())
+            && (self.habits.is_empty() || self.habits.iter().any(|h| h == id))
+    }
+}
+
+/// Empty when the file is missing or unreadable, like the dashboard
+pub fn load(vault: &Path) -> HabitStore {
+    fs::read_to_string(vault.join(HABITS_FILE))
+        .ok()
+        .and_then(|raw| serde_yaml::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn load_pauses(vault: &Path) -> Vec<HabitPause> {
+    fs::read_to_string(vault.join(PAUSES_FILE))
+        .ok()
+        .and_then(|raw| serde_yaml::from_str::<PauseLog>(&raw).ok())
+        .unwrap_or_default()
+        .pauses
+}
+
+/// Written in start order
+pub fn save_pauses(vault: &Path, pauses: &[HabitPause]) -> Result<(), String> {
+    let mut pauses = pauses.to_vec();
+    pauses.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+    let path = vault.join(PAUSES_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let raw = serde_yaml::to_string(&PauseLog { pauses }).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(&path, raw).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+pub fn paused_on(pauses: &[HabitPause], id: &str, date: NaiveDate) -> bool {
+    pauses.iter().any(|p| p.covers(id, date))
+}
+
+/// Runs of checked-in days up to `today`. Days off `target_days` and paused
+/// days neither extend nor break a run (a check-in on one still counts), and
+/// `today` unchecked doesn't break it yet.
+pub fn streak(store: &HabitStore, pauses: &[HabitPause], habit: &HabitDef, today: NaiveDate) -> Streak {
+    let first_checkin = store
+        .checkins
+        .iter()
+        .find(|(_, ids)| ids.contains(&habit.id))
+        .and_then(|(date, _)| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+    let created = NaiveDate::parse_from_str(&habit.created, "%Y-%m-%d").ok();
+    let Some(mut date) = [first_checkin, created].into_iter().flatten().min() else { return Streak::default() };
+
+    let mut out = Streak::default();
+    while date <= today {
+        if store.done_on(&habit.id, date) {
+            out.current += 1;
+            out.longest = out.longest.max(out.current);
+        } else if habit.due_on(date) && date != today && !paused_on(pauses, &habit.id, date) {
+            out.current = 0;
+        }
+        let Some(next) = date.succ_opt() else { break };
+        date = next;
+    }
+    out
+}
+
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dashboard_format() {
+        let raw = "# Habit Definitions\nhabits:\n  - id: reading\n    name: \"阅读\"\n    icon: \"📖\"\n    target_days: [1,3,5]\n    created: \"2025-01-01\"\n\n# Check-in records\ncheckins:\n";
+        let store: HabitStore = serde_yaml::from_str(raw).unwrap();
+        let monday = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        assert!(store.habits[0].due_on(monday) && !store.habits[0].due_on(monday.succ_opt().unwrap()));
+        assert!(!store.done_on("reading", monday));
+
+        let store: HabitStore = serde_yaml::from_str(&format!("{raw}  2025-03-03: [reading, exercise]\n")).unwrap();
+        assert!(store.done_on("reading", monday));
+    }
+
+    #[test]
+    fn test_streak_skips_pauses() {
+        let day = |d: u32| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+        let raw = "habits:\n  - id: run\n    target_days: [1,2,3,4,5]\n    created: \"2025-03-03\"\ncheckins:\n  2025-03-03: [run]\n  2025-03-04: [run]\n  2025-03-07: [run]\n  2025-03-10: [run]\n";
+        let store: HabitStore = serde_yaml::from_str(raw).unwrap();
+        let habit = &store.habits[0];
+
+        // Wed 5th and Thu 6th missed; the weekend is off-target anyway
+        assert_eq!(streak(&store, &[], habit, day(11)), Streak { current: 2, longest: 2 });
+
+        let pauses: PauseLog = serde_yaml::from_str(
+            "pauses:\n  - kind: vacation\n    from: \"2025-03-05\"\n    to: \"2025-03-05\"\n    habits: [run]\n  - kind: freeze\n    from: \"2025-03-06\"\n    to: \"2025-03-06\"\n",
+        )
+        .unwrap();
+        assert_eq!(streak(&store, &pauses.pauses, habit, day(11)), Streak { current: 4, longest: 4 });
+        // A missed due day that has passed still breaks it
+        assert_eq!(streak(&store, &pauses.pauses, habit, day(12)).current, 0);
+        assert!(!pauses.pauses[0].covers("read", day(5)) && pauses.pauses[1].covers("read", day(6)));
+    }
+}
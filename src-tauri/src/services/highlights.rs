@@ -0,0 +1,278 @@
+//! Parsers for e-reader highlight exports: Kindle's `My Clippings.txt`, text
+//! copied out of Apple Books ("Excerpt From …") and a CSV dump of Apple
+//! Books' annotation database. Filing them into book notes is up to the caller.
+
+use chrono::{Duration, Local, NaiveDate, TimeZone};
+use std::collections::HashMap;
+
+/// Separator between entries in My Clippings.txt
+const KINDLE_SEPARATOR: &str = "==========";
+/// Apple's Core Data timestamps count from 2001-01-01 UTC
+const CORE_DATA_EPOCH: i64 = 978_307_200;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Clipping {
+    pub title: String,
+    pub authors: Vec<String>,
+    pub text: String,
+    pub page: Option<u32>,
+    /// Kindle location (`1234-1236`) or Apple Books chapter
+    pub location: Option<String>,
+    /// `YYYY-MM-DD`
+    pub date: Option<String>,
+}
+
+/// Highlights in `raw`, and how many entries were skipped (bookmarks, notes,
+/// empty clippings). `file_name` only picks the CSV reader by extension.
+pub fn parse(file_name: &str, raw: &str) -> Result<(Vec<Clipping>, usize), String> {
+    let raw = raw.trim_start_matches('\u{feff}');
+    if file_name.to_lowercase().ends_with(".csv") {
+        parse_apple_csv(raw)
+    } else if raw.contains(KINDLE_SEPARATOR) {
+        Ok(parse_kindle(raw))
+    } else if raw.lines().any(is_excerpt_marker) {
+        Ok(parse_apple_excerpts(raw))
+    } else {
+        Err(tr!("Unrecognized highlights file: {}", file_name))
+    }
+}
+
+/// Whitespace collapsed and outer quotes dropped, for comparing highlights
+/// that were re-exported or re-wrapped
+pub fn normalize(text: &str) -> String {
+    let text = text.trim().trim_matches(['"', '“', '”', '「', '」']);
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Kindle
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// ```text
+/// Atomic Habits (James Clear)
+/// - Your Highlight on page 12 | Location 170-172 | Added on Friday, March 15, 2024 2:05:22 PM
+///
+/// You do not rise to the level of your goals.
+/// ==========
+/// ```
+/// Chinese Kindles write `- 您在第 12 页（位置 #170-172）的标注 | 添加于 2024年3月15日星期五 下午2:05:22`.
+fn parse_kindle(raw: &str) -> (Vec<Clipping>, usize) {
+    let mut clippings = Vec::new();
+    let mut skipped = 0;
+    for entry in raw.split(KINDLE_SEPARATOR) {
+        let mut lines = entry.lines().map(|l| l.trim_start_matches('\u{feff}').trim_end_matches('\r')).skip_while(|l| l.trim().is_empty());
+        let (Some(heading), Some(meta)) = (lines.next(), lines.next()) else { continue };
+        let text = lines.collect::<Vec<_>>().join("\n").trim().to_string();
+        let meta_lower = meta.to_lowercase();
+        let is_highlight = meta_lower.contains("highlight") || meta.contains("标注");
+        if !is_highlight || text.is_empty() {
+            skipped += 1;
+            continue;
+        }
+        let (title, authors) = split_heading(heading.trim());
+        clippings.push(Clipping {
+            title,
+            authors,
+            text,
+            page: token_after(&meta_lower, "page ").or_else(|| token_after(meta, "第")).and_then(|p| p.parse().ok()),
+            location: token_after(&meta_lower, "location ").or_else(|| token_after(meta, "位置")).map(str::to_string),
+            date: kindle_date(meta),
+        });
+    }
+    (clippings, skipped)
+}
+
+/// `Title (Author; Other Author)` → title and authors
+fn split_heading(heading: &str) -> (String, Vec<String>) {
+    let (open, close) = if heading.ends_with('）') { ('（', '）') } else { ('(', ')') };
+    if let Some(inner) = heading.strip_suffix(close) {
+        if let Some(start) = inner.rfind(open) {
+            let authors = inner[start + open.len_utf8()..].split(';').map(|a| a.trim().to_string()).filter(|a| !a.is_empty()).collect();
+            let title = inner[..start].trim();
+            if !title.is_empty() {
+                return (title.to_string(), authors);
+            }
+        }
+    }
+    (heading.to_string(), vec![])
+}
+
+/// Digits and dashes following `marker`, skipping spaces and `#`
+fn token_after<'a>(text: &'a str, marker: &str) -> Option<&'a str> {
+    let rest = text[text.find(marker)? + marker.len()..].trim_start_matches([' ', '#']);
+    let end = rest.find(|c: char| !(c.is_ascii_digit() || c == '-')).unwrap_or(rest.len());
+    let token = rest[..end].trim_end_matches('-');
+    (!token.is_empty()).then_some(token)
+}
+
+/// `Added on Friday, March 15, 2024 2:05:22 PM` or `添加于 2024年3月15日星期五 …`
+fn kindle_date(meta: &str) -> Option<String> {
+    let date = if let Some(i) = meta.find("Added on ") {
+        let parts: Vec<&str> = meta[i + 9..].split(", ").collect();
+        let year = parts.get(2)?.split_whitespace().next()?;
+        NaiveDate::parse_from_str(&format!("{} {year}", parts.get(1)?), "%B %d %Y").ok()?
+    } else {
+        let rest = &meta[meta.find("添加于")? + "添加于".len()..];
+        let numbers: Vec<u32> = rest.split(|c: char| !c.is_ascii_digit()).filter(|s| !s.is_empty()).take(3).filter_map(|s| s.parse().ok()).collect();
+        NaiveDate::from_ymd_opt(*numbers.first()? as i32, *numbers.get(1)?, *numbers.get(2)?)?
+    };
+    Some(date.format("%Y-%m-%d").to_string())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Apple Books
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn is_excerpt_marker(line: &str) -> bool {
+    matches!(line.trim(), "Excerpt From" | "摘录来自" | "摘錄來自")
+}
+
+/// Highlights copied from Apple Books, pasted one after another:
+///
+/// ```text
+/// “You do not rise to the level of your goals.”
+///
+/// Excerpt From
+/// Atomic Habits
+/// James Clear
+/// This material may be protected by copyright.
+/// ```
+fn parse_apple_excerpts(raw: &str) -> (Vec<Clipping>, usize) {
+    let mut clippings = Vec::new();
+    let mut quote: Vec<&str> = Vec::new();
+    let mut lines = raw.lines().map(|l| l.trim_end_matches('\r')).peekable();
+    while let Some(line) = lines.next() {
+        if !is_excerpt_marker(line) {
+            quote.push(line);
+            continue;
+        }
+        let mut source = Vec::new();
+        while source.len() < 2 {
+            match lines.next() {
+                Some(l) if l.trim().is_empty() => {}
+                Some(l) => source.push(l.trim().to_string()),
+                None => break,
+            }
+        }
+        // Copyright notice and store link
+        while lines.peek().is_some_and(|l| {
+            let l = l.trim();
+            l.is_empty() || l.contains("copyright") || l.contains("版权") || l.contains("版權") || l.starts_with("http")
+        }) {
+            lines.next();
+        }
+        let text = normalize_quote(&quote.join("\n"));
+        quote.clear();
+        let mut source = source.into_iter();
+        let Some(title) = source.next() else { continue };
+        if !text.is_empty() {
+            clippings.push(Clipping { title, authors: source.collect(), text, ..Default::default() });
+        }
+    }
+    (clippings, 0)
+}
+
+/// Trim and drop the curly quotes Apple Books wraps excerpts in
+fn normalize_quote(text: &str) -> String {
+    let text = text.trim();
+    let text = text.strip_prefix('“').and_then(|t| t.strip_suffix('”')).unwrap_or(text);
+    text.trim().to_string()
+}
+
+/// A CSV dump of AEAnnotation*.sqlite joined with the library's book titles.
+/// Columns are found by header (case-insensitive); the database's own names
+/// (`ZANNOTATIONSELECTEDTEXT`, `ZFUTUREPROOFING5`, …) work as well.
+fn parse_apple_csv(raw: &str) -> Result<(Vec<Clipping>, usize), String> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(raw.as_bytes());
+    let headers: HashMap<String, usize> = reader
+        .headers()
+        .map_err(|e| tr!("Failed to parse: {}", e))?
+        .iter()
+        .enumerate()
+        .map(|(i, h)| (h.trim().to_lowercase(), i))
+        .collect();
+    let column = |names: &[&str]| names.iter().find_map(|n| headers.get(*n).copied());
+    let (Some(title), Some(text)) = (column(&["title", "book", "ztitle"]), column(&["highlight", "text", "zannotationselectedtext"])) else {
+        return Err(tr!("Could not find title and highlight columns in CSV"));
+    };
+    let author = column(&["author", "authors", "zauthor"]);
+    let location = column(&["location", "chapter", "zfutureproofing5"]);
+    let date = column(&["date", "created", "zannotationcreationdate"]);
+
+    let mut clippings = Vec::new();
+    let mut skipped = 0;
+    for record in reader.records() {
+        let Ok(record) = record else {
+            skipped += 1;
+            continue;
+        };
+        let field = |i: Option<usize>| i.and_then(|i| record.get(i)).map(str::trim).filter(|s| !s.is_empty());
+        let (Some(title), Some(text)) = (field(Some(title)), field(Some(text))) else {
+            skipped += 1;
+            continue;
+        };
+        clippings.push(Clipping {
+            title: title.to_string(),
+            authors: field(author).map(|a| a.split([';', '&']).map(|a| a.trim().to_string()).filter(|a| !a.is_empty()).collect()).unwrap_or_default(),
+            text: text.to_string(),
+            page: None,
+            location: field(location).map(str::to_string),
+            date: field(date).and_then(csv_date),
+        });
+    }
+    Ok((clippings, skipped))
+}
+
+/// Core Data seconds (`732197122.5`) or anything starting with `YYYY-MM-DD`
+fn csv_date(raw: &str) -> Option<String> {
+    if let Ok(seconds) = raw.parse::<f64>() {
+        let at = Local.timestamp_opt(CORE_DATA_EPOCH, 0).single()? + Duration::seconds(seconds as i64);
+        return Some(at.format("%Y-%m-%d").to_string());
+    }
+    let day = raw.get(..10)?;
+    NaiveDate::parse_from_str(day, "%Y-%m-%d").ok().map(|_| day.to_string())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_kindle() {
+        let raw = "\u{feff}Atomic Habits: Tiny Changes (James Clear)\r\n- Your Highlight on page 12 | Location 170-172 | Added on Friday, March 15, 2024 2:05:22 PM\r\n\r\nYou do not rise to the level of your goals.\r\n==========\r\nAtomic Habits: Tiny Changes (James Clear)\r\n- Your Bookmark on page 14 | Location 200 | Added on Friday, March 15, 2024 2:06:00 PM\r\n\r\n\r\n==========\r\n活着 (余华)\r\n- 您在第 8 页（位置 #113-114）的标注 | 添加于 2023年11月2日星期四 下午9:10:11\r\n\r\n人是为活着本身而活着的\r\n==========\r\n";
+        let (clippings, skipped) = parse("My Clippings.txt", raw).unwrap();
+        assert_eq!(skipped, 1);
+        assert_eq!(
+            clippings[0],
+            Clipping {
+                title: "Atomic Habits: Tiny Changes".into(),
+                authors: vec!["James Clear".into()],
+                text: "You do not rise to the level of your goals.".into(),
+                page: Some(12),
+                location: Some("170-172".into()),
+                date: Some("2024-03-15".into()),
+            }
+        );
+        assert_eq!((clippings[1].title.as_str(), clippings[1].page), ("活着", Some(8)));
+        assert_eq!((clippings[1].location.as_deref(), clippings[1].date.as_deref()), (Some("113-114"), Some("2023-11-02")));
+    }
+
+    #[test]
+    fn test_parse_apple_books() {
+        let raw = "“You do not rise to the level of your goals.”\n\nExcerpt From\nAtomic Habits\nJames Clear\nThis material may be protected by copyright.\n\n“Habits are the compound interest\nof self-improvement.”\n\nExcerpt From\nAtomic Habits\nJames Clear\nhttps://books.apple.com/us/book/id1\nThis material may be protected by copyright.\n";
+        let (clippings, _) = parse("notes.txt", raw).unwrap();
+        assert_eq!(clippings.len(), 2);
+        assert_eq!(clippings[1].text, "Habits are the compound interest\nof self-improvement.");
+        assert_eq!((clippings[1].title.as_str(), clippings[1].authors.clone()), ("Atomic Habits", vec!["James Clear".to_string()]));
+
+        let csv = "ZTITLE,ZAUTHOR,ZANNOTATIONSELECTEDTEXT,ZFUTUREPROOFING5,ZANNOTATIONCREATIONDATE\nAtomic Habits,James Clear,Small habits,Chapter 1,2024-03-15 10:00:00\nAtomic Habits,James Clear,,Chapter 2,\n";
+        let (clippings, skipped) = parse("annotations.csv", csv).unwrap();
+        assert_eq!((clippings.len(), skipped), (1, 1));
+        assert_eq!((clippings[0].location.as_deref(), clippings[0].date.as_deref()), (Some("Chapter 1"), Some("2024-03-15")));
+        assert!(parse("notes.txt", "nothing here").is_err());
+    }
+}
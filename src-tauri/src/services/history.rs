@@ -0,0 +1,213 @@
+//! Past versions of a note and line diffs between them.
+//!
+//! Versions come from git when the vault is a repository, plus standalone
+//! snapshots in .lifeos/history/<hash of the note path>/<stamp>.md for vaults
+//! without git. Version ids are `git:<sha>`, `snapshot:<stamp>` or `current`
+//! (the file as it is on disk).
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use similar::{ChangeTag, TextDiff};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::durable;
+
+const HISTORY_DIR: &str = ".lifeos/history";
+const STAMP_FORMAT: &str = "%Y%m%dT%H%M%S";
+pub const CURRENT: &str = "current";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NoteVersion {
+    pub id: String,
+    /// RFC 3339
+    pub timestamp: String,
+    /// Commit subject for git versions
+    #[serde(default)]
+    pub message: Option<String>,
+    /// "git" | "snapshot"
+    pub source: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DiffLine {
+    /// "equal" | "insert" | "delete"
+    pub kind: String,
+    /// 1-based, in the `from` version
+    pub old_line: Option<usize>,
+    /// 1-based, in the `to` version
+    pub new_line: Option<usize>,
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct NoteDiff {
+    pub from: String,
+    pub to: String,
+    pub added: usize,
+    pub removed: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+/// All known versions of `rel`, newest first
+pub fn versions(vault: &Path, rel: &str) -> Vec<NoteVersion> {
+    let mut all = git_versions(vault, rel);
+    all.extend(snapshot_versions(vault, rel));
+    all.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    all
+}
+
+/// Text of `rel` at version `id`; a note that did not exist reads as empty
+pub fn content_at(vault: &Path, rel: &str, id: &str) -> Result<String, String> {
+    if id == CURRENT {
+        return Ok(fs::read_to_string(vault.join(rel)).unwrap_or_default());
+    }
+    if let Some(sha) = id.strip_prefix("git:") {
+        if sha.is_empty() || !sha.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(tr!("Unknown version: {}", id));
+        }
+        return git(vault, &["show", &format!("{sha}:./{rel}")]).ok_or_else(|| tr!("Unknown version: {}", id));
+    }
+    if let Some(stamp) = id.strip_prefix("snapshot:") {
+        if NaiveDateTime::parse_from_str(stamp, STAMP_FORMAT).is_ok() {
+            if let Ok(raw) = fs::read_to_string(snapshot_dir(vault, rel).join(format!("{stamp}.md"))) {
+                return Ok(raw);
+            }
+        }
+    }
+    Err(tr!("Unknown version: {}", id))
+}
+
+/// Save the current text as a snapshot, unless it equals the latest one.
+/// Returns the new version.
+pub fn snapshot(vault: &Path, rel: &str) -> Result<Option<NoteVersion>, String> {
+    let current = fs::read_to_string(vault.join(rel)).map_err(|e| tr!("Failed to read: {}", e))?;
+    if let Some(latest) = snapshot_versions(vault, rel).first() {
+        if content_at(vault, rel, &latest.id)? == current {
+            return Ok(None);
+        }
+    }
+    let dir = snapshot_dir(vault, rel);
+    fs::create_dir_all(&dir).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    let now = Local::now();
+    let stamp = now.format(STAMP_FORMAT).to_string();
+    durable::write(dir.join(format!("{stamp}.md")), current).map_err(|e| tr!("write_file failed: {}", e))?;
+    // Remember which note the hashed directory belongs to
+    durable::write(dir.join("path.txt"), rel).map_err(|e| tr!("write_file failed: {}", e))?;
+    Ok(Some(NoteVersion { id: format!("snapshot:{stamp}"), timestamp: now.to_rfc3339(), message: None, source: "snapshot".into() }))
+}
+
+/// Line diff from `old` to `new`
+pub fn diff(old: &str, new: &str) -> NoteDiff {
+    let text_diff = TextDiff::from_lines(old, new);
+    let mut result = NoteDiff::default();
+    for change in text_diff.iter_all_changes() {
+        let kind = match change.tag() {
+            ChangeTag::Equal => "equal",
+            ChangeTag::Insert => {
+                result.added += 1;
+                "insert"
+            }
+            ChangeTag::Delete => {
+                result.removed += 1;
+                "delete"
+            }
+        };
+        result.lines.push(DiffLine {
+            kind: kind.to_string(),
+            old_line: change.old_index().map(|i| i + 1),
+            new_line: change.new_index().map(|i| i + 1),
+            text: change.value().trim_end_matches(['\n', '\r']).to_string(),
+        });
+    }
+    result
+}
+
+fn git_versions(vault: &Path, rel: &str) -> Vec<NoteVersion> {
+    let Some(log) = git(vault, &["log", "--follow", "--format=%H%x09%cI%x09%s", "--", rel]) else {
+        return vec![];
+    };
+    log.lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '\t');
+            let (sha, timestamp) = (parts.next()?, parts.next()?);
+            Some(NoteVersion {
+                id: format!("git:{sha}"),
+                timestamp: timestamp.to_string(),
+                message: parts.next().map(str::to_string),
+                source: "git".into(),
+            })
+        })
+        .collect()
+}
+
+fn snapshot_versions(vault: &Path, rel: &str) -> Vec<NoteVersion> {
+    let Ok(entries) = fs::read_dir(snapshot_dir(vault, rel)) else { return vec![] };
+    let mut all: Vec<NoteVersion> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let stamp = name.strip_suffix(".md")?;
+            let at = NaiveDateTime::parse_from_str(stamp, STAMP_FORMAT).ok()?;
+            let at: DateTime<Local> = Local.from_local_datetime(&at).earliest()?;
+            Some(NoteVersion { id: format!("snapshot:{stamp}"), timestamp: at.to_rfc3339(), message: None, source: "snapshot".into() })
+        })
+        .collect();
+    all.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    all
+}
+
+/// Keyed by SHA-256 of the path, which unlike `DefaultHasher` is the same
+/// in every build, so snapshots stay findable after an upgrade
+fn snapshot_dir(vault: &Path, rel: &str) -> PathBuf {
+    let key: String = Sha256::digest(rel.as_bytes())[..8].iter().map(|b| format!("{b:02x}")).collect();
+    vault.join(HISTORY_DIR).join(key)
+}
+
+/// stdout of a successful git command run in the vault
+fn git(vault: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git").arg("-C").arg(vault).args(args).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let d = diff("a\nb\nc\n", "a\nc\nd\n");
+        assert_eq!((d.added, d.removed), (1, 1));
+        let kinds: Vec<(&str, Option<usize>, Option<usize>, &str)> =
+            d.lines.iter().map(|l| (l.kind.as_str(), l.old_line, l.new_line, l.text.as_str())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("equal", Some(1), Some(1), "a"),
+                ("delete", Some(2), None, "b"),
+                ("equal", Some(3), Some(2), "c"),
+                ("insert", None, Some(3), "d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_snapshots() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("note.md"), "v1\n").unwrap();
+        let first = snapshot(dir.path(), "note.md").unwrap().unwrap();
+        assert!(dir.path().join(HISTORY_DIR).join("1188c3be78790ea6").is_dir());
+        assert!(snapshot(dir.path(), "note.md").unwrap().is_none());
+        assert_eq!(content_at(dir.path(), "note.md", &first.id).unwrap(), "v1\n");
+        let ids: Vec<String> = versions(dir.path(), "note.md").into_iter().map(|v| v.id).collect();
+        assert_eq!(ids, vec![first.id]);
+        assert!(content_at(dir.path(), "note.md", "snapshot:../../x").is_err());
+        assert!(content_at(dir.path(), "note.md", "git:HEAD~1").is_err());
+    }
+}
@@ -0,0 +1,101 @@
+//! Small blocking HTTP client for webhooks and metadata lookups (ureq over
+//! the system TLS stack, same as IMAP/SMTP).
+
+use std::sync::Arc;
+use std::time::Duration;
+
+pub fn agent() -> Result<ureq::Agent, String> {
+    let tls = native_tls::TlsConnector::new().map_err(|e| tr!("Failed to create TLS connector: {}", e))?;
+    Ok(ureq::AgentBuilder::new()
+        .tls_connector(Arc::new(tls))
+        .timeout(Duration::from_secs(30))
+        .user_agent(concat!("LifeOS/", env!("CARGO_PKG_VERSION")))
+        .build())
+}
+
+/// GET `url` and parse the body as JSON
+pub fn get_json(url: &str) -> Result<serde_json::Value, String> {
+    call_json(agent()?.get(url))
+}
+
+/// GET `url` and return the body as text
+pub fn get_text(url: &str) -> Result<String, String> {
+    agent()?
+        .get(url)
+        .call()
+        .map_err(|e| tr!("Request failed: {}", e))?
+        .into_string()
+        .map_err(|e| tr!("Request failed: {}", e))
+}
+
+/// Send a prepared request (query, headers) and parse the body as JSON
+pub fn call_json(request: ureq::Request) -> Result<serde_json::Value, String> {
+    let body = request
+        .call()
+        .map_err(|e| tr!("Request failed: {}", e))?
+        .into_string()
+        .map_err(|e| tr!("Request failed: {}", e))?;
+    serde_json::from_str(&body).map_err(|e| tr!("Failed to parse: {}", e))
+}
+
+/// Send `body` as JSON with a prepared request (POST, headers) and parse the
+/// response; an empty response body is `null`
+pub fn send_json(request: ureq::Request, body: &serde_json::Value) -> Result<serde_json::Value, String> {
+    let text = request
+        .set("Content-Type", "application/json")
+        .send_string(&body.to_string())
+        .map_err(|e| tr!("Request failed: {}", e))?
+        .into_string()
+        .map_err(|e| tr!("Request failed: {}", e))?;
+    if text.trim().is_empty() {
+        return Ok(serde_json::Value::Null);
+    }
+    serde_json::from_str(&text).map_err(|e| tr!("Failed to parse: {}", e))
+}
+
+/// Percent-encode a query value
+pub fn encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// `%XX` escapes undone; malformed ones are kept as written
+pub fn decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok()).and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                out.push(b);
+                i += 3;
+            }
+            (b, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        assert_eq!(encode("上海 sh"), "%E4%B8%8A%E6%B5%B7%20sh");
+        assert_eq!(decode("%E4%B8%8A%E6%B5%B7%20sh"), "上海 sh");
+        assert_eq!(decode("100%zz"), "100%zz");
+    }
+}
@@ -0,0 +1,267 @@
+//! Read-only access to the Messages app's `~/Library/Messages/chat.db`
+//! through the system `sqlite3` (opened with `-readonly`). The database is
+//! behind Full Disk Access; without it reads fail and `access` says "denied".
+
+use chrono::{DateTime, Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Command;
+
+/// Relative to the home directory
+pub const CHAT_DB: &str = "Library/Messages/chat.db";
+
+/// 2001-01-01T00:00:00Z, where Messages timestamps count from
+const APPLE_EPOCH: i64 = 978_307_200;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Conversation {
+    /// chat.ROWID
+    pub chat_id: i64,
+    pub guid: String,
+    /// Group name; empty for one-to-one chats
+    pub name: String,
+    /// Phone numbers and emails of the other participants
+    pub handles: Vec<String>,
+    pub messages: usize,
+    pub last_at: Option<NaiveDateTime>,
+    pub last_text: String,
+    pub last_from_me: bool,
+    /// Slugs of the people the handles belong to; filled in by the caller
+    #[serde(default)]
+    pub people: Vec<String>,
+    /// Latest messages, oldest first; filled in by the caller
+    #[serde(default)]
+    pub recent: Vec<Message>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Message {
+    /// Local time
+    pub at: NaiveDateTime,
+    pub from_me: bool,
+    /// Sender's phone number or email; empty for messages from me
+    pub handle: String,
+    pub text: String,
+}
+
+/// "granted" | "denied" (no Full Disk Access) | "missing" (no Messages database)
+pub fn access(db: &Path) -> &'static str {
+    match fs::File::open(db) {
+        Ok(_) => "granted",
+        Err(e) if e.kind() == ErrorKind::NotFound => "missing",
+        Err(_) => "denied",
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Queries
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Chats with at least one message, most recently active first. SQLite takes
+/// the bare `m.` columns from the row MAX(m.date) picked.
+pub fn conversations(db: &Path) -> Result<Vec<Conversation>, String> {
+    let sql = "SELECT c.ROWID AS chat_id, c.guid, c.display_name AS name, \
+        (SELECT group_concat(h.id, char(31)) FROM chat_handle_join ch JOIN handle h ON h.ROWID = ch.handle_id WHERE ch.chat_id = c.ROWID) AS handles, \
+        COUNT(m.ROWID) AS messages, MAX(m.date) AS date, m.text, hex(m.attributedBody) AS body, m.is_from_me AS from_me \
+        FROM chat c JOIN chat_message_join cm ON cm.chat_id = c.ROWID JOIN message m ON m.ROWID = cm.message_id \
+        GROUP BY c.ROWID ORDER BY date DESC";
+    Ok(query(db, sql)?.iter().map(conversation_from_row).collect())
+}
+
+/// The chat's latest `limit` messages (all when None), oldest first.
+/// Tapbacks and other reactions are left out.
+pub fn messages(db: &Path, chat_id: i64, limit: Option<usize>) -> Result<Vec<Message>, String> {
+    let limit = limit.map_or(-1, |n| n as i64);
+    let sql = format!(
+        "SELECT m.date, m.is_from_me AS from_me, m.text, hex(m.attributedBody) AS body, m.cache_has_attachments AS attachments, h.id AS handle \
+        FROM chat_message_join cm JOIN message m ON m.ROWID = cm.message_id LEFT JOIN handle h ON h.ROWID = m.handle_id \
+        WHERE cm.chat_id = {chat_id} AND IFNULL(m.associated_message_type, 0) = 0 ORDER BY m.date DESC LIMIT {limit}"
+    );
+    let mut messages: Vec<Message> = query(db, &sql)?.iter().filter_map(message_from_row).collect();
+    messages.reverse();
+    Ok(messages)
+}
+
+fn query(db: &Path, sql: &str) -> Result<Vec<Map<String, Value>>, String> {
+    let output = Command::new("sqlite3")
+        .arg("-readonly")
+        .arg("-json")
+        .arg(db)
+        .arg(sql)
+        .output()
+        .map_err(|e| tr!("Failed to run '{}': {}", "sqlite3", e))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        let lower = stderr.to_lowercase();
+        if lower.contains("authorization denied") || lower.contains("not permitted") || lower.contains("unable to open") {
+            return Err(tr!("Full Disk Access is required to read Messages"));
+        }
+        return Err(tr!("Failed to read Messages: {}", stderr.trim()));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Zero rows print nothing rather than `[]`
+    if stdout.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(&stdout).map_err(|e| tr!("Failed to read Messages: {}", e))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Rows
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn conversation_from_row(row: &Map<String, Value>) -> Conversation {
+    let handles = string(row, "handles");
+    Conversation {
+        chat_id: row.get("chat_id").and_then(Value::as_i64).unwrap_or_default(),
+        guid: string(row, "guid"),
+        name: string(row, "name"),
+        handles: handles.split('\u{1f}').filter(|h| !h.is_empty()).map(str::to_string).collect(),
+        messages: row.get("messages").and_then(Value::as_u64).unwrap_or_default() as usize,
+        last_at: row.get("date").and_then(Value::as_i64).and_then(local_time),
+        last_text: text_of(row).unwrap_or_default(),
+        last_from_me: row.get("from_me").and_then(Value::as_i64) == Some(1),
+        ..Default::default()
+    }
+}
+
+/// None for messages with neither text nor attachments
+fn message_from_row(row: &Map<String, Value>) -> Option<Message> {
+    let attachments = row.get("attachments").and_then(Value::as_i64).unwrap_or(0) != 0;
+    let text = text_of(row).or_else(|| attachments.then(|| tr!("[Attachment]")))?;
+    Some(Message {
+        at: row.get("date").and_then(Value::as_i64).and_then(local_time)?,
+        from_me: row.get("from_me").and_then(Value::as_i64) == Some(1),
+        handle: string(row, "handle"),
+        text,
+    })
+}
+
+fn string(row: &Map<String, Value>, key: &str) -> String {
+    row.get(key).and_then(Value::as_str).unwrap_or_default().to_string()
+}
+
+/// `text`, or on newer macOS versions (which often leave it NULL) the string
+/// inside the archived `attributedBody`
+fn text_of(row: &Map<String, Value>) -> Option<String> {
+    let text = row.get("text").and_then(Value::as_str).map(str::to_string);
+    let body = || row.get("body").and_then(Value::as_str).and_then(hex).and_then(|b| attributed_text(&b));
+    // U+FFFC stands in for inline attachments
+    text.or_else(body).map(|t| t.replace('\u{fffc}', "").trim().to_string()).filter(|t| !t.is_empty())
+}
+
+/// Unix seconds of a Messages timestamp: seconds since 2001 before
+/// High Sierra, nanoseconds since
+fn unix_seconds(raw: i64) -> i64 {
+    let secs = if raw.abs() > 1_000_000_000_000 { raw / 1_000_000_000 } else { raw };
+    secs + APPLE_EPOCH
+}
+
+fn local_time(raw: i64) -> Option<NaiveDateTime> {
+    (raw != 0).then(|| DateTime::from_timestamp(unix_seconds(raw), 0)).flatten().map(|t| t.with_timezone(&Local).naive_local())
+}
+
+/// The NSString in a typedstream-archived NSAttributedString: after the
+/// class name comes a `+` marker and the UTF-8 length (one byte, or 0x81
+/// followed by two little-endian bytes)
+fn attributed_text(body: &[u8]) -> Option<String> {
+    let class = body.windows(8).position(|w| w == b"NSString")?;
+    let rest = &body[class + 8..];
+    let marker = rest.iter().position(|b| *b == b'+')?;
+    let rest = &rest[marker + 1..];
+    let (len, start) = match *rest.first()? {
+        0x81 => (u16::from_le_bytes([*rest.get(1)?, *rest.get(2)?]) as usize, 3),
+        0x82 => (u32::from_le_bytes([*rest.get(1)?, *rest.get(2)?, *rest.get(3)?, *rest.get(4)?]) as usize, 5),
+        n => (n as usize, 1),
+    };
+    rest.get(start..start + len).map(|b| String::from_utf8_lossy(b).to_string())
+}
+
+fn hex(s: &str) -> Option<Vec<u8>> {
+    s.len().is_multiple_of(2).then_some(())?;
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok()).collect()
+}
+
+/// The same phone number or email, however it was written: emails compare
+/// case-insensitively, numbers on their last 7–10 digits so `+86 138 0013 8000`
+/// matches `13800138000`
+pub fn same_handle(a: &str, b: &str) -> bool {
+    if a.contains('@') || b.contains('@') {
+        return a.trim().eq_ignore_ascii_case(b.trim());
+    }
+    let digits = |s: &str| s.chars().filter(char::is_ascii_digit).collect::<String>();
+    let (a, b) = (digits(a), digits(b));
+    let n = a.len().min(b.len()).min(10);
+    n >= 7 && a[a.len() - n..] == b[b.len() - n..]
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(json: &str) -> Map<String, Value> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_timestamps_and_handles() {
+        // 2024-03-15T14:05:22Z, in seconds (old databases) and nanoseconds
+        assert_eq!(unix_seconds(732_204_322), 1_710_511_522);
+        assert_eq!(unix_seconds(732_204_322_123_456_789), 1_710_511_522);
+        assert!(local_time(0).is_none());
+
+        assert!(same_handle("+86 138 0013 8000", "13800138000"));
+        assert!(same_handle("(415) 555-1234", "+14155551234"));
+        assert!(!same_handle("+14155551234", "+14155559999"));
+        assert!(!same_handle("123", "123"));
+        assert!(same_handle("Alice@Example.com", " alice@example.com"));
+        assert!(!same_handle("alice@example.com", "+14155551234"));
+    }
+
+    #[test]
+    fn test_rows() {
+        // Typedstream excerpt: …NSString, type bytes, `+`, length 5, "Hi 👋"… with 0x81 for longer text
+        let mut body = b"\x04\x0bstreamtyped\x81\xe8\x03\x84\x01@\x84\x84\x84\x12NSAttributedString\x00\x84\x84\x08NSObject\x00\x85\x92\x84\x84\x84\x08NSString\x01\x94\x84\x01+".to_vec();
+        let text = "Hi 👋";
+        body.push(text.len() as u8);
+        body.extend_from_slice(text.as_bytes());
+        body.extend_from_slice(b"\x86\x84\x02iI");
+        let encoded: String = body.iter().map(|b| format!("{b:02X}")).collect();
+        assert_eq!(hex(&encoded).unwrap(), body);
+        assert_eq!(attributed_text(&body).as_deref(), Some(text));
+
+        let long = "x".repeat(300);
+        let mut body = b"NSString\x01\x94\x84\x01+\x81".to_vec();
+        body.extend_from_slice(&300u16.to_le_bytes());
+        body.extend_from_slice(long.as_bytes());
+        assert_eq!(attributed_text(&body), Some(long));
+
+        let json = format!(r#"{{"date":732204322000000000,"from_me":0,"text":null,"body":"{encoded}","attachments":0,"handle":"+8613800138000"}}"#);
+        let message = message_from_row(&row(&json)).unwrap();
+        assert_eq!((message.text.as_str(), message.handle.as_str(), message.from_me), (text, "+8613800138000", false));
+
+        let reaction = row(r#"{"date":732204322,"from_me":1,"text":"￼","body":null,"attachments":0,"handle":null}"#);
+        assert!(message_from_row(&reaction).is_none());
+        let photo = row(r#"{"date":732204322,"from_me":1,"text":"￼","body":null,"attachments":1,"handle":null}"#);
+        assert!(message_from_row(&photo).unwrap().from_me);
+
+        let chat = conversation_from_row(&row(
+            r#"{"chat_id":7,"guid":"iMessage;+;chat1","name":"Family","handles":"+14155551234\u001falice@example.com","messages":42,"date":732204322,"text":"See you","body":null,"from_me":1}"#,
+        ));
+        assert_eq!((chat.chat_id, chat.name.as_str(), chat.messages), (7, "Family", 42));
+        assert_eq!(chat.handles, vec!["+14155551234", "alice@example.com"]);
+        assert_eq!((chat.last_text.as_str(), chat.last_from_me), ("See you", true));
+        assert!(chat.last_at.is_some());
+    }
+}
@@ -0,0 +1,212 @@
+//! Undo journal for destructive file operations.
+//!
+//! Deleting inside the vault moves the file (or folder) to
+//! .lifeos/trash/<operation id>/ instead of removing it, and every delete or
+//! move is appended to .lifeos/journal.json with the paths needed to reverse
+//! it. Only the newest `MAX_OPERATIONS` are kept; older trash is emptied as
+//! they fall off.
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::audit;
+use super::durable;
+
+const JOURNAL_FILE: &str = ".lifeos/journal.json";
+const TRASH_DIR: &str = ".lifeos/trash";
+const MAX_OPERATIONS: usize = 50;
+
+/// Journal reads and writes don't interleave
+static LOCK: Mutex<()> = Mutex::new(());
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Operation {
+    pub id: String,
+    /// "delete" | "move"
+    pub kind: String,
+    /// RFC 3339
+    pub timestamp: String,
+    /// Vault-relative: the deleted path, or where a move started
+    pub path: String,
+    /// Vault-relative move destination
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dest: Option<String>,
+    /// Vault-relative trash copy of a deleted path
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trash: Option<String>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Move `path` (inside `vault`) to the trash and journal it
+pub fn delete(vault: &Path, path: &Path) -> Result<Operation, String> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let rel = relative(vault, path)?;
+    let id = new_id();
+    let trash = format!("{TRASH_DIR}/{id}/{}", file_name(path));
+    let dest = vault.join(&trash);
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    fs::rename(path, &dest).map_err(|e| e.to_string())?;
+    audit::removed(&vault.join(&rel));
+    let op = Operation { id, kind: "delete".into(), timestamp: Local::now().to_rfc3339(), path: rel, dest: None, trash: Some(trash) };
+    append(vault, op.clone())?;
+    Ok(op)
+}
+
+/// Journal a move that has already happened
+pub fn record_move(vault: &Path, src: &Path, dest: &Path) -> Result<Operation, String> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let op = Operation {
+        id: new_id(),
+        kind: "move".into(),
+        timestamp: Local::now().to_rfc3339(),
+        path: relative(vault, src)?,
+        dest: Some(relative(vault, dest)?),
+        trash: None,
+    };
+    audit::moved(&vault.join(&op.path), &vault.join(dest));
+    append(vault, op.clone())?;
+    Ok(op)
+}
+
+/// Newest first
+pub fn recent(vault: &Path, limit: usize) -> Vec<Operation> {
+    load(vault).into_iter().rev().take(limit).collect()
+}
+
+/// Reverse the newest operation and drop it from the journal. Nothing is
+/// overwritten: if the original path has been reused, the undo fails.
+pub fn undo_last(vault: &Path) -> Result<Option<Operation>, String> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut ops = load(vault);
+    let Some(op) = ops.last().cloned() else { return Ok(None) };
+    let (from, to) = match (op.kind.as_str(), &op.trash, &op.dest) {
+        ("delete", Some(trash), _) => (vault.join(trash), vault.join(&op.path)),
+        ("move", _, Some(dest)) => (vault.join(dest), vault.join(&op.path)),
+        _ => return Err(tr!("Cannot undo: {}", op.kind)),
+    };
+    if !from.exists() {
+        return Err(tr!("Cannot undo, file is gone: {}", from.display()));
+    }
+    if to.exists() {
+        return Err(tr!("Cannot undo, path is in use: {}", to.display()));
+    }
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    fs::rename(&from, &to).map_err(|e| e.to_string())?;
+    audit::moved(&from, &to);
+    if op.kind == "delete" {
+        let _ = fs::remove_dir_all(vault.join(TRASH_DIR).join(&op.id));
+    }
+    ops.pop();
+    save(vault, &ops)?;
+    Ok(Some(op))
+}
+
+/// Drop an operation that has been reversed some other way, emptying its trash
+pub fn forget(vault: &Path, id: &str) -> Result<(), String> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut ops = load(vault);
+    ops.retain(|op| op.id != id);
+    let _ = fs::remove_dir_all(vault.join(TRASH_DIR).join(id));
+    save(vault, &ops)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn append(vault: &Path, op: Operation) -> Result<(), String> {
+    let mut ops = load(vault);
+    ops.push(op);
+    let excess = ops.len().saturating_sub(MAX_OPERATIONS);
+    for old in ops.drain(..excess) {
+        let _ = fs::remove_dir_all(vault.join(TRASH_DIR).join(&old.id));
+    }
+    save(vault, &ops)
+}
+
+fn load(vault: &Path) -> Vec<Operation> {
+    fs::read_to_string(vault.join(JOURNAL_FILE)).ok().and_then(|raw| serde_json::from_str(&raw).ok()).unwrap_or_default()
+}
+
+fn save(vault: &Path, ops: &[Operation]) -> Result<(), String> {
+    let path = vault.join(JOURNAL_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(ops).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(path, json).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+fn relative(vault: &Path, path: &Path) -> Result<String, String> {
+    let path: PathBuf = if path.is_absolute() { path.to_path_buf() } else { vault.join(path) };
+    path.strip_prefix(vault)
+        .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+        .map_err(|_| tr!("Path is outside the vault: {}", path.display()))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "item".into())
+}
+
+/// Sortable and unique enough for one user's operations
+fn new_id() -> String {
+    format!("{}-{}", Local::now().format("%Y%m%dT%H%M%S%3f"), &uuid::Uuid::new_v4().simple().to_string()[..6])
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delete_move_and_undo() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path();
+        fs::create_dir_all(v.join("inbox/old")).unwrap();
+        fs::write(v.join("inbox/idea.md"), "idea").unwrap();
+        fs::write(v.join("inbox/old/a.md"), "a").unwrap();
+
+        delete(v, &v.join("inbox/idea.md")).unwrap();
+        delete(v, &v.join("inbox/old")).unwrap();
+        fs::create_dir_all(v.join("archive")).unwrap();
+        fs::write(v.join("inbox/b.md"), "b").unwrap();
+        fs::rename(v.join("inbox/b.md"), v.join("archive/b.md")).unwrap();
+        record_move(v, &v.join("inbox/b.md"), &v.join("archive/b.md")).unwrap();
+        assert!(!v.join("inbox/idea.md").exists() && !v.join("inbox/old").exists());
+
+        let kinds: Vec<(String, String)> = recent(v, 10).into_iter().map(|o| (o.kind, o.path)).collect();
+        assert_eq!(kinds, [("move".into(), "inbox/b.md".into()), ("delete".into(), "inbox/old".into()), ("delete".into(), "inbox/idea.md".into())]);
+
+        assert_eq!(undo_last(v).unwrap().unwrap().kind, "move");
+        assert_eq!(fs::read_to_string(v.join("inbox/b.md")).unwrap(), "b");
+        undo_last(v).unwrap();
+        assert_eq!(fs::read_to_string(v.join("inbox/old/a.md")).unwrap(), "a");
+
+        // A new file at the old path blocks the undo instead of being overwritten
+        fs::write(v.join("inbox/idea.md"), "new").unwrap();
+        assert!(undo_last(v).is_err());
+        fs::remove_file(v.join("inbox/idea.md")).unwrap();
+        undo_last(v).unwrap();
+        assert_eq!(fs::read_to_string(v.join("inbox/idea.md")).unwrap(), "idea");
+        assert!(undo_last(v).unwrap().is_none());
+        assert_eq!(fs::read_dir(v.join(TRASH_DIR)).unwrap().count(), 0);
+        assert!(delete(v, Path::new("/elsewhere/x.md")).is_err());
+    }
+}
@@ -0,0 +1,131 @@
+//! Chinese lunisolar calendar → Gregorian conversion for 1900–2100, enough to
+//! find the solar date of a lunar birthday (农历生日) in a given year.
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+pub const MIN_YEAR: i32 = 1900;
+pub const MAX_YEAR: i32 = 2100;
+
+/// One entry per lunar year from 1900. Bits 0-3: leap month (0 = none);
+/// bits 4-15: months 12..1, set = 30 days; bit 16: leap month has 30 days.
+const LUNAR_INFO: [u32; 201] = [
+    0x04bd8, 0x04ae0, 0x0a570, 0x054d5, 0x0d260, 0x0d950, 0x16554, 0x056a0, 0x09ad0, 0x055d2, // 1900
+    0x04ae0, 0x0a5b6, 0x0a4d0, 0x0d250, 0x1d255, 0x0b540, 0x0d6a0, 0x0ada2, 0x095b0, 0x14977, // 1910
+    0x04970, 0x0a4b0, 0x0b4b5, 0x06a50, 0x06d40, 0x1ab54, 0x02b60, 0x09570, 0x052f2, 0x04970, // 1920
+    0x06566, 0x0d4a0, 0x0ea50, 0x16a95, 0x05ad0, 0x02b60, 0x186e3, 0x092e0, 0x1c8d7, 0x0c950, // 1930
+    0x0d4a0, 0x1d8a6, 0x0b550, 0x056a0, 0x1a5b4, 0x025d0, 0x092d0, 0x0d2b2, 0x0a950, 0x0b557, // 1940
+    0x06ca0, 0x0b550, 0x15355, 0x04da0, 0x0a5b0, 0x14573, 0x052b0, 0x0a9a8, 0x0e950, 0x06aa0, // 1950
+    0x0aea6, 0x0ab50, 0x04b60, 0x0aae4, 0x0a570, 0x05260, 0x0f263, 0x0d950, 0x05b57, 0x056a0, // 1960
+    0x096d0, 0x04dd5, 0x04ad0, 0x0a4d0, 0x0d4d4, 0x0d250, 0x0d558, 0x0b540, 0x0b6a0, 0x195a6, // 1970
+    0x095b0, 0x049b0, 0x0a974, 0x0a4b0, 0x0b27a, 0x06a50, 0x06d40, 0x0af46, 0x0ab60, 0x09570, // 1980
+    0x04af5, 0x04970, 0x064b0, 0x074a3, 0x0ea50, 0x06b58, 0x05ac0, 0x0ab60, 0x096d5, 0x092e0, // 1990
+    0x0c960, 0x0d954, 0x0d4a0, 0x0da50, 0x07552, 0x056a0, 0x0abb7, 0x025d0, 0x092d0, 0x0cab5, // 2000
+    0x0a950, 0x0b4a0, 0x0baa4, 0x0ad50, 0x055d9, 0x04ba0, 0x0a5b0, 0x15176, 0x052b0, 0x0a930, // 2010
+    0x07954, 0x06aa0, 0x0ad50, 0x05b52, 0x04b60, 0x0a6e6, 0x0a4e0, 0x0d260, 0x0ea65, 0x0d530, // 2020
+    0x05aa0, 0x076a3, 0x096d0, 0x04afb, 0x04ad0, 0x0a4d0, 0x1d0b6, 0x0d250, 0x0d520, 0x0dd45, // 2030
+    0x0b5a0, 0x056d0, 0x055b2, 0x049b0, 0x0a577, 0x0a4b0, 0x0aa50, 0x1b255, 0x06d20, 0x0ada0, // 2040
+    0x14b63, 0x09370, 0x049f8, 0x04970, 0x064b0, 0x168a6, 0x0ea50, 0x06b20, 0x1a6c4, 0x0aae0, // 2050
+    0x092e0, 0x0d2e3, 0x0c960, 0x0d557, 0x0d4a0, 0x0da50, 0x05d55, 0x056a0, 0x0a6d0, 0x055d4, // 2060
+    0x052d0, 0x0a9b8, 0x0a950, 0x0b4a0, 0x0b6a6, 0x0ad50, 0x055a0, 0x0aba4, 0x0a5b0, 0x052b0, // 2070
+    0x0b273, 0x06930, 0x07337, 0x06aa0, 0x0ad50, 0x14b55, 0x04b60, 0x0a570, 0x054e4, 0x0d160, // 2080
+    0x0e968, 0x0d520, 0x0daa0, 0x16aa6, 0x056d0, 0x04ae0, 0x0a9d4, 0x0a2d0, 0x0d150, 0x0f252, // 2090
+    0x0d520, // 2100
+];
+
+fn info(year: i32) -> u32 {
+    LUNAR_INFO[(year - MIN_YEAR) as usize]
+}
+
+/// 0 when the year has no leap month
+fn leap_month(year: i32) -> u32 {
+    info(year) & 0xf
+}
+
+fn leap_days(year: i32) -> i64 {
+    match leap_month(year) {
+        0 => 0,
+        _ if info(year) & 0x10000 != 0 => 30,
+        _ => 29,
+    }
+}
+
+fn month_days(year: i32, month: u32) -> i64 {
+    if info(year) & (0x10000 >> month) != 0 { 30 } else { 29 }
+}
+
+fn year_days(year: i32) -> i64 {
+    (1..=12).map(|m| month_days(year, m)).sum::<i64>() + leap_days(year)
+}
+
+/// Gregorian date of lunar `year`-`month`-`day` (the regular month, or the
+/// leap month when `leap` is set and the year has one). A day 30 in a
+/// 29-day month falls back to day 29, the usual convention for birthdays.
+pub fn to_solar(year: i32, month: u32, day: u32, leap: bool) -> Option<NaiveDate> {
+    if !(MIN_YEAR..=MAX_YEAR).contains(&year) || !(1..=12).contains(&month) || !(1..=30).contains(&day) {
+        return None;
+    }
+    let leap = leap && leap_month(year) == month;
+
+    let mut offset: i64 = (MIN_YEAR..year).map(year_days).sum();
+    for m in 1..month {
+        offset += month_days(year, m);
+        if leap_month(year) == m {
+            offset += leap_days(year);
+        }
+    }
+    let len = if leap {
+        offset += month_days(year, month);
+        leap_days(year)
+    } else {
+        month_days(year, month)
+    };
+    offset += i64::from(day.min(len as u32)) - 1;
+
+    // Lunar 1900-01-01
+    let epoch = NaiveDate::from_ymd_opt(1900, 1, 31)?;
+    Some(epoch + Duration::days(offset))
+}
+
+/// Lunar year containing `date` (so a mid-January birthday belongs to the
+/// previous lunar year)
+pub fn lunar_year_of(date: NaiveDate) -> Option<i32> {
+    let year = date.year();
+    let new_year = to_solar(year, 1, 1, false)?;
+    Some(if date < new_year { year - 1 } else { year })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_spring_festivals() {
+        assert_eq!(to_solar(1990, 1, 1, false), Some(ymd(1990, 1, 27)));
+        assert_eq!(to_solar(2000, 1, 1, false), Some(ymd(2000, 2, 5)));
+        assert_eq!(to_solar(2020, 1, 1, false), Some(ymd(2020, 1, 25)));
+        assert_eq!(to_solar(2024, 1, 1, false), Some(ymd(2024, 2, 10)));
+        assert_eq!(to_solar(2025, 1, 1, false), Some(ymd(2025, 1, 29)));
+    }
+
+    #[test]
+    fn test_mid_autumn_and_leap_months() {
+        assert_eq!(to_solar(2024, 8, 15, false), Some(ymd(2024, 9, 17)));
+        // 2025 has a leap 6th month, which pushes Mid-Autumn into October
+        assert_eq!(to_solar(2025, 8, 15, false), Some(ymd(2025, 10, 6)));
+        assert_eq!(to_solar(2023, 2, 1, true), Some(ymd(2023, 3, 22)));
+    }
+
+    #[test]
+    fn test_lunar_year_of() {
+        assert_eq!(lunar_year_of(ymd(2025, 1, 28)), Some(2024));
+        assert_eq!(lunar_year_of(ymd(2025, 1, 29)), Some(2025));
+    }
+}
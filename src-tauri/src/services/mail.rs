@@ -0,0 +1,2041 @@
+use imap::extensions::idle::SetReadTimeout;
+use mail_parser::{Message, MessageParser, MessagePart, MimeHeaders};
+use native_tls::TlsConnector;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io::{BufRead, BufReader, Cursor, Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+use super::mail_html::{self, Tracker};
+use super::{durable, gmail, mail_health};
+
+/// Where the Mail view stores one JSON file per account
+const ACCOUNTS_DIR: &str = ".lifeos/emails";
+/// Synced messages: Mailbox/<account>/index.json plus one .eml per message
+const MAILBOX_DIR: &str = "Mailbox";
+/// One per account, held for the whole of a folder sync
+static SYNC_LOCKS: Lazy<Mutex<HashMap<String, Arc<Mutex<()>>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+/// New messages by account directory, until `notify_new_mail` announces them
+static ARRIVED: Lazy<Mutex<HashMap<String, Vec<ArrivedMail>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+/// Sending identities by account id. Kept out of the account files, which
+/// the Mail view rewrites with only its own keys.
+const IDENTITIES_FILE: &str = ".lifeos/identities.yaml";
+/// Day of the last scheduled mail digest, so a restart doesn't write it twice
+pub const DIGEST_STATE_FILE: &str = ".lifeos/mail-digest.json";
+
+/// Account file as written by the Mail view. Ports arrive as strings or
+/// numbers depending on which form saved them, and `folders` is a
+/// comma-separated list. `syncMinutes` and `syncIntervals` (folder →
+/// minutes) override the background sync interval; 0 leaves it to manual sync.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountFile {
+    id: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    email: String,
+    #[serde(default)]
+    imap_host: String,
+    #[serde(default)]
+    imap_port: serde_json::Value,
+    protocol: Option<String>,
+    #[serde(default)]
+    password: String,
+    #[serde(default)]
+    folders: String,
+    enabled: Option<bool>,
+    sync_minutes: Option<u64>,
+    #[serde(default)]
+    sync_intervals: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MailAccount {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+    pub folders: Vec<String>,
+    pub enabled: bool,
+    pub imap: ImapAccount,
+    /// Background sync interval for the whole account, in minutes
+    pub sync_minutes: Option<u64>,
+    /// Per-folder intervals, winning over `sync_minutes`
+    pub sync_intervals: BTreeMap<String, u64>,
+}
+
+impl MailAccount {
+    /// Minutes between background syncs of `folder`: its own interval, else
+    /// the account's, else `default`. 0 = background sync off.
+    pub fn sync_interval(&self, folder: &str, default: u64) -> u64 {
+        self.sync_intervals
+            .iter()
+            .find(|(f, _)| f.eq_ignore_ascii_case(folder))
+            .map(|(_, m)| *m)
+            .or(self.sync_minutes)
+            .unwrap_or(default)
+    }
+}
+
+/// A synced message found by its Message-ID header
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedMessage {
+    pub message_id: String,
+    /// Mailbox/ directory holding it: `<account>`, or `<account>/<folder>`
+    /// for folders synced into their own directory
+    pub account: String,
+    /// Cache id, as taken by `get_email_content`
+    pub email_id: String,
+    pub subject: String,
+    pub from: String,
+    pub date: String,
+}
+
+/// One message cached more than once: under several accounts (the same
+/// newsletter sent to two addresses) or in several folders of one
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    pub message_id: String,
+    pub subject: String,
+    pub from: String,
+    /// By account: the copy that stays in view first, then the ones merged into it
+    pub copies: Vec<CachedMessage>,
+}
+
+/// A row of the unified inbox
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedEmail {
+    /// Mailbox/<account> directory name
+    pub account: String,
+    /// Other accounts whose copy of the message was merged into this row
+    #[serde(default)]
+    pub also_in: Vec<String>,
+    #[serde(flatten)]
+    pub email: EmailMessage,
+}
+
+/// An address an account can send as: an alias, a plus-address, a custom domain
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct EmailIdentity {
+    /// Derived from the address when saved blank
+    #[serde(default)]
+    pub id: String,
+    pub address: String,
+    #[serde(default)]
+    pub name: String,
+    /// Appended to the body below a `-- ` line
+    #[serde(default)]
+    pub signature: String,
+    /// Cache folder a copy of mail sent as this identity is filed under
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+    /// Sends that name no identity use this one
+    #[serde(default)]
+    pub default: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    pub account_id: String,
+    pub folder: String,
+    /// Mailbox/ directory the folder was cached in, as taken by
+    /// `get_cached_emails`: the account's, or `<account>/<folder>`
+    pub dir: String,
+    pub fetched: usize,
+    /// Fetched messages that weren't in the cache before
+    #[serde(default)]
+    pub new: usize,
+    pub error: Option<String>,
+}
+
+/// Read every account under {vault}/.lifeos/emails, skipping unreadable files
+pub fn load_accounts(vault_path: &str) -> Result<Vec<MailAccount>, String> {
+    let dir = PathBuf::from(vault_path).join(ACCOUNTS_DIR);
+    let Ok(entries) = fs::read_dir(&dir) else { return Ok(vec![]) };
+
+    let mut accounts = Vec::new();
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        let parsed = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<AccountFile>(&raw).ok());
+        let Some(file) = parsed else {
+            println!("[WARN] skipping unreadable account file: {}", path.display());
+            continue;
+        };
+        if file.imap_host.is_empty() {
+            continue;
+        }
+        accounts.push(into_account(file));
+    }
+    accounts.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(accounts)
+}
+
+/// Fetch the newest `limit` messages of every configured folder (INBOX when
+/// none are set; POP3 has only the one). Failures are reported per folder so
+/// one bad folder does not hide the others.
+pub fn sync_account(vault_path: &str, account: &MailAccount, limit: u32) -> Vec<SyncReport> {
+    synced_folders(account).into_iter().map(|folder| sync_folder(vault_path, account, &folder, limit)).collect()
+}
+
+/// Fetch the newest `limit` messages of one folder into the account's cache
+pub fn sync_folder(vault_path: &str, account: &MailAccount, folder: &str, limit: u32) -> SyncReport {
+    sync_folder_into(vault_path, account, folder, &account.id, limit)
+}
+
+/// `sync_folder` caching into Mailbox/`dir` instead, e.g. a `folder_dir`
+pub fn sync_folder_into(vault_path: &str, account: &MailAccount, folder: &str, dir: &str, limit: u32) -> SyncReport {
+    let lock = sync_lock(&account.id);
+    let _syncing = hold(&lock);
+    // Gmail API accounts sign in with Google instead
+    let gmail = account.imap.protocol.as_deref() == Some("gmail");
+    let cached: HashSet<String> = load_existing_emails(vault_path, dir).unwrap_or_default().into_iter().map(|m| m.id).collect();
+    let result = if account.imap.password.is_empty() && !gmail {
+        let error = tr!("No password saved for {}", account.email);
+        mail_health::record(vault_path, &account.id, folder, Duration::ZERO, Some(&error));
+        Err(error)
+    } else {
+        sync_mailbox_locked(&account.imap, vault_path, dir, folder, limit, 0)
+    };
+    SyncReport {
+        account_id: account.id.clone(),
+        folder: folder.to_string(),
+        dir: dir.to_string(),
+        fetched: result.as_ref().map_or(0, |m| m.len()),
+        new: result.as_ref().map_or(0, |m| m.iter().filter(|m| !cached.contains(&m.id)).count()),
+        error: result.err(),
+    }
+}
+
+/// Mailbox/ directory of a folder cached on its own: `<account>/<folder>`,
+/// a nested folder (`[Gmail]/Sent Mail`) as nested directories. Empty, `.`
+/// and `..` segments of the server's name are dropped.
+pub fn folder_dir(account_dir: &str, folder: &str) -> String {
+    let segments: Vec<String> = folder.split('/').filter(|s| !matches!(s.trim(), "" | "." | "..")).map(|s| s.replace('\\', "_")).collect();
+    if segments.is_empty() {
+        return account_dir.to_string();
+    }
+    format!("{account_dir}/{}", segments.join("/"))
+}
+
+/// The lock serializing syncs of an account. Manual sync, IDLE, auto sync
+/// and the background agent all fetch into the same index.json and
+/// sync_state.json, so two at once would drop each other's updates and
+/// announce the same arrivals twice.
+pub fn sync_lock(account_id: &str) -> Arc<Mutex<()>> {
+    SYNC_LOCKS.lock().unwrap().entry(account_id.to_string()).or_default().clone()
+}
+
+/// Wait for a `sync_lock`. A sync that panicked left nothing half-written
+/// (files are replaced whole), so a poisoned lock is taken as is.
+pub fn hold(lock: &Mutex<()>) -> MutexGuard<'_, ()> {
+    lock.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Folders `sync_account` pulls: the account's `folders`, else INBOX (POP3 has only the one)
+pub fn synced_folders(account: &MailAccount) -> Vec<String> {
+    let pop3 = account.imap.protocol.as_deref() == Some("pop3");
+    if pop3 || account.folders.is_empty() {
+        vec!["INBOX".to_string()]
+    } else {
+        account.folders.clone()
+    }
+}
+
+/// Write the folders to sync into the account file's `folders`, as the
+/// comma-separated list the Mail view edits, leaving its other keys alone
+pub fn set_account_folders(vault_path: &str, account_id: &str, folders: &[String]) -> Result<(), String> {
+    let dir = PathBuf::from(vault_path).join(ACCOUNTS_DIR);
+    let found = fs::read_dir(&dir).into_iter().flatten().filter_map(|e| e.ok()).find_map(|e| {
+        let raw = fs::read_to_string(e.path()).ok()?;
+        let doc: serde_json::Value = serde_json::from_str(&raw).ok()?;
+        (doc.get("id").and_then(|v| v.as_str()) == Some(account_id)).then(|| (e.path(), doc))
+    });
+    let Some((path, mut doc)) = found else {
+        return Err(tr!("Account not found: {}", account_id));
+    };
+    let list: Vec<&str> = folders.iter().map(|f| f.trim()).filter(|f| !f.is_empty()).collect();
+    doc["folders"] = serde_json::Value::String(list.join(","));
+    let raw = serde_json::to_string_pretty(&doc).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(&path, raw).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+/// Account file for a Gmail address synced over the Gmail API, created
+/// unless one exists; returns its id
+pub fn add_gmail_account(vault_path: &str, address: &str) -> Result<String, String> {
+    let id = format!("gmail-{}", address.to_lowercase().replace(|c: char| !c.is_ascii_alphanumeric(), "-"));
+    let dir = PathBuf::from(vault_path).join(ACCOUNTS_DIR);
+    let path = dir.join(format!("{id}.json"));
+    if path.exists() {
+        return Ok(id);
+    }
+    fs::create_dir_all(&dir).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    let doc = serde_json::json!({
+        "id": id,
+        "name": "Gmail",
+        "email": address,
+        "imapHost": gmail::HOST,
+        "imapPort": "443",
+        "protocol": "gmail",
+        "folders": "INBOX",
+        "enabled": true,
+    });
+    let raw = serde_json::to_string_pretty(&doc).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(&path, raw).map_err(|e| tr!("write_file failed: {}", e))?;
+    Ok(id)
+}
+
+fn into_account(file: AccountFile) -> MailAccount {
+    let imap_port = match &file.imap_port {
+        serde_json::Value::Number(n) => n.as_u64().and_then(|p| u16::try_from(p).ok()),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+    .unwrap_or(993);
+    let folders = file
+        .folders
+        .split(',')
+        .map(|f| f.trim().to_string())
+        .filter(|f| !f.is_empty())
+        .collect();
+
+    MailAccount {
+        imap: ImapAccount {
+            email: file.email.clone(),
+            password: file.password,
+            imap_host: file.imap_host,
+            imap_port,
+            // Same fallback as the Mail view's account loader
+            protocol: Some(file.protocol.unwrap_or_else(|| "pop3".to_string())),
+            account_id: Some(file.id.clone()),
+        },
+        id: file.id,
+        name: file.name,
+        email: file.email,
+        folders,
+        enabled: file.enabled != Some(false),
+        sync_minutes: file.sync_minutes,
+        sync_intervals: file.sync_intervals,
+    }
+}
+
+pub fn load_identities(vault_path: &str, account_id: &str) -> Vec<EmailIdentity> {
+    read_identities(vault_path).remove(account_id).unwrap_or_default()
+}
+
+/// Add or replace (by id) one of the account's identities. Marking it the
+/// default unmarks the others.
+pub fn save_identity(vault_path: &str, account_id: &str, mut identity: EmailIdentity) -> Result<EmailIdentity, String> {
+    identity.address = identity.address.trim().to_string();
+    let valid = identity.address.split_once('@').is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.'));
+    if !valid || identity.address.contains(char::is_whitespace) {
+        return Err(tr!("Invalid email address: {}", identity.address));
+    }
+    if identity.id.trim().is_empty() {
+        identity.id = identity
+            .address
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+    }
+
+    let mut all = read_identities(vault_path);
+    let list = all.entry(account_id.to_string()).or_default();
+    if identity.default {
+        list.iter_mut().for_each(|i| i.default = false);
+    }
+    match list.iter_mut().find(|i| i.id == identity.id) {
+        Some(existing) => *existing = identity.clone(),
+        None => list.push(identity.clone()),
+    }
+    write_identities(vault_path, &all)?;
+    Ok(identity)
+}
+
+pub fn delete_identity(vault_path: &str, account_id: &str, identity_id: &str) -> Result<(), String> {
+    let mut all = read_identities(vault_path);
+    let list = all.entry(account_id.to_string()).or_default();
+    let before = list.len();
+    list.retain(|i| i.id != identity_id);
+    if list.len() == before {
+        return Err(tr!("Identity not found: {}", identity_id));
+    }
+    if list.is_empty() {
+        all.remove(account_id);
+    }
+    write_identities(vault_path, &all)
+}
+
+/// The identity asked for, else the account's default, else none
+pub fn resolve_identity(vault_path: &str, account_id: &str, identity_id: Option<&str>) -> Result<Option<EmailIdentity>, String> {
+    let identities = load_identities(vault_path, account_id);
+    match identity_id.filter(|id| !id.trim().is_empty()) {
+        Some(id) => identities.into_iter().find(|i| i.id == id).map(Some).ok_or_else(|| tr!("Identity not found: {}", id)),
+        None => Ok(identities.into_iter().find(|i| i.default)),
+    }
+}
+
+/// `body`, then the signature under the usual `-- ` delimiter
+pub fn with_signature(body: &str, signature: &str) -> String {
+    let signature = signature.trim_end();
+    if signature.is_empty() {
+        return body.to_string();
+    }
+    let signature = signature.strip_prefix("-- \n").unwrap_or(signature);
+    format!("{}\n\n-- \n{}\n", body.trim_end(), signature)
+}
+
+/// `html` with the signature, escaped, in its own block before `</body>`
+/// (or at the end of a fragment)
+pub fn html_with_signature(html: &str, signature: &str) -> String {
+    let signature = signature.trim_end();
+    if signature.is_empty() {
+        return html.to_string();
+    }
+    let signature = signature.strip_prefix("-- \n").unwrap_or(signature);
+    let escaped = signature.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('\n', "<br>\n");
+    let block = format!("<div class=\"signature\">-- <br>\n{escaped}</div>\n");
+    match html.to_ascii_lowercase().rfind("</body>") {
+        Some(end) => format!("{}{block}{}", &html[..end], &html[end..]),
+        None => format!("{}\n{block}", html.trim_end()),
+    }
+}
+
+fn read_identities(vault_path: &str) -> BTreeMap<String, Vec<EmailIdentity>> {
+    fs::read_to_string(PathBuf::from(vault_path).join(IDENTITIES_FILE))
+        .ok()
+        .and_then(|raw| serde_yaml::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn write_identities(vault_path: &str, all: &BTreeMap<String, Vec<EmailIdentity>>) -> Result<(), String> {
+    let path = PathBuf::from(vault_path).join(IDENTITIES_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let raw = serde_yaml::to_string(all).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(&path, raw).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+/// A message's cached .eml in its account's Mailbox directory. Ids from
+/// nested folders (`[Gmail]/Sent Mail_12`) are flattened to one file name.
+pub fn eml_path(dir: &Path, email_id: &str) -> PathBuf {
+    dir.join(format!("{}.eml", email_id.replace(['/', '\\'], "_")))
+}
+
+/// Look a Message-ID (with or without `<>`) up in every account's cache.
+/// IMAP ids are folder_uid, so each cached .eml header is checked.
+pub fn find_message(vault_path: &str, message_id: &str) -> Option<CachedMessage> {
+    let wanted = normalize_message_id(message_id);
+    if wanted.is_empty() {
+        return None;
+    }
+    for (account, dir) in cache_dirs(vault_path) {
+        let Some(index) = read_index(&dir) else { continue };
+        let found = index.into_iter().find(|m| {
+            normalize_message_id(&m.id) == wanted
+                || fs::read(eml_path(&dir, &m.id))
+                    .ok()
+                    .and_then(|raw| header_message_id(&raw))
+                    .is_some_and(|id| id == wanted)
+        });
+        if let Some(m) = found {
+            return Some(CachedMessage {
+                message_id: wanted,
+                account,
+                email_id: m.id,
+                subject: m.subject,
+                from: m.from,
+                date: m.date,
+            });
+        }
+    }
+    None
+}
+
+fn normalize_message_id(id: &str) -> String {
+    id.trim().trim_start_matches('<').trim_end_matches('>').trim().to_string()
+}
+
+/// Message-ID from the header block of a raw message, folded lines included
+pub fn header_message_id(raw: &[u8]) -> Option<String> {
+    header(raw, "message-id").map(|v| normalize_message_id(&v)).filter(|id| !id.is_empty())
+}
+
+/// In-Reply-To of a raw message, without `<>`
+pub fn in_reply_to(raw: &[u8]) -> Option<String> {
+    let value = header(raw, "in-reply-to")?;
+    message_ids(&value).into_iter().next().or_else(|| Some(normalize_message_id(&value)).filter(|id| !id.is_empty()))
+}
+
+/// (In-Reply-To, References) for a reply to a raw message: its Message-ID,
+/// and its own References (else In-Reply-To) with the Message-ID added, so
+/// clients can place the reply in the whole thread
+pub fn reply_headers(raw: &[u8]) -> Option<(String, String)> {
+    let id = header_message_id(raw)?;
+    let mut chain = header(raw, "references").or_else(|| header(raw, "in-reply-to")).map(|v| message_ids(&v)).unwrap_or_default();
+    chain.retain(|c| *c != id);
+    chain.push(id.clone());
+    let references: Vec<String> = chain.iter().map(|c| format!("<{c}>")).collect();
+    Some((format!("<{id}>"), references.join(" ")))
+}
+
+/// `Re: ` and the subject without the reply prefixes it already has
+/// (`Re:`, `RE[2]:`, `Aw:`, `回复：`), so replies to replies don't pile them up
+pub fn reply_subject(subject: &str) -> String {
+    let mut rest = subject.trim();
+    loop {
+        let lower = rest.to_lowercase();
+        let prefix = ["re", "aw", "回复", "答复"].into_iter().find(|p| lower.starts_with(p)).map_or(0, str::len);
+        let after = &rest[prefix..];
+        // Re[2]: as some clients count
+        let after = match after.strip_prefix('[').and_then(|a| a.split_once(']')) {
+            Some((n, tail)) if n.chars().all(|c| c.is_ascii_digit()) => tail,
+            _ => after,
+        };
+        match after.strip_prefix(':').or_else(|| after.strip_prefix('：')) {
+            Some(tail) if prefix > 0 => rest = tail.trim_start(),
+            _ => break,
+        }
+    }
+    format!("Re: {rest}")
+}
+
+/// The `<…>` ids in a header value
+fn message_ids(value: &str) -> Vec<String> {
+    value
+        .split('<')
+        .skip(1)
+        .filter_map(|part| part.split_once('>'))
+        .map(|(id, _)| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .collect()
+}
+
+/// List-Id of a raw message, the part in angle brackets when there is one:
+/// `Weekly News <weekly.news.example.com>` gives `weekly.news.example.com`
+pub fn list_id(raw: &[u8]) -> Option<String> {
+    let value = header(raw, "list-id")?;
+    let id = match (value.find('<'), value.rfind('>')) {
+        (Some(start), Some(end)) if start < end => value[start + 1..end].trim().to_string(),
+        _ => value,
+    };
+    (!id.is_empty()).then_some(id)
+}
+
+/// A part of a message shown as an attachment
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+    pub name: String,
+    /// Decoded, in bytes
+    pub size: usize,
+    pub content_type: String,
+}
+
+/// A parsed message's attachments, in the order `attachment` numbers them
+pub fn attachments(message: &Message) -> Vec<Attachment> {
+    message.attachments().enumerate().map(|(i, part)| describe_attachment(i, part)).collect()
+}
+
+/// Attachment `index` of a raw message with its decoded contents
+pub fn attachment(raw: &[u8], index: usize) -> Option<(Attachment, Vec<u8>)> {
+    let message = MessageParser::default().parse(raw)?;
+    let part = message.attachment(index)?;
+    Some((describe_attachment(index, part), part.contents().to_vec()))
+}
+
+/// MIME type to send a file as, by extension
+pub fn content_type_for(path: &Path) -> &'static str {
+    let ext = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    match ext.as_str() {
+        "pdf" => "application/pdf",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "txt" | "log" => "text/plain",
+        "md" => "text/markdown",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "json" => "application/json",
+        "ics" => "text/calendar",
+        "eml" => "message/rfc822",
+        "zip" => "application/zip",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xls" => "application/vnd.ms-excel",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "ppt" => "application/vnd.ms-powerpoint",
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "mp3" => "audio/mpeg",
+        "m4a" => "audio/mp4",
+        "mp4" => "video/mp4",
+        "mov" => "video/quicktime",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Cached messages answering any of `sent` (Message-ID → the address it was
+/// sent from), found by their In-Reply-To and References headers. Only .eml
+/// files written since `since` are read, and messages from the sender
+/// itself (a nudge in the same thread) don't count.
+pub fn find_replies(vault_path: &str, sent: &BTreeMap<String, String>, since: SystemTime) -> BTreeMap<String, CachedMessage> {
+    let mut replies = BTreeMap::new();
+    for (account, dir) in cache_dirs(vault_path) {
+        let Some(index) = read_index(&dir) else { continue };
+        for m in index {
+            let path = eml_path(&dir, &m.id);
+            if fs::metadata(&path).and_then(|meta| meta.modified()).is_ok_and(|t| t < since) {
+                continue;
+            }
+            let Ok(raw) = fs::read(&path) else { continue };
+            let referenced = ["in-reply-to", "references"]
+                .iter()
+                .filter_map(|name| header(&raw, name))
+                .flat_map(|v| v.split_whitespace().map(normalize_message_id).collect::<Vec<_>>());
+            for id in referenced {
+                let Some(sender) = sent.get(&id) else { continue };
+                if replies.contains_key(&id) || m.from.to_lowercase().contains(&sender.to_lowercase()) {
+                    continue;
+                }
+                let reply = CachedMessage {
+                    message_id: id.clone(),
+                    account: account.clone(),
+                    email_id: m.id.clone(),
+                    subject: m.subject.clone(),
+                    from: m.from.clone(),
+                    date: m.date.clone(),
+                };
+                replies.insert(id, reply);
+            }
+        }
+    }
+    replies
+}
+
+/// Messages cached more than once under the same Message-ID, across all
+/// accounts and folders, most copies first
+pub fn find_duplicates(vault_path: &str) -> Vec<DuplicateGroup> {
+    let mut by_id: BTreeMap<String, Vec<CachedMessage>> = BTreeMap::new();
+    for (account, message_id, m) in cached_messages(vault_path) {
+        let Some(message_id) = message_id else { continue };
+        by_id.entry(message_id.clone()).or_default().push(CachedMessage {
+            message_id,
+            account,
+            email_id: m.id,
+            subject: m.subject,
+            from: m.from,
+            date: m.date,
+        });
+    }
+    let mut groups: Vec<DuplicateGroup> = by_id
+        .into_iter()
+        .filter(|(_, copies)| copies.len() > 1)
+        .map(|(message_id, copies)| DuplicateGroup { message_id, subject: copies[0].subject.clone(), from: copies[0].from.clone(), copies })
+        .collect();
+    groups.sort_by(|a, b| b.copies.len().cmp(&a.copies.len()));
+    groups
+}
+
+/// Every account's cached `folder`, newest first. With `merge`, a message
+/// cached under several accounts shows once, as the first account's copy.
+pub fn unified_inbox(vault_path: &str, folder: &str, merge: bool) -> Vec<UnifiedEmail> {
+    let mut rows: Vec<UnifiedEmail> = Vec::new();
+    let mut seen: BTreeMap<String, usize> = BTreeMap::new();
+    for (account, message_id, email) in cached_messages(vault_path) {
+        if !email.folder.eq_ignore_ascii_case(folder) {
+            continue;
+        }
+        if let Some(&row) = message_id.as_ref().filter(|_| merge).and_then(|id| seen.get(id)) {
+            let row: &mut UnifiedEmail = &mut rows[row];
+            if row.account != account && !row.also_in.contains(&account) {
+                row.also_in.push(account);
+            }
+            continue;
+        }
+        if let Some(id) = message_id {
+            seen.insert(id, rows.len());
+        }
+        rows.push(UnifiedEmail { account, also_in: Vec::new(), email });
+    }
+    let timestamp = |e: &UnifiedEmail| chrono::DateTime::parse_from_rfc2822(e.email.date.trim()).map_or(0, |d| d.timestamp());
+    rows.sort_by_key(|e| std::cmp::Reverse(timestamp(e)));
+    rows
+}
+
+/// Each cached message with its Mailbox/ directory and Message-ID,
+/// directories in name order and messages in index order. Only .eml
+/// headers are read.
+fn cached_messages(vault_path: &str) -> Vec<(String, Option<String>, EmailMessage)> {
+    let mut messages = Vec::new();
+    for (account, dir) in cache_dirs(vault_path) {
+        let Some(index) = read_index(&dir) else { continue };
+        for m in index {
+            let eml = eml_path(&dir, &m.id);
+            let message_id = read_head(&eml).and_then(|head| header_message_id(&head));
+            messages.push((account.clone(), message_id, m));
+        }
+    }
+    messages
+}
+
+/// Every directory under Mailbox/ with an index.json, by name: the
+/// accounts', and the folders `imap_sync_all` caches on their own
+/// (`<account>/<folder>`)
+fn cache_dirs(vault_path: &str) -> Vec<(String, PathBuf)> {
+    let root = PathBuf::from(vault_path).join(MAILBOX_DIR);
+    let mut dirs: Vec<(String, PathBuf)> = WalkDir::new(&root)
+        .min_depth(1)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_dir() && e.path().join("index.json").is_file())
+        .filter_map(|e| Some((e.path().strip_prefix(&root).ok()?.to_string_lossy().replace('\\', "/"), e.into_path())))
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn read_index(dir: &Path) -> Option<Vec<EmailMessage>> {
+    fs::read_to_string(dir.join("index.json")).ok().and_then(|raw| serde_json::from_str(&raw).ok())
+}
+
+/// The header block of a raw message, without reading its (possibly
+/// large) body
+fn read_head(path: &Path) -> Option<Vec<u8>> {
+    let mut reader = BufReader::new(fs::File::open(path).ok()?);
+    let mut head = Vec::new();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).ok()? == 0 || line.iter().all(|b| b.is_ascii_whitespace()) {
+            break;
+        }
+        head.extend_from_slice(&line);
+    }
+    Some(head)
+}
+
+/// Name and type of an attachment; unnamed ones get "attachment-<n>", and
+/// forwarded messages their subject
+fn describe_attachment(index: usize, part: &MessagePart) -> Attachment {
+    let name = part
+        .attachment_name()
+        .map(str::to_string)
+        .or_else(|| part.message().and_then(|m| m.subject()).map(|s| format!("{s}.eml")))
+        .unwrap_or_else(|| format!("attachment-{}", index + 1));
+    let content_type = match part.content_type() {
+        Some(ct) => match ct.subtype() {
+            Some(sub) => format!("{}/{}", ct.ctype(), sub).to_lowercase(),
+            None => ct.ctype().to_lowercase(),
+        },
+        None if part.is_message() => "message/rfc822".to_string(),
+        None => "application/octet-stream".to_string(),
+    };
+    Attachment { name, size: part.len(), content_type }
+}
+
+/// A header's value with folded continuation lines joined
+fn header(raw: &[u8], name: &str) -> Option<String> {
+    let text = String::from_utf8_lossy(raw);
+    let mut value: Option<String> = None;
+    for line in text.lines().take_while(|l| !l.trim().is_empty()) {
+        if line.starts_with([' ', '\t']) {
+            if let Some(v) = value.as_mut() {
+                v.push(' ');
+                v.push_str(line.trim());
+            }
+            continue;
+        }
+        if value.is_some() {
+            break;
+        }
+        if let Some((key, rest)) = line.split_once(':') {
+            if key.eq_ignore_ascii_case(name) {
+                value = Some(rest.trim().to_string());
+            }
+        }
+    }
+    value.map(|v| v.trim().to_string())
+}
+
+// ── Sync ─────────────────────────────────────────────────────────────────────
+
+/// A stream wrapper that replays a prefix buffer before delegating to the inner stream.
+/// Used to replay the IMAP greeting after manually sending the ID command.
+#[derive(Debug)]
+pub(crate) struct PrefixStream<T> {
+    inner: T,
+    prefix: Cursor<Vec<u8>>,
+    prefix_done: bool,
+}
+
+impl<T> PrefixStream<T> {
+    fn new(inner: T, prefix: Vec<u8>) -> Self {
+        PrefixStream {
+            inner,
+            prefix: Cursor::new(prefix),
+            prefix_done: false,
+        }
+    }
+}
+
+impl<T: Read> Read for PrefixStream<T> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if !self.prefix_done {
+            let n = self.prefix.read(buf)?;
+            if n == 0 {
+                self.prefix_done = true;
+                self.inner.read(buf)
+            } else {
+                Ok(n)
+            }
+        } else {
+            self.inner.read(buf)
+        }
+    }
+}
+
+impl<T: Write> Write for PrefixStream<T> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.inner.write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Lets an IDLE session on the implicit-TLS client wait with a timeout
+impl SetReadTimeout for PrefixStream<native_tls::TlsStream<TcpStream>> {
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> imap::error::Result<()> {
+        self.inner.get_ref().set_read_timeout(timeout).map_err(imap::error::Error::Io)
+    }
+}
+
+/// Sync state for a single folder, persisted between sessions
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct FolderSyncState {
+    #[serde(rename = "uidValidity")]
+    pub uid_validity: u32,
+    #[serde(rename = "lastUid")]
+    pub last_uid: u32,
+    #[serde(rename = "lastSync")]
+    pub last_sync: String,
+}
+
+type SyncStateMap = HashMap<String, FolderSyncState>;
+
+/// A message that arrived since the folder's previous sync, with what the
+/// frontend needs to open it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArrivedMail {
+    /// Mailbox/ directory holding it, as taken by `get_email_content`
+    pub account_id: String,
+    pub folder: String,
+    pub email_id: String,
+    pub from: String,
+    pub subject: String,
+}
+
+fn load_sync_state(vault_path: &str, account_dir: &str) -> SyncStateMap {
+    let path = PathBuf::from(vault_path)
+        .join("Mailbox")
+        .join(account_dir)
+        .join("sync_state.json");
+    if let Ok(content) = fs::read_to_string(&path) {
+        serde_json::from_str(&content).unwrap_or_default()
+    } else {
+        SyncStateMap::new()
+    }
+}
+
+fn save_sync_state(vault_path: &str, account_dir: &str, state: &SyncStateMap) -> Result<(), String> {
+    let dir = PathBuf::from(vault_path).join("Mailbox").join(account_dir);
+    fs::create_dir_all(&dir).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    let path = dir.join("sync_state.json");
+    let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    durable::write(&path, json).map_err(|e| tr!("Failed to write sync_state: {}", e))
+}
+
+/// Read a single CRLF-terminated line from a stream (byte-by-byte for safety)
+fn read_imap_line(stream: &mut impl Read) -> Result<Vec<u8>, String> {
+    let mut line = Vec::with_capacity(256);
+    let mut buf = [0u8; 1];
+    loop {
+        stream.read_exact(&mut buf).map_err(|e| tr!("Failed to read IMAP response: {}", e))?;
+        line.push(buf[0]);
+        if line.len() >= 2 && line[line.len() - 2] == b'\r' && line[line.len() - 1] == b'\n' {
+            break;
+        }
+        if line.len() > 8192 {
+            break; // Safety limit
+        }
+    }
+    Ok(line)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EmailMessage {
+    #[serde(rename = "id")]
+    pub id: String,
+    #[serde(rename = "uid")]
+    pub uid: u32,
+    #[serde(rename = "uidString")]
+    pub uid_string: Option<String>, // POP3 UIDL unique identifier
+    #[serde(rename = "from")]
+    pub from: String,
+    #[serde(rename = "to")]
+    pub to: String,
+    #[serde(rename = "subject")]
+    pub subject: String,
+    #[serde(rename = "date")]
+    pub date: String,
+    #[serde(rename = "bodyText")]
+    pub body_text: Option<String>,
+    #[serde(rename = "bodyHtml")]
+    pub body_html: Option<String>,
+    #[serde(rename = "attachments")]
+    pub attachments: Vec<Attachment>,
+    #[serde(rename = "flags")]
+    pub flags: Vec<String>,
+    #[serde(rename = "folder")]
+    pub folder: String,
+    /// Remote images and styles held back from `body_html` until the reader loads them
+    #[serde(rename = "remoteBlocked", default)]
+    pub remote_blocked: usize,
+    /// Tracking pixels and wrapped links found in the HTML
+    #[serde(rename = "trackers", default)]
+    pub trackers: Vec<Tracker>,
+    /// List-Id of mailing-list mail; set means the reader view applies
+    #[serde(rename = "listId", default, skip_serializing_if = "Option::is_none")]
+    pub list_id: Option<String>,
+    /// Message-ID (without `<>`) of the message this one answers
+    #[serde(rename = "inReplyTo", default, skip_serializing_if = "Option::is_none")]
+    pub in_reply_to: Option<String>,
+    /// Message-IDs of replies sent to it from here
+    #[serde(rename = "replies", default, skip_serializing_if = "Vec::is_empty")]
+    pub replies: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImapAccount {
+    pub email: String,
+    pub password: String,
+    pub imap_host: String,
+    pub imap_port: u16,
+    pub protocol: Option<String>, // "imap" or "pop3"
+    pub account_id: Option<String>, // 用于区分不同账户的标识
+}
+
+/// Blocking body of `imap_sync`, shared with `lifeos mail sync`. Each call
+/// is recorded in the account's sync health, and waits for any other sync
+/// of the account to finish.
+pub fn sync_mailbox(
+    account: &ImapAccount,
+    vault_path: &str,
+    folder: &str,
+    max_emails: u32,
+    skip: u32,
+) -> Result<Vec<EmailMessage>, String> {
+    let dir = account_dir(account);
+    let lock = sync_lock(&dir);
+    let _syncing = hold(&lock);
+    sync_mailbox_locked(account, vault_path, &dir, folder, max_emails, skip)
+}
+
+/// `sync_mailbox` for a caller already holding the account's `sync_lock`,
+/// caching into Mailbox/`dir`
+fn sync_mailbox_locked(
+    account: &ImapAccount,
+    vault_path: &str,
+    dir: &str,
+    folder: &str,
+    max_emails: u32,
+    skip: u32,
+) -> Result<Vec<EmailMessage>, String> {
+    println!("[DEBUG] imap_sync received - email: {}, account_id: {:?}, skip: {}", account.email, account.account_id, skip);
+
+    let account_dir = account_dir(account);
+    let started = std::time::Instant::now();
+    let result = fetch_mailbox(account, &account_dir, dir, vault_path, folder, max_emails, skip);
+    mail_health::record(vault_path, &account_dir, folder, started.elapsed(), result.as_ref().err().map(String::as_str));
+    result
+}
+
+/// Messages the account's syncs found past `last_uid` since the last call
+pub fn take_arrived(account_dir: &str) -> Option<Vec<ArrivedMail>> {
+    ARRIVED.lock().unwrap().remove(account_dir)
+}
+
+/// Mailbox/ directory of an account: its id, else the address
+pub fn account_dir(account: &ImapAccount) -> String {
+    account.account_id.clone().unwrap_or_else(|| {
+        println!("[DEBUG] account_id is None, using email as fallback: {}", account.email.replace("@", "_at_"));
+        account.email.replace("@", "_at_")
+    })
+}
+
+/// Fetch into Mailbox/`dir`; arrivals are kept under `account_dir`
+fn fetch_mailbox(
+    account: &ImapAccount,
+    account_dir: &str,
+    dir: &str,
+    vault_path: &str,
+    folder: &str,
+    max_emails: u32,
+    skip: u32,
+) -> Result<Vec<EmailMessage>, String> {
+    let host = &account.imap_host;
+    let port = account.imap_port;
+    let email = &account.email;
+    let password = &account.password;
+    let protocol = account.protocol.as_deref().unwrap_or("imap");
+
+    if protocol == "gmail" {
+        return gmail::sync(vault_path, dir, folder, max_emails, skip);
+    }
+
+    let use_tls = port == 993 || port == 995;
+
+    if protocol == "pop3" {
+        if use_tls {
+            pop3_sync_tls(host, port, email, password, vault_path, dir, max_emails, skip)
+        } else {
+            pop3_sync_plain(host, port, email, password, vault_path, dir, max_emails, skip)
+        }
+    } else {
+        imap_sync_with_crate(host, port, email, password, vault_path, account_dir, dir, folder, max_emails, skip, use_tls)
+    }
+}
+
+// ── IMAP via `imap` crate + `mail-parser` ────────────────────────────────────
+
+fn imap_sync_with_crate(
+    host: &str,
+    port: u16,
+    email: &str,
+    password: &str,
+    vault_path: &str,
+    account_dir: &str,
+    dir: &str,
+    folder: &str,
+    max_emails: u32,
+    skip: u32,
+    use_tls: bool,
+) -> Result<Vec<EmailMessage>, String> {
+    let tls = imap_tls_connector()?;
+
+    if use_tls {
+        let mut session = imap_tls_client(host, port, &tls)?
+            .login(email, password)
+            .map_err(|e| tr!("Login failed: {}", e.0))?;
+
+        let result = imap_fetch_emails(&mut session, folder, max_emails, skip, vault_path, account_dir, dir);
+        session.logout().ok();
+        result
+    } else {
+        let mut session = imap_starttls_client(host, port, &tls)?
+            .login(email, password)
+            .map_err(|e| tr!("Login failed: {}", e.0))?;
+
+        let result = imap_fetch_emails(&mut session, folder, max_emails, skip, vault_path, account_dir, dir);
+        session.logout().ok();
+        result
+    }
+}
+
+pub(crate) fn imap_tls_connector() -> Result<TlsConnector, String> {
+    TlsConnector::builder()
+        .danger_accept_invalid_certs(true)
+        .build()
+        .map_err(|e| tr!("Failed to create TLS connector: {}", e))
+}
+
+/// Implicit-TLS client (993) with the IMAP ID command already sent
+pub(crate) fn imap_tls_client(
+    host: &str,
+    port: u16,
+    tls: &TlsConnector,
+) -> Result<imap::Client<PrefixStream<native_tls::TlsStream<TcpStream>>>, String> {
+    // Connect manually to send IMAP ID command before login.
+    // Required by NetEase (163/126/yeah.net) to avoid "Unsafe Login" error.
+    let tcp = TcpStream::connect((host, port))
+        .map_err(|e| tr!("Connection failed: {}", e))?;
+    tcp.set_read_timeout(Some(std::time::Duration::from_secs(30))).ok();
+
+    let mut tls_stream = tls.connect(host, tcp)
+        .map_err(|e| tr!("TLS handshake failed: {}", e))?;
+
+    // Read server greeting
+    let greeting = read_imap_line(&mut tls_stream)?;
+    println!("[DEBUG] IMAP greeting: {}", String::from_utf8_lossy(&greeting).trim());
+
+    // Send IMAP ID command (RFC 2971) — needed by 163/126/yeah.net
+    tls_stream.write_all(
+        b"A000 ID (\"name\" \"LifeOS\" \"version\" \"1.0.0\" \"vendor\" \"LifeOS\")\r\n"
+    ).map_err(|e| tr!("Failed to send ID command: {}", e))?;
+    tls_stream.flush().map_err(|e| tr!("Flush failed: {}", e))?;
+
+    // Read ID response until tagged response
+    loop {
+        let line = read_imap_line(&mut tls_stream)?;
+        let line_str = String::from_utf8_lossy(&line);
+        println!("[DEBUG] ID response: {}", line_str.trim());
+        if line_str.starts_with("A000 ") {
+            break;
+        }
+    }
+
+    // Wrap stream: replay greeting so imap::Client::new() sees it
+    let prefix_stream = PrefixStream::new(tls_stream, greeting);
+    Ok(imap::Client::new(prefix_stream))
+}
+
+/// Plain connection upgraded with STARTTLS (ID command not injected here)
+pub(crate) fn imap_starttls_client(host: &str, port: u16, tls: &TlsConnector) -> Result<imap::Client<native_tls::TlsStream<TcpStream>>, String> {
+    let stream = TcpStream::connect((host, port))
+        .map_err(|e| tr!("Connection failed: {}", e))?;
+    imap::Client::new(stream)
+        .secure(host, tls)
+        .map_err(|e| tr!("STARTTLS failed: {}", e))
+}
+
+/// Fetch a page of emails from IMAP by sequence-number range.
+/// skip=0 → latest max_emails; skip=20 → the 20 emails before those; etc.
+fn imap_fetch_emails<T: Read + Write>(
+    session: &mut imap::Session<T>,
+    folder: &str,
+    max_emails: u32,
+    skip: u32,
+    vault_path: &str,
+    account_dir: &str,
+    dir: &str,
+) -> Result<Vec<EmailMessage>, String> {
+    let mailbox = session
+        .select(folder)
+        .map_err(|e| tr!("Failed to select folder: {}", e))?;
+
+    let total = mailbox.exists as u32;
+
+    if total == 0 || skip >= total {
+        return Ok(Vec::new());
+    }
+
+    // Sequence numbers count from 1 (oldest) to total (newest).
+    // fetch_end is the newest message in this page.
+    let fetch_end = total - skip;
+    let fetch_start = fetch_end.saturating_sub(max_emails.saturating_sub(1)).max(1);
+    let range = format!("{}:{}", fetch_start, fetch_end);
+
+    println!("[SYNC] folder={} total={} skip={} range={}", folder, total, skip, range);
+
+    let emails_dir = PathBuf::from(vault_path).join("Mailbox").join(dir);
+    fs::create_dir_all(&emails_dir).map_err(|e| tr!("Failed to create directory: {}", e))?;
+
+    let messages = session
+        .fetch(&range, "(UID FLAGS RFC822)")
+        .map_err(|e| tr!("Failed to fetch emails: {}", e))?;
+
+    let mut emails = parse_imap_messages(&messages, folder, &emails_dir)?;
+    emails.reverse(); // newest first within this page
+
+    // Pages of older mail say nothing about what's new
+    if skip == 0 {
+        let mut states = load_sync_state(vault_path, dir);
+        let (arrived, state) = arrivals(states.get(folder), mailbox.uid_validity.unwrap_or(0), &emails);
+        if !arrived.is_empty() {
+            let arrived = arrived.into_iter().map(|e| ArrivedMail {
+                account_id: dir.to_string(),
+                folder: folder.to_string(),
+                email_id: e.id.clone(),
+                from: e.from.clone(),
+                subject: e.subject.clone(),
+            });
+            ARRIVED.lock().unwrap().entry(account_dir.to_string()).or_default().extend(arrived);
+        }
+        states.insert(folder.to_string(), state);
+        if let Err(e) = save_sync_state(vault_path, dir, &states) {
+            println!("[WARN] {e}");
+        }
+    }
+
+    Ok(emails)
+}
+
+/// Messages past the folder's `last_uid`, and the state to save. The first
+/// sync and a UIDVALIDITY change only set the baseline, so a new account
+/// doesn't announce its whole inbox.
+fn arrivals<'a>(state: Option<&FolderSyncState>, uid_validity: u32, emails: &'a [EmailMessage]) -> (Vec<&'a EmailMessage>, FolderSyncState) {
+    let newest = emails.iter().map(|e| e.uid).max().unwrap_or(0);
+    let known = state.filter(|s| s.uid_validity == uid_validity && s.last_uid > 0);
+    let arrived = known.map(|s| emails.iter().filter(|e| e.uid > s.last_uid).collect()).unwrap_or_default();
+    let last_uid = known.map_or(newest, |s| s.last_uid.max(newest));
+    (arrived, FolderSyncState { uid_validity, last_uid, last_sync: chrono_now() })
+}
+
+/// Returns current UTC time as RFC3339 string (without chrono dependency)
+fn chrono_now() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    // Simple ISO 8601 UTC timestamp
+    let s = secs;
+    let sec = s % 60;
+    let min = (s / 60) % 60;
+    let hour = (s / 3600) % 24;
+    let days = s / 86400;
+    // Approximate date (good enough for sync metadata logging)
+    let year = 1970 + days / 365;
+    let day_of_year = days % 365;
+    let month = day_of_year / 30 + 1;
+    let day = day_of_year % 30 + 1;
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, hour, min, sec)
+}
+
+/// Parse a collection of IMAP fetch responses into EmailMessage structs,
+/// saving each RFC822 body as a .eml file.
+fn parse_imap_messages(
+    messages: &imap::types::ZeroCopy<Vec<imap::types::Fetch>>,
+    folder: &str,
+    emails_dir: &PathBuf,
+) -> Result<Vec<EmailMessage>, String> {
+    let mut emails = Vec::new();
+
+    for msg in messages.iter() {
+        let uid = msg.uid.unwrap_or(0);
+        let email_id = format!("{}_{}", folder, uid);
+
+        // Save raw RFC822 as .eml file
+        if let Some(raw) = msg.body() {
+            fs::write(eml_path(emails_dir, &email_id), raw).map_err(|e| tr!("Failed to save EML file: {}", e))?;
+        }
+
+        // Parse flags
+        let flags: Vec<String> = msg
+            .flags()
+            .iter()
+            .map(|f| format!("{:?}", f))
+            .collect();
+
+        // Parse the full email from RFC822 body using mail-parser
+        let (subject, from, to, date, body_text, body_html, attachments) = match msg.body() {
+            Some(raw) => {
+                println!("[DEBUG] RFC822 body for uid {}: {} bytes", uid, raw.len());
+                use mail_parser::MessageParser;
+                let parser = MessageParser::default();
+                if let Some(parsed) = parser.parse(raw) {
+                    let subject = parsed.subject().unwrap_or("").to_string();
+                    let from = parsed.from().and_then(|a| a.first())
+                        .map(|a| {
+                            if let Some(name) = a.name() {
+                                if let Some(addr) = a.address() {
+                                    format!("{} <{}>", name, addr)
+                                } else { name.to_string() }
+                            } else {
+                                a.address().unwrap_or("").to_string()
+                            }
+                        }).unwrap_or_default();
+                    let to = parsed.to().and_then(|a| a.first())
+                        .map(|a| a.address().unwrap_or("").to_string())
+                        .unwrap_or_default();
+                    let date = parsed.date()
+                        .map(|d| d.to_rfc3339())
+                        .unwrap_or_default();
+                    let body_text = parsed.body_text(0).map(|t| t.to_string());
+                    let body_html = parsed.body_html(0).map(|h| h.to_string());
+                    (subject, from, to, date, body_text, body_html, attachments(&parsed))
+                } else {
+                    println!("[DEBUG] mail-parser failed to parse uid {}", uid);
+                    (String::new(), String::new(), String::new(), String::new(), None, None, vec![])
+                }
+            }
+            None => {
+                println!("[DEBUG] msg.body() returned None for uid {}", uid);
+                (String::new(), String::new(), String::new(), String::new(), None, None, vec![])
+            }
+        };
+
+        let list_id = msg.body().and_then(list_id);
+        let in_reply_to = msg.body().and_then(in_reply_to);
+
+        emails.push(EmailMessage {
+            id: email_id,
+            uid,
+            uid_string: Some(uid.to_string()),
+            from,
+            to,
+            subject,
+            date,
+            body_text,
+            body_html,
+            attachments,
+            flags,
+            folder: folder.to_string(),
+            remote_blocked: 0,
+            trackers: vec![],
+            list_id,
+            in_reply_to,
+            replies: vec![],
+        });
+    }
+
+    Ok(emails)
+}
+
+/// Save metadata-only index.json (strips body content)
+pub(crate) fn save_index_json(emails_dir: &PathBuf, emails: &[EmailMessage]) -> Result<(), String> {
+    let index_entries: Vec<EmailMessage> = emails.iter().map(|e| EmailMessage {
+        id: e.id.clone(),
+        uid: e.uid,
+        uid_string: e.uid_string.clone(),
+        from: e.from.clone(),
+        to: e.to.clone(),
+        subject: e.subject.clone(),
+        date: e.date.clone(),
+        body_text: None,
+        body_html: None,
+        attachments: e.attachments.clone(),
+        flags: e.flags.clone(),
+        folder: e.folder.clone(),
+        remote_blocked: 0,
+        // Bodies are dropped here, so this is the last chance to scan them
+        trackers: match &e.body_html {
+            Some(html) => mail_html::find_trackers(html),
+            None => e.trackers.clone(),
+        },
+        list_id: e.list_id.clone(),
+        in_reply_to: e.in_reply_to.clone(),
+        replies: e.replies.clone(),
+    }).collect();
+    let index_path = emails_dir.join("index.json");
+    let index_json = serde_json::to_string_pretty(&index_entries).map_err(|e| e.to_string())?;
+    durable::write(&index_path, index_json).map_err(|e| tr!("Failed to write index file: {}", e))
+}
+
+// ── POP3 support ─────────────────────────────────────────────────────────────────
+
+/// Index entry for email metadata (stored in index.json)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmailIndexEntry {
+    pub uid: u32,
+    pub message_id: Option<String>,
+    pub subject: String,
+    pub from: String,
+    pub date: String,
+    pub file: String,  // EML filename
+    pub flags: Vec<String>,
+}
+
+fn pop3_sync_tls(
+    host: &str,
+    port: u16,
+    email: &str,
+    password: &str,
+    vault_path: &str,
+    account_dir: &str,
+    max_emails: u32,
+    skip: u32,
+) -> Result<Vec<EmailMessage>, String> {
+    use native_tls::TlsStream;
+
+    let connector = TlsConnector::builder()
+        .danger_accept_invalid_certs(true)
+        .build()
+        .map_err(|e| tr!("Failed to create TLS connector: {}", e))?;
+
+    let addr = format!("{}:{}", host, port);
+    let tcp_stream = TcpStream::connect(&addr).map_err(|e| tr!("Connection failed: {}", e))?;
+    tcp_stream.set_read_timeout(Some(std::time::Duration::from_secs(30))).ok();
+
+    let tls_stream = connector.connect(host, tcp_stream)
+        .map_err(|e| tr!("TLS handshake failed: {}", e))?;
+
+    let mut stream: TlsStream<TcpStream> = tls_stream;
+
+    read_response(&mut stream)?;
+
+    // Login
+    let user_cmd = format!("USER {}\r\n", email);
+    stream.write_all(user_cmd.as_bytes()).map_err(|e| tr!("Failed to send: {}", e))?;
+    let user_resp = read_response(&mut stream)?;
+    if !user_resp.contains("+OK") {
+        return Err(tr!("USER command failed: {}", user_resp));
+    }
+
+    let pass_cmd = format!("PASS {}\r\n", password);
+    stream.write_all(pass_cmd.as_bytes()).map_err(|e| tr!("Failed to send: {}", e))?;
+    let pass_resp = read_response(&mut stream)?;
+    if !pass_resp.contains("+OK") {
+        return Err(tr!("Login failed: {}", pass_resp));
+    }
+
+    // Get UIDL list (all messages)
+    stream.write_all(b"UIDL\r\n").map_err(|e| tr!("Failed to send: {}", e))?;
+    let uidl_resp = read_response(&mut stream)?;
+    let mut server_uids = parse_uidl_response(&uidl_resp);
+
+    // Sort newest first (by seq number descending)
+    server_uids.sort_by(|a, b| b.0.cmp(&a.0));
+
+    // Apply skip + limit to get the current page
+    let page: Vec<(u32, String)> = server_uids
+        .into_iter()
+        .skip(skip as usize)
+        .take(max_emails as usize)
+        .collect();
+
+    println!("[SYNC] POP3 TLS: skip={} max={} page_count={}", skip, max_emails, page.len());
+
+    if page.is_empty() {
+        stream.write_all(b"QUIT\r\n").ok();
+        return Ok(Vec::new());
+    }
+
+    let emails_dir = PathBuf::from(vault_path).join("Mailbox").join(account_dir);
+    fs::create_dir_all(&emails_dir).map_err(|e| tr!("Failed to create directory: {}", e))?;
+
+    let mut emails = Vec::new();
+
+    for (seq, uid_string) in page {
+        let retr_cmd = format!("RETR {}\r\n", seq);
+        stream.write_all(retr_cmd.as_bytes()).map_err(|e| tr!("Failed to send: {}", e))?;
+
+        let mut response = Vec::new();
+        let mut buf = [0u8; 8192];
+        loop {
+            match stream.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    response.extend_from_slice(&buf[..n]);
+                    let resp_str = String::from_utf8_lossy(&response);
+                    if resp_str.contains("\r\n.\r\n") || resp_str.contains("\n.\n") {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+
+        // Strip POP3 +OK header line
+        let resp_str = String::from_utf8_lossy(&response);
+        let raw_email: &[u8] = if let Some(idx) = resp_str.find("\r\n") {
+            &response[idx + 2..]
+        } else {
+            &response[..]
+        };
+
+        let (email_msg, message_id) = parse_pop3_email_with_parser(raw_email, account_dir, seq, Some(uid_string.clone()));
+
+        let eml_filename = message_id.clone().unwrap_or_else(|| seq.to_string());
+        let safe_filename = eml_filename.chars().filter(|c| c.is_alphanumeric() || *c == '@' || *c == '.' || *c == '-' || *c == '_').take(100).collect::<String>();
+        let eml_path = emails_dir.join(format!("{}.eml", safe_filename));
+        fs::write(&eml_path, raw_email).map_err(|e| tr!("Failed to save EML file: {}", e))?;
+
+        emails.push(email_msg);
+    }
+
+    stream.write_all(b"QUIT\r\n").ok();
+
+    Ok(emails)
+}
+
+fn pop3_sync_plain(
+    host: &str,
+    port: u16,
+    email: &str,
+    password: &str,
+    vault_path: &str,
+    account_dir: &str,
+    max_emails: u32,
+    skip: u32,
+) -> Result<Vec<EmailMessage>, String> {
+    let addr = format!("{}:{}", host, port);
+    let mut stream = TcpStream::connect(&addr).map_err(|e| tr!("Connection failed: {}", e))?;
+    stream.set_read_timeout(Some(std::time::Duration::from_secs(30))).ok();
+
+    let mut buf = [0u8; 4096];
+    stream.read(&mut buf).map_err(|e| tr!("Failed to read: {}", e))?;
+
+    // Login
+    let user_cmd = format!("USER {}\r\n", email);
+    stream.write_all(user_cmd.as_bytes()).map_err(|e| tr!("Failed to send: {}", e))?;
+    let n = stream.read(&mut buf).map_err(|e| tr!("Failed to read: {}", e))?;
+    if !String::from_utf8_lossy(&buf[..n]).contains("+OK") {
+        return Err(tr!("USER command failed"));
+    }
+
+    let pass_cmd = format!("PASS {}\r\n", password);
+    stream.write_all(pass_cmd.as_bytes()).map_err(|e| tr!("Failed to send: {}", e))?;
+    let n = stream.read(&mut buf).map_err(|e| tr!("Failed to read: {}", e))?;
+    if !String::from_utf8_lossy(&buf[..n]).contains("+OK") {
+        return Err(tr!("Login failed"));
+    }
+
+    // Get UIDL list
+    stream.write_all(b"UIDL\r\n").map_err(|e| tr!("Failed to send: {}", e))?;
+    let n = stream.read(&mut buf).map_err(|e| tr!("Failed to read: {}", e))?;
+    let uidl_resp = String::from_utf8_lossy(&buf[..n]).to_string();
+    let mut server_uids = parse_uidl_response(&uidl_resp);
+
+    // Sort newest first
+    server_uids.sort_by(|a, b| b.0.cmp(&a.0));
+
+    // Apply skip + limit
+    let page: Vec<(u32, String)> = server_uids
+        .into_iter()
+        .skip(skip as usize)
+        .take(max_emails as usize)
+        .collect();
+
+    println!("[SYNC] POP3 plain: skip={} max={} page_count={}", skip, max_emails, page.len());
+
+    if page.is_empty() {
+        stream.write_all(b"QUIT\r\n").ok();
+        return Ok(Vec::new());
+    }
+
+    let emails_dir = PathBuf::from(vault_path).join("Mailbox").join(account_dir);
+    fs::create_dir_all(&emails_dir).map_err(|e| tr!("Failed to create directory: {}", e))?;
+
+    let mut emails = Vec::new();
+
+    for (seq, uid_string) in page {
+        let retr_cmd = format!("RETR {}\r\n", seq);
+        stream.write_all(retr_cmd.as_bytes()).map_err(|e| tr!("Failed to send: {}", e))?;
+
+        let mut response = Vec::new();
+        loop {
+            match stream.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    response.extend_from_slice(&buf[..n]);
+                    let resp_str = String::from_utf8_lossy(&response);
+                    if resp_str.contains("\r\n.\r\n") || resp_str.contains("\n.\n") {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+
+        let resp_str = String::from_utf8_lossy(&response);
+        let raw_email: &[u8] = if let Some(idx) = resp_str.find("\r\n") {
+            &response[idx + 2..]
+        } else {
+            &response[..]
+        };
+
+        let (email_msg, message_id) = parse_pop3_email_with_parser(raw_email, account_dir, seq, Some(uid_string.clone()));
+
+        let eml_filename = message_id.clone().unwrap_or_else(|| seq.to_string());
+        let safe_filename = eml_filename.chars().filter(|c| c.is_alphanumeric() || *c == '@' || *c == '.' || *c == '-' || *c == '_').take(100).collect::<String>();
+        let eml_path = emails_dir.join(format!("{}.eml", safe_filename));
+        fs::write(&eml_path, raw_email).map_err(|e| tr!("Failed to save EML file: {}", e))?;
+
+        emails.push(email_msg);
+    }
+
+    stream.write_all(b"QUIT\r\n").ok();
+
+    Ok(emails)
+}
+
+/// Parse a POP3 email using mail-parser for proper MIME handling
+/// Returns (EmailMessage, Option<Message-ID>)
+pub(crate) fn parse_pop3_email_with_parser(raw: &[u8], folder: &str, seq: u32, uid_string: Option<String>) -> (EmailMessage, Option<String>) {
+    use mail_parser::MessageParser;
+
+    let parser = MessageParser::default();
+    if let Some(message) = parser.parse(raw) {
+        let subject = message.subject().unwrap_or("").to_string();
+        let from = message.from().and_then(|a| a.first())
+            .map(|a| {
+                if let Some(name) = a.name() {
+                    if let Some(addr) = a.address() {
+                        format!("{} <{}>", name, addr)
+                    } else {
+                        name.to_string()
+                    }
+                } else {
+                    a.address().unwrap_or("").to_string()
+                }
+            })
+            .unwrap_or_default();
+        let to = message.to().and_then(|a| a.first())
+            .map(|a| a.address().unwrap_or("").to_string())
+            .unwrap_or_default();
+        let date = message.date()
+            .map(|d| d.to_rfc3339())
+            .unwrap_or_default();
+        let body_text = message.body_text(0).map(|t| t.to_string());
+        let body_html = message.body_html(0).map(|h| h.to_string());
+
+        // Extract Message-ID for unique filename
+        let message_id = message.message_id().map(|id| {
+            let id_str = id.to_string();
+            // Sanitize: remove < > brackets and invalid chars
+            id_str.trim_matches(|c| c == '<' || c == '>')
+                .chars()
+                .filter(|c| c.is_alphanumeric() || *c == '@' || *c == '.' || *c == '-' || *c == '_')
+                .take(100)
+                .collect()
+        });
+
+        let email_msg = EmailMessage {
+            id: message_id.clone().unwrap_or_else(|| format!("{}_{}", folder, seq)),
+            uid: seq,
+            uid_string,
+            from,
+            to,
+            subject,
+            date,
+            body_text,
+            body_html,
+            attachments: attachments(&message),
+            flags: vec![],
+            folder: folder.to_string(),
+            remote_blocked: 0,
+            trackers: vec![],
+            list_id: list_id(raw),
+            in_reply_to: in_reply_to(raw),
+            replies: vec![],
+        };
+
+        (email_msg, message_id)
+    } else {
+        // Fallback to basic header parsing
+        let text = String::from_utf8_lossy(raw);
+        parse_pop3_email_basic_raw(&text, folder, seq, None)
+    }
+}
+
+fn parse_pop3_email_basic(response: &str, folder: &str, seq: u32) -> EmailMessage {
+    let (msg, _) = parse_pop3_email_basic_raw(response, folder, seq, None);
+    msg
+}
+
+fn parse_pop3_email_basic_raw(response: &str, folder: &str, seq: u32, uid_string: Option<String>) -> (EmailMessage, Option<String>) {
+    let mut from = String::new();
+    let mut to = String::new();
+    let mut subject = String::new();
+    let mut date = String::new();
+    let mut message_id: Option<String> = None;
+
+    for line in response.lines() {
+        let lower = line.to_lowercase();
+        if lower.starts_with("from:") {
+            from = line[5..].trim().to_string();
+        } else if lower.starts_with("to:") {
+            to = line[3..].trim().to_string();
+        } else if lower.starts_with("subject:") {
+            subject = line[8..].trim().to_string();
+        } else if lower.starts_with("date:") {
+            date = line[5..].trim().to_string();
+        } else if lower.starts_with("message-id:") {
+            let id = line[11..].trim().to_string();
+            message_id = Some(id.trim_matches(|c| c == '<' || c == '>').to_string());
+        }
+    }
+
+    let msg_id = message_id.clone().unwrap_or_else(|| format!("{}_{}", folder, seq));
+    let email_msg = EmailMessage {
+        id: msg_id,
+        uid: seq,
+        uid_string,
+        from,
+        to,
+        subject,
+        date,
+        body_text: None,
+        body_html: None,
+        attachments: vec![],
+        flags: vec![],
+        folder: folder.to_string(),
+        remote_blocked: 0,
+        trackers: vec![],
+        list_id: None,
+        in_reply_to: None,
+        replies: vec![],
+    };
+
+    (email_msg, message_id)
+}
+
+// ── Helper functions for EML file storage ─────────────────────────────────────
+
+/// Load existing email UIDs from local index (using string UID from UIDL)
+fn load_local_uids(vault_path: &str, folder: &str) -> std::collections::HashSet<String> {
+    let index_path = PathBuf::from(vault_path)
+        .join("Mailbox")
+        .join(folder)
+        .join("index.json");
+
+    if !index_path.exists() {
+        return std::collections::HashSet::new();
+    }
+
+    if let Ok(content) = fs::read_to_string(&index_path) {
+        if let Ok(emails) = serde_json::from_str::<Vec<EmailMessage>>(&content) {
+            // Use uid_string if available, otherwise fall back to uid
+            return emails.iter()
+                .filter_map(|e| e.uid_string.clone().or_else(|| Some(e.uid.to_string())))
+                .collect();
+        }
+    }
+
+    std::collections::HashSet::new()
+}
+
+/// Parse UIDL response from POP3 server
+/// Returns vector of (message_number, unique_id) tuples
+fn parse_uidl_response(response: &str) -> Vec<(u32, String)> {
+    let mut result = Vec::new();
+
+    for line in response.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("+OK") {
+            continue;
+        }
+        if line == "." {
+            break;
+        }
+
+        // Format: "1 unique_id_12345"
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() >= 2 {
+            if let Ok(seq) = parts[0].parse::<u32>() {
+                let uid = parts[1].to_string();
+                result.push((seq, uid));
+            }
+        }
+    }
+
+    result
+}
+
+/// Load existing emails from local storage
+pub(crate) fn load_existing_emails(vault_path: &str, folder: &str) -> Result<Vec<EmailMessage>, String> {
+    let index_path = PathBuf::from(vault_path)
+        .join("Mailbox")
+        .join(folder)
+        .join("index.json");
+
+    if !index_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&index_path).map_err(|e| tr!("Failed to read: {}", e))?;
+    let emails: Vec<EmailMessage> = serde_json::from_str(&content).map_err(|e| tr!("Failed to parse: {}", e))?;
+
+    Ok(emails)
+}
+
+pub(crate) fn read_response<T: Read>(stream: &mut T) -> Result<String, String> {
+    let mut buf = [0u8; 4096];
+    let n = stream.read(&mut buf).map_err(|e| tr!("Failed to read: {}", e))?;
+    Ok(String::from_utf8_lossy(&buf[..n]).to_string())
+}
+
+// ── Sending ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmtpConfig {
+    pub from_email: String,
+    pub from_name: String,
+    pub password: String,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SendEmailRequest {
+    pub smtp: SmtpConfig,
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+    pub in_reply_to: Option<String>,
+    /// With these, `identity_id` (else the account's default identity) sets
+    /// the sender, signature and where a sent copy is filed
+    #[serde(default)]
+    pub vault_path: Option<String>,
+    #[serde(default)]
+    pub account_id: Option<String>,
+    #[serde(default)]
+    pub identity_id: Option<String>,
+    /// Wait for a reply by this date (YYYY-MM-DD); needs `vault_path`
+    #[serde(default)]
+    pub follow_up_by: Option<String>,
+    /// Files to attach from the vault, absolute or relative to `vault_path`.
+    /// Read when the message is sent, so a queued message sends them as they
+    /// are then.
+    #[serde(default)]
+    pub attachments: Vec<String>,
+    /// HTML version of `body`; the message is then multipart/alternative
+    #[serde(default)]
+    pub body_html: Option<String>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn email(uid: u32) -> EmailMessage {
+        serde_json::from_value(serde_json::json!({
+            "id": format!("INBOX_{uid}"), "uid": uid, "uidString": null, "from": "", "to": "", "subject": "",
+            "date": "", "bodyText": null, "bodyHtml": null, "attachments": [], "flags": [], "folder": "INBOX",
+        }))
+        .unwrap()
+    }
+
+    fn uids(arrived: &[&EmailMessage]) -> Vec<u32> {
+        arrived.iter().map(|e| e.uid).collect()
+    }
+
+    #[test]
+    fn test_arrivals_past_last_uid() {
+        let emails: Vec<EmailMessage> = [12, 11, 10, 9].into_iter().map(email).collect();
+
+        // First sync only sets the baseline
+        let (arrived, state) = arrivals(None, 7, &emails);
+        assert!(arrived.is_empty());
+        assert_eq!((state.uid_validity, state.last_uid), (7, 12));
+
+        let seen = FolderSyncState { last_uid: 10, ..state };
+        let (arrived, state) = arrivals(Some(&seen), 7, &emails);
+        assert_eq!(uids(&arrived), vec![12, 11]);
+        assert_eq!(state.last_uid, 12);
+
+        // Nothing newer keeps the mark; a new UIDVALIDITY starts over
+        let (arrived, state) = arrivals(Some(&state), 7, &emails[2..]);
+        assert!(arrived.is_empty());
+        assert_eq!(state.last_uid, 12);
+        let (arrived, state) = arrivals(Some(&state), 8, &emails[2..]);
+        assert!(arrived.is_empty());
+        assert_eq!((state.uid_validity, state.last_uid), (8, 10));
+    }
+
+    #[test]
+    fn test_header_message_id() {
+        let raw = b"From: a@b.c\r\nMessage-ID: <abc.123@mail.example>\r\nSubject: x\r\n\r\nMessage-ID: <body@x>";
+        assert_eq!(header_message_id(raw).as_deref(), Some("abc.123@mail.example"));
+        let folded = b"Subject: x\nMessage-Id:\n <folded@x>\n\nbody";
+        assert_eq!(header_message_id(folded).as_deref(), Some("folded@x"));
+        assert_eq!(header_message_id(b"Subject: x\n\nMessage-ID: <body@x>"), None);
+        assert_eq!(normalize_message_id(" <a@b> "), "a@b");
+        let folded = b"References: <one@x>\r\n <two@x>\r\nSubject: Re: plan\r\n\r\n";
+        assert_eq!(header(folded, "references").as_deref(), Some("<one@x> <two@x>"));
+    }
+
+    #[test]
+    fn test_reply_threading() {
+        let raw = b"Message-ID: <c@x.org>\r\nIn-Reply-To: <b@x.org>\r\nReferences: <a@x.org>\r\n <b@x.org>\r\nSubject: Re: Plan\r\n\r\nbody";
+        assert_eq!(in_reply_to(raw).as_deref(), Some("b@x.org"));
+        assert_eq!(reply_headers(raw), Some(("<c@x.org>".to_string(), "<a@x.org> <b@x.org> <c@x.org>".to_string())));
+        // First reply: the chain starts at the original
+        let first = b"Message-ID: <a@x.org>\r\nSubject: Plan\r\n\r\nbody";
+        assert_eq!(reply_headers(first), Some(("<a@x.org>".to_string(), "<a@x.org>".to_string())));
+        assert_eq!(in_reply_to(first), None);
+
+        assert_eq!(reply_subject("Plan"), "Re: Plan");
+        assert_eq!(reply_subject("Re: RE[2]: Aw: Plan"), "Re: Plan");
+        assert_eq!(reply_subject("回复：周报"), "Re: 周报");
+        assert_eq!(reply_subject("Read this"), "Re: Read this");
+    }
+
+    #[test]
+    fn test_list_id() {
+        let raw = b"From: news@weekly.example\r\nList-Id: \"Weekly News\" <weekly.news.example.com>\r\n\r\nbody";
+        assert_eq!(list_id(raw).as_deref(), Some("weekly.news.example.com"));
+        assert_eq!(list_id(b"List-ID: plain.list.example\n\n").as_deref(), Some("plain.list.example"));
+        assert_eq!(list_id(b"Subject: hi\n\nList-Id: <body.example>"), None);
+    }
+
+    #[test]
+    fn test_attachments() {
+        let raw = b"From: a@x.org\r\nSubject: Invoice\r\nContent-Type: multipart/mixed; boundary=\"b\"\r\n\r\n\
+--b\r\nContent-Type: text/plain\r\n\r\nSee attached.\r\n\
+--b\r\nContent-Type: application/pdf; name=\"invoice.pdf\"\r\nContent-Disposition: attachment; filename=\"invoice.pdf\"\r\nContent-Transfer-Encoding: base64\r\n\r\nJVBERi0xLjQK\r\n\
+--b\r\nContent-Type: application/octet-stream\r\nContent-Disposition: attachment\r\n\r\nraw\r\n\
+--b--\r\n";
+        let message = MessageParser::default().parse(&raw[..]).unwrap();
+        let list = attachments(&message);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0], Attachment { name: "invoice.pdf".into(), size: 9, content_type: "application/pdf".into() });
+        assert_eq!(list[1].name, "attachment-2");
+
+        let (info, bytes) = attachment(raw, 0).unwrap();
+        assert_eq!((info.name.as_str(), bytes.as_slice()), ("invoice.pdf", &b"%PDF-1.4\n"[..]));
+        assert!(attachment(raw, 2).is_none());
+    }
+
+    #[test]
+    fn test_duplicates_and_unified_inbox() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path().to_string_lossy().to_string();
+        let cache = |account: &str, messages: &[(&str, &str, &str, &str)]| {
+            let dir = vault.path().join(MAILBOX_DIR).join(account);
+            fs::create_dir_all(&dir).unwrap();
+            let index: Vec<serde_json::Value> = messages
+                .iter()
+                .map(|(id, message_id, date, folder)| {
+                    fs::write(dir.join(format!("{id}.eml")), format!("Message-ID: <{message_id}>\r\nSubject: Weekly\r\n\r\nbody")).unwrap();
+                    serde_json::json!({"id": id, "uid": 1, "from": "news@x.org", "to": "me", "subject": "Weekly", "date": date,
+                        "attachments": [], "flags": [], "folder": folder})
+                })
+                .collect();
+            fs::write(dir.join("index.json"), serde_json::to_string(&index).unwrap()).unwrap();
+        };
+        cache("home", &[("h1", "weekly-1@x.org", "Mon, 2 Mar 2026 08:00:00 +0000", "INBOX"), ("h2", "solo@x.org", "Tue, 3 Mar 2026 08:00:00 +0000", "INBOX")]);
+        cache("work", &[("w1", "weekly-1@x.org", "Mon, 2 Mar 2026 08:00:05 +0000", "INBOX"), ("w2", "old@x.org", "Sun, 1 Mar 2026 08:00:00 +0000", "Archive")]);
+
+        let groups = find_duplicates(&v);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].message_id, "weekly-1@x.org");
+        let copies: Vec<(&str, &str)> = groups[0].copies.iter().map(|c| (c.account.as_str(), c.email_id.as_str())).collect();
+        assert_eq!(copies, vec![("home", "h1"), ("work", "w1")]);
+
+        let merged = unified_inbox(&v, "INBOX", true);
+        let rows: Vec<(&str, &str)> = merged.iter().map(|r| (r.account.as_str(), r.email.id.as_str())).collect();
+        assert_eq!(rows, vec![("home", "h2"), ("home", "h1")]);
+        assert_eq!(merged[1].also_in, vec!["work".to_string()]);
+        assert_eq!(unified_inbox(&v, "inbox", false).len(), 3);
+    }
+
+    #[test]
+    fn test_folders_synced_into_their_own_directory() {
+        assert_eq!(folder_dir("a1", "INBOX"), "a1/INBOX");
+        assert_eq!(folder_dir("a1", "[Gmail]/Sent Mail"), "a1/[Gmail]/Sent Mail");
+        assert_eq!((folder_dir("a1", "../../x"), folder_dir("a1", "..")), ("a1/x".to_string(), "a1".to_string()));
+
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path().to_string_lossy().to_string();
+        let accounts = vault.path().join(ACCOUNTS_DIR);
+        fs::create_dir_all(&accounts).unwrap();
+        // No password: the sync fails before connecting, but reports where it would cache
+        fs::write(accounts.join("a1.json"), r#"{"id":"a1","email":"me@x.com","imapHost":"imap.x.com","imapPort":"993","protocol":"imap"}"#).unwrap();
+        let account = &load_accounts(&v).unwrap()[0];
+        let report = sync_folder_into(&v, account, "[Gmail]/Sent Mail", &folder_dir(&account.id, "[Gmail]/Sent Mail"), 20);
+        assert_eq!((report.dir.as_str(), report.error.is_some()), ("a1/[Gmail]/Sent Mail", true));
+        assert_eq!(sync_folder(&v, account, "INBOX", 20).dir, "a1");
+
+        // The folder's cache is found next to the account's
+        let dir = vault.path().join(MAILBOX_DIR).join(&report.dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(eml_path(&dir, "[Gmail]/Sent Mail_7"), "Message-ID: <sent-7@x.com>\r\nSubject: Hi\r\n\r\nbody").unwrap();
+        let index = serde_json::json!([{"id": "[Gmail]/Sent Mail_7", "uid": 7, "from": "me@x.com", "to": "you", "subject": "Hi",
+            "date": "Mon, 2 Mar 2026 08:00:00 +0000", "attachments": [], "flags": [], "folder": "[Gmail]/Sent Mail"}]);
+        fs::write(dir.join("index.json"), index.to_string()).unwrap();
+        let found = find_message(&v, "<sent-7@x.com>").unwrap();
+        assert_eq!((found.account.as_str(), found.email_id.as_str()), ("a1/[Gmail]/Sent Mail", "[Gmail]/Sent Mail_7"));
+        assert_eq!(unified_inbox(&v, "[Gmail]/Sent Mail", false).len(), 1);
+    }
+
+    #[test]
+    fn test_set_account_folders() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path().to_string_lossy().to_string();
+        let dir = vault.path().join(ACCOUNTS_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a1.json"), r#"{"id":"a1","name":"Work","email":"me@x.com","imapHost":"imap.x.com","imapPort":"993","protocol":"imap","folders":"","lastSync":"yesterday"}"#).unwrap();
+
+        assert_eq!(synced_folders(&load_accounts(&v).unwrap()[0]), vec!["INBOX"]);
+        set_account_folders(&v, "a1", &["INBOX".into(), " Sent ".into(), "".into()]).unwrap();
+        assert_eq!(synced_folders(&load_accounts(&v).unwrap()[0]), vec!["INBOX", "Sent"]);
+        let doc: serde_json::Value = serde_json::from_str(&fs::read_to_string(dir.join("a1.json")).unwrap()).unwrap();
+        assert_eq!((doc["folders"].as_str(), doc["lastSync"].as_str()), (Some("INBOX,Sent"), Some("yesterday")));
+        assert!(set_account_folders(&v, "nope", &[]).is_err());
+    }
+
+    #[test]
+    fn test_sync_interval() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path().to_string_lossy().to_string();
+        let dir = vault.path().join(ACCOUNTS_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a1.json"), r#"{"id":"a1","email":"me@x.com","imapHost":"imap.x.com","folders":"INBOX,Archive,Sent","syncIntervals":{"inbox":5,"Archive":1440}}"#).unwrap();
+        fs::write(dir.join("a2.json"), r#"{"id":"a2","email":"me@y.com","imapHost":"imap.y.com","syncMinutes":0}"#).unwrap();
+
+        let accounts = load_accounts(&v).unwrap();
+        let a1 = accounts.iter().find(|a| a.id == "a1").unwrap();
+        assert_eq!((a1.sync_interval("INBOX", 15), a1.sync_interval("Archive", 15), a1.sync_interval("Sent", 15)), (5, 1440, 15));
+        assert_eq!(accounts.iter().find(|a| a.id == "a2").unwrap().sync_interval("INBOX", 15), 0);
+    }
+
+    #[test]
+    fn test_add_gmail_account() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path().to_string_lossy().to_string();
+        let id = add_gmail_account(&v, "Me.Two@gmail.com").unwrap();
+        assert_eq!(id, "gmail-me-two-gmail-com");
+        assert_eq!(add_gmail_account(&v, "me.two@gmail.com").unwrap(), id);
+        let accounts = load_accounts(&v).unwrap();
+        assert_eq!(accounts.len(), 1);
+        assert_eq!((accounts[0].imap.protocol.as_deref(), synced_folders(&accounts[0])), (Some("gmail"), vec!["INBOX".to_string()]));
+    }
+
+    #[test]
+    fn test_identities() {
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path().to_string_lossy().to_string();
+        let alias = EmailIdentity { address: "me+news@example.com".into(), name: "Me".into(), default: true, ..Default::default() };
+        let work = EmailIdentity { address: "me@work.example".into(), signature: "Me\nWork Inc.".into(), ..Default::default() };
+
+        assert_eq!(save_identity(&v, "acc", alias).unwrap().id, "me-news-example-com");
+        save_identity(&v, "acc", work.clone()).unwrap();
+        assert!(save_identity(&v, "acc", EmailIdentity { address: "not an address".into(), ..Default::default() }).is_err());
+        assert_eq!(resolve_identity(&v, "acc", None).unwrap().unwrap().address, "me+news@example.com");
+
+        // A new default replaces the old one
+        save_identity(&v, "acc", EmailIdentity { default: true, ..work }).unwrap();
+        assert_eq!(load_identities(&v, "acc").iter().filter(|i| i.default).count(), 1);
+        assert_eq!(resolve_identity(&v, "acc", None).unwrap().unwrap().id, "me-work-example");
+        assert!(resolve_identity(&v, "acc", Some("nope")).is_err());
+        assert!(resolve_identity(&v, "other", None).unwrap().is_none());
+
+        delete_identity(&v, "acc", "me-news-example-com").unwrap();
+        assert_eq!(load_identities(&v, "acc").len(), 1);
+        assert_eq!(with_signature("Hi\n\n", "-- \nMe\n"), "Hi\n\n-- \nMe\n");
+        assert_eq!(with_signature("Hi", ""), "Hi");
+        assert_eq!(html_with_signature("<p>Hi</p>\n", "Me & Co\n<Work>"), "<p>Hi</p>\n<div class=\"signature\">-- <br>\nMe &amp; Co<br>\n&lt;Work&gt;</div>\n");
+        assert_eq!(html_with_signature("<html><BODY><p>Hi</p></BODY></html>", "Me"), "<html><BODY><p>Hi</p><div class=\"signature\">-- <br>\nMe</div>\n</BODY></html>");
+    }
+
+    #[test]
+    fn test_syncs_of_an_account_wait_for_each_other() {
+        let lock = sync_lock("serial");
+        assert!(Arc::ptr_eq(&lock, &sync_lock("serial")));
+        let held = hold(&lock);
+        // Another account syncs meanwhile
+        assert!(sync_lock("other").try_lock().is_ok());
+
+        let started = std::time::Instant::now();
+        let waiter = std::thread::spawn(|| drop(hold(&sync_lock("serial"))));
+        std::thread::sleep(Duration::from_millis(50));
+        drop(held);
+        waiter.join().unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(50));
+    }
+}
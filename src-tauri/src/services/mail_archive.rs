@@ -0,0 +1,509 @@
+//! Local mail archives from other clients, imported into the Mailbox cache
+//! so years of old mail search like synced mail without re-downloading:
+//!
+//! - Apple Mail's store (`~/Library/Mail/V10/…`, or any folder of it): one
+//!   `.emlx` per message under `<Mailbox>.mbox` folders. An `.emlx` is a
+//!   byte count line, the RFC 822 message, then a plist carrying its flags.
+//! - Outlook for Mac's `.olm` export: a zip with one XML file per message
+//!   under `com.microsoft.__Messages/<Folder>/`. Those are turned back into
+//!   RFC 822 messages, attachments included.
+//!
+//! Messages are stored like imported .eml files (`<Message-ID>.eml` plus an
+//! index.json entry) with read, answered and flagged state kept. Ones
+//! already cached are skipped, so an archive can be imported again.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::carddav::elements;
+use super::mail::{self, load_existing_emails, parse_pop3_email_with_parser, save_index_json, EmailMessage};
+use super::mail_html::decode_entities;
+
+const EMLX: &str = ".emlx";
+const MBOX_SUFFIX: &str = ".mbox";
+/// Folder of the message XML files in an .olm
+const OLM_MESSAGES: &str = "com.microsoft.__Messages/";
+/// Apple Mail flag bits
+const EMLX_READ: u64 = 1;
+const EMLX_ANSWERED: u64 = 1 << 2;
+const EMLX_FLAGGED: u64 = 1 << 4;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveMailbox {
+    /// As in the archive, nested mailboxes joined with `/`
+    pub name: String,
+    /// Cache folder its messages are filed under
+    pub folder: String,
+    pub messages: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveImportReport {
+    pub imported: usize,
+    /// Already cached under the same Message-ID
+    pub duplicates: usize,
+    /// Mailboxes read, with how many messages each added
+    pub mailboxes: Vec<ArchiveMailbox>,
+    /// (message in the archive, error)
+    pub failed: Vec<(String, String)>,
+    /// Counted only; nothing was written
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// A message taken out of an archive
+struct Archived {
+    /// Where it was, for error reports
+    source: String,
+    mailbox: String,
+    raw: Vec<u8>,
+    flags: Vec<String>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// The mailboxes in an Apple Mail folder or .olm file, by name
+pub fn mailboxes(path: &Path) -> Result<Vec<ArchiveMailbox>, String> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    if path.is_dir() {
+        for file in emlx_files(path) {
+            *counts.entry(emlx_mailbox(path, &file)).or_default() += 1;
+        }
+    } else if is_olm(path) {
+        let zip = open_olm(path)?;
+        for name in zip.file_names() {
+            if let Some(mailbox) = olm_mailbox(name) {
+                *counts.entry(mailbox).or_default() += 1;
+            }
+        }
+    } else {
+        return Err(tr!("Unrecognized mail archive: {}", path.display()));
+    }
+    Ok(counts.into_iter().map(|(name, messages)| ArchiveMailbox { folder: folder_of(&name), name, messages }).collect())
+}
+
+/// Import the `selected` mailboxes (all when empty) into Mailbox/<account_id>/;
+/// `dry_run` reads the archive and counts without writing anything
+pub fn import(vault_path: &str, account_id: &str, path: &Path, selected: &[String], dry_run: bool) -> Result<ArchiveImportReport, String> {
+    let wanted = |mailbox: &str| selected.is_empty() || selected.iter().any(|s| s == mailbox);
+    let dir = PathBuf::from(vault_path).join("Mailbox").join(account_id);
+    if !dry_run {
+        fs::create_dir_all(&dir).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let mut emails = load_existing_emails(vault_path, account_id)?;
+    let mut known: HashSet<String> = emails.iter().map(|e| e.id.clone()).collect();
+    let mut report = ArchiveImportReport { dry_run, ..Default::default() };
+    let mut added: BTreeMap<String, usize> = BTreeMap::new();
+
+    let mut store = |message: Result<Archived, (String, String)>, report: &mut ArchiveImportReport| {
+        let message = match message {
+            Ok(message) => message,
+            Err(failed) => return report.failed.push(failed),
+        };
+        let folder = folder_of(&message.mailbox);
+        let seq = emails.iter().map(|e| e.uid).max().unwrap_or(0) + 1;
+        let (mut email, message_id) = parse_pop3_email_with_parser(&message.raw, &folder, seq, None);
+        if message_id.is_none() {
+            // Without one the id would be per-run; derive it from the message
+            email.id = format!("archive_{}", &hex(&Sha256::digest(&message.raw))[..24]);
+        }
+        if !known.insert(email.id.clone()) {
+            report.duplicates += 1;
+            return;
+        }
+        if !dry_run {
+            if let Err(e) = fs::write(mail::eml_path(&dir, &email.id), &message.raw) {
+                return report.failed.push((message.source, tr!("Failed to save EML file: {}", e)));
+            }
+        }
+        email.uid_string = message_id;
+        email.flags = message.flags;
+        emails.push(email);
+        report.imported += 1;
+        *added.entry(message.mailbox).or_default() += 1;
+    };
+
+    if path.is_dir() {
+        for file in emlx_files(path) {
+            let mailbox = emlx_mailbox(path, &file);
+            if !wanted(&mailbox) {
+                continue;
+            }
+            let source = file.to_string_lossy().to_string();
+            let message = fs::read(&file)
+                .map_err(|e| tr!("Failed to read email: {}", e))
+                .and_then(|bytes| parse_emlx(&bytes).ok_or_else(|| tr!("Invalid .emlx file")))
+                .map(|(raw, flags)| Archived { source: source.clone(), mailbox, raw, flags })
+                .map_err(|e| (source, e));
+            store(message, &mut report);
+        }
+    } else if is_olm(path) {
+        let mut zip = open_olm(path)?;
+        let names: Vec<String> = zip.file_names().map(str::to_string).collect();
+        for name in names {
+            let Some(mailbox) = olm_mailbox(&name).filter(|m| wanted(m)) else { continue };
+            let message = read_entry(&mut zip, &name)
+                .and_then(|xml| {
+                    let xml = String::from_utf8_lossy(&xml).to_string();
+                    olm_message(&xml, &mut |url| read_entry(&mut zip, url).ok())
+                })
+                .map(|(raw, flags)| Archived { source: name.clone(), mailbox, raw, flags })
+                .map_err(|e| (name.clone(), e));
+            store(message, &mut report);
+        }
+    } else {
+        return Err(tr!("Unrecognized mail archive: {}", path.display()));
+    }
+
+    // Newest first, like a synced folder
+    let stamp = |e: &EmailMessage| DateTime::parse_from_rfc3339(&e.date).map(|d| d.timestamp()).unwrap_or(0);
+    emails.sort_by_key(|e| std::cmp::Reverse(stamp(e)));
+    if !dry_run {
+        save_index_json(&dir, &emails)?;
+    }
+    report.mailboxes = added.into_iter().map(|(name, messages)| ArchiveMailbox { folder: folder_of(&name), name, messages }).collect();
+    Ok(report)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Apple Mail
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn emlx_files(root: &Path) -> Vec<PathBuf> {
+    WalkDir::new(root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.file_name().to_string_lossy().ends_with(EMLX))
+        .map(|e| e.into_path())
+        .collect()
+}
+
+/// `Work.mbox/Projects.mbox/<uuid>/Data/…/1234.emlx` → `Work/Projects`;
+/// the root itself counts when it is a `.mbox`
+fn emlx_mailbox(root: &Path, file: &Path) -> String {
+    let base = root.parent().unwrap_or(root);
+    let rel = file.strip_prefix(base).unwrap_or(file);
+    let names: Vec<String> = rel
+        .components()
+        .filter_map(|c| c.as_os_str().to_string_lossy().strip_suffix(MBOX_SUFFIX).map(str::to_string))
+        .collect();
+    if names.is_empty() { "INBOX".to_string() } else { names.join("/") }
+}
+
+/// The message and its flags
+fn parse_emlx(bytes: &[u8]) -> Option<(Vec<u8>, Vec<String>)> {
+    let line_end = bytes.iter().position(|b| *b == b'\n')?;
+    let length: usize = String::from_utf8_lossy(&bytes[..line_end]).trim().parse().ok()?;
+    let start = line_end + 1;
+    let message = bytes.get(start..start + length)?.to_vec();
+    let plist = String::from_utf8_lossy(&bytes[start + length..]);
+    let bits: u64 = plist
+        .split("<key>flags</key>")
+        .nth(1)
+        .and_then(|rest| elements(rest, "integer").first().and_then(|n| n.trim().parse().ok()))
+        .unwrap_or(0);
+    let flags = [(EMLX_READ, "Seen"), (EMLX_ANSWERED, "Answered"), (EMLX_FLAGGED, "Flagged")]
+        .into_iter()
+        .filter(|(bit, _)| bits & bit != 0)
+        .map(|(_, flag)| flag.to_string())
+        .collect();
+    Some((message, flags))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Outlook
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn is_olm(path: &Path) -> bool {
+    path.is_file() && path.extension().is_some_and(|x| x.eq_ignore_ascii_case("olm"))
+}
+
+fn open_olm(path: &Path) -> Result<zip::ZipArchive<fs::File>, String> {
+    let file = fs::File::open(path).map_err(|e| tr!("Failed to read: {}", e))?;
+    zip::ZipArchive::new(file).map_err(|e| tr!("Unrecognized mail archive: {}", e))
+}
+
+fn read_entry(zip: &mut zip::ZipArchive<fs::File>, name: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    zip.by_name(name).map_err(|e| tr!("Failed to read: {}", e))?.read_to_end(&mut bytes).map_err(|e| tr!("Failed to read: {}", e))?;
+    Ok(bytes)
+}
+
+/// `Accounts/me@x.com/com.microsoft.__Messages/Inbox/Projects/message_00012.xml` → `Inbox/Projects`
+fn olm_mailbox(entry: &str) -> Option<String> {
+    let (_, rest) = entry.split_once(OLM_MESSAGES)?;
+    let (mailbox, file) = rest.rsplit_once('/')?;
+    (file.starts_with("message_") && file.ends_with(".xml") && !mailbox.is_empty()).then(|| mailbox.to_string())
+}
+
+/// An .olm message XML as an RFC 822 message, and its flags. `attachment`
+/// reads an `OPFAttachmentURL` from the archive.
+fn olm_message(xml: &str, attachment: &mut dyn FnMut(&str) -> Option<Vec<u8>>) -> Result<(Vec<u8>, Vec<String>), String> {
+    let field = |name: &str| {
+        elements(xml, name).first().map(|v| decode_entities(strip_cdata(v).trim())).filter(|v| !v.is_empty())
+    };
+    let addresses = |name: &str| -> Vec<String> {
+        let list = elements(xml, name).first().copied().unwrap_or_default();
+        let names = attributes(list, "emailAddress", "OPFContactEmailAddressName");
+        attributes(list, "emailAddress", "OPFContactEmailAddressAddress")
+            .into_iter()
+            .enumerate()
+            .map(|(i, address)| match names.get(i).filter(|n| !n.is_empty() && **n != address) {
+                Some(name) => format!("{} <{address}>", encode_word(name)),
+                None => address,
+            })
+            .collect()
+    };
+    let html = field("OPFMessageCopyHTMLBody");
+    let text = field("OPFMessageCopyBody");
+    if html.is_none() && text.is_none() && field("OPFMessageCopySubject").is_none() {
+        return Err(tr!("Not an Outlook message"));
+    }
+
+    let mut headers = Vec::new();
+    let from = addresses("OPFMessageCopyFromAddresses");
+    if let Some(from) = from.first() {
+        headers.push(format!("From: {from}"));
+    }
+    for (header, name) in [("To", "OPFMessageCopyToAddresses"), ("Cc", "OPFMessageCopyCCAddresses")] {
+        let list = addresses(name);
+        if !list.is_empty() {
+            headers.push(format!("{header}: {}", list.join(", ")));
+        }
+    }
+    headers.push(format!("Subject: {}", encode_word(&field("OPFMessageCopySubject").unwrap_or_default())));
+    let sent = field("OPFMessageCopySentTime").or_else(|| field("OPFMessageCopyReceivedTime"));
+    if let Some(date) = sent.as_deref().and_then(olm_date) {
+        headers.push(format!("Date: {date}"));
+    }
+    let message_id = field("OPFMessageCopyMessageID").unwrap_or_else(|| format!("<{}@olm.lifeos>", &hex(&Sha256::digest(xml.as_bytes()))[..24]));
+    headers.push(format!("Message-ID: {message_id}"));
+    if let Some(parent) = field("OPFMessageCopyInReplyTo") {
+        headers.push(format!("In-Reply-To: {parent}"));
+    }
+    headers.push("MIME-Version: 1.0".to_string());
+
+    let body = match (&html, &text) {
+        (Some(html), Some(text)) => {
+            let boundary = "lifeos-alt";
+            format!(
+                "Content-Type: multipart/alternative; boundary=\"{boundary}\"\r\n\r\n--{boundary}\r\n{}\r\n--{boundary}\r\n{}\r\n--{boundary}--\r\n",
+                part("text/plain; charset=utf-8", text.as_bytes()),
+                part("text/html; charset=utf-8", html.as_bytes())
+            )
+        }
+        (Some(html), None) => part("text/html; charset=utf-8", html.as_bytes()),
+        (None, text) => part("text/plain; charset=utf-8", text.as_deref().unwrap_or_default().as_bytes()),
+    };
+    let list = elements(xml, "OPFMessageCopyAttachmentList").first().copied().unwrap_or_default();
+    let urls = attributes(list, "messageAttachment", "OPFAttachmentURL");
+    let names = attributes(list, "messageAttachment", "OPFAttachmentName");
+    let types = attributes(list, "messageAttachment", "OPFAttachmentContentType");
+    let mut files = Vec::new();
+    for (i, url) in urls.iter().enumerate() {
+        if let Some(bytes) = attachment(url) {
+            let name = names.get(i).cloned().filter(|n| !n.is_empty()).unwrap_or_else(|| format!("attachment-{}", i + 1));
+            let kind = types.get(i).cloned().filter(|t| t.contains('/')).unwrap_or_else(|| "application/octet-stream".to_string());
+            files.push(format!(
+                "Content-Disposition: attachment; filename=\"{}\"\r\n{}",
+                encode_word(&name).replace('"', "'"),
+                part(&format!("{kind}; name=\"{}\"", encode_word(&name).replace('"', "'")), &bytes)
+            ));
+        }
+    }
+    let body = if files.is_empty() {
+        body
+    } else {
+        let boundary = "lifeos-mixed";
+        let mut mixed = format!("Content-Type: multipart/mixed; boundary=\"{boundary}\"\r\n\r\n--{boundary}\r\n{body}");
+        for file in files {
+            mixed.push_str(&format!("\r\n--{boundary}\r\n{file}"));
+        }
+        mixed.push_str(&format!("\r\n--{boundary}--\r\n"));
+        mixed
+    };
+
+    let truthy = |name: &str| field(name).is_some_and(|v| v == "1" || v.eq_ignore_ascii_case("true"));
+    let flags = [("OPFMessageGetIsRead", "Seen"), ("OPFMessageGetHasReplied", "Answered"), ("OPFMessageGetIsFlagged", "Flagged")]
+        .into_iter()
+        .filter(|(name, _)| truthy(name))
+        .map(|(_, flag)| flag.to_string())
+        .collect();
+    Ok((format!("{}\r\n{body}", headers.join("\r\n")).into_bytes(), flags))
+}
+
+/// A base64 MIME part: its Content-Type header and encoded body
+fn part(content_type: &str, bytes: &[u8]) -> String {
+    let encoded = STANDARD.encode(bytes);
+    let lines: Vec<&str> = encoded.as_bytes().chunks(76).map(|c| std::str::from_utf8(c).unwrap_or_default()).collect();
+    format!("Content-Type: {content_type}\r\nContent-Transfer-Encoding: base64\r\n\r\n{}\r\n", lines.join("\r\n"))
+}
+
+/// RFC 2047 encoded word for non-ASCII header text
+fn encode_word(text: &str) -> String {
+    if text.is_ascii() { text.to_string() } else { format!("=?UTF-8?B?{}?=", STANDARD.encode(text)) }
+}
+
+/// `2019-03-04T10:20:30` (UTC) or RFC 3339 → RFC 2822
+fn olm_date(value: &str) -> Option<String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|d| d.to_rfc2822())
+        .ok()
+        .or_else(|| NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), "%Y-%m-%dT%H:%M:%S").ok().map(|d| d.and_utc().to_rfc2822()))
+}
+
+fn strip_cdata(value: &str) -> &str {
+    value.trim().strip_prefix("<![CDATA[").and_then(|v| v.strip_suffix("]]>")).unwrap_or(value)
+}
+
+/// Value of `attr` on every `<tag …>` in `xml`, in order ("" where missing)
+fn attributes(xml: &str, tag: &str, attr: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut rest = xml;
+    while let Some(open) = rest.find('<') {
+        rest = &rest[open + 1..];
+        let Some(end) = rest.find('>') else { break };
+        let element = &rest[..end];
+        rest = &rest[end + 1..];
+        if element.split(|c: char| c.is_whitespace() || c == '/').next() != Some(tag) {
+            continue;
+        }
+        let value = element.split(&format!(" {attr}=")).nth(1).and_then(|after| {
+            let quote = after.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            after[1..].split(quote).next().map(decode_entities)
+        });
+        out.push(value.unwrap_or_default());
+    }
+    out
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// INBOX for the inbox under any name, else the mailbox name
+fn folder_of(mailbox: &str) -> String {
+    match mailbox.to_lowercase().as_str() {
+        "inbox" | "收件箱" => "INBOX".to_string(),
+        _ => mailbox.to_string(),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn emlx(message: &str, flags: u64) -> Vec<u8> {
+        format!("{}\n{message}<?xml version=\"1.0\"?>\n<plist version=\"1.0\"><dict><key>date-received</key><integer>1</integer><key>flags</key><integer>{flags}</integer></dict></plist>\n", message.len()).into_bytes()
+    }
+
+    #[test]
+    fn test_import_apple_mail() {
+        let store = tempfile::tempdir().unwrap();
+        let root = store.path().join("V10");
+        let write = |rel: &str, bytes: Vec<u8>| {
+            let path = root.join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, bytes).unwrap();
+        };
+        let message = |id: &str, subject: &str| format!("Message-ID: <{id}@example.com>\r\nFrom: Bob <bob@example.com>\r\nTo: me@example.com\r\nSubject: {subject}\r\nDate: Mon, 2 Jan 2012 09:00:00 +0000\r\n\r\nHello\r\n");
+        write("acct/INBOX.mbox/U1/Data/Messages/1.emlx", emlx(&message("a1", "Old news"), EMLX_READ | EMLX_FLAGGED));
+        write("acct/Work.mbox/Projects.mbox/U2/Data/Messages/2.emlx", emlx(&message("a2", "Plans"), 0));
+        write("acct/Work.mbox/Projects.mbox/U2/Data/Messages/3.partial.emlx", emlx(&message("a3", "More"), EMLX_ANSWERED));
+        write("acct/Work.mbox/Projects.mbox/U2/Data/Messages/4.emlx", b"garbage".to_vec());
+
+        let found = mailboxes(&root).unwrap();
+        assert_eq!(found.iter().map(|m| (m.name.as_str(), m.folder.as_str(), m.messages)).collect::<Vec<_>>(), vec![("INBOX", "INBOX", 1), ("Work/Projects", "Work/Projects", 3)]);
+
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path().to_string_lossy().to_string();
+        let preview = import(&v, "archive", &root, &["Work/Projects".to_string()], true).unwrap();
+        assert_eq!((preview.imported, preview.duplicates, preview.failed.len(), preview.dry_run), (2, 0, 1, true));
+        assert!(!vault.path().join("Mailbox").exists());
+        let report = import(&v, "archive", &root, &["Work/Projects".to_string()], false).unwrap();
+        assert_eq!((report.imported, report.duplicates, report.failed.len()), (2, 0, 1));
+        let report = import(&v, "archive", &root, &[], false).unwrap();
+        assert_eq!((report.imported, report.duplicates), (1, 2));
+
+        let emails = load_existing_emails(&v, "archive").unwrap();
+        let inbox = emails.iter().find(|e| e.subject == "Old news").unwrap();
+        assert_eq!((inbox.folder.as_str(), inbox.flags.clone()), ("INBOX", vec!["Seen".to_string(), "Flagged".to_string()]));
+        assert_eq!(emails.iter().find(|e| e.subject == "More").unwrap().flags, vec!["Answered"]);
+        assert!(vault.path().join("Mailbox/archive/a2@example.com.eml").exists());
+    }
+
+    #[test]
+    fn test_import_olm() {
+        let dir = tempfile::tempdir().unwrap();
+        let olm = dir.path().join("export.olm");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&olm).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        let base = "Accounts/me@example.com/com.microsoft.__Messages";
+        zip.start_file(format!("{base}/Inbox/message_00000.xml"), options).unwrap();
+        zip.write_all(
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?><emails><email>
+<OPFMessageCopySubject>季度 &amp; report</OPFMessageCopySubject>
+<OPFMessageCopySentTime>2015-06-01T08:30:00</OPFMessageCopySentTime>
+<OPFMessageCopyMessageID>&lt;q2@corp.example&gt;</OPFMessageCopyMessageID>
+<OPFMessageCopyFromAddresses><emailAddress OPFContactEmailAddressAddress="ann@corp.example" OPFContactEmailAddressName="Ann Lee"/></OPFMessageCopyFromAddresses>
+<OPFMessageCopyToAddresses><emailAddress OPFContactEmailAddressAddress="me@example.com"/></OPFMessageCopyToAddresses>
+<OPFMessageCopyHTMLBody><![CDATA[<p>See attached</p>]]></OPFMessageCopyHTMLBody>
+<OPFMessageGetIsRead>1</OPFMessageGetIsRead>
+<OPFMessageCopyAttachmentList><messageAttachment OPFAttachmentName="q2.csv" OPFAttachmentContentType="text/csv" OPFAttachmentURL="{base}/Inbox/com.microsoft.__Attachments/q2.csv"/></OPFMessageCopyAttachmentList>
+</email></emails>"#
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+        zip.start_file(format!("{base}/Inbox/com.microsoft.__Attachments/q2.csv"), options).unwrap();
+        zip.write_all(b"a,b\n1,2\n").unwrap();
+        zip.start_file(format!("{base}/Sent Items/message_00001.xml"), options).unwrap();
+        zip.write_all(b"<emails><email><OPFMessageCopySubject>Re: hi</OPFMessageCopySubject><OPFMessageCopyBody>ok</OPFMessageCopyBody></email></emails>").unwrap();
+        zip.finish().unwrap();
+
+        let found = mailboxes(&olm).unwrap();
+        assert_eq!(found.iter().map(|m| (m.folder.as_str(), m.messages)).collect::<Vec<_>>(), vec![("INBOX", 1), ("Sent Items", 1)]);
+
+        let vault = tempfile::tempdir().unwrap();
+        let v = vault.path().to_string_lossy().to_string();
+        let report = import(&v, "outlook", &olm, &[], false).unwrap();
+        assert_eq!((report.imported, report.failed.len()), (2, 0));
+        assert_eq!(import(&v, "outlook", &olm, &[], false).unwrap().duplicates, 2);
+
+        let emails = load_existing_emails(&v, "outlook").unwrap();
+        let q2 = emails.iter().find(|e| e.id == "q2@corp.example").unwrap();
+        assert_eq!((q2.subject.as_str(), q2.from.as_str(), q2.flags.clone()), ("季度 & report", "Ann Lee <ann@corp.example>", vec!["Seen".to_string()]));
+        assert_eq!(DateTime::parse_from_rfc3339(&q2.date).unwrap().timestamp(), 1_433_147_400);
+        assert_eq!(q2.attachments.len(), 1);
+        let sent = emails.iter().find(|e| e.folder == "Sent Items").unwrap();
+        assert!(sent.id.ends_with("@olm.lifeos"));
+        assert!(mailboxes(&dir.path().join("nothing.txt")).is_err());
+    }
+}
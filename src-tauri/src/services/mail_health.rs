@@ -0,0 +1,252 @@
+//! Per-account mail sync history: every attempt's outcome and latency, so an
+//! account that keeps failing (an expired app password, a revoked grant) is
+//! noticed instead of quietly going stale.
+//!
+//! Attempts are recorded by `sync_mailbox`, whichever path called it. The
+//! history stays on this device: a failure is often this machine's network.
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::durable;
+
+pub const HEALTH_FILE: &str = ".lifeos/mail-health.json";
+/// Attempts kept per account
+const MAX_ATTEMPTS: usize = 50;
+/// Consecutive failures before the account is reported as failing
+pub const WARN_AFTER: u32 = 3;
+/// Lowercased error fragments that mean the server turned the login down,
+/// in English and as `tr!` renders them in Chinese
+const AUTH_MARKERS: [&str; 11] = [
+    "login failed",
+    "authenticationfailed",
+    "authentication failed",
+    "invalid credentials",
+    "invalid_grant",
+    "unauthorized",
+    "no password saved",
+    "535",
+    "登录失败",
+    "密码",
+    "授权",
+];
+
+/// Recording is read-modify-write; manual and background syncs may overlap
+static LOCK: Mutex<()> = Mutex::new(());
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncAttempt {
+    /// RFC 3339
+    pub at: String,
+    pub folder: String,
+    pub ok: bool,
+    pub latency_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The server rejected the credentials
+    #[serde(default)]
+    pub auth_error: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Ok,
+    /// Some recent failures, still syncing now and then
+    Degraded,
+    /// `WARN_AFTER` failures in a row, or the login was rejected
+    Failing,
+    /// Never synced on this device
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountHealth {
+    pub account_id: String,
+    pub status: HealthStatus,
+    pub consecutive_failures: u32,
+    /// The latest attempt failed on the credentials
+    pub auth_error: bool,
+    pub last_success: Option<String>,
+    pub last_failure: Option<String>,
+    pub last_error: Option<String>,
+    /// Share of the kept attempts that succeeded, 0–1
+    pub success_rate: f64,
+    /// Mean over the kept successful attempts
+    pub avg_latency_ms: Option<u64>,
+    /// Newest first
+    pub attempts: Vec<SyncAttempt>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct History {
+    /// Oldest first
+    attempts: Vec<SyncAttempt>,
+    /// A warning went out for the current run of failures
+    #[serde(default)]
+    warned: bool,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Add one sync attempt to the account's history. Failing to save is only
+/// logged: health tracking must never fail a sync.
+pub fn record(vault_path: &str, account_id: &str, folder: &str, latency: Duration, error: Option<&str>) {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let vault = Path::new(vault_path);
+    let mut all = load(vault);
+    let history = all.entry(account_id.to_string()).or_default();
+    history.attempts.push(SyncAttempt {
+        at: Local::now().to_rfc3339(),
+        folder: folder.to_string(),
+        ok: error.is_none(),
+        latency_ms: latency.as_millis() as u64,
+        error: error.map(str::to_string),
+        auth_error: error.is_some_and(is_auth_error),
+    });
+    if error.is_none() {
+        history.warned = false;
+    }
+    let excess = history.attempts.len().saturating_sub(MAX_ATTEMPTS);
+    history.attempts.drain(..excess);
+    if let Err(e) = save(vault, &all) {
+        println!("[WARN] failed to record mail sync for {account_id}: {e}");
+    }
+}
+
+pub fn health(vault_path: &str, account_id: &str) -> AccountHealth {
+    let history = load(Path::new(vault_path)).remove(account_id).unwrap_or_default();
+    summarize(account_id, &history.attempts)
+}
+
+/// The account's health if it has just started failing: the first call
+/// after `WARN_AFTER` failures in a row returns it, later ones don't until
+/// a sync succeeds again
+pub fn take_warning(vault_path: &str, account_id: &str) -> Option<AccountHealth> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let vault = Path::new(vault_path);
+    let mut all = load(vault);
+    let history = all.get_mut(account_id)?;
+    let health = summarize(account_id, &history.attempts);
+    if history.warned || health.status != HealthStatus::Failing {
+        return None;
+    }
+    history.warned = true;
+    if let Err(e) = save(vault, &all) {
+        println!("[WARN] failed to save mail health: {e}");
+    }
+    Some(health)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn summarize(account_id: &str, attempts: &[SyncAttempt]) -> AccountHealth {
+    let consecutive_failures = attempts.iter().rev().take_while(|a| !a.ok).count() as u32;
+    let auth_error = attempts.last().is_some_and(|a| a.auth_error);
+    let successes: Vec<&SyncAttempt> = attempts.iter().filter(|a| a.ok).collect();
+    let success_rate = if attempts.is_empty() { 0.0 } else { successes.len() as f64 / attempts.len() as f64 };
+    let status = if attempts.is_empty() {
+        HealthStatus::Unknown
+    } else if consecutive_failures >= WARN_AFTER || auth_error {
+        HealthStatus::Failing
+    } else if consecutive_failures > 0 || success_rate < 0.8 {
+        HealthStatus::Degraded
+    } else {
+        HealthStatus::Ok
+    };
+    let last_failure = attempts.iter().rev().find(|a| !a.ok);
+
+    AccountHealth {
+        account_id: account_id.to_string(),
+        status,
+        consecutive_failures,
+        auth_error,
+        last_success: successes.last().map(|a| a.at.clone()),
+        last_failure: last_failure.map(|a| a.at.clone()),
+        last_error: last_failure.and_then(|a| a.error.clone()),
+        success_rate,
+        avg_latency_ms: (!successes.is_empty()).then(|| successes.iter().map(|a| a.latency_ms).sum::<u64>() / successes.len() as u64),
+        attempts: attempts.iter().rev().cloned().collect(),
+    }
+}
+
+fn is_auth_error(error: &str) -> bool {
+    let lower = error.to_lowercase();
+    AUTH_MARKERS.iter().any(|m| lower.contains(m))
+}
+
+fn load(vault: &Path) -> BTreeMap<String, History> {
+    fs::read_to_string(vault.join(HEALTH_FILE)).ok().and_then(|raw| serde_json::from_str(&raw).ok()).unwrap_or_default()
+}
+
+fn save(vault: &Path, all: &BTreeMap<String, History>) -> Result<(), String> {
+    let path = vault.join(HEALTH_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(all).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(path, json).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_auth_error() {
+        assert!(is_auth_error("Login failed: NO [AUTHENTICATIONFAILED] Invalid credentials"));
+        assert!(is_auth_error("登录失败: NO LOGIN failed"));
+        assert!(is_auth_error("token refresh failed: invalid_grant"));
+        assert!(!is_auth_error("Connection failed: timed out"));
+    }
+
+    #[test]
+    fn test_record_and_warn() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().to_str().unwrap();
+        assert_eq!(health(vault, "work").status, HealthStatus::Unknown);
+
+        record(vault, "work", "INBOX", Duration::from_millis(300), None);
+        record(vault, "work", "INBOX", Duration::from_millis(500), None);
+        assert_eq!(health(vault, "work").status, HealthStatus::Ok);
+        assert_eq!(health(vault, "work").avg_latency_ms, Some(400));
+
+        for _ in 0..2 {
+            record(vault, "work", "INBOX", Duration::from_secs(30), Some("Connection failed: timed out"));
+        }
+        assert_eq!(health(vault, "work").status, HealthStatus::Degraded);
+        assert!(take_warning(vault, "work").is_none());
+
+        record(vault, "work", "INBOX", Duration::from_secs(30), Some("Connection failed: timed out"));
+        let warned = take_warning(vault, "work").unwrap();
+        assert_eq!((warned.status, warned.consecutive_failures), (HealthStatus::Failing, 3));
+        assert!(take_warning(vault, "work").is_none());
+
+        // A rejected login fails the account at once; success clears it
+        record(vault, "home", "INBOX", Duration::from_millis(200), Some("Login failed: bad password"));
+        assert!(take_warning(vault, "home").is_some_and(|h| h.auth_error));
+        record(vault, "work", "INBOX", Duration::from_millis(200), None);
+        let recovered = health(vault, "work");
+        assert_eq!((recovered.status, recovered.last_error.as_deref()), (HealthStatus::Degraded, Some("Connection failed: timed out")));
+        assert_eq!(recovered.attempts[0].latency_ms, 200);
+    }
+}
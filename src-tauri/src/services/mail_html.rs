@@ -0,0 +1,531 @@
+//! Cleaning message HTML before the Mail view renders it.
+//!
+//! Scripts, style blocks, forms and event handlers are stripped by ammonia.
+//! Remote images (and inline styles that pull a remote `url(...)`) are
+//! dropped too unless the reader asks for them, since loading one tells the
+//! sender the message was opened.
+//!
+//! Trackers are found in the raw HTML: tiny or hidden remote images and
+//! images from known mail-tracking services, and links wrapped in a redirect
+//! that carries the real destination in its query. Destinations are only ever
+//! read from the link itself; following the redirect would report the click.
+//!
+//! Newsletters can also be reduced to their text structure for the reader
+//! view, as HTML or as markdown.
+
+use ammonia::Builder;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Presentational attributes mail layouts lean on, allowed on every tag
+const LAYOUT_ATTRIBUTES: [&str; 10] =
+    ["style", "align", "valign", "bgcolor", "color", "width", "height", "border", "cellpadding", "cellspacing"];
+/// Host fragment → service, for pixels and click-tracking links
+const TRACKING_HOSTS: [(&str, &str); 12] = [
+    ("list-manage.com", "Mailchimp"),
+    ("mandrillapp.com", "Mandrill"),
+    ("sendgrid.net", "SendGrid"),
+    ("mailgun", "Mailgun"),
+    ("hubspotlinks.com", "HubSpot"),
+    ("hs-analytics", "HubSpot"),
+    ("exacttarget.com", "Salesforce Marketing Cloud"),
+    ("pardot.com", "Pardot"),
+    ("mailtrack.io", "Mailtrack"),
+    ("mixmax.com", "Mixmax"),
+    ("yesware.com", "Yesware"),
+    ("superhuman.com", "Superhuman"),
+];
+/// Tags the reader view keeps: text structure, no layout. Table cells and
+/// divs stay only as block boundaries.
+const READER_TAGS: [&str; 30] = [
+    "p", "br", "hr", "h1", "h2", "h3", "h4", "h5", "h6", "a", "img", "ul", "ol", "li", "blockquote", "pre", "code", "strong", "b",
+    "em", "i", "figure", "figcaption", "div", "table", "tbody", "thead", "tr", "td", "th",
+];
+/// Query parameters redirectors put the destination in
+const DESTINATION_PARAMS: [&str; 8] = ["url", "u", "q", "target", "redirect", "redirect_url", "dest", "destination"];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Tracker {
+    /// "pixel" | "link"
+    pub kind: String,
+    pub url: String,
+    /// Tracking service, when the host is a known one
+    pub service: Option<String>,
+    /// Where a wrapped link really goes
+    pub destination: Option<String>,
+}
+
+pub struct SanitizedHtml {
+    pub html: String,
+    /// Remote images and styles removed; 0 when `allow_remote`
+    pub remote_blocked: usize,
+}
+
+/// With `unwrap_links`, wrapped tracking links point straight at their destination
+pub fn sanitize(html: &str, allow_remote: bool, unwrap_links: bool) -> SanitizedHtml {
+    let blocked = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&blocked);
+    let html = Builder::default()
+        .add_generic_attributes(LAYOUT_ATTRIBUTES)
+        .add_tags(["font", "center"])
+        .add_tag_attributes("font", ["face", "size"])
+        .add_url_schemes(["data", "cid"])
+        .attribute_filter(move |element, attribute, value| {
+            let remote = match (element, attribute) {
+                ("img", "src") => is_remote(value),
+                (_, "style") => remote_style(value),
+                _ => false,
+            };
+            if remote && !allow_remote {
+                counter.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+            if unwrap_links && (element, attribute) == ("a", "href") {
+                if let Some(destination) = destination(value) {
+                    return Some(Cow::Owned(destination));
+                }
+            }
+            Some(Cow::Borrowed(value))
+        })
+        .clean(html)
+        .to_string();
+    SanitizedHtml { html, remote_blocked: blocked.load(Ordering::Relaxed) }
+}
+
+/// The reader view's HTML: `READER_TAGS` only, no styles or layout
+/// attributes, tracking links unwrapped, remote images kept only with
+/// `allow_remote`
+pub fn reader_html(html: &str, allow_remote: bool) -> SanitizedHtml {
+    let blocked = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&blocked);
+    let html = Builder::default()
+        .tags(READER_TAGS.into_iter().collect())
+        .add_url_schemes(["data", "cid"])
+        .attribute_filter(move |element, attribute, value| match (element, attribute) {
+            ("img", "src") if is_remote(value) && !allow_remote => {
+                counter.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+            ("img", "width" | "height" | "align") => None,
+            ("a", "href") => Some(destination(value).map_or(Cow::Borrowed(value), Cow::Owned)),
+            _ => Some(Cow::Borrowed(value)),
+        })
+        .clean(html)
+        .to_string();
+    SanitizedHtml { html, remote_blocked: blocked.load(Ordering::Relaxed) }
+}
+
+/// Markdown for reader HTML (see [`reader_html`]): headings, paragraphs,
+/// lists, quotes, code, links and images. Anything else is read as text.
+pub fn to_markdown(html: &str) -> String {
+    let mut out = String::new();
+    let mut lists: Vec<Option<usize>> = Vec::new();
+    let mut links: Vec<String> = Vec::new();
+    let mut pre = false;
+    let mut i = 0;
+    while i < html.len() {
+        let rest = &html[i..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            i += 4 + comment.find("-->").map_or(comment.len(), |e| e + 3);
+            continue;
+        }
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = decode_entities(&rest[..end]);
+            if pre {
+                out.push_str(&text);
+            } else {
+                let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                let lead = text.starts_with(char::is_whitespace) && !out.ends_with([' ', '\n']) && !out.is_empty();
+                let trail = text.ends_with(char::is_whitespace) && !collapsed.is_empty();
+                if lead {
+                    out.push(' ');
+                }
+                out.push_str(&collapsed);
+                if trail {
+                    out.push(' ');
+                }
+            }
+            i += end;
+            continue;
+        }
+        let end = tag_end(rest);
+        let tag = &rest[..end];
+        i += end;
+        let closing = tag.starts_with("</");
+        let name: String = tag.trim_start_matches(['<', '/']).chars().take_while(|c| c.is_ascii_alphanumeric()).collect::<String>().to_ascii_lowercase();
+        let attrs = if closing { Vec::new() } else { tags(tag, &name).pop().unwrap_or_default() };
+        match (name.as_str(), closing) {
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", false) => {
+                block(&mut out);
+                out.push_str(&"#".repeat(name[1..].parse().unwrap_or(1)));
+                out.push(' ');
+            }
+            ("p" | "div" | "table" | "tr" | "figure" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6", true) | ("p" | "div" | "table" | "tr" | "figure", false) => {
+                block(&mut out)
+            }
+            ("td" | "th" | "figcaption", _) if !out.ends_with(char::is_whitespace) && !out.is_empty() => out.push(' '),
+            // Nested lists continue their parent item's block
+            ("ul" | "ol", false) => {
+                if lists.is_empty() {
+                    block(&mut out);
+                } else {
+                    line(&mut out);
+                }
+                lists.push((name == "ol").then_some(0));
+            }
+            ("ul" | "ol", true) => {
+                lists.pop();
+                if lists.is_empty() {
+                    block(&mut out);
+                } else {
+                    line(&mut out);
+                }
+            }
+            ("li", false) => {
+                let depth = lists.len().saturating_sub(1);
+                line(&mut out);
+                out.push_str(&"  ".repeat(depth));
+                match lists.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        out.push_str(&format!("{n}. "));
+                    }
+                    _ => out.push_str("- "),
+                }
+            }
+            ("br", _) => out.push('\n'),
+            ("hr", false) => {
+                block(&mut out);
+                out.push_str("---\n\n");
+            }
+            ("blockquote", false) => {
+                block(&mut out);
+                out.push_str("> ");
+            }
+            ("blockquote", true) => block(&mut out),
+            ("pre", false) => {
+                block(&mut out);
+                out.push_str("```\n");
+                pre = true;
+            }
+            ("pre", true) => {
+                line(&mut out);
+                out.push_str("```\n\n");
+                pre = false;
+            }
+            ("code", _) if !pre => out.push('`'),
+            ("strong" | "b", _) => out.push_str("**"),
+            ("em" | "i", _) => out.push('*'),
+            ("a", false) => {
+                links.push(attr(&attrs, "href").unwrap_or_default().to_string());
+                out.push('[');
+            }
+            ("a", true) => match links.pop().filter(|href| !href.is_empty()) {
+                Some(href) => out.push_str(&format!("]({href})")),
+                None => out.push(']'),
+            },
+            ("img", false) => {
+                if let Some(src) = attr(&attrs, "src").filter(|s| !s.is_empty()) {
+                    out.push_str(&format!("![{}]({src})", attr(&attrs, "alt").unwrap_or_default()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Tidy: no trailing spaces, no runs of blank lines, no empty links
+    let mut tidy = String::new();
+    let mut blank = 0;
+    for l in out.replace("[]()", "").lines().map(str::trim_end) {
+        blank = if l.trim().is_empty() { blank + 1 } else { 0 };
+        if blank <= 1 {
+            tidy.push_str(l);
+            tidy.push('\n');
+        }
+    }
+    tidy.trim().to_string()
+}
+
+/// Tracking pixels and wrapped links, each URL once
+pub fn find_trackers(html: &str) -> Vec<Tracker> {
+    let mut trackers: Vec<Tracker> = Vec::new();
+    for attrs in tags(html, "img") {
+        let Some(src) = attr(&attrs, "src").filter(|s| is_remote(s)) else { continue };
+        let service = service(src);
+        let style = attr(&attrs, "style").unwrap_or_default().to_lowercase().replace(' ', "");
+        let tiny = [("width", "width:"), ("height", "height:")].iter().all(|&(name, css)| {
+            attr(&attrs, name).and_then(pixels).or_else(|| style.split(css).nth(1).and_then(pixels)).is_some_and(|px| px <= 1)
+        });
+        let hidden = style.contains("display:none") || style.contains("visibility:hidden");
+        if tiny || hidden || service.is_some() {
+            trackers.push(Tracker { kind: "pixel".into(), url: src.to_string(), service, destination: None });
+        }
+    }
+    for attrs in tags(html, "a") {
+        let Some(href) = attr(&attrs, "href").filter(|h| is_remote(h)) else { continue };
+        let service = service(href);
+        let destination = destination(href);
+        if service.is_some() || destination.is_some() {
+            trackers.push(Tracker { kind: "link".into(), url: href.to_string(), service, destination });
+        }
+    }
+    let mut seen = HashSet::new();
+    trackers.retain(|t| seen.insert(t.url.clone()));
+    trackers
+}
+
+/// The http(s) URL a redirect link carries in its query, if any
+fn destination(url: &str) -> Option<String> {
+    let query = url.split_once('?')?.1.split('#').next().unwrap_or_default();
+    query.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        let value = super::http::decode(value);
+        (DESTINATION_PARAMS.contains(&key.to_lowercase().as_str()) && is_remote(&value) && !value.starts_with("//")).then_some(value)
+    })
+}
+
+fn service(url: &str) -> Option<String> {
+    let host = url.split("//").nth(1)?.split(['/', '?', '#']).next()?.to_lowercase();
+    TRACKING_HOSTS.iter().find(|(fragment, _)| host.contains(fragment)).map(|(_, name)| name.to_string())
+}
+
+/// Leading integer of `1`, `1px`, `0;` …
+fn pixels(value: &str) -> Option<u32> {
+    let digits: String = value.trim().chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+fn attr<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+}
+
+/// Attributes of every `<name …>` tag, names lowercased and `&amp;` decoded.
+/// A light scan rather than a full parse, enough for img and a.
+fn tags(html: &str, name: &str) -> Vec<Vec<(String, String)>> {
+    let lower = html.to_ascii_lowercase();
+    let bytes = html.as_bytes();
+    let open = format!("<{name}");
+    let mut found = Vec::new();
+    let mut from = 0;
+    while let Some(at) = lower[from..].find(&open) {
+        let mut i = from + at + open.len();
+        from = i;
+        if !bytes.get(i).is_some_and(|b| b.is_ascii_whitespace() || *b == b'>' || *b == b'/') {
+            continue;
+        }
+        let mut attrs = Vec::new();
+        loop {
+            while bytes.get(i).is_some_and(|b| b.is_ascii_whitespace() || *b == b'/') {
+                i += 1;
+            }
+            if i >= bytes.len() || bytes[i] == b'>' {
+                break;
+            }
+            let start = i;
+            while bytes.get(i).is_some_and(|b| !b.is_ascii_whitespace() && !b"=>/".contains(b)) {
+                i += 1;
+            }
+            let key = lower[start..i].to_string();
+            if key.is_empty() {
+                i += 1;
+                continue;
+            }
+            while bytes.get(i).is_some_and(|b| b.is_ascii_whitespace()) {
+                i += 1;
+            }
+            let mut value = String::new();
+            if bytes.get(i) == Some(&b'=') {
+                i += 1;
+                while bytes.get(i).is_some_and(|b| b.is_ascii_whitespace()) {
+                    i += 1;
+                }
+                let (start, end) = match bytes.get(i) {
+                    Some(&q) if q == b'"' || q == b'\'' => {
+                        let end = html[i + 1..].find(q as char).map_or(bytes.len(), |e| i + 1 + e);
+                        (i + 1, end)
+                    }
+                    _ => {
+                        let end = html[i..].find(|c: char| c.is_ascii_whitespace() || c == '>').map_or(bytes.len(), |e| i + e);
+                        (i, end)
+                    }
+                };
+                value = html[start..end].replace("&amp;", "&");
+                i = (end + 1).min(bytes.len());
+                if bytes.get(end) == Some(&b'>') {
+                    i = end;
+                }
+            }
+            attrs.push((key, value));
+        }
+        from = i;
+        found.push(attrs);
+    }
+    found
+}
+
+/// Length of the tag `rest` starts with, quoted `>` included
+fn tag_end(rest: &str) -> usize {
+    let mut quote = None;
+    for (i, c) in rest.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '>') => return i + 1,
+            _ => {}
+        }
+    }
+    rest.len()
+}
+
+/// End the markdown so far with a blank line, unless it is empty
+fn block(out: &mut String) {
+    let trimmed = out.trim_end_matches([' ', '\n']).len();
+    out.truncate(trimmed);
+    if !out.is_empty() {
+        out.push_str("\n\n");
+    }
+}
+
+/// End the markdown so far with a line break
+fn line(out: &mut String) {
+    let trimmed = out.trim_end_matches(' ').len();
+    out.truncate(trimmed);
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+/// The entities html5ever writes, plus numeric ones
+pub(crate) fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find('&') {
+        out.push_str(&rest[..at]);
+        rest = &rest[at..];
+        let entity = rest.find(';').filter(|&e| e <= 10).map(|e| &rest[1..e]);
+        let decoded = entity.and_then(|e| match e {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => e
+                .strip_prefix("#x")
+                .or_else(|| e.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| e.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        });
+        match (entity, decoded) {
+            (Some(e), Some(c)) => {
+                out.push(c);
+                rest = &rest[e.len() + 2..];
+            }
+            _ => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn is_remote(url: &str) -> bool {
+    let url = url.trim().to_lowercase();
+    url.starts_with("http://") || url.starts_with("https://") || url.starts_with("//")
+}
+
+/// `background: url(https://…)` and the like
+fn remote_style(style: &str) -> bool {
+    style
+        .to_lowercase()
+        .split("url(")
+        .skip(1)
+        .any(|rest| is_remote(rest.trim_start_matches(['\'', '"', ' '])))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize() {
+        let html = r#"<p onclick="x()">Hi<script>alert(1)</script></p><form action="/p"><input name="q"></form><img src="https://t.example.com/open.gif" width="1"><img src="data:image/png;base64,AAAA" alt="logo"><table><tr><td style="background:url('https://x.com/bg.png')">cell</td></tr></table>"#;
+
+        let blocked = sanitize(html, false, false);
+        assert_eq!(blocked.remote_blocked, 2);
+        assert!(!blocked.html.contains("script") && !blocked.html.contains("onclick") && !blocked.html.contains("<form"));
+        assert!(!blocked.html.contains("t.example.com") && !blocked.html.contains("bg.png"));
+        assert!(blocked.html.contains("data:image/png") && blocked.html.contains("<p>Hi</p>"));
+
+        let loaded = sanitize(html, true, false);
+        assert_eq!(loaded.remote_blocked, 0);
+        assert!(loaded.html.contains("https://t.example.com/open.gif") && !loaded.html.contains("script"));
+    }
+
+    #[test]
+    fn test_reader_markdown() {
+        let html = r#"<table width="600"><tr><td style="padding:8px"><h1 style="color:red">Weekly <em>notes</em></h1>
+            <p>Hello&nbsp;there &amp; welcome.<br>Second line</p>
+            <img src="https://cdn.example.com/hero.png" width="600"><img src="data:image/png;base64,AA" alt="chart">
+            <ul><li>One <a href="https://links.example.com/r?url=https%3A%2F%2Fexample.org%2Fpost">post</a></li><li>Two<ol><li>a</li></ol></li></ul>
+            <blockquote>Quoted</blockquote><pre>let x = 1;
+  y</pre></td><td>Side</td></tr></table><!-- footer -->"#;
+
+        let reader = reader_html(html, false);
+        assert_eq!(reader.remote_blocked, 1);
+        assert!(!reader.html.contains("style=") && !reader.html.contains("width=") && !reader.html.contains("hero.png"));
+        assert!(reader.html.contains(r#"href="https://example.org/post""#));
+
+        let md = to_markdown(&reader.html);
+        assert_eq!(
+            md,
+            "# Weekly *notes*\n\nHello there & welcome.\nSecond line\n\n![chart](data:image/png;base64,AA)\n\n\
+             - One [post](https://example.org/post)\n- Two\n  1. a\n\n> Quoted\n\n```\nlet x = 1;\n  y\n```\n\nSide"
+        );
+        assert_eq!(decode_entities("a &lt;b&gt; &#39;c&#x27; &bogus d"), "a <b> 'c' &bogus d");
+    }
+
+    #[test]
+    fn test_find_trackers() {
+        let html = r#"<img src="https://example.com/logo.png" width="120">
+            <IMG SRC="https://shop.example.com/o.gif" width=1 height="1" alt="">
+            <img src='https://example.com/beacon' style="display: none">
+            <img src="https://mandrillapp.com/track/open.php?u=1">
+            <a href="https://example.list-manage.com/track/click?u=1&amp;id=2">Read</a>
+            <a href="https://links.example.com/r?id=9&url=https%3A%2F%2Fexample.org%2Fsale%3Fa%3D1">Sale</a>
+            <a href="https://example.org/about">About</a>"#;
+
+        let trackers = find_trackers(html);
+        let found: Vec<(&str, &str, Option<&str>, Option<&str>)> = trackers
+            .iter()
+            .map(|t| (t.kind.as_str(), t.url.as_str(), t.service.as_deref(), t.destination.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("pixel", "https://shop.example.com/o.gif", None, None),
+                ("pixel", "https://example.com/beacon", None, None),
+                ("pixel", "https://mandrillapp.com/track/open.php?u=1", Some("Mandrill"), None),
+                ("link", "https://example.list-manage.com/track/click?u=1&id=2", Some("Mailchimp"), None),
+                ("link", "https://links.example.com/r?id=9&url=https%3A%2F%2Fexample.org%2Fsale%3Fa%3D1", None, Some("https://example.org/sale?a=1")),
+            ]
+        );
+
+        let unwrapped = sanitize(html, true, true).html;
+        assert!(unwrapped.contains(r#"href="https://example.org/sale?a=1""#) && !unwrapped.contains("links.example.com"));
+    }
+}
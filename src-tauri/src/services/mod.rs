@@ -0,0 +1,115 @@
+//! Tauri-free logic shared by the GUI commands and the `lifeos` CLI.
+//!
+//! Functions here take plain paths and return `Result<_, String>` like the
+//! commands do, so a `#[tauri::command]` wrapper is a one-line delegation and
+//! the CLI can call the same code without an `AppHandle`.
+
+pub mod ai;
+pub mod audit;
+pub mod automations;
+pub mod batch;
+pub mod calendar;
+pub mod canvas;
+pub mod carddav;
+pub mod connectors;
+pub mod dependencies;
+pub mod diary_book;
+pub mod durable;
+pub mod embeds;
+pub mod epub;
+pub mod fuzzy;
+pub mod git;
+pub mod gmail;
+pub mod google;
+pub mod google_calendar;
+pub mod habits;
+pub mod highlights;
+pub mod history;
+pub mod http;
+pub mod imessage;
+pub mod journal;
+pub mod lunar;
+pub mod mail;
+pub mod mail_archive;
+pub mod mail_html;
+pub mod mail_health;
+pub mod mood;
+pub mod note_ids;
+pub mod note_locks;
+pub mod notes;
+pub mod outbox;
+pub mod pdf;
+pub mod people;
+pub mod periodic;
+pub mod planner;
+pub mod records;
+pub mod schemas;
+pub mod secrets;
+pub mod spotlight;
+pub mod stats;
+pub mod sync;
+pub mod task_export;
+pub mod tasks;
+pub mod templates;
+pub mod transfer;
+pub mod weather;
+
+use once_cell::sync::OnceCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the pointer file holding the vault path, in $HOME or `CONFIG_DIR`
+const CONFIG_FILE_NAME: &str = ".life-os-vault";
+
+/// Overrides the home dir as the location of the global config file.
+/// Mobile sandboxes have no usable $HOME, so `run()` points this at the
+/// app config dir there.
+static CONFIG_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+#[cfg(mobile)]
+pub fn set_config_dir(dir: PathBuf) {
+    let _ = CONFIG_DIR.set(dir);
+}
+
+/// Returns the path to the global config file stored in the user's home dir
+pub fn config_file() -> Result<PathBuf, String> {
+    let dir = match CONFIG_DIR.get() {
+        Some(dir) => dir.clone(),
+        None => home_dir().ok_or_else(|| tr!("Cannot find home dir"))?,
+    };
+    Ok(dir.join(CONFIG_FILE_NAME))
+}
+
+/// $HOME, or %USERPROFILE% on Windows
+pub fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")).map(PathBuf::from)
+}
+
+/// Vault chosen in the app (the global pointer file in $HOME), if any
+pub fn configured_vault() -> Option<String> {
+    let cfg = config_file().ok()?;
+    if cfg.exists() {
+        fs::read_to_string(&cfg).ok().map(|s| s.trim().to_string())
+    } else {
+        None
+    }
+}
+
+/// Raw .lifeos/settings.yaml of the vault ("" when missing), picking up the
+/// settings that affect the backend, currently the message language
+pub fn load_settings(vault_path: &str) -> Result<String, String> {
+    let settings_path = Path::new(vault_path).join(".lifeos/settings.yaml");
+    if settings_path.exists() {
+        let content = fs::read_to_string(&settings_path).map_err(|e| e.to_string())?;
+        crate::i18n::apply_settings(&content);
+        Ok(content)
+    } else {
+        Ok(String::new())
+    }
+}
+
+/// Error returned by commands that have no implementation on this platform
+#[cfg(not(target_os = "macos"))]
+pub fn unsupported(command: &str) -> String {
+    tr!("{} is not supported on {}", command, std::env::consts::OS)
+}
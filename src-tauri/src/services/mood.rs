@@ -0,0 +1,115 @@
+//! Mood as written in diary and daily-note frontmatter (`mood: 😊`), mapped
+//! to a 1–5 score so it can be averaged and correlated.
+
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use super::notes::split_frontmatter;
+
+/// Score for the diary's mood picker emoji, words, or a number (1–5, or
+/// 1–10 halved)
+pub fn score(mood: &str) -> Option<f64> {
+    let mood = mood.trim().trim_end_matches('\u{fe0f}');
+    if let Ok(n) = mood.parse::<f64>() {
+        return match n {
+            n if (1.0..=5.0).contains(&n) => Some(n),
+            n if (5.0..=10.0).contains(&n) => Some(n / 2.0),
+            _ => None,
+        };
+    }
+    Some(match mood {
+        "🔥" | "✨" | "🤩" | "😄" | "great" | "excellent" | "充沛" | "兴奋" | "很好" => 5.0,
+        "😊" | "😌" | "🎯" | "☕" | "🙂" | "good" | "happy" | "开心" | "满足" | "专注" | "悠闲" | "好" => 4.0,
+        "😐" | "🤔" | "ok" | "okay" | "neutral" | "calm" | "平静" | "思考" | "一般" => 3.0,
+        "😴" | "😕" | "tired" | "meh" | "疲惫" | "累" => 2.0,
+        "😔" | "🌧" | "😤" | "😢" | "😡" | "bad" | "sad" | "angry" | "低落" | "忧伤" | "愤怒" | "差" => 1.0,
+        _ => return None,
+    })
+}
+
+/// `energy: low|medium|high` (also 低/中/高 or 1–5) on the same 1–5 scale
+pub fn energy_score(energy: &str) -> Option<f64> {
+    let energy = energy.trim().to_lowercase();
+    if let Ok(n) = energy.parse::<f64>() {
+        return (1.0..=5.0).contains(&n).then_some(n);
+    }
+    Some(match energy.as_str() {
+        "low" | "低" => 1.0,
+        "medium" | "mid" | "normal" | "中" => 3.0,
+        "high" | "高" => 5.0,
+        _ => return None,
+    })
+}
+
+/// Frontmatter of every diary entry and daily note, keyed by its `date`
+/// (or the date in the file name). Several entries on one day are all kept.
+pub fn dated_frontmatter(vault: &Path) -> BTreeMap<NaiveDate, Vec<serde_yaml::Value>> {
+    let mut out: BTreeMap<NaiveDate, Vec<serde_yaml::Value>> = BTreeMap::new();
+    for dir in ["diary", "daily/tasks"] {
+        let entries = WalkDir::new(vault.join(dir))
+            .max_depth(3)
+            .into_iter()
+            .filter_entry(|e| e.file_name() != "templates")
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext == "md"));
+        for entry in entries {
+            let Ok(raw) = fs::read_to_string(entry.path()) else { continue };
+            let (Some(yaml), _) = split_frontmatter(&raw) else { continue };
+            let Ok(fm) = serde_yaml::from_str::<serde_yaml::Value>(yaml) else { continue };
+            let from_fm = fm.get("date").and_then(|d| d.as_str()).map(str::to_string);
+            let from_name = entry.file_name().to_string_lossy().get(..10).map(str::to_string);
+            let Some(date) = [from_fm, from_name]
+                .into_iter()
+                .flatten()
+                .find_map(|d| NaiveDate::parse_from_str(d.get(..10).unwrap_or(&d), "%Y-%m-%d").ok())
+            else {
+                continue;
+            };
+            out.entry(date).or_default().push(fm);
+        }
+    }
+    out
+}
+
+/// String or number frontmatter value as text
+pub fn field(fm: &serde_yaml::Value, key: &str) -> Option<String> {
+    match fm.get(key)? {
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Average mood score per day
+pub fn daily_scores(vault: &Path) -> BTreeMap<NaiveDate, f64> {
+    dated_frontmatter(vault)
+        .into_iter()
+        .filter_map(|(date, fms)| {
+            let scores: Vec<f64> = fms.iter().filter_map(|fm| score(&field(fm, "mood")?)).collect();
+            super::stats::mean(&scores).map(|m| (date, m))
+        })
+        .collect()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score() {
+        assert_eq!(score("😊"), Some(4.0));
+        assert_eq!(score("🌧️"), Some(1.0));
+        assert_eq!(score("8"), Some(4.0));
+        assert_eq!(score("开心"), Some(4.0));
+        assert_eq!(score("🦄"), None);
+        assert_eq!(energy_score("High"), Some(5.0));
+        assert_eq!(energy_score("中"), Some(3.0));
+    }
+}
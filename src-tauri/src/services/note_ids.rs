@@ -0,0 +1,184 @@
+//! Stable note IDs, so links from emails, calendar events and other tools
+//! survive a note being renamed or moved. The ID is a UUID in the note's
+//! frontmatter:
+//!
+//! ```yaml
+//! uid: 9b2f6c1e-4d3a-4f7b-8e21-5c0d9a7e3f10
+//! ```
+//!
+//! Notes created through `write_note` get one; older notes only when asked
+//! (`assign`). `resolve` finds a note by ID through .lifeos/note-ids.json, a
+//! cache of where each ID was last seen that is rebuilt by scanning the vault
+//! whenever it points at the wrong file.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use walkdir::WalkDir;
+
+use super::durable;
+use super::notes::{self, split_frontmatter, vault_rel};
+
+pub const ID_KEY: &str = "uid";
+pub const INDEX_FILE: &str = ".lifeos/note-ids.json";
+
+/// The index is read-modify-write
+static LOCK: Mutex<()> = Mutex::new(());
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// The ID in a note's frontmatter, if any
+pub fn id_of(raw: &str) -> Option<String> {
+    let yaml = split_frontmatter(raw).0?;
+    let value: serde_yaml::Value = serde_yaml::from_str(yaml).ok()?;
+    let id = match value.get(ID_KEY)? {
+        serde_yaml::Value::String(s) => s.trim().to_string(),
+        other => serde_yaml::to_string(other).ok()?.trim().to_string(),
+    };
+    (!id.is_empty()).then_some(id)
+}
+
+/// Give frontmatter about to be written to `path` its ID: the one the note
+/// already has when the caller left it out, or a new one for a new note
+pub fn fill(path: &Path, frontmatter: &mut serde_json::Value) {
+    let Some(map) = frontmatter.as_object_mut() else { return };
+    if map.get(ID_KEY).and_then(|v| v.as_str()).is_some_and(|s| !s.trim().is_empty()) {
+        return;
+    }
+    let id = match fs::read_to_string(path) {
+        Ok(raw) => id_of(&raw),
+        Err(_) if !path.exists() => Some(new_id()),
+        Err(_) => None,
+    };
+    if let Some(id) = id {
+        map.insert(ID_KEY.to_string(), serde_json::Value::String(id));
+    }
+}
+
+/// The note's ID, adding one to its frontmatter when it has none
+pub fn assign(vault: &Path, path: &Path) -> Result<String, String> {
+    let raw = fs::read_to_string(path).map_err(|e| tr!("Failed to read: {}", e))?;
+    if let Some(id) = id_of(&raw) {
+        return Ok(id);
+    }
+    let id = new_id();
+    notes::write_field(path, ID_KEY, Some(&serde_yaml::Value::String(id.clone())))?;
+    if let Ok(rel) = path.strip_prefix(vault) {
+        let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut index = load_index(vault);
+        index.insert(id.clone(), rel.to_string_lossy().replace('\\', "/"));
+        save_index(vault, &index);
+    }
+    Ok(id)
+}
+
+/// Where the note with `id` is now
+pub fn resolve(vault: &Path, id: &str) -> Option<PathBuf> {
+    let id = id.trim();
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(rel) = load_index(vault).get(id) {
+        let path = vault.join(rel);
+        if fs::read_to_string(&path).ok().and_then(|raw| id_of(&raw)).as_deref() == Some(id) {
+            return Some(path);
+        }
+    }
+    let index = scan(vault);
+    save_index(vault, &index);
+    index.get(id).map(|rel| vault.join(rel))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn new_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Every ID in the vault's notes; on duplicates (a copied note) the first
+/// path in name order wins
+fn scan(vault: &Path) -> BTreeMap<String, String> {
+    let mut index = BTreeMap::new();
+    for entry in WalkDir::new(vault)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|x| x == "md"))
+    {
+        let Some(id) = fs::read_to_string(entry.path()).ok().and_then(|raw| id_of(&raw)) else { continue };
+        let rel = vault_rel(vault, entry.path());
+        index.entry(id).or_insert(rel);
+    }
+    index
+}
+
+fn load_index(vault: &Path) -> BTreeMap<String, String> {
+    fs::read_to_string(vault.join(INDEX_FILE)).ok().and_then(|raw| serde_json::from_str(&raw).ok()).unwrap_or_default()
+}
+
+/// Only a cache: failing to save it just means scanning again next time
+fn save_index(vault: &Path, index: &BTreeMap<String, String>) {
+    let path = vault.join(INDEX_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    let written = serde_json::to_string_pretty(index).map_err(|e| e.to_string()).and_then(|json| durable::write(&path, json).map_err(|e| e.to_string()));
+    if let Err(e) = written {
+        println!("[WARN] failed to write {}: {e}", path.display());
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill() {
+        let dir = tempfile::tempdir().unwrap();
+        let note = dir.path().join("a.md");
+
+        let mut fm = serde_json::json!({ "title": "A" });
+        fill(&note, &mut fm);
+        let id = fm[ID_KEY].as_str().unwrap().to_string();
+        fs::write(&note, format!("---\ntitle: A\nuid: {id}\n---\n")).unwrap();
+
+        // Rewritten without it, as views that rebuild frontmatter do
+        let mut fm = serde_json::json!({ "title": "B" });
+        fill(&note, &mut fm);
+        assert_eq!(fm[ID_KEY], id.as_str());
+
+        // Existing notes without one are left alone
+        fs::write(&note, "---\ntitle: A\n---\n").unwrap();
+        let mut fm = serde_json::json!({ "title": "A" });
+        fill(&note, &mut fm);
+        assert!(fm.get(ID_KEY).is_none());
+    }
+
+    #[test]
+    fn test_assign_and_resolve_after_move() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path();
+        fs::create_dir_all(vault.join("projects/active")).unwrap();
+        fs::create_dir_all(vault.join("projects/done")).unwrap();
+        let note = vault.join("projects/active/launch.md");
+        fs::write(&note, "---\ntitle: Launch\n---\n\nbody\n").unwrap();
+
+        let id = assign(vault, &note).unwrap();
+        assert_eq!(assign(vault, &note).unwrap(), id);
+        assert!(fs::read_to_string(&note).unwrap().ends_with("\nbody\n"));
+        assert_eq!(resolve(vault, &id), Some(note.clone()));
+
+        let moved = vault.join("projects/done/launch-2025.md");
+        fs::rename(&note, &moved).unwrap();
+        assert_eq!(resolve(vault, &id), Some(moved));
+        assert_eq!(resolve(vault, "no-such-id"), None);
+    }
+}
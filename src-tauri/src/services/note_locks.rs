@@ -0,0 +1,175 @@
+//! Advisory note locks, so the app and AI agents working on the vault (an
+//! editor session, a CLI agent rewriting notes) don't edit the same file at
+//! once. Nothing enforces them: writers are expected to take a lock, keep it
+//! short, and respect the ones they find.
+//!
+//! Locks live in .lifeos/locks.json, a plain map an agent without the app can
+//! read and edit too:
+//!
+//! ```json
+//! { "diary/2025/2025-01-15-0930.md": { "owner": "agent", "acquired_at": "…", "expires_at": "…" } }
+//! ```
+//!
+//! Every lock expires, so a crashed holder never blocks a note for long.
+
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::durable;
+
+pub const LOCKS_FILE: &str = ".lifeos/locks.json";
+pub const DEFAULT_TTL_SECS: u64 = 300;
+/// Longest a lock can be taken for; renew it to hold on longer
+const MAX_TTL_SECS: u64 = 24 * 3600;
+
+/// The locks file is read-modify-write
+static LOCK: Mutex<()> = Mutex::new(());
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NoteLock {
+    /// Vault-relative; the map key is what counts, this may be left out
+    #[serde(default)]
+    pub path: String,
+    pub owner: String,
+    /// RFC 3339
+    pub acquired_at: String,
+    /// RFC 3339
+    pub expires_at: String,
+}
+
+impl NoteLock {
+    fn expired(&self, now: DateTime<Local>) -> bool {
+        DateTime::parse_from_rfc3339(&self.expires_at).map_or(true, |t| t <= now)
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Take the lock on `path` for `ttl_secs`, or renew it when `owner` already
+/// holds it. Fails while someone else's lock is live.
+pub fn acquire(vault: &Path, path: &Path, owner: &str, ttl_secs: u64) -> Result<NoteLock, String> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let rel = relative(vault, path)?;
+    let now = Local::now();
+    let mut locks = live(vault, now);
+    let acquired_at = match locks.get(&rel) {
+        Some(held) if held.owner != owner => return Err(tr!("Note is locked by {} until {}", held.owner, held.expires_at)),
+        Some(held) => held.acquired_at.clone(),
+        None => now.to_rfc3339(),
+    };
+    let ttl = Duration::seconds(ttl_secs.clamp(1, MAX_TTL_SECS) as i64);
+    let lock = NoteLock { path: rel.clone(), owner: owner.to_string(), acquired_at, expires_at: (now + ttl).to_rfc3339() };
+    locks.insert(rel, lock.clone());
+    save(vault, &locks)?;
+    Ok(lock)
+}
+
+/// Give up `owner`'s lock on `path`; false when there was none. `force`
+/// breaks another owner's lock, for the user clearing a stuck agent.
+pub fn release(vault: &Path, path: &Path, owner: &str, force: bool) -> Result<bool, String> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let rel = relative(vault, path)?;
+    let mut locks = live(vault, Local::now());
+    match locks.get(&rel) {
+        None => return Ok(false),
+        Some(held) if held.owner != owner && !force => return Err(tr!("Note is locked by {} until {}", held.owner, held.expires_at)),
+        Some(_) => {}
+    }
+    locks.remove(&rel);
+    save(vault, &locks)?;
+    Ok(true)
+}
+
+/// The live lock on `path`, if any
+pub fn holder(vault: &Path, path: &Path) -> Result<Option<NoteLock>, String> {
+    let rel = relative(vault, path)?;
+    Ok(live(vault, Local::now()).remove(&rel))
+}
+
+/// Every live lock, by path
+pub fn list(vault: &Path) -> Vec<NoteLock> {
+    live(vault, Local::now()).into_values().collect()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Unexpired locks, paths filled in
+fn live(vault: &Path, now: DateTime<Local>) -> BTreeMap<String, NoteLock> {
+    let all: BTreeMap<String, NoteLock> =
+        fs::read_to_string(vault.join(LOCKS_FILE)).ok().and_then(|raw| serde_json::from_str(&raw).ok()).unwrap_or_default();
+    all.into_iter()
+        .filter(|(_, lock)| !lock.expired(now))
+        .map(|(path, lock)| (path.clone(), NoteLock { path, ..lock }))
+        .collect()
+}
+
+fn save(vault: &Path, locks: &BTreeMap<String, NoteLock>) -> Result<(), String> {
+    let path = vault.join(LOCKS_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(locks).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(path, json).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+fn relative(vault: &Path, path: &Path) -> Result<String, String> {
+    let path: PathBuf = if path.is_absolute() { path.to_path_buf() } else { vault.join(path) };
+    path.strip_prefix(vault)
+        .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+        .map_err(|_| tr!("Path is outside the vault: {}", path.display()))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acquire_and_release() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path();
+        let note = vault.join("diary/2025/2025-01-15-0930.md");
+
+        let first = acquire(vault, &note, "lifeos", 60).unwrap();
+        assert_eq!(first.path, "diary/2025/2025-01-15-0930.md");
+        assert!(acquire(vault, Path::new("diary/2025/2025-01-15-0930.md"), "agent", 60).unwrap_err().contains("lifeos"));
+        // Renewing keeps when it was first taken
+        assert_eq!(acquire(vault, &note, "lifeos", 600).unwrap().acquired_at, first.acquired_at);
+        assert_eq!(holder(vault, &note).unwrap().map(|l| l.owner), Some("lifeos".into()));
+
+        assert!(release(vault, &note, "agent", false).is_err());
+        assert!(release(vault, &note, "lifeos", false).unwrap());
+        assert!(!release(vault, &note, "lifeos", false).unwrap());
+        assert!(acquire(vault, &note, "agent", 60).is_ok());
+        assert!(release(vault, &note, "lifeos", true).unwrap());
+        assert!(relative(vault, Path::new("/elsewhere/note.md")).is_err());
+    }
+
+    #[test]
+    fn test_expired_locks_are_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path();
+        let stale = r#"{ "projects/active/a.md": { "owner": "agent", "acquired_at": "2020-01-01T00:00:00+00:00", "expires_at": "2020-01-01T00:05:00+00:00" } }"#;
+        fs::create_dir_all(vault.join(".lifeos")).unwrap();
+        fs::write(vault.join(LOCKS_FILE), stale).unwrap();
+
+        assert!(list(vault).is_empty());
+        assert_eq!(acquire(vault, Path::new("projects/active/a.md"), "lifeos", 60).unwrap().owner, "lifeos");
+        assert_eq!(list(vault).len(), 1);
+    }
+}
@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::durable;
+
+/// Longest line excerpt returned per match
+const SNIPPET_CHARS: usize = 160;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NoteMatch {
+    pub path: String,
+    /// 1-based line number (within the page for PDFs)
+    pub line: usize,
+    pub text: String,
+    /// 1-based page, for hits in extracted PDF text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<usize>,
+}
+
+/// Case-insensitive full-text search over the vault's .md files, then the
+/// text of PDFs extracted so far. Dot-dirs (.lifeos, .git, ...) are skipped;
+/// results stop at `limit`.
+pub fn search_notes(vault_path: &str, query: &str, limit: usize) -> Result<Vec<NoteMatch>, String> {
+    let root = PathBuf::from(vault_path);
+    if !root.exists() {
+        return Err(tr!("Path does not exist: {}", vault_path));
+    }
+    let needle = query.trim().to_lowercase();
+    if needle.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut matches = Vec::new();
+    let walker = WalkDir::new(&root)
+        .min_depth(1)
+        .max_depth(10)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| !e.file_name().to_string_lossy().starts_with('.'));
+
+    for entry in walker.filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() || entry.path().extension().is_none_or(|ext| ext != "md") {
+            continue;
+        }
+        let Ok(raw) = fs::read_to_string(entry.path()) else { continue };
+        for (i, line) in raw.lines().enumerate() {
+            if !line.to_lowercase().contains(&needle) {
+                continue;
+            }
+            matches.push(NoteMatch {
+                path: entry.path().to_string_lossy().to_string(),
+                line: i + 1,
+                text: line.trim().chars().take(SNIPPET_CHARS).collect(),
+                page: None,
+            });
+            if matches.len() >= limit {
+                return Ok(matches);
+            }
+        }
+    }
+
+    for (path, pdf) in super::pdf::cached(&root) {
+        for (p, page) in pdf.pages.iter().enumerate() {
+            for (i, line) in page.lines().enumerate().filter(|(_, l)| l.to_lowercase().contains(&needle)) {
+                matches.push(NoteMatch {
+                    path: path.to_string_lossy().to_string(),
+                    line: i + 1,
+                    text: line.trim().chars().take(SNIPPET_CHARS).collect(),
+                    page: Some(p + 1),
+                });
+                if matches.len() >= limit {
+                    return Ok(matches);
+                }
+            }
+        }
+    }
+    Ok(matches)
+}
+
+/// Split `---\n<yaml>\n---` from the body
+pub fn split_frontmatter(raw: &str) -> (Option<&str>, &str) {
+    if let Some(rest) = raw.strip_prefix("---") {
+        if let Some(end) = rest.find("\n---") {
+            let after = &rest[end + 4..];
+            // Drop the remainder of the closing `---` line, then blank lines
+            let body = after.find('\n').map_or("", |i| &after[i + 1..]).trim_start_matches(['\r', '\n']);
+            return (Some(&rest[..end]), body);
+        }
+    }
+    (None, raw)
+}
+
+/// Lowercase, keep letters/digits of any script (Chinese names stay readable),
+/// collapse everything else to single dashes
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.trim().chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.ends_with('-') && !slug.is_empty() {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-').to_string();
+    if slug.is_empty() { "person".to_string() } else { slug }
+}
+
+/// A slug names one file directly in its folder; the frontend sends them,
+/// so anything that could leave the folder is refused
+pub fn check_slug(slug: &str) -> Result<(), String> {
+    if slug.is_empty() || slug.contains(['/', '\\']) || slug.contains("..") {
+        return Err(tr!("Invalid slug: {}", slug));
+    }
+    Ok(())
+}
+
+/// `path` relative to the vault with forward slashes, as notes link to it
+/// and indexes key it; a path outside the vault is returned whole
+pub fn vault_rel(vault: &Path, path: &Path) -> String {
+    path.strip_prefix(vault).unwrap_or(path).to_string_lossy().replace('\\', "/")
+}
+
+/// Drop `key` (with its indented or `- ` continuation lines) from the
+/// frontmatter and append the new value before the closing `---`
+pub fn set_field(content: &str, key: &str, value: Option<&serde_yaml::Value>) -> Result<String, String> {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let has_frontmatter = lines.first().is_some_and(|l| l.trim_end() == "---");
+    let close = if has_frontmatter { lines.iter().skip(1).position(|l| l.trim_end() == "---").map(|i| i + 1) } else { None };
+    let close = match close {
+        Some(close) => close,
+        None if value.is_none() => return Ok(content.to_string()),
+        None => {
+            lines.splice(0..0, ["---".to_string(), "---".to_string()]);
+            1
+        }
+    };
+
+    let prefix = format!("{key}:");
+    let mut fm: Vec<String> = Vec::new();
+    let mut skipping = false;
+    for line in &lines[1..close] {
+        if skipping && (line.starts_with(' ') || line.starts_with('\t') || line.starts_with("- ")) {
+            continue;
+        }
+        skipping = line.starts_with(&prefix);
+        if !skipping {
+            fm.push(line.clone());
+        }
+    }
+    if let Some(value) = value {
+        let mut map = serde_yaml::Mapping::new();
+        map.insert(serde_yaml::Value::String(key.to_string()), value.clone());
+        let yaml = serde_yaml::to_string(&map).map_err(|e| tr!("Failed to serialize: {}", e))?;
+        fm.extend(yaml.lines().map(str::to_string));
+    }
+    lines.splice(1..close, fm);
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    Ok(out)
+}
+
+/// `set_field` on a note file in place
+pub fn write_field(path: &Path, key: &str, value: Option<&serde_yaml::Value>) -> Result<(), String> {
+    let content = fs::read_to_string(path).map_err(|e| tr!("Failed to read: {}", e))?;
+    let updated = set_field(&content, key, value)?;
+    if updated != content {
+        durable::write(path, updated).map_err(|e| tr!("write_file failed: {}", e))?;
+    }
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Jane  O'Neil"), "jane-o-neil");
+        assert_eq!(slugify("张三"), "张三");
+        assert_eq!(slugify("!!"), "person");
+    }
+
+    #[test]
+    fn test_check_slug() {
+        assert!(check_slug("jane-doe").is_ok());
+        for slug in ["../notes", "a/b", "a\\b", "..", ""] {
+            assert_eq!(check_slug(slug).unwrap_err(), tr!("Invalid slug: {}", slug));
+        }
+    }
+
+    #[test]
+    fn test_set_field() {
+        let day = "---\ndate: 2025-03-01\nplaces:\n- 家\n- 公司\nmood: 😊\n---\n\nbody\n";
+        let places = serde_yaml::to_value(vec!["咖啡馆"]).unwrap();
+        let out = set_field(day, "places", Some(&places)).unwrap();
+        assert_eq!(out, "---\ndate: 2025-03-01\nmood: 😊\nplaces:\n- 咖啡馆\n---\n\nbody\n");
+        let out = set_field(&out, "places", None).unwrap();
+        assert_eq!(out, "---\ndate: 2025-03-01\nmood: 😊\n---\n\nbody\n");
+        assert_eq!(set_field("no frontmatter", "places", None).unwrap(), "no frontmatter");
+    }
+}
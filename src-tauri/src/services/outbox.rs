@@ -0,0 +1,218 @@
+//! Outgoing operations held while offline: emails to send, read flags and
+//! archive moves to push to the server, calendar syncs. Commands queue an
+//! operation here when it fails for want of a network and carry on locally;
+//! the outbox loop replays the queue, in order, once the network is back.
+//!
+//! The queue holds server credentials, like the account files it came from,
+//! so it never leaves this device.
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::durable;
+use super::mail::SendEmailRequest;
+
+pub const OUTBOX_FILE: &str = ".lifeos/outbox.json";
+/// Public resolvers tried by `is_online`; reaching any one is enough
+const PROBES: [&str; 3] = ["1.1.1.1:443", "8.8.8.8:53", "223.5.5.5:53"];
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+/// Lowercased error fragments that mean the network, not the request, failed:
+/// ureq and OS errors, and `tr!("Connection failed: {}")` in both languages
+const NETWORK_MARKERS: [&str; 14] = [
+    "connection failed",
+    "connection error",
+    "network error",
+    "连接失败",
+    "dns failed",
+    "failed to lookup address",
+    "name or service not known",
+    "nodename nor servname",
+    "temporary failure in name resolution",
+    "network is unreachable",
+    "network is down",
+    "no route to host",
+    "connection refused",
+    "timed out",
+];
+
+/// Queue edits are read-modify-write; commands and the loop may overlap
+static LOCK: Mutex<()> = Mutex::new(());
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// A cached message and how to reach its server, as the Mail view passes them
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteMessage {
+    pub account_id: String,
+    pub email_id: String,
+    pub folder: Option<String>,
+    pub imap_host: Option<String>,
+    pub imap_port: Option<u16>,
+    pub imap_password: Option<String>,
+    pub email: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Operation {
+    SendEmail { request: SendEmailRequest },
+    SetRead { message: RemoteMessage, read: bool },
+    Archive { message: RemoteMessage },
+    /// Pull and push Google Calendar; queued once however often it failed
+    CalendarSync,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedOperation {
+    pub id: String,
+    /// RFC 3339
+    pub queued_at: String,
+    pub operation: Operation,
+    /// Replays that failed for another reason than the network
+    #[serde(default)]
+    pub attempts: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Queued operations, oldest first
+pub fn pending(vault: &Path) -> Vec<QueuedOperation> {
+    fs::read_to_string(vault.join(OUTBOX_FILE)).ok().and_then(|raw| serde_json::from_str(&raw).ok()).unwrap_or_default()
+}
+
+/// Add an operation to the end of the queue. A calendar sync already
+/// waiting is returned instead of queuing a second one.
+pub fn enqueue(vault: &Path, operation: Operation) -> Result<QueuedOperation, String> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut queue = pending(vault);
+    if matches!(operation, Operation::CalendarSync) {
+        if let Some(waiting) = queue.iter().find(|q| matches!(q.operation, Operation::CalendarSync)) {
+            return Ok(waiting.clone());
+        }
+    }
+    let queued = QueuedOperation {
+        id: uuid::Uuid::new_v4().to_string(),
+        queued_at: Local::now().to_rfc3339(),
+        operation,
+        attempts: 0,
+        last_error: None,
+    };
+    queue.push(queued.clone());
+    save(vault, &queue)?;
+    Ok(queued)
+}
+
+/// Drop an operation, replayed or discarded; false when it wasn't queued
+pub fn remove(vault: &Path, id: &str) -> Result<bool, String> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut queue = pending(vault);
+    let before = queue.len();
+    queue.retain(|q| q.id != id);
+    if queue.len() == before {
+        return Ok(false);
+    }
+    save(vault, &queue)?;
+    Ok(true)
+}
+
+/// Note a replay that failed for a reason other than the network
+pub fn record_failure(vault: &Path, id: &str, error: &str) -> Result<(), String> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut queue = pending(vault);
+    let Some(queued) = queue.iter_mut().find(|q| q.id == id) else { return Ok(()) };
+    queued.attempts += 1;
+    queued.last_error = Some(error.to_string());
+    save(vault, &queue)
+}
+
+pub fn is_network_error(error: &str) -> bool {
+    let lower = error.to_lowercase();
+    NETWORK_MARKERS.iter().any(|m| lower.contains(m))
+}
+
+/// Whether any public resolver answers a TCP connect
+pub fn is_online() -> bool {
+    PROBES
+        .iter()
+        .filter_map(|probe| probe.to_socket_addrs().ok()?.next())
+        .any(|addr| TcpStream::connect_timeout(&addr, PROBE_TIMEOUT).is_ok())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn save(vault: &Path, queue: &[QueuedOperation]) -> Result<(), String> {
+    let path = vault.join(OUTBOX_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(queue).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(path, json).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(email_id: &str) -> RemoteMessage {
+        RemoteMessage {
+            account_id: "work".into(),
+            email_id: email_id.into(),
+            folder: Some("INBOX".into()),
+            imap_host: None,
+            imap_port: None,
+            imap_password: None,
+            email: None,
+        }
+    }
+
+    #[test]
+    fn test_is_network_error() {
+        assert!(is_network_error("IMAP connection failed: failed to lookup address information"));
+        assert!(is_network_error("连接失败: Network is unreachable (os error 51)"));
+        assert!(is_network_error("Failed to send: Connection error: timed out"));
+        assert!(is_network_error("请求失败: Dns Failed: resolve dns name 'www.googleapis.com:443'"));
+        assert!(!is_network_error("Login failed: [AUTHENTICATIONFAILED] Invalid credentials"));
+        assert!(!is_network_error("Invalid recipient address: Missing domain or user"));
+    }
+
+    #[test]
+    fn test_queue() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path();
+        assert!(pending(vault).is_empty());
+
+        let read = enqueue(vault, Operation::SetRead { message: message("INBOX_1"), read: true }).unwrap();
+        let sync = enqueue(vault, Operation::CalendarSync).unwrap();
+        enqueue(vault, Operation::Archive { message: message("INBOX_2") }).unwrap();
+        assert_eq!(enqueue(vault, Operation::CalendarSync).unwrap().id, sync.id);
+        assert_eq!(pending(vault).len(), 3);
+
+        record_failure(vault, &read.id, "Failed to select folder: NO").unwrap();
+        let queue = pending(vault);
+        assert_eq!((queue[0].attempts, queue[0].last_error.as_deref()), (1, Some("Failed to select folder: NO")));
+        assert!(matches!(&queue[2].operation, Operation::Archive { message } if message.email_id == "INBOX_2"));
+
+        assert!(remove(vault, &read.id).unwrap());
+        assert!(!remove(vault, &read.id).unwrap());
+        assert_eq!(pending(vault).len(), 2);
+    }
+}
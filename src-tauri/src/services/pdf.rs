@@ -0,0 +1,114 @@
+//! Text of PDFs in the vault, extracted per page and cached under
+//! .lifeos/pdf-text so note search can include them.
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::durable;
+use super::notes::vault_rel;
+
+const CACHE_DIR: &str = ".lifeos/pdf-text";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PdfText {
+    /// Vault-relative path of the PDF
+    pub source: String,
+    /// Unix seconds of the PDF when it was extracted
+    pub modified: u64,
+    /// One entry per page, first page first
+    pub pages: Vec<String>,
+}
+
+/// Page texts of `pdf`, from the cache while the file is unchanged
+pub fn extract(vault: &Path, pdf: &Path) -> Result<PdfText, String> {
+    let source = vault_rel(vault, pdf);
+    let modified = fs::metadata(pdf)
+        .map_err(|e| tr!("Failed to read: {}", e))?
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs());
+    let cache = cache_path(vault, &source);
+    if let Some(hit) = read_cached(&cache).filter(|c| c.modified == modified) {
+        return Ok(hit);
+    }
+    let bytes = fs::read(pdf).map_err(|e| tr!("Failed to read: {}", e))?;
+    let pages = pdf_extract::extract_text_from_mem_by_pages(&bytes).map_err(|e| tr!("Failed to extract PDF text: {}", e))?;
+    let text = PdfText { source, modified, pages: pages.iter().map(|p| tidy(p)).collect() };
+    if let Some(parent) = cache.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let json = serde_json::to_string(&text).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(&cache, json).map_err(|e| tr!("write_file failed: {}", e))?;
+    Ok(text)
+}
+
+/// Extract every PDF under `dir` that is new or changed; returns (indexed, failed)
+pub fn index_dir(vault: &Path, dir: &Path) -> (usize, Vec<String>) {
+    let mut indexed = 0;
+    let mut failed = Vec::new();
+    let pdfs = WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("pdf")));
+    for entry in pdfs {
+        match extract(vault, entry.path()) {
+            Ok(_) => indexed += 1,
+            Err(e) => failed.push(format!("{}: {e}", entry.path().display())),
+        }
+    }
+    (indexed, failed)
+}
+
+/// Every cached extraction whose PDF still exists
+pub fn cached(vault: &Path) -> Vec<(PathBuf, PdfText)> {
+    let Ok(entries) = fs::read_dir(vault.join(CACHE_DIR)) else { return vec![] };
+    let mut out: Vec<(PathBuf, PdfText)> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| read_cached(&e.path()))
+        .map(|t| (vault.join(&t.source), t))
+        .filter(|(path, _)| path.exists())
+        .collect();
+    out.sort_by(|a, b| a.1.source.cmp(&b.1.source));
+    out
+}
+
+fn cache_path(vault: &Path, source: &str) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    vault.join(CACHE_DIR).join(format!("{:016x}.json", hasher.finish()))
+}
+
+fn read_cached(path: &Path) -> Option<PdfText> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// Drop the blank-line runs and trailing spaces the extractor leaves
+fn tidy(page: &str) -> String {
+    let mut out: Vec<&str> = Vec::new();
+    for line in page.lines().map(str::trim_end) {
+        if line.trim().is_empty() && out.last().is_none_or(|l| l.trim().is_empty()) {
+            continue;
+        }
+        out.push(line);
+    }
+    out.join("\n").trim().to_string()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tidy() {
+        assert_eq!(tidy("\n\nTitle  \n\n\n\nBody line\n  \n"), "Title\n\nBody line");
+    }
+}
@@ -0,0 +1,107 @@
+//! People notes: people/<slug>.md with a `PersonMeta` frontmatter and the
+//! person's notes as the body. Mail, meetings, chats and CardDAV sync read
+//! and write them through here.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::records;
+
+pub const PEOPLE_DIR: &str = "people";
+
+/// Typed frontmatter of people/<slug>.md. Keys the app does not know about
+/// are kept in `extra` so hand-added fields survive a save.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PersonMeta {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub emails: Vec<String>,
+    /// `YYYY-MM-DD`, or `MM-DD` when the year is unknown
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub birthday: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub company: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// `YYYY-MM-DD`; bumped by `link_people` from mail and meeting notes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_contacted: Option<String>,
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_yaml::Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Person {
+    /// File stem; empty on create to derive it from the name
+    #[serde(default)]
+    pub slug: String,
+    #[serde(default)]
+    pub path: String,
+    #[serde(flatten)]
+    pub meta: PersonMeta,
+    /// Markdown body
+    #[serde(default)]
+    pub notes: String,
+}
+
+pub fn load_people(vault: &Path) -> Vec<Person> {
+    let mut people: Vec<Person> = records::list(vault, PEOPLE_DIR).iter().filter_map(|p| read_person(p)).collect();
+    people.sort_by(|a, b| a.meta.name.cmp(&b.meta.name));
+    people
+}
+
+pub fn person_path(vault: &Path, slug: &str) -> Result<PathBuf, String> {
+    records::path(vault, PEOPLE_DIR, slug)
+}
+
+pub fn read_person(path: &Path) -> Option<Person> {
+    let record = records::read::<PersonMeta>(path)?;
+    let mut meta = record.meta.unwrap_or_default();
+    if meta.name.is_empty() {
+        meta.name = record.slug.clone();
+    }
+    Some(Person { slug: record.slug, path: record.path, meta, notes: record.body })
+}
+
+pub fn write_person(path: &Path, person: &Person) -> Result<(), String> {
+    records::write(path, &person.meta, person.notes.trim_start())
+}
+
+pub fn unique_slug(vault: &Path, base: &str) -> String {
+    records::unique_slug(vault, PEOPLE_DIR, base)
+}
+
+/// `Jane <jane@x.com>` → `jane@x.com`
+pub fn bare_address(s: &str) -> String {
+    let s = s.trim();
+    let inner = match (s.rfind('<'), s.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &s[start + 1..end],
+        _ => s,
+    };
+    inner.trim().to_lowercase()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_person_roundtrip_keeps_unknown_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jane.md");
+        fs::write(&path, "---\nname: Jane\nemails: [jane@x.com]\nnickname: JD\n---\n\nMet at PyCon.\n").unwrap();
+        let p = read_person(&path).unwrap();
+        assert_eq!(p.meta.emails, vec!["jane@x.com"]);
+        write_person(&path, &p).unwrap();
+        let raw = fs::read_to_string(&path).unwrap();
+        assert!(raw.contains("nickname: JD"));
+        assert!(raw.ends_with("Met at PyCon.\n"));
+    }
+}
@@ -0,0 +1,83 @@
+//! Background loops bound to the open vault: reminders, schedulers and sync.
+//! Each feature keeps one `Periodic` in a static; starting it again (say
+//! after switching vaults) stops the loop it was running first.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How often a loop wakes to check whether it was stopped
+const POLL: Duration = Duration::from_secs(1);
+
+/// Slot for at most one running loop
+pub struct Periodic(Mutex<Option<Arc<AtomicBool>>>);
+
+impl Periodic {
+    pub const fn new() -> Self {
+        Periodic(Mutex::new(None))
+    }
+
+    /// Call `tick` on a new thread now and then every `every`, replacing
+    /// any loop already running here. Loops whose interval comes from
+    /// settings pass one second and keep their own last-run time, so edits
+    /// apply without a restart.
+    pub fn start(&self, every: Duration, mut tick: impl FnMut() + Send + 'static) {
+        let stop = Arc::new(AtomicBool::new(false));
+        if let Some(previous) = self.0.lock().unwrap().replace(stop.clone()) {
+            previous.store(true, Ordering::Relaxed);
+        }
+        std::thread::spawn(move || {
+            let mut last: Option<Instant> = None;
+            while !stop.load(Ordering::Relaxed) {
+                if last.is_none_or(|t| t.elapsed() >= every) {
+                    last = Some(Instant::now());
+                    tick();
+                }
+                std::thread::sleep(POLL);
+            }
+        });
+    }
+
+    /// Stop the running loop, if any; a tick in progress finishes first
+    pub fn stop(&self) {
+        if let Some(stop) = self.0.lock().unwrap().take() {
+            stop.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Default for Periodic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_restart_replaces_and_stop_ends_the_loop() {
+        let slot = Periodic::new();
+        let (tx, rx) = mpsc::channel();
+        let first = tx.clone();
+        slot.start(Duration::from_secs(3600), move || {
+            let _ = first.send("first");
+        });
+        assert_eq!(rx.recv_timeout(Duration::from_secs(2)), Ok("first"));
+
+        slot.start(POLL, move || {
+            let _ = tx.send("second");
+        });
+        assert_eq!(rx.recv_timeout(Duration::from_secs(2)), Ok("second"));
+        assert_eq!(rx.recv_timeout(Duration::from_secs(2)), Ok("second"));
+        slot.stop();
+        // Both threads exit, dropping their senders, without another tick
+        assert_eq!(rx.recv_timeout(Duration::from_secs(3)), Err(mpsc::RecvTimeoutError::Disconnected));
+    }
+}
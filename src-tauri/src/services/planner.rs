@@ -0,0 +1,62 @@
+//! Time blocks of the day planner: daily/tasks/<date>.schedule.yaml lists
+//! the tasks put on that day's timeline. Google Calendar sync pushes them
+//! out as events.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::durable;
+
+const DAY_DIR: &str = "daily/tasks";
+/// daily/tasks/<date>.schedule.yaml, next to the day note. Kept apart from
+/// it so the Daily view can rewrite the note without losing the plan.
+const SCHEDULE_SUFFIX: &str = ".schedule.yaml";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TimeBlock {
+    /// `YYYY-MM-DD:line` (day file, 0-based line) or a task's 🆔 id
+    pub task_ref: String,
+    /// Task text when it was scheduled
+    pub title: String,
+    /// HH:MM
+    pub start: String,
+    /// Minutes
+    pub duration: u32,
+}
+
+/// (date, block) of every day from `from` on, by date
+pub fn blocks_from(vault: &Path, from: NaiveDate) -> Vec<(String, TimeBlock)> {
+    let Ok(entries) = fs::read_dir(vault.join(DAY_DIR)) else { return Vec::new() };
+    let mut dates: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.file_name().to_string_lossy().strip_suffix(SCHEDULE_SUFFIX).map(str::to_string))
+        .filter(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").is_ok_and(|d| d >= from))
+        .collect();
+    dates.sort();
+    dates.into_iter().flat_map(|date| load(vault, &date).into_iter().map(move |b| (date.clone(), b))).collect()
+}
+
+pub fn schedule_path(vault: &Path, date: &str) -> PathBuf {
+    vault.join(DAY_DIR).join(format!("{date}{SCHEDULE_SUFFIX}"))
+}
+
+/// Blocks of `date`; none when it has no schedule file
+pub fn load(vault: &Path, date: &str) -> Vec<TimeBlock> {
+    fs::read_to_string(schedule_path(vault, date)).ok().and_then(|raw| serde_yaml::from_str(&raw).ok()).unwrap_or_default()
+}
+
+/// Replace the blocks of `date`; an empty list removes the file
+pub fn save(vault: &Path, date: &str, blocks: &[TimeBlock]) -> Result<(), String> {
+    let path = schedule_path(vault, date);
+    if blocks.is_empty() {
+        let _ = fs::remove_file(&path);
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let raw = serde_yaml::to_string(blocks).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(path, raw).map_err(|e| tr!("write_file failed: {}", e))
+}
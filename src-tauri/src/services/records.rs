@@ -0,0 +1,92 @@
+//! Folders holding one note per record — people/, life/books/, life/media/,
+//! life/trips/ — named `<slug>.md` with the record's fields as YAML
+//! frontmatter. Slugs arrive from the frontend, so every path is built by
+//! `path`, which keeps them inside their folder.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::durable;
+use super::notes::{check_slug, split_frontmatter};
+
+/// A record note as read from disk
+pub struct Record<T> {
+    /// File stem
+    pub slug: String,
+    pub path: String,
+    /// None when the note has no frontmatter or it doesn't fit `T`
+    pub meta: Option<T>,
+    pub body: String,
+}
+
+/// `<dir>/<slug>.md` in the vault; a slug that could leave `dir` is an error
+pub fn path(vault: &Path, dir: &str, slug: &str) -> Result<PathBuf, String> {
+    check_slug(slug)?;
+    Ok(vault.join(dir).join(format!("{slug}.md")))
+}
+
+/// `base`, or `base-2`, `base-3`, ... when that file is taken
+pub fn unique_slug(vault: &Path, dir: &str, base: &str) -> String {
+    let mut slug = base.to_string();
+    let mut n = 2;
+    while vault.join(dir).join(format!("{slug}.md")).exists() {
+        slug = format!("{base}-{n}");
+        n += 1;
+    }
+    slug
+}
+
+/// The .md files directly in `dir`, in no particular order
+pub fn list(vault: &Path, dir: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(vault.join(dir)) else { return vec![] };
+    entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "md")).collect()
+}
+
+pub fn read<T: DeserializeOwned>(path: &Path) -> Option<Record<T>> {
+    let raw = fs::read_to_string(path).ok()?;
+    let (yaml, body) = split_frontmatter(&raw);
+    Some(Record {
+        slug: path.file_stem()?.to_string_lossy().to_string(),
+        path: path.to_string_lossy().to_string(),
+        meta: yaml.and_then(|y| serde_yaml::from_str(y).ok()),
+        body: body.to_string(),
+    })
+}
+
+/// `meta` as frontmatter, then `body`, creating the folder if needed
+pub fn write<T: Serialize>(path: &Path, meta: &T, body: &str) -> Result<(), String> {
+    let yaml = serde_yaml::to_string(meta).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    durable::write(path, format!("---\n{yaml}---\n\n{body}")).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_path_stays_in_folder_and_slugs_are_unique() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path();
+        assert_eq!(path(vault, "life/books", "../../x").unwrap_err(), tr!("Invalid slug: {}", "../../x"));
+        let first = path(vault, "life/books", "dune").unwrap();
+        assert_eq!(first, vault.join("life/books/dune.md"));
+
+        let meta: BTreeMap<String, String> = [("title".to_string(), "Dune".to_string())].into();
+        write(&first, &meta, "# Dune\n").unwrap();
+        assert_eq!(unique_slug(vault, "life/books", "dune"), "dune-2");
+        assert_eq!(list(vault, "life/books"), vec![first.clone()]);
+
+        let record = read::<BTreeMap<String, String>>(&first).unwrap();
+        assert_eq!((record.slug.as_str(), record.meta, record.body.as_str()), ("dune", Some(meta), "# Dune\n"));
+    }
+}
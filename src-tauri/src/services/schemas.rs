@@ -0,0 +1,309 @@
+//! Frontmatter schemas per folder, so features reading note metadata can
+//! trust it. One YAML file per schema in .lifeos/schemas/:
+//!
+//! ```yaml
+//! folder: projects
+//! fields:
+//!   title: { required: true }
+//!   status: { required: true }
+//!   priority: { required: true, enum: [low, medium, high, urgent] }
+//!   progress: { type: number, min: 0, max: 100 }
+//!   due: { type: date }
+//! ```
+//!
+//! A schema covers every note below its folder; the deepest folder wins.
+//! Types are `string` (default), `number`, `integer`, `date` (YYYY-MM-DD),
+//! `boolean` and `list`. Numbers and booleans may be quoted, as the app
+//! writes them. Empty values only fail `required`.
+//!
+//! The built-in projects and goals schemas apply until a file of the same
+//! name (projects.yaml, goals.yaml) replaces them.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use super::notes::{split_frontmatter, vault_rel};
+
+pub const SCHEMAS_DIR: &str = ".lifeos/schemas";
+
+const BUILTIN: &[(&str, &str)] = &[
+    (
+        "projects",
+        "folder: projects\nfields:\n  title: { required: true }\n  status: { required: true }\n  priority: { required: true, enum: [low, medium, high, urgent] }\n  progress: { type: number, min: 0, max: 100 }\n  due: { type: date }\n",
+    ),
+    (
+        "goals",
+        "folder: planning/goals\nfields:\n  title: { required: true }\n  type: { required: true, enum: [annual, quarterly, monthly] }\n  year: { required: true, type: integer, min: 1900, max: 9999 }\n  quarter: { type: integer, min: 1, max: 4 }\n  month: { type: integer, min: 1, max: 12 }\n  progress: { type: number, min: 0, max: 100 }\n",
+    ),
+];
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Schema {
+    /// File stem under .lifeos/schemas/
+    #[serde(default)]
+    pub name: String,
+    /// Vault-relative
+    pub folder: String,
+    #[serde(default)]
+    pub fields: BTreeMap<String, FieldRule>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct FieldRule {
+    #[serde(default)]
+    pub required: bool,
+    #[serde(rename = "type", default)]
+    pub kind: FieldType,
+    /// Allowed values, compared as text
+    #[serde(rename = "enum", default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    #[default]
+    String,
+    Number,
+    Integer,
+    Date,
+    Boolean,
+    List,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// Vault-relative
+    pub path: String,
+    pub schema: String,
+    /// Empty when the frontmatter as a whole is unreadable
+    pub field: String,
+    pub message: String,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// The vault's schemas over the built-in ones, by name. Files that fail to
+/// parse are skipped with a warning.
+pub fn load(vault: &Path) -> Vec<Schema> {
+    let mut schemas: BTreeMap<String, Schema> = BUILTIN
+        .iter()
+        .filter_map(|(name, raw)| serde_yaml::from_str::<Schema>(raw).ok().map(|s| (name.to_string(), Schema { name: name.to_string(), ..s })))
+        .collect();
+    if let Ok(entries) = fs::read_dir(vault.join(SCHEMAS_DIR)) {
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            if !path.extension().is_some_and(|e| e == "yaml" || e == "yml") {
+                continue;
+            }
+            let name = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            let parsed = fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|raw| serde_yaml::from_str::<Schema>(&raw).map_err(|e| e.to_string()));
+            match parsed {
+                Ok(schema) => {
+                    let folder = schema.folder.trim_matches('/').to_string();
+                    schemas.insert(name.clone(), Schema { name, folder, ..schema });
+                }
+                Err(e) => println!("[WARN] skipping schema {}: {e}", path.display()),
+            }
+        }
+    }
+    schemas.into_values().collect()
+}
+
+/// The schema for a vault-relative note path: the one with the deepest folder
+pub fn schema_for<'a>(schemas: &'a [Schema], rel: &str) -> Option<&'a Schema> {
+    schemas
+        .iter()
+        .filter(|s| s.folder.is_empty() || rel.strip_prefix(&s.folder).is_some_and(|rest| rest.starts_with('/')))
+        .max_by_key(|s| s.folder.len())
+}
+
+/// Violations of `frontmatter` against the schema covering `rel`, if any
+pub fn check(schemas: &[Schema], rel: &str, frontmatter: &Value) -> Vec<Violation> {
+    let Some(schema) = schema_for(schemas, rel) else { return vec![] };
+    let violation = |field: &str, message: String| Violation {
+        path: rel.to_string(),
+        schema: schema.name.clone(),
+        field: field.to_string(),
+        message,
+    };
+    let mut out = Vec::new();
+    for (field, rule) in &schema.fields {
+        let value = frontmatter.get(field).filter(|v| !is_empty(v));
+        match value {
+            None if rule.required => out.push(violation(field, tr!("Missing required field: {}", field))),
+            None => {}
+            Some(value) => {
+                if let Err(message) = check_value(field, rule, value) {
+                    out.push(violation(field, message));
+                }
+            }
+        }
+    }
+    out
+}
+
+/// `check` for a note at an absolute `path`; nothing outside the vault is checked
+pub fn check_note(vault: &Path, path: &Path, frontmatter: &Value) -> Vec<Violation> {
+    match path.strip_prefix(vault) {
+        Ok(rel) => check(&load(vault), &rel.to_string_lossy().replace('\\', "/"), frontmatter),
+        Err(_) => vec![],
+    }
+}
+
+/// Check every note under `scope` (vault-relative folder or note; default the
+/// whole vault). Returns how many notes a schema covered, and the violations.
+pub fn validate(vault: &Path, scope: Option<&str>) -> Result<(usize, Vec<Violation>), String> {
+    let root = match scope.map(|s| s.trim().trim_matches('/')).filter(|s| !s.is_empty()) {
+        Some(scope) => vault.join(scope),
+        None => vault.to_path_buf(),
+    };
+    if !root.exists() {
+        return Err(tr!("File not found: {}", root.display()));
+    }
+    let schemas = load(vault);
+    let (mut notes, mut violations) = (0, Vec::new());
+    for entry in walkdir::WalkDir::new(&root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|x| x == "md"))
+    {
+        let rel = vault_rel(vault, entry.path());
+        let Some(schema) = schema_for(&schemas, &rel) else { continue };
+        notes += 1;
+        let raw = fs::read_to_string(entry.path()).unwrap_or_default();
+        match parse_frontmatter(&raw) {
+            Ok(frontmatter) => violations.extend(check(&schemas, &rel, &frontmatter)),
+            Err(e) => violations.push(Violation { path: rel, schema: schema.name.clone(), field: String::new(), message: e }),
+        }
+    }
+    Ok((notes, violations))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn parse_frontmatter(raw: &str) -> Result<Value, String> {
+    match split_frontmatter(raw).0 {
+        Some(yaml) if !yaml.trim().is_empty() => {
+            let value: Value = serde_yaml::from_str(yaml).map_err(|e| tr!("Invalid frontmatter: {}", e))?;
+            Ok(if value.is_object() { value } else { Value::Object(Default::default()) })
+        }
+        _ => Ok(Value::Object(Default::default())),
+    }
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.trim().is_empty(),
+        Value::Array(items) => items.is_empty(),
+        _ => false,
+    }
+}
+
+fn check_value(field: &str, rule: &FieldRule, value: &Value) -> Result<(), String> {
+    let text = match value {
+        Value::String(s) => s.trim().to_string(),
+        other => other.to_string(),
+    };
+    let number = || value.as_f64().or_else(|| text.parse::<f64>().ok());
+    match rule.kind {
+        FieldType::String => {}
+        FieldType::Number => {
+            number().ok_or_else(|| tr!("{} must be a number", field))?;
+        }
+        FieldType::Integer => {
+            number().filter(|n| n.fract() == 0.0).ok_or_else(|| tr!("{} must be a whole number", field))?;
+        }
+        FieldType::Date => {
+            let day = text.get(..10).unwrap_or(&text);
+            chrono::NaiveDate::parse_from_str(day, "%Y-%m-%d").map_err(|_| tr!("{} must be a date (YYYY-MM-DD)", field))?;
+        }
+        FieldType::Boolean => {
+            if !value.is_boolean() && text != "true" && text != "false" {
+                return Err(tr!("{} must be true or false", field));
+            }
+        }
+        // Comma-separated text is how the app writes tags
+        FieldType::List => {
+            if !value.is_array() && !value.is_string() {
+                return Err(tr!("{} must be a list", field));
+            }
+        }
+    }
+    if !rule.values.is_empty() && !rule.values.contains(&text) {
+        return Err(tr!("{} must be one of: {}", field, rule.values.join(", ")));
+    }
+    if let Some(n) = number().filter(|_| matches!(rule.kind, FieldType::Number | FieldType::Integer)) {
+        if rule.min.is_some_and(|min| n < min) {
+            return Err(tr!("{} must be at least {}", field, rule.min.unwrap_or_default()));
+        }
+        if rule.max.is_some_and(|max| n > max) {
+            return Err(tr!("{} must be at most {}", field, rule.max.unwrap_or_default()));
+        }
+    }
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_schemas() {
+        let schemas = load(Path::new("/nonexistent"));
+        assert_eq!(schema_for(&schemas, "projects/active/a.md").map(|s| s.name.as_str()), Some("projects"));
+        assert!(schema_for(&schemas, "projects-old/a.md").is_none());
+
+        // As the app writes them: numbers quoted, optional fields empty
+        let project = serde_json::json!({ "title": "官网改版", "status": "backlog", "priority": "high", "progress": "0", "due": "" });
+        assert!(check(&schemas, "projects/官网改版.md", &project).is_empty());
+
+        let goal = serde_json::json!({ "title": "跑半马", "type": "yearly", "year": "2025.5", "month": "13" });
+        let fields: Vec<_> = check(&schemas, "planning/goals/2025-annual-跑半马.md", &goal).into_iter().map(|v| v.field).collect();
+        assert_eq!(fields, ["month", "type", "year"]);
+    }
+
+    #[test]
+    fn test_vault_schemas_and_validate() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path();
+        fs::create_dir_all(vault.join(SCHEMAS_DIR)).unwrap();
+        fs::write(vault.join(SCHEMAS_DIR).join("projects.yaml"), "folder: projects\nfields:\n  owner: { required: true }\n").unwrap();
+        fs::write(vault.join(SCHEMAS_DIR).join("reading.yaml"), "folder: /life/books/\nfields:\n  rating: { type: integer, min: 1, max: 5 }\n").unwrap();
+        fs::create_dir_all(vault.join("projects")).unwrap();
+        fs::create_dir_all(vault.join("life/books")).unwrap();
+        fs::write(vault.join("projects/a.md"), "---\ntitle: A\n---\n").unwrap();
+        fs::write(vault.join("projects/b.md"), "---\nowner: me\n---\n").unwrap();
+        fs::write(vault.join("life/books/c.md"), "---\nrating: 7\n---\n").unwrap();
+        fs::write(vault.join("life/books/d.md"), "---\nrating: [1\n---\n").unwrap();
+        fs::write(vault.join("inbox.md"), "no schema here").unwrap();
+
+        let (notes, violations) = validate(vault, None).unwrap();
+        assert_eq!(notes, 4);
+        let found: Vec<_> = violations.iter().map(|v| (v.path.as_str(), v.field.as_str())).collect();
+        assert_eq!(found, [("life/books/c.md", "rating"), ("life/books/d.md", ""), ("projects/a.md", "owner")]);
+        assert_eq!(validate(vault, Some("projects")).unwrap().0, 2);
+        assert!(validate(vault, Some("missing")).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+//! Secret values live in the macOS Keychain (service `LifeOS`, account =
+//! secret name); the vault only keeps metadata in .lifeos/secrets.yaml.
+//! Config values written as `keychain:<name>` are resolved through here.
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use super::durable;
+
+const METADATA_FILE: &str = ".lifeos/secrets.yaml";
+#[cfg(target_os = "macos")]
+const SERVICE: &str = "LifeOS";
+/// Prefix marking a config value as a Keychain reference
+pub const REFERENCE_PREFIX: &str = "keychain:";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct SecretMeta {
+    pub name: String,
+    #[serde(default)]
+    pub note: String,
+    /// RFC 3339
+    #[serde(default)]
+    pub updated: String,
+}
+
+/// Store (or replace) a secret and record its metadata
+pub fn store(vault: &Path, name: &str, value: &str, note: Option<&str>) -> Result<SecretMeta, String> {
+    validate_name(name)?;
+    if value.is_empty() {
+        return Err(tr!("Secret value is empty"));
+    }
+    // `security -i` reads one command per line; a line break would end this one early
+    if value.contains(['\r', '\n']) {
+        return Err(tr!("Secret value can't contain line breaks"));
+    }
+    keychain::set(name, value)?;
+    let mut all = list(vault);
+    let meta = match all.iter_mut().find(|m| m.name == name) {
+        Some(m) => m,
+        None => {
+            all.push(SecretMeta { name: name.to_string(), ..Default::default() });
+            all.last_mut().expect("just pushed")
+        }
+    };
+    if let Some(note) = note {
+        meta.note = note.trim().to_string();
+    }
+    meta.updated = Local::now().to_rfc3339();
+    let meta = meta.clone();
+    write_list(vault, &mut all)?;
+    Ok(meta)
+}
+
+pub fn get(name: &str) -> Result<String, String> {
+    validate_name(name)?;
+    keychain::get(name)
+}
+
+pub fn delete(vault: &Path, name: &str) -> Result<(), String> {
+    validate_name(name)?;
+    keychain::delete(name)?;
+    let mut all = list(vault);
+    all.retain(|m| m.name != name);
+    write_list(vault, &mut all)
+}
+
+/// Metadata only, never values
+pub fn list(vault: &Path) -> Vec<SecretMeta> {
+    fs::read_to_string(vault.join(METADATA_FILE))
+        .ok()
+        .and_then(|raw| serde_yaml::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// `keychain:<name>` → the stored secret; other values are returned as-is
+pub fn resolve(value: &str) -> Result<String, String> {
+    match value.trim().strip_prefix(REFERENCE_PREFIX) {
+        Some(name) => get(name.trim()),
+        None => Ok(value.to_string()),
+    }
+}
+
+fn write_list(vault: &Path, all: &mut [SecretMeta]) -> Result<(), String> {
+    all.sort_by(|a, b| a.name.cmp(&b.name));
+    let path = vault.join(METADATA_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let yaml = serde_yaml::to_string(&all).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(&path, yaml).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+/// Letters, digits and `.-_` — the name goes into Keychain commands
+fn validate_name(name: &str) -> Result<(), String> {
+    let ok = !name.is_empty() && name.len() <= 64 && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if ok {
+        Ok(())
+    } else {
+        Err(tr!("Invalid secret name: {}", name))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod keychain {
+    use super::SERVICE;
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    /// Through `security -i` on stdin, so the value never appears in argv
+    pub fn set(name: &str, value: &str) -> Result<(), String> {
+        let line = format!("add-generic-password -U -a {} -s {} -w {}\n", quote(name), quote(SERVICE), quote(value));
+        let mut child = Command::new("security")
+            .arg("-i")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| tr!("Keychain error: {}", e))?;
+        child.stdin.take().ok_or_else(|| tr!("Keychain error: {}", "stdin"))?.write_all(line.as_bytes()).map_err(|e| tr!("Keychain error: {}", e))?;
+        let output = child.wait_with_output().map_err(|e| tr!("Keychain error: {}", e))?;
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        // Interactive mode exits 0 even when the command fails
+        if output.status.success() && stderr.is_empty() {
+            Ok(())
+        } else {
+            Err(tr!("Keychain error: {}", stderr))
+        }
+    }
+
+    pub fn get(name: &str) -> Result<String, String> {
+        let output = Command::new("security")
+            .args(["find-generic-password", "-a", name, "-s", SERVICE, "-w"])
+            .output()
+            .map_err(|e| tr!("Keychain error: {}", e))?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim_end_matches('\n').to_string())
+        } else {
+            Err(tr!("Secret not found: {}", name))
+        }
+    }
+
+    pub fn delete(name: &str) -> Result<(), String> {
+        let output = Command::new("security")
+            .args(["delete-generic-password", "-a", name, "-s", SERVICE])
+            .output()
+            .map_err(|e| tr!("Keychain error: {}", e))?;
+        // Already gone is fine: the metadata is removed either way
+        if output.status.success() || output.status.code() == Some(44) {
+            Ok(())
+        } else {
+            Err(tr!("Keychain error: {}", String::from_utf8_lossy(&output.stderr).trim()))
+        }
+    }
+
+    fn quote(s: &str) -> String {
+        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod keychain {
+    use crate::services::unsupported;
+
+    pub fn set(_name: &str, _value: &str) -> Result<(), String> {
+        Err(unsupported("Keychain"))
+    }
+
+    pub fn get(_name: &str) -> Result<String, String> {
+        Err(unsupported("Keychain"))
+    }
+
+    pub fn delete(_name: &str) -> Result<(), String> {
+        Err(unsupported("Keychain"))
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_and_references() {
+        assert!(validate_name("tmdb.api_key").is_ok());
+        assert!(validate_name("server pw").is_err());
+        assert!(validate_name("").is_err());
+        assert_eq!(resolve("plain-token").unwrap(), "plain-token");
+        assert!(resolve("keychain:bad name").is_err());
+    }
+
+    #[test]
+    fn test_value_with_line_break_rejected() {
+        let vault = tempfile::tempdir().unwrap();
+        let err = tr!("Secret value can't contain line breaks");
+        assert_eq!(store(vault.path(), "pw", "a\ndelete-keychain login.keychain", None).unwrap_err(), err);
+        assert_eq!(store(vault.path(), "pw", "a\r", None).unwrap_err(), err);
+        assert!(list(vault.path()).is_empty());
+    }
+}
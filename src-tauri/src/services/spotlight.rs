@@ -0,0 +1,269 @@
+//! Spotlight metadata for vault notes.
+//!
+//! Spotlight already indexes .md files by content; what it lacks is a title
+//! and keywords, so results show file names and tags don't match. Those are
+//! given to it as `com.apple.metadata:kMDItem*` extended attributes holding
+//! binary plists, the form Spotlight reads them in. Writing the attributes
+//! is left to the macOS command; everything here is plain data.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use walkdir::WalkDir;
+
+use super::durable;
+use super::notes::{split_frontmatter, vault_rel};
+
+/// Kept apart from settings.yaml, which the frontend rewrites with only its own keys
+const SETTINGS_FILE: &str = ".lifeos/spotlight.yaml";
+/// Notes last given metadata: vault-relative path → mtime (seconds)
+const STATE_FILE: &str = ".lifeos/spotlight-state.json";
+pub const ATTRIBUTES: [&str; 3] =
+    ["com.apple.metadata:kMDItemTitle", "com.apple.metadata:kMDItemKeywords", "com.apple.metadata:kMDItemDescription"];
+const DESCRIPTION_CHARS: usize = 200;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Settings {
+    /// On unless turned off
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NoteMetadata {
+    pub title: String,
+    pub keywords: Vec<String>,
+    pub description: String,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub fn settings(vault: &Path) -> Settings {
+    fs::read_to_string(vault.join(SETTINGS_FILE))
+        .ok()
+        .and_then(|raw| serde_yaml::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_settings(vault: &Path, settings: &Settings) -> Result<(), String> {
+    let raw = serde_yaml::to_string(settings).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    write(&vault.join(SETTINGS_FILE), &raw)
+}
+
+/// Title from the frontmatter `title`, else the first `# ` heading, else the
+/// file name; keywords from frontmatter `tags` and inline `#tags`
+pub fn metadata(raw: &str, stem: &str) -> NoteMetadata {
+    let (frontmatter, body) = split_frontmatter(raw);
+    let yaml: serde_yaml::Value = frontmatter.and_then(|f| serde_yaml::from_str(f).ok()).unwrap_or_default();
+
+    let title = yaml
+        .get("title")
+        .and_then(|t| t.as_str())
+        .map(str::to_string)
+        .or_else(|| body.lines().find_map(|l| l.strip_prefix("# ")).map(|t| t.trim().to_string()))
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| stem.to_string());
+
+    let mut keywords: Vec<String> = match yaml.get("tags") {
+        Some(serde_yaml::Value::Sequence(tags)) => tags.iter().filter_map(|t| t.as_str()).map(str::to_string).collect(),
+        Some(serde_yaml::Value::String(tags)) => tags.split(',').map(|t| t.trim().to_string()).collect(),
+        _ => Vec::new(),
+    };
+    for word in body.split_whitespace() {
+        // `#` alone is a heading marker, `#1` usually a number
+        if let Some(tag) = word.strip_prefix('#').filter(|t| t.chars().next().is_some_and(char::is_alphabetic)) {
+            keywords.push(tag.trim_end_matches([',', '.', '，', '。']).to_string());
+        }
+    }
+    keywords.retain(|k| !k.is_empty());
+    keywords.iter_mut().for_each(|k| *k = k.trim_start_matches('#').to_string());
+    keywords.sort();
+    keywords.dedup();
+
+    let description: String = body
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(DESCRIPTION_CHARS)
+        .collect();
+
+    NoteMetadata { title, keywords, description }
+}
+
+/// Attribute name → binary plist value, in `ATTRIBUTES` order
+pub fn attributes(meta: &NoteMetadata) -> Vec<(&'static str, Vec<u8>)> {
+    vec![
+        (ATTRIBUTES[0], bplist(&Plist::String(&meta.title))),
+        (ATTRIBUTES[1], bplist(&Plist::Array(meta.keywords.iter().map(String::as_str).collect()))),
+        (ATTRIBUTES[2], bplist(&Plist::String(&meta.description))),
+    ]
+}
+
+/// Notes added or changed since they were last given metadata, with the
+/// mtimes to record once they have been
+pub fn stale_notes(vault: &Path) -> (Vec<PathBuf>, BTreeMap<String, u64>) {
+    let indexed = indexed(vault);
+    let mut stale = Vec::new();
+    let mut current = BTreeMap::new();
+    for entry in WalkDir::new(vault)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|e| !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|x| x == "md"))
+    {
+        let rel = vault_rel(vault, entry.path());
+        let mtime = entry.metadata().ok().and_then(|m| m.modified().ok()).and_then(|t| t.duration_since(UNIX_EPOCH).ok()).map_or(0, |d| d.as_secs());
+        if indexed.get(&rel) != Some(&mtime) {
+            stale.push(entry.path().to_path_buf());
+        }
+        current.insert(rel, mtime);
+    }
+    (stale, current)
+}
+
+/// Vault-relative paths of notes that carry metadata
+pub fn indexed(vault: &Path) -> BTreeMap<String, u64> {
+    fs::read_to_string(vault.join(STATE_FILE)).ok().and_then(|raw| serde_json::from_str(&raw).ok()).unwrap_or_default()
+}
+
+pub fn save_indexed(vault: &Path, indexed: &BTreeMap<String, u64>) -> Result<(), String> {
+    let json = serde_json::to_string(indexed).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    write(&vault.join(STATE_FILE), &json)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn write(path: &Path, text: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    durable::write(path, text).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+enum Plist<'a> {
+    String(&'a str),
+    Array(Vec<&'a str>),
+}
+
+/// Binary property list ("bplist00") of one string or an array of strings
+fn bplist(value: &Plist) -> Vec<u8> {
+    let strings: Vec<&str> = match value {
+        Plist::String(s) => vec![*s],
+        Plist::Array(items) => items.clone(),
+    };
+    let is_array = matches!(value, Plist::Array(_));
+    let count = strings.len() + usize::from(is_array);
+    let ref_size: usize = if count < 256 { 1 } else { 2 };
+
+    let mut out = b"bplist00".to_vec();
+    let mut offsets = Vec::with_capacity(count);
+    if is_array {
+        offsets.push(out.len());
+        marker(&mut out, 0xA0, strings.len());
+        for i in 1..=strings.len() {
+            out.extend_from_slice(&(i as u64).to_be_bytes()[8 - ref_size..]);
+        }
+    }
+    for s in &strings {
+        offsets.push(out.len());
+        if s.is_ascii() {
+            marker(&mut out, 0x50, s.len());
+            out.extend_from_slice(s.as_bytes());
+        } else {
+            let units: Vec<u16> = s.encode_utf16().collect();
+            marker(&mut out, 0x60, units.len());
+            units.iter().for_each(|u| out.extend_from_slice(&u.to_be_bytes()));
+        }
+    }
+
+    let table_offset = out.len();
+    let offset_size: usize = match table_offset {
+        0..=0xFF => 1,
+        0x100..=0xFFFF => 2,
+        _ => 4,
+    };
+    for offset in offsets {
+        out.extend_from_slice(&(offset as u64).to_be_bytes()[8 - offset_size..]);
+    }
+    out.extend_from_slice(&[0; 6]);
+    out.extend_from_slice(&[offset_size as u8, ref_size as u8]);
+    out.extend_from_slice(&(count as u64).to_be_bytes());
+    out.extend_from_slice(&0u64.to_be_bytes());
+    out.extend_from_slice(&(table_offset as u64).to_be_bytes());
+    out
+}
+
+/// Type marker with its length, spilling lengths of 15+ into an int object
+fn marker(out: &mut Vec<u8>, kind: u8, len: usize) {
+    if len < 15 {
+        out.push(kind | len as u8);
+        return;
+    }
+    out.push(kind | 0x0F);
+    match len {
+        0..=0xFF => out.extend_from_slice(&[0x10, len as u8]),
+        0x100..=0xFFFF => {
+            out.push(0x11);
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        _ => {
+            out.push(0x12);
+            out.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metadata_and_bplist() {
+        let raw = "---\ntags: [work, review]\n---\n# 季度复盘\n\nNotes on #planning and #2026 numbers.\n## Next\n";
+        let meta = metadata(raw, "2026-q3");
+        assert_eq!(meta.title, "季度复盘");
+        assert_eq!(meta.keywords, ["planning", "review", "work"]);
+        assert_eq!(meta.description, "Notes on #planning and #2026 numbers.");
+        assert_eq!(metadata("plain text", "idea").title, "idea");
+
+        assert_eq!(
+            bplist(&Plist::String("Hi")),
+            [b"bplist00".as_slice(), &[0x52, b'H', b'i', 0x08], &[0, 0, 0, 0, 0, 0, 1, 1], &1u64.to_be_bytes(), &0u64.to_be_bytes(), &11u64.to_be_bytes()].concat()
+        );
+        let array = bplist(&Plist::Array(vec!["a", "工作"]));
+        // array of two refs, an ASCII string, then a UTF-16 one
+        assert_eq!(&array[8..18], &[0xA2, 1, 2, 0x51, b'a', 0x62, 0x5D, 0xE5, 0x4F, 0x5C]);
+        assert_eq!(&array[array.len() - 26..array.len() - 24], &[1, 1]);
+        assert_eq!(array[array.len() - 17], 3);
+        let long = bplist(&Plist::String(&"x".repeat(20)));
+        assert_eq!(&long[8..11], &[0x5F, 0x10, 20]);
+    }
+}
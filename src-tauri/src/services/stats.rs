@@ -0,0 +1,91 @@
+//! Date ranges and small statistics helpers for the life-data trend commands.
+
+use chrono::{Duration, NaiveDate};
+
+/// `7d`, `4w`, `3m`, `1y` (ending `today`), `YYYY-MM-DD..YYYY-MM-DD`, or a
+/// single month `YYYY-MM`. Returns the inclusive (from, to).
+pub fn parse_range(range: &str, today: NaiveDate) -> Result<(NaiveDate, NaiveDate), String> {
+    let range = range.trim();
+    let invalid = || tr!("Invalid range: {}", range);
+    if let Some((from, to)) = range.split_once("..") {
+        let parse = |s: &str| NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").map_err(|_| invalid());
+        let (from, to) = (parse(from)?, parse(to)?);
+        return if from <= to { Ok((from, to)) } else { Err(invalid()) };
+    }
+    if let Ok(first) = NaiveDate::parse_from_str(&format!("{range}-01"), "%Y-%m-%d") {
+        let next = first.checked_add_months(chrono::Months::new(1)).ok_or_else(invalid)?;
+        return Ok((first, next - Duration::days(1)));
+    }
+    let split = range.len().checked_sub(1).ok_or_else(invalid)?;
+    let (n, unit) = range.split_at(split);
+    let n: i64 = n.parse().map_err(|_| invalid())?;
+    let days = match unit {
+        "d" => n,
+        "w" => n * 7,
+        "m" => n * 30,
+        "y" => n * 365,
+        _ => return Err(invalid()),
+    };
+    if days < 1 {
+        return Err(invalid());
+    }
+    Ok((today - Duration::days(days - 1), today))
+}
+
+pub fn mean(xs: &[f64]) -> Option<f64> {
+    (!xs.is_empty()).then(|| xs.iter().sum::<f64>() / xs.len() as f64)
+}
+
+/// Pearson correlation of paired samples; None below 3 pairs or with no variance
+pub fn pearson(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < 3 {
+        return None;
+    }
+    let n = pairs.len() as f64;
+    let (mx, my) = (pairs.iter().map(|p| p.0).sum::<f64>() / n, pairs.iter().map(|p| p.1).sum::<f64>() / n);
+    let (mut cov, mut vx, mut vy) = (0.0, 0.0, 0.0);
+    for (x, y) in pairs {
+        cov += (x - mx) * (y - my);
+        vx += (x - mx).powi(2);
+        vy += (y - my).powi(2);
+    }
+    if vx == 0.0 || vy == 0.0 {
+        return None;
+    }
+    Some(round(cov / (vx.sqrt() * vy.sqrt()), 2))
+}
+
+pub fn round(v: f64, places: i32) -> f64 {
+    let f = 10f64.powi(places);
+    (v * f).round() / f
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse_range() {
+        let today = ymd(2025, 3, 10);
+        assert_eq!(parse_range("7d", today), Ok((ymd(2025, 3, 4), today)));
+        assert_eq!(parse_range("2025-02", today), Ok((ymd(2025, 2, 1), ymd(2025, 2, 28))));
+        assert_eq!(parse_range("2025-01-01..2025-01-31", today), Ok((ymd(2025, 1, 1), ymd(2025, 1, 31))));
+        assert!(parse_range("0d", today).is_err());
+        assert!(parse_range("soon", today).is_err());
+    }
+
+    #[test]
+    fn test_pearson() {
+        assert_eq!(pearson(&[(1.0, 2.0), (2.0, 4.0), (3.0, 6.0)]), Some(1.0));
+        assert_eq!(pearson(&[(1.0, 3.0), (2.0, 2.0), (3.0, 1.0)]), Some(-1.0));
+        assert_eq!(pearson(&[(1.0, 1.0), (1.0, 2.0), (1.0, 3.0)]), None);
+    }
+}
@@ -0,0 +1,394 @@
+//! Tasks pushed to Todoist or TickTick, for capture and reminders on the
+//! phone, with their completion pulled back into the day files.
+//!
+//! Each service has a section in connectors.yaml (`todoist:` / `ticktick:`)
+//! with a `token`, an optional `project_id` and `fields`, which maps a
+//! LifeOS task field (`text`, `tags`, `contexts`, `due`, `energy`, `note`)
+//! to the service's field name. List fields sent to the same name are
+//! merged, `energy` becomes a priority and `note` points back at the day
+//! file. Pushed tasks are remembered in .lifeos/task-export.json, so each
+//! goes out once and can be checked later.
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use super::mood::energy_score;
+use super::tasks::{self, DayTask};
+use super::{connectors, durable, http};
+
+const STATE_FILE: &str = ".lifeos/task-export.json";
+const TODOIST_API: &str = "https://api.todoist.com/rest/v2";
+const TICKTICK_API: &str = "https://api.ticktick.com/open/v1";
+/// TickTick's `status` of a completed task
+const TICKTICK_COMPLETED: i64 = 2;
+
+/// State reads and writes don't interleave
+static LOCK: Mutex<()> = Mutex::new(());
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    Todoist,
+    TickTick,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExportedTask {
+    pub provider: Provider,
+    pub remote_id: String,
+    /// TickTick looks tasks up by project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_project: Option<String>,
+    /// Day file (YYYY-MM-DD)
+    pub date: String,
+    /// Text and 🆔 id when pushed, to find the line again after edits
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    /// RFC 3339
+    pub pushed: String,
+    /// Completed on either side; no longer checked
+    pub done: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PullReport {
+    /// Ticked off in their day files because they were completed remotely
+    pub completed: Vec<ExportedTask>,
+    /// `service: error`; those tasks are checked again next time
+    pub errors: Vec<String>,
+}
+
+struct Config {
+    token: String,
+    project_id: Option<String>,
+    /// (LifeOS field, service field)
+    fields: Vec<(String, String)>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Public API
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Send the tasks `refs` point at (`YYYY-MM-DD:line` or a 🆔 id) to
+/// `provider`. A task already there and not done yet is not sent again.
+pub fn push(vault: &Path, provider: Provider, refs: &[String]) -> Result<Vec<ExportedTask>, String> {
+    let config = Config::load(vault, provider)?;
+    let vault_path = vault.to_string_lossy();
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut state = load(vault);
+    let mut pushed = Vec::new();
+    for task_ref in refs {
+        let (date, task) = tasks::find_task(&vault_path, task_ref).ok_or_else(|| tr!("Task not found: {}", task_ref))?;
+        let existing = state.iter().find(|e| e.provider == provider && !e.done && e.date == date && e.text == task.text);
+        if let Some(existing) = existing {
+            pushed.push(existing.clone());
+            continue;
+        }
+        let body = payload(provider, &config.fields, &date, &task, config.project_id.as_deref());
+        let (remote_id, remote_project) = provider.create(&config.token, &body)?;
+        let exported = ExportedTask {
+            provider,
+            remote_id,
+            remote_project,
+            date,
+            text: task.text,
+            task_id: task.id,
+            pushed: Local::now().to_rfc3339(),
+            done: false,
+        };
+        // Saved one by one: a failure halfway must not lose what already went out
+        state.push(exported.clone());
+        save(vault, &state)?;
+        pushed.push(exported);
+    }
+    Ok(pushed)
+}
+
+/// Ask each service about the pushed tasks that aren't done and tick off
+/// the ones completed there. Tasks already ticked off locally are marked
+/// done without asking.
+pub fn pull(vault: &Path) -> Result<PullReport, String> {
+    let vault_path = vault.to_string_lossy();
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut state = load(vault);
+    let mut report = PullReport::default();
+    for provider in [Provider::Todoist, Provider::TickTick] {
+        let mut open = Vec::new();
+        for (i, entry) in state.iter_mut().enumerate().filter(|(_, e)| e.provider == provider && !e.done) {
+            match locate(&vault_path, entry) {
+                Some(task) if task.done => entry.done = true,
+                _ => open.push(i),
+            }
+        }
+        if open.is_empty() {
+            continue;
+        }
+        let entries: Vec<&ExportedTask> = open.iter().map(|&i| &state[i]).collect();
+        let completed = match Config::load(vault, provider).and_then(|config| provider.completed(&config.token, &entries)) {
+            Ok(completed) => completed,
+            Err(e) => {
+                report.errors.push(format!("{}: {e}", provider.name()));
+                continue;
+            }
+        };
+        for i in open {
+            let entry = &mut state[i];
+            if !completed.contains(&entry.remote_id) {
+                continue;
+            }
+            // Deleted locally: nothing to tick off, but nothing to track either
+            if let Some(task) = locate(&vault_path, entry) {
+                if tasks::check_task(&vault_path, &entry.date, task.line)? {
+                    report.completed.push(entry.clone());
+                }
+            }
+            entry.done = true;
+        }
+    }
+    save(vault, &state)?;
+    Ok(report)
+}
+
+/// Everything pushed so far, oldest first
+pub fn list(vault: &Path) -> Vec<ExportedTask> {
+    load(vault)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Services
+// ─────────────────────────────────────────────────────────────────────────────
+
+impl Provider {
+    fn name(self) -> &'static str {
+        match self {
+            Provider::Todoist => "todoist",
+            Provider::TickTick => "ticktick",
+        }
+    }
+
+    /// Used when connectors.yaml has no `fields`
+    fn default_fields(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Provider::Todoist => &[("text", "content"), ("tags", "labels"), ("contexts", "labels"), ("due", "due_date"), ("energy", "priority"), ("note", "description")],
+            Provider::TickTick => &[("text", "title"), ("tags", "tags"), ("contexts", "tags"), ("due", "dueDate"), ("energy", "priority"), ("note", "content")],
+        }
+    }
+
+    fn title_field(self) -> &'static str {
+        match self {
+            Provider::Todoist => "content",
+            Provider::TickTick => "title",
+        }
+    }
+
+    /// Energy score (1–5) as the service's priority: Todoist 1–4 with 4
+    /// the highest, TickTick 1, 3 or 5
+    fn priority(self, score: f64) -> i64 {
+        match self {
+            Provider::Todoist => ((score - 1.0) * 0.75).round() as i64 + 1,
+            Provider::TickTick if score <= 2.0 => 1,
+            Provider::TickTick if score < 4.0 => 3,
+            Provider::TickTick => 5,
+        }
+    }
+
+    /// (task id, project id)
+    fn create(self, token: &str, body: &Value) -> Result<(String, Option<String>), String> {
+        let url = match self {
+            Provider::Todoist => format!("{TODOIST_API}/tasks"),
+            Provider::TickTick => format!("{TICKTICK_API}/task"),
+        };
+        let request = http::agent()?.post(&url).set("Authorization", &format!("Bearer {token}"));
+        let created = http::send_json(request, body)?;
+        let id = id_of(&created["id"]).ok_or_else(|| tr!("Failed to parse: {}", created))?;
+        Ok((id, id_of(&created["projectId"])))
+    }
+
+    /// Remote ids among `entries` that are completed. Todoist only lists
+    /// active tasks, so one that is missing counts as completed.
+    fn completed(self, token: &str, entries: &[&ExportedTask]) -> Result<HashSet<String>, String> {
+        let agent = http::agent()?;
+        let auth = format!("Bearer {token}");
+        match self {
+            Provider::Todoist => {
+                let ids: Vec<&str> = entries.iter().map(|e| e.remote_id.as_str()).collect();
+                let request = agent.get(&format!("{TODOIST_API}/tasks")).query("ids", &ids.join(",")).set("Authorization", &auth);
+                let active: HashSet<String> = match http::call_json(request)? {
+                    Value::Array(items) => items.iter().filter_map(|t| id_of(&t["id"])).collect(),
+                    other => return Err(tr!("Failed to parse: {}", other)),
+                };
+                Ok(ids.into_iter().filter(|id| !active.contains(*id)).map(str::to_string).collect())
+            }
+            Provider::TickTick => {
+                let mut completed = HashSet::new();
+                for entry in entries {
+                    let project = entry.remote_project.as_deref().unwrap_or("inbox");
+                    let request = agent.get(&format!("{TICKTICK_API}/project/{project}/task/{}", entry.remote_id)).set("Authorization", &auth);
+                    match http::call_json(request) {
+                        Ok(task) if task["status"].as_i64() == Some(TICKTICK_COMPLETED) => {
+                            completed.insert(entry.remote_id.clone());
+                        }
+                        Ok(_) => {}
+                        Err(e) => println!("[WARN] ticktick task {}: {e}", entry.remote_id),
+                    }
+                }
+                Ok(completed)
+            }
+        }
+    }
+}
+
+impl Config {
+    fn load(vault: &Path, provider: Provider) -> Result<Config, String> {
+        let name = provider.name();
+        let token = connectors::value(vault, name, "token").ok_or_else(|| tr!("Task service is not configured: {}", format!("{name}.token")))?;
+        let section = connectors::section(vault, name).unwrap_or_default();
+        let fields = match section.get("fields").and_then(|f| f.as_mapping()) {
+            Some(map) => map
+                .iter()
+                .filter_map(|(k, v)| Some((k.as_str()?.to_string(), v.as_str()?.trim().to_string())))
+                .filter(|(_, remote)| !remote.is_empty())
+                .collect(),
+            None => provider.default_fields().iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        };
+        Ok(Config { token, project_id: connectors::value(vault, name, "project_id"), fields })
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// The request body for a new task, per the field mapping
+fn payload(provider: Provider, fields: &[(String, String)], date: &str, task: &DayTask, project: Option<&str>) -> Value {
+    let mut body = Map::new();
+    for (field, remote) in fields {
+        let value = match field.as_str() {
+            "text" => json!(task.text),
+            "tags" => json!(task.tags),
+            "contexts" => json!(task.contexts),
+            "due" => match (provider, &task.due) {
+                (_, None) => continue,
+                (Provider::Todoist, Some(due)) => json!(due),
+                (Provider::TickTick, Some(due)) => json!(format!("{due}T00:00:00+0000")),
+            },
+            "energy" => match task.energy.as_deref().and_then(energy_score) {
+                Some(score) => json!(provider.priority(score)),
+                None => continue,
+            },
+            "note" => json!(format!("LifeOS: daily/tasks/{date}.md")),
+            _ => continue,
+        };
+        match (body.get_mut(remote), value) {
+            (Some(Value::Array(existing)), Value::Array(more)) => existing.extend(more),
+            (_, value) => {
+                body.insert(remote.clone(), value);
+            }
+        }
+    }
+    body.entry(provider.title_field()).or_insert_with(|| json!(task.text));
+    if provider == Provider::TickTick && body.contains_key("dueDate") {
+        body.insert("isAllDay".into(), json!(true));
+    }
+    if let Some(project) = project {
+        let key = match provider {
+            Provider::Todoist => "project_id",
+            Provider::TickTick => "projectId",
+        };
+        body.insert(key.into(), json!(project));
+    }
+    Value::Object(body)
+}
+
+/// The pushed task in its day file: by 🆔 id, else by text
+fn locate(vault_path: &str, entry: &ExportedTask) -> Option<DayTask> {
+    let day = tasks::day_tasks(vault_path, &entry.date);
+    match &entry.task_id {
+        Some(id) => day.into_iter().find(|t| t.id.as_ref() == Some(id)),
+        None => day.into_iter().find(|t| t.text == entry.text),
+    }
+}
+
+/// Ids come back as strings or numbers depending on the service
+fn id_of(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn load(vault: &Path) -> Vec<ExportedTask> {
+    fs::read_to_string(vault.join(STATE_FILE)).ok().and_then(|raw| serde_json::from_str(&raw).ok()).unwrap_or_default()
+}
+
+fn save(vault: &Path, state: &[ExportedTask]) -> Result<(), String> {
+    let path = vault.join(STATE_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(state).map_err(|e| tr!("Failed to serialize: {}", e))?;
+    durable::write(path, json).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_mapping() {
+        let task = &tasks::parse_tasks("- [ ] 报税 #finance @computer ⚡high 📅2026-04-15\n")[0];
+        let defaults = |p: Provider| p.default_fields().iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<Vec<_>>();
+
+        let body = payload(Provider::Todoist, &defaults(Provider::Todoist), "2026-04-01", task, Some("42"));
+        assert_eq!(
+            body,
+            json!({ "content": "报税", "labels": ["finance", "computer"], "due_date": "2026-04-15", "priority": 4, "description": "LifeOS: daily/tasks/2026-04-01.md", "project_id": "42" })
+        );
+        let body = payload(Provider::TickTick, &defaults(Provider::TickTick), "2026-04-01", task, None);
+        assert_eq!((body["dueDate"].as_str(), body["isAllDay"].as_bool(), body["priority"].as_i64()), (Some("2026-04-15T00:00:00+0000"), Some(true), Some(5)));
+
+        // A mapping without `text` still gets a title
+        let body = payload(Provider::Todoist, &[("due".into(), "due_string".into())], "2026-04-01", task, None);
+        assert_eq!(body, json!({ "due_string": "2026-04-15", "content": "报税" }));
+        assert_eq!([1.0, 3.0, 5.0].map(|s| Provider::Todoist.priority(s)), [1, 3, 4]);
+    }
+
+    #[test]
+    fn test_pull_without_network() {
+        let dir = tempfile::tempdir().unwrap();
+        let v = dir.path();
+        fs::create_dir_all(v.join("daily/tasks")).unwrap();
+        fs::write(v.join("daily/tasks/2026-04-01.md"), "- [x] 报税 🆔tax\n").unwrap();
+        let entry = ExportedTask {
+            provider: Provider::Todoist,
+            remote_id: "1".into(),
+            remote_project: None,
+            date: "2026-04-01".into(),
+            text: "报税".into(),
+            task_id: Some("tax".into()),
+            pushed: String::new(),
+            done: false,
+        };
+        save(v, &[entry]).unwrap();
+        // Ticked off locally: marked done without asking Todoist
+        let report = pull(v).unwrap();
+        assert!(report.completed.is_empty() && report.errors.is_empty());
+        assert!(list(v)[0].done);
+        assert!(push(v, Provider::TickTick, &["tax".into()]).is_err());
+    }
+}
@@ -0,0 +1,355 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::durable;
+use super::templates;
+
+const TASK_HEADING: &str = "## 今日任务";
+
+/// A checkbox of a day file, parsed like `parseTasks` in the frontend, plus
+/// the GTD markers `@context`, `⚡low|medium|high` and `📅YYYY-MM-DD` and the
+/// dependency markers `🆔id` and `⛔id,id` (see `services::dependencies`)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DayTask {
+    /// 0-based line in the file
+    pub line: usize,
+    /// Without `#tags`, `@contexts` and the ⏰/⚡/📅/🆔/⛔ markers
+    pub text: String,
+    pub done: bool,
+    pub tags: Vec<String>,
+    pub time: Option<String>,
+    #[serde(default)]
+    pub contexts: Vec<String>,
+    /// Energy the task needs
+    #[serde(default)]
+    pub energy: Option<String>,
+    #[serde(default)]
+    pub due: Option<String>,
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Task ids or project names this waits on
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+/// Append an open task to daily/tasks/{date}.md, creating the day file the
+/// same way the Daily view does when it is missing. Returns the file path.
+pub fn add_task(vault_path: &str, date: &str, text: &str) -> Result<PathBuf, String> {
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| tr!("Invalid date (expected YYYY-MM-DD): {}", date))?;
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(tr!("Task text is empty"));
+    }
+
+    let root = PathBuf::from(vault_path);
+    let path = root.join("daily/tasks").join(format!("{date}.md"));
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(_) => new_day_file(&root, date),
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let updated = insert_task(&content, &format!("- [ ] {text}"));
+    durable::write(&path, updated).map_err(|e| tr!("write_file failed: {}", e))?;
+    Ok(path)
+}
+
+/// Set (or with `None` remove) one frontmatter key of daily/tasks/{date}.md,
+/// leaving the rest of the file as written. A missing day file is created
+/// only when there is something to set.
+pub fn set_day_field(vault_path: &str, date: &str, key: &str, value: Option<&serde_yaml::Value>) -> Result<(), String> {
+    let root = PathBuf::from(vault_path);
+    let path = root.join("daily/tasks").join(format!("{date}.md"));
+    let content = match (fs::read_to_string(&path), value) {
+        (Ok(content), _) => content,
+        (Err(_), None) => return Ok(()),
+        (Err(_), Some(_)) => new_day_file(&root, date),
+    };
+    let updated = super::notes::set_field(&content, key, value)?;
+    if updated == content {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    durable::write(&path, updated).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+/// Replace the `heading` section of daily/tasks/{date}.md (up to the next
+/// `## ` heading) with `body`, appending the section when it is missing;
+/// `None` removes it. A missing day file is created only when there is
+/// something to write.
+pub fn set_day_section(vault_path: &str, date: &str, heading: &str, body: Option<&str>) -> Result<(), String> {
+    let root = PathBuf::from(vault_path);
+    let path = root.join("daily/tasks").join(format!("{date}.md"));
+    let content = match (fs::read_to_string(&path), body) {
+        (Ok(content), _) => content,
+        (Err(_), None) => return Ok(()),
+        (Err(_), Some(_)) => new_day_file(&root, date),
+    };
+    let updated = replace_section(&content, heading, body);
+    if updated == content {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    durable::write(&path, updated).map_err(|e| tr!("write_file failed: {}", e))
+}
+
+/// Tick the open checkbox on 0-based `line` of daily/tasks/{date}.md.
+/// Returns false when that line is not an open task.
+pub fn check_task(vault_path: &str, date: &str, line: usize) -> Result<bool, String> {
+    let path = PathBuf::from(vault_path).join("daily/tasks").join(format!("{date}.md"));
+    let content = fs::read_to_string(&path).map_err(|e| tr!("Failed to read: {}", e))?;
+    let mut lines: Vec<&str> = content.split('\n').collect();
+    let Some(rest) = lines.get(line).and_then(|l| l.strip_prefix("- [ ]")) else { return Ok(false) };
+    let checked = format!("- [x]{rest}");
+    lines[line] = &checked;
+    durable::write(&path, lines.join("\n")).map_err(|e| tr!("write_file failed: {}", e))?;
+    Ok(true)
+}
+
+fn replace_section(content: &str, heading: &str, body: Option<&str>) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let (before, after) = match lines.iter().position(|l| l.trim() == heading) {
+        Some(start) => {
+            let end = lines[start + 1..].iter().position(|l| l.starts_with("## ")).map_or(lines.len(), |i| start + 1 + i);
+            (lines[..start].join("\n"), lines[end..].join("\n"))
+        }
+        None if body.is_none() => return content.to_string(),
+        None => (content.to_string(), String::new()),
+    };
+    let mut parts: Vec<String> = vec![before.trim_end().to_string()];
+    if let Some(body) = body {
+        parts.push(format!("{heading}\n\n{}", body.trim()));
+    }
+    parts.push(after.trim_end().to_string());
+    let mut out = parts.into_iter().filter(|p| !p.is_empty()).collect::<Vec<_>>().join("\n\n");
+    out.push('\n');
+    out
+}
+
+/// Mirrors `loadToday` in the frontend: prefer the diary template, else the
+/// built-in skeleton. Templates that need prompts answered get the skeleton.
+fn new_day_file(root: &Path, date: &str) -> String {
+    let from_template = fs::read_to_string(root.join("diary/templates/daily.md")).ok().and_then(|template| {
+        let day = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+        let ctx = templates::Context::new(day.and_time(chrono::Local::now().time())).with_user_vars(root);
+        templates::render(&template, &ctx).ok()
+    });
+    from_template.unwrap_or_else(|| {
+        format!("---\ndate: {date}\nenergy: high\nmood: 😊\n---\n\n{TASK_HEADING}\n\n- [ ] \n\n## 今日笔记\n\n")
+    })
+}
+
+/// Place `task` after the last checkbox of the task section, reusing the
+/// empty `- [ ]` placeholder if the file still has one. Files without a task
+/// section get the task appended at the end.
+fn insert_task(content: &str, task: &str) -> String {
+    let mut lines: Vec<&str> = content.lines().collect();
+
+    match lines.iter().position(|l| l.trim() == TASK_HEADING) {
+        Some(heading) => {
+            let start = heading + 1;
+            let end = lines[start..]
+                .iter()
+                .position(|l| l.starts_with("## "))
+                .map_or(lines.len(), |i| start + i);
+            let section = &lines[start..end];
+
+            if let Some(i) = section.iter().position(|l| l.trim() == "- [ ]") {
+                lines[start + i] = task;
+            } else if let Some(i) = section.iter().rposition(|l| is_checkbox(l)) {
+                lines.insert(start + i + 1, task);
+            } else {
+                lines.splice(start..start, ["", task]);
+            }
+        }
+        None => {
+            if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+                lines.push("");
+            }
+            lines.push(task);
+        }
+    }
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+/// Tasks of daily/tasks/{date}.md; empty when there is no day file
+pub fn day_tasks(vault_path: &str, date: &str) -> Vec<DayTask> {
+    let path = PathBuf::from(vault_path).join("daily/tasks").join(format!("{date}.md"));
+    fs::read_to_string(path).map(|content| parse_tasks(&content)).unwrap_or_default()
+}
+
+/// Unchecked tasks of every day file up to `through` (YYYY-MM-DD), keyed by
+/// the file's date, oldest first
+pub fn open_tasks(vault_path: &str, through: &str) -> Vec<(String, DayTask)> {
+    let Ok(entries) = fs::read_dir(PathBuf::from(vault_path).join("daily/tasks")) else { return Vec::new() };
+    let mut dates: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.file_name().to_string_lossy().strip_suffix(".md").map(str::to_string))
+        .filter(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").is_ok() && d.as_str() <= through)
+        .collect();
+    dates.sort();
+    dates
+        .into_iter()
+        .flat_map(|date| day_tasks(vault_path, &date).into_iter().filter(|t| !t.done).map(move |t| (date.clone(), t)))
+        .collect()
+}
+
+/// The task `task_ref` points at, with its day file's date:
+/// `YYYY-MM-DD:line` (0-based) is that line of the day file; anything else
+/// is a 🆔 id, looked up among open tasks
+pub fn find_task(vault_path: &str, task_ref: &str) -> Option<(String, DayTask)> {
+    let by_line = task_ref.rsplit_once(':').filter(|(date, _)| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok());
+    if let Some((date, line)) = by_line {
+        let line: usize = line.parse().ok()?;
+        return day_tasks(vault_path, date).into_iter().find(|t| t.line == line).map(|t| (date.to_string(), t));
+    }
+    let id = task_ref.trim_start_matches('🆔');
+    open_tasks(vault_path, "9999-12-31").into_iter().find(|(_, t)| t.id.as_deref() == Some(id))
+}
+
+/// Top-level checkboxes with text, as the Daily view shows them
+pub fn parse_tasks(content: &str) -> Vec<DayTask> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| is_checkbox(l) && !l.starts_with([' ', '\t']) && !l[5..].trim().is_empty())
+        .map(|(line, l)| {
+            let words: Vec<&str> = l[5..].split_whitespace().collect();
+            let tags = words.iter().filter_map(|w| w.strip_prefix('#')).filter(|t| !t.is_empty()).map(str::to_string).collect();
+            let time = words.iter().find_map(|w| w.strip_prefix('⏰')).filter(|t| t.len() == 5 && t.as_bytes()[2] == b':').map(str::to_string);
+            let contexts = words.iter().filter_map(|w| w.strip_prefix('@')).filter(|c| !c.is_empty()).map(str::to_string).collect();
+            let energy = words.iter().find_map(|w| w.strip_prefix('⚡')).filter(|e| !e.is_empty()).map(str::to_string);
+            let due = words.iter().find_map(|w| w.strip_prefix('📅')).filter(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").is_ok()).map(str::to_string);
+            let id = words.iter().find_map(|w| w.strip_prefix('🆔')).filter(|i| !i.is_empty()).map(str::to_string);
+            let depends_on = words
+                .iter()
+                .filter_map(|w| w.strip_prefix('⛔'))
+                .flat_map(|refs| refs.split(','))
+                .filter(|r| !r.is_empty())
+                .map(str::to_string)
+                .collect();
+            let text = words.iter().filter(|w| !w.starts_with(['#', '⏰', '@', '⚡', '📅', '🆔', '⛔'])).copied().collect::<Vec<_>>().join(" ");
+            DayTask { line, text, done: !l.starts_with("- [ ]"), tags, time, contexts, energy, due, id, depends_on }
+        })
+        .collect()
+}
+
+/// (done, total) checkboxes in a day file, ignoring the template's empty `- [ ]`
+pub fn count_tasks(content: &str) -> (usize, usize) {
+    content
+        .lines()
+        .map(str::trim_start)
+        .filter(|l| is_checkbox(l) && !l[5..].trim().is_empty())
+        .fold((0, 0), |(done, total), l| (done + usize::from(!l.starts_with("- [ ]")), total + 1))
+}
+
+fn is_checkbox(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("- [ ]") || line.starts_with("- [x]") || line.starts_with("- [X]")
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_task_replaces_placeholder() {
+        let day = "## 今日任务\n\n- [ ] \n\n## 今日笔记\n";
+        let out = insert_task(day, "- [ ] Call mom");
+        assert_eq!(out, "## 今日任务\n\n- [ ] Call mom\n\n## 今日笔记\n");
+    }
+
+    #[test]
+    fn test_insert_task_after_last_checkbox() {
+        let day = "## 今日任务\n\n- [x] Run\n- [ ] Read\n\n## 今日笔记\n\nnotes\n";
+        let out = insert_task(day, "- [ ] Write");
+        assert!(out.contains("- [ ] Read\n- [ ] Write\n\n## 今日笔记"));
+    }
+
+    #[test]
+    fn test_insert_task_without_section() {
+        let out = insert_task("---\ndate: 2025-01-01\n---\n\nfree text", "- [ ] Plan");
+        assert!(out.ends_with("free text\n\n- [ ] Plan\n"));
+    }
+
+    #[test]
+    fn test_replace_section() {
+        let day = "## 今日任务\n\n- [x] Run\n\n## 提交记录\n\nold\n\n## 今日笔记\n\nnotes\n";
+        assert_eq!(
+            replace_section(day, "## 提交记录", Some("- new\n")),
+            "## 今日任务\n\n- [x] Run\n\n## 提交记录\n\n- new\n\n## 今日笔记\n\nnotes\n"
+        );
+        assert_eq!(replace_section(day, "## 提交记录", None), "## 今日任务\n\n- [x] Run\n\n## 今日笔记\n\nnotes\n");
+        assert_eq!(replace_section("free text\n", "## 提交记录", Some("- a")), "free text\n\n## 提交记录\n\n- a\n");
+        assert_eq!(replace_section("free text\n", "## 提交记录", None), "free text\n");
+    }
+
+    #[test]
+    fn test_count_tasks_skips_placeholder() {
+        let content = "## 今日任务\n\n- [ ] \n- [x] Write\n- [X] Run\n  - [ ] Sub\n";
+        assert_eq!(count_tasks(content), (2, 3));
+    }
+
+    #[test]
+    fn test_parse_tasks() {
+        let content = "## 今日任务\n\n- [ ] \n- [x] 晨跑 ⏰07:30 #health\n  - [ ] Sub\n- [ ] 读书 #growth\n";
+        let tasks = parse_tasks(content);
+        assert_eq!(tasks.len(), 2);
+        assert_eq!((tasks[0].line, tasks[0].text.as_str(), tasks[0].done), (3, "晨跑", true));
+        assert_eq!((tasks[0].time.as_deref(), tasks[0].tags.clone()), (Some("07:30"), vec!["health".to_string()]));
+        assert_eq!((tasks[1].text.as_str(), tasks[1].time.clone()), ("读书", None));
+
+        let task = &parse_tasks("- [ ] 打电话给房东 @phone @home ⚡low 📅2025-03-05 📅soon\n")[0];
+        assert_eq!((task.text.as_str(), task.contexts.clone()), ("打电话给房东", vec!["phone".to_string(), "home".to_string()]));
+        assert_eq!((task.energy.as_deref(), task.due.as_deref()), (Some("low"), Some("2025-03-05")));
+
+        let task = &parse_tasks("- [ ] 发布 🆔ship ⛔review,[[官网改版]]\n")[0];
+        assert_eq!((task.text.as_str(), task.id.as_deref()), ("发布", Some("ship")));
+        assert_eq!(task.depends_on, vec!["review", "[[官网改版]]"]);
+    }
+
+    #[test]
+    fn test_add_task_creates_day_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().to_string_lossy().to_string();
+        let path = add_task(&vault, "2025-03-01", "Buy milk #life").unwrap();
+        let content = fs::read_to_string(path).unwrap();
+        assert!(content.contains("date: 2025-03-01"));
+        assert!(content.contains("- [ ] Buy milk #life"));
+        assert!(add_task(&vault, "03/01/2025", "x").is_err());
+    }
+
+    #[test]
+    fn test_find_and_check_task() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().to_string_lossy().to_string();
+        fs::create_dir_all(dir.path().join("daily/tasks")).unwrap();
+        fs::write(dir.path().join("daily/tasks/2025-03-01.md"), "## 今日任务\n\n- [ ] 写周报\n- [ ] 发布 🆔ship\n").unwrap();
+
+        let (date, task) = find_task(&vault, "ship").unwrap();
+        assert_eq!((date.as_str(), task.line), ("2025-03-01", 3));
+        assert_eq!(find_task(&vault, "2025-03-01:2").unwrap().1.text, "写周报");
+        assert!(find_task(&vault, "2025-03-01:9").is_none());
+
+        assert!(check_task(&vault, "2025-03-01", 2).unwrap());
+        assert!(!check_task(&vault, "2025-03-01", 2).unwrap());
+        assert!(!check_task(&vault, "2025-03-01", 0).unwrap());
+        let content = fs::read_to_string(dir.path().join("daily/tasks/2025-03-01.md")).unwrap();
+        assert_eq!(content, "## 今日任务\n\n- [x] 写周报\n- [ ] 发布 🆔ship\n");
+    }
+}
@@ -0,0 +1,214 @@
+//! `{{...}}` substitution for note templates.
+//!
+//! - `{{name}}` — a variable: built-ins (`date`, `time`, `datetime`, `weekday`,
+//!   `title`, `content`) plus the user's own from templates/variables.yaml
+//! - `{{date+7d}}`, `{{date-1w:%m月%d日}}` — date math in d/w/m/y, with an
+//!   optional chrono format
+//! - `{{prompt:项目名称}}`, `{{prompt:地点|线上}}` — asked by the caller, who
+//!   passes the answers back keyed by the label
+//!
+//! Unknown names are left untouched, like the automation renderer.
+
+use chrono::{Datelike, Days, Months, NaiveDate, NaiveDateTime};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// User templates, one Markdown file each
+pub const TEMPLATES_DIR: &str = "templates";
+/// `name: value` pairs available to every template
+pub const VARIABLES_FILE: &str = "templates/variables.yaml";
+
+const PROMPT_PREFIX: &str = "prompt:";
+const WEEKDAYS: [&str; 7] = ["周一", "周二", "周三", "周四", "周五", "周六", "周日"];
+
+pub struct Context {
+    /// Base for `date`, `time` and date math
+    pub now: NaiveDateTime,
+    pub vars: HashMap<String, String>,
+    pub answers: HashMap<String, String>,
+}
+
+impl Context {
+    pub fn new(now: NaiveDateTime) -> Self {
+        Context { now, vars: HashMap::new(), answers: HashMap::new() }
+    }
+
+    /// Adds the user variables from templates/variables.yaml
+    pub fn with_user_vars(mut self, vault: &Path) -> Self {
+        self.vars.extend(user_vars(vault));
+        self
+    }
+}
+
+/// Render `template`; fails when a prompt has neither an answer nor a default
+pub fn render(template: &str, ctx: &Context) -> Result<String, String> {
+    let mut out = String::with_capacity(template.len());
+    let mut missing = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else { break };
+        out.push_str(&rest[..start]);
+        let expr = rest[start + 2..start + 2 + len].trim();
+        match eval(expr, ctx) {
+            Eval::Value(v) => out.push_str(&v),
+            Eval::Unknown => out.push_str(&rest[start..start + len + 4]),
+            Eval::Unanswered(label) => missing.push(label),
+        }
+        rest = &rest[start + len + 4..];
+    }
+    out.push_str(rest);
+    if missing.is_empty() {
+        Ok(out)
+    } else {
+        Err(tr!("Missing answers: {}", missing.join(", ")))
+    }
+}
+
+/// Prompt labels in order of first appearance, without duplicates
+pub fn prompts(template: &str) -> Vec<String> {
+    let mut labels: Vec<String> = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else { break };
+        if let Some(prompt) = rest[start + 2..start + 2 + len].trim().strip_prefix(PROMPT_PREFIX) {
+            let label = split_default(prompt).0;
+            if !labels.iter().any(|l| l == label) {
+                labels.push(label.to_string());
+            }
+        }
+        rest = &rest[start + len + 4..];
+    }
+    labels
+}
+
+/// Variables from templates/variables.yaml; non-string values are stringified
+pub fn user_vars(vault: &Path) -> HashMap<String, String> {
+    let Ok(raw) = fs::read_to_string(vault.join(VARIABLES_FILE)) else {
+        return HashMap::new();
+    };
+    let map: HashMap<String, serde_yaml::Value> = serde_yaml::from_str(&raw).unwrap_or_default();
+    map.into_iter()
+        .filter_map(|(k, v)| {
+            let text = match v {
+                serde_yaml::Value::String(s) => s,
+                serde_yaml::Value::Number(n) => n.to_string(),
+                serde_yaml::Value::Bool(b) => b.to_string(),
+                _ => return None,
+            };
+            Some((k, text))
+        })
+        .collect()
+}
+
+enum Eval {
+    Value(String),
+    Unknown,
+    Unanswered(String),
+}
+
+fn eval(expr: &str, ctx: &Context) -> Eval {
+    if let Some(prompt) = expr.strip_prefix(PROMPT_PREFIX) {
+        let (label, default) = split_default(prompt);
+        return match (ctx.answers.get(label), default) {
+            (Some(answer), _) => Eval::Value(answer.clone()),
+            (None, Some(default)) => Eval::Value(default.to_string()),
+            (None, None) => Eval::Unanswered(label.to_string()),
+        };
+    }
+    // Explicit variables win over built-ins, so callers can pin `date`
+    if let Some(v) = ctx.vars.get(expr) {
+        return Eval::Value(v.clone());
+    }
+    match expr {
+        "time" => return Eval::Value(ctx.now.format("%H:%M").to_string()),
+        "datetime" => return Eval::Value(ctx.now.format("%Y-%m-%d %H:%M").to_string()),
+        "weekday" => return Eval::Value(WEEKDAYS[ctx.now.weekday().num_days_from_monday() as usize].to_string()),
+        "content" | "title" => return Eval::Value(String::new()),
+        _ => {}
+    }
+    match date_expr(expr, ctx.now.date()) {
+        Some(v) => Eval::Value(v),
+        None => Eval::Unknown,
+    }
+}
+
+/// `label|default` → (label, Some(default))
+fn split_default(prompt: &str) -> (&str, Option<&str>) {
+    match prompt.split_once('|') {
+        Some((label, default)) => (label.trim(), Some(default.trim())),
+        None => (prompt.trim(), None),
+    }
+}
+
+/// `date`, `date+7d`, `date-2w:%Y/%m/%d`; None for anything else
+fn date_expr(expr: &str, base: NaiveDate) -> Option<String> {
+    let (expr, fmt) = match expr.split_once(':') {
+        Some((e, f)) => (e.trim(), f.trim()),
+        None => (expr, "%Y-%m-%d"),
+    };
+    let offset = expr.strip_prefix("date")?.trim();
+    let date = if offset.is_empty() { base } else { shift(base, offset)? };
+    Some(date.format(fmt).to_string())
+}
+
+fn shift(base: NaiveDate, offset: &str) -> Option<NaiveDate> {
+    let (forward, rest) = match (offset.strip_prefix('+'), offset.strip_prefix('-')) {
+        (Some(rest), _) => (true, rest),
+        (_, Some(rest)) => (false, rest),
+        _ => return None,
+    };
+    let unit = rest.chars().last()?;
+    let n: u32 = rest[..rest.len() - unit.len_utf8()].trim().parse().ok()?;
+    let months = match unit {
+        'd' | 'w' => {
+            let days = Days::new(if unit == 'w' { n as u64 * 7 } else { n as u64 });
+            return if forward { base.checked_add_days(days) } else { base.checked_sub_days(days) };
+        }
+        'm' => Months::new(n),
+        'y' => Months::new(n * 12),
+        _ => return None,
+    };
+    if forward {
+        base.checked_add_months(months)
+    } else {
+        base.checked_sub_months(months)
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> Context {
+        let now = NaiveDate::from_ymd_opt(2025, 1, 31).unwrap().and_hms_opt(9, 5, 0).unwrap();
+        Context::new(now)
+    }
+
+    #[test]
+    fn test_dates_and_vars() {
+        let mut ctx = ctx();
+        ctx.vars.insert("author".into(), "阿伟".into());
+        let out = render("{{date}} {{ date+7d }} {{date-1w}} {{date+1m}} {{date+1y:%Y年}} {{weekday}} {{time}}", &ctx).unwrap();
+        assert_eq!(out, "2025-01-31 2025-02-07 2025-01-24 2025-02-28 2026年 周五 09:05");
+        assert_eq!(render("by {{author}} {{unknown}} {{date+x}} {{open", &ctx).unwrap(), "by 阿伟 {{unknown}} {{date+x}} {{open");
+
+        ctx.vars.insert("date".into(), "2024-12-01".into());
+        assert_eq!(render("{{date}}", &ctx).unwrap(), "2024-12-01");
+    }
+
+    #[test]
+    fn test_prompts() {
+        let template = "# {{prompt:项目名称}}\n地点: {{prompt:地点|线上}}\n{{prompt:项目名称}}";
+        assert_eq!(prompts(template), vec!["项目名称", "地点"]);
+
+        let mut ctx = ctx();
+        assert!(render(template, &ctx).is_err());
+        ctx.answers.insert("项目名称".into(), "LifeOS".into());
+        assert_eq!(render(template, &ctx).unwrap(), "# LifeOS\n地点: 线上\nLifeOS");
+    }
+}
@@ -0,0 +1,232 @@
+//! File moves that work across filesystems.
+//!
+//! `fs::rename` can't leave its filesystem, so moving attachments to an
+//! external drive falls back to copying. Each file is hashed while it is
+//! written, then read back and hashed again before the source is removed.
+//! Copies land under a temporary name first: a failed or interrupted move
+//! leaves the source as it was and nothing half-written at the destination.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+const CHUNK: usize = 1 << 20;
+
+/// What to do when the destination already exists
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Collision {
+    /// Replace it, as `fs::rename` does
+    #[default]
+    Overwrite,
+    /// Move to "name (1).ext" instead
+    Rename,
+    /// Leave both alone
+    Skip,
+}
+
+/// Move `src` (file or folder) to `dest`, creating its parent folders.
+/// Returns where it ended up, or None when skipped. `progress` gets
+/// (bytes copied, total bytes) while a cross-filesystem copy runs.
+pub fn move_path(src: &Path, dest: &Path, on_collision: Collision, progress: &mut dyn FnMut(u64, u64)) -> Result<Option<PathBuf>, String> {
+    if fs::symlink_metadata(src).is_err() {
+        return Err(tr!("File not found: {}", src.display()));
+    }
+    let mut dest = dest.to_path_buf();
+    let replacing = dest.exists();
+    if replacing {
+        match on_collision {
+            Collision::Skip => return Ok(None),
+            Collision::Rename => dest = free_name(&dest),
+            Collision::Overwrite => {}
+        }
+    }
+    let replacing = replacing && on_collision == Collision::Overwrite;
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+
+    // A folder only replaces another once that's gone, so replacements are
+    // staged next to the destination
+    let staging = staging_path(&dest);
+    let _ = remove_all(&staging);
+    let copied = match fs::rename(src, if replacing { &staging } else { &dest }) {
+        Ok(()) => false,
+        Err(e) if e.kind() == ErrorKind::CrossesDevices => {
+            if let Err(e) = copy_verified(src, &staging, progress) {
+                let _ = remove_all(&staging);
+                return Err(e);
+            }
+            true
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+    if replacing || copied {
+        if replacing {
+            remove_all(&dest).map_err(|e| e.to_string())?;
+        }
+        fs::rename(&staging, &dest).map_err(|e| e.to_string())?;
+    }
+    if copied {
+        remove_all(src).map_err(|e| e.to_string())?;
+    }
+    Ok(Some(dest))
+}
+
+/// Copy `src` (file or folder) to a `dest` that doesn't exist yet, with the
+/// same verification and staging as a cross-filesystem move
+pub fn copy_path(src: &Path, dest: &Path) -> Result<(), String> {
+    if fs::symlink_metadata(src).is_err() {
+        return Err(tr!("File not found: {}", src.display()));
+    }
+    if dest.exists() {
+        return Err(tr!("Path already exists: {}", dest.display()));
+    }
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| tr!("Failed to create directory: {}", e))?;
+    }
+    let staging = staging_path(dest);
+    let _ = remove_all(&staging);
+    if let Err(e) = copy_verified(src, &staging, &mut |_, _| {}) {
+        let _ = remove_all(&staging);
+        return Err(e);
+    }
+    fs::rename(&staging, dest).map_err(|e| e.to_string())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn copy_verified(src: &Path, dest: &Path, progress: &mut dyn FnMut(u64, u64)) -> Result<(), String> {
+    let entries: Vec<walkdir::DirEntry> = WalkDir::new(src).into_iter().collect::<Result<_, _>>().map_err(|e| e.to_string())?;
+    let total: u64 = entries.iter().filter(|e| !e.file_type().is_dir()).filter_map(|e| e.metadata().ok()).map(|m| m.len()).sum();
+    let mut done = 0u64;
+    for entry in &entries {
+        let rel = entry.path().strip_prefix(src).unwrap_or(Path::new(""));
+        let to = if rel.as_os_str().is_empty() { dest.to_path_buf() } else { dest.join(rel) };
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&to).map_err(|e| tr!("Failed to create directory: {}", e))?;
+            continue;
+        }
+        let written = copy_file(entry.path(), &to, &mut |n| {
+            done += n;
+            progress(done, total);
+        })?;
+        if hash_file(&to)? != written {
+            return Err(tr!("Checksum mismatch after copying: {}", entry.path().display()));
+        }
+    }
+    Ok(())
+}
+
+/// Copy one file, keeping its permissions and mtime; returns the SHA-256 of
+/// what was written
+fn copy_file(from: &Path, to: &Path, on_chunk: &mut dyn FnMut(u64)) -> Result<Vec<u8>, String> {
+    let mut input = File::open(from).map_err(|e| tr!("Failed to read: {}", e))?;
+    let mut output = File::create(to).map_err(|e| tr!("write_file failed: {}", e))?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0; CHUNK];
+    loop {
+        let n = input.read(&mut buf).map_err(|e| tr!("Failed to read: {}", e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        output.write_all(&buf[..n]).map_err(|e| tr!("write_file failed: {}", e))?;
+        on_chunk(n as u64);
+    }
+    let meta = input.metadata().map_err(|e| tr!("Failed to read: {}", e))?;
+    if let Ok(modified) = meta.modified() {
+        let _ = output.set_modified(modified);
+    }
+    let _ = output.set_permissions(meta.permissions());
+    // On disk, not just in the page cache, before the source goes
+    output.sync_all().map_err(|e| tr!("write_file failed: {}", e))?;
+    Ok(hasher.finalize().to_vec())
+}
+
+fn hash_file(path: &Path) -> Result<Vec<u8>, String> {
+    let mut file = File::open(path).map_err(|e| tr!("Failed to read: {}", e))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).map_err(|e| tr!("Failed to read: {}", e))?;
+    Ok(hasher.finalize().to_vec())
+}
+
+/// "name (1).ext", "name (2).ext", … whichever is free first
+pub fn free_name(path: &Path) -> PathBuf {
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let ext = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    (1..)
+        .map(|n| path.with_file_name(format!("{stem} ({n}){ext}")))
+        .find(|p| !p.exists())
+        .expect("some suffix is free")
+}
+
+/// Hidden sibling of `dest`, on the same filesystem so it can be renamed into place
+fn staging_path(dest: &Path) -> PathBuf {
+    let name = dest.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    dest.with_file_name(format!(".{name}.lifeos-partial"))
+}
+
+fn remove_all(path: &Path) -> std::io::Result<()> {
+    if fs::symlink_metadata(path)?.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, rel: &str, text: &str) {
+        let path = dir.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    #[test]
+    fn test_collisions() {
+        let dir = tempfile::tempdir().unwrap();
+        let d = dir.path();
+        let mut ignore = |_: u64, _: u64| {};
+        write(d, "a/photo.jpg", "new");
+        write(d, "b/photo.jpg", "old");
+
+        assert_eq!(move_path(&d.join("a/photo.jpg"), &d.join("b/photo.jpg"), Collision::Skip, &mut ignore).unwrap(), None);
+        let renamed = move_path(&d.join("a/photo.jpg"), &d.join("b/photo.jpg"), Collision::Rename, &mut ignore).unwrap();
+        assert_eq!(renamed, Some(d.join("b/photo (1).jpg")));
+        assert_eq!(fs::read_to_string(d.join("b/photo.jpg")).unwrap(), "old");
+
+        // A folder replaces a non-empty one
+        write(d, "x/assets/1.png", "one");
+        write(d, "y/assets/2.png", "two");
+        move_path(&d.join("x/assets"), &d.join("y/assets"), Collision::Overwrite, &mut ignore).unwrap();
+        assert!(d.join("y/assets/1.png").exists() && !d.join("y/assets/2.png").exists() && !d.join("x/assets").exists());
+        assert!(move_path(&d.join("missing"), &d.join("z"), Collision::Overwrite, &mut ignore).is_err());
+    }
+
+    #[test]
+    fn test_copy_verified() {
+        let dir = tempfile::tempdir().unwrap();
+        let d = dir.path();
+        write(d, "src/a.md", "alpha");
+        write(d, "src/sub/b.bin", &"x".repeat(CHUNK + 10));
+        let mut seen = Vec::new();
+        copy_verified(&d.join("src"), &d.join("dest"), &mut |done, total| seen.push((done, total))).unwrap();
+        assert_eq!(fs::read_to_string(d.join("dest/a.md")).unwrap(), "alpha");
+        assert_eq!(fs::metadata(d.join("dest/sub/b.bin")).unwrap().len(), CHUNK as u64 + 10);
+        let total = CHUNK as u64 + 15;
+        assert_eq!(seen.last(), Some(&(total, total)));
+        assert!(seen.iter().all(|(_, t)| *t == total));
+    }
+}
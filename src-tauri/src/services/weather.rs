@@ -0,0 +1,172 @@
+//! Daily weather from Open-Meteo (no API key) for the location set under
+//! `weather:` in connectors.yaml, cached per date in .lifeos/weather-cache.json.
+
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use super::{connectors, durable, http};
+use crate::i18n;
+
+const CACHE_FILE: &str = ".lifeos/weather-cache.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Weather {
+    pub date: String,
+    pub location: String,
+    /// WMO weather code
+    pub code: u32,
+    pub emoji: String,
+    /// In the active locale
+    pub summary: String,
+    pub temp_min: Option<f64>,
+    pub temp_max: Option<f64>,
+    pub precipitation_mm: Option<f64>,
+}
+
+impl Weather {
+    /// Frontmatter value, e.g. `☀️ 晴 12–20°C`
+    pub fn text(&self) -> String {
+        match (self.temp_min, self.temp_max) {
+            (Some(lo), Some(hi)) => format!("{} {} {}–{}°C", self.emoji, self.summary, lo.round(), hi.round()),
+            _ => format!("{} {}", self.emoji, self.summary),
+        }
+    }
+}
+
+/// Weather for `date` at the configured location
+pub fn for_date(vault: &Path, date: NaiveDate) -> Result<Weather, String> {
+    let key = date.format("%Y-%m-%d").to_string();
+    let mut cache = read_cache(vault);
+    if let Some(hit) = cache.get(&key) {
+        return Ok(hit.clone());
+    }
+    let (lat, lon, location) = location(vault)?;
+    let weather = fetch(lat, lon, &location, &key)?;
+    // Forecasts for future days still change; only settled days are kept
+    if date <= Local::now().date_naive() {
+        cache.insert(key, weather.clone());
+        write_cache(vault, &cache);
+    }
+    Ok(weather)
+}
+
+/// (latitude, longitude, display name) from connectors.yaml, geocoding
+/// `city` when no coordinates are set
+fn location(vault: &Path) -> Result<(f64, f64, String), String> {
+    let section = connectors::section(vault, "weather").unwrap_or_default();
+    if section.get("enabled").and_then(|v| v.as_bool()) == Some(false) {
+        return Err(tr!("Weather is disabled in connectors.yaml"));
+    }
+    let number = |key: &str| match section.get(key) {
+        Some(serde_yaml::Value::Number(n)) => n.as_f64(),
+        Some(serde_yaml::Value::String(s)) => s.trim().parse().ok(),
+        _ => None,
+    };
+    let city = connectors::value(vault, "weather", "city");
+    if let (Some(lat), Some(lon)) = (number("latitude"), number("longitude")) {
+        return Ok((lat, lon, city.unwrap_or_else(|| format!("{lat:.2},{lon:.2}"))));
+    }
+    let city = city.ok_or_else(|| tr!("Set weather.city or latitude/longitude in connectors.yaml"))?;
+    geocode(&city)
+}
+
+fn geocode(city: &str) -> Result<(f64, f64, String), String> {
+    let url = format!(
+        "https://geocoding-api.open-meteo.com/v1/search?count=1&language={}&name={}",
+        i18n::locale().as_str(),
+        http::encode(city)
+    );
+    let json = http::get_json(&url)?;
+    let hit = json.get("results").and_then(|r| r.get(0)).ok_or_else(|| tr!("Unknown city: {}", city))?;
+    let coord = |key: &str| hit.get(key).and_then(|v| v.as_f64());
+    let (Some(lat), Some(lon)) = (coord("latitude"), coord("longitude")) else {
+        return Err(tr!("Unknown city: {}", city));
+    };
+    let name = hit.get("name").and_then(|n| n.as_str()).unwrap_or(city).to_string();
+    Ok((lat, lon, name))
+}
+
+fn fetch(lat: f64, lon: f64, location: &str, date: &str) -> Result<Weather, String> {
+    let url = format!(
+        "https://api.open-meteo.com/v1/forecast?latitude={lat}&longitude={lon}&timezone=auto\
+         &daily=weather_code,temperature_2m_max,temperature_2m_min,precipitation_sum&start_date={date}&end_date={date}"
+    );
+    parse_daily(&http::get_json(&url)?, location, date)
+}
+
+fn parse_daily(json: &serde_json::Value, location: &str, date: &str) -> Result<Weather, String> {
+    let daily = json.get("daily").ok_or_else(|| tr!("No weather data for {}", date))?;
+    let first = |key: &str| daily.get(key).and_then(|v| v.get(0)).and_then(|v| v.as_f64());
+    let code = first("weather_code").ok_or_else(|| tr!("No weather data for {}", date))? as u32;
+    let (emoji, label) = describe(code);
+    Ok(Weather {
+        date: date.to_string(),
+        location: location.to_string(),
+        code,
+        emoji: emoji.to_string(),
+        summary: i18n::translate(label).to_string(),
+        temp_min: first("temperature_2m_min"),
+        temp_max: first("temperature_2m_max"),
+        precipitation_mm: first("precipitation_sum"),
+    })
+}
+
+/// WMO code → (emoji, English label looked up in the catalog)
+fn describe(code: u32) -> (&'static str, &'static str) {
+    match code {
+        0 => ("☀️", "Clear"),
+        1 | 2 => ("⛅", "Partly cloudy"),
+        3 => ("☁️", "Overcast"),
+        45 | 48 => ("🌫️", "Fog"),
+        51..=57 => ("🌦️", "Drizzle"),
+        61 | 63 | 66 => ("🌧️", "Rain"),
+        65 | 67 => ("🌧️", "Heavy rain"),
+        71..=77 | 85 | 86 => ("❄️", "Snow"),
+        80..=82 => ("🌦️", "Showers"),
+        95..=99 => ("⛈️", "Thunderstorm"),
+        _ => ("🌡️", "Unknown"),
+    }
+}
+
+fn read_cache(vault: &Path) -> BTreeMap<String, Weather> {
+    fs::read_to_string(vault.join(CACHE_FILE))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn write_cache(vault: &Path, cache: &BTreeMap<String, Weather>) {
+    if let Ok(json) = serde_json::to_string_pretty(cache) {
+        let _ = durable::write(vault.join(CACHE_FILE), json);
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_daily() {
+        let json = serde_json::json!({
+            "daily": {
+                "time": ["2025-03-01"],
+                "weather_code": [61],
+                "temperature_2m_max": [14.6],
+                "temperature_2m_min": [8.2],
+                "precipitation_sum": [3.1]
+            }
+        });
+        let w = parse_daily(&json, "上海", "2025-03-01").unwrap();
+        assert_eq!((w.code, w.emoji.as_str()), (61, "🌧️"));
+        assert_eq!(w.precipitation_mm, Some(3.1));
+        assert!(w.text().ends_with(" 8–15°C"));
+        assert!(parse_daily(&serde_json::json!({}), "x", "2025-03-01").is_err());
+    }
+}
@@ -8,6 +8,7 @@ import Shell from "@/components/layout/Shell";
 import { isTauri } from "@/services/env";
 import { loadDirectoryHandle } from "@/services/web-fs-store";
 import { setDirectoryHandle } from "@/services/web-fs";
+import { startConfigWatch, stopConfigWatch, onConfigChanged, startAutomations, stopAutomations, onAutomationRun, startOccasionReminders, stopOccasionReminders, startRenewalReminders, stopRenewalReminders, startMedicationReminders, stopMedicationReminders, startFollowupReminders, stopFollowupReminders, startLocationLogger, stopLocationLogger, startSyncLoop, stopSyncLoop, pullTaskStatus, startCalendarSyncLoop, stopCalendarSyncLoop, startCommitSummaryLoop, stopCommitSummaryLoop, startMailDigestLoop, stopMailDigestLoop, startExportScheduler, stopExportScheduler, startBackgroundAgent, stopBackgroundAgent, startOutboxLoop, stopOutboxLoop, startMailIdle, stopMailIdle, getAppState, setAppState, getPlatformInfo, reindexSpotlight } from "@/services/tauri";
 
 function AppContent() {
   const { vaultPath, setVaultPath } = useStore();
@@ -51,6 +52,190 @@ function AppContent() {
     if (vaultPath) loadAll();
   }, [vaultPath]);
 
+  // Reopen the view that was open last time and remember it as it changes.
+  // Standalone windows opened on a given view leave it alone.
+  useEffect(() => {
+    if (!vaultPath || !isTauri() || new URLSearchParams(window.location.search).get("view")) return;
+    getAppState(vaultPath)
+      .then((state) => {
+        if (state.active_tab) setView(state.active_tab as import("@/types").ViewId);
+      })
+      .catch(console.error);
+    return useStore.subscribe((state, prev) => {
+      if (state.currentView !== prev.currentView) {
+        setAppState(vaultPath, { active_tab: state.currentView }).catch(console.error);
+      }
+    });
+  }, [vaultPath]);
+
+  // Spotlight metadata for notes changed since last time (macOS, unless turned off)
+  useEffect(() => {
+    if (!vaultPath || !isTauri()) return;
+    getPlatformInfo()
+      .then((platform) => (platform.spotlight ? reindexSpotlight(vaultPath) : undefined))
+      .catch(console.error);
+  }, [vaultPath]);
+
+  // Hot-reload .lifeos/*.yaml edited by hand or by AI agents
+  useEffect(() => {
+    if (!vaultPath || !isTauri()) return;
+    const { loadMenuConfigFromVault, loadAppSettingsFromVault } = useStore.getState();
+    startConfigWatch(vaultPath).catch(console.error);
+    const unlisten = onConfigChanged((status) => {
+      if (!status.valid) {
+        console.warn(`Ignoring invalid ${status.kind} config:`, status.error);
+        return;
+      }
+      if (status.kind === "menu") loadMenuConfigFromVault();
+      if (status.kind === "settings") loadAppSettingsFromVault();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+      stopConfigWatch().catch(console.error);
+    };
+  }, [vaultPath]);
+
+  // Run the vault's .lifeos/automations rules while it is open
+  useEffect(() => {
+    if (!vaultPath || !isTauri()) return;
+    startAutomations(vaultPath).catch(console.error);
+    const unlisten = onAutomationRun((run) => {
+      if (!run.ok) console.warn(`Automation ${run.ruleId} failed:`, run.actions.find((a) => !a.ok)?.message);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+      stopAutomations().catch(console.error);
+    };
+  }, [vaultPath]);
+
+  // System notifications for upcoming birthdays / anniversaries
+  useEffect(() => {
+    if (!vaultPath || !isTauri()) return;
+    startOccasionReminders(vaultPath).catch(console.error);
+    return () => {
+      stopOccasionReminders().catch(console.error);
+    };
+  }, [vaultPath]);
+
+  // System notifications before subscription renewals
+  useEffect(() => {
+    if (!vaultPath || !isTauri()) return;
+    startRenewalReminders(vaultPath).catch(console.error);
+    return () => {
+      stopRenewalReminders().catch(console.error);
+    };
+  }, [vaultPath]);
+
+  // System notifications when an email follow-up falls due without a reply
+  useEffect(() => {
+    if (!vaultPath || !isTauri()) return;
+    startFollowupReminders(vaultPath).catch(console.error);
+    return () => {
+      stopFollowupReminders().catch(console.error);
+    };
+  }, [vaultPath]);
+
+  // System notifications at scheduled medication times
+  useEffect(() => {
+    if (!vaultPath || !isTauri()) return;
+    startMedicationReminders(vaultPath).catch(console.error);
+    return () => {
+      stopMedicationReminders().catch(console.error);
+    };
+  }, [vaultPath]);
+
+  // Location history (a no-op until enabled in its settings)
+  useEffect(() => {
+    if (!vaultPath || !isTauri()) return;
+    startLocationLogger(vaultPath).catch(console.error);
+    return () => {
+      stopLocationLogger().catch(console.error);
+    };
+  }, [vaultPath]);
+
+  // Background vault sync (a no-op until sync.enabled in connectors.yaml)
+  useEffect(() => {
+    if (!vaultPath || !isTauri()) return;
+    startSyncLoop(vaultPath).catch(console.error);
+    return () => {
+      stopSyncLoop().catch(console.error);
+    };
+  }, [vaultPath]);
+
+  // Tasks completed in Todoist/TickTick since last time (no requests until some were pushed)
+  useEffect(() => {
+    if (!vaultPath || !isTauri()) return;
+    pullTaskStatus(vaultPath)
+      .then((r) => {
+        if (r.completed.length > 0) loadAll();
+      })
+      .catch(console.error);
+  }, [vaultPath]);
+
+  // Google Calendar sync (a no-op until calendar.enabled and signed in)
+  useEffect(() => {
+    if (!vaultPath || !isTauri()) return;
+    startCalendarSyncLoop(vaultPath).catch(console.error);
+    return () => {
+      stopCalendarSyncLoop().catch(console.error);
+    };
+  }, [vaultPath]);
+
+  // End-of-day commit summary (a no-op until git.commit_summary is on)
+  useEffect(() => {
+    if (!vaultPath || !isTauri()) return;
+    startCommitSummaryLoop(vaultPath).catch(console.error);
+    return () => {
+      stopCommitSummaryLoop().catch(console.error);
+    };
+  }, [vaultPath]);
+
+  // Morning mail digest (a no-op until mail_digest.enabled is on)
+  useEffect(() => {
+    if (!vaultPath || !isTauri()) return;
+    startMailDigestLoop(vaultPath).catch(console.error);
+    return () => {
+      stopMailDigestLoop().catch(console.error);
+    };
+  }, [vaultPath]);
+
+  // Scheduled export profiles
+  useEffect(() => {
+    if (!vaultPath || !isTauri()) return;
+    startExportScheduler(vaultPath).catch(console.error);
+    return () => {
+      stopExportScheduler().catch(console.error);
+    };
+  }, [vaultPath]);
+
+  // Periodic mail sync, which keeps going while the window is hidden
+  useEffect(() => {
+    if (!vaultPath || !isTauri()) return;
+    startBackgroundAgent(vaultPath).catch(console.error);
+    return () => {
+      stopBackgroundAgent().catch(console.error);
+    };
+  }, [vaultPath]);
+
+  // Replay operations queued while offline once the network is back
+  useEffect(() => {
+    if (!vaultPath || !isTauri()) return;
+    startOutboxLoop(vaultPath).catch(console.error);
+    return () => {
+      stopOutboxLoop().catch(console.error);
+    };
+  }, [vaultPath]);
+
+  // Push new mail as it arrives; restarted when accounts are added or toggled
+  const idleAccounts = useStore((s) => s.emailAccounts.map((a) => `${a.id}:${a.enabled}`).join(","));
+  useEffect(() => {
+    if (!vaultPath || !isTauri()) return;
+    startMailIdle(vaultPath).catch(console.error);
+    return () => {
+      stopMailIdle().catch(console.error);
+    };
+  }, [vaultPath, idleAccounts]);
+
   return (
     <>
       <div className="grid-bg" />
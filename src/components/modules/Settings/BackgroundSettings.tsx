@@ -0,0 +1,90 @@
+import { useEffect, useState } from "react";
+import { useStore } from "@/stores/app";
+import { isTauri } from "@/services/env";
+import {
+  getBackgroundSettings,
+  saveBackgroundSettings,
+  getLaunchAtLogin,
+  setLaunchAtLogin,
+  type BackgroundSettings as Settings,
+} from "@/services/tauri";
+
+export default function BackgroundSettings() {
+  const vaultPath = useStore((s) => s.vaultPath);
+  const [settings, setSettings] = useState<Settings | null>(null);
+  const [atLogin, setAtLogin] = useState(false);
+  const [error, setError] = useState("");
+
+  useEffect(() => {
+    if (!vaultPath || !isTauri()) return;
+    getBackgroundSettings(vaultPath).then(setSettings).catch(console.error);
+    getLaunchAtLogin().then(setAtLogin).catch(console.error);
+  }, [vaultPath]);
+
+  if (!isTauri()) {
+    return <div className="text-sm text-text-dim">仅桌面应用支持后台运行。</div>;
+  }
+  if (!vaultPath || !settings) return null;
+
+  const update = async (patch: Partial<Settings>) => {
+    const next = { ...settings, ...patch };
+    setSettings(next);
+    try {
+      await saveBackgroundSettings(vaultPath, next);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const toggleAtLogin = async () => {
+    setError("");
+    try {
+      await setLaunchAtLogin(!atLogin);
+      setAtLogin(!atLogin);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  return (
+    <div className="flex flex-col gap-4">
+      <div className="flex items-center justify-between">
+        <div>
+          <div className="text-sm text-text">开机时启动</div>
+          <div className="text-xs text-text-dim mt-0.5">登录后在后台启动，不打开窗口</div>
+        </div>
+        <div className="toggle-wrap cursor-pointer" onClick={toggleAtLogin}>
+          <button className={`toggle ${atLogin ? "on" : ""}`} />
+        </div>
+      </div>
+
+      <div className="flex items-center justify-between">
+        <div>
+          <div className="text-sm text-text">关闭窗口后继续运行</div>
+          <div className="text-xs text-text-dim mt-0.5">提醒、同步照常进行；点击 Dock 图标重新打开窗口</div>
+        </div>
+        <div className="toggle-wrap cursor-pointer" onClick={() => update({ keep_running: !settings.keep_running })}>
+          <button className={`toggle ${settings.keep_running ? "on" : ""}`} />
+        </div>
+      </div>
+
+      <div className="flex items-center justify-between">
+        <div className="text-sm text-text">邮件同步间隔</div>
+        <select
+          className="input"
+          style={{ width: 140, cursor: "pointer" }}
+          value={settings.mail_sync_minutes}
+          onChange={(e) => update({ mail_sync_minutes: Number(e.target.value) })}
+        >
+          <option value={0}>不自动同步</option>
+          <option value={5}>每 5 分钟</option>
+          <option value={15}>每 15 分钟</option>
+          <option value={30}>每 30 分钟</option>
+          <option value={60}>每小时</option>
+        </select>
+      </div>
+
+      {error && <div className="text-xs text-accent4">{error}</div>}
+    </div>
+  );
+}
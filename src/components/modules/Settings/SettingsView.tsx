@@ -4,6 +4,9 @@ import { pickVaultFolder, setVaultPath as saveVaultPath, initVault, openInFinder
 import { Settings, FolderOpen, Info, Bot, CheckCircle, RefreshCw, FolderSearch } from "lucide-react";
 import MenuManager from "./MenuManager";
 import ThemeCustomizer from "./ThemeCustomizer";
+import BackgroundSettings from "./BackgroundSettings";
+import SpotlightSettings from "./SpotlightSettings";
+import StorageSettings from "./StorageSettings";
 
 export default function SettingsView() {
   const vaultPath = useStore((s) => s.vaultPath);
@@ -132,6 +135,30 @@ export default function SettingsView() {
         </div>
       </div>
 
+      {/* Background */}
+      <div>
+        <div className="label mb-3">后台运行</div>
+        <div className="panel-inner p-5 transition-all duration-200 hover:border-accent/20">
+          <BackgroundSettings />
+        </div>
+      </div>
+
+      {/* Spotlight */}
+      <div>
+        <div className="label mb-3">系统搜索</div>
+        <div className="panel-inner p-5 transition-all duration-200 hover:border-accent/20">
+          <SpotlightSettings />
+        </div>
+      </div>
+
+      {/* Storage */}
+      <div>
+        <div className="label mb-3">存储空间</div>
+        <div className="panel-inner p-5 transition-all duration-200 hover:border-accent/20">
+          <StorageSettings />
+        </div>
+      </div>
+
       {/* Claude Code */}
       <div>
         <div className="label mb-3">Claude Code AI</div>
@@ -0,0 +1,61 @@
+import { useEffect, useState } from "react";
+import { useStore } from "@/stores/app";
+import { isTauri } from "@/services/env";
+import { getPlatformInfo, getSpotlightSettings, setSpotlightEnabled, reindexSpotlight } from "@/services/tauri";
+
+export default function SpotlightSettings() {
+  const vaultPath = useStore((s) => s.vaultPath);
+  const [supported, setSupported] = useState(false);
+  const [enabled, setEnabled] = useState(true);
+  const [busy, setBusy] = useState(false);
+  const [message, setMessage] = useState("");
+
+  useEffect(() => {
+    if (!vaultPath || !isTauri()) return;
+    getPlatformInfo().then((p) => setSupported(p.spotlight)).catch(console.error);
+    getSpotlightSettings(vaultPath).then((s) => setEnabled(s.enabled)).catch(console.error);
+  }, [vaultPath]);
+
+  if (!supported || !vaultPath) {
+    return <div className="text-sm text-text-dim">仅 macOS 支持 Spotlight 索引。</div>;
+  }
+
+  const run = async (action: () => Promise<{ indexed: number; failed: [string, string][] }>) => {
+    setBusy(true);
+    setMessage("");
+    try {
+      const report = await action();
+      setMessage(`已处理 ${report.indexed} 篇笔记${report.failed.length ? `，${report.failed.length} 篇失败` : ""}`);
+    } catch (e) {
+      setMessage(String(e));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const toggle = () => {
+    const next = !enabled;
+    setEnabled(next);
+    run(() => setSpotlightEnabled(vaultPath, next));
+  };
+
+  return (
+    <div className="flex flex-col gap-4">
+      <div className="flex items-center justify-between">
+        <div>
+          <div className="text-sm text-text">在 Spotlight 中按标题和标签搜索笔记</div>
+          <div className="text-xs text-text-dim mt-0.5">关闭后移除已添加的元数据，Spotlight 仍可按正文找到文件</div>
+        </div>
+        <div className="toggle-wrap cursor-pointer" onClick={busy ? undefined : toggle}>
+          <button className={`toggle ${enabled ? "on" : ""}`} />
+        </div>
+      </div>
+      <div className="flex items-center gap-3">
+        <button className="btn btn-ghost" disabled={!enabled || busy} onClick={() => run(() => reindexSpotlight(vaultPath, true))}>
+          {busy ? "索引中..." : "重新索引"}
+        </button>
+        {message && <span className="text-xs text-text-dim">{message}</span>}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,117 @@
+import { useEffect, useState } from "react";
+import { useStore } from "@/stores/app";
+import { isTauri } from "@/services/env";
+import { openInFinder } from "@/services/fs";
+import { analyzeStorage, type StorageReport, type StorageCategory } from "@/services/tauri";
+
+const CATEGORY_LABELS: Record<StorageCategory, string> = {
+  notes: "笔记",
+  mail: "邮件缓存",
+  assets: "附件",
+  app: "应用数据",
+  other: "其他",
+};
+
+function formatBytes(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  const units = ["KB", "MB", "GB", "TB"];
+  let value = bytes / 1024;
+  let unit = 0;
+  while (value >= 1024 && unit < units.length - 1) {
+    value /= 1024;
+    unit++;
+  }
+  return `${value.toFixed(value < 10 ? 1 : 0)} ${units[unit]}`;
+}
+
+export default function StorageSettings() {
+  const vaultPath = useStore((s) => s.vaultPath);
+  const [report, setReport] = useState<StorageReport | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState("");
+
+  const load = async (refresh = false) => {
+    if (!vaultPath) return;
+    setBusy(true);
+    setError("");
+    try {
+      setReport(await analyzeStorage(vaultPath, refresh));
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  useEffect(() => {
+    if (vaultPath && isTauri()) load();
+  }, [vaultPath]);
+
+  if (!isTauri() || !vaultPath) {
+    return <div className="text-sm text-text-dim">仅桌面端支持存储分析。</div>;
+  }
+
+  const topLevel = report?.folders.filter((f) => !f.path.includes("/")) ?? [];
+
+  return (
+    <div className="flex flex-col gap-4">
+      <div className="flex items-center gap-3">
+        <div className="text-sm text-text flex-1">
+          {report ? `共 ${formatBytes(report.bytes)} · ${report.files} 个文件` : busy ? "分析中..." : ""}
+        </div>
+        <button className="btn btn-ghost" disabled={busy} onClick={() => load(true)}>
+          {busy ? "分析中..." : "重新分析"}
+        </button>
+      </div>
+      {error && <div className="text-xs text-accent4">{error}</div>}
+
+      {report && (
+        <>
+          {/* Categories as a stacked bar */}
+          <div className="flex h-2 rounded-sm overflow-hidden bg-panel-2">
+            {report.categories.map((c, i) => (
+              <div
+                key={c.category}
+                title={`${CATEGORY_LABELS[c.category]} ${formatBytes(c.bytes)}`}
+                style={{ width: `${(c.bytes / Math.max(report.bytes, 1)) * 100}%`, background: `var(--accent${i === 0 ? "" : i + 1})` }}
+              />
+            ))}
+          </div>
+          <div className="flex flex-wrap gap-2">
+            {report.categories.map((c) => (
+              <span key={c.category} className="tag text-[11px]">
+                {CATEGORY_LABELS[c.category]} {formatBytes(c.bytes)}
+              </span>
+            ))}
+          </div>
+
+          <div className="grid gap-4" style={{ gridTemplateColumns: "1fr 1fr" }}>
+            <div className="flex flex-col gap-1">
+              <div className="text-xs text-text-dim mb-1">文件夹</div>
+              {topLevel.map((f) => (
+                <div key={f.path} className="flex items-center gap-2 text-xs">
+                  <button
+                    className="flex-1 text-left overflow-hidden text-ellipsis whitespace-nowrap hover:text-accent"
+                    onClick={() => openInFinder(f.path === "." ? vaultPath : `${vaultPath}/${f.path}`)}
+                  >
+                    {f.path === "." ? "(根目录)" : f.path}
+                  </button>
+                  <span className="text-text-dim font-mono">{formatBytes(f.bytes)}</span>
+                </div>
+              ))}
+            </div>
+            <div className="flex flex-col gap-1">
+              <div className="text-xs text-text-dim mb-1">最大的文件</div>
+              {report.largest_files.slice(0, 10).map((f) => (
+                <div key={f.path} className="flex items-center gap-2 text-xs" title={f.path}>
+                  <span className="flex-1 overflow-hidden text-ellipsis whitespace-nowrap">{f.path}</span>
+                  <span className="text-text-dim font-mono">{formatBytes(f.bytes)}</span>
+                </div>
+              ))}
+            </div>
+          </div>
+        </>
+      )}
+    </div>
+  );
+}
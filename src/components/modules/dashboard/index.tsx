@@ -1,10 +1,12 @@
-import { useState, useMemo } from "react";
+import { useState, useMemo, useEffect } from "react";
 import { useStore } from "@/stores/app";
 import { writeFile } from "@/services/fs";
+import { getDevEnvironment, type DevEnvironment } from "@/services/tauri";
+import { isTauri } from "@/services/env";
 import { format, subDays } from "date-fns";
 import { zhCN } from "date-fns/locale";
 import type { HabitStore } from "@/types";
-import { ClipboardList, Rocket, BookOpen, Scale, CheckCircle, FolderKanban, ChevronRight, Check } from "lucide-react";
+import { ClipboardList, Rocket, BookOpen, Scale, CheckCircle, FolderKanban, ChevronRight, Check, RefreshCw } from "lucide-react";
 
 export default function Dashboard() {
   const todayNote = useStore((s) => s.todayNote);
@@ -370,6 +372,9 @@ export default function Dashboard() {
             </div>
           </div>
 
+          {/* Machine health */}
+          {isTauri() && <MachineHealth />}
+
           {/* Recent diary */}
           <div className="panel p-5 fade-in" style={{ animationDelay: "0.25s" }}>
             <SectionLabel dot="var(--accent5)">最近日记</SectionLabel>
@@ -421,6 +426,67 @@ function serializeHabits(store: HabitStore): string {
   return yaml;
 }
 
+// 开发环境：工具链版本和待更新的包
+function MachineHealth() {
+  const [env, setEnv] = useState<DevEnvironment | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  function load(refresh = false) {
+    setLoading(true);
+    getDevEnvironment(refresh)
+      .then(setEnv)
+      .catch(() => {})
+      .finally(() => setLoading(false));
+  }
+
+  useEffect(() => load(), []);
+
+  const installed = env?.tools.filter((t) => t.version) ?? [];
+
+  return (
+    <div className="panel p-5 fade-in" style={{ animationDelay: "0.3s" }}>
+      <div className="flex justify-between items-center mb-3">
+        <SectionLabel dot="var(--accent)">开发环境</SectionLabel>
+        <button
+          className="btn-ghost text-[11px] px-2 py-0.75 flex items-center gap-1"
+          onClick={() => load(true)}
+          disabled={loading}
+        >
+          <RefreshCw size={12} className={loading ? "spin" : ""} /> 检查更新
+        </button>
+      </div>
+      {!env && <div className="text-text-dim text-sm">{loading ? "检查中..." : "无法读取开发环境"}</div>}
+      {env && (
+        <div className="flex flex-col gap-2">
+          <div className="flex flex-wrap gap-1.5">
+            {installed.map((t) => (
+              <span key={t.name} className="tag text-[10px]" title={t.path ?? ""}>
+                {t.name} {t.version}
+              </span>
+            ))}
+          </div>
+          {env.updates.length === 0 ? (
+            <div className="text-[11px] text-accent3">全部是最新版本</div>
+          ) : (
+            <div className="flex flex-col gap-1">
+              <div className="text-[11px] text-text-dim">{env.updates.length} 个待更新</div>
+              {env.updates.slice(0, 6).map((u) => (
+                <div key={`${u.source}:${u.name}`} className="flex items-center gap-2 text-xs">
+                  <span className="tag orange text-[9px]">{u.source}</span>
+                  <span className="flex-1 overflow-hidden text-ellipsis whitespace-nowrap">{u.name}</span>
+                  <span className="text-text-dim font-mono text-[10px]">
+                    {u.current} → {u.latest}
+                  </span>
+                </div>
+              ))}
+            </div>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
+
 function SectionLabel({ dot, children }: { dot: string; children: string }) {
   return (
     <div className="flex items-center gap-2">
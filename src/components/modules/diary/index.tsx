@@ -1,6 +1,8 @@
 import { useState, useEffect } from "react";
 import { useStore } from "@/stores/app";
 import { writeNote, deleteFile } from "@/services/fs";
+import { fillWeather, insertJournalPrompt, isWriteConflict, lockNote, unlockNote } from "@/services/tauri";
+import { isTauri } from "@/services/env";
 import { useVaultLoader } from "@/hooks/useVaultLoader";
 import type { DiaryEntry } from "@/types";
 import { format } from "date-fns";
@@ -21,6 +23,10 @@ const MOODS: { emoji: string; icon: React.ReactNode; label: string }[] = [
   { emoji: "😤", icon: <Zap size={18} className="rotate-180" />, label: "愤怒" },
 ];
 
+// Owner name of the editor's advisory locks; agents use their own
+const LOCK_OWNER = "lifeos";
+const LOCK_TTL_SECS = 300;
+
 export default function DiaryView() {
   const diaryEntries = useStore((s) => s.diaryEntries);
   const vaultPath = useStore((s) => s.vaultPath);
@@ -35,10 +41,30 @@ export default function DiaryView() {
     if (diaryEntries.length && !active) setActive(diaryEntries[0]);
   }, [diaryEntries]);
 
+  // Hold an advisory lock on the entry being edited, renewed while open, so
+  // agents editing the vault leave it alone; their lock is shown instead
+  const [lockedBy, setLockedBy] = useState<string | null>(null);
+  const editingPath = editing?.path;
+  useEffect(() => {
+    if (!editingPath || !vaultPath || !isTauri()) return;
+    const take = () =>
+      lockNote(vaultPath, editingPath, LOCK_OWNER, LOCK_TTL_SECS)
+        .then(() => setLockedBy(null))
+        .catch((e) => setLockedBy(String(e)));
+    take();
+    const renew = setInterval(take, (LOCK_TTL_SECS / 2) * 1000);
+    return () => {
+      clearInterval(renew);
+      setLockedBy(null);
+      unlockNote(vaultPath, editingPath, LOCK_OWNER).catch(() => {});
+    };
+  }, [editingPath, vaultPath]);
+
   const open = (e: DiaryEntry) => { setActive(e); setEditing({ ...e }); };
 
   const save = async () => {
     if (!editing || !vaultPath) return;
+    if (lockedBy && !confirm(`${lockedBy}\n仍要保存吗？`)) return;
     setSaving(true);
     try {
       const fm = {
@@ -48,8 +74,19 @@ export default function DiaryView() {
         energy: editing.energy,
         tags: editing.tags.join(", "),
       };
-      await writeNote(editing.path, fm, editing.content);
+      let written;
+      try {
+        written = await writeNote(editing.path, fm, editing.content, { hash: editing.hash });
+      } catch (e) {
+        // Changed elsewhere (an agent, another window) since it was opened
+        if (!isWriteConflict(e) || !confirm("这篇日记已在别处被修改，是否用当前内容覆盖？")) throw e;
+        written = await writeNote(editing.path, fm, editing.content);
+      }
+      if (written) setEditing({ ...editing, hash: written.hash });
       await loadAll();
+    } catch (e) {
+      if (isWriteConflict(e)) alert("已保留外部修改，当前编辑未保存");
+      else alert("保存失败: " + e);
     } finally {
       setSaving(false);
     }
@@ -76,7 +113,10 @@ export default function DiaryView() {
     const path = `${vaultPath}/diary/${year}/${filename}`;
     const fm = { date: dateStr, mood: "😊", energy: "high", tags: "" };
     await writeNote(path, fm, `# ${dateStr}\n\n`);
+    if (isTauri()) await insertJournalPrompt(vaultPath, path).catch(console.error);
     await loadAll();
+    // Network lookup; the entry shows up first and gains its weather after
+    if (isTauri()) fillWeather(vaultPath, path).then(loadAll).catch(console.error);
   };
 
   // Group entries by date
@@ -159,6 +199,7 @@ export default function DiaryView() {
               {current.date}
             </div>
             <div className="flex gap-2 items-center">
+              {lockedBy && <span className="text-xs text-warning">{lockedBy}</span>}
               {saving && <span className="text-xs text-text-dim">保存中...</span>}
               <button
                 className="btn btn-ghost px-3 py-1.5 text-sm text-red-400 hover:text-red-300"
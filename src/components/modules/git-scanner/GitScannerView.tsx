@@ -1,10 +1,12 @@
 import { useState } from "react";
 import { useStore } from "@/stores/app";
 import { scanGitRepos, pickVaultFolder, openInFinder } from "@/services/fs";
+import { scanCodeTodos, addTask, type CodeTodo } from "@/services/tauri";
 
 export default function GitScannerView() {
   const gitRepos = useStore((s) => s.gitRepos);
   const setGitRepos = useStore((s) => s.setGitRepos);
+  const vaultPath = useStore((s) => s.vaultPath);
 
   const [scanRoot, setScanRoot] = useState("");
   const [scanning, setScanning] = useState(false);
@@ -12,6 +14,10 @@ export default function GitScannerView() {
   const [maxDepth, setMaxDepth] = useState(5);
   const [error, setError] = useState("");
   const [copied, setCopied] = useState<string | null>(null);
+  const [todos, setTodos] = useState<CodeTodo[] | null>(null);
+  const [todoPatterns, setTodoPatterns] = useState("TODO, FIXME, HACK");
+  const [scanningTodos, setScanningTodos] = useState(false);
+  const [filed, setFiled] = useState<Set<string>>(new Set());
 
   async function handlePickFolder() {
     const folder = await pickVaultFolder();
@@ -32,6 +38,33 @@ export default function GitScannerView() {
     }
   }
 
+  async function handleScanTodos() {
+    if (!scanRoot) return;
+    setScanningTodos(true);
+    setError("");
+    try {
+      const patterns = todoPatterns.split(",").map((p) => p.trim()).filter(Boolean);
+      setTodos(await scanCodeTodos(scanRoot, patterns, maxDepth));
+    } catch (e: unknown) {
+      setError(String(e));
+    } finally {
+      setScanningTodos(false);
+    }
+  }
+
+  // 把代码里的 TODO 记到今天的任务里
+  async function fileTodo(todo: CodeTodo) {
+    if (!vaultPath) return;
+    const repoName = todo.repo.split("/").pop() || todo.repo;
+    const key = `${todo.repo}:${todo.path}:${todo.line}`;
+    try {
+      await addTask(vaultPath, `${todo.kind}: ${todo.text || "(无说明)"} \`${repoName}/${todo.path}:${todo.line}\``);
+      setFiled((prev) => new Set(prev).add(key));
+    } catch (e: unknown) {
+      setError(String(e));
+    }
+  }
+
   function copyPath(path: string) {
     navigator.clipboard.writeText(path);
     setCopied(path);
@@ -201,6 +234,50 @@ export default function GitScannerView() {
         </div>
       )}
 
+      {/* Code TODOs */}
+      <div className="panel p-4 flex flex-col gap-3">
+        <div className="flex items-center gap-2.5">
+          <span className="text-sm font-semibold">代码 TODO</span>
+          <input
+            className="input flex-1"
+            placeholder="标记，逗号分隔"
+            value={todoPatterns}
+            onChange={(e) => setTodoPatterns(e.target.value)}
+          />
+          <button className="btn btn-ghost" onClick={handleScanTodos} disabled={scanningTodos || !scanRoot}>
+            {scanningTodos ? "扫描中..." : "扫描 TODO"}
+          </button>
+        </div>
+        {todos && todos.length === 0 && <div className="text-xs text-text-dim">没有找到 TODO 注释</div>}
+        {todos && todos.length > 0 && (
+          <div className="flex flex-col gap-1 text-xs">
+            <div className="text-text-dim">共 {todos.length} 条</div>
+            {todos.map((t) => {
+              const key = `${t.repo}:${t.path}:${t.line}`;
+              return (
+                <div key={key} className="flex items-center gap-2 py-1 border-b border-border">
+                  <span className="tag orange">{t.kind}</span>
+                  <span className="flex-1 min-w-0 overflow-hidden text-ellipsis whitespace-nowrap" title={t.text}>
+                    {t.text || "(无说明)"}
+                  </span>
+                  <span className="text-text-dim font-mono overflow-hidden text-ellipsis whitespace-nowrap max-w-[300px]" title={`${t.repo}/${t.path}:${t.line}`}>
+                    {(t.repo.split("/").pop() || t.repo)}/{t.path}:{t.line}
+                  </span>
+                  {t.author && <span className="text-text-mid">{t.author}</span>}
+                  <button
+                    className="btn btn-ghost px-2 py-0.5 text-xs"
+                    onClick={() => fileTodo(t)}
+                    disabled={!vaultPath || filed.has(key)}
+                  >
+                    {filed.has(key) ? "已转为任务" : "转为任务"}
+                  </button>
+                </div>
+              );
+            })}
+          </div>
+        )}
+      </div>
+
       {/* Empty state */}
       {!scanning && gitRepos.length === 0 && (
         <div className="text-center p-[60px] text-text-dim text-sm">
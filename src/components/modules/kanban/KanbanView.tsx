@@ -1,6 +1,8 @@
 import { useState, useMemo, useEffect } from "react";
 import { useStore } from "@/stores/app";
 import { writeNote, deleteFile } from "@/services/fs";
+import { moveProject, getProjectDevStatus, isWriteInvalid, writeErrorMessage, type DevStatus } from "@/services/tauri";
+import { isTauri } from "@/services/env";
 import type { Project, KanbanColumn, Priority } from "@/types";
 import { format } from "date-fns";
 import {
@@ -233,6 +235,7 @@ function ProjectList({
   defaultProject: string | null;
   onSetDefault: (path: string | null) => void;
 }) {
+  const vaultPath = useStore((s) => s.vaultPath);
   const [showAdd, setShowAdd] = useState(false);
   const [newTitle, setNewTitle] = useState("");
   const [newPriority, setNewPriority] = useState<Priority>("medium");
@@ -304,10 +307,25 @@ function ProjectList({
       tags: updatedProject.tags.join(","),
       due: updatedProject.due || "",
     };
+    // 状态变更交给后端：检查列的在制上限并执行 board.yaml 中的规则
+    const moveIn = editStatus !== editingProject.status && isTauri() ? vaultPath : null;
+    if (moveIn) frontmatter.status = editingProject.status;
     try {
       await writeNote(updatedProject.path, frontmatter, newContent);
+      if (moveIn) {
+        const moved = await moveProject(moveIn, updatedProject.path, editStatus);
+        if (typeof moved.fields.progress === "number") updatedProject.progress = moved.fields.progress;
+      }
     } catch (e) {
       console.error("Failed to save project:", e);
+      if (isWriteInvalid(e)) {
+        alert("保存失败: " + writeErrorMessage(e));
+        return;
+      }
+      if (moveIn) {
+        alert("移动失败: " + e);
+        updatedProject.status = editingProject.status;
+      }
     }
     // 调用 upsertProject 更新
     useStore.getState().upsertProject(updatedProject);
@@ -994,6 +1012,7 @@ function ProjectBoard({
       progress: String(project.progress),
       tags: project.tags.join(","),
       due: project.due || "",
+      ...(project.repo ? { repo: project.repo } : {}),
     };
     try {
       await writeNote(project.path, frontmatter, newContent);
@@ -1257,6 +1276,8 @@ function ProjectBoard({
         </div>
       </div>
 
+      {project.repo && isTauri() && <DevStatusPanel project={project} />}
+
       <DndContext
         sensors={sensors}
         collisionDetection={closestCorners}
@@ -1587,3 +1608,59 @@ function ProjectBoard({
     </div>
   );
 }
+
+// 关联仓库的开发状态：分支、未提交改动、最近提交、TODO 注释
+function DevStatusPanel({ project }: { project: Project }) {
+  const vaultPath = useStore((s) => s.vaultPath);
+  const [status, setStatus] = useState<DevStatus | null>(null);
+  const [error, setError] = useState("");
+  const slug = project.path.split("/").pop()?.replace(/\.md$/, "") ?? "";
+
+  useEffect(() => {
+    if (!vaultPath || !slug) return;
+    setError("");
+    getProjectDevStatus(vaultPath, slug)
+      .then(setStatus)
+      .catch((e) => setError(String(e)));
+  }, [vaultPath, slug, project.repo]);
+
+  if (error) {
+    return <div className="panel p-3 text-[12px] text-text-dim">{error}</div>;
+  }
+  if (!status) return null;
+
+  return (
+    <div className="panel p-4 flex flex-col gap-3 text-[12px]">
+      <div className="flex items-center gap-3 font-[var(--font-mono)]">
+        <span className="text-accent">⎇ {status.branch}</span>
+        <span className="text-text-dim">{status.repo}</span>
+        <span className="ml-auto text-text-dim">
+          {status.changes.length > 0 ? `${status.changes.length} 处未提交改动` : "工作区干净"} · {status.todos.length} 个 TODO
+        </span>
+      </div>
+      {status.readme && <div className="text-text-dim">{status.readme}</div>}
+      <div className="grid gap-4" style={{ gridTemplateColumns: "1fr 1fr" }}>
+        <div className="flex flex-col gap-1">
+          <div className="text-text-dim tracking-[1px]">最近提交</div>
+          {status.recent_commits.slice(0, 5).map((c) => (
+            <div key={c.hash} className="flex gap-2 truncate">
+              <span className="font-[var(--font-mono)] text-text-dim">{c.hash}</span>
+              <span className="truncate flex-1">{c.subject}</span>
+              <span className="text-text-dim">{c.date.slice(0, 10)}</span>
+            </div>
+          ))}
+        </div>
+        <div className="flex flex-col gap-1">
+          <div className="text-text-dim tracking-[1px]">TODO 注释</div>
+          {status.todos.slice(0, 8).map((t) => (
+            <div key={`${t.path}:${t.line}`} className="flex gap-2 truncate" title={`${t.path}:${t.line}`}>
+              <span className="font-[var(--font-mono)] text-warning">{t.kind}</span>
+              <span className="truncate flex-1">{t.text || `${t.path}:${t.line}`}</span>
+            </div>
+          ))}
+          {status.todos.length === 0 && <div className="text-text-dim">无</div>}
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -1,13 +1,26 @@
-import { useState, useEffect } from "react";
+import { useState, useEffect, useRef } from "react";
 import { useStore } from "@/stores/app";
-import { imapSync, getEmailContent, deleteFile, sendEmail, readFile, writeFile, listDir, deleteEmail, markEmailRead, openExternalUrl } from "@/services/fs";
+import { connectGmail, findDuplicateEmails, getReaderView, saveReadingPosition, getAccountHealth, onMailAccountWarning, getOutbox, flushOutbox, onOutboxChanged, onNewMail, downloadAttachment, pickVaultFolder, pickFiles } from "@/services/tauri";
+import type { ReaderView, AccountHealth, QueuedOperation } from "@/services/tauri";
+import { imapSync, getEmailContent, loadRemoteContent, deleteFile, sendEmail, readFile, writeFile, listDir, deleteEmail, markEmailRead, archiveEmail, openExternalUrl, linkPeople } from "@/services/fs";
 import type { EmailMessage, SendEmailRequest } from "@/services/fs";
 import type { EmailAccount } from "@/types";
-import { HelpCircle, Send, ChevronDown, ChevronRight, Inbox, Mail, Star, Trash2, Archive, RefreshCw, Plus, X, MailOpen, Circle, Search, Loader2 } from "lucide-react";
+import { marked } from "marked";
+import { HelpCircle, Send, ChevronDown, ChevronRight, Inbox, Mail, Star, Trash2, Archive, RefreshCw, Plus, X, MailOpen, Circle, Search, Loader2, BookOpen, AlertTriangle, CloudOff, Paperclip } from "lucide-react";
 
 const EMAILS_DIR = ".lifeos/emails";
 const PAGE_SIZE = 20;
 
+// "INBOX=5, Archive=1440" ⇄ { INBOX: 5, Archive: 1440 }
+const parseSyncIntervals = (text: string): Record<string, number> =>
+  Object.fromEntries(
+    text.split(",").map((part) => part.split("=").map((s) => s.trim()))
+      .filter(([folder, minutes]) => folder && minutes !== undefined && !isNaN(Number(minutes)))
+      .map(([folder, minutes]) => [folder, Math.max(0, Math.round(Number(minutes)))])
+  );
+const formatSyncIntervals = (intervals?: Record<string, number>) =>
+  Object.entries(intervals || {}).map(([folder, minutes]) => `${folder}=${minutes}`).join(", ");
+
 // 常见邮箱配置帮助
 const EMAIL_PROVIDERS = {
   "163": { name: "163邮箱", imapHost: "imap.163.com", imapPort: "993", steps: ["登录 163 邮箱网页版", "设置 → POP3/SMTP/IMAP → 开启 IMAP/SMTP 服务", "设置 → 账户安全 → 开启客户端授权密码", "使用授权密码作为登录密码"] },
@@ -65,6 +78,7 @@ export default function MailView() {
   const [formUsername, setFormUsername] = useState("");
   const [formPassword, setFormPassword] = useState("");
   const [formFolders, setFormFolders] = useState("INBOX,Sent,Draft,Trash,Archive");
+  const [formSyncIntervals, setFormSyncIntervals] = useState("");
 
   // Pagination state
   const [hasMoreEmails, setHasMoreEmails] = useState(false);
@@ -82,14 +96,19 @@ export default function MailView() {
   const [composeBcc, setComposeBcc] = useState("");
   const [composeSubject, setComposeSubject] = useState("");
   const [composeBody, setComposeBody] = useState("");
+  const [composeAttachments, setComposeAttachments] = useState<string[]>([]);
   const [composing, setComposing] = useState(false);
 
   // Delete email state
   const [showDeleteConfirm, setShowDeleteConfirm] = useState(false);
   const [deleting, setDeleting] = useState(false);
+  const [readerOpen, setReaderOpen] = useState(false);
 
   // Search state
   const [searchQuery, setSearchQuery] = useState("");
+  // 同一封邮件发到多个地址时，只在第一个账户里显示
+  const [hideDuplicates, setHideDuplicates] = useState(false);
+  const [duplicateCopies, setDuplicateCopies] = useState<Set<string>>(new Set());
 
   // Forward state
   const [forwardMode, setForwardMode] = useState<"reply" | "forward" | null>(null);
@@ -125,6 +144,8 @@ export default function MailView() {
                 folders: data.folders ? data.folders.split(",") : [],
                 lastSync: data.lastSync,
                 enabled: data.enabled !== false,
+                syncMinutes: typeof data.syncMinutes === "number" ? data.syncMinutes : undefined,
+                syncIntervals: data.syncIntervals,
               });
             }
           } catch (e) {
@@ -155,7 +176,7 @@ export default function MailView() {
         const imapHost = selectedAccount.imapHost || "imap.example.com";
         const imapPort = selectedAccount.imapPort || 993;
         const password = selectedAccount.password || "";
-        if (!password) { setEmails([]); return; }
+        if (!password && selectedAccount.protocol !== "gmail") { setEmails([]); return; }
         const fetched = await imapSync(
           { email: selectedAccount.email, password, imapHost, imapPort, protocol: selectedAccount.protocol || "imap", account_id: selectedAccount.id },
           vaultPath, selectedFolder, PAGE_SIZE, 0
@@ -174,6 +195,65 @@ export default function MailView() {
     loadEmails();
   }, [selectedAccount?.id, selectedFolder, vaultPath]);
 
+  useEffect(() => {
+    if (!hideDuplicates || !vaultPath) {
+      setDuplicateCopies(new Set());
+      return;
+    }
+    findDuplicateEmails(vaultPath)
+      .then((groups) => setDuplicateCopies(new Set(groups.flatMap((g) => g.copies.slice(1).map((c) => `${c.account}/${c.emailId}`)))))
+      .catch((e) => console.error("Failed to find duplicate emails:", e));
+  }, [hideDuplicates, vaultPath, emails]);
+
+  // Mail pushed by the IDLE session, already in the cache: merge it into the open folder
+  useEffect(() => {
+    if (!selectedAccount) return;
+    const unlisten = onNewMail(({ accountId, folder, emails: arrived }) => {
+      if (accountId !== selectedAccount.id || folder !== selectedFolder) return;
+      setEmails(prev => {
+        const known = new Set(prev.map(e => e.id));
+        return [...arrived.filter(e => !known.has(e.id)), ...prev];
+      });
+    });
+    return () => { unlisten.then((fn) => fn()); };
+  }, [selectedAccount?.id, selectedFolder]);
+
+  // Sync health per account, refreshed after each sync and when one starts failing
+  const [accountHealth, setAccountHealth] = useState<Record<string, AccountHealth>>({});
+  useEffect(() => {
+    if (!vaultPath || syncing) return;
+    const load = () =>
+      getAccountHealth(vaultPath)
+        .then((list) => setAccountHealth(Object.fromEntries(list.map((h) => [h.accountId, h]))))
+        .catch(console.error);
+    load();
+    const unlisten = onMailAccountWarning(load);
+    return () => { unlisten.then((fn) => fn()); };
+  }, [vaultPath, syncing]);
+
+  // Operations queued while offline, replayed by the outbox loop
+  const [outbox, setOutbox] = useState<QueuedOperation[]>([]);
+  const [flushing, setFlushing] = useState(false);
+  useEffect(() => {
+    if (!vaultPath) return;
+    getOutbox(vaultPath).then(setOutbox).catch(console.error);
+    const unlisten = onOutboxChanged(setOutbox);
+    return () => { unlisten.then((fn) => fn()); };
+  }, [vaultPath]);
+
+  const handleFlushOutbox = async () => {
+    if (!vaultPath) return;
+    setFlushing(true);
+    try {
+      const report = await flushOutbox(vaultPath);
+      const pending = await getOutbox(vaultPath);
+      setOutbox(pending);
+      if (report.offline) alert("网络仍不可用，稍后会自动重试");
+      else if (report.failed > 0) alert(`${report.failed} 项操作失败: ${pending.find((q) => q.lastError)?.lastError ?? ""}`);
+    } catch (e) { alert("重试失败: " + e); }
+    finally { setFlushing(false); }
+  };
+
   const toggleAccountExpand = (accountId: string) => {
     setExpandedAccounts(prev => {
       const next = new Set(prev);
@@ -196,6 +276,7 @@ export default function MailView() {
     setFormImapPort("993"); setFormSmtpHost(""); setFormSmtpPort("587");
 
     setFormUsername(""); setFormPassword(""); setFormFolders("INBOX,Sent,Draft,Trash,Archive");
+    setFormSyncIntervals("");
   };
 
   const autoFillProvider = (email: string) => {
@@ -207,6 +288,17 @@ export default function MailView() {
     if (!formUsername && email) setFormUsername(email);
   };
 
+  // Gmail over its API: Google sign-in in the browser instead of an app password
+  const handleConnectGmail = async () => {
+    if (!vaultPath) return;
+    try {
+      await connectGmail(vaultPath);
+      setShowAccountForm(false);
+      resetForm();
+      await loadAccounts();
+    } catch (e) { alert("Gmail 连接失败: " + e); }
+  };
+
   const handleSaveAccount = async () => {
     if (!vaultPath || !formName.trim() || !formEmail.trim()) return;
     // 生成唯一的账户 ID
@@ -225,6 +317,7 @@ export default function MailView() {
       password: formPassword || "",
       authType: "password",
       folders: formFolders,
+      syncIntervals: parseSyncIntervals(formSyncIntervals),
       enabled: true
     };
     try {
@@ -253,7 +346,7 @@ export default function MailView() {
       const imapHost = account.imapHost || "imap.example.com";
       const imapPort = account.imapPort || 993;
       const password = account.password || "";
-      if (!password) { alert("请先在账户设置中填写密码"); setSyncing(false); return; }
+      if (!password && account.protocol !== "gmail") { alert("请先在账户设置中填写密码"); setSyncing(false); return; }
       const fetched = await imapSync(
         { email: account.email, password, imapHost, imapPort, protocol: account.protocol || "imap", account_id: account.id },
         vaultPath, folder, PAGE_SIZE, 0
@@ -262,6 +355,8 @@ export default function MailView() {
       setHasMoreEmails(fetched.length === PAGE_SIZE);
       setSelectedEmail(null);
       setEmailContent(null);
+      // 根据发件人更新联系人的 last_contacted
+      linkPeople(vaultPath).catch(console.error);
     } catch (e) {
       console.error("IMAP sync error:", e);
       alert("同步失败: " + e);
@@ -284,6 +379,8 @@ export default function MailView() {
       password: account.password || "",
       authType: account.authType || "password",
       folders: account.folders.join(","),
+      syncMinutes: account.syncMinutes,
+      syncIntervals: account.syncIntervals,
       enabled: !account.enabled,
       lastSync: account.lastSync || ""
     };
@@ -296,6 +393,7 @@ export default function MailView() {
     setFormImapHost(account.imapHost); setFormImapPort(String(account.imapPort));
     setFormSmtpHost(account.smtpHost || ""); setFormSmtpPort(String(account.smtpPort || 587));
     setFormUsername(account.username); setFormPassword(""); setFormFolders(account.folders.join(","));
+    setFormSyncIntervals(formatSyncIntervals(account.syncIntervals));
     setShowAccountForm(true);
   };
 
@@ -317,6 +415,8 @@ export default function MailView() {
       password,
       authType: "password",
       folders: formFolders,
+      syncMinutes: editingAccount.syncMinutes,
+      syncIntervals: parseSyncIntervals(formSyncIntervals),
       enabled: editingAccount.enabled
     };
     try {
@@ -335,7 +435,7 @@ export default function MailView() {
       const imapHost = selectedAccount.imapHost || "imap.example.com";
       const imapPort = selectedAccount.imapPort || 993;
       const password = selectedAccount.password || "";
-      if (!password) return;
+      if (!password && selectedAccount.protocol !== "gmail") return;
       const fetched = await imapSync(
         { email: selectedAccount.email, password, imapHost, imapPort, protocol: selectedAccount.protocol || "imap", account_id: selectedAccount.id },
         vaultPath, selectedFolder, PAGE_SIZE, emails.length
@@ -356,11 +456,14 @@ export default function MailView() {
         to: selectedEmail.from,
         subject: `Re: ${selectedEmail.subject}`,
         body: replyBody,
+        body_html: toHtml(replyBody),
         in_reply_to: selectedEmail.id,
+        vault_path: vaultPath || undefined,
+        account_id: selectedAccount.id,
       };
       console.log("[DEBUG] handleSendReply request:", JSON.stringify(request));
-      await sendEmail(request);
-      alert("回复发送成功！");
+      const queued = await sendEmail(request);
+      alert(queued ? "网络不可用，回复已加入待发送队列" : "回复发送成功！");
       setReplyBody(""); setShowReply(false);
     } catch (e) { alert("发送失败: " + e); }
     finally { setSending(false); }
@@ -381,13 +484,17 @@ export default function MailView() {
         to: composeTo,
         subject: composeSubject,
         body: composeBody,
+        body_html: toHtml(composeBody),
+        vault_path: vaultPath || undefined,
+        account_id: selectedAccount.id,
+        attachments: composeAttachments,
       };
       console.log("[DEBUG] handleSendCompose request:", JSON.stringify(request));
-      await sendEmail(request);
-      alert("邮件发送成功！");
+      const queued = await sendEmail(request);
+      alert(queued ? "网络不可用，邮件已加入待发送队列" : "邮件发送成功！");
       setShowCompose(false);
       setComposeTo(""); setComposeCc(""); setComposeBcc("");
-      setComposeSubject(""); setComposeBody("");
+      setComposeSubject(""); setComposeBody(""); setComposeAttachments([]);
       setForwardMode(null);
     } catch (e) { alert("发送失败: " + e); }
     finally { setComposing(false); }
@@ -429,6 +536,18 @@ ${originalContent}`;
     finally { setDeleting(false); }
   };
 
+  // Archive the open email, on the server too when the account allows it
+  const handleArchiveEmail = async () => {
+    if (!selectedAccount || !selectedEmail || !vaultPath) return;
+    try {
+      await archiveEmail(vaultPath, selectedAccount.id, selectedEmail.id, selectedEmail.folder, selectedAccount.imapHost, selectedAccount.imapPort, selectedAccount.password, selectedAccount.email);
+      setEmails(prev => prev.filter(e => e.id !== selectedEmail.id));
+      setSelectedEmail(null);
+      setEmailContent(null);
+      setReaderOpen(false);
+    } catch (e) { alert("归档失败: " + e); }
+  };
+
   // Handle mark email as read/unread
   const handleMarkAsRead = async (read: boolean) => {
     if (!selectedAccount || !selectedEmail || !vaultPath) return;
@@ -444,6 +563,7 @@ ${originalContent}`;
     setSelectedEmail(email);
     setEmailContent(null); // Clear previous content
     setShowReply(false);
+    setReaderOpen(false);
     setLoadingEmailContent(true);
 
     // Load full email content from file
@@ -469,6 +589,25 @@ ${originalContent}`;
     }
   };
 
+  // Re-fetch the open email with its remote images and styles
+  const handleLoadRemote = async () => {
+    if (!selectedEmail || !selectedAccount || !vaultPath) return;
+    try {
+      setEmailContent(await loadRemoteContent(vaultPath, selectedAccount.id, selectedEmail.id));
+    } catch (e) { console.error(e); }
+  };
+
+  const handleDownloadAttachment = async (index: number) => {
+    if (!selectedEmail || !selectedAccount || !vaultPath) return;
+    const dir = await pickVaultFolder();
+    if (!dir) return;
+    try {
+      await downloadAttachment(vaultPath, selectedAccount.id, selectedEmail.id, index, dir);
+    } catch (e) {
+      alert("保存附件失败: " + e);
+    }
+  };
+
   // Check if email is read
   const isEmailRead = (email: EmailMessage) => email.flags?.includes("Seen") ?? false;
 
@@ -508,6 +647,14 @@ ${originalContent}`;
                   {expandedAccounts.has(account.id) ? <ChevronDown size={14} /> : <ChevronRight size={14} />}
                   <span className="text-[16px]">📧</span>
                   <div className="flex-1 overflow-hidden text-ellipsis whitespace-nowrap text-[13px] font-medium">{account.name}</div>
+                  {(() => {
+                    const health = accountHealth[account.id];
+                    if (!health || (health.status !== "failing" && health.status !== "degraded")) return null;
+                    const tip = health.authError
+                      ? "服务器拒绝登录，请检查密码或授权码"
+                      : `连续 ${health.consecutiveFailures} 次同步失败${health.lastError ? `：${health.lastError}` : ""}`;
+                    return <span title={tip}><AlertTriangle size={13} className={health.status === "failing" ? "text-error" : "text-warning"} /></span>;
+                  })()}
                   <div className="w-1.5 h-1.5 rounded-full" style={{ background: account.enabled ? "var(--accent3)" : "var(--text-dim)" }} />
                 </div>
 
@@ -544,6 +691,17 @@ ${originalContent}`;
           <div>
             <div className="text-[14px] font-semibold">{selectedFolder}</div>
             <div className="text-[11px] text-text-dim">{selectedAccount?.email}</div>
+            {outbox.length > 0 && (
+              <button
+                className="flex items-center gap-1 text-[11px] text-warning hover:underline"
+                onClick={handleFlushOutbox}
+                disabled={flushing}
+                title="联网后自动发送，点击立即重试"
+              >
+                {flushing ? <Loader2 size={11} className="spin" /> : <CloudOff size={11} />}
+                {outbox.length} 项操作待发送
+              </button>
+            )}
           </div>
           <div className="flex items-center gap-1">
             <button className="btn btn-primary flex items-center gap-1" onClick={() => { if (!selectedAccount) { alert("请先选择一个邮箱账户"); return; } setShowCompose(true); }} style={{ fontSize: 11, padding: "4px 8px" }} disabled={!selectedAccount}>
@@ -573,6 +731,10 @@ ${originalContent}`;
                 </button>
               )}
             </div>
+            <label className="flex items-center gap-1.5 mt-1.5 text-[11px] text-text-dim cursor-pointer">
+              <input type="checkbox" checked={hideDuplicates} onChange={(e) => setHideDuplicates(e.target.checked)} />
+              隐藏其他账户已收到的重复邮件
+            </label>
           </div>
         )}
 
@@ -590,8 +752,11 @@ ${originalContent}`;
               <span className="text-[11px]">点击同步按钮收取邮件</span>
             </div>
           ) : (() => {
+            const visibleEmails = duplicateCopies.size
+              ? emails.filter(email => !duplicateCopies.has(`${selectedAccount.id}/${email.id}`))
+              : emails;
             const filteredEmails = searchQuery.trim()
-              ? emails.filter(email => {
+              ? visibleEmails.filter(email => {
                 const query = searchQuery.toLowerCase();
                 return (
                   (email.subject?.toLowerCase().includes(query)) ||
@@ -599,7 +764,7 @@ ${originalContent}`;
                   (email.to?.toLowerCase().includes(query))
                 );
               })
-              : emails;
+              : visibleEmails;
 
             if (searchQuery.trim() && filteredEmails.length === 0) {
               return (
@@ -671,11 +836,13 @@ ${originalContent}`;
             formUsername={formUsername} setFormUsername={setFormUsername}
             formPassword={formPassword} setFormPassword={setFormPassword}
             formFolders={formFolders} setFormFolders={setFormFolders}
+            formSyncIntervals={formSyncIntervals} setFormSyncIntervals={setFormSyncIntervals}
             showHelp={showHelp} setShowHelp={setShowHelp}
             editingAccount={editingAccount}
             onSave={editingAccount ? handleSaveEdit : handleSaveAccount}
             onCancel={() => { setShowAccountForm(false); setEditingAccount(null); resetForm(); }}
             autoFillProvider={autoFillProvider}
+            onConnectGmail={handleConnectGmail}
           />
         ) : selectedEmail ? (
           loadingEmailContent ? (
@@ -685,6 +852,14 @@ ${originalContent}`;
                 <div>加载邮件内容...</div>
               </div>
             </div>
+          ) : readerOpen && selectedAccount && vaultPath ? (
+            <ReaderPane
+              vaultPath={vaultPath}
+              accountId={selectedAccount.id}
+              emailId={selectedEmail.id}
+              onClose={() => setReaderOpen(false)}
+              onArchive={handleArchiveEmail}
+            />
           ) : (
             <EmailDetail
               email={emailContent || selectedEmail}
@@ -695,6 +870,9 @@ ${originalContent}`;
               onForward={handleForward}
               onDelete={() => setShowDeleteConfirm(true)}
               onMarkAsRead={handleMarkAsRead}
+              onLoadRemote={handleLoadRemote}
+              onOpenReader={() => setReaderOpen(true)}
+              onDownloadAttachment={handleDownloadAttachment}
               isRead={isEmailRead(selectedEmail)}
             />
           )
@@ -766,6 +944,18 @@ ${originalContent}`;
                 <div className="mt-2">
                   <textarea className="input w-full resize-vertical text-[13px]" value={composeBody} onChange={(e) => setComposeBody(e.target.value)} placeholder="邮件正文..." rows={12} style={{ minHeight: "200px" }} />
                 </div>
+                <div className="flex flex-wrap items-center gap-2">
+                  {composeAttachments.map((path) => (
+                    <span key={path} className="flex items-center gap-1 px-2 py-1 text-[12px] bg-panel2 rounded-[var(--radius-sm)]">
+                      <Paperclip size={12} /> {path.split(/[\\/]/).pop()}
+                      <button className="btn btn-ghost p-0" onClick={() => setComposeAttachments((prev) => prev.filter((p) => p !== path))}><X size={12} /></button>
+                    </span>
+                  ))}
+                  <button className="btn btn-ghost flex items-center gap-1 text-[12px]" onClick={async () => {
+                    const picked = await pickFiles(vaultPath || undefined);
+                    setComposeAttachments((prev) => [...prev, ...picked.filter((p) => !prev.includes(p))]);
+                  }}><Paperclip size={12} /> 添加附件</button>
+                </div>
               </div>
             </div>
             {/* Footer */}
@@ -806,7 +996,7 @@ ${originalContent}`;
 
 // ==================== 子组件 ====================
 
-function AccountForm({ formName, setFormName, formEmail, setFormEmail, formImapHost, setFormImapHost, formImapPort, setFormImapPort, formSmtpHost, setFormSmtpHost, formSmtpPort, setFormSmtpPort, formUsername, setFormUsername, formPassword, setFormPassword, formFolders, setFormFolders, showHelp, setShowHelp, editingAccount, onSave, onCancel, autoFillProvider }: any) {
+function AccountForm({ formName, setFormName, formEmail, setFormEmail, formImapHost, setFormImapHost, formImapPort, setFormImapPort, formSmtpHost, setFormSmtpHost, formSmtpPort, setFormSmtpPort, formUsername, setFormUsername, formPassword, setFormPassword, formFolders, setFormFolders, formSyncIntervals, setFormSyncIntervals, showHelp, setShowHelp, editingAccount, onSave, onCancel, autoFillProvider, onConnectGmail }: any) {
   return (
     <div className="p-6 overflow-auto max-w-[500px]">
       <div className="flex items-center justify-between mb-4">
@@ -830,6 +1020,9 @@ function AccountForm({ formName, setFormName, formEmail, setFormEmail, formImapH
         <div><label className="text-[12px] text-text-mid block mb-1">邮箱地址</label><input className="input w-full" value={formEmail} onChange={(e) => { setFormEmail(e.target.value); autoFillProvider(e.target.value); }} placeholder="you@example.com" /></div>
         <div><label className="text-[12px] text-text-mid block mb-1">协议</label>
           <div className="text-sm">IMAP (默认)</div>
+          {!editingAccount && (
+            <button className="btn btn-ghost mt-2" onClick={onConnectGmail} style={{ padding: "4px 8px", fontSize: 12 }}>Gmail：用 Google 登录（无需应用专用密码）</button>
+          )}
         </div>
         <div className="grid grid-cols-[2fr_1fr] gap-3">
           <div><label className="text-[12px] text-text-mid block mb-1">IMAP 服务器</label><input className="input w-full" value={formImapHost} onChange={(e) => setFormImapHost(e.target.value)} placeholder="imap.example.com" /></div>
@@ -842,6 +1035,7 @@ function AccountForm({ formName, setFormName, formEmail, setFormEmail, formImapH
         <div><label className="text-[12px] text-text-mid block mb-1">用户名</label><input className="input w-full" value={formUsername} onChange={(e) => setFormUsername(e.target.value)} placeholder="your@email.com" /></div>
         <div><label className="text-[12px] text-text-mid block mb-1">密码/应用专用密码</label><input className="input w-full" type="password" value={formPassword} onChange={(e) => setFormPassword(e.target.value)} placeholder="••••••••" /></div>
         <div><label className="text-[12px] text-text-mid block mb-1">文件夹（逗号分隔）</label><input className="input w-full" value={formFolders} onChange={(e) => setFormFolders(e.target.value)} placeholder="INBOX,Sent,Draft,Trash,Archive" /></div>
+        <div><label className="text-[12px] text-text-mid block mb-1">后台同步间隔（分钟，按文件夹；未填的用设置中的默认值，0 = 仅手动）</label><input className="input w-full" value={formSyncIntervals} onChange={(e) => setFormSyncIntervals(e.target.value)} placeholder="INBOX=5, Archive=1440" /></div>
         <div className="flex gap-2 mt-2">
           <button className="btn btn-primary" onClick={onSave}>保存</button>
           <button className="btn btn-ghost" onClick={onCancel}>取消</button>
@@ -851,7 +1045,12 @@ function AccountForm({ formName, setFormName, formEmail, setFormEmail, formImapH
   );
 }
 
-function EmailDetail({ email, showReply, setShowReply, replyBody, setReplyBody, sending, onSend, onForward, onDelete, onMarkAsRead, isRead }: { email: EmailMessage; showReply: boolean; setShowReply: (v: boolean) => void; replyBody: string; setReplyBody: (v: string) => void; sending: boolean; onSend: () => void; onForward?: () => void; onDelete?: () => void; onMarkAsRead?: (read: boolean) => void; isRead?: boolean }) {
+/** The body as written, rendered as Markdown for clients that show HTML */
+function toHtml(text: string): string {
+  return marked.parse(text, { breaks: true, async: false }) as string;
+}
+
+function EmailDetail({ email, showReply, setShowReply, replyBody, setReplyBody, sending, onSend, onForward, onDelete, onMarkAsRead, onLoadRemote, onOpenReader, onDownloadAttachment, isRead }: { email: EmailMessage; showReply: boolean; setShowReply: (v: boolean) => void; replyBody: string; setReplyBody: (v: string) => void; sending: boolean; onSend: () => void; onForward?: () => void; onDelete?: () => void; onMarkAsRead?: (read: boolean) => void; onLoadRemote?: () => void; onOpenReader?: () => void; onDownloadAttachment?: (index: number) => void; isRead?: boolean }) {
   // Handle external link clicks from iframe
   useEffect(() => {
     const handleMessage = (event: MessageEvent) => {
@@ -919,15 +1118,34 @@ function EmailDetail({ email, showReply, setShowReply, replyBody, setReplyBody,
             </div>
           </div>
           <div className="flex items-center gap-2">
+            {email.listId && onOpenReader && <button className="btn btn-ghost flex items-center gap-1 text-[12px]" onClick={onOpenReader} title={email.listId}><BookOpen size={14} /> 阅读模式</button>}
             {onForward && <button className="btn btn-ghost flex items-center gap-1 text-[12px]" onClick={onForward}><Send size={14} /> 转发</button>}
             {onMarkAsRead && <button className="btn btn-ghost flex items-center gap-1 text-[12px]" onClick={() => onMarkAsRead(!isRead)}>{isRead ? <Circle size={14} /> : <MailOpen size={14} />}{isRead ? "未读" : "已读"}</button>}
             {onDelete && <button className="btn btn-ghost flex items-center gap-1 text-[12px]" onClick={onDelete} style={{ color: "var(--accent4)" }}><Trash2 size={14} /> 删除</button>}
           </div>
         </div>
+        {!!email.attachments?.length && (
+          <div className="flex flex-wrap gap-2 mt-3">
+            {email.attachments.map((a, i) => (
+              <button key={i} className="btn btn-ghost flex items-center gap-1 text-[12px]" onClick={() => onDownloadAttachment?.(i)} title={a.contentType} disabled={!onDownloadAttachment}>
+                <Paperclip size={12} /> {a.name} <span className="text-text-dim">({a.size < 1024 * 1024 ? `${Math.max(1, Math.round(a.size / 1024))} KB` : `${(a.size / 1024 / 1024).toFixed(1)} MB`})</span>
+              </button>
+            ))}
+          </div>
+        )}
       </div>
 
       {/* 内容 */}
       <div className="flex-1 overflow-auto p-5">
+        {email.bodyHtml && !!email.remoteBlocked && onLoadRemote && (
+          <div className="flex items-center justify-between mb-3 px-3 py-2 text-[12px] text-text-dim bg-panel2 rounded-[var(--radius-sm)]">
+            <span>
+              已拦截 {email.remoteBlocked} 处远程图片，以防发件人追踪打开情况
+              {!!email.trackers?.length && `（其中检测到 ${email.trackers.length} 个追踪器）`}
+            </span>
+            <button className="btn btn-ghost text-[12px]" onClick={onLoadRemote}>显示远程内容</button>
+          </div>
+        )}
         {email.bodyHtml ? (
           <iframe
             srcDoc={getWrappedHtml(email.bodyHtml)}
@@ -960,3 +1178,93 @@ function EmailDetail({ email, showReply, setShowReply, replyBody, setReplyBody,
     </div>
   );
 }
+
+// Newsletter reader: the message reduced to its article, resuming where the
+// last read stopped. Position is saved as a fraction of the scroll height.
+function ReaderPane({ vaultPath, accountId, emailId, onClose, onArchive }: { vaultPath: string; accountId: string; emailId: string; onClose: () => void; onArchive: () => void }) {
+  const [view, setView] = useState<ReaderView | null>(null);
+  const [error, setError] = useState("");
+  const [showMarkdown, setShowMarkdown] = useState(false);
+  const [allowRemote, setAllowRemote] = useState(false);
+  const scrollRef = useRef<HTMLDivElement>(null);
+  const saveTimer = useRef<ReturnType<typeof setTimeout>>();
+
+  useEffect(() => {
+    setError("");
+    getReaderView(vaultPath, accountId, emailId, allowRemote)
+      .then(setView)
+      .catch((e) => setError(String(e)));
+  }, [vaultPath, accountId, emailId, allowRemote]);
+
+  // Resume once the article has laid out
+  useEffect(() => {
+    const el = scrollRef.current;
+    if (!view || !el) return;
+    requestAnimationFrame(() => { el.scrollTop = view.progress.position * (el.scrollHeight - el.clientHeight); });
+  }, [view, showMarkdown]);
+
+  useEffect(() => () => clearTimeout(saveTimer.current), []);
+
+  const handleScroll = () => {
+    const el = scrollRef.current;
+    if (!el) return;
+    const position = el.scrollHeight > el.clientHeight ? el.scrollTop / (el.scrollHeight - el.clientHeight) : 1;
+    clearTimeout(saveTimer.current);
+    saveTimer.current = setTimeout(() => {
+      saveReadingPosition(vaultPath, accountId, emailId, position, position >= 0.98 ? true : undefined).catch(console.error);
+    }, 800);
+  };
+
+  const handleFinish = async () => {
+    clearTimeout(saveTimer.current);
+    await saveReadingPosition(vaultPath, accountId, emailId, 1, true).catch(console.error);
+    onArchive();
+  };
+
+  // Links open in the browser, never inside the app
+  const handleClick = (e: React.MouseEvent) => {
+    const link = (e.target as HTMLElement).closest("a");
+    if (!link) return;
+    e.preventDefault();
+    const href = link.getAttribute("href");
+    if (href && /^https?:/.test(href)) openExternalUrl(href).catch(console.error);
+  };
+
+  if (error) return <div className="flex-1 flex items-center justify-center text-text-dim text-[13px]">{error}</div>;
+  if (!view) {
+    return (
+      <div className="flex-1 flex items-center justify-center text-text-dim">
+        <Loader2 size={24} className="animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="flex-1 flex flex-col overflow-hidden">
+      <div className="px-5 py-3 border-b border-border bg-panel flex items-center gap-2">
+        <button className="btn btn-ghost p-1" onClick={onClose} title="返回邮件"><X size={16} /></button>
+        <div className="flex-1 min-w-0 text-[12px] text-text-dim truncate">
+          {view.from} · 约 {view.minutes} 分钟
+          {view.progress.finished && " · 已读完"}
+        </div>
+        {view.remoteBlocked > 0 && !allowRemote && <button className="btn btn-ghost text-[12px]" onClick={() => setAllowRemote(true)}>显示图片（{view.remoteBlocked}）</button>}
+        <button className="btn btn-ghost text-[12px]" onClick={() => setShowMarkdown((v) => !v)}>{showMarkdown ? "排版" : "Markdown"}</button>
+        <button className="btn btn-primary flex items-center gap-1 text-[12px]" onClick={handleFinish}><Archive size={14} /> 读完并归档</button>
+      </div>
+      <div ref={scrollRef} onScroll={handleScroll} className="flex-1 overflow-auto">
+        <div className="max-w-[680px] mx-auto px-6 py-8">
+          <h1 className="text-[22px] font-semibold leading-snug mb-6">{view.subject || "(无主题)"}</h1>
+          {showMarkdown ? (
+            <pre className="text-[13px] leading-[1.7] whitespace-pre-wrap text-text font-[var(--font-mono)]">{view.markdown}</pre>
+          ) : (
+            <div
+              className="text-[15px] text-text leading-[1.8] [&_p]:mb-4 [&_h1]:text-[20px] [&_h2]:text-[18px] [&_h3]:text-[16px] [&_h1,&_h2,&_h3]:font-semibold [&_h1,&_h2,&_h3]:mt-6 [&_h1,&_h2,&_h3]:mb-3 [&_ul]:list-disc [&_ol]:list-decimal [&_ul,&_ol]:pl-6 [&_ul,&_ol]:mb-4 [&_blockquote]:border-l-2 [&_blockquote]:border-border [&_blockquote]:pl-4 [&_blockquote]:text-text-mid [&_img]:max-w-full [&_img]:h-auto [&_img]:my-4 [&_a]:text-accent [&_a]:underline [&_pre]:bg-panel2 [&_pre]:p-3 [&_pre]:overflow-auto"
+              onClick={handleClick}
+              dangerouslySetInnerHTML={{ __html: view.html }}
+            />
+          )}
+        </div>
+      </div>
+    </div>
+  );
+}
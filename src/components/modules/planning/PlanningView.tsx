@@ -1,6 +1,7 @@
 import { useState } from "react";
 import { useStore } from "@/stores/app";
 import { writeNote, readNote, deleteFile } from "@/services/fs";
+import { writeErrorMessage } from "@/services/tauri";
 import type { Goal, GoalType, Priority } from "@/types";
 
 const TYPE_LABELS: Record<GoalType, string> = { annual: "年度目标", quarterly: "季度目标", monthly: "月度目标" };
@@ -106,7 +107,7 @@ ${newGoalTitle.trim()}
       resetForm();
     } catch (err) {
       console.error("Failed to create goal:", err);
-      alert("创建目标失败: " + err);
+      alert("创建目标失败: " + writeErrorMessage(err));
     } finally {
       setCreating(false);
     }
@@ -151,6 +152,7 @@ ${newGoalTitle.trim()}
       setEditingGoal(null);
     } catch (err) {
       console.error("Failed to save goal:", err);
+      alert("保存目标失败: " + writeErrorMessage(err));
     }
   };
 
@@ -50,7 +50,6 @@ const EMPTY_TASK: NewTask = {
 export default function SchedulerView() {
   const scheduledTasks = useStore((s) => s.scheduledTasks);
   const setScheduledTasks = useStore((s) => s.setScheduledTasks);
-  const vaultPath = useStore((s) => s.vaultPath);
 
   const [showNew, setShowNew] = useState(false);
   const [form, setForm] = useState<NewTask>({ ...EMPTY_TASK });
@@ -155,10 +154,16 @@ export default function SchedulerView() {
   const presets = [
     {
       label: "每日备份 Vault",
-      program: "/bin/sh",
-      args: ["-c", `cp -r ${vaultPath ?? "~/vault"} ${vaultPath ?? "~/vault"}_backup`],
+      program: "internal",
+      args: ["backup"],
       interval: 86400,
     },
+    {
+      label: "同步邮件",
+      program: "internal",
+      args: ["mail-sync"],
+      interval: 900,
+    },
     {
       label: "每小时提醒",
       program: "/usr/bin/osascript",
@@ -249,7 +254,9 @@ export default function SchedulerView() {
                 <div
                   className="text-[13px] text-text-mid font-[var(--font-mono)] mb-[6px] break-all"
                 >
-                  {task.program} {task.args.join(" ")}
+                  {task.program === "internal"
+                    ? `LifeOS · ${task.args.join(" ")}`
+                    : `${task.program} ${task.args.join(" ")}`}
                 </div>
                 <div className="text-[13px] text-text-mid">
                   {formatInterval(task.interval_seconds)}
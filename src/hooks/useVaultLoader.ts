@@ -2,6 +2,8 @@ import { useCallback } from "react";
 import { useStore } from "@/stores/app";
 import * as fs from "@/services/fs";
 import * as parser from "@/services/parser";
+import { fillWeather } from "@/services/tauri";
+import { isTauri } from "@/services/env";
 import { format } from "date-fns";
 import type { HabitStore, DayNote, Project, DiaryEntry, Decision, Goal, FinancePerson, FinanceRecord, FinanceSubItem, Subscription } from "@/types";
 
@@ -58,6 +60,8 @@ async function loadToday(
       ? (await fs.readFile(templatePath)).replace("{{date}}", today).replace("{{content}}", "")
       : `---\ndate: ${today}\nenergy: high\nmood: 😊\n---\n\n## 今日任务\n\n- [ ] \n\n## 今日笔记\n\n`;
     await fs.writeFile(path, content);
+    // Not awaited: loading must not wait on the weather service
+    if (isTauri()) fillWeather(vault, path).catch(console.error);
   }
 
   const note = await fs.readNote(path);
@@ -84,7 +88,7 @@ async function loadDiary(
   const entries = notes
     .filter((n) => n.filename.match(/^\d{4}-\d{2}-\d{2}(-\d{4})?\.md$/))
     .map((n) =>
-      parser.parseDiaryEntry(n.path, n.frontmatter, n.content, n.modified)
+      parser.parseDiaryEntry(n.path, n.frontmatter, n.content, n.modified, n.hash)
     );
   setDiaryEntries(entries);
 }
@@ -48,12 +48,18 @@ export const loadAppSettings = (vaultPath: string): Promise<string> =>
 export const saveAppSettings = (vaultPath: string, content: string): Promise<void> =>
   isTauri() ? tauri.saveAppSettings(vaultPath, content) : webFs.saveAppSettings(vaultPath, content);
 
+// Web mode has no backend messages to localise
+export const setLocale = (locale: string): Promise<void> =>
+  isTauri() ? tauri.setLocale(locale) : Promise.resolve();
+
 // Generic FS
 export const readFile = (path: string): Promise<string> =>
   isTauri() ? tauri.readFile(path) : webFs.readFile(path);
 
-export const writeFile = (path: string, content: string): Promise<void> =>
-  isTauri() ? tauri.writeFile(path, content) : webFs.writeFile(path, content);
+// `expected` guards against overwriting a newer version; desktop only, as is
+// the version written that it resolves to
+export const writeFile = (path: string, content: string, expected?: Partial<tauri.FileVersion>): Promise<tauri.FileVersion | void> =>
+  isTauri() ? tauri.writeFile(path, content, expected) : webFs.writeFile(path, content);
 
 export const deleteFile = (path: string): Promise<void> =>
   isTauri() ? tauri.deleteFile(path) : webFs.deleteFile(path);
@@ -65,7 +71,7 @@ export const createDirAll = (path: string): Promise<void> =>
   isTauri() ? tauri.createDirAll(path) : webFs.createDirAll(path);
 
 export const moveFile = (src: string, dest: string): Promise<void> =>
-  isTauri() ? tauri.moveFile(src, dest) : webFs.moveFile(src, dest);
+  isTauri() ? tauri.moveFile(src, dest).then(() => undefined) : webFs.moveFile(src, dest);
 
 export const listDir = (
   path: string,
@@ -82,10 +88,11 @@ export const readNote = (path: string): Promise<NoteFile> =>
 export const writeNote = (
   path: string,
   frontmatter: Record<string, unknown>,
-  content: string
-): Promise<void> =>
+  content: string,
+  expected?: Partial<tauri.FileVersion>
+): Promise<tauri.FileVersion | void> =>
   isTauri()
-    ? tauri.writeNote(path, frontmatter, content)
+    ? tauri.writeNote(path, frontmatter, content, expected)
     : webFs.writeNote(path, frontmatter, content);
 
 export const listNotes = (
@@ -98,6 +105,9 @@ export const listNotes = (
 export const openInFinder = (path: string): Promise<void> =>
   tauri.openInFinder(path);
 
+export const openInEditor = (vaultPath: string, path: string, opts?: { line?: number; editor?: string }): Promise<string> =>
+  tauri.openInEditor(vaultPath, path, opts);
+
 export const runShellCommand = (command: string, args: string[]): Promise<string> =>
   tauri.runShellCommand(command, args);
 
@@ -127,6 +137,8 @@ export const listLaunchdTasks = () =>
 export const deleteLaunchdTask = (id: string) =>
   tauri.deleteLaunchdTask(id);
 
+export const listInternalJobs = () => tauri.listInternalJobs();
+
 // Apple Notes (Tauri only)
 export const getAppleNotes = (
   query?: string,
@@ -162,8 +174,11 @@ export const imapSync = (
 export const getCachedEmails = (vaultPath: string, accountId: string, offset?: number, limit?: number) =>
   tauri.getCachedEmails(vaultPath, accountId, offset, limit);
 
-export const getEmailContent = (vaultPath: string, accountId: string, emailId: string) =>
-  tauri.getEmailContent(vaultPath, accountId, emailId);
+export const getEmailContent = (vaultPath: string, accountId: string, emailId: string, unwrapLinks?: boolean) =>
+  tauri.getEmailContent(vaultPath, accountId, emailId, unwrapLinks);
+
+export const loadRemoteContent = (vaultPath: string, accountId: string, emailId: string, unwrapLinks?: boolean) =>
+  tauri.loadRemoteContent(vaultPath, accountId, emailId, unwrapLinks);
 
 export const listEmailFolders = (vaultPath: string) =>
   tauri.listEmailFolders(vaultPath);
@@ -196,5 +211,21 @@ export const markEmailRead = (
 ) =>
   tauri.markEmailRead(vaultPath, accountId, emailId, read, folder, imapHost, imapPort, imapPassword, email);
 
+export const archiveEmail = (
+  vaultPath: string,
+  accountId: string,
+  emailId: string,
+  folder?: string,
+  imapHost?: string,
+  imapPort?: number,
+  imapPassword?: string,
+  email?: string
+) =>
+  tauri.archiveEmail(vaultPath, accountId, emailId, folder, imapHost, imapPort, imapPassword, email);
+
 export const openExternalUrl = (url: string): Promise<void> =>
   tauri.openExternalUrl(url);
+
+// People (Tauri only)
+export const linkPeople = (vaultPath: string): Promise<string[]> =>
+  tauri.linkPeople(vaultPath);
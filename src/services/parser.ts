@@ -112,6 +112,7 @@ export function parseProject(
     tags: fm.tags ? fm.tags.split(",").map((t) => t.trim()) : [],
     progress: parseInt(fm.progress ?? "0"),
     github: fm.github,
+    repo: fm.repo,
     content,
   };
 }
@@ -124,7 +125,8 @@ export function parseDiaryEntry(
   path: string,
   fm: Record<string, string>,
   content: string,
-  modified: string
+  modified: string,
+  hash?: string
 ): DiaryEntry {
   const dateFromPath = path.match(/(\d{4}-\d{2}-\d{2})/)?.[1] ?? "";
   // Prefer frontmatter title, fallback to content heading, then date
@@ -139,6 +141,7 @@ export function parseDiaryEntry(
     tags: fm.tags ? fm.tags.split(",").map((t) => t.trim()) : [],
     content,
     modified,
+    hash,
   };
 }
 
@@ -1,6 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { open } from "@tauri-apps/plugin-dialog";
-import type { NoteFile, GitRepo, Skill, SkillPath, ScheduledTask } from "@/types";
+import type { NoteFile, GitRepo, Skill, SkillPath, ScheduledTask, Subscription } from "@/types";
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Vault
@@ -12,35 +13,2102 @@ export const getVaultPath = (): Promise<string | null> =>
 export const setVaultPath = (path: string): Promise<void> =>
   invoke("set_vault_path", { path });
 
+export const getDefaultVaultPath = (): Promise<string> =>
+  invoke("get_default_vault_path");
+
 export const initVault = (path: string): Promise<void> =>
   invoke("init_vault", { path });
 
-export const loadMenuConfig = (vaultPath: string): Promise<string> =>
-  invoke("load_menu_config", { vaultPath });
+export const loadMenuConfig = (vaultPath: string): Promise<string> =>
+  invoke("load_menu_config", { vaultPath });
+
+export const saveMenuConfig = (vaultPath: string, content: string): Promise<void> =>
+  invoke("save_menu_config", { vaultPath, content });
+
+export const loadBoardConfig = (vaultPath: string): Promise<string> =>
+  invoke("load_board_config", { vaultPath });
+
+export const saveBoardConfig = (vaultPath: string, content: string): Promise<void> =>
+  invoke("save_board_config", { vaultPath, content });
+
+export const regenerateSkills = (vaultPath: string): Promise<void> =>
+  invoke("regenerate_skills", { vaultPath });
+
+export const loadAppSettings = (vaultPath: string): Promise<string> =>
+  invoke("load_app_settings", { vaultPath });
+
+export const saveAppSettings = (vaultPath: string, content: string): Promise<void> =>
+  invoke("save_app_settings", { vaultPath, content });
+
+export const getLocale = (): Promise<string> => invoke("get_locale");
+
+export const setLocale = (locale: string): Promise<void> =>
+  invoke("set_locale", { locale });
+
+// ── Config hot-reload ────────────────────────────────────────────────────────
+
+export type ConfigKind = "settings" | "menu" | "board" | "connectors";
+
+export interface ConfigStatus {
+  kind: ConfigKind;
+  path: string;
+  exists: boolean;
+  valid: boolean;
+  error: string | null;
+}
+
+export const startConfigWatch = (vaultPath: string): Promise<void> =>
+  invoke("start_config_watch", { vaultPath });
+
+export const stopConfigWatch = (): Promise<void> =>
+  invoke("stop_config_watch");
+
+export const validateConfigs = (vaultPath: string): Promise<ConfigStatus[]> =>
+  invoke("validate_configs", { vaultPath });
+
+/** Fires when a .lifeos/*.yaml file is edited outside the app (or by it) */
+export const onConfigChanged = (cb: (status: ConfigStatus) => void): Promise<UnlistenFn> =>
+  listen<ConfigStatus>("config-changed", (e) => cb(e.payload));
+
+// ── People ───────────────────────────────────────────────────────────────────
+
+/** people/<slug>.md; unknown frontmatter keys are passed through as-is */
+export interface Person {
+  slug: string; // "" to create from name
+  path: string;
+  name: string;
+  emails: string[];
+  birthday?: string; // YYYY-MM-DD or MM-DD
+  company?: string;
+  tags: string[];
+  last_contacted?: string;
+  notes: string;
+  [key: string]: unknown;
+}
+
+export interface PersonTimelineItem {
+  kind: "email" | "note" | "meeting" | "task";
+  date: string;
+  title: string;
+  path: string;
+}
+
+export const listPeople = (vaultPath: string): Promise<Person[]> =>
+  invoke("list_people", { vaultPath });
+
+export const getPerson = (vaultPath: string, slug: string): Promise<Person> =>
+  invoke("get_person", { vaultPath, slug });
+
+export const savePerson = (vaultPath: string, person: Person): Promise<Person> =>
+  invoke("save_person", { vaultPath, person });
+
+export const deletePerson = (vaultPath: string, slug: string): Promise<void> =>
+  invoke("delete_person", { vaultPath, slug });
+
+export const findPersonByEmail = (vaultPath: string, email: string): Promise<Person | null> =>
+  invoke("find_person_by_email", { vaultPath, email });
+
+/** Refresh last_contacted from cached mail and meeting notes; resolves to changed slugs */
+export const linkPeople = (vaultPath: string): Promise<string[]> =>
+  invoke("link_people", { vaultPath });
+
+export const getPersonTimeline = (vaultPath: string, slug: string, limit?: number): Promise<PersonTimelineItem[]> =>
+  invoke("get_person_timeline", { vaultPath, slug, limit });
+
+/** Slugs touched by a CardDAV sync, per outcome */
+export interface ContactSyncReport {
+  finished: string; // RFC 3339
+  created: string[];
+  merged: string[]; // new cards joined to an existing person
+  updated: string[];
+  pushed: string[];
+  conflicts: string[]; // changed on both sides, server won
+  unlinked: string[]; // card deleted on the server, note kept
+  errors: string[];
+}
+
+/** Two-way sync with the address book under carddav: in connectors.yaml */
+export const syncContacts = (vaultPath: string): Promise<ContactSyncReport> =>
+  invoke("sync_contacts", { vaultPath });
+
+export const getContactSyncStatus = (vaultPath: string): Promise<ContactSyncReport | null> =>
+  invoke("get_contact_sync_status", { vaultPath });
+
+// ── Birthdays & anniversaries ────────────────────────────────────────────────
+
+export interface Occasion {
+  kind: "birthday" | "anniversary";
+  name: string;
+  path: string;
+  date: string; // next occurrence, YYYY-MM-DD
+  days_until: number;
+  lunar: boolean;
+  years: number | null;
+  source: string;
+}
+
+export const getUpcomingOccasions = (vaultPath: string, days?: number): Promise<Occasion[]> =>
+  invoke("get_upcoming_occasions", { vaultPath, days });
+
+export const startOccasionReminders = (vaultPath: string): Promise<void> =>
+  invoke("start_occasion_reminders", { vaultPath });
+
+export const stopOccasionReminders = (): Promise<void> =>
+  invoke("stop_occasion_reminders");
+
+// ── Finance ──────────────────────────────────────────────────────────────────
+
+export interface Transaction {
+  id: string;
+  date: string; // YYYY-MM-DD
+  amount: number; // negative = money out
+  currency: string;
+  description: string;
+  category: string;
+  account: string;
+}
+
+export interface ImportReport {
+  imported: number;
+  duplicates: number;
+  skipped: number;
+  months: string[];
+  dry_run: boolean; // counted only, nothing written
+}
+
+export interface MonthSummary {
+  month: string; // YYYY-MM
+  income: number;
+  expense: number;
+  net: number;
+  count: number;
+  by_category: Record<string, number>;
+}
+
+/** Import a bank CSV / OFX / QIF export into life/finance/transactions */
+export const importTransactions = (
+  vaultPath: string,
+  filePath: string,
+  account?: string,
+  format?: "csv" | "ofx" | "qif",
+  dryRun = false
+): Promise<ImportReport> =>
+  invoke("import_transactions", { vaultPath, filePath, account, format, dryRun });
+
+export const listTransactions = (vaultPath: string, month: string): Promise<Transaction[]> =>
+  invoke("list_transactions", { vaultPath, month });
+
+export const setTransactionCategory = (vaultPath: string, month: string, id: string, category: string): Promise<void> =>
+  invoke("set_transaction_category", { vaultPath, month, id, category });
+
+/** Re-apply life/finance/rules.yaml; resolves to the number of changed transactions */
+/** How many transactions changed, or with `dryRun` would change */
+export const applyCategoryRules = (vaultPath: string, overwrite = false, dryRun = false): Promise<number> =>
+  invoke("apply_category_rules", { vaultPath, overwrite, dryRun });
+
+export const getSpendingSummary = (vaultPath: string, months?: number): Promise<MonthSummary[]> =>
+  invoke("get_spending_summary", { vaultPath, months });
+
+// ── Subscriptions ────────────────────────────────────────────────────────────
+
+/** Subscription as returned by the backend, with renewal rolled forward */
+export interface SubscriptionStatus extends Omit<Subscription, "cycle"> {
+  cycle: Subscription["cycle"] | "quarterly";
+  remindDays?: number | null;
+  nextRenewal: string | null; // YYYY-MM-DD
+  daysUntil: number | null;
+  monthlyAmount: number;
+}
+
+export interface SubscriptionBurn {
+  monthly: Record<string, number>; // per currency
+  yearly: Record<string, number>;
+  active: number;
+  /** Monthly burn of subscriptions that existed before `since` */
+  monthly_before: Record<string, number>;
+  added: SubscriptionStatus[];
+  renewing: SubscriptionStatus[];
+}
+
+export const listSubscriptions = (vaultPath: string): Promise<SubscriptionStatus[]> =>
+  invoke("list_subscriptions", { vaultPath });
+
+export const saveSubscription = (
+  vaultPath: string,
+  subscription: Partial<SubscriptionStatus> & Pick<Subscription, "name" | "amount">
+): Promise<SubscriptionStatus> =>
+  invoke("save_subscription", { vaultPath, subscription });
+
+export const deleteSubscription = (vaultPath: string, id: string): Promise<void> =>
+  invoke("delete_subscription", { vaultPath, id });
+
+/** Burn and creep since `since` (YYYY-MM-DD, default 30 days ago) */
+export const getSubscriptionBurn = (vaultPath: string, since?: string): Promise<SubscriptionBurn> =>
+  invoke("get_subscription_burn", { vaultPath, since });
+
+export const startRenewalReminders = (vaultPath: string): Promise<void> =>
+  invoke("start_renewal_reminders", { vaultPath });
+
+export const stopRenewalReminders = (): Promise<void> =>
+  invoke("stop_renewal_reminders");
+
+// ── Books ────────────────────────────────────────────────────────────────────
+
+export type BookStatus = "want" | "reading" | "finished" | "abandoned";
+
+/** life/books/<slug>.md */
+export interface Book {
+  slug: string;
+  path: string;
+  title: string;
+  authors: string[];
+  isbn?: string;
+  status: BookStatus;
+  pages?: number;
+  progress?: number; // current page
+  started?: string;
+  finished?: string;
+  rating?: number; // 1–5
+  publisher?: string;
+  published?: string;
+  cover?: string;
+  file?: string; // vault-relative EPUB or PDF read in the app
+  position?: string; // where the reader left off in `file` (EPUB CFI, PDF page)
+  percent?: number; // 0–100
+  tags: string[];
+  highlights: { text: string; page: number | null; location: string | null; date: string | null }[];
+  notes: string;
+  [extra: string]: unknown;
+}
+
+export interface ReadingStats {
+  year: number;
+  finished: number;
+  pages: number;
+  by_month: number[]; // 12 entries, January first
+  average_rating: number | null;
+  reading: Book[];
+  finished_books: Book[];
+}
+
+export const listBooks = (vaultPath: string, status?: BookStatus): Promise<Book[]> =>
+  invoke("list_books", { vaultPath, status });
+
+export const getBook = (vaultPath: string, slug: string): Promise<Book> =>
+  invoke("get_book", { vaultPath, slug });
+
+/** Leave `slug` empty to create; resolves with the stored book */
+export const saveBook = (vaultPath: string, book: Partial<Book> & { title: string }): Promise<Book> =>
+  invoke("save_book", { vaultPath, book: { slug: "", authors: [], tags: [], highlights: [], notes: "", ...book } });
+
+export const deleteBook = (vaultPath: string, slug: string): Promise<void> =>
+  invoke("delete_book", { vaultPath, slug });
+
+/** Open Library metadata for an ISBN */
+export const lookupIsbn = (isbn: string): Promise<Omit<Book, "slug" | "path" | "highlights" | "notes">> =>
+  invoke("lookup_isbn", { isbn });
+
+export const addBookByIsbn = (vaultPath: string, isbn: string): Promise<Book> =>
+  invoke("add_book_by_isbn", { vaultPath, isbn });
+
+export const updateBookProgress = (vaultPath: string, slug: string, page: number): Promise<Book> =>
+  invoke("update_book_progress", { vaultPath, slug, page });
+
+export const addBookHighlight = (vaultPath: string, slug: string, text: string, page?: number, location?: string): Promise<Book> =>
+  invoke("add_book_highlight", { vaultPath, slug, text, page, location });
+
+/** Track an EPUB/PDF in the vault as a book; reuses the note with that file or title */
+export const registerReadingFile = (vaultPath: string, path: string): Promise<Book> =>
+  invoke("register_reading_file", { vaultPath, path });
+
+/** `fraction` 0–1 through the file; `page` when it has pages. Updates status like updateBookProgress */
+export const saveBookPosition = (vaultPath: string, slug: string, position: string, fraction: number, page?: number): Promise<Book> =>
+  invoke("save_book_position", { vaultPath, slug, position, fraction, page });
+
+export interface HighlightImportReport {
+  imported: number;
+  existing: number; // already filed, possibly re-wrapped
+  skipped: number; // bookmarks, notes, empty entries
+  created: string[]; // slugs of new book notes
+  updated: string[];
+  dry_run: boolean;
+}
+
+/** Kindle "My Clippings.txt", text copied from Apple Books, or a CSV of Apple Books annotations */
+export const importHighlights = (vaultPath: string, filePath: string, dryRun = false): Promise<HighlightImportReport> =>
+  invoke("import_highlights", { vaultPath, filePath, dryRun });
+
+export const getReadingStats = (vaultPath: string, year?: number): Promise<ReadingStats> =>
+  invoke("get_reading_stats", { vaultPath, year });
+
+// ── Watchlist ────────────────────────────────────────────────────────────────
+
+export type MediaKind = "movie" | "series";
+export type MediaStatus = "want" | "watching" | "watched" | "dropped";
+
+/** life/media/<slug>.md frontmatter */
+export interface MediaMeta {
+  title: string;
+  kind: MediaKind;
+  status: MediaStatus;
+  year?: number;
+  tmdb_id?: number;
+  original_title?: string;
+  overview?: string;
+  poster?: string;
+  genres: string[];
+  tmdb_rating?: number; // 0–10
+  rating?: number; // own, 1–5
+  season?: number;
+  episode?: number;
+  seasons?: number;
+  watched?: string;
+  tags: string[];
+  [extra: string]: unknown;
+}
+
+export interface MediaItem extends MediaMeta {
+  slug: string;
+  path: string;
+  notes: string;
+}
+
+export const listMedia = (vaultPath: string, status?: MediaStatus, kind?: MediaKind): Promise<MediaItem[]> =>
+  invoke("list_media", { vaultPath, status, kind });
+
+export const getMedia = (vaultPath: string, slug: string): Promise<MediaItem> =>
+  invoke("get_media", { vaultPath, slug });
+
+export const saveMedia = (vaultPath: string, item: Partial<MediaItem> & { title: string }): Promise<MediaItem> =>
+  invoke("save_media", { vaultPath, item: { slug: "", genres: [], tags: [], notes: "", ...item } });
+
+export const deleteMedia = (vaultPath: string, slug: string): Promise<void> =>
+  invoke("delete_media", { vaultPath, slug });
+
+/** Needs tmdb.api_key in connectors.yaml */
+export const searchTmdb = (vaultPath: string, query: string): Promise<MediaMeta[]> =>
+  invoke("search_tmdb", { vaultPath, query });
+
+export const addMediaFromTmdb = (vaultPath: string, tmdbId: number, kind: MediaKind): Promise<MediaItem> =>
+  invoke("add_media_from_tmdb", { vaultPath, tmdbId, kind });
+
+/** Optional rating (1–5) and a note appended under today's date */
+export const setMediaStatus = (
+  vaultPath: string,
+  slug: string,
+  status: MediaStatus,
+  rating?: number,
+  note?: string
+): Promise<MediaItem> =>
+  invoke("set_media_status", { vaultPath, slug, status, rating, note });
+
+export const updateMediaProgress = (vaultPath: string, slug: string, season: number, episode: number): Promise<MediaItem> =>
+  invoke("update_media_progress", { vaultPath, slug, season, episode });
+
+// ── Nutrition ────────────────────────────────────────────────────────────────
+
+export type MealSlot = "breakfast" | "lunch" | "dinner" | "snack";
+
+export interface Meal {
+  time: string; // HH:MM
+  meal: MealSlot;
+  text: string;
+  calories?: number;
+  estimated?: boolean; // calories from the AI estimate
+}
+
+export interface NutritionDay {
+  date: string;
+  meals: Meal[];
+}
+
+export interface NutritionWeek {
+  start: string; // Monday
+  end: string;
+  days: { date: string; meals: number; calories: number; unknown: number }[];
+  total_calories: number;
+  average_calories: number;
+  by_meal: Partial<Record<MealSlot, number>>;
+}
+
+/** Append to life/nutrition/{date}.yaml; `estimate` asks the AI CLI for calories */
+export const logMeal = (
+  vaultPath: string,
+  text: string,
+  opts: { datetime?: string; calories?: number; estimate?: boolean } = {}
+): Promise<Meal> =>
+  invoke("log_meal", { vaultPath, text, ...opts });
+
+export const listMeals = (vaultPath: string, date: string): Promise<NutritionDay> =>
+  invoke("list_meals", { vaultPath, date });
+
+export const deleteMeal = (vaultPath: string, date: string, index: number): Promise<NutritionDay> =>
+  invoke("delete_meal", { vaultPath, date, index });
+
+export const estimateMissingCalories = (vaultPath: string, date: string): Promise<NutritionDay> =>
+  invoke("estimate_missing_calories", { vaultPath, date });
+
+/** Week (Mon–Sun) containing `date`, default this week */
+export const getNutritionSummary = (vaultPath: string, date?: string): Promise<NutritionWeek> =>
+  invoke("get_nutrition_summary", { vaultPath, date });
+
+// ── Medication ───────────────────────────────────────────────────────────────
+
+/** Entry of life/health/medications.yaml */
+export interface Medication {
+  id: string; // empty on create
+  name: string;
+  dose: string;
+  times: string[]; // HH:MM
+  days: number[]; // 1=Mon..7=Sun, empty = daily
+  start?: string;
+  end?: string;
+  active: boolean;
+  notes: string;
+}
+
+export interface DoseLog {
+  id: string;
+  slot: string;
+  at: string;
+  skipped?: boolean;
+}
+
+export interface DoseStatus {
+  id: string;
+  name: string;
+  dose: string;
+  slot: string;
+  at: string | null;
+  skipped: boolean;
+}
+
+export interface Adherence {
+  id: string;
+  name: string;
+  scheduled: number;
+  taken: number;
+  skipped: number;
+  rate: number; // 0–1
+  streak: number;
+}
+
+export const listMedications = (vaultPath: string): Promise<Medication[]> =>
+  invoke("list_medications", { vaultPath });
+
+export const saveMedication = (vaultPath: string, medication: Medication): Promise<Medication> =>
+  invoke("save_medication", { vaultPath, medication });
+
+export const deleteMedication = (vaultPath: string, id: string): Promise<void> =>
+  invoke("delete_medication", { vaultPath, id });
+
+/** `slot` defaults to the scheduled time closest to now */
+export const logMedicationDose = (
+  vaultPath: string,
+  id: string,
+  opts: { slot?: string; date?: string; skipped?: boolean } = {}
+): Promise<DoseLog> =>
+  invoke("log_medication_dose", { vaultPath, id, ...opts });
+
+export const getMedicationDay = (vaultPath: string, date?: string): Promise<DoseStatus[]> =>
+  invoke("get_medication_day", { vaultPath, date });
+
+export const getMedicationAdherence = (vaultPath: string, days?: number): Promise<Adherence[]> =>
+  invoke("get_medication_adherence", { vaultPath, days });
+
+export const startMedicationReminders = (vaultPath: string): Promise<void> =>
+  invoke("start_medication_reminders", { vaultPath });
+
+export const stopMedicationReminders = (): Promise<void> =>
+  invoke("stop_medication_reminders");
+
+// ── Sleep ────────────────────────────────────────────────────────────────────
+
+export interface SleepEntry {
+  date: string; // wake date
+  bedtime: string; // HH:MM
+  wake: string;
+  minutes: number; // asleep
+  quality?: number; // 1–5
+  source: "manual" | "health" | "csv";
+  note?: string;
+}
+
+export interface SleepTrends {
+  from: string;
+  to: string;
+  nights: SleepEntry[];
+  average_minutes: number | null;
+  average_bedtime: string | null;
+  average_wake: string | null;
+  weekday_minutes: number | null;
+  weekend_minutes: number | null;
+  target_minutes: number;
+  debt_minutes: number;
+  mood_correlation: number | null; // Pearson r, sleep vs. diary mood
+  mood_samples: number;
+}
+
+export const logSleep = (
+  vaultPath: string,
+  date: string,
+  bedtime: string,
+  wake: string,
+  quality?: number,
+  note?: string
+): Promise<SleepEntry> =>
+  invoke("log_sleep", { vaultPath, date, bedtime, wake, quality, note });
+
+export const deleteSleep = (vaultPath: string, date: string): Promise<void> =>
+  invoke("delete_sleep", { vaultPath, date });
+
+/** Apple Health export.xml or a date,bedtime,wake[,minutes] CSV */
+export const importSleep = (
+  vaultPath: string,
+  filePath: string,
+  dryRun = false
+): Promise<{ imported: number; existing: number; skipped: number; dry_run: boolean }> =>
+  invoke("import_sleep", { vaultPath, filePath, dryRun });
+
+export const listSleep = (vaultPath: string, from: string, to: string): Promise<SleepEntry[]> =>
+  invoke("list_sleep", { vaultPath, from, to });
+
+/** `range`: "7d" | "30d" | "3m" | "1y" | "YYYY-MM" | "YYYY-MM-DD..YYYY-MM-DD" */
+export const getSleepTrends = (vaultPath: string, range: string, targetHours?: number): Promise<SleepTrends> =>
+  invoke("get_sleep_trends", { vaultPath, range, targetHours });
+
+// ── Mood ─────────────────────────────────────────────────────────────────────
+
+export interface MoodDay {
+  date: string;
+  mood: number | null; // 1–5
+  energy: number | null; // 1–5
+  moods: string[]; // raw frontmatter values
+  sleep_minutes: number | null;
+  tasks_done: number;
+  tasks_total: number;
+  completion: number | null; // 0–1
+}
+
+export interface MoodTrends {
+  from: string;
+  to: string;
+  days: MoodDay[];
+  average_mood: number | null;
+  average_energy: number | null;
+  distribution: Record<string, number>;
+  by_weekday: (number | null)[]; // Monday..Sunday
+  correlations: {
+    mood_sleep: number | null;
+    mood_tasks: number | null;
+    mood_energy: number | null;
+    energy_sleep: number | null;
+  };
+}
+
+/** `range`: same forms as `getSleepTrends` */
+export const getMoodTrends = (vaultPath: string, range: string): Promise<MoodTrends> =>
+  invoke("get_mood_trends", { vaultPath, range });
+
+// ── Trips ────────────────────────────────────────────────────────────────────
+
+export type TripStatus = "planning" | "booked" | "done" | "cancelled";
+
+export interface Booking {
+  kind: string; // flight | train | hotel | car | activity | ...
+  title: string;
+  start: string; // YYYY-MM-DD or YYYY-MM-DD HH:MM
+  end?: string; // arrival / check-out
+  location?: string;
+  confirmation?: string;
+  message_id?: string; // confirmation email
+  note?: string;
+}
+
+/** Synced email found by Message-ID */
+export interface CachedMessage {
+  messageId: string;
+  account: string; // Mailbox/<account>
+  emailId: string; // for getEmailContent
+  subject: string;
+  from: string;
+  date: string;
+}
+
+/** life/trips/<slug>.md */
+export interface Trip {
+  slug: string;
+  path: string;
+  title: string;
+  destination: string;
+  start: string;
+  end: string;
+  status: TripStatus;
+  bookings: Booking[];
+  emails: CachedMessage[];
+  tags: string[];
+  packing: { text: string; done: boolean }[];
+  notes: string;
+  [extra: string]: unknown;
+}
+
+export interface ItineraryDay {
+  date: string;
+  day: number | null; // 1-based, null outside the trip dates
+  events: {
+    time: string | null; // HH:MM, null for all-day
+    kind: string;
+    title: string;
+    phase: "start" | "end";
+    location: string | null;
+    confirmation: string | null;
+    booking: number; // index into trip.bookings
+  }[];
+}
+
+export const listTrips = (vaultPath: string): Promise<Trip[]> =>
+  invoke("list_trips", { vaultPath });
+
+export const getTrip = (vaultPath: string, slug: string): Promise<Trip> =>
+  invoke("get_trip", { vaultPath, slug });
+
+/** Empty `slug` creates a new trip named after the title */
+export const saveTrip = (vaultPath: string, trip: Trip): Promise<Trip> =>
+  invoke("save_trip", { vaultPath, trip });
+
+export const deleteTrip = (vaultPath: string, slug: string): Promise<void> =>
+  invoke("delete_trip", { vaultPath, slug });
+
+export const linkTripEmail = (
+  vaultPath: string,
+  slug: string,
+  messageId: string,
+  booking?: number
+): Promise<Trip> => invoke("link_trip_email", { vaultPath, slug, messageId, booking });
+
+export const unlinkTripEmail = (vaultPath: string, slug: string, messageId: string): Promise<Trip> =>
+  invoke("unlink_trip_email", { vaultPath, slug, messageId });
+
+export const listPackingTemplates = (vaultPath: string): Promise<string[]> =>
+  invoke("list_packing_templates", { vaultPath });
+
+export const generatePackingList = (vaultPath: string, slug: string, templates: string[]): Promise<Trip> =>
+  invoke("generate_packing_list", { vaultPath, slug, templates });
+
+export const setPackingItem = (vaultPath: string, slug: string, index: number, done: boolean): Promise<Trip> =>
+  invoke("set_packing_item", { vaultPath, slug, index, done });
+
+export const getTripItinerary = (vaultPath: string, slug: string): Promise<ItineraryDay[]> =>
+  invoke("get_trip_itinerary", { vaultPath, slug });
+
+// ── Location ─────────────────────────────────────────────────────────────────
+
+/** .lifeos/location.yaml — nothing is recorded while `enabled` is false */
+export interface LocationSettings {
+  enabled: boolean;
+  source: "inbox" | "shortcut" | "command";
+  shortcut: string; // Shortcuts name printing `lat,lon`
+  command: string; // e.g. CoreLocationCLI
+  interval_minutes: number;
+  min_stay_minutes: number;
+  radius_m: number;
+  places: { name: string; lat: number; lon: number }[];
+}
+
+export interface Visit {
+  name: string;
+  lat: number;
+  lon: number;
+  arrived: string; // HH:MM
+  left: string;
+  minutes: number;
+}
+
+export const getLocationSettings = (vaultPath: string): Promise<LocationSettings> =>
+  invoke("get_location_settings", { vaultPath });
+
+export const saveLocationSettings = (vaultPath: string, settings: LocationSettings): Promise<LocationSettings> =>
+  invoke("save_location_settings", { vaultPath, settings });
+
+/** One fix, e.g. from navigator.geolocation; returns the day's places */
+export const recordLocation = (
+  vaultPath: string,
+  lat: number,
+  lon: number,
+  at?: string,
+  name?: string
+): Promise<Visit[]> => invoke("record_location", { vaultPath, lat, lon, at, name });
+
+/** Lines `<RFC 3339>,<lat>,<lon>[,<name>]` in .lifeos/location-inbox.txt; `dryRun` only counts them */
+export const importLocationInbox = (vaultPath: string, dryRun = false): Promise<number> =>
+  invoke("import_location_inbox", { vaultPath, dryRun });
+
+export const getLocationDay = (vaultPath: string, date: string): Promise<Visit[]> =>
+  invoke("get_location_day", { vaultPath, date });
+
+/** Without `before` everything is deleted, including daily-note `places` */
+export const purgeLocationHistory = (
+  vaultPath: string,
+  before?: string,
+  dryRun = false
+): Promise<{ days: number; notes: number; dry_run: boolean }> =>
+  invoke("purge_location_history", { vaultPath, before, dryRun });
+
+export const startLocationLogger = (vaultPath: string): Promise<void> =>
+  invoke("start_location_logger", { vaultPath });
+
+export const stopLocationLogger = (): Promise<void> =>
+  invoke("stop_location_logger");
+
+// ── Weather ──────────────────────────────────────────────────────────────────
+
+/** Open-Meteo day for the `weather:` location in connectors.yaml */
+export interface Weather {
+  date: string;
+  location: string;
+  code: number; // WMO
+  emoji: string;
+  summary: string;
+  temp_min: number | null;
+  temp_max: number | null;
+  precipitation_mm: number | null;
+}
+
+export const getWeather = (vaultPath: string, date?: string): Promise<Weather> =>
+  invoke("get_weather", { vaultPath, date });
+
+/** Fills an empty `weather` field from the note's date; null if already set */
+export const fillWeather = (vaultPath: string, path: string): Promise<string | null> =>
+  invoke("fill_weather", { vaultPath, path });
+
+// ── Secrets ──────────────────────────────────────────────────────────────────
+
+/** Values stay in the macOS Keychain; config can reference `keychain:<name>` */
+export interface SecretMeta {
+  name: string;
+  note: string;
+  updated: string;
+}
+
+export const storeSecret = (vaultPath: string, name: string, value: string, note?: string): Promise<SecretMeta> =>
+  invoke("store_secret", { vaultPath, name, value, note });
+
+export const getSecret = (name: string): Promise<string> =>
+  invoke("get_secret", { name });
+
+export const deleteSecret = (vaultPath: string, name: string): Promise<void> =>
+  invoke("delete_secret", { vaultPath, name });
+
+export const listSecrets = (vaultPath: string): Promise<SecretMeta[]> =>
+  invoke("list_secrets", { vaultPath });
+
+// ── Assets ───────────────────────────────────────────────────────────────────
+
+/** Entry of .lifeos/assets-index.json; paths are vault-relative */
+export interface Asset {
+  path: string;
+  name: string;
+  size: number;
+  modified: number; // unix seconds
+  width: number | null;
+  height: number | null;
+  taken: string | null; // EXIF, YYYY-MM-DD HH:MM:SS
+  thumbnail: string | null; // .lifeos/thumbnails/*.jpg
+  references: string[]; // notes embedding or linking it
+}
+
+export interface AssetFilter {
+  query?: string;
+  from?: string; // YYYY-MM-DD
+  to?: string;
+  extension?: string;
+  referenced?: boolean;
+}
+
+export const rebuildAssetIndex = (
+  vaultPath: string
+): Promise<{ assets: number; thumbnails_created: number; orphaned: number; bytes: number }> =>
+  invoke("rebuild_asset_index", { vaultPath });
+
+export const listAssets = (vaultPath: string, filters?: AssetFilter): Promise<Asset[]> =>
+  invoke("list_assets", { vaultPath, filters });
+
+/** Rescans before answering, so the result is safe to act on */
+export const findOrphanedAssets = (vaultPath: string): Promise<Asset[]> =>
+  invoke("find_orphaned_assets", { vaultPath });
+
+// ── PDF ──────────────────────────────────────────────────────────────────────
+
+export interface PdfText {
+  source: string; // vault-relative
+  modified: number;
+  pages: string[]; // first page first
+}
+
+/** Cached per file; extracted PDFs are included in searchNotes */
+export const extractPdfText = (vaultPath: string, path: string): Promise<PdfText> =>
+  invoke("extract_pdf_text", { vaultPath, path });
+
+export const indexPdfs = (vaultPath: string): Promise<{ indexed: number; failed: string[] }> =>
+  invoke("index_pdfs", { vaultPath });
+
+/** annotations/<slug>.md linked to the PDF; returns the note path */
+export const createPdfAnnotationNote = (vaultPath: string, path: string): Promise<string> =>
+  invoke("create_pdf_annotation_note", { vaultPath, path });
+
+export const addPdfAnnotation = (vaultPath: string, path: string, page: number, text: string): Promise<string> =>
+  invoke("add_pdf_annotation", { vaultPath, path, page, text });
+
+// ── Screenshots ──────────────────────────────────────────────────────────────
+
+export type ScreenshotMode = "full" | "window" | "selection";
+
+export interface Screenshot {
+  path: string;
+  markdown: string; // ![截图](...) relative to notePath when given
+}
+
+/** macOS only; null when the window/selection picker is cancelled */
+export const captureScreenshot = (vaultPath: string, mode: ScreenshotMode, notePath?: string): Promise<Screenshot | null> =>
+  invoke("capture_screenshot", { vaultPath, mode, notePath });
+
+// ── Templates ────────────────────────────────────────────────────────────────
+
+export interface TemplateInfo {
+  name: string;
+  path: string;
+  prompts: string[]; // labels of {{prompt:...}} to ask before instantiating
+}
+
+export const listTemplates = (vaultPath: string): Promise<TemplateInfo[]> =>
+  invoke("list_templates", { vaultPath });
+
+/** Renders templates/<name>.md into the vault-relative target; returns its path */
+export const instantiateTemplate = (
+  vaultPath: string,
+  name: string,
+  target: string,
+  answers?: Record<string, string>
+): Promise<string> =>
+  invoke("instantiate_template", { vaultPath, name, target, answers });
+
+export interface ProjectTemplateInfo {
+  name: string;
+  notes: string[]; // sub-notes besides project.md
+  prompts: string[];
+}
+
+export interface ScaffoldedProject {
+  path: string; // projects/<slug>.md
+  notes: string[]; // under projects/<slug>/
+}
+
+export const listProjectTemplates = (vaultPath: string): Promise<ProjectTemplateInfo[]> =>
+  invoke("list_project_templates", { vaultPath });
+
+/** Scaffolds templates/projects/<template>/ into a new project; nothing is written on error */
+export const createProjectFromTemplate = (
+  vaultPath: string,
+  template: string,
+  name: string,
+  answers?: Record<string, string>
+): Promise<ScaffoldedProject> =>
+  invoke("create_project_from_template", { vaultPath, template, name, answers });
+
+// ── Note history ─────────────────────────────────────────────────────────────
+
+export interface NoteVersion {
+  id: string; // "git:<sha>" | "snapshot:<stamp>"; "current" is the file on disk
+  timestamp: string;
+  message?: string;
+  source: "git" | "snapshot";
+}
+
+export interface DiffLine {
+  kind: "equal" | "insert" | "delete";
+  old_line?: number;
+  new_line?: number;
+  text: string;
+}
+
+export interface NoteDiff {
+  from: string;
+  to: string;
+  added: number;
+  removed: number;
+  lines: DiffLine[];
+}
+
+export const listNoteVersions = (vaultPath: string, path: string): Promise<NoteVersion[]> =>
+  invoke("list_note_versions", { vaultPath, path });
+
+/** Returns null when the note is unchanged since its last snapshot */
+export const snapshotNote = (vaultPath: string, path: string): Promise<NoteVersion | null> =>
+  invoke("snapshot_note", { vaultPath, path });
+
+export const getNoteVersion = (vaultPath: string, path: string, version: string): Promise<string> =>
+  invoke("get_note_version", { vaultPath, path, version });
+
+/** `to` defaults to the current file */
+export const getNoteDiff = (vaultPath: string, path: string, from: string, to?: string): Promise<NoteDiff> =>
+  invoke("get_note_diff", { vaultPath, path, from, to });
+
+// ── Vault sync ───────────────────────────────────────────────────────────────
+
+export interface SyncReport {
+  finished: string;
+  uploaded: string[];
+  downloaded: string[];
+  deleted_local: string[];
+  deleted_remote: string[];
+  conflicts: string[]; // conflict copies of the remote version
+  errors: string[];
+}
+
+/** Uses the `sync:` section of connectors.yaml (WebDAV or S3) */
+export const syncVault = (vaultPath: string): Promise<SyncReport> =>
+  invoke("sync_vault", { vaultPath });
+
+export const getSyncStatus = (vaultPath: string): Promise<SyncReport | null> =>
+  invoke("get_sync_status", { vaultPath });
+
+export const startSyncLoop = (vaultPath: string): Promise<void> =>
+  invoke("start_sync_loop", { vaultPath });
+
+export const stopSyncLoop = (): Promise<void> =>
+  invoke("stop_sync_loop");
+
+// ── Export profiles ──────────────────────────────────────────────────────────
+
+export interface ExportProfile {
+  name: string;
+  include: string[]; // vault-relative globs; dot-folders are never exported
+  exclude: string[];
+  target: string; // absolute, outside the vault: a folder or the .zip to write
+  format: "folder" | "zip";
+  password?: string; // zip only, AES-256; may be keychain:<name>
+  expand_embeds?: boolean; // write notes with ![[...]] embeds expanded
+  interval_hours: number; // 0 = manual only
+}
+
+export interface ExportReport {
+  profile: string;
+  target: string;
+  files: number;
+  copied: number;
+  removed: number;
+  encrypted: boolean;
+  finished: string;
+}
+
+export const getExportProfiles = (vaultPath: string): Promise<{ profiles: ExportProfile[] }> =>
+  invoke("get_export_profiles", { vaultPath });
+
+export const saveExportProfiles = (vaultPath: string, settings: { profiles: ExportProfile[] }): Promise<{ profiles: ExportProfile[] }> =>
+  invoke("save_export_profiles", { vaultPath, settings });
+
+export const runExport = (vaultPath: string, name: string): Promise<ExportReport> =>
+  invoke("run_export", { vaultPath, name });
+
+export const getExportStatus = (vaultPath: string): Promise<Record<string, ExportReport>> =>
+  invoke("get_export_status", { vaultPath });
+
+export const startExportScheduler = (vaultPath: string): Promise<void> =>
+  invoke("start_export_scheduler", { vaultPath });
+
+export const stopExportScheduler = (): Promise<void> =>
+  invoke("stop_export_scheduler");
+
+export interface BookReport {
+  path: string;
+  entries: number;
+  images: number;
+  missing_images: string[]; // image references that could not be found or decoded
+}
+
+/** A year of diary as one book; `dest` ends in .epub, or .html to print to PDF */
+export const exportDiaryBook = (vaultPath: string, year: number, dest: string): Promise<BookReport> =>
+  invoke("export_diary_book", { vaultPath, year, dest });
+
+// ── Board ────────────────────────────────────────────────────────────────────
+
+export interface MoveResult {
+  path: string;
+  from: string;
+  to: string;
+  fields: Record<string, unknown>; // written by board.yaml rules (+ updated); null = removed
+}
+
+/** Rejects when the target column is at its wip_limit */
+export const moveProject = (vaultPath: string, path: string, to: string): Promise<MoveResult> =>
+  invoke("move_project", { vaultPath, path, to });
+
+export interface CodeTodo {
+  repo: string;
+  path: string; // repo-relative
+  line: number; // 1-based
+  kind: string; // "TODO" | "FIXME" | "HACK" | …
+  text: string;
+  author: string | null; // git blame; only from scanCodeTodos
+}
+
+export interface DevStatus {
+  repo: string;
+  branch: string; // "HEAD" when detached
+  changes: string[]; // git status --porcelain lines
+  recent_commits: { hash: string; date: string; author: string; subject: string }[];
+  todos: CodeTodo[];
+  readme: string | null; // first paragraph
+}
+
+/** Git state of the repo in the project's `repo` frontmatter; `slug` is the file name without .md */
+export const getProjectDevStatus = (vaultPath: string, slug: string): Promise<DevStatus> =>
+  invoke("get_project_dev_status", { vaultPath, slug });
+
+// ── Agenda ───────────────────────────────────────────────────────────────────
+
+export interface DayTask {
+  line: number; // 0-based line in the day file
+  text: string; // without #tags, @contexts and the ⏰/⚡/📅/🆔/⛔ markers
+  done: boolean;
+  tags: string[];
+  time: string | null;
+  contexts: string[]; // @phone → "phone"
+  energy: string | null; // ⚡low | ⚡medium | ⚡high
+  due: string | null; // 📅YYYY-MM-DD
+  id: string | null; // 🆔id
+  depends_on: string[]; // ⛔id,id — task ids or project names
+}
+
+export interface AgendaEvent {
+  time: string | null; // HH:MM; null for all-day
+  end: string | null;
+  title: string;
+  location: string | null;
+  source: "calendar" | "trip";
+  path: string; // .ics file or trip note
+  id?: string; // calendar UID, for createMeetingNote
+  note?: string; // meeting note made for the event
+}
+
+export interface DueProject {
+  path: string;
+  title: string;
+  status: string;
+  priority: string;
+  due: string;
+  overdue: boolean;
+}
+
+export interface HabitDue {
+  id: string;
+  name: string;
+  icon: string;
+  done: boolean;
+}
+
+export interface DailyAgenda {
+  date: string;
+  tasks: DayTask[];
+  events: AgendaEvent[]; // timed first, then all-day
+  projects: DueProject[]; // unfinished, due on the day or earlier
+  habits: HabitDue[];
+  occasions: Occasion[];
+  renewals: SubscriptionStatus[]; // enabled, renewing on the day
+  medications: DoseStatus[];
+}
+
+/** Everything on one day (default today) in a single call */
+export const getDailyAgenda = (vaultPath: string, date?: string): Promise<DailyAgenda> =>
+  invoke("get_daily_agenda", { vaultPath, date });
+
+// ── Focus ────────────────────────────────────────────────────────────────────
+
+/** .lifeos/focus.yaml */
+export interface FocusProfile {
+  minutes?: number; // default length for the profile
+  mute_notifications?: boolean; // default true: hold back LifeOS reminders
+  start_shortcut?: string; // macOS Shortcut, e.g. one that turns a Focus on
+  end_shortcut?: string;
+}
+
+export interface FocusSettings {
+  profiles: Record<string, FocusProfile>;
+}
+
+export interface FocusSession {
+  profile: string;
+  label?: string;
+  start: string; // RFC 3339
+  ends_at: string; // RFC 3339
+  minutes: number;
+  mute_notifications: boolean;
+}
+
+/** A line of life/time/sessions.jsonl */
+export interface TimeEntry {
+  kind: "focus";
+  profile: string;
+  label?: string;
+  start: string;
+  end: string;
+  minutes: number;
+  completed: boolean; // ran its full length
+}
+
+export const getFocusSettings = (vaultPath: string): Promise<FocusSettings> =>
+  invoke("get_focus_settings", { vaultPath });
+
+export const saveFocusSettings = (vaultPath: string, settings: FocusSettings): Promise<void> =>
+  invoke("save_focus_settings", { vaultPath, settings });
+
+/** Replaces (and logs) any running session */
+export const startFocus = (
+  vaultPath: string,
+  opts: { minutes?: number; profile?: string; label?: string } = {}
+): Promise<FocusSession> => invoke("start_focus", { vaultPath, ...opts });
+
+export const stopFocus = (): Promise<TimeEntry | null> => invoke("stop_focus");
+
+export const getFocusStatus = (): Promise<FocusSession | null> => invoke("get_focus_status");
+
+/** Sessions that started on the day (default today) */
+export const getTimeEntries = (vaultPath: string, date?: string): Promise<TimeEntry[]> =>
+  invoke("get_time_entries", { vaultPath, date });
+
+export const onFocusStarted = (cb: (session: FocusSession) => void): Promise<UnlistenFn> =>
+  listen<FocusSession>("focus-started", (e) => cb(e.payload));
+
+/** Fires both when a session runs out and when it is stopped */
+export const onFocusEnded = (cb: (entry: TimeEntry) => void): Promise<UnlistenFn> =>
+  listen<TimeEntry>("focus-ended", (e) => cb(e.payload));
+
+// ── Habits ───────────────────────────────────────────────────────────────────
+
+/** daily/habits/pauses.yaml: missed days here don't break a streak */
+export interface HabitPause {
+  kind: "vacation" | "freeze";
+  from: string; // YYYY-MM-DD, inclusive
+  to: string;
+  habits?: string[]; // ids; omitted = all habits
+  note?: string;
+}
+
+export interface HabitStreak {
+  id: string;
+  name: string;
+  current: number;
+  longest: number;
+  paused: boolean; // under a pause on the day asked about
+}
+
+export const getHabitStreaks = (vaultPath: string, date?: string): Promise<HabitStreak[]> =>
+  invoke("get_habit_streaks", { vaultPath, date });
+
+export const listHabitPauses = (vaultPath: string): Promise<HabitPause[]> =>
+  invoke("list_habit_pauses", { vaultPath });
+
+export const setHabitVacation = (
+  vaultPath: string,
+  from: string,
+  to: string,
+  opts: { habits?: string[]; note?: string } = {}
+): Promise<HabitPause> => invoke("set_habit_vacation", { vaultPath, from, to, ...opts });
+
+export const freezeHabitDay = (vaultPath: string, date: string, habits?: string[]): Promise<HabitPause> =>
+  invoke("freeze_habit_day", { vaultPath, date, habits });
+
+export const removeHabitPause = (vaultPath: string, kind: HabitPause["kind"], from: string): Promise<void> =>
+  invoke("remove_habit_pause", { vaultPath, kind, from });
+
+// ── Activity ─────────────────────────────────────────────────────────────────
+
+export interface ActivityDay {
+  date: string; // YYYY-MM-DD
+  tasks: number; // checked in that day's task file
+  words: number; // diary words, CJK characters one each
+  commits: number; // own commits in the repos passed
+  emails: number; // received that day and since read
+  total: number; // tasks + commits + emails + one per 250 words
+  level: 0 | 1 | 2 | 3 | 4;
+}
+
+/** One entry per day, default the year up to today; `repos` e.g. from the Git scanner */
+export const getActivityHeatmap = (
+  vaultPath: string,
+  opts: { from?: string; to?: string; repos?: string[] } = {}
+): Promise<ActivityDay[]> => invoke("get_activity_heatmap", { vaultPath, ...opts });
+
+// ── Triage ───────────────────────────────────────────────────────────────────
+
+/** email: unread INBOX mail · capture: inbox/*.md · apple-note: in a "LifeOS" folder or tagged #lifeos (macOS) */
+export type TriageSource = "email" | "capture" | "apple-note";
+export type TriageAction = "taskify" | "archive" | "delete" | "delegate";
+
+export interface TriageItem {
+  source: TriageSource;
+  id: string; // account/email id, inbox path, or Apple Notes id
+  title: string;
+  preview: string;
+  date: string | null; // RFC 3339; oldest first
+}
+
+export interface TriageQueue {
+  item: TriageItem | null; // null = inbox zero
+  remaining: number;
+  by_source: Partial<Record<TriageSource, number>>;
+}
+
+export interface TriageDecision {
+  source: TriageSource;
+  id: string;
+  title: string;
+  action: TriageAction;
+  at: string;
+  task?: string; // line added for taskify / delegate
+  delegate_to?: string;
+}
+
+export const getTriageQueue = (vaultPath: string, sources?: TriageSource[]): Promise<TriageQueue> =>
+  invoke("get_triage_queue", { vaultPath, sources });
+
+/** Captures are archived to archive/inbox (or deleted); mail and notes are only marked triaged */
+export const triageItem = (
+  vaultPath: string,
+  item: Pick<TriageItem, "source" | "id">,
+  action: TriageAction,
+  options?: { date?: string; text?: string; delegate_to?: string }
+): Promise<TriageDecision> => invoke("triage_item", { vaultPath, source: item.source, id: item.id, action, options });
+
+export const getTriageHistory = (vaultPath: string, limit?: number): Promise<TriageDecision[]> =>
+  invoke("get_triage_history", { vaultPath, limit });
+
+// ── Tasks ────────────────────────────────────────────────────────────────────
+
+export interface NextAction extends DayTask {
+  date: string; // day file the task is in
+  path: string;
+  overdue: boolean;
+}
+
+export interface NextActions {
+  energy: string | null; // the one asked for, else the day note's
+  contexts: string[];
+  actions: NextAction[];
+}
+
+export interface DependencyReport {
+  /** Unfinished items waiting on incomplete ones */
+  blocked: {
+    kind: "task" | "project";
+    name: string;
+    path: string;
+    line: number | null;
+    waiting_on: string[];
+  }[];
+  problems: {
+    path: string;
+    line: number | null;
+    reference: string;
+    problem: "missing" | "cycle" | "duplicate-id";
+  }[];
+}
+
+export const getBlockedTasks = (vaultPath: string): Promise<DependencyReport> =>
+  invoke("get_blocked_tasks", { vaultPath });
+
+/** "What should I do now": open, unblocked tasks up to the day that fit the context and energy */
+export const getNextActions = (
+  vaultPath: string,
+  opts: { context?: string; energy?: string; dueWithin?: number; date?: string } = {}
+): Promise<NextActions> => invoke("get_next_actions", { vaultPath, ...opts });
+
+export interface TaskSuggestion extends NextAction {
+  effort: number | null; // minutes, from a #30m / #1h30m tag
+  score: number;
+}
+
+export interface TaskSuggestions {
+  energy: string | null; // the day note's
+  available_minutes: number | null; // until next_event; null when the day is clear
+  next_event: string | null;
+  suggestions: TaskSuggestion[]; // best first
+}
+
+/** Next actions that suit today's energy and fit before the next calendar event */
+export const suggestTasks = (vaultPath: string, now?: string, limit?: number): Promise<TaskSuggestions> =>
+  invoke("suggest_tasks", { vaultPath, now, limit });
+
+// ── Drop ─────────────────────────────────────────────────────────────────────
+
+export interface DroppedFile {
+  source: string; // path as dropped
+  kind: "image" | "pdf" | "email" | "calendar" | "note" | "unsupported";
+  created: string[]; // vault-relative
+  title: string | null; // email subject or event count
+  error: string | null;
+}
+
+/** Import dropped files by type: images/PDFs → assets, .eml → Mailbox, .ics → calendar, .md → inbox */
+export const ingestDroppedFiles = (vaultPath: string, paths: string[]): Promise<DroppedFile[]> =>
+  invoke("ingest_dropped_files", { vaultPath, paths });
+
+// ── Background mode ──────────────────────────────────────────────────────────
+
+export interface BackgroundSettings {
+  keep_running: boolean; // closing the window hides it; the app keeps working
+  mail_sync_minutes: number; // 0 = off
+}
+
+export const getBackgroundSettings = (vaultPath: string): Promise<BackgroundSettings> =>
+  invoke("get_background_settings", { vaultPath });
+
+export const saveBackgroundSettings = (vaultPath: string, settings: BackgroundSettings): Promise<void> =>
+  invoke("save_background_settings", { vaultPath, settings });
+
+/** Periodic mail sync per the background settings */
+export const startBackgroundAgent = (vaultPath: string): Promise<void> =>
+  invoke("start_background_agent", { vaultPath });
+
+export const stopBackgroundAgent = (): Promise<void> =>
+  invoke("stop_background_agent");
+
+/** Always false where unsupported (macOS only) */
+export const getLaunchAtLogin = (): Promise<boolean> =>
+  invoke("get_launch_at_login");
+
+/** Installs a login item that starts the app with its window hidden */
+export const setLaunchAtLogin = (enabled: boolean): Promise<void> =>
+  invoke("set_launch_at_login", { enabled });
+
+// ── Session state ────────────────────────────────────────────────────────────
+
+export interface AppSessionState {
+  window?: { x: number; y: number; width: number; height: number; maximized: boolean }; // kept by the backend
+  last_note?: string; // vault-relative
+  tabs: string[];
+  active_tab?: string;
+  views: Record<string, unknown>; // per-plugin view state
+}
+
+/** Saved to .lifeos/state.json shortly after the last change */
+export const getAppState = (vaultPath: string): Promise<AppSessionState> =>
+  invoke("get_app_state", { vaultPath });
+
+/** Top-level keys replace, null clears; `views` merges per plugin */
+export const setAppState = (vaultPath: string, patch: Record<string, unknown>): Promise<AppSessionState> =>
+  invoke("set_app_state", { vaultPath, patch });
+
+export const setViewState = (vaultPath: string, view: string, state: unknown): Promise<void> =>
+  invoke("set_view_state", { vaultPath, view, state });
+
+// ── Spotlight ────────────────────────────────────────────────────────────────
+
+export interface SpotlightReport {
+  indexed: number;
+  unchanged: number;
+  failed: [string, string][]; // [path, error]
+  dryRun: boolean; // counted only, nothing written
+}
+
+export const getSpotlightSettings = (vaultPath: string): Promise<{ enabled: boolean }> =>
+  invoke("get_spotlight_settings", { vaultPath });
+
+/** Title/keywords/description for macOS search; only changed notes unless `full` */
+export const reindexSpotlight = (vaultPath: string, full?: boolean): Promise<SpotlightReport> =>
+  invoke("reindex_spotlight", { vaultPath, full });
+
+/** Off removes the metadata added so far */
+export const setSpotlightEnabled = (vaultPath: string, enabled: boolean): Promise<SpotlightReport> =>
+  invoke("set_spotlight_enabled", { vaultPath, enabled });
+
+// ── Quick switcher ───────────────────────────────────────────────────────────
+
+export interface SwitcherResult {
+  kind: "note" | "heading" | "project" | "command";
+  title: string;
+  target: string; // vault-relative path, or the view id for commands
+  line?: number; // headings
+  detail?: string; // note title of a heading, folder of a note
+  score: number;
+  matched: number[]; // char positions in title, for highlighting
+}
+
+/** Cmd+P matches from an in-memory index; `scope` narrows to one kind */
+export const fuzzyFind = (
+  vaultPath: string,
+  query: string,
+  scope?: SwitcherResult["kind"] | "all",
+  limit?: number
+): Promise<SwitcherResult[]> => invoke("fuzzy_find", { vaultPath, query, scope, limit });
+
+/** Rebuild on the next search, e.g. after creating or renaming notes */
+export const refreshFuzzyIndex = (): Promise<void> =>
+  invoke("refresh_fuzzy_index");
+
+// ── Email follow-ups ─────────────────────────────────────────────────────────
+
+export interface FollowUp {
+  message_id: string;
+  account_id: string;
+  subject: string;
+  to: string;
+  from: string;
+  since: string;
+  due: string; // YYYY-MM-DD
+  notified: string[];
+}
+
+export interface PendingFollowUp extends FollowUp {
+  days_left: number; // negative once overdue
+  overdue: boolean;
+}
+
+/** Wait for a reply to a cached sent message by `due` */
+export const trackFollowup = (vaultPath: string, accountId: string, emailId: string, due: string): Promise<FollowUp> =>
+  invoke("track_followup", { vaultPath, accountId, emailId, due });
+
+export const cancelFollowup = (vaultPath: string, messageId: string): Promise<void> =>
+  invoke("cancel_followup", { vaultPath, messageId });
+
+/** Still unanswered, soonest due first; answered ones are dropped on the way */
+export const getPendingFollowups = (vaultPath: string): Promise<PendingFollowUp[]> =>
+  invoke("get_pending_followups", { vaultPath });
+
+export const startFollowupReminders = (vaultPath: string): Promise<void> =>
+  invoke("start_followup_reminders", { vaultPath });
+
+export const stopFollowupReminders = (): Promise<void> =>
+  invoke("stop_followup_reminders");
+
+// ── Meeting notes ────────────────────────────────────────────────────────────
+
+/** Note under meetings/ for a calendar event (AgendaEvent.id); an existing one is returned as is */
+export const createMeetingNote = (vaultPath: string, eventId: string): Promise<string> =>
+  invoke("create_meeting_note", { vaultPath, eventId });
+
+export const findMeetingNote = (vaultPath: string, eventId: string): Promise<string | null> =>
+  invoke("find_meeting_note", { vaultPath, eventId });
+
+// ── Time blocking ────────────────────────────────────────────────────────────
+
+export interface TimeBlock {
+  task_ref: string; // "YYYY-MM-DD:line" (0-based) or a task's 🆔 id
+  title: string;
+  start: string; // HH:MM
+  duration: number; // minutes
+}
+
+export interface ScheduleConflict {
+  kind: "event" | "block";
+  title: string;
+  start: string;
+  end: string;
+}
+
+export interface ScheduledBlock extends TimeBlock {
+  end: string;
+  conflicts: ScheduleConflict[];
+}
+
+export interface DayPlan {
+  date: string;
+  blocks: ScheduledBlock[];
+  events: AgendaEvent[]; // timed calendar events
+}
+
+/** `start` is "YYYY-MM-DDTHH:MM" or "HH:MM" for today; overlaps are reported, not refused */
+export const scheduleTask = (vaultPath: string, taskRef: string, start: string, duration: number): Promise<ScheduledBlock> =>
+  invoke("schedule_task", { vaultPath, taskRef, start, duration });
+
+export const unscheduleTask = (vaultPath: string, date: string, taskRef: string): Promise<void> =>
+  invoke("unschedule_task", { vaultPath, date, taskRef });
+
+export const getDayPlan = (vaultPath: string, date?: string): Promise<DayPlan> =>
+  invoke("get_day_plan", { vaultPath, date });
+
+// ── Journaling prompts ───────────────────────────────────────────────────────
+
+export interface JournalPrompt {
+  text: string;
+  source: "builtin" | "vault"; // vault: diary/templates/prompts.md
+  uses: number;
+  last_used: string | null; // YYYY-MM-DD
+}
+
+export const listJournalPrompts = (vaultPath: string): Promise<JournalPrompt[]> =>
+  invoke("list_journal_prompts", { vaultPath });
+
+/** Add the least-used reflection question to a new diary note; null if it already has one */
+export const insertJournalPrompt = (vaultPath: string, path: string): Promise<string | null> =>
+  invoke("insert_journal_prompt", { vaultPath, path });
+
+// ── Link integrity ───────────────────────────────────────────────────────────
+
+export interface BrokenLink {
+  path: string; // vault-relative note
+  line: number; // 1-based
+  kind: "note" | "asset" | "project" | "goal";
+  target: string; // as written
+  fix: string | null; // where a moved or renamed target is now
+}
+
+export interface LinkReport {
+  notes: number;
+  links: number;
+  broken: BrokenLink[];
+}
+
+/** Broken links and project/goal references in the notes under scope (default the whole vault) */
+export const checkLinks = (vaultPath: string, scope?: string): Promise<LinkReport> =>
+  invoke("check_links", { vaultPath, scope });
+
+/** Apply the `fix` of each link; returns how many were changed */
+export const fixLinks = (vaultPath: string, links: BrokenLink[], dryRun = false): Promise<number> =>
+  invoke("fix_links", { vaultPath, links, dryRun });
+
+// ── Frontmatter schemas ──────────────────────────────────────────────────────
+
+export type SchemaFieldType = "string" | "number" | "integer" | "date" | "boolean" | "list";
+
+/** .lifeos/schemas/<name>.yaml, or a built-in (projects, goals) */
+export interface FrontmatterSchema {
+  name: string;
+  folder: string; // vault-relative; covers every note below it
+  fields: Record<string, { required: boolean; type: SchemaFieldType; enum?: string[]; min?: number; max?: number }>;
+}
+
+export interface SchemaViolation {
+  path: string; // vault-relative
+  schema: string;
+  field: string; // "" when the frontmatter doesn't parse
+  message: string;
+}
+
+export interface SchemaReport {
+  notes: number; // notes a schema applies to
+  violations: SchemaViolation[];
+}
+
+export const listSchemas = (vaultPath: string): Promise<FrontmatterSchema[]> =>
+  invoke("list_schemas", { vaultPath });
+
+/** `scope`: vault-relative folder or note, default the whole vault */
+export const validateNotes = (vaultPath: string, scope?: string): Promise<SchemaReport> =>
+  invoke("validate_notes", { vaultPath, scope });
+
+// ── Note restructuring ───────────────────────────────────────────────────────
+
+/** concatenate: each note in turn under its title; interleave: sections with the same heading together */
+export type MergeStrategy = "concatenate" | "interleave";
+
+export interface MergeReport {
+  target: string; // vault-relative
+  merged: string[]; // sources moved to the trash
+  links: number; // links elsewhere pointed at the target
+}
+
+/** Merge notes into `target` (one of them, an existing note, or a new one), retargeting links to them */
+export const mergeNotes = (vaultPath: string, sources: string[], target: string, strategy: MergeStrategy = "concatenate"): Promise<MergeReport> =>
+  invoke("merge_notes", { vaultPath, sources, target, strategy });
+
+export interface SplitReport {
+  index: string; // the original note, now linking the pieces
+  pieces: string[]; // vault-relative, in order
+  links: number; // note#heading links elsewhere pointed at a piece
+}
+
+/** One note per heading of `level` in `destDir` (default: a folder named after the note) */
+export const splitNote = (vaultPath: string, path: string, level: number, destDir?: string): Promise<SplitReport> =>
+  invoke("split_note", { vaultPath, path, level, destDir });
+
+export interface OutlineHeading {
+  text: string;
+  level: number;
+  line: number; // 1-based, frontmatter counted
+  end_line: number; // last line of the section, subsections included
+  children: OutlineHeading[];
+}
+
+/** The note's headings as a tree */
+export const getNoteOutline = (path: string): Promise<OutlineHeading[]> =>
+  invoke("get_note_outline", { path });
+
+/** A heading's section, up to the next heading of its level or above; matched case-insensitively */
+export const getSection = (path: string, heading: string): Promise<string> =>
+  invoke("get_section", { path, heading });
+
+// ── Canvas ───────────────────────────────────────────────────────────────────
+
+/** JSON Canvas (.canvas, as in Obsidian); fields not listed here are kept on save */
+export type CanvasSide = "top" | "right" | "bottom" | "left";
+
+export interface CanvasNode {
+  id: string;
+  type: "text" | "file" | "link" | "group";
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  color?: string; // preset "1"-"6" or #rrggbb
+  text?: string; // markdown, text nodes
+  file?: string; // vault-relative, file nodes
+  subpath?: string; // #heading within file
+  url?: string; // link nodes
+  label?: string; // group title
+  [key: string]: unknown;
+}
+
+export interface CanvasEdge {
+  id: string;
+  fromNode: string;
+  fromSide?: CanvasSide;
+  toNode: string;
+  toSide?: CanvasSide;
+  color?: string;
+  label?: string;
+  [key: string]: unknown;
+}
+
+export interface Canvas {
+  nodes: CanvasNode[];
+  edges: CanvasEdge[];
+}
+
+export interface CanvasIssue {
+  id: string; // node or edge
+  message: string;
+}
+
+export interface CanvasSummary {
+  path: string; // vault-relative
+  nodes: number;
+  edges: number;
+  references: string[]; // files its nodes embed
+  modified: number; // unix seconds
+}
+
+export const listCanvases = (vaultPath: string): Promise<CanvasSummary[]> =>
+  invoke("list_canvases", { vaultPath });
+
+export const readCanvas = (vaultPath: string, path: string): Promise<Canvas> =>
+  invoke("read_canvas", { vaultPath, path });
+
+/** Resolves to the vault-relative path of the new, empty canvas */
+export const createCanvas = (vaultPath: string, path: string): Promise<string> =>
+  invoke("create_canvas", { vaultPath, path });
+
+/** Rejects a canvas with duplicate ids, dangling edges or missing content; resolves to file nodes whose files don't exist */
+export const saveCanvas = (vaultPath: string, path: string, canvas: Canvas): Promise<CanvasIssue[]> =>
+  invoke("save_canvas", { vaultPath, path, canvas });
+
+/** Moves the canvas to the trash (undoable) */
+export const deleteCanvas = (vaultPath: string, path: string): Promise<void> =>
+  invoke("delete_canvas", { vaultPath, path });
+
+export const validateCanvas = (vaultPath: string, path: string): Promise<CanvasIssue[]> =>
+  invoke("validate_canvas", { vaultPath, path });
+
+/** Resolves to the vault-relative PNG under .lifeos/thumbnails */
+export const generateCanvasThumbnail = (vaultPath: string, path: string): Promise<string> =>
+  invoke("generate_canvas_thumbnail", { vaultPath, path });
+
+// ── Todoist / TickTick ───────────────────────────────────────────────────────
+
+export type TaskProvider = "todoist" | "ticktick";
+
+export interface ExportedTask {
+  provider: TaskProvider;
+  remote_id: string;
+  remote_project?: string;
+  date: string; // day file, YYYY-MM-DD
+  text: string;
+  task_id?: string;
+  pushed: string; // RFC 3339
+  done: boolean;
+}
+
+export interface TaskPullReport {
+  completed: ExportedTask[]; // ticked off locally
+  errors: string[];
+}
+
+/** taskRefs: "YYYY-MM-DD:line" (0-based) or a 🆔 id */
+export const pushTasks = (vaultPath: string, provider: TaskProvider, taskRefs: string[]): Promise<ExportedTask[]> =>
+  invoke("push_tasks", { vaultPath, provider, taskRefs });
+
+export const pullTaskStatus = (vaultPath: string): Promise<TaskPullReport> =>
+  invoke("pull_task_status", { vaultPath });
+
+export const listExportedTasks = (vaultPath: string): Promise<ExportedTask[]> =>
+  invoke("list_exported_tasks", { vaultPath });
+
+// ── Google Calendar ──────────────────────────────────────────────────────────
+
+export interface GoogleStatus {
+  configured: boolean; // google.client_id set in connectors.yaml
+  signed_in: boolean;
+  scopes: string[];
+}
+
+export interface CalendarSyncReport {
+  finished: string; // RFC 3339
+  pulled: number;
+  removed: number;
+  pushed: number; // time blocks created or moved in Google
+  deleted_remote: number;
+  errors: string[];
+}
+
+export const getGoogleStatus = (vaultPath: string): Promise<GoogleStatus> =>
+  invoke("google_status", { vaultPath });
+
+/** Opens the browser and resolves once Google redirects back; scopes default to the calendar */
+export const googleSignIn = (vaultPath: string, scopes?: string[]): Promise<string[]> =>
+  invoke("google_sign_in", { vaultPath, scopes });
+
+export const googleSignOut = (vaultPath: string): Promise<void> =>
+  invoke("google_sign_out", { vaultPath });
+
+export const syncGoogleCalendar = (vaultPath: string): Promise<CalendarSyncReport> =>
+  invoke("sync_google_calendar", { vaultPath });
+
+export const getCalendarSyncStatus = (vaultPath: string): Promise<CalendarSyncReport | null> =>
+  invoke("get_calendar_sync_status", { vaultPath });
+
+export const startCalendarSyncLoop = (vaultPath: string): Promise<void> =>
+  invoke("start_calendar_sync_loop", { vaultPath });
+
+export const stopCalendarSyncLoop = (): Promise<void> =>
+  invoke("stop_calendar_sync_loop");
+
+// ── Chat import ──────────────────────────────────────────────────────────────
+
+export interface ChatImportReport {
+  conversation: string;
+  format: "whatsapp" | "wechat" | "imessage";
+  days: number; // day notes under connectors/chats/ created or extended
+  imported: number;
+  existing: number; // already there from an earlier import
+  skipped: number;
+  linked: string[]; // people slugs
+  unmatched: string[]; // senders without a person note
+  dry_run: boolean;
+}
+
+/** WhatsApp "Export chat" .txt or a WeChat text export; `title` defaults to the file name */
+export const importChat = (vaultPath: string, filePath: string, title?: string, dryRun = false): Promise<ChatImportReport> =>
+  invoke("import_chat", { vaultPath, filePath, title, dryRun });
+
+// ── iMessage ─────────────────────────────────────────────────────────────────
+
+export interface IMessage {
+  at: string; // local time, "YYYY-MM-DDTHH:MM:SS"
+  from_me: boolean;
+  handle: string; // sender's phone or email; empty for my messages
+  text: string;
+}
+
+export interface IMessageConversation {
+  chat_id: number;
+  guid: string;
+  name: string; // group name; empty for one-to-one chats
+  handles: string[];
+  messages: number;
+  last_at: string | null;
+  last_text: string;
+  last_from_me: boolean;
+  people: string[]; // slugs of the people the handles belong to
+  recent: IMessage[]; // oldest first; only filled by getPersonIMessages
+}
+
+/** "denied" means the app needs Full Disk Access to read chat.db */
+export const getIMessageAccess = (): Promise<"granted" | "denied" | "missing" | "unsupported"> => invoke("get_imessage_access");
+
+export const openFullDiskAccessSettings = (): Promise<void> => invoke("open_full_disk_access_settings");
+
+export const listIMessageConversations = (vaultPath: string, limit?: number): Promise<IMessageConversation[]> =>
+  invoke("list_imessage_conversations", { vaultPath, limit });
+
+/** Conversations with the person's emails and phones, each with its latest messages */
+export const getPersonIMessages = (vaultPath: string, slug: string, limit?: number): Promise<IMessageConversation[]> =>
+  invoke("get_person_imessages", { vaultPath, slug, limit });
+
+/** Files whole threads into connectors/chats/ day notes; again later adds only new messages */
+export const archiveIMessageThreads = (vaultPath: string, chatIds: number[], dryRun = false): Promise<ChatImportReport[]> =>
+  invoke("archive_imessage_threads", { vaultPath, chatIds, dryRun });
+
+// ── Commit summary ───────────────────────────────────────────────────────────
+
+export interface CommitSummary {
+  date: string;
+  repos: number; // repositories searched under git.roots
+  commits: { repo: string; hash: string; time: string; subject: string }[];
+}
+
+/** Writes the day's commits (default today) into the daily note's 提交记录 section */
+export const appendCommitSummary = (vaultPath: string, date?: string): Promise<CommitSummary> =>
+  invoke("append_commit_summary", { vaultPath, date });
 
-export const saveMenuConfig = (vaultPath: string, content: string): Promise<void> =>
-  invoke("save_menu_config", { vaultPath, content });
+export const startCommitSummaryLoop = (vaultPath: string): Promise<void> =>
+  invoke("start_commit_summary_loop", { vaultPath });
 
-export const loadBoardConfig = (vaultPath: string): Promise<string> =>
-  invoke("load_board_config", { vaultPath });
+export const stopCommitSummaryLoop = (): Promise<void> =>
+  invoke("stop_commit_summary_loop");
 
-export const saveBoardConfig = (vaultPath: string, content: string): Promise<void> =>
-  invoke("save_board_config", { vaultPath, content });
+// ── Mail digest ──────────────────────────────────────────────────────────────
 
-export const regenerateSkills = (vaultPath: string): Promise<void> =>
-  invoke("regenerate_skills", { vaultPath });
+export interface MailDigest {
+  date: string;
+  path: string; // daily/mail/<date>.md
+  since: string; // start of the period covered, RFC 3339
+  messages: {
+    account: string;
+    email_id: string;
+    from: string;
+    subject: string;
+    received: string;
+    importance: "important" | "other" | "bulk";
+    summary?: string; // from the AI CLI when enabled
+  }[];
+}
 
-export const loadAppSettings = (vaultPath: string): Promise<string> =>
-  invoke("load_app_settings", { vaultPath });
+/** Writes the morning mail digest for `date` (default today); `ai` overrides mail_digest.ai */
+export const generateMailDigest = (vaultPath: string, date?: string, ai?: boolean): Promise<MailDigest> =>
+  invoke("generate_mail_digest", { vaultPath, date, ai });
 
-export const saveAppSettings = (vaultPath: string, content: string): Promise<void> =>
-  invoke("save_app_settings", { vaultPath, content });
+export const startMailDigestLoop = (vaultPath: string): Promise<void> =>
+  invoke("start_mail_digest_loop", { vaultPath });
+
+export const stopMailDigestLoop = (): Promise<void> =>
+  invoke("stop_mail_digest_loop");
+
+// ── Reader ───────────────────────────────────────────────────────────────────
+
+export interface ReadingProgress {
+  position: number; // 0–1, fraction scrolled past
+  finished: boolean;
+  updatedAt: string;
+}
+
+export interface ReaderView {
+  accountId: string;
+  emailId: string;
+  subject: string;
+  from: string;
+  date: string;
+  listId: string | null;
+  html: string; // text structure only
+  markdown: string;
+  remoteBlocked: number;
+  minutes: number;
+  progress: ReadingProgress;
+}
+
+export const getReaderView = (vaultPath: string, accountId: string, emailId: string, allowRemote?: boolean): Promise<ReaderView> =>
+  invoke("get_reader_view", { vaultPath, accountId, emailId, allowRemote });
+
+export const saveReadingPosition = (
+  vaultPath: string,
+  accountId: string,
+  emailId: string,
+  position: number,
+  finished?: boolean
+): Promise<ReadingProgress> =>
+  invoke("save_reading_position", { vaultPath, accountId, emailId, position, finished });
+
+// ── Outbox ───────────────────────────────────────────────────────────────────
+
+/** Cached message and how to reach its server */
+export interface RemoteMessage {
+  accountId: string;
+  emailId: string;
+  folder: string | null;
+  imapHost: string | null;
+  imapPort: number | null;
+  imapPassword: string | null;
+  email: string | null;
+}
+
+export type OutboxOperation =
+  | { kind: "send_email"; request: SendEmailRequest }
+  | { kind: "set_read"; message: RemoteMessage; read: boolean }
+  | { kind: "archive"; message: RemoteMessage }
+  | { kind: "calendar_sync" };
+
+/** Network operation waiting in .lifeos/outbox.json */
+export interface QueuedOperation {
+  id: string;
+  queuedAt: string;
+  operation: OutboxOperation;
+  attempts: number; // replays rejected for another reason than the network
+  lastError?: string;
+}
+
+export interface FlushReport {
+  sent: number;
+  failed: number;
+  remaining: number;
+  offline: boolean; // the network went away mid-replay
+}
+
+export const getOutbox = (vaultPath: string): Promise<QueuedOperation[]> =>
+  invoke("get_outbox", { vaultPath });
+
+export const flushOutbox = (vaultPath: string): Promise<FlushReport> =>
+  invoke("flush_outbox", { vaultPath });
+
+export const discardOutboxOperation = (vaultPath: string, id: string): Promise<boolean> =>
+  invoke("discard_outbox_operation", { vaultPath, id });
+
+export const startOutboxLoop = (vaultPath: string): Promise<void> =>
+  invoke("start_outbox_loop", { vaultPath });
+
+export const stopOutboxLoop = (): Promise<void> =>
+  invoke("stop_outbox_loop");
+
+export const onOutboxChanged = (cb: (pending: QueuedOperation[]) => void): Promise<UnlistenFn> =>
+  listen<QueuedOperation[]>("outbox-changed", (e) => cb(e.payload));
+
+// ── Audit log ────────────────────────────────────────────────────────────────
+
+/** One change to the vault, from .lifeos/logs/audit.jsonl */
+export interface AuditEntry {
+  at: string;
+  actor: string; // "app", "app:<window>", "automation:<rule>", "cli", or an agent's own name
+  action: string; // command name or automation action, else "write", "delete", "move" or "run"
+  path?: string; // vault-relative when inside the vault
+  dest?: string;
+  detail?: string; // program run by run_shell_command, without its arguments
+}
+
+export interface AuditQuery {
+  actor?: string; // exact, or a prefix ending in ":"
+  action?: string;
+  path?: string; // substring of path or dest
+  since?: string; // RFC 3339 or YYYY-MM-DD, inclusive
+  until?: string;
+  limit?: number; // default 200
+}
+
+/** Newest first */
+export const queryAuditLog = (vaultPath: string, filter?: AuditQuery): Promise<AuditEntry[]> =>
+  invoke("query_audit_log", { vaultPath, filter });
+
+// ── Dev environment ──────────────────────────────────────────────────────────
+
+export interface DevTool {
+  name: string;
+  version: string | null; // null when not installed
+  path: string | null;
+}
+
+export interface PendingUpdate {
+  source: "brew" | "cask" | "npm" | "rustup";
+  name: string;
+  current: string;
+  latest: string;
+}
+
+export interface DevEnvironment {
+  os: string;
+  tools: DevTool[];
+  updates: PendingUpdate[];
+  checked_at: string; // RFC 3339
+}
+
+/** Toolchain versions and pending updates; cached for 30 minutes unless `refresh` */
+export const getDevEnvironment = (refresh?: boolean): Promise<DevEnvironment> =>
+  invoke("get_dev_environment", { refresh });
+
+// ── Storage ──────────────────────────────────────────────────────────────────
+
+export type StorageCategory = "notes" | "mail" | "assets" | "app" | "other";
+
+export interface SizeEntry {
+  path: string; // relative to the analyzed root; "." for files at the top
+  bytes: number;
+  files: number;
+}
+
+export interface StorageReport {
+  root: string;
+  bytes: number;
+  files: number;
+  folders: SizeEntry[]; // up to two levels deep, largest first
+  largest_files: SizeEntry[];
+  categories: { category: StorageCategory; bytes: number; files: number }[];
+  scanned_at: string;
+}
+
+/** Size breakdown of `root`; cached for 10 minutes unless `refresh` */
+export const analyzeStorage = (root: string, refresh?: boolean): Promise<StorageReport> =>
+  invoke("analyze_storage", { root, refresh });
+
+// ── Automations ──────────────────────────────────────────────────────────────
+
+/** Rule as stored in .lifeos/automations/{id}.yaml */
+export interface AutomationRule {
+  id: string;
+  name: string;
+  enabled: boolean;
+  trigger:
+    | { type: "schedule"; every?: string; at?: string; days?: number[] }
+    | { type: "file-change"; path: string }
+    | { type: "new-email"; from?: string; subject?: string; account?: string }
+    | { type: "task-done"; contains?: string; tag?: string };
+  actions: Array<
+    | { type: "run-shortcut"; name: string }
+    | { type: "move-file"; from?: string; to: string }
+    | { type: "create-note"; path: string; content?: string; append?: boolean }
+    | { type: "webhook"; url: string; method?: string; body?: string }
+  >;
+}
+
+export interface AutomationRuleFile {
+  path: string;
+  rule: AutomationRule | null;
+  error: string | null;
+}
+
+export interface AutomationRun {
+  ruleId: string;
+  trigger: string;
+  started: string;
+  ok: boolean;
+  actions: { action: string; ok: boolean; message: string }[];
+  dryRun?: boolean; // planned only; not in the run log
+}
+
+export const startAutomations = (vaultPath: string): Promise<void> =>
+  invoke("start_automations", { vaultPath });
+
+export const stopAutomations = (): Promise<void> =>
+  invoke("stop_automations");
+
+export const listAutomations = (vaultPath: string): Promise<AutomationRuleFile[]> =>
+  invoke("list_automations", { vaultPath });
+
+/** `dryRun` resolves each action and reports what it would do, changing nothing */
+export const runAutomation = (vaultPath: string, id: string, dryRun = false): Promise<AutomationRun> =>
+  invoke("run_automation", { vaultPath, id, dryRun });
+
+export const getAutomationRuns = (vaultPath: string, limit?: number): Promise<AutomationRun[]> =>
+  invoke("get_automation_runs", { vaultPath, limit });
+
+export const onAutomationRun = (cb: (run: AutomationRun) => void): Promise<UnlistenFn> =>
+  listen<AutomationRun>("automation-run", (e) => cb(e.payload));
 
 export const pickVaultFolder = async (): Promise<string | null> => {
   const selected = await open({ directory: true, multiple: false });
   return selected as string | null;
 };
 
+export const pickFiles = async (defaultPath?: string): Promise<string[]> => {
+  const selected = await open({ multiple: true, defaultPath });
+  return (selected as string[] | null) ?? [];
+};
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Generic FS
 // ─────────────────────────────────────────────────────────────────────────────
@@ -48,8 +2116,46 @@ export const pickVaultFolder = async (): Promise<string | null> => {
 export const readFile = (path: string): Promise<string> =>
   invoke("read_file", { path });
 
-export const writeFile = (path: string, content: string): Promise<void> =>
-  invoke("write_file", { path, content });
+/** What a reader saw of a file; pass it back to refuse overwriting later changes */
+export interface FileVersion {
+  modified: string;
+  hash: string; // SHA-256 hex; "" when writing means "must not exist yet"
+}
+
+export interface VersionedFile extends FileVersion {
+  content: string;
+}
+
+/** Rejection of a guarded write; other failures reject with a message string */
+export interface WriteConflict {
+  kind: "conflict";
+  path: string;
+  content: string | null; // null when the file was deleted
+  version: FileVersion | null;
+}
+
+export const isWriteConflict = (e: unknown): e is WriteConflict =>
+  typeof e === "object" && e !== null && (e as { kind?: string }).kind === "conflict";
+
+/** write_note refused frontmatter that breaks its folder's schema */
+export interface WriteInvalid {
+  kind: "invalid";
+  path: string;
+  violations: SchemaViolation[];
+}
+
+export const isWriteInvalid = (e: unknown): e is WriteInvalid =>
+  typeof e === "object" && e !== null && (e as { kind?: string }).kind === "invalid";
+
+/** Schema violations one per line; any other error as is */
+export const writeErrorMessage = (e: unknown): string =>
+  isWriteInvalid(e) ? e.violations.map((v) => v.message).join("\n") : String(e);
+
+export const readFileVersioned = (path: string): Promise<VersionedFile> =>
+  invoke("read_file_versioned", { path });
+
+export const writeFile = (path: string, content: string, expected?: Partial<FileVersion>): Promise<FileVersion> =>
+  invoke("write_file", { path, content, expectedHash: expected?.hash, expectedModified: expected?.modified });
 
 export const deleteFile = (path: string): Promise<void> =>
   invoke("delete_file", { path });
@@ -60,8 +2166,83 @@ export const fileExists = (path: string): Promise<boolean> =>
 export const createDirAll = (path: string): Promise<void> =>
   invoke("create_dir_all", { path });
 
-export const moveFile = (src: string, dest: string): Promise<void> =>
-  invoke("move_file", { src, dest });
+/** When `dest` exists: replace it (default), move to "name (1).ext", or leave both */
+export type MoveCollision = "overwrite" | "rename" | "skip";
+
+/** Resolves to where it ended up, or null when skipped */
+export const moveFile = (src: string, dest: string, onCollision?: MoveCollision): Promise<string | null> =>
+  invoke("move_file", { src, dest, onCollision });
+
+export interface MoveProgress {
+  src: string;
+  dest: string;
+  bytes: number;
+  total: number;
+}
+
+/** Large moves to another drive, which are copied and verified */
+export const onMoveProgress = (cb: (progress: MoveProgress) => void): Promise<UnlistenFn> =>
+  listen<MoveProgress>("move-progress", (e) => cb(e.payload));
+
+export type BatchOp =
+  | { kind: "move"; src: string; dest: string }
+  | { kind: "copy"; src: string; dest: string }
+  | { kind: "delete"; path: string }
+  | { kind: "create"; path: string; content?: string }; // a folder without content
+
+export interface BatchReport {
+  ok: boolean;
+  results: { status: "done" | "failed" | "rolled_back" | "skipped"; error?: string }[]; // one per op
+}
+
+/** Run `ops` in order as one change; when one fails, those before it are reversed. Never overwrites. */
+export const batchFsOperations = (ops: BatchOp[]): Promise<BatchReport> =>
+  invoke("batch_fs_operations", { ops });
+
+export interface JournalOperation {
+  id: string;
+  kind: "delete" | "move";
+  timestamp: string;
+  /** Vault-relative; where a move started */
+  path: string;
+  dest?: string;
+  trash?: string;
+}
+
+export const listRecentOperations = (vaultPath: string, limit?: number): Promise<JournalOperation[]> =>
+  invoke("list_recent_operations", { vaultPath, limit });
+
+export const undoLastOperation = (vaultPath: string): Promise<JournalOperation | null> =>
+  invoke("undo_last_operation", { vaultPath });
+
+/** Advisory lock from .lifeos/locks.json, shared with agents editing the vault */
+export interface NoteLock {
+  path: string; // vault-relative
+  owner: string;
+  acquired_at: string;
+  expires_at: string;
+}
+
+/** Take or renew the lock for `ttl` seconds (default 300); rejects while another owner holds it */
+export const lockNote = (vaultPath: string, path: string, owner: string, ttl?: number): Promise<NoteLock> =>
+  invoke("lock_note", { vaultPath, path, owner, ttl });
+
+export const unlockNote = (vaultPath: string, path: string, owner: string, force?: boolean): Promise<boolean> =>
+  invoke("unlock_note", { vaultPath, path, owner, force });
+
+export const getNoteLock = (vaultPath: string, path: string): Promise<NoteLock | null> =>
+  invoke("get_note_lock", { vaultPath, path });
+
+export const listNoteLocks = (vaultPath: string): Promise<NoteLock[]> =>
+  invoke("list_note_locks", { vaultPath });
+
+/** Current path of the note whose frontmatter `uid` is `id`, across renames and moves */
+export const resolveNoteId = (vaultPath: string, id: string): Promise<string> =>
+  invoke("resolve_note_id", { vaultPath, id });
+
+/** The note's `uid`, added to its frontmatter when missing */
+export const assignNoteId = (vaultPath: string, path: string): Promise<string> =>
+  invoke("assign_note_id", { vaultPath, path });
 
 export const listDir = (
   path: string,
@@ -79,14 +2260,75 @@ export const readNote = (path: string): Promise<NoteFile> =>
 export const writeNote = (
   path: string,
   frontmatter: Record<string, unknown>,
-  content: string
-): Promise<void> => invoke("write_note", { path, frontmatter, content });
+  content: string,
+  expected?: Partial<FileVersion>
+): Promise<FileVersion> =>
+  invoke("write_note", { path, frontmatter, content, expectedHash: expected?.hash, expectedModified: expected?.modified });
 
 export const listNotes = (
   dir: string,
   recursive = false
 ): Promise<NoteFile[]> => invoke("list_notes", { dir, recursive });
 
+export interface NoteMatch {
+  path: string;
+  line: number; // 1-based, within the page for PDFs
+  text: string;
+  page?: number; // set for hits in extracted PDF text
+}
+
+export const searchNotes = (vaultPath: string, query: string, limit?: number): Promise<NoteMatch[]> =>
+  invoke("search_notes", { vaultPath, query, limit });
+
+export interface ResolvedNote {
+  content: string; // frontmatter kept, ![[note#section]] embeds expanded
+  sources: string[]; // vault-relative notes pulled in
+  unresolved: string[]; // embeds left as written (missing or cyclic)
+}
+
+export const resolveEmbeds = (vaultPath: string, path: string): Promise<ResolvedNote> =>
+  invoke("resolve_embeds", { vaultPath, path });
+
+/** Append a task to daily/tasks/{date}.md (today by default); resolves to the file path */
+export const addTask = (vaultPath: string, text: string, date?: string): Promise<string> =>
+  invoke("add_task", { vaultPath, text, date });
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Platform
+// ─────────────────────────────────────────────────────────────────────────────
+
+/** Which native integrations this build supports (macOS-only ones are false elsewhere) */
+export interface PlatformInfo {
+  os: string;
+  mobile: boolean;
+  finder: boolean;
+  shell: boolean;
+  git: boolean;
+  shortcuts: boolean;
+  launchd: boolean;
+  apple_notes: boolean;
+  keychain: boolean;
+  screenshot: boolean;
+  spotlight: boolean;
+}
+
+export const getPlatformInfo = (): Promise<PlatformInfo> =>
+  invoke("get_platform_info");
+
+export interface PermissionStatus {
+  id: "automation_notes" | "automation_calendar" | "full_disk_access" | "notifications" | "accessibility";
+  status: "granted" | "denied" | "not_determined" | "unknown" | "unsupported";
+  hint: string; // what to do about it; empty once granted
+  settings_url: string;
+}
+
+/** The Automation probes may show macOS's consent dialog the first time */
+export const checkSystemPermissions = (): Promise<PermissionStatus[]> =>
+  invoke("check_system_permissions");
+
+export const openPermissionSettings = (permission: PermissionStatus["id"]): Promise<void> =>
+  invoke("open_permission_settings", { permission });
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Extra: System & Tools
 // ─────────────────────────────────────────────────────────────────────────────
@@ -95,6 +2337,23 @@ export const listNotes = (
 export const openInFinder = (path: string): Promise<void> =>
   invoke("open_in_finder", { path });
 
+/** .lifeos/editors.yaml; built in: vscode, cursor, zed, sublime, obsidian, typora, system */
+export interface EditorSettings {
+  default?: string;
+  extensions: Record<string, string>; // "md" → "obsidian"
+  editors: Record<string, { command: string; args?: string[] }>; // args may use {path} and {line}
+}
+
+/** Open a note in the chosen, extension-mapped or default editor; resolves to the editor used */
+export const openInEditor = (vaultPath: string, path: string, opts: { line?: number; editor?: string } = {}): Promise<string> =>
+  invoke("open_in_editor", { vaultPath, path, ...opts });
+
+export const getEditorSettings = (vaultPath: string): Promise<EditorSettings> =>
+  invoke("get_editor_settings", { vaultPath });
+
+export const saveEditorSettings = (vaultPath: string, settings: EditorSettings): Promise<void> =>
+  invoke("save_editor_settings", { vaultPath, settings });
+
 /** Run a shell command and return stdout */
 export const runShellCommand = (
   command: string,
@@ -116,6 +2375,10 @@ export const scanGitRepos = (
 ): Promise<GitRepo[]> =>
   invoke("scan_git_repos", { root, maxDepth });
 
+/** TODO/FIXME/HACK comments (or `patterns`) in the repos under `root`, .gitignore respected */
+export const scanCodeTodos = (root: string, patterns?: string[], maxDepth = 5): Promise<CodeTodo[]> =>
+  invoke("scan_code_todos", { root, patterns, maxDepth });
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Extra: Skills Manager
 // ─────────────────────────────────────────────────────────────────────────────
@@ -139,6 +2402,10 @@ export const listLaunchdTasks = (): Promise<ScheduledTask[]> =>
 export const deleteLaunchdTask = (id: string): Promise<void> =>
   invoke("delete_launchd_task", { id });
 
+/** Jobs a task with program "internal" can run (its first arg), e.g. "mail-sync" */
+export const listInternalJobs = (): Promise<string[]> =>
+  invoke("list_internal_jobs");
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Apple Notes (备忘录)
 // ─────────────────────────────────────────────────────────────────────────────
@@ -193,9 +2460,28 @@ export interface EmailMessage {
   date: string;
   bodyText?: string;
   bodyHtml?: string;
-  attachments: string[];
+  attachments: MailAttachment[];
   flags: string[];
   folder: string;
+  remoteBlocked?: number; // remote images/styles held back from bodyHtml
+  trackers?: EmailTracker[];
+  listId?: string; // set on mailing-list mail (newsletters)
+  inReplyTo?: string; // Message-ID of the message this answers
+  replies?: string[]; // Message-IDs of replies sent from here
+}
+
+export interface MailAttachment {
+  name: string;
+  size: number; // decoded bytes
+  contentType: string;
+}
+
+/** Tracking pixel or wrapped link found in a message's HTML */
+export interface EmailTracker {
+  kind: "pixel" | "link";
+  url: string;
+  service: string | null; // e.g. "Mailchimp"
+  destination: string | null; // where a wrapped link really goes
 }
 
 export const imapSync = (
@@ -221,12 +2507,245 @@ export const imapSync = (
 export const getCachedEmails = (vaultPath: string, accountId: string, offset?: number, limit?: number): Promise<EmailMessage[]> =>
   invoke("get_cached_emails", { vaultPath, accountId, offset, limit });
 
-export const getEmailContent = (vaultPath: string, accountId: string, emailId: string): Promise<EmailMessage> =>
-  invoke("get_email_content", { vaultPath, accountId, emailId });
+/** unwrapLinks: point wrapped tracking links straight at their destination */
+export const getEmailContent = (vaultPath: string, accountId: string, emailId: string, unwrapLinks?: boolean): Promise<EmailMessage> =>
+  invoke("get_email_content", { vaultPath, accountId, emailId, unwrapLinks });
+
+/** getEmailContent with remote images and styles left in */
+export const loadRemoteContent = (vaultPath: string, accountId: string, emailId: string, unwrapLinks?: boolean): Promise<EmailMessage> =>
+  invoke("load_remote_content", { vaultPath, accountId, emailId, unwrapLinks });
+
+/** Save attachment `index` of a cached message to a file, or into a folder under its own name; resolves to the file written */
+export const downloadAttachment = (vaultPath: string, accountId: string, emailId: string, index: number, dest: string): Promise<string> =>
+  invoke("download_attachment", { vaultPath, accountId, emailId, index, dest });
 
 export const listEmailFolders = (vaultPath: string): Promise<string[]> =>
   invoke("list_email_folders", { vaultPath });
 
+export interface RemoteFolder {
+  name: string;
+  subscribed: boolean; // in the server's LSUB list
+  selectable: boolean; // false for \Noselect containers
+  synced: boolean; // pulled by sync (the account's `folders`)
+}
+
+/** IMAP LIST/LSUB for the account, marked with what sync currently pulls */
+export const discoverEmailFolders = (vaultPath: string, accountId: string): Promise<RemoteFolder[]> =>
+  invoke("discover_email_folders", { vaultPath, accountId });
+
+/** Written to the account file's `folders` */
+export const setSyncedFolders = (vaultPath: string, accountId: string, folders: string[]): Promise<void> =>
+  invoke("set_synced_folders", { vaultPath, accountId, folders });
+
+export interface MailSyncReport {
+  accountId: string;
+  folder: string;
+  dir: string; // Mailbox/ directory it was cached in, the accountId for getCachedEmails: <account> or <account>/<folder>
+  fetched: number;
+  new: number; // fetched messages that weren't cached before
+  error: string | null;
+}
+
+/** Every selectable folder the server LISTs for one account, newest `limit` (default 20) each into Mailbox/<account>/<folder>; one report per folder */
+export const imapSyncAll = (vaultPath: string, accountId: string, limit?: number): Promise<MailSyncReport[]> =>
+  invoke("imap_sync_all", { vaultPath, accountId, limit });
+
+/** Every enabled account's chosen folders, newest `limit` (default 20) each */
+export const syncAllAccounts = (vaultPath: string, limit?: number): Promise<MailSyncReport[]> =>
+  invoke("sync_all_accounts", { vaultPath, limit });
+
+/** A message cached under several accounts (or folders) with one Message-ID */
+export interface DuplicateEmailGroup {
+  messageId: string;
+  subject: string;
+  from: string;
+  copies: { messageId: string; account: string; emailId: string; subject: string; from: string; date: string }[]; // first stays in view
+}
+
+export type UnifiedEmail = EmailMessage & {
+  account: string; // Mailbox/<account> directory
+  alsoIn: string[]; // accounts whose copy was merged into this row
+};
+
+export const findDuplicateEmails = (vaultPath: string): Promise<DuplicateEmailGroup[]> =>
+  invoke("find_duplicate_emails", { vaultPath });
+
+/** Every account's cached folder (INBOX by default), newest first; duplicates merged unless `mergeDuplicates` is false */
+export const getUnifiedInbox = (vaultPath: string, folder?: string, limit?: number, mergeDuplicates?: boolean): Promise<UnifiedEmail[]> =>
+  invoke("get_unified_inbox", { vaultPath, folder, limit, mergeDuplicates });
+
+export interface MailSyncAttempt {
+  at: string;
+  folder: string;
+  ok: boolean;
+  latencyMs: number;
+  error?: string;
+  authError: boolean; // the server rejected the credentials
+}
+
+export interface AccountHealth {
+  accountId: string;
+  status: "ok" | "degraded" | "failing" | "unknown";
+  consecutiveFailures: number;
+  authError: boolean;
+  lastSuccess: string | null;
+  lastFailure: string | null;
+  lastError: string | null;
+  successRate: number; // 0–1 over the kept attempts
+  avgLatencyMs: number | null;
+  attempts: MailSyncAttempt[]; // newest first
+}
+
+/** Sync history of every account on this device, most consecutive failures first */
+export const getAccountHealth = (vaultPath: string): Promise<AccountHealth[]> =>
+  invoke("get_account_health", { vaultPath });
+
+/** Fires once when an account starts failing (3 failed syncs in a row or a rejected login) */
+export const onMailAccountWarning = (cb: (health: AccountHealth) => void): Promise<UnlistenFn> =>
+  listen<AccountHealth>("mail-account-warning", (e) => cb(e.payload));
+
+export interface NewMail {
+  accountId: string;
+  folder: string;
+  emails: EmailMessage[]; // newly synced, newest first
+}
+
+/** Watch the INBOX of every enabled IMAP account over IDLE; resolves to the account ids watched */
+export const startMailIdle = (vaultPath: string): Promise<string[]> =>
+  invoke("start_mail_idle", { vaultPath });
+
+export const stopMailIdle = (): Promise<void> =>
+  invoke("stop_mail_idle");
+
+/** Fires when an IDLE session sees new mail, after it has been synced to the cache */
+export const onNewMail = (cb: (mail: NewMail) => void): Promise<UnlistenFn> =>
+  listen<NewMail>("new-mail", (e) => cb(e.payload));
+
+export interface MailSynced {
+  new: number; // across all accounts
+  reports: MailSyncReport[];
+}
+
+/** Sync every enabled account now and every `interval` minutes, emitting "mail-synced" after each round */
+export const startAutoSync = (vaultPath: string, interval: number): Promise<void> =>
+  invoke("start_auto_sync", { vaultPath, interval });
+
+export const stopAutoSync = (): Promise<void> =>
+  invoke("stop_auto_sync");
+
+export const onMailSynced = (cb: (synced: MailSynced) => void): Promise<UnlistenFn> =>
+  listen<MailSynced>("mail-synced", (e) => cb(e.payload));
+
+/** A message a sync found past its folder's last seen UID; open it with `accountId` + `emailId` */
+export interface ArrivedMail {
+  accountId: string;
+  folder: string;
+  emailId: string;
+  from: string;
+  subject: string;
+}
+
+export const onMailArrived = (cb: (arrived: ArrivedMail[]) => void): Promise<UnlistenFn> =>
+  listen<ArrivedMail[]>("mail-arrived", (e) => cb(e.payload));
+
+/** Google sign-in for Gmail (synced over its API, no app password); resolves to the new account id */
+export const connectGmail = (vaultPath: string): Promise<string> =>
+  invoke("connect_gmail", { vaultPath });
+
+export interface DiagnosticStep {
+  step: "dns" | "tcp" | "tls" | "auth" | "folders";
+  status: "ok" | "warning" | "failed" | "skipped";
+  detail: string;
+  millis: number;
+}
+
+export interface AccountDiagnostics {
+  ok: boolean;
+  steps: DiagnosticStep[]; // up to and including the first failure
+  certificate: {
+    subject: string | null;
+    issuer: string | null;
+    not_after: string | null; // YYYY-MM-DD
+    sha256: string;
+    trusted: boolean;
+  } | null;
+  folders: string[];
+}
+
+/** Step-by-step connection check for the account setup wizard */
+export const testEmailAccount = (account: ImapAccount): Promise<AccountDiagnostics> =>
+  invoke("test_email_account", {
+    account: {
+      email: account.email,
+      password: account.password,
+      imap_host: account.imapHost,
+      imap_port: account.imapPort,
+      protocol: account.protocol,
+      account_id: account.account_id,
+    },
+  });
+
+export interface ServerSettings {
+  host: string;
+  port: number;
+  security: "ssl" | "starttls" | "none";
+  username: string;
+}
+
+export interface EmailSettings {
+  source: "builtin" | "autoconfig" | "srv";
+  imap: ServerSettings | null;
+  pop3: ServerSettings | null;
+  smtp: ServerSettings | null;
+}
+
+/** Prefill servers from the address alone; null when nothing is found */
+export const discoverEmailSettings = (address: string): Promise<EmailSettings | null> =>
+  invoke("discover_email_settings", { address });
+
+export interface EmlImportReport {
+  imported: EmailMessage[];
+  duplicates: number; // already cached under the same Message-ID
+  failed: [string, string][]; // [path, error]
+  dry_run: boolean; // counted only, nothing written
+}
+
+/** Add .eml files from other clients to Mailbox/<accountId>/ under `folder` (default INBOX) */
+export const importEmlFiles = (
+  vaultPath: string,
+  paths: string[],
+  accountId: string,
+  folder?: string,
+  dryRun = false
+): Promise<EmlImportReport> => invoke("import_eml_files", { vaultPath, paths, accountId, folder, dryRun });
+
+export interface ArchiveMailbox {
+  name: string; // as in the archive, nested mailboxes joined with "/"
+  folder: string; // cache folder it is filed under
+  messages: number;
+}
+
+export interface ArchiveImportReport {
+  imported: number;
+  duplicates: number; // already cached under the same Message-ID
+  mailboxes: ArchiveMailbox[]; // messages added per mailbox
+  failed: [string, string][]; // [message in the archive, error]
+  dryRun: boolean; // counted only, nothing written
+}
+
+/** Mailboxes in an Apple Mail folder (~/Library/Mail/V10 or part of it) or an Outlook .olm */
+export const scanMailArchive = (path: string): Promise<ArchiveMailbox[]> =>
+  invoke("scan_mail_archive", { path });
+
+/** Import the chosen mailboxes (all when omitted) into Mailbox/<accountId>/ */
+export const importMailArchive = (
+  vaultPath: string,
+  path: string,
+  accountId: string,
+  mailboxes?: string[],
+  dryRun = false
+): Promise<ArchiveImportReport> => invoke("import_mail_archive", { vaultPath, path, accountId, mailboxes, dryRun });
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Email / SMTP Send
 // ─────────────────────────────────────────────────────────────────────────────
@@ -242,12 +2761,39 @@ export interface SendEmailRequest {
   to: string;
   subject: string;
   body: string;
-  in_reply_to?: string;
+  in_reply_to?: string; // email id or Message-ID; sets In-Reply-To/References and a single "Re:"
+  // With vault_path and account_id, identity_id (else the account's default identity) sets the sender
+  vault_path?: string;
+  account_id?: string;
+  identity_id?: string;
+  follow_up_by?: string; // YYYY-MM-DD: wait for a reply by then; needs vault_path
+  attachments?: string[]; // vault files, absolute or relative to vault_path
+  body_html?: string; // HTML version of body, sent as multipart/alternative
 }
 
-export const sendEmail = (request: SendEmailRequest): Promise<void> =>
+/** Resolves to the queued operation when offline (needs vault_path), else null */
+export const sendEmail = (request: SendEmailRequest): Promise<QueuedOperation | null> =>
   invoke("send_email", { request });
 
+/** Alias / plus-address an account can send as, from .lifeos/identities.yaml */
+export interface EmailIdentity {
+  id: string; // derived from the address when saved blank
+  address: string;
+  name: string;
+  signature: string; // appended below "-- "
+  folder?: string; // cache folder a sent copy is filed under
+  default: boolean;
+}
+
+export const listEmailIdentities = (vaultPath: string, accountId: string): Promise<EmailIdentity[]> =>
+  invoke("list_email_identities", { vaultPath, accountId });
+
+export const saveEmailIdentity = (vaultPath: string, accountId: string, identity: EmailIdentity): Promise<EmailIdentity> =>
+  invoke("save_email_identity", { vaultPath, accountId, identity });
+
+export const deleteEmailIdentity = (vaultPath: string, accountId: string, identityId: string): Promise<void> =>
+  invoke("delete_email_identity", { vaultPath, accountId, identityId });
+
 export const deleteEmail = (
   vaultPath: string,
   accountId: string,
@@ -292,5 +2838,26 @@ export const markEmailRead = (
     email,
   });
 
+export const archiveEmail = (
+  vaultPath: string,
+  accountId: string,
+  emailId: string,
+  folder?: string,
+  imapHost?: string,
+  imapPort?: number,
+  imapPassword?: string,
+  email?: string
+): Promise<void> =>
+  invoke("archive_email", {
+    vaultPath,
+    accountId,
+    emailId,
+    folder,
+    imapHost,
+    imapPort,
+    imapPassword,
+    email,
+  });
+
 export const openExternalUrl = (url: string): Promise<void> =>
   invoke("open_external_url", { url });
@@ -1,5 +1,5 @@
 import { create } from "zustand";
-import { saveMenuConfig, loadMenuConfig, saveAppSettings, loadAppSettings } from "@/services/fs";
+import { saveMenuConfig, loadMenuConfig, saveAppSettings, loadAppSettings, setLocale } from "@/services/fs";
 import type {
   ViewId,
   DayNote,
@@ -14,6 +14,7 @@ import type {
   GitRepo,
   ScheduledTask,
   Theme,
+  Language,
   MenuConfig,
   EmailAccount,
   Email,
@@ -149,10 +150,12 @@ function settingsToYaml(theme: string, claudeCodeEnabled: boolean, claudeCodePat
   if (defaultProject) {
     yaml += `defaultProject: ${defaultProject}\n`;
   }
+  // Read by the backend to localise its error messages
+  yaml += `language: ${useStore.getState().language}\n`;
   return yaml;
 }
 
-function parseSettingsYaml(yaml: string): { theme?: string; claudeCodeEnabled?: boolean; claudeCodePath?: string; defaultProject?: string } {
+function parseSettingsYaml(yaml: string): { theme?: string; claudeCodeEnabled?: boolean; claudeCodePath?: string; defaultProject?: string; language?: string } {
   const result: any = {};
   for (const line of yaml.split("\n")) {
     const trimmed = line.trim();
@@ -160,6 +163,7 @@ function parseSettingsYaml(yaml: string): { theme?: string; claudeCodeEnabled?:
     if (trimmed.startsWith("claudeCodeEnabled:")) result.claudeCodeEnabled = trimmed.replace("claudeCodeEnabled:", "").trim() === "true";
     if (trimmed.startsWith("claudeCodePath:")) result.claudeCodePath = trimmed.replace("claudeCodePath:", "").trim();
     if (trimmed.startsWith("defaultProject:")) result.defaultProject = trimmed.replace("defaultProject:", "").trim();
+    if (trimmed.startsWith("language:")) result.language = trimmed.replace("language:", "").trim();
   }
   return result;
 }
@@ -293,6 +297,10 @@ interface AppState {
   defaultProject: string | null;
   setDefaultProject: (p: string | null) => void;
 
+  // Language for backend-originated messages
+  language: Language;
+  setLanguage: (l: Language) => void;
+
   // Menu / Plugin System
   menuConfig: MenuConfig;
   setMenuConfig: (config: MenuConfig) => void;
@@ -429,6 +437,18 @@ export const useStore = create<AppState>((set) => ({
     }
   },
 
+  language: "zh",
+  setLanguage: (language) => {
+    set({ language });
+    setLocale(language).catch(console.error);
+    // Auto-save settings
+    const state = useStore.getState();
+    if (state.vaultPath) {
+      const yaml = settingsToYaml(state.theme, state.claudeCodeEnabled, state.claudeCodePath, state.defaultProject || undefined);
+      saveAppSettings(state.vaultPath, yaml).catch(console.error);
+    }
+  },
+
   // Menu / Plugin System
   menuConfig: DEFAULT_MENU_CONFIG,
   setMenuConfig: (menuConfig) => set({ menuConfig }),
@@ -528,6 +548,7 @@ export const useStore = create<AppState>((set) => ({
       if (settings.claudeCodeEnabled !== undefined) set({ claudeCodeEnabled: settings.claudeCodeEnabled });
       if (settings.claudeCodePath) set({ claudeCodePath: settings.claudeCodePath });
       if (settings.defaultProject) set({ defaultProject: settings.defaultProject });
+      if (settings.language === "zh" || settings.language === "en") set({ language: settings.language });
     } catch (e) {
       console.log("No settings file found, using defaults");
     }
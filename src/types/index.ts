@@ -8,6 +8,7 @@ export interface NoteFile {
   frontmatter: Record<string, string>;
   content: string;
   modified: string;
+  hash?: string; // SHA-256 of the file, desktop only
 }
 
 // ── Daily / Tasks ──────────────────────────────────────────────────────────
@@ -60,6 +61,7 @@ export interface Project {
   tags: string[];
   progress: number;    // 0-100
   github?: string;
+  repo?: string;       // local git checkout: ~/code/x, absolute, or vault-relative
   content: string;
 }
 
@@ -107,6 +109,7 @@ export interface DiaryEntry {
   tags: string[];
   content: string;
   modified: string;
+  hash?: string; // the note as read, to guard the save
 }
 
 // ── Decisions ──────────────────────────────────────────────────────────────
@@ -240,6 +243,9 @@ export interface HealthData {
 
 export type Theme = "dark" | "light";
 
+/** Language for backend-originated messages */
+export type Language = "zh" | "en";
+
 // ── App state ──────────────────────────────────────────────────────────────
 
 export type ViewId =
@@ -339,6 +345,9 @@ export interface EmailAccount {
   folders: string[];
   lastSync?: string;
   enabled: boolean;
+  // 后台同步间隔（分钟），覆盖设置中的默认值；0 = 仅手动同步
+  syncMinutes?: number;
+  syncIntervals?: Record<string, number>; // 按文件夹，如 { INBOX: 5, Archive: 1440 }
 }
 
 export interface Email {